@@ -63,6 +63,11 @@ pub enum MentionUri {
     MergeConflict {
         file_path: String,
     },
+    McpResource {
+        server_id: String,
+        uri: String,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -221,6 +226,22 @@ impl MentionUri {
                 } else if path.starts_with("/agent/merge-conflict") {
                     let file_path = single_query_param(&url, "path")?.unwrap_or_default();
                     Ok(Self::MergeConflict { file_path })
+                } else if let Some(server_id) = path.strip_prefix("/agent/mcp-resource/") {
+                    let mut uri = None;
+                    let mut name = None;
+                    for (key, value) in url.query_pairs() {
+                        match key.as_ref() {
+                            "uri" => uri = Some(value.into_owned()),
+                            "name" => name = Some(value.into_owned()),
+                            _ => bail!("invalid query parameter"),
+                        }
+                    }
+                    let uri = uri.context("Missing resource uri")?;
+                    Ok(Self::McpResource {
+                        server_id: decode(server_id)?.into_owned(),
+                        name: name.unwrap_or_else(|| uri.clone()),
+                        uri,
+                    })
                 } else {
                     bail!("invalid zed url: {:?}", input);
                 }
@@ -264,6 +285,7 @@ impl MentionUri {
                 ..
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
+            MentionUri::McpResource { name, .. } => name.clone(),
         }
     }
 
@@ -298,6 +320,9 @@ impl MentionUri {
                 )
                 .into(),
             ),
+            MentionUri::McpResource { server_id, uri, .. } => {
+                Some(format!("{uri} ({server_id})").into())
+            }
             _ => None,
         }
     }
@@ -320,6 +345,7 @@ impl MentionUri {
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
             MentionUri::GitDiff { .. } => IconName::GitBranch.path().into(),
             MentionUri::MergeConflict { .. } => IconName::GitMergeConflict.path().into(),
+            MentionUri::McpResource { .. } => IconName::ToolHammer.path().into(),
         }
     }
 
@@ -428,6 +454,21 @@ impl MentionUri {
                 url.query_pairs_mut().append_pair("path", file_path);
                 url
             }
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///").unwrap();
+                url.set_path(&format!(
+                    "/agent/mcp-resource/{}",
+                    urlencoding::encode(server_id)
+                ));
+                url.query_pairs_mut()
+                    .append_pair("uri", uri)
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -751,4 +792,29 @@ mod tests {
         let parsed_single = MentionUri::parse(single_line_uri, PathStyle::local()).unwrap();
         assert_eq!(parsed_single.name(), "Terminal (1 line)");
     }
+
+    #[test]
+    fn test_parse_mcp_resource_uri() {
+        let resource_uri =
+            "zed:///agent/mcp-resource/my%20server?uri=file%3A%2F%2F%2Flogs%2Fapp.log&name=app.log";
+        let parsed = MentionUri::parse(resource_uri, PathStyle::local()).unwrap();
+        assert_eq!(
+            parsed,
+            MentionUri::McpResource {
+                server_id: "my server".into(),
+                uri: "file:///logs/app.log".into(),
+                name: "app.log".into(),
+            }
+        );
+        assert_eq!(parsed.name(), "app.log");
+        assert_eq!(parsed.to_uri().to_string(), resource_uri);
+
+        assert!(
+            MentionUri::parse(
+                "zed:///agent/mcp-resource/server?name=missing-uri",
+                PathStyle::local()
+            )
+            .is_err()
+        );
+    }
 }
@@ -5,7 +5,8 @@ use collections::{BTreeMap, HashMap};
 use fs::MTime;
 use futures::{FutureExt, StreamExt, channel::mpsc};
 use gpui::{
-    App, AppContext, AsyncApp, Context, Entity, SharedString, Subscription, Task, WeakEntity,
+    App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
+    WeakEntity,
 };
 use language::{Anchor, Buffer, BufferEvent, Point, ToOffset, ToPoint};
 use project::{Project, ProjectItem, lsp_store::OpenLspBufferHandle};
@@ -48,6 +49,12 @@ pub struct LastRejectUndo {
     pub buffers: Vec<PerBufferUndo>,
 }
 
+pub enum ActionLogEvent {
+    /// The agent edited a buffer. Emitted by the log the edit was reported
+    /// to, but not by its linked action log.
+    BufferEdited(Entity<Buffer>),
+}
+
 /// Tracks actions performed by tools in a thread
 pub struct ActionLog {
     /// Buffers that we want to notify the model about when they change.
@@ -64,6 +71,8 @@ pub struct ActionLog {
     file_read_times: HashMap<PathBuf, MTime>,
 }
 
+impl EventEmitter<ActionLogEvent> for ActionLog {}
+
 impl ActionLog {
     /// Creates a new, empty action log associated with the given project.
     pub fn new(project: Entity<Project>) -> Self {
@@ -587,7 +596,8 @@ impl ActionLog {
 
     /// Mark a buffer as edited by agent, so we can refresh it in the context
     pub fn buffer_edited(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
-        self.buffer_edited_impl(buffer, true, cx);
+        self.buffer_edited_impl(buffer.clone(), true, cx);
+        cx.emit(ActionLogEvent::BufferEdited(buffer));
     }
 
     fn buffer_edited_impl(
@@ -1,5 +1,7 @@
+mod budget;
 mod db;
 mod edit_agent;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -12,6 +14,7 @@ mod thread_store;
 mod tool_permissions;
 mod tools;
 
+pub use budget::*;
 use context_server::ContextServerId;
 pub use db::*;
 use itertools::Itertools;
@@ -770,7 +773,8 @@ impl NativeAgent {
         cx: &mut Context<Self>,
     ) {
         match event {
-            ContextServerRegistryEvent::ToolsChanged => {}
+            ContextServerRegistryEvent::ToolsChanged
+            | ContextServerRegistryEvent::ResourcesChanged => {}
             ContextServerRegistryEvent::PromptsChanged => {
                 let project_id = self.projects.iter().find_map(|(id, state)| {
                     if state.context_server_registry == registry {
@@ -0,0 +1,261 @@
+//! Enforces the token and cost budgets from the agent settings.
+//!
+//! Threads check their budgets before each request to the model. When a
+//! budget is close to being reached they warn, and when it's reached they
+//! stop until the user extends it. Each extension allows another budget's
+//! worth of usage.
+
+use agent_settings::{AgentBudget, AgentProfileId};
+use chrono::NaiveDate;
+use language_model::{LanguageModelCostInfo, TokenUsage};
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::AddAssign};
+
+/// The tokens and cost used by requests to models.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub tokens: u64,
+    /// The cost in US dollars, only counting models whose token prices are
+    /// known. Cached input tokens are priced as other input tokens when the
+    /// model doesn't have separate cache prices.
+    pub cost: f64,
+}
+
+impl BudgetUsage {
+    pub fn for_request(usage: &TokenUsage, cost_info: Option<&LanguageModelCostInfo>) -> Self {
+        let cost = match cost_info {
+            Some(LanguageModelCostInfo::TokenCost {
+                input_token_cost_per_1m,
+                output_token_cost_per_1m,
+                cache_read_token_cost_per_1m,
+                cache_creation_token_cost_per_1m,
+            }) => {
+                let cache_read_token_cost_per_1m =
+                    cache_read_token_cost_per_1m.unwrap_or(*input_token_cost_per_1m);
+                let cache_creation_token_cost_per_1m =
+                    cache_creation_token_cost_per_1m.unwrap_or(*input_token_cost_per_1m);
+                (usage.input_tokens as f64 * input_token_cost_per_1m
+                    + usage.cache_read_input_tokens as f64 * cache_read_token_cost_per_1m
+                    + usage.cache_creation_input_tokens as f64 * cache_creation_token_cost_per_1m
+                    + usage.output_tokens as f64 * output_token_cost_per_1m)
+                    / 1_000_000.
+            }
+            // Costs per request are in units of the provider's plan rather
+            // than in dollars.
+            Some(LanguageModelCostInfo::RequestCost { .. }) | None => 0.,
+        };
+        Self {
+            tokens: usage.total_tokens(),
+            cost,
+        }
+    }
+}
+
+impl AddAssign for BudgetUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.tokens += other.tokens;
+        self.cost += other.cost;
+    }
+}
+
+/// A thread's usage counted toward its budget, and how many times its budget
+/// was extended.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThreadBudget {
+    pub usage: BudgetUsage,
+    pub extensions: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BudgetScope {
+    Thread,
+    Daily,
+    Profile(AgentProfileId),
+}
+
+impl BudgetScope {
+    /// The key under which extensions of the scope's budget are stored, for
+    /// the scopes whose extensions are stored per day.
+    pub(crate) fn daily_key(&self) -> Option<String> {
+        match self {
+            BudgetScope::Thread => None,
+            BudgetScope::Daily => Some("daily".to_string()),
+            BudgetScope::Profile(profile_id) => Some(format!("profile:{profile_id}")),
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            BudgetScope::Thread => "this thread's budget".to_string(),
+            BudgetScope::Daily => "the agent's daily budget".to_string(),
+            BudgetScope::Profile(profile_id) => {
+                format!("the daily budget of the `{profile_id}` profile")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BudgetLimit {
+    Tokens(u64),
+    Cost(f64),
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Tokens(tokens) => write!(f, "{tokens} tokens"),
+            BudgetLimit::Cost(cost) => write!(f, "${cost:.2}"),
+        }
+    }
+}
+
+/// The error a turn fails with when one of the agent's budgets is reached.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[error("Reached {} of {limit}.", .scope.description())]
+pub struct BudgetExceededError {
+    pub scope: BudgetScope,
+    pub limit: BudgetLimit,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BudgetCheck {
+    WithinBudget,
+    Warning(String),
+    Exceeded(BudgetExceededError),
+}
+
+/// Checks usage against a budget that was extended `extensions` times.
+pub fn check_budget(
+    scope: BudgetScope,
+    budget: &AgentBudget,
+    extensions: u32,
+    usage: BudgetUsage,
+    warning_threshold: f64,
+) -> BudgetCheck {
+    let allowance = 1. + extensions as f64;
+    let limits = budget
+        .max_tokens
+        .map(|max_tokens| {
+            let limit = max_tokens.saturating_mul(1 + extensions as u64);
+            (
+                BudgetLimit::Tokens(limit),
+                usage.tokens as f64,
+                limit as f64,
+            )
+        })
+        .into_iter()
+        .chain(budget.max_cost.map(|max_cost| {
+            let limit = max_cost * allowance;
+            (BudgetLimit::Cost(limit), usage.cost, limit)
+        }));
+
+    let mut warning = None;
+    for (limit, used, max) in limits {
+        if used >= max {
+            return BudgetCheck::Exceeded(BudgetExceededError { scope, limit });
+        }
+        if warning.is_none() && used >= max * warning_threshold {
+            warning = Some(format!(
+                "Used over {}% of {} of {limit}.",
+                (warning_threshold * 100.).round(),
+                scope.description(),
+            ));
+        }
+    }
+    warning.map_or(BudgetCheck::WithinBudget, BudgetCheck::Warning)
+}
+
+/// The day that daily budgets are counted in.
+pub(crate) fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(tokens: u64, cost: f64) -> BudgetUsage {
+        BudgetUsage { tokens, cost }
+    }
+
+    #[test]
+    fn test_usage_for_request() {
+        let usage = TokenUsage {
+            input_tokens: 1_000,
+            output_tokens: 500,
+            cache_creation_input_tokens: 200,
+            cache_read_input_tokens: 800,
+        };
+        let cost_info = LanguageModelCostInfo::TokenCost {
+            input_token_cost_per_1m: 3.,
+            output_token_cost_per_1m: 15.,
+            cache_read_token_cost_per_1m: None,
+            cache_creation_token_cost_per_1m: None,
+        };
+        assert_eq!(
+            BudgetUsage::for_request(&usage, Some(&cost_info)),
+            BudgetUsage {
+                tokens: 2_500,
+                cost: 0.0135,
+            }
+        );
+
+        let cost_info = LanguageModelCostInfo::TokenCost {
+            input_token_cost_per_1m: 3.,
+            output_token_cost_per_1m: 15.,
+            cache_read_token_cost_per_1m: Some(0.25),
+            cache_creation_token_cost_per_1m: Some(3.75),
+        };
+        assert_eq!(
+            BudgetUsage::for_request(&usage, Some(&cost_info)),
+            BudgetUsage {
+                tokens: 2_500,
+                cost: 0.01145,
+            }
+        );
+        assert_eq!(BudgetUsage::for_request(&usage, None).cost, 0.);
+    }
+
+    #[test]
+    fn test_check_budget() {
+        let budget = AgentBudget {
+            max_tokens: Some(1_000),
+            max_cost: Some(2.),
+        };
+        let check =
+            |extensions, usage| check_budget(BudgetScope::Thread, &budget, extensions, usage, 0.8);
+
+        assert_eq!(check(0, usage(500, 1.)), BudgetCheck::WithinBudget);
+        assert_eq!(
+            check(0, usage(850, 1.)),
+            BudgetCheck::Warning("Used over 80% of this thread's budget of 1000 tokens.".into())
+        );
+        assert_eq!(
+            check(0, usage(500, 2.)),
+            BudgetCheck::Exceeded(BudgetExceededError {
+                scope: BudgetScope::Thread,
+                limit: BudgetLimit::Cost(2.),
+            })
+        );
+        assert_eq!(check(1, usage(1_500, 2.)), BudgetCheck::WithinBudget);
+
+        let unlimited = AgentBudget::default();
+        assert_eq!(
+            check_budget(BudgetScope::Daily, &unlimited, 0, usage(u64::MAX, 1e9), 0.8),
+            BudgetCheck::WithinBudget
+        );
+    }
+
+    #[test]
+    fn test_exceeded_message() {
+        let error = BudgetExceededError {
+            scope: BudgetScope::Profile(AgentProfileId("write".into())),
+            limit: BudgetLimit::Cost(20.),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Reached the daily budget of the `write` profile of $20.00."
+        );
+    }
+}
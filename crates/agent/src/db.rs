@@ -1,9 +1,11 @@
-use crate::{AgentMessage, AgentMessageContent, UserMessage, UserMessageContent};
+use crate::{
+    AgentMessage, AgentMessageContent, BudgetScope, BudgetUsage, UserMessage, UserMessageContent,
+};
 use acp_thread::UserMessageId;
 use agent_client_protocol as acp;
 use agent_settings::AgentProfileId;
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use collections::{HashMap, IndexMap};
 use futures::{FutureExt, future::Shared};
 use gpui::{BackgroundExecutor, Global, Task};
@@ -65,6 +67,8 @@ pub struct DbThread {
     #[serde(default)]
     pub request_token_usage: HashMap<acp_thread::UserMessageId, language_model::TokenUsage>,
     #[serde(default)]
+    pub budget: crate::ThreadBudget,
+    #[serde(default)]
     pub model: Option<DbLanguageModel>,
     #[serde(default)]
     pub profile: Option<AgentProfileId>,
@@ -84,6 +88,19 @@ pub struct DbThread {
     pub ui_scroll_position: Option<SerializedScrollPosition>,
 }
 
+/// The usage of the agent on a day.
+#[derive(Debug, Default, PartialEq)]
+pub struct DailyUsage {
+    /// The usage of all threads.
+    pub total: BudgetUsage,
+    /// The usage of the threads using a profile.
+    pub profile: BudgetUsage,
+    /// How many times the daily budget was extended.
+    pub daily_extensions: u32,
+    /// How many times the profile's budget was extended.
+    pub profile_extensions: u32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SerializedScrollPosition {
     pub item_ix: usize,
@@ -122,6 +139,7 @@ impl SharedThread {
             initial_project_snapshot: None,
             cumulative_token_usage: Default::default(),
             request_token_usage: Default::default(),
+            budget: Default::default(),
             model: self.model,
             profile: None,
             imported: true,
@@ -301,6 +319,7 @@ impl DbThread {
             initial_project_snapshot: thread.initial_project_snapshot,
             cumulative_token_usage: thread.cumulative_token_usage,
             request_token_usage,
+            budget: Default::default(),
             model: thread.model,
             profile: thread.profile,
             imported: false,
@@ -433,6 +452,23 @@ impl ThreadsDatabase {
             }
         }
 
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS daily_usage (
+                day TEXT NOT NULL,
+                profile_id TEXT NOT NULL,
+                tokens INTEGER NOT NULL,
+                cost REAL NOT NULL,
+                PRIMARY KEY (day, profile_id)
+            );
+            CREATE TABLE IF NOT EXISTS budget_extensions (
+                day TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                PRIMARY KEY (day, scope)
+            );
+        "})?()
+        .map_err(|e| anyhow!("Failed to create usage tables: {}", e))?;
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
@@ -613,6 +649,99 @@ impl ThreadsDatabase {
         })
     }
 
+    /// Returns the usage on the given day of all threads and of the threads
+    /// using the given profile, and how many times their budgets were
+    /// extended on that day.
+    pub fn daily_usage(
+        &self,
+        day: NaiveDate,
+        profile_id: AgentProfileId,
+    ) -> Task<Result<DailyUsage>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let day = day.to_string();
+
+            let mut select_usage =
+                connection.select_bound::<&str, (String, u64, f64)>(indoc! {"
+                    SELECT profile_id, tokens, cost FROM daily_usage WHERE day = ?
+                "})?;
+            let mut usage = DailyUsage::default();
+            for (row_profile_id, tokens, cost) in select_usage(day.as_str())? {
+                let row_usage = BudgetUsage { tokens, cost };
+                usage.total += row_usage;
+                if row_profile_id == profile_id.as_str() {
+                    usage.profile += row_usage;
+                }
+            }
+
+            let mut select_extensions =
+                connection.select_bound::<&str, (String, u32)>(indoc! {"
+                    SELECT scope, count FROM budget_extensions WHERE day = ?
+                "})?;
+            let daily_key = BudgetScope::Daily.daily_key();
+            let profile_key = BudgetScope::Profile(profile_id).daily_key();
+            for (scope, count) in select_extensions(day.as_str())? {
+                if Some(&scope) == daily_key.as_ref() {
+                    usage.daily_extensions = count;
+                } else if Some(&scope) == profile_key.as_ref() {
+                    usage.profile_extensions = count;
+                }
+            }
+
+            Ok(usage)
+        })
+    }
+
+    pub fn record_usage(
+        &self,
+        day: NaiveDate,
+        profile_id: AgentProfileId,
+        usage: BudgetUsage,
+    ) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+
+            let mut insert = connection.exec_bound::<(String, Arc<str>, u64, f64)>(indoc! {"
+                INSERT INTO daily_usage (day, profile_id, tokens, cost)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(day, profile_id) DO UPDATE SET
+                    tokens = tokens + excluded.tokens,
+                    cost = cost + excluded.cost
+            "})?;
+
+            insert((day.to_string(), profile_id.0, usage.tokens, usage.cost))?;
+
+            Ok(())
+        })
+    }
+
+    /// Extends a daily budget on the given day. Thread budgets are extended
+    /// in their threads.
+    pub fn extend_budget(&self, day: NaiveDate, scope: &BudgetScope) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        let Some(scope) = scope.daily_key() else {
+            return Task::ready(Err(anyhow!("{scope:?} isn't a daily budget")));
+        };
+
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+
+            let mut insert = connection.exec_bound::<(String, String)>(indoc! {"
+                INSERT INTO budget_extensions (day, scope, count)
+                VALUES (?1, ?2, 1)
+                ON CONFLICT(day, scope) DO UPDATE SET count = count + 1
+            "})?;
+
+            insert((day.to_string(), scope))?;
+
+            Ok(())
+        })
+    }
+
     pub fn delete_threads(&self) -> Task<Result<()>> {
         let connection = self.connection.clone();
 
@@ -686,6 +815,7 @@ mod tests {
             initial_project_snapshot: None,
             cumulative_token_usage: Default::default(),
             request_token_usage: HashMap::default(),
+            budget: Default::default(),
             model: None,
             profile: None,
             imported: false,
@@ -884,6 +1014,76 @@ mod tests {
         assert_eq!(threads[0].folder_paths, folder_paths);
     }
 
+    #[gpui::test]
+    async fn test_daily_usage(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        let write = AgentProfileId("write".into());
+        let ask = AgentProfileId("ask".into());
+
+        for (day, profile_id, tokens, cost) in [
+            (day, &write, 1_000, 0.5),
+            (day, &write, 500, 0.25),
+            (day, &ask, 200, 0.1),
+            (next_day, &write, 4_000, 2.),
+        ] {
+            database
+                .record_usage(day, profile_id.clone(), BudgetUsage { tokens, cost })
+                .await
+                .unwrap();
+        }
+        database
+            .extend_budget(day, &BudgetScope::Daily)
+            .await
+            .unwrap();
+        database
+            .extend_budget(day, &BudgetScope::Profile(write.clone()))
+            .await
+            .unwrap();
+        database
+            .extend_budget(day, &BudgetScope::Profile(write.clone()))
+            .await
+            .unwrap();
+        assert!(
+            database
+                .extend_budget(day, &BudgetScope::Thread)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            database.daily_usage(day, write.clone()).await.unwrap(),
+            DailyUsage {
+                total: BudgetUsage {
+                    tokens: 1_700,
+                    cost: 0.85,
+                },
+                profile: BudgetUsage {
+                    tokens: 1_500,
+                    cost: 0.75,
+                },
+                daily_extensions: 1,
+                profile_extensions: 2,
+            }
+        );
+        assert_eq!(
+            database
+                .daily_usage(day, ask)
+                .await
+                .unwrap()
+                .profile_extensions,
+            0
+        );
+        assert_eq!(
+            database.daily_usage(next_day, write).await.unwrap().total,
+            BudgetUsage {
+                tokens: 4_000,
+                cost: 2.,
+            }
+        );
+    }
+
     #[gpui::test]
     async fn test_folder_paths_empty_when_not_set(cx: &mut TestAppContext) {
         let database = ThreadsDatabase::new(cx.executor()).unwrap();
@@ -0,0 +1,449 @@
+//! Runs the hooks from the agent settings at points of a thread's turns.
+//!
+//! Each hook is a shell command, or a task from the task inventory, that
+//! receives the event as JSON on its standard input. What's done with its output depends on the event, but it's
+//! always reported to the model when the hook prints something or fails.
+
+use agent_settings::{AgentHook, AgentHookAction};
+use anyhow::{Context as _, Result};
+use futures::{AsyncWriteExt as _, FutureExt as _};
+use gpui::{AsyncApp, BackgroundExecutor, Entity};
+use project::{WorktreeId, task_inventory::Inventory};
+use serde::Serialize;
+use std::path::PathBuf;
+use task::{SpawnInTerminal, TaskContext};
+use util::{
+    command::{Stdio, new_command},
+    shell::{Shell, get_default_system_shell_preferring_bash},
+    shell_builder::ShellBuilder,
+};
+
+/// The output of a hook is truncated to this many bytes before being sent to
+/// the model.
+const HOOK_OUTPUT_LIMIT: usize = 16 * 1024;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum HookEvent<'a> {
+    BeforeToolCall {
+        tool_name: &'a str,
+        tool_input: &'a serde_json::Value,
+    },
+    AfterToolCall {
+        tool_name: &'a str,
+        tool_input: &'a serde_json::Value,
+        tool_output: &'a str,
+        is_error: bool,
+    },
+    AfterEdit {
+        tool_name: &'a str,
+        paths: &'a [PathBuf],
+    },
+    TurnFinished,
+}
+
+impl HookEvent<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::BeforeToolCall { .. } => "before_tool_call",
+            HookEvent::AfterToolCall { .. } => "after_tool_call",
+            HookEvent::AfterEdit { .. } => "after_edit",
+            HookEvent::TurnFinished => "turn_finished",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    thread_id: &'a str,
+    #[serde(flatten)]
+    event: &'a HookEvent<'a>,
+}
+
+/// Runs hooks on behalf of a thread.
+#[derive(Clone)]
+pub(crate) struct HookRunner {
+    thread_id: String,
+    cwd: Option<PathBuf>,
+    executor: BackgroundExecutor,
+    /// Where hooks find the tasks they run, and the worktree of their tasks.
+    task_inventory: Option<(Entity<Inventory>, Option<WorktreeId>)>,
+}
+
+impl HookRunner {
+    pub fn new(thread_id: String, cwd: Option<PathBuf>, executor: BackgroundExecutor) -> Self {
+        Self {
+            thread_id,
+            cwd,
+            executor,
+            task_inventory: None,
+        }
+    }
+
+    pub fn with_task_inventory(
+        mut self,
+        inventory: Entity<Inventory>,
+        worktree_id: Option<WorktreeId>,
+    ) -> Self {
+        self.task_inventory = Some((inventory, worktree_id));
+        self
+    }
+
+    pub async fn run(&self, hook: &AgentHook, event: &HookEvent<'_>, cx: &AsyncApp) -> HookOutput {
+        let mut output = HookOutput {
+            event: event.name(),
+            command: match &hook.action {
+                AgentHookAction::Command(command) => command.clone(),
+                AgentHookAction::Task(label) => format!("task: {label}"),
+            },
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+        };
+        let payload = match serde_json::to_vec(&HookPayload {
+            thread_id: &self.thread_id,
+            event,
+        }) {
+            Ok(payload) => payload,
+            Err(error) => {
+                output.error = Some(format!("couldn't serialize the event: {error}"));
+                return output;
+            }
+        };
+
+        let spawn = match &hook.action {
+            AgentHookAction::Command(command) => SpawnInTerminal {
+                command: Some(command.clone()),
+                cwd: self.cwd.clone(),
+                shell: Shell::Program(get_default_system_shell_preferring_bash()),
+                ..SpawnInTerminal::default()
+            },
+            AgentHookAction::Task(label) => match self.resolve_task(label, cx).await {
+                Ok(spawn) => spawn,
+                Err(error) => {
+                    output.error = Some(format!("couldn't be run: {error:#}"));
+                    return output;
+                }
+            },
+        };
+        let (program, args) = ShellBuilder::new(&spawn.shell, cfg!(windows))
+            .non_interactive()
+            .build(spawn.command, &spawn.args);
+        let mut command = new_command(program);
+        command
+            .args(args)
+            .envs(spawn.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = spawn.cwd.or_else(|| self.cwd.clone()) {
+            command.current_dir(cwd);
+        }
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                output.error = Some(format!("couldn't be run: {error}"));
+                return output;
+            }
+        };
+
+        // The event is written while the output is read, and both are subject
+        // to the timeout, so that a hook which neither reads its input nor
+        // exits can't block on a full pipe. Hooks that don't read the event
+        // close their standard input early, so failing to write it isn't an
+        // error.
+        let stdin = child.stdin.take();
+        let write_event = async move {
+            if let Some(mut stdin) = stdin {
+                stdin.write_all(&payload).await.ok();
+            }
+        };
+        let run = async move {
+            let ((), result) = futures::join!(write_event, child.output());
+            result
+        };
+        let result = futures::select! {
+            result = run.fuse() => result,
+            _ = self.executor.timer(hook.timeout).fuse() => {
+                output.error = Some(format!("timed out after {} seconds", hook.timeout.as_secs()));
+                return output;
+            }
+        };
+        match result {
+            Ok(result) => {
+                output.exit_code = result.status.code();
+                output.stdout = String::from_utf8_lossy(&result.stdout).into_owned();
+                output.stderr = String::from_utf8_lossy(&result.stderr).into_owned();
+            }
+            Err(error) => output.error = Some(format!("failed: {error}")),
+        }
+        output
+    }
+
+    /// Resolves the task with the given label, in the hooks' working directory.
+    async fn resolve_task(&self, label: &str, cx: &AsyncApp) -> Result<SpawnInTerminal> {
+        let (inventory, worktree_id) = self
+            .task_inventory
+            .clone()
+            .context("tasks aren't available in this project")?;
+        let template = cx
+            .update(|cx| {
+                inventory
+                    .read(cx)
+                    .task_template_by_label(None, worktree_id, label, cx)
+            })
+            .await
+            .with_context(|| format!("there's no task labeled `{label}`"))?;
+        let context = TaskContext {
+            cwd: self.cwd.clone(),
+            ..TaskContext::default()
+        };
+        let task = template
+            .resolve_task("agent-hook", &context)
+            .with_context(|| format!("the task `{label}` couldn't be resolved"))?;
+        Ok(task.resolved)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct HookOutput {
+    event: &'static str,
+    command: String,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    /// Why the hook didn't run to completion, if it didn't.
+    error: Option<String>,
+}
+
+impl HookOutput {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.exit_code == Some(0)
+    }
+
+    /// Returns the input a `before_tool_call` hook replaced the tool's input
+    /// with, if it printed `{"tool_input": ...}`.
+    pub fn replaced_tool_input(&self) -> Option<serde_json::Value> {
+        if !self.succeeded() {
+            return None;
+        }
+        let serde_json::Value::Object(mut object) =
+            serde_json::from_str::<serde_json::Value>(self.stdout.trim()).ok()?
+        else {
+            return None;
+        };
+        object.remove("tool_input")
+    }
+
+    /// Describes what the hook printed, or how it failed, for the model.
+    /// Returns `None` when it succeeded without printing anything.
+    pub fn to_model_text(&self) -> Option<String> {
+        let mut text = String::new();
+        for stream in [&self.stdout, &self.stderr] {
+            let stream = stream.trim();
+            if !stream.is_empty() {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(stream);
+            }
+        }
+        if text.len() > HOOK_OUTPUT_LIMIT {
+            let mut end = HOOK_OUTPUT_LIMIT;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text.truncate(end);
+            text.push_str("\n[output truncated]");
+        }
+
+        let status = match (&self.error, self.exit_code) {
+            (Some(error), _) => error.clone(),
+            (None, Some(0)) if text.is_empty() => return None,
+            (None, Some(0)) => "succeeded".to_string(),
+            (None, Some(code)) => format!("failed with exit code {code}"),
+            (None, None) => "was terminated by a signal".to_string(),
+        };
+        let mut description = format!("The `{}` hook `{}` {status}.", self.event, self.command);
+        if !text.is_empty() {
+            description.push_str(&format!(" Its output:\n\n```\n{text}\n```"));
+        }
+        Some(description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::task_store::TaskSettingsLocation;
+    use std::{path::Path, time::Duration};
+
+    fn hook(command: &str) -> AgentHook {
+        AgentHook {
+            action: AgentHookAction::Command(command.to_string()),
+            tools: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    fn output(exit_code: Option<i32>, stdout: &str, stderr: &str) -> HookOutput {
+        HookOutput {
+            event: "after_edit",
+            command: "cargo fmt".to_string(),
+            exit_code,
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_model_text() {
+        assert_eq!(output(Some(0), "", "  \n").to_model_text(), None);
+        assert_eq!(
+            output(Some(0), "formatted 2 files\n", "").to_model_text(),
+            Some(
+                "The `after_edit` hook `cargo fmt` succeeded. Its output:\n\n```\nformatted 2 files\n```"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            output(Some(1), "", "error: expected `;`").to_model_text(),
+            Some(
+                "The `after_edit` hook `cargo fmt` failed with exit code 1. Its output:\n\n```\nerror: expected `;`\n```"
+                    .to_string()
+            )
+        );
+        let mut timed_out = output(None, "", "");
+        timed_out.error = Some("timed out after 60 seconds".to_string());
+        assert_eq!(
+            timed_out.to_model_text(),
+            Some("The `after_edit` hook `cargo fmt` timed out after 60 seconds.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_replaced_tool_input() {
+        assert_eq!(
+            output(Some(0), r#"{"tool_input": {"command": "ls"}}"#, "").replaced_tool_input(),
+            Some(serde_json::json!({"command": "ls"}))
+        );
+        assert_eq!(output(Some(0), "allowed", "").replaced_tool_input(), None);
+        assert_eq!(
+            output(Some(2), r#"{"tool_input": {"command": "ls"}}"#, "").replaced_tool_input(),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_run_hook(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let runner = HookRunner::new("thread-1".to_string(), None, cx.executor());
+        let tool_input = serde_json::json!({"command": "rm -rf target"});
+        let event = HookEvent::BeforeToolCall {
+            tool_name: "terminal",
+            tool_input: &tool_input,
+        };
+
+        let output = runner.run(&hook("cat"), &event, &cx.to_async()).await;
+        assert!(output.succeeded());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output.stdout).unwrap(),
+            serde_json::json!({
+                "thread_id": "thread-1",
+                "event": "before_tool_call",
+                "tool_name": "terminal",
+                "tool_input": {"command": "rm -rf target"},
+            })
+        );
+
+        let output = runner
+            .run(
+                &hook("echo 'not in this repo' >&2; exit 2"),
+                &event,
+                &cx.to_async(),
+            )
+            .await;
+        assert!(!output.succeeded());
+        assert_eq!(output.exit_code, Some(2));
+        assert_eq!(output.stderr.trim(), "not in this repo");
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_run_task_hook(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let inventory = cx.update(Inventory::new);
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(Path::new("/tasks.json")),
+                    Some(
+                        r#"[{ "label": "print event", "command": "cat", "env": { "HOOK": "1" } }]"#,
+                    ),
+                )
+                .unwrap();
+        });
+        let runner = HookRunner::new("thread-1".to_string(), None, cx.executor())
+            .with_task_inventory(inventory, None);
+        let task_hook = |label: &str| AgentHook {
+            action: AgentHookAction::Task(label.to_string()),
+            ..hook("")
+        };
+
+        let output = runner
+            .run(
+                &task_hook("print event"),
+                &HookEvent::TurnFinished,
+                &cx.to_async(),
+            )
+            .await;
+        assert!(output.succeeded());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&output.stdout).unwrap(),
+            serde_json::json!({"thread_id": "thread-1", "event": "turn_finished"})
+        );
+
+        let output = runner
+            .run(
+                &task_hook("missing"),
+                &HookEvent::TurnFinished,
+                &cx.to_async(),
+            )
+            .await;
+        assert_eq!(
+            output.error.as_deref(),
+            Some("couldn't be run: there's no task labeled `missing`")
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_timeout_while_writing_event(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let runner = HookRunner::new("thread-1".to_string(), None, cx.executor());
+        let hook = AgentHook {
+            timeout: Duration::from_secs(1),
+            ..hook("sleep 60")
+        };
+        let run = cx.spawn(async move |cx| {
+            // The event doesn't fit in the pipe, and the hook never reads it.
+            let tool_output = "x".repeat(1024 * 1024);
+            let event = HookEvent::AfterToolCall {
+                tool_name: "terminal",
+                tool_input: &serde_json::Value::Null,
+                tool_output: &tool_output,
+                is_error: false,
+            };
+            runner.run(&hook, &event, cx).await
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        let output = run.await;
+        assert_eq!(output.error.as_deref(), Some("timed out after 1 seconds"));
+    }
+}
@@ -4,7 +4,7 @@ use acp_thread::{
     UserMessageId,
 };
 use agent_client_protocol::{self as acp};
-use agent_settings::AgentProfileId;
+use agent_settings::{AgentHook, AgentHookAction, AgentHooks, AgentProfileId, AgentSettings};
 use anyhow::Result;
 use client::{Client, UserStore};
 use cloud_llm_client::CompletionIntent;
@@ -3028,6 +3028,98 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
     assert_first_message_state(cx);
 }
 
+#[gpui::test]
+async fn test_thread_budget(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.budgets.thread.max_tokens = Some(1_000);
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+
+    let respond = |input_tokens| {
+        fake_model.send_last_completion_stream_text_chunk("Hey!");
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+            TokenUsage {
+                input_tokens,
+                output_tokens: 100,
+                ..Default::default()
+            },
+        ));
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::EndTurn,
+        ));
+        fake_model.end_last_completion_stream();
+    };
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hi"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    respond(750);
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+    thread.read_with(cx, |thread, _| assert_eq!(thread.budget_warning(), None));
+
+    // The thread is close to its budget, so the next request warns.
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hi"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| {
+        assert_eq!(
+            thread.budget_warning().map(|warning| warning.as_ref()),
+            Some("Used over 80% of this thread's budget of 1000 tokens.")
+        );
+    });
+    respond(200);
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+
+    // The budget was reached, so no more requests are made until it's
+    // extended.
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hi"], cx)
+        })
+        .unwrap();
+    let events = events.collect::<Vec<_>>().await;
+    let error = events
+        .iter()
+        .find_map(|event| event.as_ref().err())
+        .and_then(|error| error.downcast_ref::<BudgetExceededError>())
+        .cloned();
+    assert_eq!(
+        error,
+        Some(BudgetExceededError {
+            scope: BudgetScope::Thread,
+            limit: BudgetLimit::Tokens(1_000),
+        })
+    );
+    assert!(fake_model.pending_completions().is_empty());
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.extend_budget(&BudgetScope::Thread, cx)
+        })
+        .await
+        .unwrap();
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hi"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    respond(100);
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+}
+
 #[gpui::test]
 async fn test_title_generation(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -3704,6 +3796,274 @@ async fn test_streaming_tool_completes_when_llm_stream_ends_without_final_input(
     });
 }
 
+fn hook(command: &str) -> AgentHook {
+    AgentHook {
+        action: AgentHookAction::Command(command.to_string()),
+        tools: None,
+        timeout: Duration::from_secs(10),
+    }
+}
+
+fn set_hooks(cx: &mut TestAppContext, update: impl FnOnce(&mut AgentHooks)) {
+    cx.update(|cx| {
+        let mut settings = AgentSettings::get_global(cx).clone();
+        update(&mut settings.hooks);
+        AgentSettings::override_global(settings, cx);
+    });
+}
+
+/// Moves the thread's project into a real directory, since hooks are run as
+/// processes in the project's first worktree.
+async fn move_project_to_temp_dir(
+    thread: &Entity<Thread>,
+    fs: &FakeFs,
+    cx: &mut TestAppContext,
+) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs.insert_tree(
+        dir.path(),
+        json!({ "src": { "main.rs": "fn main() {}\n" } }),
+    )
+    .await;
+    let project = thread.read_with(cx, |thread, _| thread.project().clone());
+    project.update(cx, |project, cx| {
+        let worktree_ids = project
+            .worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        for worktree_id in worktree_ids {
+            project.remove_worktree(worktree_id, cx);
+        }
+    });
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(dir.path(), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    dir
+}
+
+/// Drives the turn until it requests another completion, and returns it.
+/// Hooks are real processes, which `run_until_parked` doesn't wait for, so
+/// this waits for the turn's events in between.
+async fn next_completion(
+    events: &mut UnboundedReceiver<Result<ThreadEvent>>,
+    fake_model: &FakeLanguageModel,
+    cx: &mut TestAppContext,
+) -> LanguageModelRequest {
+    loop {
+        cx.run_until_parked();
+        if let Some(completion) = fake_model.pending_completions().pop() {
+            return completion;
+        }
+        events
+            .next()
+            .await
+            .expect("the turn ended without requesting a completion")
+            .unwrap();
+    }
+}
+
+fn last_tool_result(completion: &LanguageModelRequest) -> LanguageModelToolResult {
+    completion
+        .messages
+        .last()
+        .and_then(|message| {
+            message.content.iter().find_map(|content| match content {
+                MessageContent::ToolResult(result) => Some(result.clone()),
+                _ => None,
+            })
+        })
+        .expect("the completion doesn't end with a tool result")
+}
+
+fn tool_result_text(result: &LanguageModelToolResult) -> String {
+    match &result.content {
+        language_model::LanguageModelToolResultContent::Text(text) => text.to_string(),
+        language_model::LanguageModelToolResultContent::Image(_) => String::new(),
+    }
+}
+
+fn echo_tool_use(id: &str, text: &str) -> LanguageModelToolUse {
+    LanguageModelToolUse {
+        id: id.into(),
+        name: EchoTool::NAME.into(),
+        raw_input: json!({ "text": text }).to_string(),
+        input: json!({ "text": text }),
+        is_input_complete: true,
+        thought_signature: None,
+    }
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_before_tool_call_hooks(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let _dir = move_project_to_temp_dir(&thread, &fs, cx).await;
+    thread.update(cx, |thread, _| thread.add_tool(EchoTool));
+
+    // A hook can replace the input of the tool call.
+    set_hooks(cx, |hooks| {
+        hooks.before_tool_call = vec![hook(r#"echo '{"tool_input": {"text": "rewritten"}}'"#)];
+    });
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Use the echo tool"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        echo_tool_use("tool_1", "original"),
+    ));
+    fake_model.end_last_completion_stream();
+    let completion = next_completion(&mut events, fake_model, cx).await;
+    let result = last_tool_result(&completion);
+    assert!(!result.is_error);
+    assert_eq!(tool_result_text(&result), "rewritten");
+    fake_model.send_last_completion_stream_text_chunk("Done");
+    fake_model
+        .send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(StopReason::EndTurn));
+    fake_model.end_last_completion_stream();
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+
+    // A failing hook blocks the tool call, and its output tells the model why.
+    set_hooks(cx, |hooks| {
+        hooks.before_tool_call = vec![hook("echo 'not in this repo' >&2; exit 1")];
+    });
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Use the echo tool again"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        echo_tool_use("tool_2", "original"),
+    ));
+    fake_model.end_last_completion_stream();
+    let completion = next_completion(&mut events, fake_model, cx).await;
+    let result = last_tool_result(&completion);
+    assert!(result.is_error);
+    assert_eq!(
+        tool_result_text(&result),
+        "The tool call was blocked by a hook. The `before_tool_call` hook \
+        `echo 'not in this repo' >&2; exit 1` failed with exit code 1. \
+        Its output:\n\n```\nnot in this repo\n```"
+    );
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_after_edit_hooks(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let dir = move_project_to_temp_dir(&thread, &fs, cx).await;
+    let (complete_tx, complete_rx) = oneshot::channel();
+    thread.update(cx, |thread, _| {
+        thread.add_tool(StreamingEchoTool::new().with_wait_until_complete(complete_rx))
+    });
+    set_hooks(cx, |hooks| hooks.after_edit = vec![hook("cat")]);
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Edit main.rs"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_1".into(),
+            name: StreamingEchoTool::NAME.into(),
+            raw_input: json!({ "text": "edited" }).to_string(),
+            input: json!({ "text": "edited" }),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Edit a buffer on behalf of the running tool.
+    let path = dir.path().join("src/main.rs");
+    let (project, action_log) = thread.read_with(cx, |thread, _| {
+        (thread.project().clone(), thread.action_log().clone())
+    });
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer(&path, cx))
+        .await
+        .unwrap();
+    action_log.update(cx, |log, cx| log.buffer_read(buffer.clone(), cx));
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// edited\n")], None, cx)
+    });
+    action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+    complete_tx.send(()).unwrap();
+
+    // The hook is given the edited paths, and what it prints is appended to
+    // the tool's output.
+    let completion = next_completion(&mut events, fake_model, cx).await;
+    let text = tool_result_text(&last_tool_result(&completion));
+    let (output, hook_output) = text
+        .split_once("\n\nThe `after_edit` hook `cat` succeeded. Its output:\n\n```\n")
+        .unwrap();
+    assert_eq!(output, "edited");
+    let payload =
+        serde_json::from_str::<serde_json::Value>(hook_output.strip_suffix("\n```").unwrap())
+            .unwrap();
+    assert_eq!(payload["event"], "after_edit");
+    assert_eq!(payload["tool_name"], StreamingEchoTool::NAME);
+    assert_eq!(payload["paths"], json!([path]));
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_turn_finished_hooks(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let _dir = move_project_to_temp_dir(&thread, &fs, cx).await;
+    set_hooks(cx, |hooks| {
+        hooks.turn_finished = vec![hook("echo 'tests failed'; exit 1")];
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Hi"], cx)
+        })
+        .unwrap();
+    // A failing hook continues the turn with its output, until the model has
+    // been asked to continue as many times as allowed.
+    for continuation in 0..=MAX_TURN_FINISHED_HOOK_FAILURES {
+        let completion = next_completion(&mut events, fake_model, cx).await;
+        if continuation > 0 {
+            assert_eq!(
+                completion.messages.last().unwrap().content,
+                vec![MessageContent::Text(
+                    "The `turn_finished` hook `echo 'tests failed'; exit 1` failed with \
+                    exit code 1. Its output:\n\n```\ntests failed\n```"
+                        .into()
+                )]
+            );
+        }
+        fake_model.send_last_completion_stream_text_chunk("Done");
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            StopReason::EndTurn,
+        ));
+        fake_model.end_last_completion_stream();
+    }
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+    assert!(fake_model.pending_completions().is_empty());
+}
+
 /// Filters out the stop events for asserting against in tests
 fn stop_events(result_events: Vec<Result<ThreadEvent>>) -> Vec<acp::StopReason> {
     result_events
@@ -5961,7 +6321,7 @@ async fn test_web_search_tool_deny_rule_blocks_search(cx: &mut TestAppContext) {
     });
 
     #[allow(clippy::arc_with_non_send_sync)]
-    let tool = Arc::new(crate::WebSearchTool);
+    let tool = Arc::new(crate::WebSearchTool::new(gpui::WeakEntity::new_invalid()));
     let (event_stream, _rx) = crate::ToolCallEventStream::test();
 
     let input: crate::WebSearchToolInput =
@@ -5982,6 +6342,73 @@ async fn test_web_search_tool_deny_rule_blocks_search(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_web_search_provider_per_profile(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    struct FakeWebSearchProvider;
+
+    impl web_search::WebSearchProvider for FakeWebSearchProvider {
+        fn id(&self) -> web_search::WebSearchProviderId {
+            web_search::WebSearchProviderId("searxng".into())
+        }
+
+        fn search(
+            &self,
+            _query: String,
+            _cx: &mut App,
+        ) -> Task<Result<cloud_llm_client::WebSearchResponse>> {
+            Task::ready(Ok(cloud_llm_client::WebSearchResponse { results: vec![] }))
+        }
+    }
+
+    cx.update(|cx| {
+        web_search::init(cx);
+        web_search::WebSearchRegistry::global(cx).update(cx, |registry, _| {
+            registry.set_setting_providers([
+                Arc::new(FakeWebSearchProvider) as Arc<dyn web_search::WebSearchProvider>
+            ]);
+        });
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings
+            .profiles
+            .get_mut(&AgentProfileId("write".into()))
+            .unwrap()
+            .web_search_provider = Some(settings::WebSearchProviderContent::Searxng);
+        settings
+            .profiles
+            .get_mut(&AgentProfileId("ask".into()))
+            .unwrap()
+            .web_search_provider = Some(settings::WebSearchProviderContent::Kagi);
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+
+    cx.update(|cx| {
+        let write = AgentProfileId("write".into());
+        let ask = AgentProfileId("ask".into());
+        let minimal = AgentProfileId("minimal".into());
+        let provider_id = language_model::LanguageModelProviderId("ollama".into());
+
+        let provider = crate::WebSearchTool::provider(Some(&write), cx).unwrap();
+        assert_eq!(provider.id().0, "searxng");
+        assert!(crate::WebSearchTool::is_available(&write, &provider_id, cx));
+
+        // Selected providers that aren't set up are offered, and explain
+        // themselves when used.
+        assert_eq!(
+            crate::WebSearchTool::provider(Some(&ask), cx).err(),
+            Some(
+                "The `kagi` web search provider isn't configured. Set it up in the `agent.web_search` settings."
+                    .to_string()
+            )
+        );
+        assert!(crate::WebSearchTool::is_available(&ask, &provider_id, cx));
+
+        // Without a selected provider, only Zed's provider is used.
+        assert!(!crate::WebSearchTool::is_available(&minimal, &provider_id, cx));
+    });
+}
+
 #[gpui::test]
 async fn test_edit_file_tool_allow_rule_skips_confirmation(cx: &mut TestAppContext) {
     init_test(cx);
@@ -1,13 +1,16 @@
+use crate::hooks::{HookEvent, HookRunner};
 use crate::{
-    ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
-    RestoreFileFromDiskTool, SaveFileTool, SpawnAgentTool, StreamingEditFileTool,
-    SystemPromptTemplate, Template, Templates, TerminalTool, ToolPermissionDecision, WebSearchTool,
-    decide_permission_from_settings,
+    BudgetCheck, BudgetScope, BudgetUsage, ContextServerRegistry, CopyPathTool,
+    CreateDirectoryTool, DailyUsage, DbLanguageModel, DbThread, DeletePathTool, DiagnosticsTool,
+    EditFileTool, FetchTool, FindDefinitionTool, FindPathTool, FindReferencesTool, GrepTool,
+    HoverTool, ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
+    RenameSymbolTool, RestoreFileFromDiskTool, SaveFileTool, SemanticSearchTool, SpawnAgentTool,
+    StreamingEditFileTool, SymbolSearchTool, SystemPromptTemplate, Template, Templates,
+    TerminalTool, ThreadBudget, ThreadsDatabase, ToolPermissionDecision, WebSearchTool,
+    check_budget, decide_permission_from_settings,
 };
 use acp_thread::{MentionUri, UserMessageId};
-use action_log::ActionLog;
+use action_log::{ActionLog, ActionLogEvent};
 use feature_flags::{FeatureFlagAppExt as _, StreamingEditFileToolFeatureFlag};
 
 use agent_client_protocol as acp;
@@ -33,6 +36,7 @@ use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity,
 };
 use heck::ToSnakeCase as _;
+use language::Buffer;
 use language_model::{
     LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId,
     LanguageModelImage, LanguageModelProviderId, LanguageModelRegistry, LanguageModelRequest,
@@ -44,11 +48,13 @@ use language_model::{
 use project::Project;
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
+use semantic_index::SemanticIndexSettings;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use settings::{LanguageModelSelection, Settings, ToolPermissionMode, update_settings_file};
 use smol::stream::StreamExt;
 use std::{
+    cell::RefCell,
     collections::BTreeMap,
     marker::PhantomData,
     ops::RangeInclusive,
@@ -64,6 +70,9 @@ use uuid::Uuid;
 const TOOL_CANCELED_MESSAGE: &str = "Tool canceled by user";
 pub const MAX_TOOL_NAME_LENGTH: usize = 64;
 pub const MAX_SUBAGENT_DEPTH: u8 = 1;
+/// How many times failing `turn_finished` hooks can make the model continue a
+/// turn, so that a hook it can't satisfy doesn't keep it going forever.
+pub(crate) const MAX_TURN_FINISHED_HOOK_FAILURES: usize = 3;
 
 /// Context passed to a subagent thread for lifecycle management
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -220,6 +229,7 @@ impl UserMessage {
         const OPEN_DIAGNOSTICS_TAG: &str = "<diagnostics>";
         const OPEN_DIFFS_TAG: &str = "<diffs>";
         const MERGE_CONFLICT_TAG: &str = "<merge_conflicts>";
+        const OPEN_RESOURCES_TAG: &str = "<resources>";
 
         let mut file_context = OPEN_FILES_TAG.to_string();
         let mut directory_context = OPEN_DIRECTORIES_TAG.to_string();
@@ -231,6 +241,7 @@ impl UserMessage {
         let mut diagnostics_context = OPEN_DIAGNOSTICS_TAG.to_string();
         let mut diffs_context = OPEN_DIFFS_TAG.to_string();
         let mut merge_conflict_context = MERGE_CONFLICT_TAG.to_string();
+        let mut resource_context = OPEN_RESOURCES_TAG.to_string();
 
         for chunk in &self.content {
             let chunk = match chunk {
@@ -350,6 +361,14 @@ impl UserMessage {
                             )
                             .ok();
                         }
+                        MentionUri::McpResource { server_id, uri, .. } => {
+                            write!(
+                                &mut resource_context,
+                                "\nResource {} from {}:\n\n{}\n",
+                                uri, server_id, content
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -431,6 +450,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(merge_conflict_context));
         }
 
+        if resource_context.len() > OPEN_RESOURCES_TAG.len() {
+            resource_context.push_str("</resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(resource_context));
+        }
+
         if message.content.len() > len_before_context {
             message.content.insert(
                 len_before_context,
@@ -899,6 +925,12 @@ pub struct Thread {
     request_token_usage: HashMap<UserMessageId, language_model::TokenUsage>,
     #[allow(unused)]
     cumulative_token_usage: TokenUsage,
+    budget: ThreadBudget,
+    /// The usage last reported for the request being streamed, which is
+    /// counted toward the budgets when the request ends.
+    request_usage: Option<TokenUsage>,
+    budget_warning: Option<SharedString>,
+    dismissed_budget_warning: Option<SharedString>,
     #[allow(unused)]
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
     pub(crate) context_server_registry: Entity<ContextServerRegistry>,
@@ -1014,6 +1046,10 @@ impl Thread {
             tools: BTreeMap::default(),
             request_token_usage: HashMap::default(),
             cumulative_token_usage: TokenUsage::default(),
+            budget: ThreadBudget::default(),
+            request_usage: None,
+            budget_warning: None,
+            dismissed_budget_warning: None,
             initial_project_snapshot: {
                 let project_snapshot = Self::project_snapshot(project.clone(), cx);
                 cx.foreground_executor()
@@ -1234,6 +1270,10 @@ impl Thread {
             tools: BTreeMap::default(),
             request_token_usage: db_thread.request_token_usage.clone(),
             cumulative_token_usage: db_thread.cumulative_token_usage,
+            budget: db_thread.budget,
+            request_usage: None,
+            budget_warning: None,
+            dismissed_budget_warning: None,
             initial_project_snapshot: Task::ready(db_thread.initial_project_snapshot).shared(),
             context_server_registry,
             profile_id,
@@ -1270,6 +1310,7 @@ impl Thread {
             initial_project_snapshot: None,
             cumulative_token_usage: self.cumulative_token_usage,
             request_token_usage: self.request_token_usage.clone(),
+            budget: self.budget,
             model: self.model.as_ref().map(|model| DbLanguageModel {
                 provider: model.provider_id().to_string(),
                 model: model.id().0.to_string(),
@@ -1477,8 +1518,14 @@ impl Thread {
             language_registry,
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
+        self.add_tool(FindDefinitionTool::new(self.project.clone()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        self.add_tool(HoverTool::new(self.project.clone()));
+        if SemanticIndexSettings::get_global(cx).enabled {
+            self.add_tool(SemanticSearchTool::new(self.project.clone()));
+        }
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(NowTool);
@@ -1488,10 +1535,15 @@ impl Thread {
             self.action_log.clone(),
             update_agent_location,
         ));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
+        self.add_tool(SymbolSearchTool::new(self.project.clone()));
         self.add_tool(TerminalTool::new(self.project.clone(), environment.clone()));
-        self.add_tool(WebSearchTool);
+        self.add_tool(WebSearchTool::new(cx.weak_entity()));
 
         if self.depth() < MAX_SUBAGENT_DEPTH {
             self.add_tool(SpawnAgentTool::new(environment));
@@ -1566,6 +1618,39 @@ impl Thread {
         self.has_queued_message
     }
 
+    /// Returns the warning about a budget that's close to being reached,
+    /// unless it was dismissed.
+    pub fn budget_warning(&self) -> Option<&SharedString> {
+        self.budget_warning
+            .as_ref()
+            .filter(|warning| Some(*warning) != self.dismissed_budget_warning.as_ref())
+    }
+
+    pub fn dismiss_budget_warning(&mut self, cx: &mut Context<Self>) {
+        self.dismissed_budget_warning = self.budget_warning.clone();
+        cx.notify();
+    }
+
+    /// Extends a budget that was reached, allowing another budget's worth of
+    /// usage. Daily budgets are extended until the end of the day.
+    pub fn extend_budget(
+        &mut self,
+        scope: &BudgetScope,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if *scope == BudgetScope::Thread {
+            self.budget.extensions += 1;
+            cx.notify();
+            return Task::ready(Ok(()));
+        }
+        let database = ThreadsDatabase::connect(cx);
+        let scope = scope.clone();
+        cx.background_spawn(async move {
+            let database = database.await.map_err(|err| anyhow!(err))?;
+            database.extend_budget(crate::budget::today(), &scope).await
+        })
+    }
+
     fn update_token_usage(&mut self, update: language_model::TokenUsage, cx: &mut Context<Self>) {
         let Some(last_user_message) = self.last_user_message() else {
             return;
@@ -1845,7 +1930,9 @@ impl Thread {
     ) -> Result<()> {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+        let mut turn_finished_hook_failures = 0;
         loop {
+            Self::check_budgets(this, cx).await?;
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -1963,6 +2050,7 @@ impl Thread {
             // tool execution, which could cause deadlocks when tools spawn subagents
             // that need their own permits.
             drop(events);
+            Self::record_request_usage(this, &model, cx).await;
 
             // Drop streaming tool input senders that never received their final input.
             // This prevents deadlock when the LLM stream ends (e.g. because of an error)
@@ -2024,7 +2112,20 @@ impl Thread {
                     }
                 })?;
             } else if end_turn {
-                return Ok(());
+                if turn_finished_hook_failures >= MAX_TURN_FINISHED_HOOK_FAILURES {
+                    return Ok(());
+                }
+                let Some(message) = Self::run_turn_finished_hooks(this, cx).await? else {
+                    return Ok(());
+                };
+                turn_finished_hook_failures += 1;
+                event_stream.send_user_message(&message);
+                this.update(cx, |this, cx| {
+                    this.messages.push(Message::User(message));
+                    cx.notify();
+                })?;
+                intent = CompletionIntent::UserPrompt;
+                attempt = 0;
             } else {
                 let has_queued = this.update(cx, |this, _| this.has_queued_message())?;
                 if has_queued {
@@ -2037,6 +2138,131 @@ impl Thread {
         }
     }
 
+    /// Fails with a [`crate::BudgetExceededError`] when one of the budgets
+    /// that apply to the thread was reached, and warns when one is close to
+    /// being reached.
+    async fn check_budgets(this: &WeakEntity<Self>, cx: &mut AsyncApp) -> Result<()> {
+        let (budgets, profile_id, profile_budget, thread_budget) =
+            this.read_with(cx, |this, cx| {
+                let settings = AgentSettings::get_global(cx);
+                let profile_budget = settings
+                    .profiles
+                    .get(&this.profile_id)
+                    .map(|profile| profile.budget)
+                    .unwrap_or_default();
+                (
+                    settings.budgets.clone(),
+                    this.profile_id.clone(),
+                    profile_budget,
+                    this.budget,
+                )
+            })?;
+
+        let daily_usage = if budgets.daily.is_unlimited() && profile_budget.is_unlimited() {
+            DailyUsage::default()
+        } else {
+            let database = cx.update(ThreadsDatabase::connect);
+            let database = database.await.map_err(|err| anyhow!(err))?;
+            database
+                .daily_usage(crate::budget::today(), profile_id.clone())
+                .await?
+        };
+
+        let checks = [
+            check_budget(
+                BudgetScope::Thread,
+                &budgets.thread,
+                thread_budget.extensions,
+                thread_budget.usage,
+                budgets.warning_threshold,
+            ),
+            check_budget(
+                BudgetScope::Daily,
+                &budgets.daily,
+                daily_usage.daily_extensions,
+                daily_usage.total,
+                budgets.warning_threshold,
+            ),
+            check_budget(
+                BudgetScope::Profile(profile_id),
+                &profile_budget,
+                daily_usage.profile_extensions,
+                daily_usage.profile,
+                budgets.warning_threshold,
+            ),
+        ];
+        let mut warning = None;
+        for check in checks {
+            match check {
+                BudgetCheck::WithinBudget => {}
+                BudgetCheck::Warning(message) => {
+                    warning.get_or_insert(SharedString::from(message));
+                }
+                BudgetCheck::Exceeded(error) => return Err(error.into()),
+            }
+        }
+        this.update(cx, |this, cx| {
+            if this.budget_warning != warning {
+                this.budget_warning = warning;
+                cx.notify();
+            }
+        })
+    }
+
+    /// Counts the usage of the request that just ended toward the thread's
+    /// budget and the daily budgets.
+    async fn record_request_usage(
+        this: &WeakEntity<Self>,
+        model: &Arc<dyn LanguageModel>,
+        cx: &mut AsyncApp,
+    ) {
+        let Ok(Some((usage, profile_id, database))) = this.update(cx, |this, cx| {
+            let usage = this.request_usage.take()?;
+            let usage = BudgetUsage::for_request(&usage, model.model_cost_info().as_ref());
+            this.budget.usage += usage;
+            Some((usage, this.profile_id.clone(), ThreadsDatabase::connect(cx)))
+        }) else {
+            return;
+        };
+        match database.await {
+            Ok(database) => {
+                database
+                    .record_usage(crate::budget::today(), profile_id, usage)
+                    .await
+                    .log_err();
+            }
+            Err(error) => log::error!("failed to record the agent's usage: {error}"),
+        }
+    }
+
+    /// Runs the `turn_finished` hooks, returning a message describing the
+    /// ones that failed, which is sent to the model to continue the turn.
+    async fn run_turn_finished_hooks(
+        this: &WeakEntity<Self>,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<UserMessage>> {
+        let (hooks, hook_runner) = this.update(cx, |this, cx| {
+            (
+                AgentSettings::get_global(cx).hooks.turn_finished.clone(),
+                this.hook_runner(cx),
+            )
+        })?;
+        let mut failures = Vec::new();
+        for hook in &hooks {
+            let output = hook_runner.run(hook, &HookEvent::TurnFinished, cx).await;
+            if !output.succeeded() {
+                failures.extend(output.to_model_text());
+            }
+        }
+        if failures.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(UserMessage {
+            id: UserMessageId::new(),
+            content: vec![UserMessageContent::Text(failures.join("\n\n"))],
+        }))
+    }
+
     fn process_tool_result(
         this: &WeakEntity<Thread>,
         event_stream: &ThreadEventStream,
@@ -2182,6 +2408,7 @@ impl Thread {
                     cache_creation_input_tokens = usage.cache_creation_input_tokens,
                     cache_read_input_tokens = usage.cache_read_input_tokens,
                 );
+                self.request_usage = Some(usage);
                 self.update_token_usage(usage, cx);
             }
             Stop(StopReason::Refusal) => return Err(CompletionError::Refusal.into()),
@@ -2327,6 +2554,186 @@ impl Thread {
         event_stream: &ThreadEventStream,
         cancellation_rx: watch::Receiver<bool>,
         cx: &mut Context<Self>,
+    ) -> Task<LanguageModelToolResult> {
+        let hooks = AgentSettings::get_global(cx).hooks.for_tool(&tool_name);
+        if hooks.is_empty() {
+            return self.start_tool(
+                tool,
+                tool_input,
+                tool_use_id,
+                tool_name,
+                event_stream,
+                cancellation_rx,
+                cx,
+            );
+        }
+
+        let hook_runner = self.hook_runner(cx);
+        let event_stream = event_stream.clone();
+        cx.spawn(async move |this, cx| {
+            // Hooks are given the whole input, so streaming tools wait for it
+            // before they start.
+            let mut input = match tool_input.recv().await {
+                Ok(input) => input,
+                Err(error) => {
+                    return LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name,
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text(error.to_string().into()),
+                        output: None,
+                    };
+                }
+            };
+
+            let mut hook_notes = Vec::new();
+            for hook in &hooks.before_tool_call {
+                let output = hook_runner
+                    .run(
+                        hook,
+                        &HookEvent::BeforeToolCall {
+                            tool_name: &tool_name,
+                            tool_input: &input,
+                        },
+                        cx,
+                    )
+                    .await;
+                if !output.succeeded() {
+                    let reason = output
+                        .to_model_text()
+                        .unwrap_or_else(|| "A `before_tool_call` hook failed.".to_string());
+                    return LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name,
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text(
+                            format!("The tool call was blocked by a hook. {reason}").into(),
+                        ),
+                        output: None,
+                    };
+                }
+                match output.replaced_tool_input() {
+                    Some(replaced_input) => input = replaced_input,
+                    None => hook_notes.extend(output.to_model_text()),
+                }
+            }
+
+            let edited_buffers = Rc::new(RefCell::new(Vec::<Entity<Buffer>>::new()));
+            let started = this.update(cx, |this, cx| {
+                let subscription = (!hooks.after_edit.is_empty()).then(|| {
+                    let edited_buffers = edited_buffers.clone();
+                    cx.subscribe(&this.action_log, move |_, _, event: &ActionLogEvent, _| {
+                        let ActionLogEvent::BufferEdited(buffer) = event;
+                        let mut edited_buffers = edited_buffers.borrow_mut();
+                        if !edited_buffers.contains(buffer) {
+                            edited_buffers.push(buffer.clone());
+                        }
+                    })
+                });
+                let task = this.start_tool(
+                    tool,
+                    ToolInput::ready(input.clone()),
+                    tool_use_id.clone(),
+                    tool_name.clone(),
+                    &event_stream,
+                    cancellation_rx,
+                    cx,
+                );
+                (task, subscription)
+            });
+            let Ok((task, subscription)) = started else {
+                return LanguageModelToolResult {
+                    tool_use_id,
+                    tool_name,
+                    is_error: true,
+                    content: LanguageModelToolResultContent::Text("The thread was closed".into()),
+                    output: None,
+                };
+            };
+            let mut result = task.await;
+            drop(subscription);
+
+            let tool_output = match &result.content {
+                LanguageModelToolResultContent::Text(text) => text.to_string(),
+                LanguageModelToolResultContent::Image(_) => String::new(),
+            };
+            for hook in &hooks.after_tool_call {
+                let output = hook_runner
+                    .run(
+                        hook,
+                        &HookEvent::AfterToolCall {
+                            tool_name: &tool_name,
+                            tool_input: &input,
+                            tool_output: &tool_output,
+                            is_error: result.is_error,
+                        },
+                        cx,
+                    )
+                    .await;
+                hook_notes.extend(output.to_model_text());
+            }
+
+            let edited_paths = edited_buffers
+                .take()
+                .into_iter()
+                .filter_map(|buffer| {
+                    buffer.read_with(cx, |buffer, cx| {
+                        buffer.file()?.as_local().map(|file| file.abs_path(cx))
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !edited_paths.is_empty() {
+                for hook in &hooks.after_edit {
+                    let output = hook_runner
+                        .run(
+                            hook,
+                            &HookEvent::AfterEdit {
+                                tool_name: &tool_name,
+                                paths: &edited_paths,
+                            },
+                            cx,
+                        )
+                        .await;
+                    hook_notes.extend(output.to_model_text());
+                }
+            }
+
+            if !hook_notes.is_empty()
+                && let LanguageModelToolResultContent::Text(text) = &result.content
+            {
+                result.content = LanguageModelToolResultContent::Text(
+                    format!("{text}\n\n{}", hook_notes.join("\n\n")).into(),
+                );
+            }
+            result
+        })
+    }
+
+    fn hook_runner(&self, cx: &App) -> HookRunner {
+        let project = self.project.read(cx);
+        let worktree = project.visible_worktrees(cx).next();
+        let cwd = worktree
+            .as_ref()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+        let runner = HookRunner::new(self.id.to_string(), cwd, cx.background_executor().clone());
+        match project.task_store().read(cx).task_inventory() {
+            Some(inventory) => runner.with_task_inventory(
+                inventory.clone(),
+                worktree.map(|worktree| worktree.read(cx).id()),
+            ),
+            None => runner,
+        }
+    }
+
+    fn start_tool(
+        &self,
+        tool: Arc<dyn AnyAgentTool>,
+        tool_input: ToolInput<serde_json::Value>,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        event_stream: &ThreadEventStream,
+        cancellation_rx: watch::Receiver<bool>,
+        cx: &mut Context<Self>,
     ) -> Task<LanguageModelToolResult> {
         let fs = self.project.read(cx).fs().clone();
         let tool_event_stream = ToolCallEventStream::new(
@@ -2722,8 +3129,13 @@ impl Thread {
                     tool_name.as_ref()
                 };
 
+                // Web search is only offered when there's a provider to search with.
+                let available = tool_name != WebSearchTool::NAME
+                    || WebSearchTool::is_available(self.profile(), &model.provider_id(), cx);
+
                 if tool.supports_provider(&model.provider_id())
                     && profile.is_tool_enabled(profile_tool_name)
+                    && available
                 {
                     match (tool_name.as_ref(), use_streaming_edit_tool) {
                         (StreamingEditFileTool::NAME, false) | (EditFileTool::NAME, true) => None,
@@ -154,6 +154,7 @@ mod tests {
             initial_project_snapshot: None,
             cumulative_token_usage: Default::default(),
             request_token_usage: HashMap::default(),
+            budget: Default::default(),
             model: None,
             profile: None,
             imported: false,
@@ -561,6 +561,10 @@ mod tests {
             tool_permissions,
             show_turn_stats: false,
             new_thread_location: Default::default(),
+            terminal_sandbox: Default::default(),
+            hooks: Default::default(),
+            budgets: Default::default(),
+            web_search_provider: None,
         }
     }
 
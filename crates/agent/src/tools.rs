@@ -5,17 +5,24 @@ mod delete_path_tool;
 mod diagnostics_tool;
 mod edit_file_tool;
 mod fetch_tool;
+mod find_definition_tool;
 mod find_path_tool;
+mod find_references_tool;
 mod grep_tool;
+mod hover_tool;
 mod list_directory_tool;
 mod move_path_tool;
 mod now_tool;
 mod open_tool;
 mod read_file_tool;
+mod rename_symbol_tool;
 mod restore_file_from_disk_tool;
 mod save_file_tool;
+mod semantic_search_tool;
 mod spawn_agent_tool;
 mod streaming_edit_file_tool;
+mod symbol_position;
+mod symbol_search_tool;
 mod terminal_tool;
 mod tool_edit_parser;
 mod tool_permissions;
@@ -31,17 +38,24 @@ pub use delete_path_tool::*;
 pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
 pub use fetch_tool::*;
+pub use find_definition_tool::*;
 pub use find_path_tool::*;
+pub use find_references_tool::*;
 pub use grep_tool::*;
+pub use hover_tool::*;
 pub use list_directory_tool::*;
 pub use move_path_tool::*;
 pub use now_tool::*;
 pub use open_tool::*;
 pub use read_file_tool::*;
+pub use rename_symbol_tool::*;
 pub use restore_file_from_disk_tool::*;
 pub use save_file_tool::*;
+pub use semantic_search_tool::*;
 pub use spawn_agent_tool::*;
 pub use streaming_edit_file_tool::*;
+pub use symbol_position::SymbolPosition;
+pub use symbol_search_tool::*;
 pub use terminal_tool::*;
 pub use tool_permissions::*;
 pub use web_search_tool::*;
@@ -121,16 +135,22 @@ tools! {
     DiagnosticsTool,
     EditFileTool,
     FetchTool,
+    FindDefinitionTool,
     FindPathTool,
+    FindReferencesTool,
     GrepTool,
+    HoverTool,
     ListDirectoryTool,
     MovePathTool,
     NowTool,
     OpenTool,
     ReadFileTool,
+    RenameSymbolTool,
     RestoreFileFromDiskTool,
     SaveFileTool,
+    SemanticSearchTool,
     SpawnAgentTool,
+    SymbolSearchTool,
     TerminalTool,
     WebSearchTool,
 }
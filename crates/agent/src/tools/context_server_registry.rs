@@ -21,9 +21,30 @@ pub struct ContextServerPrompt {
     pub prompt: context_server::types::Prompt,
 }
 
+/// A resource listed by a context server through `resources/list`.
+#[derive(Clone, Debug)]
+pub struct ContextServerResource {
+    pub server_id: ContextServerId,
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+/// A parameterized resource listed by a context server through
+/// `resources/templates/list`.
+#[derive(Clone, Debug)]
+pub struct ContextServerResourceTemplate {
+    pub server_id: ContextServerId,
+    pub uri_template: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
 pub enum ContextServerRegistryEvent {
     ToolsChanged,
     PromptsChanged,
+    ResourcesChanged,
 }
 
 impl EventEmitter<ContextServerRegistryEvent> for ContextServerRegistry {}
@@ -37,9 +58,13 @@ pub struct ContextServerRegistry {
 struct RegisteredContextServer {
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
     prompts: BTreeMap<SharedString, ContextServerPrompt>,
+    resources: Vec<ContextServerResource>,
+    resource_templates: Vec<ContextServerResourceTemplate>,
     load_tools: Task<Result<()>>,
     load_prompts: Task<Result<()>>,
+    load_resources: Task<Result<()>>,
     _tools_updated_subscription: Option<NotificationSubscription>,
+    _resources_updated_subscription: Option<NotificationSubscription>,
 }
 
 impl ContextServerRegistry {
@@ -52,6 +77,7 @@ impl ContextServerRegistry {
         for server in server_store.read(cx).running_servers() {
             this.reload_tools_for_server(server.id(), cx);
             this.reload_prompts_for_server(server.id(), cx);
+            this.reload_resources_for_server(server.id(), cx);
         }
         this
     }
@@ -102,6 +128,63 @@ impl ContextServerRegistry {
         }
     }
 
+    pub fn resources(&self) -> impl Iterator<Item = &ContextServerResource> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resources.iter())
+    }
+
+    pub fn resource_templates(&self) -> impl Iterator<Item = &ContextServerResourceTemplate> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resource_templates.iter())
+    }
+
+    /// Asks a server to suggest values for one argument of a resource template,
+    /// via `completion/complete`. Servers that don't advertise the completions
+    /// capability yield no suggestions.
+    pub fn complete_resource_template_argument(
+        &self,
+        server_id: &ContextServerId,
+        uri_template: String,
+        argument_name: String,
+        argument_value: String,
+        cx: &App,
+    ) -> Task<Result<Vec<String>>> {
+        let Some(client) = self
+            .server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        if client.initialize.capabilities.completions.is_none() {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        cx.background_spawn(async move {
+            let response = client
+                .request::<context_server::types::requests::CompletionComplete>(
+                    context_server::types::CompletionCompleteParams {
+                        reference: context_server::types::CompletionReference::Resource(
+                            context_server::types::ResourceReference {
+                                ty: context_server::types::PromptReferenceType::Resource,
+                                uri: uri_template,
+                            },
+                        ),
+                        argument: context_server::types::CompletionArgument {
+                            name: argument_name,
+                            value: argument_value,
+                        },
+                        meta: None,
+                    },
+                )
+                .await?;
+            Ok(response.completion.values)
+        })
+    }
+
     pub fn server_store(&self) -> &Entity<ContextServerStore> {
         &self.server_store
     }
@@ -153,12 +236,44 @@ impl ContextServerRegistry {
                 ))
             });
 
+        let resources_updated_subscription = server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| {
+                let client = server.client()?;
+
+                if !client.capable(context_server::protocol::ServerCapability::Resources) {
+                    return None;
+                }
+
+                let server_id = server.id();
+                let this = cx.entity().downgrade();
+
+                Some(client.on_notification(
+                    "notifications/resources/list_changed",
+                    Box::new(move |_params, cx: AsyncApp| {
+                        let server_id = server_id.clone();
+                        let this = this.clone();
+                        cx.spawn(async move |cx| {
+                            this.update(cx, |this, cx| {
+                                this.reload_resources_for_server(server_id, cx);
+                            })
+                        })
+                        .detach();
+                    }),
+                ))
+            });
+
         RegisteredContextServer {
             tools: BTreeMap::default(),
             prompts: BTreeMap::default(),
+            resources: Vec::new(),
+            resource_templates: Vec::new(),
             load_tools: Task::ready(Ok(())),
             load_prompts: Task::ready(Ok(())),
+            load_resources: Task::ready(Ok(())),
             _tools_updated_subscription: tools_updated_subscription,
+            _resources_updated_subscription: resources_updated_subscription,
         }
     }
 
@@ -244,6 +359,64 @@ impl ContextServerRegistry {
         });
     }
 
+    fn reload_resources_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
+        let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        if !client.capable(context_server::protocol::ServerCapability::Resources) {
+            return;
+        }
+
+        let registered_server = self.get_or_register_server(&server_id, cx);
+
+        registered_server.load_resources = cx.spawn(async move |this, cx| {
+            let resources = client
+                .request::<context_server::types::requests::ResourcesList>(())
+                .await;
+            // Templates are optional, so servers that don't implement
+            // `resources/templates/list` still contribute their resources.
+            let resource_templates = client
+                .request::<context_server::types::requests::ListResourceTemplates>(())
+                .await
+                .map(|response| response.resource_templates)
+                .unwrap_or_default();
+
+            this.update(cx, |this, cx| {
+                let Some(registered_server) = this.registered_servers.get_mut(&server_id) else {
+                    return;
+                };
+
+                if let Some(response) = resources.log_err() {
+                    registered_server.resources = response
+                        .resources
+                        .into_iter()
+                        .map(|resource| ContextServerResource {
+                            server_id: server_id.clone(),
+                            uri: resource.uri.to_string(),
+                            name: resource.name,
+                            description: resource.description,
+                            mime_type: resource.mime_type,
+                        })
+                        .collect();
+                    registered_server.resource_templates = resource_templates
+                        .into_iter()
+                        .map(|template| ContextServerResourceTemplate {
+                            server_id: server_id.clone(),
+                            uri_template: template.uri_template,
+                            name: template.name,
+                            description: template.description,
+                        })
+                        .collect();
+                    cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                    cx.notify();
+                }
+            })
+        });
+    }
+
     fn handle_context_server_store_event(
         &mut self,
         _: Entity<ContextServerStore>,
@@ -257,8 +430,11 @@ impl ContextServerRegistry {
             ContextServerStatus::Running => {
                 self.reload_tools_for_server(server_id.clone(), cx);
                 self.reload_prompts_for_server(server_id.clone(), cx);
+                self.reload_resources_for_server(server_id.clone(), cx);
             }
-            ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
+            ContextServerStatus::Stopped
+            | ContextServerStatus::Error(_)
+            | ContextServerStatus::AuthRequired => {
                 if let Some(registered_server) = self.registered_servers.remove(server_id) {
                     if !registered_server.tools.is_empty() {
                         cx.emit(ContextServerRegistryEvent::ToolsChanged);
@@ -266,6 +442,11 @@ impl ContextServerRegistry {
                     if !registered_server.prompts.is_empty() {
                         cx.emit(ContextServerRegistryEvent::PromptsChanged);
                     }
+                    if !registered_server.resources.is_empty()
+                        || !registered_server.resource_templates.is_empty()
+                    {
+                        cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                    }
                 }
                 cx.notify();
             }
@@ -0,0 +1,219 @@
+use super::symbol_position::{SymbolPosition, format_locations};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Finds where a symbol is defined, using the project's language servers.
+///
+/// Prefer this over searching for the symbol's name with grep: it resolves imports, methods and shadowed names the way the compiler does.
+///
+/// Set `type_definition` to find the definition of the symbol's type instead, e.g. the struct of a variable.
+///
+/// <example>
+/// To find the definition of `parse_config` called on line 42 of `app/src/main.rs`:
+/// {
+///     "path": "app/src/main.rs",
+///     "line": 42,
+///     "symbol": "parse_config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindDefinitionToolInput {
+    #[serde(flatten)]
+    pub position: SymbolPosition,
+    /// Whether to find the definition of the symbol's type rather than of the symbol itself.
+    #[serde(default)]
+    pub type_definition: bool,
+}
+
+pub struct FindDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl FindDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindDefinitionTool {
+    type Input = FindDefinitionToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "find_definition";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) if input.type_definition => {
+                format!("Find type definition of {}", input.position.title()).into()
+            }
+            Ok(input) => format!("Find definition of {}", input.position.title()).into(),
+            Err(_) => "Find definition".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let (buffer, position) = input
+                .position
+                .resolve(&project, &event_stream, cx)
+                .await?;
+
+            let definitions = project.update(cx, |project, cx| {
+                if input.type_definition {
+                    project.type_definitions(&buffer, position, cx)
+                } else {
+                    project.definitions(&buffer, position, cx)
+                }
+            });
+            let definitions = futures::select! {
+                result = definitions.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Finding definition cancelled by user".to_string());
+                }
+            };
+            let Some(definitions) = definitions.filter(|definitions| !definitions.is_empty())
+            else {
+                return Ok(format!(
+                    "No definition found for `{}`. The file's language may not have a language server, or it may still be starting.",
+                    input.position.symbol
+                ));
+            };
+
+            Ok(cx.update(|cx| {
+                format_locations(
+                    definitions
+                        .into_iter()
+                        .map(|link| (link.target.buffer, link.target.range)),
+                    50,
+                    cx,
+                )
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_find_definition(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "lib.rs": "pub fn helper() {}\n",
+                    "main.rs": "fn main() {\n    lib::helper();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    definition_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let _handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::request::GotoDefinition, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 9)
+                );
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                    lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 13)),
+                    ),
+                )))
+            },
+        );
+
+        let tool = Arc::new(FindDefinitionTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(FindDefinitionToolInput {
+                        position: SymbolPosition {
+                            path: "root/src/main.rs".into(),
+                            line: 2,
+                            symbol: "helper".into(),
+                        },
+                        type_definition: false,
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, "root/src/lib.rs\n  1: pub fn helper() {}\n");
+    }
+}
@@ -0,0 +1,219 @@
+use super::symbol_position::{SymbolPosition, format_locations};
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// At most this many references are listed.
+const MAX_REFERENCES: usize = 200;
+
+/// Finds all references to a symbol in the project, using the project's language servers.
+///
+/// Unlike grep, this only finds the uses of this particular symbol, and not of other symbols that share its name. Use it to see how a function is called, or what a change to it would affect.
+///
+/// The references are grouped by file, with the line number and the text of each line.
+///
+/// <example>
+/// To find the callers of the `Config::load` method defined on line 17 of `app/src/config.rs`:
+/// {
+///     "path": "app/src/config.rs",
+///     "line": 17,
+///     "symbol": "load"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    #[serde(flatten)]
+    pub position: SymbolPosition,
+}
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "find_references";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Find references to {}", input.position.title()).into(),
+            Err(_) => "Find references".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let (buffer, position) = input
+                .position
+                .resolve(&project, &event_stream, cx)
+                .await?;
+
+            let references =
+                project.update(cx, |project, cx| project.references(&buffer, position, cx));
+            let references = futures::select! {
+                result = references.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Finding references cancelled by user".to_string());
+                }
+            };
+            let Some(references) = references.filter(|references| !references.is_empty()) else {
+                return Ok(format!(
+                    "No references found for `{}`. The file's language may not have a language server, or it may still be starting.",
+                    input.position.symbol
+                ));
+            };
+
+            Ok(cx.update(|cx| {
+                format_locations(
+                    references
+                        .into_iter()
+                        .map(|location| (location.buffer, location.range)),
+                    MAX_REFERENCES,
+                    cx,
+                )
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "lib.rs": "pub fn helper() {}\n",
+                    "main.rs": "fn main() {\n    lib::helper();\n    lib::helper();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let _handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>(
+            |params, _| async move {
+                assert_eq!(
+                    params.text_document_position.position,
+                    lsp::Position::new(0, 7)
+                );
+                let main = lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap();
+                let lib = lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap();
+                let range = |line, start, end| {
+                    lsp::Range::new(
+                        lsp::Position::new(line, start),
+                        lsp::Position::new(line, end),
+                    )
+                };
+                Ok(Some(vec![
+                    lsp::Location::new(main.clone(), range(2, 9, 15)),
+                    lsp::Location::new(lib, range(0, 7, 13)),
+                    lsp::Location::new(main, range(1, 9, 15)),
+                ]))
+            },
+        );
+
+        let tool = Arc::new(FindReferencesTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(FindReferencesToolInput {
+                        position: SymbolPosition {
+                            path: "root/src/lib.rs".into(),
+                            line: 1,
+                            symbol: "helper".into(),
+                        },
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            "root/src/main.rs\n  2: lib::helper();\n  3: lib::helper();\nroot/src/lib.rs\n  1: pub fn helper() {}\n"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use super::symbol_position::SymbolPosition;
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::{HoverBlock, HoverBlockKind, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Hover text is truncated to this many bytes, since some language servers
+/// include a symbol's entire documentation.
+const MAX_HOVER_LEN: usize = 4 * 1024;
+
+/// Shows a symbol's type and documentation, as the project's language server describes it when hovering over the symbol in the editor.
+///
+/// Use this instead of guessing a variable's type or a function's signature, or reading the files that define them.
+///
+/// <example>
+/// To get the type of the `config` variable declared on line 42 of `app/src/main.rs`:
+/// {
+///     "path": "app/src/main.rs",
+///     "line": 42,
+///     "symbol": "config"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    #[serde(flatten)]
+    pub position: SymbolPosition,
+}
+
+pub struct HoverTool {
+    project: Entity<Project>,
+}
+
+impl HoverTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for HoverTool {
+    type Input = HoverToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "hover";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Get type info for {}", input.position.title()).into(),
+            Err(_) => "Get type info".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let (buffer, position) = input
+                .position
+                .resolve(&project, &event_stream, cx)
+                .await?;
+
+            let hovers = project.update(cx, |project, cx| project.hover(&buffer, position, cx));
+            let hovers = futures::select! {
+                hovers = hovers.fuse() => hovers.unwrap_or_default(),
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Getting type info cancelled by user".to_string());
+                }
+            };
+
+            let text = format_hover_blocks(hovers.iter().flat_map(|hover| &hover.contents));
+            if text.is_empty() {
+                return Ok(format!(
+                    "No type info found for `{}`. The file's language may not have a language server, or it may still be starting.",
+                    input.position.symbol
+                ));
+            }
+            Ok(text)
+        })
+    }
+}
+
+fn format_hover_blocks<'a>(blocks: impl IntoIterator<Item = &'a HoverBlock>) -> String {
+    let mut text = String::new();
+    for block in blocks {
+        let block_text = block.text.trim();
+        if block_text.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        match &block.kind {
+            HoverBlockKind::Code { language } => {
+                text.push_str(&format!("```{language}\n{block_text}\n```"));
+            }
+            HoverBlockKind::PlainText | HoverBlockKind::Markdown => text.push_str(block_text),
+        }
+    }
+    if text.len() > MAX_HOVER_LEN {
+        let mut end = MAX_HOVER_LEN;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n[truncated]");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_hover_blocks() {
+        let blocks = [
+            HoverBlock {
+                text: "fn load(path: &Path) -> Result<Config>".into(),
+                kind: HoverBlockKind::Code {
+                    language: "rust".into(),
+                },
+            },
+            HoverBlock {
+                text: "  \n".into(),
+                kind: HoverBlockKind::PlainText,
+            },
+            HoverBlock {
+                text: "Loads the config at `path`.\n".into(),
+                kind: HoverBlockKind::Markdown,
+            },
+        ];
+        assert_eq!(
+            format_hover_blocks(&blocks),
+            "```rust\nfn load(path: &Path) -> Result<Config>\n```\n\nLoads the config at `path`."
+        );
+
+        let long = HoverBlock {
+            text: "é".repeat(MAX_HOVER_LEN),
+            kind: HoverBlockKind::Markdown,
+        };
+        let text = format_hover_blocks([&long]);
+        assert!(text.ends_with("\n[truncated]"));
+        assert!(text.len() <= MAX_HOVER_LEN + "\n[truncated]".len());
+    }
+}
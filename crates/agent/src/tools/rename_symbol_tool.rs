@@ -0,0 +1,430 @@
+use super::symbol_position::{SymbolPosition, buffer_path, format_locations};
+use crate::{
+    AgentTool, EditFileTool, ToolCallEventStream, ToolInput, ToolPermissionDecision,
+    decide_permission_for_paths,
+};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use agent_settings::AgentSettings;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use language::Buffer;
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// At most this many renamed locations are listed.
+const MAX_LOCATIONS: usize = 200;
+
+/// Renames a symbol and all of its references across the project, using the project's language servers.
+///
+/// Prefer this over editing each occurrence: it only renames this particular symbol, including in files you haven't read, and leaves other symbols with the same name alone.
+///
+/// The renamed files aren't saved, so that the user can review the changes.
+///
+/// <example>
+/// To rename the `load` method defined on line 17 of `app/src/config.rs` to `read_from_disk`:
+/// {
+///     "path": "app/src/config.rs",
+///     "line": 17,
+///     "symbol": "load",
+///     "new_name": "read_from_disk"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    #[serde(flatten)]
+    pub position: SymbolPosition,
+    /// The symbol's new name.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "rename_symbol";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!(
+                "Rename {} to {}",
+                input.position.title(),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into(),
+            Err(_) => "Rename symbol".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let new_name = input.new_name.trim().to_string();
+            if new_name.is_empty() {
+                return Err("The new name must not be empty.".to_string());
+            }
+            let (buffer, position) = input
+                .position
+                .resolve(&project, &event_stream, cx)
+                .await?;
+
+            // The rename's edits are applied as soon as the language server
+            // returns them, so the references are used to find the files it
+            // will change, both to check the permissions for them and to
+            // track their contents before the rename in the action log.
+            let references =
+                project.update(cx, |project, cx| project.references(&buffer, position, cx));
+            let references = futures::select! {
+                result = references.fuse() => result.ok().flatten().unwrap_or_default(),
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Rename cancelled by user".to_string());
+                }
+            };
+            let mut buffers = vec![buffer.clone()];
+            for reference in references {
+                if !buffers.contains(&reference.buffer) {
+                    buffers.push(reference.buffer);
+                }
+            }
+            let paths = cx.update(|cx| {
+                buffers
+                    .iter()
+                    .map(|buffer| buffer_path(buffer.read(cx), cx).to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+            });
+
+            let decision = cx.update(|cx| {
+                decide_permission_for_paths(
+                    EditFileTool::NAME,
+                    &paths,
+                    AgentSettings::get_global(cx),
+                )
+            });
+            match decision {
+                ToolPermissionDecision::Allow => {}
+                ToolPermissionDecision::Deny(reason) => return Err(reason),
+                ToolPermissionDecision::Confirm => {
+                    let authorize = cx.update(|cx| {
+                        let title = match paths.len() {
+                            1 => format!(
+                                "Rename {} to {}",
+                                MarkdownInlineCode(&input.position.symbol),
+                                MarkdownInlineCode(&new_name)
+                            ),
+                            count => format!(
+                                "Rename {} to {} in {count} files",
+                                MarkdownInlineCode(&input.position.symbol),
+                                MarkdownInlineCode(&new_name)
+                            ),
+                        };
+                        let context =
+                            crate::ToolPermissionContext::new(EditFileTool::NAME, paths.clone());
+                        event_stream.authorize(title, context, cx)
+                    });
+                    authorize.await.map_err(|e| e.to_string())?;
+                }
+            }
+
+            action_log.update(cx, |log, cx| {
+                for buffer in &buffers {
+                    log.buffer_read(buffer.clone(), cx);
+                }
+            });
+
+            let rename = project.update(cx, |project, cx| {
+                project.perform_rename(buffer, position, new_name.clone(), cx)
+            });
+            let transaction = futures::select! {
+                result = rename.fuse() => result.map_err(|e| format!("Rename failed: {e}"))?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Rename cancelled by user".to_string());
+                }
+            };
+            if transaction.0.is_empty() {
+                return Err(format!(
+                    "The language server didn't rename `{}`. The symbol may not be renameable, or the file's language may not have a language server.",
+                    input.position.symbol
+                ));
+            }
+
+            // Only the files found from the references were approved, so the
+            // whole rename is undone if the language server changed others.
+            let unapproved_paths = cx.update(|cx| {
+                transaction
+                    .0
+                    .keys()
+                    .filter(|buffer| !buffers.contains(buffer))
+                    .map(|buffer| {
+                        buffer_path(buffer.read(cx), cx)
+                            .to_string_lossy()
+                            .into_owned()
+                    })
+                    .collect::<Vec<_>>()
+            });
+            if !unapproved_paths.is_empty() {
+                cx.update(|cx| {
+                    for (buffer, transaction) in &transaction.0 {
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.undo_transaction(transaction.id, cx);
+                        });
+                    }
+                });
+                return Err(format!(
+                    "The rename was undone, because the language server also changed files that weren't approved for editing: {}",
+                    unapproved_paths.join(", ")
+                ));
+            }
+
+            Ok(cx.update(|cx| {
+                let mut renamed = Vec::new();
+                for (buffer, transaction) in &transaction.0 {
+                    action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                    renamed.extend(renamed_ranges(buffer, transaction, cx));
+                }
+                let file_count = transaction.0.len();
+                format!(
+                    "Renamed `{}` to `{new_name}` in {file_count} {}:\n{}",
+                    input.position.symbol,
+                    if file_count == 1 { "file" } else { "files" },
+                    format_locations(renamed, MAX_LOCATIONS, cx)
+                )
+            }))
+        })
+    }
+}
+
+fn renamed_ranges(
+    buffer: &Entity<Buffer>,
+    transaction: &language::Transaction,
+    cx: &App,
+) -> Vec<(Entity<Buffer>, std::ops::Range<language::Anchor>)> {
+    let snapshot = buffer.read(cx).snapshot();
+    buffer
+        .read(cx)
+        .edited_ranges_for_transaction::<usize>(transaction)
+        .map(|range| {
+            let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            (buffer.clone(), range)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.tool_permissions.default = settings::ToolPermissionMode::Allow;
+            AgentSettings::override_global(settings, cx);
+        });
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> lsp::Range {
+        lsp::Range::new(
+            lsp::Position::new(line, start),
+            lsp::Position::new(line, end),
+        )
+    }
+
+    /// Renames `helper` in `lib.rs` with a language server that finds
+    /// `referenced_files` and renames in `renamed_files`, returning the tool's
+    /// output and the contents of both files afterwards.
+    async fn rename(
+        referenced_files: &'static [&'static str],
+        renamed_files: &'static [&'static str],
+        cx: &mut TestAppContext,
+    ) -> (Result<String, String>, String, String) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "lib.rs": "pub fn helper() {}\n",
+                    "main.rs": "fn main() {\n    lib::helper();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let _handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        let location = |file: &str| match file {
+            "lib.rs" => (
+                lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap(),
+                range(0, 7, 13),
+            ),
+            _ => (
+                lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap(),
+                range(1, 9, 15),
+            ),
+        };
+        fake_language_server.set_request_handler::<lsp::request::References, _, _>(
+            move |_, _| async move {
+                Ok(Some(
+                    referenced_files
+                        .iter()
+                        .map(|file| {
+                            let (uri, range) = location(file);
+                            lsp::Location::new(uri, range)
+                        })
+                        .collect(),
+                ))
+            },
+        );
+        fake_language_server.set_request_handler::<lsp::request::Rename, _, _>(
+            move |params, _| async move {
+                assert_eq!(params.new_name, "assist");
+                Ok(Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        renamed_files
+                            .iter()
+                            .map(|file| {
+                                let (uri, range) = location(file);
+                                (uri, vec![lsp::TextEdit::new(range, "assist".to_string())])
+                            })
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }))
+            },
+        );
+
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let tool = Arc::new(RenameSymbolTool::new(project.clone(), action_log));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(RenameSymbolToolInput {
+                        position: SymbolPosition {
+                            path: "root/src/lib.rs".into(),
+                            line: 1,
+                            symbol: "helper".into(),
+                        },
+                        new_name: "assist".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await;
+
+        let mut texts = Vec::new();
+        for path in [path!("/root/src/lib.rs"), path!("/root/src/main.rs")] {
+            let buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(path, cx))
+                .await
+                .unwrap();
+            texts.push(buffer.read_with(cx, |buffer, _| buffer.text()));
+        }
+        let main_text = texts.pop().unwrap();
+        let lib_text = texts.pop().unwrap();
+        (output, lib_text, main_text)
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol(cx: &mut TestAppContext) {
+        let (output, lib_text, main_text) =
+            rename(&["main.rs", "lib.rs"], &["lib.rs", "main.rs"], cx).await;
+        let output = output.unwrap();
+        assert!(
+            output.starts_with("Renamed `helper` to `assist` in 2 files:\n"),
+            "unexpected output: {output}"
+        );
+        assert!(output.contains("root/src/lib.rs\n  1: pub fn assist() {}\n"));
+        assert!(output.contains("root/src/main.rs\n  2: lib::assist();\n"));
+        assert_eq!(lib_text, "pub fn assist() {}\n");
+        assert_eq!(main_text, "fn main() {\n    lib::assist();\n}\n");
+    }
+
+    #[gpui::test]
+    async fn test_rename_symbol_in_unapproved_file(cx: &mut TestAppContext) {
+        // The references don't include `main.rs`, so its edit wasn't approved
+        // and the whole rename is undone.
+        let (output, lib_text, main_text) = rename(&["lib.rs"], &["lib.rs", "main.rs"], cx).await;
+        assert_eq!(
+            output.unwrap_err(),
+            "The rename was undone, because the language server also changed files that weren't approved for editing: root/src/main.rs"
+        );
+        assert_eq!(lib_text, "pub fn helper() {}\n");
+        assert_eq!(main_text, "fn main() {\n    lib::helper();\n}\n");
+    }
+}
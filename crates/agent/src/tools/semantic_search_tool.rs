@@ -0,0 +1,169 @@
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use language::{Bias, Point, ToPoint as _};
+use project::{Project, ProjectPath};
+use schemars::JsonSchema;
+use semantic_index::{SemanticDb, Status};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Searches the project's code by meaning, using a local semantic index.
+///
+/// - Use this tool to find code related to a concept when you don't know what it's called, e.g. "where are failed requests retried?"
+/// - Prefer `grep` when you know the exact text or symbol name to look for.
+/// - Results are the chunks of code most related to the query, from most to least related, with their path and lines.
+/// - Results can be loosely related to the query. Read the code before relying on it.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SemanticSearchToolInput {
+    /// A description of the code to find, in natural language.
+    pub query: String,
+    /// The maximum number of results to return. Defaults to 10.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_RESULTS: usize = 10;
+const MAX_RESULTS: usize = 50;
+
+pub struct SemanticSearchTool {
+    project: Entity<Project>,
+}
+
+impl SemanticSearchTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SemanticSearchTool {
+    type Input = SemanticSearchToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "semantic_search";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search code for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search code by meaning".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            let limit = input.limit.unwrap_or(DEFAULT_RESULTS).clamp(1, MAX_RESULTS);
+
+            let (search, status) = cx.update(|cx| {
+                let project_index = SemanticDb::project_index(&project, cx).ok_or_else(|| {
+                    "The semantic index isn't available for this project. Use grep instead."
+                        .to_string()
+                })?;
+                let project_index = project_index.read(cx);
+                Ok::<_, String>((
+                    project_index.search(input.query.clone(), limit, cx),
+                    project_index.status(),
+                ))
+            })?;
+            if status == Status::Disabled {
+                return Err(
+                    "The semantic index is disabled in the user's settings. Use grep instead."
+                        .to_string(),
+                );
+            }
+
+            let results = futures::select! {
+                results = search.fuse() => results.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Search cancelled by user".to_string());
+                }
+            };
+
+            let note = match status {
+                Status::Indexing { remaining_files } => format!(
+                    "Note: {remaining_files} files are still being indexed, so these results may be incomplete.\n"
+                ),
+                Status::Failed => "Note: some files couldn't be indexed, so these results may be incomplete or outdated.\n".to_string(),
+                Status::Idle | Status::Disabled => String::new(),
+            };
+
+            let mut output = String::new();
+            let mut results_found = 0;
+            for result in results {
+                let open_buffer = project.update(cx, |project, cx| {
+                    project.open_buffer(
+                        ProjectPath {
+                            worktree_id: result.worktree_id,
+                            path: result.path.clone(),
+                        },
+                        cx,
+                    )
+                });
+                let Ok(buffer) = open_buffer.await else {
+                    continue;
+                };
+                let (full_path, snapshot) = buffer.read_with(cx, |buffer, cx| {
+                    (
+                        buffer.file().map(|file| file.full_path(cx)),
+                        buffer.snapshot(),
+                    )
+                });
+                let Some(full_path) = full_path else {
+                    continue;
+                };
+
+                // The file may have changed since it was indexed, so the
+                // chunk's range is clipped and expanded to whole lines.
+                let start = snapshot
+                    .clip_offset(result.range.start.min(snapshot.len()), Bias::Left)
+                    .to_point(&snapshot);
+                let mut end = snapshot
+                    .clip_offset(result.range.end.min(snapshot.len()), Bias::Left)
+                    .to_point(&snapshot);
+                if end.column == 0 && end.row > start.row {
+                    end.row -= 1;
+                }
+                let range = Point::new(start.row, 0)..Point::new(end.row, snapshot.line_len(end.row));
+
+                writeln!(
+                    output,
+                    "\n## {} L{}-{}",
+                    full_path.display(),
+                    range.start.row + 1,
+                    range.end.row + 1
+                )
+                .ok();
+                output.push_str("```\n");
+                output.extend(snapshot.text_for_range(range));
+                output.push_str("\n```\n");
+                results_found += 1;
+            }
+
+            if results_found == 0 {
+                Ok(format!("{note}No matches found"))
+            } else {
+                Ok(format!("{note}Found {results_found} results:\n{output}"))
+            }
+        })
+    }
+}
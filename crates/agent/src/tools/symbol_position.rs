@@ -0,0 +1,211 @@
+//! Helpers for the tools that ask language servers about a symbol.
+//!
+//! Models are bad at counting columns, so symbols are identified by the line
+//! they're on and their name instead. Locations in the results are printed as
+//! `path:line` followed by the text of the line, so that the model can tell
+//! what's there without reading the file.
+
+use crate::ToolCallEventStream;
+use futures::FutureExt as _;
+use gpui::{App, AsyncApp, Entity};
+use language::{Anchor, Buffer, BufferSnapshot, Point, PointUtf16, ToPoint as _};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, ops::Range, path::PathBuf};
+use util::markdown::MarkdownInlineCode;
+
+/// Lines in the results are truncated to this many characters.
+const MAX_LINE_CHARS: usize = 160;
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolPosition {
+    /// The path of the file containing the symbol.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based number of the line the symbol is on.
+    pub line: u32,
+    /// The symbol's name, exactly as it's written on that line. When it's
+    /// written more than once on the line, the first occurrence is used.
+    pub symbol: String,
+}
+
+impl SymbolPosition {
+    pub(crate) fn title(&self) -> String {
+        format!(
+            "{} in {}",
+            MarkdownInlineCode(&self.symbol),
+            MarkdownInlineCode(&format!("{}:{}", self.path, self.line))
+        )
+    }
+
+    /// Opens the buffer containing the symbol, and returns the position of
+    /// the symbol's start in it.
+    pub(crate) async fn resolve(
+        &self,
+        project: &Entity<Project>,
+        event_stream: &ToolCallEventStream,
+        cx: &mut AsyncApp,
+    ) -> Result<(Entity<Buffer>, PointUtf16), String> {
+        let open_buffer = project.update(cx, |project, cx| {
+            let Some(project_path) = project.find_project_path(&self.path, cx) else {
+                return Err(format!("Could not find path {} in project", self.path));
+            };
+            Ok(project.open_buffer(project_path, cx))
+        })?;
+        let buffer = futures::select! {
+            result = open_buffer.fuse() => result.map_err(|e| e.to_string())?,
+            _ = event_stream.cancelled_by_user().fuse() => {
+                return Err("Cancelled by user".to_string());
+            }
+        };
+        let position = buffer.read_with(cx, |buffer, _| self.position_in(&buffer.snapshot()))?;
+        Ok((buffer, position))
+    }
+
+    fn position_in(&self, snapshot: &BufferSnapshot) -> Result<PointUtf16, String> {
+        let row = self.line.saturating_sub(1);
+        if self.line == 0 || row > snapshot.max_point().row {
+            return Err(format!(
+                "{} only has {} lines.",
+                self.path,
+                snapshot.max_point().row + 1
+            ));
+        }
+        let line = line_text(snapshot, row);
+        let column = find_symbol(&line, &self.symbol).ok_or_else(|| {
+            format!(
+                "{} isn't on line {} of {}, which is: {}",
+                MarkdownInlineCode(&self.symbol),
+                self.line,
+                self.path,
+                MarkdownInlineCode(line.trim()),
+            )
+        })?;
+        Ok(snapshot.point_to_point_utf16(Point::new(row, column as u32)))
+    }
+}
+
+/// Returns the byte offset of the first occurrence of `symbol` in `line`,
+/// preferring occurrences that aren't part of a longer identifier.
+fn find_symbol(line: &str, symbol: &str) -> Option<usize> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    line.match_indices(symbol)
+        .map(|(ix, _)| ix)
+        .find(|&ix| {
+            let before = line[..ix].chars().next_back();
+            let after = line[ix + symbol.len()..].chars().next();
+            !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+        })
+        .or_else(|| line.find(symbol))
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> String {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect()
+}
+
+/// The path of a buffer's file as the model refers to it.
+pub(crate) fn buffer_path(buffer: &Buffer, cx: &App) -> PathBuf {
+    buffer
+        .file()
+        .map(|file| file.full_path(cx))
+        .unwrap_or_else(|| PathBuf::from("untitled"))
+}
+
+/// Formats a location as its line number and the trimmed text of the line.
+pub(crate) fn format_line(snapshot: &BufferSnapshot, position: Anchor) -> String {
+    let row = position.to_point(snapshot).row;
+    let text = line_text(snapshot, row);
+    let text = text.trim();
+    match text.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}: {}…", row + 1, &text[..end]),
+        None => format!("{}: {text}", row + 1),
+    }
+}
+
+/// Formats locations grouped by file, with at most `limit` locations.
+pub(crate) fn format_locations(
+    locations: impl IntoIterator<Item = (Entity<Buffer>, Range<Anchor>)>,
+    limit: usize,
+    cx: &App,
+) -> String {
+    let mut files = Vec::<(PathBuf, BufferSnapshot, Vec<Anchor>)>::new();
+    let mut count = 0;
+    for (buffer, range) in locations {
+        count += 1;
+        if count > limit {
+            continue;
+        }
+        let buffer = buffer.read(cx);
+        let path = buffer_path(buffer, cx);
+        match files
+            .iter_mut()
+            .find(|(file_path, _, _)| *file_path == path)
+        {
+            Some((_, _, positions)) => positions.push(range.start),
+            None => files.push((path, buffer.snapshot(), vec![range.start])),
+        }
+    }
+
+    let mut output = String::new();
+    for (path, snapshot, mut positions) in files {
+        positions.sort_by(|a, b| a.cmp(b, &snapshot));
+        positions.dedup_by_key(|position| position.to_point(&snapshot).row);
+        writeln!(output, "{}", path.display()).ok();
+        for position in positions {
+            writeln!(output, "  {}", format_line(&snapshot, position)).ok();
+        }
+    }
+    if count > limit {
+        writeln!(output, "…and {} more", count - limit).ok();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[test]
+    fn test_find_symbol() {
+        assert_eq!(find_symbol("let item = items[0];", "item"), Some(4));
+        assert_eq!(find_symbol("let items = item;", "item"), Some(12));
+        assert_eq!(find_symbol("fn foo_bar() {}", "foo"), Some(3));
+        assert_eq!(find_symbol("self.état = état;", "état"), Some(5));
+        assert_eq!(find_symbol("let x = 1;", "y"), None);
+        assert_eq!(find_symbol("let x = 1;", ""), None);
+    }
+
+    #[gpui::test]
+    fn test_position_in(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    let 🙂 = run(🙂);\n}\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let position = |line, symbol: &str| {
+            SymbolPosition {
+                path: "root/main.rs".into(),
+                line,
+                symbol: symbol.into(),
+            }
+            .position_in(&snapshot)
+        };
+
+        assert_eq!(position(1, "main"), Ok(PointUtf16::new(0, 3)));
+        assert_eq!(position(2, "run"), Ok(PointUtf16::new(1, 13)));
+        assert_eq!(
+            position(2, "walk"),
+            Err("`walk` isn't on line 2 of root/main.rs, which is: `let 🙂 = run(🙂);`".into())
+        );
+        assert_eq!(
+            position(9, "main"),
+            Err("root/main.rs only has 4 lines.".into())
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{AgentTool, ToolCallEventStream, ToolInput};
+use agent_client_protocol as acp;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::{Project, Symbol, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write as _, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// At most this many symbols are listed.
+const MAX_SYMBOLS: usize = 100;
+
+/// Searches the project's symbols by name, using the project's language servers.
+///
+/// Use this to find where a type, function or constant is defined when you know (part of) its name but not its file. Language servers match the query fuzzily, so the best matches come first.
+///
+/// Each result has the symbol's kind, name and container, and the path and line of its definition.
+///
+/// <example>
+/// To find the struct that holds the project's settings:
+/// {
+///     "query": "Settings"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SymbolSearchToolInput {
+    /// The name, or part of the name, of the symbols to find.
+    pub query: String,
+}
+
+pub struct SymbolSearchTool {
+    project: Entity<Project>,
+}
+
+impl SymbolSearchTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for SymbolSearchTool {
+    type Input = SymbolSearchToolInput;
+    type Output = String;
+
+    const NAME: &'static str = "symbol_search";
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search symbols for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search symbols".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: ToolInput<Self::Input>,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output, Self::Output>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let input = input
+                .recv()
+                .await
+                .map_err(|e| format!("Failed to receive tool input: {e}"))?;
+            if input.query.trim().is_empty() {
+                return Err("The query must not be empty.".to_string());
+            }
+
+            let symbols = project.update(cx, |project, cx| project.symbols(&input.query, cx));
+            let symbols = futures::select! {
+                result = symbols.fuse() => result.map_err(|e| e.to_string())?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    return Err("Symbol search cancelled by user".to_string());
+                }
+            };
+            if symbols.is_empty() {
+                return Ok(format!(
+                    "No symbols found for `{}`. The project's languages may not have language servers, or they may still be starting.",
+                    input.query
+                ));
+            }
+
+            Ok(project.read_with(cx, |project, cx| {
+                let mut output = String::new();
+                for symbol in symbols.iter().take(MAX_SYMBOLS) {
+                    writeln!(output, "{}", format_symbol(symbol, project, cx)).ok();
+                }
+                if symbols.len() > MAX_SYMBOLS {
+                    writeln!(output, "…and {} more", symbols.len() - MAX_SYMBOLS).ok();
+                }
+                output
+            }))
+        })
+    }
+}
+
+fn format_symbol(symbol: &Symbol, project: &Project, cx: &App) -> String {
+    let path = match &symbol.path {
+        SymbolLocation::InProject(project_path) => project
+            .worktree_for_id(project_path.worktree_id, cx)
+            .map(|worktree| worktree.read(cx).full_path(&project_path.path))
+            .unwrap_or_else(|| project_path.path.as_std_path().to_path_buf()),
+        SymbolLocation::OutsideProject { abs_path, .. } => abs_path.to_path_buf(),
+    };
+    let mut text = format!(
+        "{}:{} {} `{}`",
+        path.display(),
+        symbol.range.start.0.row + 1,
+        symbol_kind_name(symbol.kind),
+        symbol.name
+    );
+    if let Some(container_name) = &symbol.container_name {
+        write!(text, " in `{container_name}`").ok();
+    }
+    text
+}
+
+fn symbol_kind_name(kind: lsp::SymbolKind) -> &'static str {
+    match kind {
+        lsp::SymbolKind::FILE => "file",
+        lsp::SymbolKind::MODULE => "module",
+        lsp::SymbolKind::NAMESPACE => "namespace",
+        lsp::SymbolKind::PACKAGE => "package",
+        lsp::SymbolKind::CLASS => "class",
+        lsp::SymbolKind::METHOD => "method",
+        lsp::SymbolKind::PROPERTY => "property",
+        lsp::SymbolKind::FIELD => "field",
+        lsp::SymbolKind::CONSTRUCTOR => "constructor",
+        lsp::SymbolKind::ENUM => "enum",
+        lsp::SymbolKind::INTERFACE => "interface",
+        lsp::SymbolKind::FUNCTION => "function",
+        lsp::SymbolKind::VARIABLE => "variable",
+        lsp::SymbolKind::CONSTANT => "constant",
+        lsp::SymbolKind::ENUM_MEMBER => "enum member",
+        lsp::SymbolKind::STRUCT => "struct",
+        lsp::SymbolKind::EVENT => "event",
+        lsp::SymbolKind::OPERATOR => "operator",
+        lsp::SymbolKind::TYPE_PARAMETER => "type parameter",
+        _ => "symbol",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_symbol_search(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "lib.rs": "pub fn helper() {}\n",
+                    "main.rs": "fn main() {\n    lib::helper();\n}\n",
+                }
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+        let mut fake_language_servers = language_registry.register_fake_lsp(
+            "Rust",
+            language::FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let _handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        fake_language_server.set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+            |params, _| async move {
+                assert_eq!(params.query, "help");
+                #[allow(deprecated)]
+                let symbol = lsp::SymbolInformation {
+                    name: "helper".to_string(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    deprecated: None,
+                    container_name: Some("lib".to_string()),
+                    location: lsp::Location::new(
+                        lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap(),
+                        lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 13)),
+                    ),
+                };
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![symbol])))
+            },
+        );
+
+        let tool = Arc::new(SymbolSearchTool::new(project));
+        let output = cx
+            .update(|cx| {
+                tool.run(
+                    ToolInput::resolved(SymbolSearchToolInput {
+                        query: "help".into(),
+                    }),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, "root/src/lib.rs:1 function `helper` in `lib`\n");
+    }
+}
@@ -1,3 +1,5 @@
+mod sandbox;
+
 use agent_client_protocol as acp;
 use agent_settings::AgentSettings;
 use anyhow::Result;
@@ -18,6 +20,7 @@ use crate::{
     AgentTool, ThreadEnvironment, ToolCallEventStream, ToolInput, ToolPermissionDecision,
     decide_permission_from_settings,
 };
+use sandbox::Sandbox;
 
 const COMMAND_OUTPUT_LIMIT: u64 = 16 * 1024;
 
@@ -95,14 +98,17 @@ impl AgentTool for TerminalTool {
                 .await
                 .map_err(|e| format!("Failed to receive tool input: {e}"))?;
 
-            let (working_dir, authorize) = cx.update(|cx| {
+            let (working_dir, sandbox, authorize) = cx.update(|cx| {
                 let working_dir =
                     working_dir(&input, &self.project, cx).map_err(|err| err.to_string())?;
+                let settings = AgentSettings::get_global(cx);
+                let sandbox = Sandbox::for_project(&settings.terminal_sandbox, &self.project, cx)
+                    .map_err(|err| err.to_string())?;
 
                 let decision = decide_permission_from_settings(
                     Self::NAME,
                     std::slice::from_ref(&input.command),
-                    AgentSettings::get_global(cx),
+                    settings,
                 );
 
                 let authorize = match decision {
@@ -122,20 +128,21 @@ impl AgentTool for TerminalTool {
                         ))
                     }
                 };
-                Ok((working_dir, authorize))
+                Ok((working_dir, sandbox, authorize))
             })?;
             if let Some(authorize) = authorize {
                 authorize.await.map_err(|e| e.to_string())?;
             }
 
+            let command = match &sandbox {
+                Some(sandbox) => sandbox
+                    .wrap_command(&input.command)
+                    .map_err(|e| e.to_string())?,
+                None => input.command.clone(),
+            };
             let terminal = self
                 .environment
-                .create_terminal(
-                    input.command.clone(),
-                    working_dir,
-                    Some(COMMAND_OUTPUT_LIMIT),
-                    cx,
-                )
+                .create_terminal(command, working_dir, Some(COMMAND_OUTPUT_LIMIT), cx)
                 .await
                 .map_err(|e| e.to_string())?;
 
@@ -192,12 +199,14 @@ impl AgentTool for TerminalTool {
 
             let output = terminal.current_output(cx).map_err(|e| e.to_string())?;
 
-            Ok(process_content(
-                output,
-                &input.command,
-                timed_out,
-                user_stopped,
-            ))
+            let denials = sandbox
+                .as_ref()
+                .and_then(|sandbox| sandbox.explain_denials(&output.output));
+            let content = process_content(output, &input.command, timed_out, user_stopped);
+            match denials {
+                Some(denials) => Ok(format!("{content}\n\n{denials}")),
+                None => Ok(content),
+            }
         })
     }
 }
@@ -0,0 +1,300 @@
+//! Confines the terminal tool's commands with bubblewrap, so that they can
+//! only write to the project's worktrees, the temporary directory and the
+//! paths allowed in the settings, and can only access the network when it's
+//! allowed. The worktrees' `.git` directories stay read-only, so that
+//! commands can't rewrite the repository's hooks or configuration.
+
+use agent_settings::TerminalSandboxSettings;
+use anyhow::{Context as _, Result};
+use gpui::{App, Entity};
+use project::Project;
+use std::path::{Path, PathBuf};
+use util::{
+    get_default_system_shell_preferring_bash,
+    shell::{Shell, ShellKind},
+    shell_builder::ShellBuilder,
+};
+
+/// Errors printed by commands that were denied access by the sandbox.
+const WRITE_DENIED_ERRORS: &[&str] = &["Read-only file system"];
+const NETWORK_DENIED_ERRORS: &[&str] = &[
+    "Network is unreachable",
+    "Temporary failure in name resolution",
+    "Could not resolve host",
+    "Name or service not known",
+];
+
+#[derive(Debug)]
+pub struct Sandbox {
+    shell: String,
+    writable_paths: Vec<PathBuf>,
+    read_only_paths: Vec<PathBuf>,
+    allow_network: bool,
+    hidden_paths: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// Returns the sandbox to run a command of the project in, or `None` if
+    /// the sandbox is disabled.
+    ///
+    /// Fails when the sandbox is enabled but isn't supported, so that
+    /// commands are never run outside of it by accident.
+    pub fn for_project(
+        settings: &TerminalSandboxSettings,
+        project: &Entity<Project>,
+        cx: &App,
+    ) -> Result<Option<Self>> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        anyhow::ensure!(
+            cfg!(target_os = "linux"),
+            "The terminal sandbox is enabled in the user's settings, but it's only supported on Linux, so the command wasn't run."
+        );
+        let project = project.read(cx);
+        anyhow::ensure!(
+            project.is_local(),
+            "The terminal sandbox is enabled in the user's settings, but it isn't supported for remote projects, so the command wasn't run."
+        );
+
+        let mut writable_paths = project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .collect::<Vec<_>>();
+        let read_only_paths = writable_paths
+            .iter()
+            .map(|path| path.join(".git"))
+            .collect();
+        let temp_dir = std::env::temp_dir();
+        writable_paths.push(temp_dir.clone());
+        writable_paths.extend(settings.writable_paths.iter().cloned());
+
+        // Sockets in the runtime directory, such as the session bus, and in
+        // the temporary directory, such as the X server's, would let commands
+        // ask other processes to act outside of the sandbox.
+        let mut hidden_paths = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .into_iter()
+            .collect::<Vec<_>>();
+        if let Ok(entries) = std::fs::read_dir(&temp_dir) {
+            hidden_paths.extend(
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+                    .filter(|entry| is_socket_directory(&entry.file_name().to_string_lossy()))
+                    .map(|entry| entry.path()),
+            );
+        }
+
+        Ok(Some(Self {
+            shell: get_default_system_shell_preferring_bash(),
+            writable_paths,
+            read_only_paths,
+            allow_network: settings.allow_network,
+            hidden_paths,
+        }))
+    }
+
+    /// Returns a shell command that runs the given one in the sandbox.
+    pub fn wrap_command(&self, command: &str) -> Result<String> {
+        let bwrap = which::which("bwrap").context(
+            "The terminal sandbox is enabled in the user's settings, but bubblewrap (`bwrap`) isn't installed, so the command wasn't run.",
+        )?;
+        let args = self.bwrap_args(&bwrap, command);
+        args.iter()
+            .map(|arg| {
+                ShellKind::Posix
+                    .try_quote(arg)
+                    .map(|arg| arg.into_owned())
+                    .with_context(|| format!("failed to quote sandbox argument {arg:?}"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|args| args.join(" "))
+    }
+
+    fn bwrap_args(&self, bwrap: &Path, command: &str) -> Vec<String> {
+        let mut args = vec![bwrap.to_string_lossy().into_owned()];
+        let mut push = |values: &[&str]| args.extend(values.iter().map(|value| value.to_string()));
+
+        push(&["--ro-bind", "/", "/"]);
+        push(&["--dev", "/dev"]);
+        push(&["--proc", "/proc"]);
+        for path in &self.writable_paths {
+            let path = path.to_string_lossy();
+            push(&["--bind-try", &path, &path]);
+        }
+        for path in &self.read_only_paths {
+            let path = path.to_string_lossy();
+            push(&["--ro-bind-try", &path, &path]);
+        }
+        // Hidden paths are mounted last, so that binding a parent directory
+        // doesn't reveal them again.
+        for path in &self.hidden_paths {
+            let path = path.to_string_lossy();
+            push(&["--tmpfs", &path]);
+        }
+        push(&["--unshare-pid", "--die-with-parent", "--new-session"]);
+        if !self.allow_network {
+            push(&["--unshare-net"]);
+        }
+        push(&["--"]);
+
+        // Commands are run with the same shell as outside of the sandbox.
+        let (shell, shell_args) = ShellBuilder::new(&Shell::Program(self.shell.clone()), false)
+            .non_interactive()
+            .build(Some(command.to_string()), &[]);
+        args.push(shell);
+        args.extend(shell_args);
+        args
+    }
+
+    /// Explains which of the command's failures were caused by the sandbox,
+    /// so that the model doesn't try to work around them.
+    pub fn explain_denials(&self, output: &str) -> Option<String> {
+        let mut explanation = String::new();
+        if let Some(line) = output.lines().find(|line| line.starts_with("bwrap: ")) {
+            explanation.push_str(&format!(
+                "The sandbox couldn't be set up ({line}), so the command may not have run at all. \
+                Tell the user, since this usually means that unprivileged user namespaces are disabled on their system.\n"
+            ));
+        }
+        if WRITE_DENIED_ERRORS
+            .iter()
+            .any(|error| output.contains(error))
+        {
+            let paths = self
+                .writable_paths
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            explanation.push_str(&format!(
+                "The command was denied writing to a path by the sandbox. It can only write to {paths}.\n"
+            ));
+        }
+        if !self.allow_network
+            && NETWORK_DENIED_ERRORS
+                .iter()
+                .any(|error| output.contains(error))
+        {
+            explanation.push_str("The command was denied network access by the sandbox.\n");
+        }
+
+        if explanation.is_empty() {
+            return None;
+        }
+        explanation.push_str(
+            "Don't try to work around the sandbox. If the command needs this access, ask the user to allow it in the `agent.terminal_sandbox` settings.",
+        );
+        Some(format!("Sandbox denials:\n{explanation}"))
+    }
+}
+
+/// Whether a directory in the temporary directory holds the sockets of the X
+/// server, tmux or ssh-agent.
+fn is_socket_directory(name: &str) -> bool {
+    matches!(name, ".X11-unix" | ".ICE-unix" | ".XIM-unix" | ".font-unix")
+        || name.starts_with("tmux-")
+        || name.starts_with("ssh-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox(allow_network: bool) -> Sandbox {
+        Sandbox {
+            shell: "/bin/bash".to_string(),
+            writable_paths: vec![PathBuf::from("/home/user/project"), PathBuf::from("/tmp")],
+            read_only_paths: vec![PathBuf::from("/home/user/project/.git")],
+            allow_network,
+            hidden_paths: vec![
+                PathBuf::from("/run/user/1000"),
+                PathBuf::from("/tmp/.X11-unix"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bwrap_args() {
+        let args = sandbox(false).bwrap_args(Path::new("/usr/bin/bwrap"), "cargo test");
+        assert_eq!(
+            args,
+            [
+                "/usr/bin/bwrap",
+                "--ro-bind",
+                "/",
+                "/",
+                "--dev",
+                "/dev",
+                "--proc",
+                "/proc",
+                "--bind-try",
+                "/home/user/project",
+                "/home/user/project",
+                "--bind-try",
+                "/tmp",
+                "/tmp",
+                "--ro-bind-try",
+                "/home/user/project/.git",
+                "/home/user/project/.git",
+                "--tmpfs",
+                "/run/user/1000",
+                "--tmpfs",
+                "/tmp/.X11-unix",
+                "--unshare-pid",
+                "--die-with-parent",
+                "--new-session",
+                "--unshare-net",
+                "--",
+                "/bin/bash",
+                "-c",
+                "cargo test",
+            ]
+        );
+
+        let args = sandbox(true).bwrap_args(Path::new("/usr/bin/bwrap"), "cargo test");
+        assert!(!args.iter().any(|arg| arg == "--unshare-net"));
+    }
+
+    #[test]
+    fn test_is_socket_directory() {
+        assert!(is_socket_directory(".X11-unix"));
+        assert!(is_socket_directory(".ICE-unix"));
+        assert!(is_socket_directory("tmux-1000"));
+        assert!(is_socket_directory("ssh-XXXXabcd"));
+        assert!(!is_socket_directory("cargo-install1234"));
+        assert!(!is_socket_directory(".tmp1234"));
+    }
+
+    #[test]
+    fn test_explain_denials() {
+        let sandbox = sandbox(false);
+        assert_eq!(sandbox.explain_denials("test result: ok"), None);
+
+        let explanation = sandbox
+            .explain_denials("touch: cannot touch '/etc/hosts': Read-only file system")
+            .unwrap();
+        assert!(explanation.contains("denied writing"));
+        assert!(explanation.contains("`/home/user/project`, `/tmp`"));
+        assert!(!explanation.contains("network"));
+
+        let explanation = sandbox
+            .explain_denials("curl: (6) Could not resolve host: example.com")
+            .unwrap();
+        assert!(explanation.contains("denied network access"));
+
+        let explanation = sandbox
+            .explain_denials("bwrap: No permissions to create new namespace")
+            .unwrap();
+        assert!(explanation.contains("couldn't be set up"));
+    }
+
+    #[test]
+    fn test_network_errors_are_not_denials_when_allowed() {
+        assert_eq!(
+            sandbox(true).explain_denials("curl: (6) Could not resolve host: example.com"),
+            None
+        );
+    }
+}
@@ -1,24 +1,24 @@
 use std::sync::Arc;
 
 use crate::{
-    AgentTool, ToolCallEventStream, ToolInput, ToolPermissionDecision,
+    AgentTool, Thread, ToolCallEventStream, ToolInput, ToolPermissionDecision,
     decide_permission_from_settings,
 };
 use agent_client_protocol as acp;
-use agent_settings::AgentSettings;
+use agent_settings::{AgentProfileId, AgentSettings};
 use anyhow::Result;
 use cloud_llm_client::WebSearchResponse;
 use futures::FutureExt as _;
-use gpui::{App, Task};
+use gpui::{App, Task, WeakEntity};
 use language_model::{
     LanguageModelProviderId, LanguageModelToolResultContent, ZED_CLOUD_PROVIDER_ID,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, WebSearchProviderContent};
 use ui::prelude::*;
 use util::markdown::MarkdownInlineCode;
-use web_search::WebSearchRegistry;
+use web_search::{WebSearchProvider, WebSearchProviderId, WebSearchRegistry};
 
 /// Search the web for information using your query.
 /// Use this when you need real-time information, facts, or data that might not be in your training.
@@ -47,7 +47,71 @@ impl From<WebSearchToolOutput> for LanguageModelToolResultContent {
     }
 }
 
-pub struct WebSearchTool;
+pub struct WebSearchTool {
+    thread: WeakEntity<Thread>,
+}
+
+impl WebSearchTool {
+    pub fn new(thread: WeakEntity<Thread>) -> Self {
+        Self { thread }
+    }
+
+    /// Returns the provider selected in the profile, or else in the agent
+    /// settings.
+    fn selected_provider(
+        profile_id: Option<&AgentProfileId>,
+        cx: &App,
+    ) -> Option<WebSearchProviderContent> {
+        let settings = AgentSettings::get_global(cx);
+        profile_id
+            .and_then(|profile_id| settings.profiles.get(profile_id))
+            .and_then(|profile| profile.web_search_provider)
+            .or(settings.web_search_provider)
+    }
+
+    /// Returns the provider to search with in threads using the given
+    /// profile, falling back to Zed's provider when none is selected.
+    pub fn provider(
+        profile_id: Option<&AgentProfileId>,
+        cx: &App,
+    ) -> Result<Arc<dyn WebSearchProvider>, String> {
+        let selected = Self::selected_provider(profile_id, cx);
+        let Some(registry) = WebSearchRegistry::try_global(cx) else {
+            return Err("Web search is not available.".to_string());
+        };
+        let registry = registry.read(cx);
+        match selected {
+            Some(selected) => registry
+                .provider(&WebSearchProviderId(selected.id().into()))
+                .ok_or_else(|| {
+                    format!(
+                        "The `{}` web search provider isn't configured. Set it up in the `agent.web_search` settings.",
+                        selected.id()
+                    )
+                }),
+            None => registry
+                .active_provider()
+                .ok_or_else(|| "Web search is not available.".to_string()),
+        }
+    }
+
+    /// Whether web search can be offered to a model of the given provider in
+    /// threads using the given profile.
+    pub fn is_available(
+        profile_id: &AgentProfileId,
+        model_provider: &LanguageModelProviderId,
+        cx: &App,
+    ) -> bool {
+        match Self::provider(Some(profile_id), cx) {
+            Ok(provider) => {
+                !provider.requires_zed_models() || model_provider == &ZED_CLOUD_PROVIDER_ID
+            }
+            // When the selected provider isn't set up, the tool is still
+            // offered so that its error tells the user how to fix it.
+            Err(_) => Self::selected_provider(Some(profile_id), cx).is_some(),
+        }
+    }
+}
 
 impl AgentTool for WebSearchTool {
     type Input = WebSearchToolInput;
@@ -67,18 +131,17 @@ impl AgentTool for WebSearchTool {
         "Searching the Web".into()
     }
 
-    /// We currently only support Zed Cloud as a provider.
-    fn supports_provider(provider: &LanguageModelProviderId) -> bool {
-        provider == &ZED_CLOUD_PROVIDER_ID
-    }
-
     fn run(
         self: Arc<Self>,
         input: ToolInput<Self::Input>,
         event_stream: ToolCallEventStream,
         cx: &mut App,
     ) -> Task<Result<Self::Output, Self::Output>> {
+        let thread = self.thread.clone();
         cx.spawn(async move |cx| {
+            let profile_id = thread
+                .read_with(cx, |thread, _| thread.profile().clone())
+                .ok();
             let input = input
                 .recv()
                 .await
@@ -109,11 +172,8 @@ impl AgentTool for WebSearchTool {
                     }
                 };
 
-                let Some(provider) = WebSearchRegistry::read_global(cx).active_provider() else {
-                    return Err(WebSearchToolOutput::Error {
-                        error: "Web search is not available.".to_string(),
-                    });
-                };
+                let provider = Self::provider(profile_id.as_ref(), cx)
+                    .map_err(|error| WebSearchToolOutput::Error { error })?;
 
                 let search_task = provider.search(input.query, cx);
                 Ok((authorize, search_task))
@@ -7,11 +7,11 @@ use fs::Fs;
 use gpui::{App, SharedString};
 use settings::{
     AgentProfileContent, ContextServerPresetContent, LanguageModelSelection, Settings as _,
-    SettingsContent, update_settings_file,
+    SettingsContent, WebSearchProviderContent, update_settings_file,
 };
 use util::ResultExt as _;
 
-use crate::{AgentProfileId, AgentSettings};
+use crate::{AgentBudget, AgentProfileId, AgentSettings};
 
 pub mod builtin_profiles {
     use super::AgentProfileId;
@@ -77,6 +77,10 @@ impl AgentProfile {
             enable_all_context_servers,
             context_servers,
             default_model,
+            budget: AgentBudget::default(),
+            web_search_provider: base_profile
+                .as_ref()
+                .and_then(|profile| profile.web_search_provider),
         };
 
         update_settings_file(fs, cx, {
@@ -109,6 +113,11 @@ pub struct AgentProfileSettings {
     pub context_servers: IndexMap<Arc<str>, ContextServerPreset>,
     /// Default language model to apply when this profile becomes active.
     pub default_model: Option<LanguageModelSelection>,
+    /// The daily budget of the threads using this profile.
+    pub budget: AgentBudget,
+    /// The provider the `web_search` tool searches with in threads using this
+    /// profile, instead of the one in the agent settings.
+    pub web_search_provider: Option<WebSearchProviderContent>,
 }
 
 impl AgentProfileSettings {
@@ -158,6 +167,8 @@ impl AgentProfileSettings {
                     })
                     .collect(),
                 default_model: self.default_model.clone(),
+                budget: self.budget.to_content(),
+                web_search_provider: self.web_search_provider,
             },
         );
 
@@ -173,6 +184,8 @@ impl From<AgentProfileContent> for AgentProfileSettings {
             enable_all_context_servers,
             context_servers,
             default_model,
+            budget,
+            web_search_provider,
         } = content;
 
         Self {
@@ -184,6 +197,8 @@ impl From<AgentProfileContent> for AgentProfileSettings {
                 .map(|(server_id, preset)| (server_id, preset.into()))
                 .collect(),
             default_model,
+            budget: budget.map(Into::into).unwrap_or_default(),
+            web_search_provider,
         }
     }
 }
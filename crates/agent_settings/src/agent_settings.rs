@@ -1,7 +1,8 @@
 mod agent_profile;
 
-use std::path::{Component, Path};
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use agent_client_protocol::ModelId;
 use collections::{HashSet, IndexMap};
@@ -13,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use settings::{
     DefaultAgentView, DockPosition, LanguageModelParameters, LanguageModelSelection,
     NewThreadLocation, NotifyWhenAgentWaiting, RegisterSetting, Settings, ToolPermissionMode,
+    WebSearchProviderContent,
 };
 
 pub use crate::agent_profile::*;
@@ -52,6 +54,12 @@ pub struct AgentSettings {
     pub show_turn_stats: bool,
     pub tool_permissions: ToolPermissions,
     pub new_thread_location: NewThreadLocation,
+    pub terminal_sandbox: TerminalSandboxSettings,
+    pub hooks: AgentHooks,
+    pub budgets: AgentBudgets,
+    /// The provider the `web_search` tool searches with, unless the profile
+    /// sets another one.
+    pub web_search_provider: Option<WebSearchProviderContent>,
 }
 
 impl AgentSettings {
@@ -110,6 +118,125 @@ impl Default for AgentProfileId {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentHooks {
+    pub before_tool_call: Vec<AgentHook>,
+    pub after_tool_call: Vec<AgentHook>,
+    pub after_edit: Vec<AgentHook>,
+    pub turn_finished: Vec<AgentHook>,
+}
+
+impl AgentHooks {
+    /// Returns the hooks run by calls to the given tool.
+    pub fn for_tool(&self, tool_name: &str) -> ToolHooks {
+        let matching = |hooks: &[AgentHook]| {
+            hooks
+                .iter()
+                .filter(|hook| hook.applies_to_tool(tool_name))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        ToolHooks {
+            before_tool_call: matching(&self.before_tool_call),
+            after_tool_call: matching(&self.after_tool_call),
+            after_edit: matching(&self.after_edit),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ToolHooks {
+    pub before_tool_call: Vec<AgentHook>,
+    pub after_tool_call: Vec<AgentHook>,
+    pub after_edit: Vec<AgentHook>,
+}
+
+impl ToolHooks {
+    pub fn is_empty(&self) -> bool {
+        self.before_tool_call.is_empty()
+            && self.after_tool_call.is_empty()
+            && self.after_edit.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgentHook {
+    pub action: AgentHookAction,
+    pub tools: Option<Vec<String>>,
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AgentHookAction {
+    /// A shell command.
+    Command(String),
+    /// The label of a task from the task inventory.
+    Task(String),
+}
+
+impl AgentHook {
+    pub fn applies_to_tool(&self, tool_name: &str) -> bool {
+        self.tools
+            .as_ref()
+            .is_none_or(|tools| tools.iter().any(|tool| tool == tool_name))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AgentBudgets {
+    /// The fraction of a budget after which the agent warns that it's close
+    /// to being reached.
+    pub warning_threshold: f64,
+    pub thread: AgentBudget,
+    pub daily: AgentBudget,
+}
+
+impl Default for AgentBudgets {
+    fn default() -> Self {
+        Self {
+            warning_threshold: 0.8,
+            thread: AgentBudget::default(),
+            daily: AgentBudget::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AgentBudget {
+    pub max_tokens: Option<u64>,
+    /// The maximum cost, in US dollars.
+    pub max_cost: Option<f64>,
+}
+
+impl AgentBudget {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost.is_none()
+    }
+
+    pub fn to_content(&self) -> Option<settings::AgentBudgetContent> {
+        (!self.is_unlimited()).then(|| settings::AgentBudgetContent {
+            max_tokens: self.max_tokens,
+            max_cost: self.max_cost,
+        })
+    }
+}
+
+impl From<settings::AgentBudgetContent> for AgentBudget {
+    fn from(content: settings::AgentBudgetContent) -> Self {
+        Self {
+            max_tokens: content.max_tokens,
+            max_cost: content.max_cost,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TerminalSandboxSettings {
+    pub enabled: bool,
+    pub allow_network: bool,
+    pub writable_paths: Vec<PathBuf>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ToolPermissions {
     /// Global default permission when no tool-specific rules or patterns match.
@@ -440,10 +567,77 @@ impl Settings for AgentSettings {
             show_turn_stats: agent.show_turn_stats.unwrap(),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
             new_thread_location: agent.new_thread_location.unwrap_or_default(),
+            terminal_sandbox: terminal_sandbox_settings(agent.terminal_sandbox),
+            hooks: agent_hooks(agent.hooks),
+            budgets: agent_budgets(agent.budgets),
+            web_search_provider: agent.web_search.and_then(|web_search| web_search.provider),
         }
     }
 }
 
+fn agent_budgets(content: Option<settings::AgentBudgetsContent>) -> AgentBudgets {
+    let content = content.unwrap_or_default();
+    let defaults = AgentBudgets::default();
+    AgentBudgets {
+        warning_threshold: content
+            .warning_threshold
+            .map_or(defaults.warning_threshold, |threshold| {
+                threshold.clamp(0., 1.)
+            }),
+        thread: content.thread.map(Into::into).unwrap_or_default(),
+        daily: content.daily.map(Into::into).unwrap_or_default(),
+    }
+}
+
+fn agent_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let content = content.unwrap_or_default();
+    let hooks = |hooks: Option<Vec<settings::AgentHookContent>>| {
+        hooks
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|hook| {
+                let action = match hook.task.filter(|task| !task.trim().is_empty()) {
+                    Some(task) => AgentHookAction::Task(task),
+                    None if !hook.command.trim().is_empty() => {
+                        AgentHookAction::Command(hook.command)
+                    }
+                    None => return None,
+                };
+                Some(AgentHook {
+                    action,
+                    tools: hook.tools,
+                    timeout: Duration::from_secs(hook.timeout_seconds.unwrap_or(60)),
+                })
+            })
+            .collect()
+    };
+    AgentHooks {
+        before_tool_call: hooks(content.before_tool_call),
+        after_tool_call: hooks(content.after_tool_call),
+        after_edit: hooks(content.after_edit),
+        turn_finished: hooks(content.turn_finished),
+    }
+}
+
+fn terminal_sandbox_settings(
+    content: Option<settings::TerminalSandboxSettingsContent>,
+) -> TerminalSandboxSettings {
+    let content = content.unwrap_or_default();
+    TerminalSandboxSettings {
+        enabled: content.enabled.unwrap_or_default(),
+        allow_network: content.allow_network.unwrap_or_default(),
+        writable_paths: content
+            .writable_paths
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| match path.strip_prefix("~/") {
+                Some(relative_path) => paths::home_dir().join(relative_path),
+                None => PathBuf::from(path),
+            })
+            .collect(),
+    }
+}
+
 fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -> ToolPermissions {
     let Some(content) = content else {
         return ToolPermissions::default();
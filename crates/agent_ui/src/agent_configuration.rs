@@ -38,7 +38,7 @@ use ui::{
     WithScrollbar, prelude::*,
 };
 use util::ResultExt as _;
-use workspace::{Workspace, create_and_open_local_file};
+use workspace::{Workspace, create_and_open_local_file, notifications::DetachAndPromptErr};
 use zed_actions::{ExtensionCategoryFilter, OpenBrowser};
 
 pub(crate) use configure_context_server_modal::ConfigureContextServerModal;
@@ -646,6 +646,11 @@ impl AgentConfiguration {
         } else {
             None
         };
+        let requires_authentication = matches!(server_status, ContextServerStatus::AuthRequired);
+        let is_signed_in = self
+            .context_server_store
+            .read(cx)
+            .has_oauth_credentials(&context_server_id);
 
         let tool_count = self
             .context_server_registry
@@ -689,6 +694,10 @@ impl AgentConfiguration {
                 Indicator::dot().color(Color::Muted).into_any_element(),
                 "Server is stopped.",
             ),
+            ContextServerStatus::AuthRequired => (
+                Indicator::dot().color(Color::Warning).into_any_element(),
+                "Server requires authentication.",
+            ),
         };
         let is_remote = server_configuration
             .as_ref()
@@ -708,6 +717,7 @@ impl AgentConfiguration {
                 let language_registry = self.language_registry.clone();
                 let workspace = self.workspace.clone();
                 let context_server_registry = self.context_server_registry.clone();
+                let context_server_store = self.context_server_store.clone();
 
                 move |window, cx| {
                     Some(ContextMenu::build(window, cx, |menu, _window, _cx| {
@@ -754,6 +764,17 @@ impl AgentConfiguration {
                                 .ok();
                             }
                         }))
+                        .when(is_signed_in, |this| this.entry("Sign Out", None, {
+                            let context_server_id = context_server_id.clone();
+                            let context_server_store = context_server_store.clone();
+                            move |_, cx| {
+                                context_server_store
+                                    .update(cx, |store, cx| {
+                                        store.sign_out_server(&context_server_id, cx)
+                                    })
+                                    .detach_and_log_err(cx);
+                            }
+                        }))
                         .separator()
                         .entry("Uninstall", None, {
                             let fs = fs.clone();
@@ -864,10 +885,14 @@ impl AgentConfiguration {
                             .flex_none()
                             .child(context_server_configuration_menu)
                             .child(
-                            Switch::new("context-server-switch", is_running.into())
+                            Switch::new(
+                                "context-server-switch",
+                                (is_running || requires_authentication).into(),
+                            )
                                 .on_click({
                                     let context_server_manager = self.context_server_store.clone();
                                     let fs = self.fs.clone();
+                                    let context_server_id = context_server_id.clone();
 
                                     move |state, _window, cx| {
                                         let is_enabled = match state {
@@ -941,6 +966,43 @@ impl AgentConfiguration {
                             ),
                     );
                 }
+                if requires_authentication {
+                    return parent.child(
+                        h_flex()
+                            .gap_2()
+                            .pr_4()
+                            .justify_between()
+                            .child(
+                                Label::new("Authenticate to use the tools of this server.")
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            )
+                            .child(
+                                Button::new("authenticate-context-server", "Authenticate")
+                                    .style(ButtonStyle::Outlined)
+                                    .label_size(LabelSize::Small)
+                                    .on_click({
+                                        let context_server_store =
+                                            self.context_server_store.clone();
+                                        move |_, window, cx| {
+                                            context_server_store
+                                                .update(cx, |store, cx| {
+                                                    store.authenticate_server(
+                                                        &context_server_id,
+                                                        cx,
+                                                    )
+                                                })
+                                                .detach_and_prompt_err(
+                                                    "Failed to authenticate with the MCP server",
+                                                    window,
+                                                    cx,
+                                                    |_, _, _| None,
+                                                );
+                                        }
+                                    }),
+                            ),
+                    );
+                }
                 parent
             })
     }
@@ -891,7 +891,8 @@ fn wait_for_context_server(
         let project::context_server_store::ServerStatusChangedEvent { server_id, status } = event;
 
         match status {
-            ContextServerStatus::Running => {
+            // Servers requiring authentication are authenticated from the configuration view
+            ContextServerStatus::Running | ContextServerStatus::AuthRequired => {
                 if server_id == &context_server_id
                     && let Some(tx) = tx.lock().unwrap().take()
                 {
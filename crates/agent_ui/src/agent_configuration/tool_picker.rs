@@ -310,6 +310,8 @@ impl PickerDelegate for ToolPickerDelegate {
                             })
                             .collect(),
                         default_model: default_profile.default_model.clone(),
+                        budget: default_profile.budget.to_content(),
+                        web_search_provider: default_profile.web_search_provider,
                     });
 
                 if let Some(server_id) = server_id {
@@ -29,6 +29,7 @@ use zed_actions::agent::{
     ResolveConflictedFilesWithAgent, ResolveConflictsWithAgent, ReviewBranchDiff,
 };
 
+use crate::context_server_elicitation::ElicitationForm;
 use crate::ui::{AcpOnboardingModal, ClaudeCodeOnboardingModal, HoldForDefault};
 use crate::{
     AddContextServer, AgentDiffPane, ConnectionView, CopyThreadToClipboard, CycleStartThreadIn,
@@ -898,6 +899,7 @@ pub struct AgentPanel {
     on_boarding_upsell_dismissed: AtomicBool,
     _active_view_observation: Option<Subscription>,
     pub(crate) sidebar: Option<Entity<crate::sidebar::Sidebar>>,
+    elicitations: Vec<Entity<ElicitationForm>>,
 }
 
 impl AgentPanel {
@@ -1234,6 +1236,7 @@ impl AgentPanel {
             on_boarding_upsell_dismissed: AtomicBool::new(OnboardingUpsell::dismissed()),
             _active_view_observation: None,
             sidebar: None,
+            elicitations: Vec::new(),
         };
 
         // Initial sync of agent servers from extensions
@@ -4853,6 +4856,21 @@ impl AgentPanel {
         )
     }
 
+    /// Show a form requested by a context server. Forms are shown one at a time, oldest first.
+    pub(crate) fn push_elicitation(
+        &mut self,
+        form: Entity<ElicitationForm>,
+        cx: &mut Context<Self>,
+    ) {
+        cx.subscribe(&form, |this, form, _: &DismissEvent, cx| {
+            this.elicitations.retain(|pending| pending != &form);
+            cx.notify();
+        })
+        .detach();
+        self.elicitations.push(form);
+        cx.notify();
+    }
+
     fn key_context(&self) -> KeyContext {
         let mut key_context = KeyContext::new_with_defaults();
         key_context.add("AgentPanel");
@@ -4907,6 +4925,7 @@ impl Render for AgentPanel {
             .child(self.render_toolbar(window, cx))
             .children(self.render_worktree_creation_status(cx))
             .children(self.render_workspace_trust_message(cx))
+            .children(self.elicitations.first().cloned())
             .children(self.render_onboarding(window, cx))
             .map(|parent| {
                 // Emit configuration error telemetry before entering the match to avoid borrow conflicts
@@ -11,6 +11,8 @@ mod config_options;
 pub(crate) mod connection_view;
 mod context;
 mod context_server_configuration;
+mod context_server_elicitation;
+mod context_server_sampling;
 mod entry_view_state;
 mod external_source_prompt;
 mod favorite_models;
@@ -23,6 +25,7 @@ mod mode_selector;
 mod model_selector;
 mod model_selector_popover;
 mod profile_selector;
+mod resource_template;
 pub mod sidebar;
 mod slash_command;
 mod slash_command_picker;
@@ -377,6 +380,8 @@ pub fn init(
     assistant_slash_command::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
+    context_server_elicitation::init(cx);
+    context_server_sampling::init(cx);
     TextThreadEditor::init(cx);
 
     register_slash_commands(cx);
@@ -657,6 +662,10 @@ mod tests {
             tool_permissions: Default::default(),
             show_turn_stats: false,
             new_thread_location: Default::default(),
+            terminal_sandbox: Default::default(),
+            hooks: Default::default(),
+            budgets: Default::default(),
+            web_search_provider: None,
         };
 
         cx.update(|cx| {
@@ -6,6 +6,7 @@ use std::sync::atomic::AtomicBool;
 
 use crate::ThreadHistory;
 use acp_thread::MentionUri;
+use agent::ContextServerResourceTemplate;
 use agent_client_protocol as acp;
 use anyhow::Result;
 use editor::{
@@ -40,6 +41,7 @@ use workspace::dock::DockPosition;
 
 use crate::AgentPanel;
 use crate::mention_set::MentionSet;
+use crate::resource_template::{TemplateMatch, UriTemplate};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum PromptContextEntry {
@@ -65,6 +67,7 @@ pub(crate) enum PromptContextType {
     Rules,
     Diagnostics,
     BranchDiff,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -104,6 +107,7 @@ impl TryFrom<&str> for PromptContextType {
             "rule" => Ok(Self::Rules),
             "diagnostics" => Ok(Self::Diagnostics),
             "diff" => Ok(Self::BranchDiff),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -119,6 +123,7 @@ impl PromptContextType {
             Self::Rules => "rule",
             Self::Diagnostics => "diagnostics",
             Self::BranchDiff => "branch diff",
+            Self::McpResource => "resource",
         }
     }
 
@@ -131,6 +136,7 @@ impl PromptContextType {
             Self::Rules => "Rules",
             Self::Diagnostics => "Diagnostics",
             Self::BranchDiff => "Branch Diff",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -143,6 +149,7 @@ impl PromptContextType {
             Self::Rules => IconName::Reader,
             Self::Diagnostics => IconName::Warning,
             Self::BranchDiff => IconName::GitBranch,
+            Self::McpResource => IconName::ToolHammer,
         }
     }
 }
@@ -156,6 +163,7 @@ pub(crate) enum Match {
     Rules(RulesContextEntry),
     Entry(EntryMatch),
     BranchDiff(BranchDiffMatch),
+    McpResource(McpResourceMatch),
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +171,48 @@ pub struct BranchDiffMatch {
     pub base_ref: SharedString,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) enum McpResourceMatch {
+    /// A concrete resource, attached as soon as it is picked.
+    Resource {
+        server_id: context_server::ContextServerId,
+        uri: String,
+        name: String,
+        description: Option<String>,
+    },
+    /// A step towards expanding a resource template. Picking it replaces the
+    /// mention's argument and keeps the menu open for the next variable.
+    TemplateStep {
+        label: String,
+        argument: String,
+        description: Option<String>,
+    },
+}
+
+impl McpResourceMatch {
+    fn for_template(
+        template: &ContextServerResourceTemplate,
+        label: String,
+        argument: String,
+        complete: bool,
+    ) -> Self {
+        if complete {
+            Self::Resource {
+                server_id: template.server_id.clone(),
+                name: argument.clone(),
+                uri: argument,
+                description: Some(template.name.clone()),
+            }
+        } else {
+            Self::TemplateStep {
+                label,
+                argument,
+                description: template.description.clone(),
+            }
+        }
+    }
+}
+
 impl Match {
     pub fn score(&self) -> f64 {
         match self {
@@ -174,6 +224,7 @@ impl Match {
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
             Match::BranchDiff(_) => 1.,
+            Match::McpResource(_) => 1.,
         }
     }
 }
@@ -541,6 +592,80 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_mcp_resource(
+        resource: McpResourceMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        match resource {
+            McpResourceMatch::Resource {
+                server_id,
+                uri,
+                name,
+                description,
+            } => {
+                let documentation = match description {
+                    Some(description) => format!("{uri}\n\n{description}"),
+                    None => uri.clone(),
+                };
+                let mention_uri = MentionUri::McpResource {
+                    server_id: server_id.0.to_string(),
+                    uri,
+                    name: name.clone(),
+                };
+                let new_text = format!("{} ", mention_uri.as_link());
+                let new_text_len = new_text.len();
+                let icon_path = mention_uri.icon_path(cx);
+                Completion {
+                    replace_range: source_range.clone(),
+                    new_text,
+                    label: CodeLabel::plain(name.clone(), None),
+                    documentation: Some(CompletionDocumentation::MultiLinePlainText(
+                        documentation.into(),
+                    )),
+                    source: project::CompletionSource::Custom,
+                    icon_path: Some(icon_path),
+                    match_start: None,
+                    snippet_deduplication_key: None,
+                    insert_text_mode: None,
+                    confirm: Some(confirm_completion_callback(
+                        name.into(),
+                        source_range.start,
+                        new_text_len - 1,
+                        mention_uri,
+                        source,
+                        editor,
+                        mention_set,
+                        workspace,
+                    )),
+                }
+            }
+            McpResourceMatch::TemplateStep {
+                label,
+                argument,
+                description,
+            } => Completion {
+                replace_range: source_range,
+                new_text: format!("@{} {}", PromptContextType::McpResource.keyword(), argument),
+                label: CodeLabel::plain(label, None),
+                documentation: description.map(|description| {
+                    CompletionDocumentation::MultiLinePlainText(description.into())
+                }),
+                source: project::CompletionSource::Custom,
+                icon_path: Some(PromptContextType::McpResource.icon().path().into()),
+                match_start: None,
+                snippet_deduplication_key: None,
+                insert_text_mode: None,
+                // Keep the menu open so the next variable can be filled in.
+                confirm: Some(Arc::new(|_, _, _| true)),
+            },
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -865,6 +990,152 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn search_mcp_resources(
+        &self,
+        query: String,
+        cancellation_flag: Arc<AtomicBool>,
+        workspace: &Entity<Workspace>,
+        cx: &mut App,
+    ) -> Task<Vec<Match>> {
+        let Some(registry) = workspace
+            .read(cx)
+            .panel::<AgentPanel>(cx)
+            .map(|panel| panel.read(cx).context_server_registry().clone())
+        else {
+            return Task::ready(Vec::new());
+        };
+        let registry = registry.read(cx);
+        let resources = registry.resources().cloned().collect::<Vec<_>>();
+        let templates = registry.resource_templates().cloned().collect::<Vec<_>>();
+
+        // When the argument is already a partial expansion of a template, offer
+        // the next step of that expansion, asking the server for the values of
+        // the variable being typed.
+        let mut template_matches = Vec::new();
+        let mut argument_completions = Vec::new();
+        if !query.is_empty() {
+            for template in &templates {
+                let uri_template = UriTemplate::parse(&template.uri_template);
+                match uri_template.match_prefix(&query) {
+                    None => {}
+                    Some(TemplateMatch::Complete) => {
+                        template_matches.push(McpResourceMatch::for_template(
+                            template,
+                            query.clone(),
+                            query.clone(),
+                            true,
+                        ));
+                    }
+                    Some(TemplateMatch::Literal {
+                        literal_start,
+                        part_ix,
+                    }) => {
+                        let (argument, complete) =
+                            uri_template.advance(query[..literal_start].to_string(), part_ix);
+                        template_matches.push(McpResourceMatch::for_template(
+                            template,
+                            template.name.clone(),
+                            argument,
+                            complete,
+                        ));
+                    }
+                    Some(TemplateMatch::Variable {
+                        name,
+                        value,
+                        value_start,
+                        part_ix,
+                    }) => {
+                        // Values the server doesn't know about can still be attached.
+                        if uri_template.is_last_variable(part_ix) && !value.is_empty() {
+                            let (uri, _) = uri_template.advance(query.clone(), part_ix + 1);
+                            template_matches.push(McpResourceMatch::for_template(
+                                template,
+                                uri.clone(),
+                                uri,
+                                true,
+                            ));
+                        }
+                        let values = registry.complete_resource_template_argument(
+                            &template.server_id,
+                            template.uri_template.clone(),
+                            name,
+                            value,
+                            cx,
+                        );
+                        argument_completions.push((
+                            template.clone(),
+                            uri_template,
+                            value_start,
+                            part_ix,
+                            values,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let candidates = resources
+            .iter()
+            .map(|resource| resource.name.as_str())
+            .chain(templates.iter().map(|template| template.name.as_str()))
+            .enumerate()
+            .map(|(ix, name)| StringMatchCandidate::new(ix, name))
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |cx| {
+            let mut matches = template_matches;
+            for (template, uri_template, value_start, part_ix, values) in argument_completions {
+                for value in values.await.log_err().unwrap_or_default() {
+                    let (argument, complete) =
+                        uri_template.fill_variable(&query, value_start, part_ix, &value);
+                    matches.push(McpResourceMatch::for_template(
+                        &template, value, argument, complete,
+                    ));
+                }
+            }
+
+            let candidate_ids = if query.is_empty() {
+                (0..candidates.len()).collect::<Vec<_>>()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &cancellation_flag,
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|mat| mat.candidate_id)
+                .collect()
+            };
+            for ix in candidate_ids {
+                if let Some(resource) = resources.get(ix) {
+                    matches.push(McpResourceMatch::Resource {
+                        server_id: resource.server_id.clone(),
+                        uri: resource.uri.clone(),
+                        name: resource.name.clone(),
+                        description: resource.description.clone(),
+                    });
+                } else {
+                    let template = &templates[ix - resources.len()];
+                    let uri_template = UriTemplate::parse(&template.uri_template);
+                    let (argument, complete) = uri_template.advance(String::new(), 0);
+                    matches.push(McpResourceMatch::for_template(
+                        template,
+                        template.name.clone(),
+                        argument,
+                        complete,
+                    ));
+                }
+            }
+
+            matches.into_iter().map(Match::McpResource).collect()
+        })
+    }
+
     fn fetch_branch_diff_match(
         &self,
         workspace: &Entity<Workspace>,
@@ -968,6 +1239,10 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
 
             Some(PromptContextType::BranchDiff) => Task::ready(Vec::new()),
 
+            Some(PromptContextType::McpResource) => {
+                self.search_mcp_resources(query, cancellation_flag, &workspace, cx)
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -1228,6 +1503,19 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             }
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::McpResource, cx)
+            && let Some(agent_panel) = workspace.read(cx).panel::<AgentPanel>(cx)
+        {
+            let registry = agent_panel.read(cx).context_server_registry().read(cx);
+            if registry.resources().next().is_some()
+                || registry.resource_templates().next().is_some()
+            {
+                entries.push(PromptContextEntry::Mode(PromptContextType::McpResource));
+            }
+        }
+
         entries
     }
 }
@@ -1496,6 +1784,17 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                         cx,
                                     ))
                                 }
+                                Match::McpResource(resource) => {
+                                    Some(Self::completion_for_mcp_resource(
+                                        resource,
+                                        source_range.clone(),
+                                        source.clone(),
+                                        editor.clone(),
+                                        mention_set.clone(),
+                                        workspace.clone(),
+                                        cx,
+                                    ))
+                                }
                             })
                             .collect::<Vec<_>>()
                     });
@@ -2488,6 +2787,20 @@ mod tests {
             None,
             "Should not parse with a space after @ at the start of the line"
         );
+
+        assert_eq!(
+            MentionCompletion::try_parse(
+                "Lorem @resource github://repos/zed",
+                0,
+                &[PromptContextType::McpResource]
+            ),
+            Some(MentionCompletion {
+                source_range: 6..34,
+                mode: Some(PromptContextType::McpResource),
+                argument: Some("github://repos/zed".to_string()),
+            }),
+            "Should keep a partially expanded resource template as the argument"
+        );
     }
 
     #[gpui::test]
@@ -106,6 +106,7 @@ pub(crate) enum ThreadError {
     PaymentRequired,
     Refusal,
     AuthenticationRequired(SharedString),
+    BudgetExceeded(agent::BudgetExceededError),
     Other {
         message: SharedString,
         acp_error_code: Option<SharedString>,
@@ -116,6 +117,8 @@ impl From<anyhow::Error> for ThreadError {
     fn from(error: anyhow::Error) -> Self {
         if error.is::<language_model::PaymentRequiredError>() {
             Self::PaymentRequired
+        } else if let Some(error) = error.downcast_ref::<agent::BudgetExceededError>() {
+            Self::BudgetExceeded(error.clone())
         } else if let Some(acp_error) = error.downcast_ref::<acp::Error>()
             && acp_error.code == acp::ErrorCode::AuthRequired
         {
@@ -1114,6 +1114,9 @@ impl ThreadView {
                 ThreadError::AuthenticationRequired(message) => {
                     ("authentication_required", None, message.clone())
                 }
+                ThreadError::BudgetExceeded(error) => {
+                    ("budget_exceeded", None, error.to_string().into())
+                }
                 ThreadError::Other {
                     acp_error_code,
                     message,
@@ -7172,6 +7175,9 @@ impl ThreadView {
                 self.render_authentication_required_error(error.clone(), cx)
             }
             ThreadError::PaymentRequired => self.render_payment_required_error(cx),
+            ThreadError::BudgetExceeded(error) => {
+                self.render_budget_exceeded_error(error.clone(), cx)
+            }
         };
 
         Some(div().child(content))
@@ -7232,6 +7238,54 @@ impl ThreadView {
             .dismiss_action(self.dismiss_error_button(cx))
     }
 
+    fn render_budget_exceeded_error(
+        &self,
+        error: agent::BudgetExceededError,
+        cx: &mut Context<Self>,
+    ) -> Callout {
+        let message = error.to_string();
+        let description = format!(
+            "{message} The agent stopped so that it doesn't use more until you extend the budget."
+        );
+
+        Callout::new()
+            .severity(Severity::Error)
+            .icon(IconName::XCircle)
+            .title("Budget Reached")
+            .description(description)
+            .actions_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(self.extend_budget_button(error.scope, cx))
+                    .child(self.create_copy_button(message)),
+            )
+            .dismiss_action(self.dismiss_error_button(cx))
+    }
+
+    fn extend_budget_button(
+        &self,
+        scope: agent::BudgetScope,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new("extend-budget", "Extend Budget")
+            .label_size(LabelSize::Small)
+            .style(ButtonStyle::Filled)
+            .on_click(cx.listener(move |this, _, _, cx| {
+                let Some(thread) = this.as_native_thread(cx) else {
+                    return;
+                };
+                let extend = thread.update(cx, |thread, cx| thread.extend_budget(&scope, cx));
+                cx.spawn(async move |this, cx| {
+                    let result = extend.await;
+                    this.update(cx, |this, cx| match result {
+                        Ok(()) => this.retry_generation(cx),
+                        Err(error) => this.handle_thread_error(error, cx),
+                    })
+                })
+                .detach();
+            }))
+    }
+
     fn upgrade_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
         Button::new("upgrade", "Upgrade")
             .label_size(LabelSize::Small)
@@ -7612,6 +7666,27 @@ impl ThreadView {
         )
     }
 
+    fn render_budget_warning_callout(&self, cx: &mut Context<Self>) -> Option<Callout> {
+        let thread = self.as_native_thread(cx)?;
+        let warning = thread.read(cx).budget_warning()?.clone();
+
+        Some(
+            Callout::new()
+                .severity(Severity::Warning)
+                .icon(IconName::Warning)
+                .title("Budget almost reached")
+                .description(warning)
+                .dismiss_action(
+                    IconButton::new("dismiss-budget-warning", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Dismiss"))
+                        .on_click(move |_, _, cx| {
+                            thread.update(cx, |thread, cx| thread.dismiss_budget_warning(cx));
+                        }),
+                ),
+        )
+    }
+
     fn open_permission_dropdown(
         &mut self,
         _: &crate::OpenPermissionDropdown,
@@ -7917,6 +7992,7 @@ impl Render for ThreadView {
                 |this, version| this.child(self.render_new_version_callout(&version, cx)),
             )
             .children(self.render_token_limit_callout(cx))
+            .children(self.render_budget_warning_callout(cx))
             .child(self.render_message_editor(window, cx))
     }
 }
@@ -8033,6 +8109,7 @@ pub(crate) fn open_link(
             MentionUri::TerminalSelection { .. } => {}
             MentionUri::GitDiff { .. } => {}
             MentionUri::MergeConflict { .. } => {}
+            MentionUri::McpResource { .. } => {}
         })
     } else {
         cx.open_url(&url);
@@ -0,0 +1,568 @@
+//! Forms shown in the agent panel when a context server asks the user for structured input
+//! through an `elicitation/create` request.
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::HashMap;
+use context_server::{
+    ContextServerId, ElicitationHandler,
+    types::{
+        ElicitAction, ElicitParams, ElicitResult, NumberSchema, PrimitiveSchemaDefinition,
+        StringSchema,
+    },
+};
+use futures::channel::oneshot;
+use gpui::{AnyWindowHandle, AsyncApp, DismissEvent, Entity, EventEmitter, WeakEntity};
+use serde_json::Value;
+use ui::{Checkbox, prelude::*};
+use ui_input::InputField;
+use workspace::Workspace;
+
+use crate::AgentPanel;
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let handler = elicitation_handler(window.window_handle(), cx.weak_entity());
+        workspace
+            .project()
+            .read(cx)
+            .context_server_store()
+            .update(cx, |store, _| store.set_elicitation_handler(Some(handler)));
+    })
+    .detach();
+}
+
+fn elicitation_handler(
+    window: AnyWindowHandle,
+    workspace: WeakEntity<Workspace>,
+) -> ElicitationHandler {
+    Arc::new(
+        move |server_id: ContextServerId, params: ElicitParams, cx: AsyncApp| {
+            let workspace = workspace.clone();
+            cx.spawn(async move |cx| {
+                let (response_tx, response_rx) = oneshot::channel();
+                window.update(cx, |_, window, cx| {
+                    let workspace = workspace.upgrade().context("workspace was closed")?;
+                    workspace.update(cx, |workspace, cx| {
+                        let panel = workspace
+                            .panel::<AgentPanel>(cx)
+                            .context("the agent panel is not available")?;
+                        let form = cx.new(|cx| {
+                            ElicitationForm::new(server_id, params, response_tx, window, cx)
+                        });
+                        panel.update(cx, |panel, cx| panel.push_elicitation(form, cx));
+                        workspace.focus_panel::<AgentPanel>(window, cx);
+                        anyhow::Ok(())
+                    })
+                })??;
+
+                // The form is dropped without an answer when the panel or its window is closed
+                Ok(response_rx.await.unwrap_or(ElicitResult {
+                    action: ElicitAction::Cancel,
+                    content: None,
+                    meta: None,
+                }))
+            })
+        },
+    )
+}
+
+pub(crate) struct ElicitationForm {
+    server_id: ContextServerId,
+    message: SharedString,
+    fields: Vec<FormField>,
+    error: Option<SharedString>,
+    response_tx: Option<oneshot::Sender<ElicitResult>>,
+}
+
+struct FormField {
+    name: String,
+    label: SharedString,
+    description: Option<SharedString>,
+    required: bool,
+    input: FieldInput,
+}
+
+enum FieldInput {
+    Text {
+        input: Entity<InputField>,
+        schema: StringSchema,
+    },
+    Number {
+        input: Entity<InputField>,
+        schema: NumberSchema,
+        integer: bool,
+    },
+    Boolean(bool),
+    Choice {
+        options: Vec<String>,
+        labels: Vec<SharedString>,
+        selected: Option<usize>,
+    },
+}
+
+impl EventEmitter<DismissEvent> for ElicitationForm {}
+
+impl ElicitationForm {
+    fn new(
+        server_id: ContextServerId,
+        params: ElicitParams,
+        response_tx: oneshot::Sender<ElicitResult>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let schema = params.requested_schema;
+        let fields = schema
+            .properties
+            .into_iter()
+            .map(|(name, property)| {
+                let required = schema.required.contains(&name);
+                FormField::new(name, property, required, window, cx)
+            })
+            .collect();
+        Self {
+            server_id,
+            message: params.message.into(),
+            fields,
+            error: None,
+            response_tx: Some(response_tx),
+        }
+    }
+
+    /// Values of the filled fields, keyed by property name
+    fn content(&self, cx: &App) -> Result<HashMap<String, Value>> {
+        let mut content = HashMap::default();
+        for field in &self.fields {
+            let value = match &field.input {
+                FieldInput::Text { input, schema } => {
+                    string_value(schema, &input.read(cx).text(cx))
+                }
+                FieldInput::Number {
+                    input,
+                    schema,
+                    integer,
+                } => number_value(schema, *integer, &input.read(cx).text(cx)),
+                FieldInput::Boolean(checked) => Ok(Some(Value::Bool(*checked))),
+                FieldInput::Choice {
+                    options, selected, ..
+                } => Ok(selected.map(|ix| Value::String(options[ix].clone()))),
+            }
+            .map_err(|error| anyhow!("{}: {error}", field.label))?;
+
+            match value {
+                Some(value) => {
+                    content.insert(field.name.clone(), value);
+                }
+                None if field.required => bail!("{} is required", field.label),
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+
+    fn respond(&mut self, action: ElicitAction, cx: &mut Context<Self>) {
+        let content = if action == ElicitAction::Accept {
+            match self.content(cx) {
+                Ok(content) => Some(content),
+                Err(error) => {
+                    self.error = Some(error.to_string().into());
+                    cx.notify();
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(response_tx) = self.response_tx.take() {
+            response_tx
+                .send(ElicitResult {
+                    action,
+                    content,
+                    meta: None,
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn render_field(&self, ix: usize, field: &FormField, cx: &mut Context<Self>) -> AnyElement {
+        let label = h_flex()
+            .gap_0p5()
+            .child(Label::new(field.label.clone()).size(LabelSize::Small))
+            .when(field.required, |this| {
+                this.child(Label::new("*").size(LabelSize::Small).color(Color::Error))
+            });
+        let description = field.description.clone().map(|description| {
+            Label::new(description)
+                .size(LabelSize::XSmall)
+                .color(Color::Muted)
+        });
+
+        match &field.input {
+            FieldInput::Text { input, .. } | FieldInput::Number { input, .. } => v_flex()
+                .gap_1()
+                .child(label)
+                .children(description)
+                .child(input.clone())
+                .into_any_element(),
+            FieldInput::Boolean(checked) => v_flex()
+                .gap_0p5()
+                .child(
+                    Checkbox::new(("elicitation-checkbox", ix), (*checked).into())
+                        .label(field.label.clone())
+                        .on_click(cx.listener(move |this, state: &ToggleState, _, cx| {
+                            if let FieldInput::Boolean(checked) = &mut this.fields[ix].input {
+                                *checked = state.selected();
+                            }
+                            cx.notify();
+                        })),
+                )
+                .children(description)
+                .into_any_element(),
+            FieldInput::Choice {
+                labels, selected, ..
+            } => v_flex()
+                .gap_1()
+                .child(label)
+                .children(description)
+                .child(
+                    h_flex()
+                        .flex_wrap()
+                        .gap_1()
+                        .children(labels.iter().enumerate().map(|(option_ix, option_label)| {
+                            Button::new(
+                                SharedString::from(format!("elicitation-choice-{ix}-{option_ix}")),
+                                option_label.clone(),
+                            )
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Outlined)
+                            .toggle_state(*selected == Some(option_ix))
+                            .on_click(cx.listener(
+                                move |this, _, _, cx| {
+                                    if let FieldInput::Choice { selected, .. } =
+                                        &mut this.fields[ix].input
+                                    {
+                                        *selected = Some(option_ix);
+                                    }
+                                    cx.notify();
+                                },
+                            ))
+                        })),
+                )
+                .into_any_element(),
+        }
+    }
+}
+
+impl FormField {
+    fn new(
+        name: String,
+        schema: PrimitiveSchemaDefinition,
+        required: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self {
+        let (title, description) = match &schema {
+            PrimitiveSchemaDefinition::String(schema) => (&schema.title, &schema.description),
+            PrimitiveSchemaDefinition::Number(schema)
+            | PrimitiveSchemaDefinition::Integer(schema) => (&schema.title, &schema.description),
+            PrimitiveSchemaDefinition::Boolean(schema) => (&schema.title, &schema.description),
+        };
+        let label = title.clone().unwrap_or_else(|| name.clone()).into();
+        let description = description.clone().map(SharedString::from);
+
+        let input = match schema {
+            PrimitiveSchemaDefinition::String(schema) => {
+                if let Some(options) = schema.enum_values.clone() {
+                    let labels = schema
+                        .enum_names
+                        .clone()
+                        .filter(|names| names.len() == options.len())
+                        .unwrap_or_else(|| options.clone())
+                        .into_iter()
+                        .map(SharedString::from)
+                        .collect();
+                    let selected = schema
+                        .default
+                        .as_ref()
+                        .and_then(|default| options.iter().position(|option| option == default));
+                    FieldInput::Choice {
+                        options,
+                        labels,
+                        selected,
+                    }
+                } else {
+                    let placeholder = schema.format.clone().unwrap_or_default();
+                    let default = schema.default.clone();
+                    FieldInput::Text {
+                        input: text_input(&placeholder, default.as_deref(), window, cx),
+                        schema,
+                    }
+                }
+            }
+            PrimitiveSchemaDefinition::Number(schema) => number_input(schema, false, window, cx),
+            PrimitiveSchemaDefinition::Integer(schema) => number_input(schema, true, window, cx),
+            PrimitiveSchemaDefinition::Boolean(schema) => {
+                FieldInput::Boolean(schema.default.unwrap_or(false))
+            }
+        };
+
+        Self {
+            name,
+            label,
+            description,
+            required,
+            input,
+        }
+    }
+}
+
+fn text_input(
+    placeholder: &str,
+    text: Option<&str>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<InputField> {
+    cx.new(|cx| {
+        let input = InputField::new(window, cx, placeholder).tab_stop(true);
+        if let Some(text) = text {
+            input.set_text(text, window, cx);
+        }
+        input
+    })
+}
+
+fn number_input(
+    schema: NumberSchema,
+    integer: bool,
+    window: &mut Window,
+    cx: &mut App,
+) -> FieldInput {
+    let placeholder = match (schema.minimum, schema.maximum) {
+        (Some(minimum), Some(maximum)) => format!("{minimum} to {maximum}"),
+        (Some(minimum), None) => format!("At least {minimum}"),
+        (None, Some(maximum)) => format!("At most {maximum}"),
+        (None, None) if integer => "Whole number".to_string(),
+        (None, None) => "Number".to_string(),
+    };
+    let default = schema.default.map(|default| {
+        if integer {
+            (default as i64).to_string()
+        } else {
+            default.to_string()
+        }
+    });
+    FieldInput::Number {
+        input: text_input(&placeholder, default.as_deref(), window, cx),
+        schema,
+        integer,
+    }
+}
+
+/// The entered text checked against the schema, or `None` when nothing was entered
+fn string_value(schema: &StringSchema, text: &str) -> Result<Option<Value>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let len = text.chars().count();
+    if let Some(min_length) = schema.min_length
+        && len < min_length
+    {
+        bail!("must be at least {min_length} characters long");
+    }
+    if let Some(max_length) = schema.max_length
+        && len > max_length
+    {
+        bail!("must be at most {max_length} characters long");
+    }
+    Ok(Some(Value::String(text.to_string())))
+}
+
+/// The entered number checked against the schema, or `None` when nothing was entered
+fn number_value(schema: &NumberSchema, integer: bool, text: &str) -> Result<Option<Value>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let (number, value) = if integer {
+        let integer = text
+            .parse::<i64>()
+            .map_err(|_| anyhow!("must be a whole number"))?;
+        (integer as f64, Value::from(integer))
+    } else {
+        let number = text
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .context("must be a number")?;
+        (number, Value::from(number))
+    };
+    if let Some(minimum) = schema.minimum
+        && number < minimum
+    {
+        bail!("must be at least {minimum}");
+    }
+    if let Some(maximum) = schema.maximum
+        && number > maximum
+    {
+        bail!("must be at most {maximum}");
+    }
+    Ok(Some(value))
+}
+
+impl Render for ElicitationForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .id("elicitation-form")
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .child(
+                        Icon::new(IconName::ToolHammer)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(format!("The {} MCP server requests input", self.server_id))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(Label::new(self.message.clone()))
+            .children(
+                self.fields
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, field)| self.render_field(ix, field, cx)),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+            )
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_1()
+                    .child(
+                        Button::new("elicitation-cancel", "Cancel")
+                            .label_size(LabelSize::Small)
+                            .on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.respond(ElicitAction::Cancel, cx)
+                                }),
+                            ),
+                    )
+                    .child(
+                        Button::new("elicitation-decline", "Decline")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.respond(ElicitAction::Decline, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("elicitation-accept", "Submit")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.respond(ElicitAction::Accept, cx)
+                                }),
+                            ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_server::types::ElicitationSchema;
+
+    #[test]
+    fn test_parse_requested_schema() {
+        let schema: ElicitationSchema = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "title": "Name", "minLength": 2 },
+                "retries": { "type": "integer", "minimum": 0, "maximum": 5, "default": 3 },
+                "confirm": { "type": "boolean" },
+                "env": {
+                    "type": "string",
+                    "enum": ["dev", "prod"],
+                    "enumNames": ["Development", "Production"]
+                }
+            },
+            "required": ["name"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            schema.properties.keys().collect::<Vec<_>>(),
+            vec!["name", "retries", "confirm", "env"]
+        );
+        assert_eq!(schema.required, vec!["name".to_string()]);
+        assert!(matches!(
+            &schema.properties["retries"],
+            PrimitiveSchemaDefinition::Integer(NumberSchema {
+                maximum: Some(5.),
+                default: Some(3.),
+                ..
+            })
+        ));
+        let PrimitiveSchemaDefinition::String(env) = &schema.properties["env"] else {
+            panic!("expected a string schema");
+        };
+        assert_eq!(
+            env.enum_values.as_deref(),
+            Some(&["dev".to_string(), "prod".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_string_value() {
+        let schema = StringSchema {
+            min_length: Some(2),
+            max_length: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(string_value(&schema, "").unwrap(), None);
+        assert!(string_value(&schema, "a").is_err());
+        assert!(string_value(&schema, "abcde").is_err());
+        assert_eq!(
+            string_value(&schema, "äbc").unwrap(),
+            Some(Value::String("äbc".into()))
+        );
+    }
+
+    #[test]
+    fn test_number_value() {
+        let schema = NumberSchema {
+            minimum: Some(0.),
+            maximum: Some(10.),
+            ..Default::default()
+        };
+        assert_eq!(number_value(&schema, true, " ").unwrap(), None);
+        assert_eq!(
+            number_value(&schema, true, "7").unwrap(),
+            Some(Value::from(7))
+        );
+        assert!(number_value(&schema, true, "7.5").is_err());
+        assert_eq!(
+            number_value(&schema, false, "7.5").unwrap(),
+            Some(Value::from(7.5))
+        );
+        assert!(number_value(&schema, false, "11").is_err());
+        assert!(number_value(&schema, false, "-1").is_err());
+        assert!(number_value(&schema, false, "NaN").is_err());
+    }
+}
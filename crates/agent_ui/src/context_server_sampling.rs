@@ -0,0 +1,444 @@
+//! Answers `sampling/createMessage` requests of context servers with the user's language models,
+//! once the user approved the request.
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use context_server::{
+    ContextServerId, SamplingHandler,
+    types::{self, CreateMessageRequest, CreateMessageResult, ModelPreferences},
+};
+use futures::{Stream, StreamExt as _};
+use gpui::{AnyWindowHandle, App, AsyncApp, PromptLevel};
+use language_model::{
+    LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent,
+    LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role, StopReason,
+};
+use workspace::Workspace;
+
+/// Longest excerpt of the latest message shown in the approval prompt
+const PREVIEW_LEN: usize = 500;
+
+/// Bytes of text per output token, to estimate the number of tokens of a
+/// response before the model reports its usage
+const BYTES_PER_TOKEN_GUESS: u64 = 3;
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let handler = sampling_handler(window.window_handle());
+        workspace
+            .project()
+            .read(cx)
+            .context_server_store()
+            .update(cx, |store, _| store.set_sampling_handler(Some(handler)));
+    })
+    .detach();
+}
+
+fn sampling_handler(window: AnyWindowHandle) -> SamplingHandler {
+    Arc::new(
+        move |server_id: ContextServerId, request: CreateMessageRequest, cx: AsyncApp| {
+            cx.spawn(async move |cx| create_message(server_id, request, window, cx).await)
+        },
+    )
+}
+
+async fn create_message(
+    server_id: ContextServerId,
+    request: CreateMessageRequest,
+    window: AnyWindowHandle,
+    cx: &mut AsyncApp,
+) -> Result<CreateMessageResult> {
+    let model = cx
+        .update(|cx| {
+            let registry = LanguageModelRegistry::read_global(cx);
+            let available_models = registry.available_models(cx).collect::<Vec<_>>();
+            select_model(
+                request.model_preferences.as_ref(),
+                &available_models,
+                registry.default_model().map(|configured| configured.model),
+                registry
+                    .default_fast_model()
+                    .map(|configured| configured.model),
+            )
+        })
+        .context("no language model is configured")?;
+    let completion_request = completion_request(&request)?;
+
+    let message = format!(
+        "The {server_id} MCP server wants to generate a response with {}",
+        model.name().0
+    );
+    let detail = approval_detail(&request);
+    let answer = window.update(cx, |_, window, cx| {
+        window.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Allow", "Deny"],
+            cx,
+        )
+    })?;
+    if answer.await? != 0 {
+        bail!("the user rejected the sampling request");
+    }
+
+    let events = model.stream_completion(completion_request, cx).await?;
+    let (text, stop_reason) = collect_response(events, request.max_tokens).await?;
+
+    Ok(CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: stop_reason.map(|reason| {
+            match reason {
+                StopReason::EndTurn => "endTurn",
+                StopReason::MaxTokens => "maxTokens",
+                StopReason::ToolUse => "toolUse",
+                StopReason::Refusal => "refusal",
+            }
+            .to_string()
+        }),
+    })
+}
+
+/// Collects the response's text, cutting it off once it reaches the server's
+/// `maxTokens`.
+///
+/// Language model requests have no limit on the number of output tokens, so
+/// they're counted from the model's usage updates, or estimated from the
+/// text's length until the model reports its usage.
+async fn collect_response(
+    mut events: impl Stream<Item = Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>
+    + Unpin,
+    max_tokens: u32,
+) -> Result<(String, Option<StopReason>)> {
+    let max_tokens = max_tokens as u64;
+    let mut text = String::new();
+    let mut stop_reason = None;
+    let mut output_tokens = None;
+    while let Some(event) = events.next().await {
+        match event? {
+            LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+            LanguageModelCompletionEvent::Stop(reason) => stop_reason = Some(reason),
+            LanguageModelCompletionEvent::UsageUpdate(usage) => {
+                output_tokens = Some(usage.output_tokens)
+            }
+            _ => {}
+        }
+        if stop_reason.is_some() {
+            continue;
+        }
+        let max_len = max_tokens.saturating_mul(BYTES_PER_TOKEN_GUESS) as usize;
+        let reached_max_tokens = match output_tokens {
+            Some(output_tokens) => output_tokens >= max_tokens,
+            None if text.len() >= max_len => {
+                text.truncate(text.floor_char_boundary(max_len));
+                true
+            }
+            None => false,
+        };
+        if reached_max_tokens {
+            stop_reason = Some(StopReason::MaxTokens);
+            break;
+        }
+    }
+    Ok((text, stop_reason))
+}
+
+/// Picks the model answering a sampling request.
+///
+/// Hints are considered in order, and match the available models whose id or name contains them,
+/// preferring the provider of the default model. Without a matching hint, the fast model is used
+/// when the server gives speed or cost a higher priority than intelligence.
+fn select_model(
+    preferences: Option<&ModelPreferences>,
+    available_models: &[Arc<dyn LanguageModel>],
+    default_model: Option<Arc<dyn LanguageModel>>,
+    fast_model: Option<Arc<dyn LanguageModel>>,
+) -> Option<Arc<dyn LanguageModel>> {
+    let preferred_provider = default_model.as_ref().map(|model| model.provider_id());
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref())
+        .filter(|hint| !hint.is_empty());
+    for hint in hints {
+        let hint = hint.to_lowercase();
+        let matching_models = available_models
+            .iter()
+            .filter(|model| {
+                model.id().0.to_lowercase().contains(&hint)
+                    || model.name().0.to_lowercase().contains(&hint)
+            })
+            .collect::<Vec<_>>();
+        let model = matching_models
+            .iter()
+            .find(|model| Some(model.provider_id()) == preferred_provider)
+            .or(matching_models.first());
+        if let Some(model) = model {
+            return Some((*model).clone());
+        }
+    }
+
+    let priority = |priority: fn(&ModelPreferences) -> Option<f64>| {
+        preferences.and_then(priority).unwrap_or(0.)
+    };
+    let intelligence = priority(|preferences| preferences.intelligence_priority);
+    let speed = priority(|preferences| preferences.speed_priority);
+    let cost = priority(|preferences| preferences.cost_priority);
+    if speed.max(cost) > intelligence {
+        fast_model.or(default_model)
+    } else {
+        default_model.or(fast_model)
+    }
+}
+
+fn completion_request(request: &CreateMessageRequest) -> Result<LanguageModelRequest> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = request
+        .system_prompt
+        .as_ref()
+        .filter(|prompt| !prompt.is_empty())
+    {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    for message in &request.messages {
+        let text = match &message.content {
+            types::MessageContent::Text { text, .. } => text.clone(),
+            types::MessageContent::Resource { resource, .. } => {
+                format!("Resource: {}", resource.uri)
+            }
+            types::MessageContent::Image { .. } | types::MessageContent::Audio { .. } => {
+                bail!("only text sampling messages are supported")
+            }
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                types::Role::User => Role::User,
+                types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![text.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+
+    Ok(LanguageModelRequest {
+        thread_id: None,
+        prompt_id: None,
+        intent: None,
+        messages,
+        tools: Vec::new(),
+        tool_choice: None,
+        stop: request.stop_sequences.clone().unwrap_or_default(),
+        temperature: request.temperature.map(|temperature| temperature as f32),
+        thinking_allowed: false,
+        thinking_effort: None,
+        speed: None,
+    })
+}
+
+/// The latest message of the request, so that the user knows what they approve
+fn approval_detail(request: &CreateMessageRequest) -> String {
+    let latest_text = request
+        .messages
+        .iter()
+        .rev()
+        .find_map(|message| match &message.content {
+            types::MessageContent::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let mut detail = util::truncate_and_trailoff(latest_text, PREVIEW_LEN);
+    if let Some(system_prompt) = &request.system_prompt {
+        detail.push_str("\n\nSystem prompt: ");
+        detail.push_str(&util::truncate_and_trailoff(system_prompt, PREVIEW_LEN));
+    }
+    detail
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use context_server::types::{ModelHint, SamplingMessage};
+    use language_model::fake_provider::FakeLanguageModel;
+
+    fn model(provider: &str, id: &str, name: &str) -> Arc<dyn LanguageModel> {
+        Arc::new(FakeLanguageModel::with_id_and_thinking(
+            provider, id, name, false,
+        ))
+    }
+
+    fn preferences(hints: &[&str], speed: f64, intelligence: f64) -> ModelPreferences {
+        ModelPreferences {
+            hints: Some(
+                hints
+                    .iter()
+                    .map(|hint| ModelHint {
+                        name: Some(hint.to_string()),
+                    })
+                    .collect(),
+            ),
+            cost_priority: None,
+            speed_priority: Some(speed),
+            intelligence_priority: Some(intelligence),
+        }
+    }
+
+    #[test]
+    fn test_select_model() {
+        let sonnet = model("anthropic", "claude-sonnet-4", "Claude Sonnet 4");
+        let haiku = model("anthropic", "claude-haiku-4", "Claude Haiku 4");
+        let sonnet_elsewhere = model("openrouter", "anthropic/claude-sonnet-4", "Sonnet");
+        let gpt = model("openai", "gpt-5", "GPT-5");
+        let available = vec![
+            sonnet_elsewhere.clone(),
+            sonnet.clone(),
+            haiku.clone(),
+            gpt.clone(),
+        ];
+        let select = |preferences: Option<&ModelPreferences>| {
+            select_model(
+                preferences,
+                &available,
+                Some(sonnet.clone()),
+                Some(haiku.clone()),
+            )
+            .map(|model| model.id().0.to_string())
+        };
+
+        assert_eq!(select(None).as_deref(), Some("claude-sonnet-4"));
+        // Hints match case-insensitively, in order, and prefer the default provider
+        assert_eq!(
+            select(Some(&preferences(&["gemini", "SONNET"], 0., 1.))).as_deref(),
+            Some("claude-sonnet-4")
+        );
+        assert_eq!(
+            select(Some(&preferences(&["gpt"], 1., 0.))).as_deref(),
+            Some("gpt-5")
+        );
+        // Without a matching hint, priorities choose between the default and fast models
+        assert_eq!(
+            select(Some(&preferences(&["gemini"], 0.8, 0.2))).as_deref(),
+            Some("claude-haiku-4")
+        );
+        assert_eq!(
+            select(Some(&preferences(&[], 0.2, 0.8))).as_deref(),
+            Some("claude-sonnet-4")
+        );
+        assert!(select_model(None, &available, None, None).is_none());
+    }
+
+    #[test]
+    fn test_completion_request() {
+        let request = CreateMessageRequest {
+            messages: vec![
+                SamplingMessage {
+                    role: types::Role::User,
+                    content: types::MessageContent::Text {
+                        text: "Summarize the issue".into(),
+                        annotations: None,
+                    },
+                },
+                SamplingMessage {
+                    role: types::Role::Assistant,
+                    content: types::MessageContent::Text {
+                        text: "Which issue?".into(),
+                        annotations: None,
+                    },
+                },
+            ],
+            model_preferences: None,
+            system_prompt: Some("Be brief".into()),
+            include_context: None,
+            temperature: Some(0.5),
+            max_tokens: 100,
+            stop_sequences: Some(vec!["END".into()]),
+            metadata: None,
+        };
+        let completion = completion_request(&request).unwrap();
+        assert_eq!(
+            completion
+                .messages
+                .iter()
+                .map(|message| (message.role, message.string_contents()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Role::System, "Be brief".to_string()),
+                (Role::User, "Summarize the issue".to_string()),
+                (Role::Assistant, "Which issue?".to_string()),
+            ]
+        );
+        assert_eq!(completion.temperature, Some(0.5));
+        assert_eq!(completion.stop, vec!["END".to_string()]);
+        assert_eq!(
+            approval_detail(&request),
+            "Which issue?\n\nSystem prompt: Be brief"
+        );
+    }
+
+    #[test]
+    fn test_collect_response() {
+        let collect = |events: Vec<LanguageModelCompletionEvent>, max_tokens| {
+            futures::executor::block_on(collect_response(
+                futures::stream::iter(events.into_iter().map(Ok)),
+                max_tokens,
+            ))
+            .unwrap()
+        };
+        let text = |text: &str| LanguageModelCompletionEvent::Text(text.into());
+        let usage = |output_tokens| {
+            LanguageModelCompletionEvent::UsageUpdate(language_model::TokenUsage {
+                output_tokens,
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(
+            collect(
+                vec![
+                    text("Short"),
+                    LanguageModelCompletionEvent::Stop(StopReason::EndTurn)
+                ],
+                10
+            ),
+            ("Short".to_string(), Some(StopReason::EndTurn))
+        );
+
+        // Without usage updates, the tokens are estimated from the text.
+        assert_eq!(
+            collect(vec![text("A response "), text("that goes on")], 2),
+            ("A resp".to_string(), Some(StopReason::MaxTokens))
+        );
+
+        // Usage updates are preferred over the estimate.
+        assert_eq!(
+            collect(
+                vec![
+                    text("A response "),
+                    usage(1),
+                    text("that goes on"),
+                    usage(5)
+                ],
+                5
+            ),
+            (
+                "A response that goes on".to_string(),
+                Some(StopReason::MaxTokens)
+            )
+        );
+    }
+}
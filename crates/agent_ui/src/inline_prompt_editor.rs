@@ -1,7 +1,7 @@
 use crate::ThreadHistory;
 use agent::ThreadStore;
 use agent_settings::AgentSettings;
-use collections::{HashMap, VecDeque};
+use collections::VecDeque;
 use editor::actions::Paste;
 use editor::code_context_menus::CodeContextMenu;
 use editor::display_map::{CreaseId, EditorMargins};
@@ -378,13 +378,7 @@ impl<T: 'static> PromptEditor<T> {
                 mention_set.creases().len(),
                 "Missing creases"
             );
-
-            let mentions = mention_set
-                .clear()
-                .zip(creases)
-                .map(|((_, value), id)| (id, value))
-                .collect::<HashMap<_, _>>();
-            mention_set.set_mentions(mentions);
+            mention_set.replace_creases(creases);
         });
 
         self.assign_completion_provider(cx);
@@ -440,8 +434,9 @@ impl<T: 'static> PromptEditor<T> {
             EditorEvent::Edited { .. } => {
                 let snapshot = editor.update(cx, |editor, cx| editor.snapshot(window, cx));
 
-                self.mention_set
-                    .update(cx, |mention_set, _cx| mention_set.remove_invalid(&snapshot));
+                self.mention_set.update(cx, |mention_set, cx| {
+                    mention_set.remove_invalid(&snapshot, cx)
+                });
 
                 if let Some(workspace) = Workspace::for_window(window, cx) {
                     workspace.update(cx, |workspace, cx| {
@@ -5,6 +5,10 @@ use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_slash_commands::{codeblock_fence_for_path, collect_diagnostics_output};
 use collections::{HashMap, HashSet};
+use context_server::{
+    ContextServerId, client::NotificationSubscription, protocol::InitializedContextServerProtocol,
+    types,
+};
 use editor::{
     Anchor, Editor, EditorSnapshot, ExcerptId, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -13,7 +17,7 @@ use editor::{
 use futures::{AsyncReadExt as _, FutureExt as _, future::Shared};
 use gpui::{
     AppContext, ClipboardEntry, Context, Empty, Entity, EntityId, Image, ImageFormat, Img,
-    SharedString, Task, WeakEntity,
+    SharedString, Subscription, Task, WeakEntity,
 };
 use http_client::{AsyncBody, HttpClientWithUrl};
 use itertools::Either;
@@ -21,7 +25,10 @@ use language::Buffer;
 use language_model::LanguageModelImage;
 use multi_buffer::MultiBufferRow;
 use postage::stream::Stream as _;
-use project::{Project, ProjectItem, ProjectPath, Worktree};
+use project::{
+    Project, ProjectItem, ProjectPath, Worktree,
+    context_server_store::{ContextServerStatus, ServerStatusChangedEvent},
+};
 use prompt_store::{PromptId, PromptStore};
 use rope::Point;
 use std::{
@@ -63,6 +70,18 @@ pub struct MentionSet {
     thread_store: Option<Entity<ThreadStore>>,
     prompt_store: Option<Entity<PromptStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
+    /// The mentioned resources of each context server that we're subscribed to.
+    resource_subscriptions: HashMap<ContextServerId, ResourceSubscriptions>,
+}
+
+struct ResourceSubscriptions {
+    uris: HashSet<String>,
+    client: Arc<InitializedContextServerProtocol>,
+    /// Listens for `notifications/resources/updated`.
+    _notifications: NotificationSubscription,
+    /// Forgets the subscriptions once the server stops, as a restarted server
+    /// doesn't know about them anymore.
+    _server_status: Subscription,
 }
 
 impl MentionSet {
@@ -76,6 +95,7 @@ impl MentionSet {
             thread_store,
             prompt_store,
             mentions: HashMap::default(),
+            resource_subscriptions: HashMap::default(),
         }
     }
 
@@ -106,12 +126,13 @@ impl MentionSet {
         })
     }
 
-    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot) {
+    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot, cx: &App) {
         for (crease_id, crease) in snapshot.crease_snapshot.creases() {
             if !crease.range().start.is_valid(snapshot.buffer_snapshot()) {
                 self.mentions.remove(&crease_id);
             }
         }
+        self.unsubscribe_from_unmentioned_resources(cx);
     }
 
     pub fn insert_mention(&mut self, crease_id: CreaseId, uri: MentionUri, task: MentionTask) {
@@ -150,6 +171,9 @@ impl MentionSet {
             MentionUri::GitDiff { base_ref } => {
                 self.confirm_mention_for_git_diff(base_ref.into(), cx)
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(server_id, uri, cx)
+            }
             MentionUri::PastedImage
             | MentionUri::Selection { .. }
             | MentionUri::TerminalSelection { .. }
@@ -159,8 +183,9 @@ impl MentionSet {
         }
     }
 
-    pub fn remove_mention(&mut self, crease_id: &CreaseId) {
+    pub fn remove_mention(&mut self, crease_id: &CreaseId, cx: &App) {
         self.mentions.remove(crease_id);
+        self.unsubscribe_from_unmentioned_resources(cx);
     }
 
     pub fn creases(&self) -> HashSet<CreaseId> {
@@ -171,12 +196,19 @@ impl MentionSet {
         self.mentions.values().map(|(uri, _)| uri.clone()).collect()
     }
 
-    pub fn set_mentions(&mut self, mentions: HashMap<CreaseId, (MentionUri, MentionTask)>) {
-        self.mentions = mentions;
+    /// Moves the mentions over to the given creases, e.g. after the editor was recreated.
+    pub fn replace_creases(&mut self, creases: impl IntoIterator<Item = CreaseId>) {
+        self.mentions = self
+            .mentions
+            .drain()
+            .zip(creases)
+            .map(|((_, value), id)| (id, value))
+            .collect();
     }
 
-    pub fn clear(&mut self) -> impl Iterator<Item = (CreaseId, (MentionUri, MentionTask))> {
-        self.mentions.drain()
+    pub fn clear(&mut self, cx: &App) {
+        self.mentions.clear();
+        self.unsubscribe_from_unmentioned_resources(cx);
     }
 
     #[cfg(test)]
@@ -307,6 +339,9 @@ impl MentionSet {
                 debug_panic!("unexpected merge conflict URI");
                 Task::ready(Err(anyhow!("unexpected merge conflict URI")))
             }
+            MentionUri::McpResource { server_id, uri, .. } => {
+                self.confirm_mention_for_mcp_resource(server_id, uri, cx)
+            }
         };
         let task = cx
             .spawn(async move |_, _| task.await.map_err(|e| e.to_string()))
@@ -406,6 +441,206 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_mcp_resource(
+        &mut self,
+        server_id: String,
+        uri: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let server_id = ContextServerId(server_id.into());
+        let Some(client) = self.context_server_client(&server_id, cx) else {
+            return Task::ready(Err(anyhow!("MCP server {server_id} is not running")));
+        };
+        self.subscribe_to_resource(&server_id, &uri, &client, cx);
+        cx.background_spawn(read_mcp_resource(client, uri))
+    }
+
+    fn context_server_client(
+        &self,
+        server_id: &ContextServerId,
+        cx: &App,
+    ) -> Option<Arc<InitializedContextServerProtocol>> {
+        self.project
+            .upgrade()?
+            .read(cx)
+            .context_server_store()
+            .read(cx)
+            .get_running_server(server_id)?
+            .client()
+    }
+
+    fn subscribe_to_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        client: &Arc<InitializedContextServerProtocol>,
+        cx: &mut Context<Self>,
+    ) {
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscriptions {
+            return;
+        }
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+
+        if !self.resource_subscriptions.contains_key(server_id) {
+            let this = cx.entity().downgrade();
+            let notifications = client.on_notification(
+                "notifications/resources/updated",
+                Box::new({
+                    let server_id = server_id.clone();
+                    move |params, cx: gpui::AsyncApp| {
+                        let Some(params) =
+                            serde_json::from_value::<types::ResourcesUpdatedParams>(params)
+                                .log_err()
+                        else {
+                            return;
+                        };
+                        let server_id = server_id.clone();
+                        let this = this.clone();
+                        cx.spawn(async move |cx| {
+                            this.update(cx, |this, cx| {
+                                this.refresh_mcp_resource(&server_id, &params.uri, cx)
+                            })
+                        })
+                        .detach();
+                    }
+                }),
+            );
+            let context_server_store = project.read(cx).context_server_store();
+            let server_status = cx.subscribe(&context_server_store, {
+                let server_id = server_id.clone();
+                move |this, _, event: &ServerStatusChangedEvent, _| {
+                    if event.server_id == server_id && event.status != ContextServerStatus::Running
+                    {
+                        this.resource_subscriptions.remove(&server_id);
+                    }
+                }
+            });
+            self.resource_subscriptions.insert(
+                server_id.clone(),
+                ResourceSubscriptions {
+                    uris: HashSet::default(),
+                    client: client.clone(),
+                    _notifications: notifications,
+                    _server_status: server_status,
+                },
+            );
+        }
+
+        let Some(subscriptions) = self.resource_subscriptions.get_mut(server_id) else {
+            return;
+        };
+        if !subscriptions.uris.insert(uri.to_string()) {
+            return;
+        }
+        let Ok(uri) = url::Url::parse(uri) else {
+            return;
+        };
+        let client = subscriptions.client.clone();
+        cx.background_spawn(async move {
+            client
+                .request::<types::requests::ResourcesSubscribe>(types::ResourcesSubscribeParams {
+                    uri,
+                    meta: None,
+                })
+                .await
+                .log_err();
+        })
+        .detach();
+    }
+
+    /// Unsubscribes from the resources whose mentions were all removed.
+    fn unsubscribe_from_unmentioned_resources(&mut self, cx: &App) {
+        let mentioned = self
+            .mentions
+            .values()
+            .filter_map(|(mention_uri, _)| match mention_uri {
+                MentionUri::McpResource { server_id, uri, .. } => {
+                    Some((server_id.as_str(), uri.as_str()))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        self.resource_subscriptions
+            .retain(|server_id, subscriptions| {
+                let mut unmentioned = Vec::new();
+                subscriptions.uris.retain(|uri| {
+                    let is_mentioned = mentioned.contains(&(server_id.0.as_ref(), uri.as_str()));
+                    if !is_mentioned {
+                        unmentioned.push(uri.clone());
+                    }
+                    is_mentioned
+                });
+
+                for uri in unmentioned {
+                    let Ok(uri) = url::Url::parse(&uri) else {
+                        continue;
+                    };
+                    let client = subscriptions.client.clone();
+                    cx.background_spawn(async move {
+                        client
+                            .request::<types::requests::ResourcesUnsubscribe>(
+                                types::ResourcesUnsubscribeParams { uri, meta: None },
+                            )
+                            .await
+                            .log_err();
+                    })
+                    .detach();
+                }
+                !subscriptions.uris.is_empty()
+            });
+    }
+
+    /// Re-reads a subscribed resource after its server reported a change, so
+    /// that the next message includes its current contents.
+    fn refresh_mcp_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let crease_ids = self
+            .mentions
+            .iter()
+            .filter(|(_, (mention_uri, _))| {
+                matches!(
+                    mention_uri,
+                    MentionUri::McpResource {
+                        server_id: mention_server_id,
+                        uri: resource_uri,
+                        ..
+                    } if mention_server_id.as_str() == server_id.0.as_ref() && resource_uri == uri
+                )
+            })
+            .map(|(crease_id, _)| *crease_id)
+            .collect::<Vec<_>>();
+        if crease_ids.is_empty() {
+            return;
+        }
+        let Some(client) = self.context_server_client(server_id, cx) else {
+            return;
+        };
+
+        let read = cx.background_spawn(read_mcp_resource(client, uri.to_string()));
+        let task = cx
+            .spawn(async move |_, _| read.await.map_err(|e| e.to_string()))
+            .shared();
+        for crease_id in crease_ids {
+            if let Some((_, mention_task)) = self.mentions.get_mut(&crease_id) {
+                *mention_task = task.clone();
+            }
+        }
+    }
+
     fn confirm_mention_for_symbol(
         &self,
         abs_path: PathBuf,
@@ -645,6 +880,7 @@ impl MentionSet {
 mod tests {
     use super::*;
 
+    use context_server::{ContextServer, test::FakeTransport};
     use fs::FakeFs;
     use gpui::TestAppContext;
     use project::Project;
@@ -653,7 +889,7 @@ mod tests {
     use semver::Version;
     use serde_json::json;
     use settings::SettingsStore;
-    use std::path::Path;
+    use std::{path::Path, sync::Mutex};
     use theme;
     use util::path;
 
@@ -689,6 +925,124 @@ mod tests {
             "Unexpected error: {error:#}"
         );
     }
+
+    #[gpui::test]
+    async fn test_mcp_resource_subscriptions(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/project", json!({"file": ""})).await;
+        let project = Project::test(fs, [Path::new(path!("/project"))], cx).await;
+        let context_server_store =
+            project.read_with(cx, |project, _| project.context_server_store());
+        let mention_set = cx.new(|_cx| MentionSet::new(project.downgrade(), None, None));
+
+        let server_id = ContextServerId("docs".into());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let start_server = |cx: &mut TestAppContext| {
+            let server = fake_resource_server(&server_id, requests.clone(), cx);
+            context_server_store.update(cx, |store, cx| store.test_start_server(server, cx));
+            cx.run_until_parked();
+        };
+        let mention_resource = |cx: &mut TestAppContext| {
+            mention_set.update(cx, |mention_set, cx| {
+                // The resource isn't read in this test
+                drop(mention_set.confirm_mention_for_mcp_resource(
+                    "docs".into(),
+                    "file:///guide.md".into(),
+                    cx,
+                ));
+                mention_set.insert_mention(
+                    CreaseId::default(),
+                    MentionUri::McpResource {
+                        server_id: "docs".into(),
+                        uri: "file:///guide.md".into(),
+                        name: "guide.md".into(),
+                    },
+                    Task::ready(Ok(Mention::Link)).shared(),
+                );
+            });
+            cx.run_until_parked();
+        };
+
+        start_server(cx);
+        mention_resource(cx);
+        mention_resource(cx);
+        assert_eq!(
+            requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec!["subscribe file:///guide.md"]
+        );
+
+        mention_set.update(cx, |mention_set, cx| {
+            mention_set.remove_mention(&CreaseId::default(), cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec!["unsubscribe file:///guide.md"]
+        );
+
+        // A restarted server doesn't know about previous subscriptions
+        mention_resource(cx);
+        start_server(cx);
+        mention_resource(cx);
+        assert_eq!(
+            requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec!["subscribe file:///guide.md", "subscribe file:///guide.md"]
+        );
+
+        mention_set.update(cx, |mention_set, cx| mention_set.clear(cx));
+        cx.run_until_parked();
+        assert_eq!(
+            requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec!["unsubscribe file:///guide.md"]
+        );
+    }
+
+    fn fake_resource_server(
+        server_id: &ContextServerId,
+        requests: Arc<Mutex<Vec<String>>>,
+        cx: &TestAppContext,
+    ) -> Arc<ContextServer> {
+        let transport = FakeTransport::new(cx.executor())
+            .on_request::<types::requests::Initialize, _>(|_| async {
+                types::InitializeResponse {
+                    protocol_version: types::ProtocolVersion(
+                        types::LATEST_PROTOCOL_VERSION.to_string(),
+                    ),
+                    capabilities: types::ServerCapabilities {
+                        resources: Some(types::ResourcesCapabilities {
+                            subscribe: Some(true),
+                            list_changed: None,
+                        }),
+                        ..Default::default()
+                    },
+                    server_info: types::Implementation {
+                        name: "docs".into(),
+                        version: "1.0.0".into(),
+                    },
+                    meta: None,
+                }
+            })
+            .on_request::<types::requests::ResourcesSubscribe, _>({
+                let requests = requests.clone();
+                move |params| {
+                    requests
+                        .lock()
+                        .unwrap()
+                        .push(format!("subscribe {}", params.uri));
+                    async {}
+                }
+            })
+            .on_request::<types::requests::ResourcesUnsubscribe, _>(move |params| {
+                requests
+                    .lock()
+                    .unwrap()
+                    .push(format!("unsubscribe {}", params.uri));
+                async {}
+            });
+        Arc::new(ContextServer::new(server_id.clone(), Arc::new(transport)))
+    }
 }
 
 /// Inserts a list of images into the editor as context mentions.
@@ -784,7 +1138,7 @@ pub(crate) async fn insert_images_as_context(
                 editor.edit([(start_anchor..end_anchor, "")], cx);
             });
             mention_set.update(cx, |mention_set, _cx| {
-                mention_set.remove_mention(&crease_id)
+                mention_set.remove_mention(&crease_id, cx)
             });
         }
     }
@@ -1262,3 +1616,40 @@ async fn fetch_url_content(http_client: Arc<HttpClientWithUrl>, url: String) ->
         }
     }
 }
+
+async fn read_mcp_resource(
+    client: Arc<InitializedContextServerProtocol>,
+    uri: String,
+) -> Result<Mention> {
+    let response = client
+        .request::<types::requests::ResourcesRead>(types::ResourcesReadParams {
+            uri: url::Url::parse(&uri).context("invalid resource URI")?,
+            meta: None,
+        })
+        .await?;
+
+    let mut content = String::new();
+    for contents in response.contents {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        match contents {
+            types::ResourceContentsType::Text(text) => content.push_str(&text.text),
+            // Binary contents can't be inlined as text, so only describe them.
+            types::ResourceContentsType::Blob(blob) => {
+                write!(
+                    content,
+                    "[{} ({} bytes, base64-encoded)]",
+                    blob.mime_type.as_deref().unwrap_or("binary data"),
+                    blob.blob.len()
+                )
+                .ok();
+            }
+        }
+    }
+
+    Ok(Mention::Text {
+        content,
+        tracked_buffers: Vec::new(),
+    })
+}
@@ -0,0 +1,360 @@
+//! Incremental expansion of MCP resource templates (RFC 6570 URI templates).
+//!
+//! The `@resource` mention lets users type a concrete URI for a template one
+//! variable at a time. [`UriTemplate::match_prefix`] tells the completion
+//! provider where the typed text is within the template, so it can ask the
+//! server for values of the variable being typed.
+
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Variable { name: String, reserved: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UriTemplate {
+    parts: Vec<Part>,
+}
+
+/// Where some typed text stops within a [`UriTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplateMatch {
+    /// The text is a complete expansion of the template.
+    Complete,
+    /// The text stops while the value of a variable is being typed.
+    Variable {
+        name: String,
+        value: String,
+        /// Byte offset of the value within the typed text.
+        value_start: usize,
+        part_ix: usize,
+    },
+    /// The text stops within the literal that starts at `literal_start`.
+    Literal {
+        literal_start: usize,
+        part_ix: usize,
+    },
+}
+
+impl UriTemplate {
+    /// Parses a template, keeping only the expressions that can be typed
+    /// positionally. Optional query expressions (`{?x}`, `{&x}`) are dropped.
+    pub fn parse(template: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            push_literal(&mut parts, &rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                push_literal(&mut parts, &rest[open..]);
+                rest = "";
+                break;
+            };
+            let expression = &rest[open + 1..open + close];
+            rest = &rest[open + close + 1..];
+
+            let (operator, variables) = match expression.chars().next() {
+                Some(c @ ('+' | '#' | '/' | '.' | ';' | '?' | '&')) => (Some(c), &expression[1..]),
+                _ => (None, expression),
+            };
+            if matches!(operator, Some('?' | '&')) {
+                continue;
+            }
+
+            let (separator, reserved) = match operator {
+                Some('+') => (",", true),
+                Some('#') => (",", true),
+                Some('/') => ("/", false),
+                Some('.') => (".", false),
+                Some(';') => (";", false),
+                _ => (",", false),
+            };
+            for (ix, variable) in variables.split(',').enumerate() {
+                // Prefix (`{var:3}`) and explode (`{var*}`) modifiers don't
+                // change how a single typed value looks.
+                let name = variable
+                    .split(':')
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches('*')
+                    .to_string();
+                if name.is_empty() {
+                    continue;
+                }
+                let prefix = match operator {
+                    Some('#') if ix == 0 => "#".to_string(),
+                    Some('/' | '.') if ix == 0 => separator.to_string(),
+                    Some(';') => format!(";{name}="),
+                    _ if ix == 0 => String::new(),
+                    _ => separator.to_string(),
+                };
+                push_literal(&mut parts, &prefix);
+                parts.push(Part::Variable { name, reserved });
+            }
+        }
+        push_literal(&mut parts, rest);
+        Self { parts }
+    }
+
+    pub fn has_variables(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Variable { .. }))
+    }
+
+    /// The text of the template up to its first variable.
+    pub fn literal_prefix(&self) -> String {
+        self.advance(String::new(), 0).0
+    }
+
+    /// Appends the literals starting at `part_ix` to `text`, stopping at the
+    /// next variable. Also returns whether the end of the template was reached.
+    pub fn advance(&self, mut text: String, part_ix: usize) -> (String, bool) {
+        for part in &self.parts[part_ix.min(self.parts.len())..] {
+            match part {
+                Part::Literal(literal) => text.push_str(literal),
+                Part::Variable { .. } => return (text, false),
+            }
+        }
+        (text, true)
+    }
+
+    /// Replaces the value being typed for the variable at `part_ix` and moves
+    /// on to the next variable.
+    pub fn fill_variable(
+        &self,
+        typed: &str,
+        value_start: usize,
+        part_ix: usize,
+        value: &str,
+    ) -> (String, bool) {
+        let mut text = typed[..value_start].to_string();
+        match self.parts.get(part_ix) {
+            Some(Part::Variable { reserved: true, .. }) => text.push_str(value),
+            _ => encode_unreserved(&mut text, value),
+        }
+        self.advance(text, part_ix + 1)
+    }
+
+    /// Determines where `typed` stops within the template, or returns `None`
+    /// if it can't be an expansion of it.
+    pub fn match_prefix(&self, typed: &str) -> Option<TemplateMatch> {
+        let mut offset = 0;
+        for (part_ix, part) in self.parts.iter().enumerate() {
+            let rest = &typed[offset..];
+            match part {
+                Part::Literal(literal) => {
+                    if let Some(after) = rest.strip_prefix(literal.as_str()) {
+                        offset = typed.len() - after.len();
+                    } else if literal.starts_with(rest) {
+                        return Some(TemplateMatch::Literal {
+                            literal_start: offset,
+                            part_ix,
+                        });
+                    } else {
+                        return None;
+                    }
+                }
+                Part::Variable { name, reserved } => {
+                    let next_literal = match self.parts.get(part_ix + 1) {
+                        Some(Part::Literal(literal)) => Some(literal.as_str()),
+                        _ => None,
+                    };
+                    let mut value_len = if *reserved {
+                        rest.len()
+                    } else {
+                        rest.find(|c: char| !is_unreserved(c) && c != '%')
+                            .unwrap_or(rest.len())
+                    };
+                    if let Some(next_literal) = next_literal
+                        && let Some(ix) = rest[..value_len].find(next_literal)
+                    {
+                        value_len = ix;
+                    }
+
+                    // A value that runs to the end of the typed text may still
+                    // be growing, even when it's the last part of the template.
+                    if value_len == rest.len() {
+                        return Some(TemplateMatch::Variable {
+                            name: name.clone(),
+                            value: rest.to_string(),
+                            value_start: offset,
+                            part_ix,
+                        });
+                    }
+                    offset += value_len;
+                }
+            }
+        }
+
+        (offset == typed.len()).then_some(TemplateMatch::Complete)
+    }
+
+    /// Whether only literals follow the part at `part_ix`, so that filling it
+    /// in completes the template.
+    pub fn is_last_variable(&self, part_ix: usize) -> bool {
+        self.advance(String::new(), part_ix + 1).1
+    }
+}
+
+fn push_literal(parts: &mut Vec<Part>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(Part::Literal(literal)) = parts.last_mut() {
+        literal.push_str(text);
+    } else {
+        parts.push(Part::Literal(text.to_string()));
+    }
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn encode_unreserved(text: &mut String, value: &str) {
+    for c in value.chars() {
+        if is_unreserved(c) {
+            text.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                write!(text, "%{byte:02X}").ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let template = UriTemplate::parse("github://repos/{owner}/{repo}/issues{/number}{?state}");
+        assert_eq!(
+            template.parts,
+            vec![
+                Part::Literal("github://repos/".into()),
+                Part::Variable {
+                    name: "owner".into(),
+                    reserved: false
+                },
+                Part::Literal("/".into()),
+                Part::Variable {
+                    name: "repo".into(),
+                    reserved: false
+                },
+                Part::Literal("/issues/".into()),
+                Part::Variable {
+                    name: "number".into(),
+                    reserved: false
+                },
+            ]
+        );
+        assert_eq!(template.literal_prefix(), "github://repos/");
+        assert!(template.has_variables());
+        assert!(!UriTemplate::parse("config://app").has_variables());
+    }
+
+    #[test]
+    fn test_match_prefix() {
+        let template = UriTemplate::parse("github://repos/{owner}/{repo}/readme");
+
+        assert_eq!(template.match_prefix("gitlab://"), None);
+        assert_eq!(
+            template.match_prefix("git"),
+            Some(TemplateMatch::Literal {
+                literal_start: 0,
+                part_ix: 0
+            })
+        );
+        assert_eq!(
+            template.match_prefix("github://repos/zed-ind"),
+            Some(TemplateMatch::Variable {
+                name: "owner".into(),
+                value: "zed-ind".into(),
+                value_start: 15,
+                part_ix: 1,
+            })
+        );
+        assert_eq!(
+            template.match_prefix("github://repos/zed-industries/"),
+            Some(TemplateMatch::Variable {
+                name: "repo".into(),
+                value: "".into(),
+                value_start: 30,
+                part_ix: 3,
+            })
+        );
+        assert_eq!(
+            template.match_prefix("github://repos/zed-industries/zed/rea"),
+            Some(TemplateMatch::Literal {
+                literal_start: 33,
+                part_ix: 4
+            })
+        );
+        assert_eq!(
+            template.match_prefix("github://repos/zed-industries/zed/readme"),
+            Some(TemplateMatch::Complete)
+        );
+        assert_eq!(
+            template.match_prefix("github://repos/zed-industries/zed/readme/extra"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_match_trailing_variable() {
+        let template = UriTemplate::parse("file:///{+path}");
+        assert_eq!(
+            template.match_prefix("file:///src/main"),
+            Some(TemplateMatch::Variable {
+                name: "path".into(),
+                value: "src/main".into(),
+                value_start: 8,
+                part_ix: 1,
+            })
+        );
+        assert!(template.is_last_variable(1));
+    }
+
+    #[test]
+    fn test_fill_variable() {
+        let template = UriTemplate::parse("github://repos/{owner}/{repo}/readme");
+        let typed = "github://repos/zed";
+        let Some(TemplateMatch::Variable {
+            value_start,
+            part_ix,
+            ..
+        }) = template.match_prefix(typed)
+        else {
+            panic!("expected a variable match");
+        };
+        assert_eq!(
+            template.fill_variable(typed, value_start, part_ix, "zed industries"),
+            ("github://repos/zed%20industries/".to_string(), false)
+        );
+
+        let typed = "github://repos/zed-industries/";
+        let Some(TemplateMatch::Variable {
+            value_start,
+            part_ix,
+            ..
+        }) = template.match_prefix(typed)
+        else {
+            panic!("expected a variable match");
+        };
+        assert_eq!(
+            template.fill_variable(typed, value_start, part_ix, "zed"),
+            ("github://repos/zed-industries/zed/readme".to_string(), true)
+        );
+
+        let template = UriTemplate::parse("file:///{+path}");
+        assert_eq!(
+            template.fill_variable("file:///", 8, 1, "src/main.rs"),
+            ("file:///src/main.rs".to_string(), true)
+        );
+    }
+}
@@ -2101,6 +2101,7 @@ mod tests {
             initial_project_snapshot: None,
             cumulative_token_usage: Default::default(),
             request_token_usage: Default::default(),
+            budget: Default::default(),
             model: None,
             profile: None,
             imported: false,
@@ -0,0 +1,325 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window, actions,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::bookmark_store::{BookmarkEntry, BookmarkStore};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    bookmarks,
+    [
+        /// Opens a picker with all of the project's bookmarks.
+        Toggle,
+        /// Removes the selected bookmark in the bookmarks picker.
+        RemoveSelected
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _window: Option<&mut Window>, _: &mut Context<Workspace>) {
+    workspace.register_action(toggle);
+}
+
+fn toggle(workspace: &mut Workspace, _: &Toggle, window: &mut Window, cx: &mut Context<Workspace>) {
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, move |window, cx| {
+        BookmarkPicker::new(bookmark_store, workspace_handle, window, cx)
+    });
+}
+
+pub struct BookmarkPicker {
+    picker: Entity<Picker<BookmarkPickerDelegate>>,
+}
+
+impl BookmarkPicker {
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate =
+            BookmarkPickerDelegate::new(bookmark_store, workspace, cx.entity().downgrade(), cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+
+    fn remove_selected(&mut self, _: &RemoveSelected, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let ix = picker.delegate.selected_index;
+            picker.delegate.remove_bookmark_at(ix, window, cx);
+        });
+    }
+}
+
+impl ModalView for BookmarkPicker {}
+
+impl EventEmitter<DismissEvent> for BookmarkPicker {}
+
+impl Focusable for BookmarkPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for BookmarkPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarkPicker")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::remove_selected))
+            .child(self.picker.clone())
+    }
+}
+
+struct BookmarkCandidate {
+    entry: BookmarkEntry,
+    label: String,
+    location: String,
+}
+
+pub struct BookmarkPickerDelegate {
+    bookmark_store: Entity<BookmarkStore>,
+    workspace: WeakEntity<Workspace>,
+    bookmark_picker: WeakEntity<BookmarkPicker>,
+    candidates: Vec<BookmarkCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+}
+
+impl BookmarkPickerDelegate {
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        workspace: WeakEntity<Workspace>,
+        bookmark_picker: WeakEntity<BookmarkPicker>,
+        cx: &App,
+    ) -> Self {
+        let mut this = Self {
+            bookmark_store,
+            workspace,
+            bookmark_picker,
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+        };
+        this.load_candidates(cx);
+        this
+    }
+
+    fn load_candidates(&mut self, cx: &App) {
+        self.candidates = self
+            .bookmark_store
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| {
+                let buffer = entry.buffer.read(cx);
+                let path = buffer
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().into_owned())
+                    .unwrap_or_else(|| entry.abs_path.to_string_lossy().into_owned());
+                let line = buffer
+                    .text_for_range(
+                        Point::new(entry.row, 0)..Point::new(entry.row, buffer.line_len(entry.row)),
+                    )
+                    .collect::<String>();
+                let label = match &entry.bookmark.name {
+                    Some(name) => format!("{name}: {}", line.trim()),
+                    None => line.trim().to_string(),
+                };
+                BookmarkCandidate {
+                    location: format!("{path}:{}", entry.row + 1),
+                    label,
+                    entry,
+                }
+            })
+            .collect();
+    }
+
+    fn remove_bookmark_at(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        let entry = self.candidates[mat.candidate_id].entry.clone();
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.remove_bookmark(&entry.abs_path, entry.bookmark.position, cx);
+        });
+        self.load_candidates(cx);
+        let query = self.query.clone();
+        self.update_matches(query, window, cx).detach();
+    }
+}
+
+impl PickerDelegate for BookmarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        "Search bookmarks...".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No bookmarks".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = self.candidates[mat.candidate_id].entry.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    editor::open_bookmark(workspace, &entry, window, cx);
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmark_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.query = query.clone();
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| {
+                StringMatchCandidate::new(
+                    id,
+                    &format!("{} {}", candidate.label, candidate.location),
+                )
+            })
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let candidate = self.candidates.get(mat.candidate_id)?;
+
+        // The match string is the label followed by a space and the location.
+        let label_len = candidate.label.len();
+        let label_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|&position| position < label_len)
+            .collect::<Vec<_>>();
+        let location_positions = mat
+            .positions
+            .iter()
+            .filter_map(|&position| position.checked_sub(label_len + 1))
+            .collect::<Vec<_>>();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Bookmark).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .gap_x_2()
+                        .child(HighlightedLabel::new(
+                            candidate.label.clone(),
+                            label_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(candidate.location.clone(), location_positions)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                )
+                .end_hover_slot(
+                    IconButton::new(("remove-bookmark", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Remove Bookmark"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.remove_bookmark_at(ix, window, cx);
+                        })),
+                ),
+        )
+    }
+}
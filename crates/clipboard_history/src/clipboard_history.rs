@@ -0,0 +1,323 @@
+use editor::{ClipboardHistory, ClipboardHistoryEntry, Editor};
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// The number of characters of an entry shown in the picker.
+const MAX_LABEL_LEN: usize = 200;
+
+actions!(
+    clipboard_history,
+    [
+        /// Opens a picker with the recently copied text, to paste an entry into the selections.
+        Toggle,
+        /// Removes the selected entry in the clipboard history picker.
+        RemoveSelected,
+        /// Removes all of the entries in the clipboard history.
+        Clear
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    ClipboardHistory::load(<dyn fs::Fs>::global(cx), cx);
+    cx.observe_new(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _window: Option<&mut Window>, _: &mut Context<Workspace>) {
+    workspace.register_action(toggle);
+    workspace.register_action(|_, _: &Clear, _, cx| ClipboardHistory::clear(cx));
+}
+
+fn toggle(workspace: &mut Workspace, _: &Toggle, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let editor = editor.downgrade();
+    workspace.toggle_modal(window, cx, move |window, cx| {
+        ClipboardHistoryPicker::new(editor, window, cx)
+    });
+}
+
+pub struct ClipboardHistoryPicker {
+    picker: Entity<Picker<ClipboardHistoryPickerDelegate>>,
+}
+
+impl ClipboardHistoryPicker {
+    fn new(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let delegate = ClipboardHistoryPickerDelegate::new(editor, cx.entity().downgrade(), cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+
+    fn remove_selected(&mut self, _: &RemoveSelected, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let ix = picker.delegate.selected_index;
+            picker.delegate.remove_entry_at(ix, window, cx);
+        });
+    }
+}
+
+impl ModalView for ClipboardHistoryPicker {}
+
+impl EventEmitter<DismissEvent> for ClipboardHistoryPicker {}
+
+impl Focusable for ClipboardHistoryPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ClipboardHistoryPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("ClipboardHistoryPicker")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::remove_selected))
+            .child(self.picker.clone())
+    }
+}
+
+struct EntryCandidate {
+    /// The entry's text on a single line.
+    label: String,
+    detail: String,
+}
+
+impl EntryCandidate {
+    fn new(entry: &ClipboardHistoryEntry, now: OffsetDateTime, local_offset: UtcOffset) -> Self {
+        let label = entry
+            .text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(MAX_LABEL_LEN)
+            .collect();
+
+        let mut detail = Vec::new();
+        let selection_count = entry.selections.as_ref().map_or(1, Vec::len);
+        if selection_count > 1 {
+            detail.push(format!("{selection_count} selections"));
+        } else {
+            let line_count = entry.text.lines().count();
+            if line_count > 1 {
+                detail.push(format!("{line_count} lines"));
+            }
+        }
+        if let Some(file_name) = entry
+            .selections
+            .as_ref()
+            .and_then(|selections| selections.first()?.file_path.as_ref()?.file_name())
+        {
+            detail.push(file_name.to_string_lossy().into_owned());
+        }
+        detail.push(time_format::format_localized_timestamp(
+            OffsetDateTime::from(entry.copied_at),
+            now,
+            local_offset,
+            time_format::TimestampFormat::Relative,
+        ));
+
+        Self {
+            label,
+            detail: detail.join(" · "),
+        }
+    }
+}
+
+pub struct ClipboardHistoryPickerDelegate {
+    editor: WeakEntity<Editor>,
+    clipboard_history_picker: WeakEntity<ClipboardHistoryPicker>,
+    candidates: Vec<EntryCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+}
+
+impl ClipboardHistoryPickerDelegate {
+    fn new(
+        editor: WeakEntity<Editor>,
+        clipboard_history_picker: WeakEntity<ClipboardHistoryPicker>,
+        cx: &App,
+    ) -> Self {
+        let mut this = Self {
+            editor,
+            clipboard_history_picker,
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+        };
+        this.load_candidates(cx);
+        this
+    }
+
+    fn load_candidates(&mut self, cx: &App) {
+        let now = OffsetDateTime::now_utc();
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        self.candidates = ClipboardHistory::entries(cx)
+            .map(|entry| EntryCandidate::new(entry, now, local_offset))
+            .collect();
+    }
+
+    fn remove_entry_at(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        ClipboardHistory::remove(mat.candidate_id, cx);
+        self.load_candidates(cx);
+        let query = self.query.clone();
+        self.update_matches(query, window, cx).detach();
+    }
+}
+
+impl PickerDelegate for ClipboardHistoryPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        "Search clipboard history...".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No copied text".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let ix = mat.candidate_id;
+            self.editor
+                .update(cx, |editor, cx| {
+                    if secondary {
+                        editor.diff_clipboard_history_entry_with_selection(ix, window, cx);
+                    } else {
+                        editor.paste_from_clipboard_history(ix, window, cx);
+                    }
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.clipboard_history_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.query = query.clone();
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| StringMatchCandidate::new(id, &candidate.label))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let candidate = self.candidates.get(mat.candidate_id)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Copy).color(Color::Muted))
+                .child(
+                    v_flex()
+                        .child(
+                            HighlightedLabel::new(candidate.label.clone(), mat.positions.clone())
+                                .truncate(),
+                        )
+                        .child(
+                            Label::new(candidate.detail.clone())
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                )
+                .end_hover_slot(
+                    IconButton::new(("remove-entry", ix), IconName::Trash)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Remove from Clipboard History"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.remove_entry_at(ix, window, cx);
+                        })),
+                ),
+        )
+    }
+}
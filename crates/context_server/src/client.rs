@@ -22,7 +22,9 @@ use util::{ResultExt, TryFutureExt};
 
 use crate::{
     transport::{StdioTransport, Transport},
-    types::{CancelledParams, ClientNotification, Notification as _, notifications::Cancelled},
+    types::{
+        self, CancelledParams, ClientNotification, Notification as _, notifications::Cancelled,
+    },
 };
 
 const JSON_RPC_VERSION: &str = "2.0";
@@ -52,6 +54,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -101,7 +104,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -114,7 +116,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -202,12 +203,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -237,6 +240,7 @@ impl Client {
         Ok(Self {
             server_id,
             subscription_set,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -254,12 +258,14 @@ impl Client {
     /// This function continuously reads lines from the provided stdout stream,
     /// parses them as JSON-RPC responses or notifications, and dispatches them
     /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// to pending requests), requests sent by the server (which are answered by
+    /// registered handlers) and notifications (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -274,6 +280,17 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    log::debug!("Unhandled request from context_server: {}", request.method);
+                    send_response::<()>(
+                        &outbound_tx,
+                        request.id,
+                        Err(Error {
+                            message: format!("Method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        }),
+                    )
+                    .log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -452,6 +469,45 @@ impl Client {
         Ok(())
     }
 
+    /// Registers a handler answering requests of type `T` sent by the context server.
+    /// The response is sent once the task returned by the handler completes.
+    pub fn on_request<T: types::Request>(
+        &self,
+        mut f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            T::METHOD,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str::<T::Params>(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        send_response::<T::Response>(
+                            &outbound_tx,
+                            id,
+                            Err(Error {
+                                message: format!("Invalid params: {error}"),
+                                code: INVALID_PARAMS,
+                            }),
+                        )
+                        .log_err();
+                        return;
+                    }
+                };
+                let response = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let response = response.await.map_err(|error| Error {
+                        message: error.to_string(),
+                        code: INTERNAL_ERROR,
+                    });
+                    send_response(&outbound_tx, id, response).log_err();
+                })
+                .detach();
+            }),
+        );
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -467,6 +523,24 @@ impl Client {
     }
 }
 
+/// Answers a request sent by the context server
+fn send_response<T: Serialize>(
+    outbound_tx: &channel::Sender<String>,
+    id: RequestId,
+    response: Result<T, Error>,
+) -> Result<()> {
+    let message = serde_json::to_string(&Response {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value: match response {
+            Ok(result) => CspResult::Ok(Some(result)),
+            Err(error) => CspResult::Error(Some(error)),
+        },
+    })?;
+    outbound_tx.try_send(message)?;
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct RequestCanceled;
 
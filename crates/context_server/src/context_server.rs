@@ -1,5 +1,6 @@
 pub mod client;
 pub mod listener;
+pub mod oauth;
 pub mod protocol;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
@@ -15,11 +16,12 @@ use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
 use client::Client;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use parking_lot::RwLock;
 pub use settings::ContextServerCommand;
 use url::Url;
 
+use crate::oauth::OAuthSession;
 use crate::transport::HttpTransport;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -31,6 +33,24 @@ impl Display for ContextServerId {
     }
 }
 
+/// Answers `sampling/createMessage` requests, through which a server asks the client for an LLM completion
+pub type SamplingHandler = Arc<
+    dyn Send
+        + Sync
+        + Fn(
+            ContextServerId,
+            types::CreateMessageRequest,
+            AsyncApp,
+        ) -> Task<Result<types::CreateMessageResult>>,
+>;
+
+/// Answers `elicitation/create` requests, through which a server asks the user for structured input
+pub type ElicitationHandler = Arc<
+    dyn Send
+        + Sync
+        + Fn(ContextServerId, types::ElicitParams, AsyncApp) -> Task<Result<types::ElicitResult>>,
+>;
+
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
     Custom(Arc<dyn crate::transport::Transport>),
@@ -41,6 +61,8 @@ pub struct ContextServer {
     client: RwLock<Option<Arc<crate::protocol::InitializedContextServerProtocol>>>,
     configuration: ContextServerTransport,
     request_timeout: Option<Duration>,
+    sampling_handler: RwLock<Option<SamplingHandler>>,
+    elicitation_handler: RwLock<Option<ElicitationHandler>>,
 }
 
 impl ContextServer {
@@ -57,6 +79,8 @@ impl ContextServer {
                 working_directory.map(|directory| directory.to_path_buf()),
             ),
             request_timeout: None,
+            sampling_handler: RwLock::new(None),
+            elicitation_handler: RwLock::new(None),
         }
     }
 
@@ -67,12 +91,16 @@ impl ContextServer {
         http_client: Arc<dyn HttpClient>,
         executor: gpui::BackgroundExecutor,
         request_timeout: Option<Duration>,
+        oauth: Option<Arc<OAuthSession>>,
     ) -> Result<Self> {
         let transport = match endpoint.scheme() {
             "http" | "https" => {
                 log::info!("Using HTTP transport for {}", endpoint);
-                let transport =
+                let mut transport =
                     HttpTransport::new(http_client, endpoint.to_string(), headers, executor);
+                if let Some(oauth) = oauth {
+                    transport = transport.with_oauth(oauth);
+                }
                 Arc::new(transport) as _
             }
             _ => anyhow::bail!("unsupported MCP url scheme {}", endpoint.scheme()),
@@ -94,6 +122,8 @@ impl ContextServer {
             client: RwLock::new(None),
             configuration: ContextServerTransport::Custom(transport),
             request_timeout,
+            sampling_handler: RwLock::new(None),
+            elicitation_handler: RwLock::new(None),
         }
     }
 
@@ -105,6 +135,18 @@ impl ContextServer {
         self.client.read().clone()
     }
 
+    /// Sets the handler answering sampling requests. Sampling is only advertised to the server
+    /// when a handler is set before the server is started.
+    pub fn set_sampling_handler(&self, handler: Option<SamplingHandler>) {
+        *self.sampling_handler.write() = handler;
+    }
+
+    /// Sets the handler answering elicitation requests. Like sampling, elicitation is only
+    /// advertised to the server when a handler is set before the server is started.
+    pub fn set_elicitation_handler(&self, handler: Option<ElicitationHandler>) {
+        *self.elicitation_handler.write() = handler;
+    }
+
     pub async fn start(&self, cx: &AsyncApp) -> Result<()> {
         self.initialize(self.new_client(cx)?).await
     }
@@ -134,12 +176,32 @@ impl ContextServer {
 
     async fn initialize(&self, client: Client) -> Result<()> {
         log::debug!("starting context server {}", self.id);
+        let sampling_handler = self.sampling_handler.read().clone();
+        if let Some(handler) = sampling_handler.clone() {
+            let id = self.id();
+            client.on_request::<types::requests::CreateMessage>(move |params, cx| {
+                handler(id.clone(), params, cx)
+            });
+        }
+        let elicitation_handler = self.elicitation_handler.read().clone();
+        if let Some(handler) = elicitation_handler.clone() {
+            let id = self.id();
+            client.on_request::<types::requests::Elicit>(move |params, cx| {
+                handler(id.clone(), params, cx)
+            });
+        }
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let initialized_protocol = protocol.initialize(client_info).await?;
+        let capabilities = types::ClientCapabilities {
+            experimental: None,
+            sampling: sampling_handler.is_some().then(|| serde_json::json!({})),
+            roots: None,
+            elicitation: elicitation_handler.is_some().then(|| serde_json::json!({})),
+        };
+        let initialized_protocol = protocol.initialize(client_info, capabilities).await?;
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -0,0 +1,926 @@
+//! OAuth 2.1 authorization of remote context servers, as described by the MCP authorization spec.
+//!
+//! Authorization starts from the protected resource metadata of the server (RFC 9728), which
+//! points to the authorization server and its metadata (RFC 8414). Zed registers itself as a
+//! public client (RFC 7591) and runs the authorization code flow with PKCE, receiving the redirect
+//! on a loopback listener. Tokens are bound to the server with resource indicators (RFC 8707).
+
+use std::{
+    net::Ipv4Addr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use collections::HashMap;
+use credentials_provider::CredentialsProvider;
+use futures::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _};
+use gpui::AsyncApp;
+use http_client::{AsyncBody, HttpClient, Request, http::Method};
+use parking_lot::Mutex;
+use rand::Rng as _;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest as _, Sha256};
+use smol::{channel, net::TcpListener};
+use url::Url;
+
+const CALLBACK_PATH: &str = "/callback";
+const CLIENT_NAME: &str = "Zed";
+const CREDENTIALS_USERNAME: &str = "oauth";
+
+/// The error message of requests rejected because the server needs the user to authorize Zed
+pub const AUTHORIZATION_REQUIRED: &str = "Authorization required";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProtectedResourceMetadata {
+    pub resource: Option<String>,
+    #[serde(default)]
+    pub authorization_servers: Vec<String>,
+    pub scopes_supported: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthorizationServerMetadata {
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub registration_endpoint: Option<String>,
+    pub code_challenge_methods_supported: Option<Vec<String>>,
+    pub scopes_supported: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientRegistration {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Everything needed to authorize requests to a context server, and to refresh its access token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthCredentials {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub token_endpoint: String,
+    pub resource: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Expiration of the access token, in seconds since the Unix epoch
+    pub expires_at: Option<u64>,
+}
+
+/// The parameters of a `Bearer` challenge in a `WWW-Authenticate` header
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthChallenge {
+    pub resource_metadata: Option<String>,
+    pub scope: Option<String>,
+}
+
+impl AuthChallenge {
+    pub fn parse(www_authenticate: &str) -> Self {
+        let mut challenge = Self::default();
+        let Some(parameters) = www_authenticate
+            .trim_start()
+            .strip_prefix("Bearer")
+            .or_else(|| www_authenticate.trim_start().strip_prefix("bearer"))
+        else {
+            return challenge;
+        };
+
+        let mut rest = parameters.trim_start();
+        while !rest.is_empty() {
+            let Some((name, value)) = rest.split_once('=') else {
+                break;
+            };
+            let name = name.trim().trim_start_matches(',').trim();
+            let value = value.trim_start();
+            let (value, remainder) = if let Some(quoted) = value.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or_default())
+            } else {
+                let end = value.find(',').unwrap_or(value.len());
+                (value[..end].trim(), &value[end..])
+            };
+            match name {
+                "resource_metadata" => challenge.resource_metadata = Some(value.to_string()),
+                "scope" => challenge.scope = Some(value.to_string()),
+                _ => {}
+            }
+            rest = remainder.trim_start().trim_start_matches(',').trim_start();
+        }
+        challenge
+    }
+}
+
+/// The OAuth state of a context server, shared between its transport and the store that
+/// persists the credentials and runs the authorization flow
+pub struct OAuthSession {
+    endpoint: Url,
+    credentials: Mutex<Option<OAuthCredentials>>,
+    challenge: Mutex<AuthChallenge>,
+    authorization_required: AtomicBool,
+    refresh_lock: smol::lock::Mutex<()>,
+    refreshed_tx: channel::Sender<OAuthCredentials>,
+    refreshed_rx: channel::Receiver<OAuthCredentials>,
+    authorization_required_tx: channel::Sender<()>,
+    authorization_required_rx: channel::Receiver<()>,
+}
+
+impl OAuthSession {
+    pub fn new(endpoint: Url, credentials: Option<OAuthCredentials>) -> Self {
+        let (refreshed_tx, refreshed_rx) = channel::unbounded();
+        let (authorization_required_tx, authorization_required_rx) = channel::unbounded();
+        Self {
+            endpoint,
+            credentials: Mutex::new(credentials),
+            challenge: Mutex::default(),
+            authorization_required: AtomicBool::new(false),
+            refresh_lock: smol::lock::Mutex::new(()),
+            refreshed_tx,
+            refreshed_rx,
+            authorization_required_tx,
+            authorization_required_rx,
+        }
+    }
+
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    pub fn access_token(&self) -> Option<String> {
+        self.credentials
+            .lock()
+            .as_ref()
+            .map(|credentials| credentials.access_token.clone())
+    }
+
+    pub fn set_credentials(&self, credentials: Option<OAuthCredentials>) {
+        *self.credentials.lock() = credentials;
+        self.authorization_required.store(false, Ordering::SeqCst);
+    }
+
+    /// The challenge of the latest rejected request, used to discover the authorization server
+    pub fn challenge(&self) -> AuthChallenge {
+        self.challenge.lock().clone()
+    }
+
+    /// Whether the server rejected a request that could not be authorized by refreshing the token
+    pub fn authorization_required(&self) -> bool {
+        self.authorization_required.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_authorization_required(&self) {
+        if !self.authorization_required.swap(true, Ordering::SeqCst) {
+            self.authorization_required_tx.try_send(()).ok();
+        }
+    }
+
+    /// Notified whenever the server starts rejecting requests, for instance once the refresh
+    /// token of a running server has expired, so that the user can authorize Zed again
+    pub fn authorization_required_changes(&self) -> channel::Receiver<()> {
+        self.authorization_required_rx.clone()
+    }
+
+    /// Credentials obtained by refreshing the access token, which should be persisted
+    pub fn refreshed_credentials(&self) -> channel::Receiver<OAuthCredentials> {
+        self.refreshed_rx.clone()
+    }
+
+    /// Handles a `401 Unauthorized` response to a request sent with `failed_token`, returning
+    /// whether the request should be retried with a new access token.
+    pub(crate) async fn refresh_after_unauthorized(
+        &self,
+        http_client: &Arc<dyn HttpClient>,
+        www_authenticate: Option<&str>,
+        failed_token: Option<&str>,
+    ) -> bool {
+        if let Some(www_authenticate) = www_authenticate {
+            *self.challenge.lock() = AuthChallenge::parse(www_authenticate);
+        }
+
+        // Concurrent requests may fail with the same token, which should only be refreshed once
+        let _guard = self.refresh_lock.lock().await;
+        let credentials = self.credentials.lock().clone();
+        let Some(credentials) = credentials else {
+            return false;
+        };
+        if Some(credentials.access_token.as_str()) != failed_token {
+            return true;
+        }
+        if credentials.refresh_token.is_none() {
+            return false;
+        }
+
+        match refresh_credentials(http_client.as_ref(), &credentials).await {
+            Ok(credentials) => {
+                *self.credentials.lock() = Some(credentials.clone());
+                self.refreshed_tx.try_send(credentials).ok();
+                true
+            }
+            Err(error) => {
+                log::warn!(
+                    "failed to refresh the OAuth token of {}: {error:#}",
+                    self.endpoint
+                );
+                false
+            }
+        }
+    }
+}
+
+/// An authorization flow waiting for the user to approve Zed in their browser
+pub struct PendingAuthorization {
+    /// The URL to open in the browser
+    pub url: Url,
+    listener: LoopbackListener,
+    state: String,
+    code_verifier: String,
+    client: ClientRegistration,
+    token_endpoint: String,
+    resource: String,
+}
+
+/// Discovers the authorization server of `endpoint`, registers Zed with it, and prepares the
+/// authorization request.
+pub async fn prepare_authorization(
+    http_client: &dyn HttpClient,
+    endpoint: &Url,
+    challenge: &AuthChallenge,
+) -> Result<PendingAuthorization> {
+    let resource_metadata = discover_resource_metadata(
+        http_client,
+        endpoint,
+        challenge.resource_metadata.as_deref(),
+    )
+    .await
+    .inspect_err(|error| log::info!("{error:#}"))
+    .ok();
+    let resource = resource_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.resource.clone())
+        .unwrap_or_else(|| canonical_resource(endpoint));
+    let issuer = match resource_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.authorization_servers.first())
+    {
+        Some(issuer) => Url::parse(issuer).context("invalid authorization server URL")?,
+        // Servers implementing earlier revisions of the spec are their own authorization server
+        None => origin(endpoint),
+    };
+    let metadata = discover_authorization_server(http_client, &issuer).await?;
+    if let Some(methods) = &metadata.code_challenge_methods_supported
+        && !methods.iter().any(|method| method == "S256")
+    {
+        bail!("the authorization server does not support PKCE");
+    }
+
+    let listener = LoopbackListener::bind().await?;
+    let registration_endpoint = metadata
+        .registration_endpoint
+        .as_deref()
+        .context("the authorization server does not support dynamic client registration")?;
+    let client =
+        register_client(http_client, registration_endpoint, listener.redirect_uri()).await?;
+
+    let scope = challenge.scope.clone().or_else(|| {
+        resource_metadata
+            .as_ref()
+            .and_then(|resource| resource.scopes_supported.as_ref())
+            .or(metadata.scopes_supported.as_ref())
+            .map(|scopes| scopes.join(" "))
+    });
+    let state = random_string();
+    let code_verifier = random_string();
+    let mut url =
+        Url::parse(&metadata.authorization_endpoint).context("invalid authorization endpoint")?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &client.client_id)
+            .append_pair("redirect_uri", listener.redirect_uri().as_str())
+            .append_pair("code_challenge", &code_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state)
+            .append_pair("resource", &resource);
+        if let Some(scope) = scope.filter(|scope| !scope.is_empty()) {
+            query.append_pair("scope", &scope);
+        }
+    }
+
+    Ok(PendingAuthorization {
+        url,
+        listener,
+        state,
+        code_verifier,
+        client,
+        token_endpoint: metadata.token_endpoint,
+        resource,
+    })
+}
+
+impl PendingAuthorization {
+    /// Waits for the browser to redirect to Zed, and exchanges the authorization code for tokens.
+    pub async fn complete(self, http_client: &dyn HttpClient) -> Result<OAuthCredentials> {
+        let redirect_uri = self.listener.redirect_uri().to_string();
+        let code = self.listener.receive_code(&self.state).await?;
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", self.client.client_id.as_str()),
+            ("code_verifier", self.code_verifier.as_str()),
+            ("resource", self.resource.as_str()),
+        ];
+        if let Some(client_secret) = &self.client.client_secret {
+            params.push(("client_secret", client_secret));
+        }
+        let response: TokenResponse = post_form(http_client, &self.token_endpoint, &params).await?;
+        Ok(OAuthCredentials {
+            client_id: self.client.client_id,
+            client_secret: self.client.client_secret,
+            token_endpoint: self.token_endpoint,
+            resource: self.resource,
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: response.expires_in.map(|expires_in| now() + expires_in),
+        })
+    }
+}
+
+pub async fn refresh_credentials(
+    http_client: &dyn HttpClient,
+    credentials: &OAuthCredentials,
+) -> Result<OAuthCredentials> {
+    let refresh_token = credentials
+        .refresh_token
+        .as_deref()
+        .context("no refresh token")?;
+    let mut params = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", credentials.client_id.as_str()),
+        ("resource", credentials.resource.as_str()),
+    ];
+    if let Some(client_secret) = &credentials.client_secret {
+        params.push(("client_secret", client_secret));
+    }
+    let response: TokenResponse =
+        post_form(http_client, &credentials.token_endpoint, &params).await?;
+    Ok(OAuthCredentials {
+        access_token: response.access_token,
+        // Servers don't have to rotate refresh tokens
+        refresh_token: response
+            .refresh_token
+            .or_else(|| credentials.refresh_token.clone()),
+        expires_at: response.expires_in.map(|expires_in| now() + expires_in),
+        ..credentials.clone()
+    })
+}
+
+pub async fn discover_resource_metadata(
+    http_client: &dyn HttpClient,
+    endpoint: &Url,
+    metadata_url: Option<&str>,
+) -> Result<ProtectedResourceMetadata> {
+    let mut candidates = Vec::new();
+    if let Some(metadata_url) = metadata_url {
+        candidates.push(metadata_url.to_string());
+    }
+    candidates.push(well_known_url(endpoint, "oauth-protected-resource", true).to_string());
+    candidates.push(well_known_url(endpoint, "oauth-protected-resource", false).to_string());
+    first_json(http_client, candidates)
+        .await
+        .context("failed to discover the protected resource metadata")
+}
+
+pub async fn discover_authorization_server(
+    http_client: &dyn HttpClient,
+    issuer: &Url,
+) -> Result<AuthorizationServerMetadata> {
+    let mut candidates = vec![
+        well_known_url(issuer, "oauth-authorization-server", true).to_string(),
+        well_known_url(issuer, "openid-configuration", true).to_string(),
+    ];
+    let issuer_path = issuer.path().trim_end_matches('/');
+    if !issuer_path.is_empty() {
+        let mut url = issuer.clone();
+        url.set_path(&format!("{issuer_path}/.well-known/openid-configuration"));
+        candidates.push(url.to_string());
+    }
+    match first_json(http_client, candidates).await {
+        Ok(metadata) => Ok(metadata),
+        // Earlier revisions of the spec fall back to default endpoints on the server's origin
+        Err(error) if issuer_path.is_empty() => {
+            log::info!("using default OAuth endpoints of {issuer}: {error:#}");
+            let endpoint = |path: &str| issuer.join(path).map(|url| url.to_string());
+            Ok(AuthorizationServerMetadata {
+                authorization_endpoint: endpoint("/authorize")?,
+                token_endpoint: endpoint("/token")?,
+                registration_endpoint: Some(endpoint("/register")?),
+                code_challenge_methods_supported: None,
+                scopes_supported: None,
+            })
+        }
+        Err(error) => Err(error.context("failed to discover the authorization server metadata")),
+    }
+}
+
+pub async fn register_client(
+    http_client: &dyn HttpClient,
+    registration_endpoint: &str,
+    redirect_uri: &Url,
+) -> Result<ClientRegistration> {
+    let body = serde_json::json!({
+        "client_name": CLIENT_NAME,
+        "redirect_uris": [redirect_uri],
+        "grant_types": ["authorization_code", "refresh_token"],
+        "response_types": ["code"],
+        "token_endpoint_auth_method": "none",
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(registration_endpoint)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json")
+        .body(AsyncBody::from(body.to_string()))?;
+    send_json(http_client, request)
+        .await
+        .context("failed to register Zed with the authorization server")
+}
+
+/// Receives the redirect of the authorization server on a local port
+pub struct LoopbackListener {
+    listener: TcpListener,
+    redirect_uri: Url,
+}
+
+impl LoopbackListener {
+    pub async fn bind() -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let port = listener.local_addr()?.port();
+        let redirect_uri = Url::parse(&format!("http://127.0.0.1:{port}{CALLBACK_PATH}"))?;
+        Ok(Self {
+            listener,
+            redirect_uri,
+        })
+    }
+
+    pub fn redirect_uri(&self) -> &Url {
+        &self.redirect_uri
+    }
+
+    /// Waits for the redirect carrying the authorization code, checking it matches `state`.
+    pub async fn receive_code(self, state: &str) -> Result<String> {
+        let (result_tx, result_rx) = channel::bounded(1);
+        let accept = async {
+            // Browsers may open connections without sending a request on them, which must not
+            // hold up the redirect, so every connection is handled separately.
+            let mut connections = Vec::new();
+            loop {
+                let (stream, _) = match self.listener.accept().await {
+                    Ok(connection) => connection,
+                    Err(error) => return Result::<String>::Err(error.into()),
+                };
+                let redirect_uri = self.redirect_uri.clone();
+                let state = state.to_string();
+                let result_tx = result_tx.clone();
+                connections.push(smol::spawn(async move {
+                    if let Some(result) = handle_redirect(stream, &redirect_uri, &state).await {
+                        result_tx.send(result).await.ok();
+                    }
+                }));
+            }
+        };
+        let received = async { result_rx.recv().await? };
+        smol::future::or(accept, received).await
+    }
+}
+
+/// Answers a request to the loopback listener, returning the authorization code it carries
+async fn handle_redirect(
+    mut stream: smol::net::TcpStream,
+    redirect_uri: &Url,
+    state: &str,
+) -> Option<Result<String>> {
+    let mut request_line = String::new();
+    futures::io::BufReader::new(&mut stream)
+        .read_line(&mut request_line)
+        .await
+        .ok()?;
+    let url = request_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|target| redirect_uri.join(target).ok());
+    let Some(url) = url.filter(|url| url.path() == CALLBACK_PATH) else {
+        // Browsers also ask for a favicon
+        respond(&mut stream, "404 Not Found", "Not found")
+            .await
+            .ok();
+        return None;
+    };
+
+    let params = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+    let result = if let Some(error) = params.get("error") {
+        Err(anyhow!(
+            "authorization failed: {}",
+            params.get("error_description").unwrap_or(error)
+        ))
+    } else if params.get("state").map(String::as_str) != Some(state) {
+        Err(anyhow!(
+            "the authorization response has an unexpected state"
+        ))
+    } else {
+        params
+            .get("code")
+            .cloned()
+            .context("the authorization response has no code")
+    };
+    let (status, message) = match &result {
+        Ok(_) => (
+            "200 OK",
+            "Zed is authorized. You can close this page and return to Zed.",
+        ),
+        Err(_) => (
+            "400 Bad Request",
+            "Authorization failed. You can close this page and try again in Zed.",
+        ),
+    };
+    respond(&mut stream, status, message).await.ok();
+    Some(result)
+}
+
+async fn respond(stream: &mut smol::net::TcpStream, status: &str, message: &str) -> Result<()> {
+    let body = format!("<!DOCTYPE html><html><body><p>{message}</p></body></html>");
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+pub async fn read_credentials(
+    provider: &dyn CredentialsProvider,
+    endpoint: &Url,
+    cx: &AsyncApp,
+) -> Result<Option<OAuthCredentials>> {
+    let Some((_, credentials)) = provider.read_credentials(endpoint.as_str(), cx).await? else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&credentials)?))
+}
+
+pub async fn write_credentials(
+    provider: &dyn CredentialsProvider,
+    endpoint: &Url,
+    credentials: &OAuthCredentials,
+    cx: &AsyncApp,
+) -> Result<()> {
+    provider
+        .write_credentials(
+            endpoint.as_str(),
+            CREDENTIALS_USERNAME,
+            &serde_json::to_vec(credentials)?,
+            cx,
+        )
+        .await
+}
+
+pub async fn delete_credentials(
+    provider: &dyn CredentialsProvider,
+    endpoint: &Url,
+    cx: &AsyncApp,
+) -> Result<()> {
+    provider.delete_credentials(endpoint.as_str(), cx).await
+}
+
+async fn first_json<T: DeserializeOwned>(
+    http_client: &dyn HttpClient,
+    urls: Vec<String>,
+) -> Result<T> {
+    let mut last_error = anyhow!("no metadata URL");
+    for url in urls {
+        let request = Request::builder()
+            .uri(&url)
+            .header("Accept", "application/json")
+            .body(AsyncBody::empty())?;
+        match send_json(http_client, request).await {
+            Ok(metadata) => return Ok(metadata),
+            Err(error) => last_error = error.context(url),
+        }
+    }
+    Err(last_error)
+}
+
+async fn post_form<T: DeserializeOwned>(
+    http_client: &dyn HttpClient,
+    url: &str,
+    params: &[(&str, &str)],
+) -> Result<T> {
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Accept", "application/json")
+        .body(AsyncBody::from(body))?;
+    send_json(http_client, request).await
+}
+
+async fn send_json<T: DeserializeOwned>(
+    http_client: &dyn HttpClient,
+    request: Request<AsyncBody>,
+) -> Result<T> {
+    let mut response = http_client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    if !response.status().is_success() {
+        if let Ok(error) = serde_json::from_str::<ErrorResponse>(&body) {
+            bail!(
+                "{}: {}",
+                error.error,
+                error.error_description.unwrap_or_default()
+            );
+        }
+        bail!("HTTP {}: {body}", response.status());
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+/// The `.well-known` URL of `url`, with the path of `url` appended to it when `with_path` is set
+fn well_known_url(url: &Url, name: &str, with_path: bool) -> Url {
+    let path = if with_path {
+        url.path().trim_end_matches('/')
+    } else {
+        ""
+    };
+    let mut well_known = origin(url);
+    well_known.set_path(&format!("/.well-known/{name}{path}"));
+    well_known
+}
+
+fn origin(url: &Url) -> Url {
+    let mut origin = url.clone();
+    origin.set_path("/");
+    origin.set_query(None);
+    origin.set_fragment(None);
+    origin
+}
+
+fn canonical_resource(endpoint: &Url) -> String {
+    let mut resource = endpoint.clone();
+    resource.set_fragment(None);
+    resource.to_string()
+}
+
+fn random_string() -> String {
+    let mut bytes = [0; 32];
+    rand::rng().fill(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_client::{FakeHttpClient, Response};
+
+    const SERVER: &str = "https://mcp.example.com/mcp";
+
+    /// A mock authorization server, with the MCP server delegating authorization to it
+    fn authorization_server() -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(|request| async move {
+            let url = request.uri().to_string();
+            let mut body = String::new();
+            let mut request_body = request.into_body();
+            request_body.read_to_string(&mut body).await?;
+            let form = url::form_urlencoded::parse(body.as_bytes())
+                .into_owned()
+                .collect::<HashMap<_, _>>();
+            let json = match url.as_str() {
+                "https://mcp.example.com/.well-known/oauth-protected-resource/mcp" => {
+                    serde_json::json!({
+                        "resource": SERVER,
+                        "authorization_servers": ["https://auth.example.com"],
+                        "scopes_supported": ["mcp:tools", "mcp:resources"],
+                    })
+                }
+                "https://auth.example.com/.well-known/oauth-authorization-server" => {
+                    serde_json::json!({
+                        "issuer": "https://auth.example.com",
+                        "authorization_endpoint": "https://auth.example.com/authorize",
+                        "token_endpoint": "https://auth.example.com/token",
+                        "registration_endpoint": "https://auth.example.com/register",
+                        "code_challenge_methods_supported": ["S256"],
+                    })
+                }
+                "https://auth.example.com/register" => {
+                    let registration: serde_json::Value = serde_json::from_str(&body)?;
+                    assert_eq!(registration["token_endpoint_auth_method"], "none");
+                    serde_json::json!({ "client_id": "zed-client" })
+                }
+                "https://auth.example.com/token" => {
+                    assert_eq!(form["client_id"], "zed-client");
+                    assert_eq!(form["resource"], SERVER);
+                    match form["grant_type"].as_str() {
+                        "authorization_code" if form["code"] == "the-code" => {
+                            assert!(!form["code_verifier"].is_empty());
+                            serde_json::json!({
+                                "access_token": "access-1",
+                                "token_type": "Bearer",
+                                "expires_in": 3600,
+                                "refresh_token": "refresh-1",
+                            })
+                        }
+                        "refresh_token" if form["refresh_token"] == "refresh-1" => {
+                            serde_json::json!({
+                                "access_token": "access-2",
+                                "token_type": "Bearer",
+                            })
+                        }
+                        _ => {
+                            return Ok(Response::builder()
+                                .status(400)
+                                .body(r#"{"error":"invalid_grant"}"#.into())?);
+                        }
+                    }
+                }
+                _ => return Ok(Response::builder().status(404).body("".into())?),
+            };
+            Ok(Response::builder()
+                .status(200)
+                .header("Content-Type", "application/json")
+                .body(json.to_string().into())?)
+        })
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        assert_eq!(
+            AuthChallenge::parse(
+                r#"Bearer error="invalid_token", resource_metadata="https://mcp.example.com/.well-known/oauth-protected-resource", scope="files:read files:write""#
+            ),
+            AuthChallenge {
+                resource_metadata: Some(
+                    "https://mcp.example.com/.well-known/oauth-protected-resource".into()
+                ),
+                scope: Some("files:read files:write".into()),
+            }
+        );
+        assert_eq!(
+            AuthChallenge::parse("Bearer realm=mcp,scope=read"),
+            AuthChallenge {
+                resource_metadata: None,
+                scope: Some("read".into()),
+            }
+        );
+        assert_eq!(AuthChallenge::parse("Basic"), AuthChallenge::default());
+    }
+
+    #[test]
+    fn test_authorization_flow() {
+        smol::block_on(async {
+            let http_client = authorization_server();
+            let endpoint = Url::parse(SERVER).unwrap();
+            let pending =
+                prepare_authorization(http_client.as_ref(), &endpoint, &AuthChallenge::default())
+                    .await
+                    .unwrap();
+
+            let params = pending
+                .url
+                .query_pairs()
+                .into_owned()
+                .collect::<HashMap<_, _>>();
+            assert_eq!(pending.url.path(), "/authorize");
+            assert_eq!(params["client_id"], "zed-client");
+            assert_eq!(params["code_challenge_method"], "S256");
+            assert_eq!(
+                params["code_challenge"],
+                code_challenge(&pending.code_verifier)
+            );
+            assert_eq!(params["resource"], SERVER);
+            assert_eq!(params["scope"], "mcp:tools mcp:resources");
+
+            // Act as the browser following the redirect of the authorization server
+            let mut redirect = Url::parse(&params["redirect_uri"]).unwrap();
+            redirect
+                .query_pairs_mut()
+                .append_pair("code", "the-code")
+                .append_pair("state", &params["state"]);
+            let browser = smol::spawn(async move {
+                let mut stream =
+                    smol::net::TcpStream::connect(redirect.socket_addrs(|| None).unwrap()[0])
+                        .await
+                        .unwrap();
+                let request = format!(
+                    "GET {}?{} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n",
+                    redirect.path(),
+                    redirect.query().unwrap()
+                );
+                stream.write_all(request.as_bytes()).await.unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).await.unwrap();
+                response
+            });
+
+            let credentials = pending.complete(http_client.as_ref()).await.unwrap();
+            assert!(browser.await.starts_with("HTTP/1.1 200 OK"));
+            assert_eq!(credentials.access_token, "access-1");
+            assert_eq!(credentials.refresh_token.as_deref(), Some("refresh-1"));
+            assert_eq!(credentials.token_endpoint, "https://auth.example.com/token");
+            assert!(credentials.expires_at.is_some());
+
+            let refreshed = refresh_credentials(http_client.as_ref(), &credentials)
+                .await
+                .unwrap();
+            assert_eq!(refreshed.access_token, "access-2");
+            assert_eq!(refreshed.refresh_token.as_deref(), Some("refresh-1"));
+            assert_eq!(refreshed.expires_at, None);
+
+            let revoked = OAuthCredentials {
+                refresh_token: Some("revoked".into()),
+                ..refreshed
+            };
+            let error = refresh_credentials(http_client.as_ref(), &revoked)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("invalid_grant"));
+        });
+    }
+
+    #[test]
+    fn test_session_refresh() {
+        smol::block_on(async {
+            let http_client = authorization_server();
+            let credentials = OAuthCredentials {
+                client_id: "zed-client".into(),
+                client_secret: None,
+                token_endpoint: "https://auth.example.com/token".into(),
+                resource: SERVER.into(),
+                access_token: "access-1".into(),
+                refresh_token: Some("refresh-1".into()),
+                expires_at: None,
+            };
+            let session = OAuthSession::new(Url::parse(SERVER).unwrap(), Some(credentials));
+            let challenge = r#"Bearer resource_metadata="https://mcp.example.com/meta""#;
+
+            assert!(
+                session
+                    .refresh_after_unauthorized(&http_client, Some(challenge), Some("access-1"))
+                    .await
+            );
+            assert_eq!(session.access_token().as_deref(), Some("access-2"));
+            assert_eq!(
+                session.challenge().resource_metadata.as_deref(),
+                Some("https://mcp.example.com/meta")
+            );
+            let refreshed = session.refreshed_credentials().try_recv().unwrap();
+            assert_eq!(refreshed.access_token, "access-2");
+
+            // A request that failed with the previous token is retried with the new one
+            assert!(
+                session
+                    .refresh_after_unauthorized(&http_client, None, Some("access-1"))
+                    .await
+            );
+            assert!(session.refreshed_credentials().try_recv().is_err());
+
+            let session = OAuthSession::new(Url::parse(SERVER).unwrap(), None);
+            assert!(
+                !session
+                    .refresh_after_unauthorized(&http_client, None, None)
+                    .await
+            );
+        });
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::Context as _;
 use collections::HashMap;
+use credentials_provider::CredentialsProvider;
 use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
-use gpui::BackgroundExecutor;
+use gpui::{AsyncApp, BackgroundExecutor};
 use std::{pin::Pin, sync::Arc};
 
 use crate::{
@@ -128,3 +129,42 @@ impl Transport for FakeTransport {
         Box::pin(futures::stream::empty())
     }
 }
+
+/// Keeps OAuth credentials in memory instead of the system keychain
+#[derive(Default)]
+pub struct FakeCredentialsProvider {
+    credentials: parking_lot::Mutex<HashMap<String, (String, Vec<u8>)>>,
+}
+
+impl CredentialsProvider for FakeCredentialsProvider {
+    fn read_credentials<'a>(
+        &'a self,
+        url: &'a str,
+        _cx: &'a AsyncApp,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<(String, Vec<u8>)>>> + 'a>> {
+        let credentials = self.credentials.lock().get(url).cloned();
+        futures::future::ready(Ok(credentials)).boxed_local()
+    }
+
+    fn write_credentials<'a>(
+        &'a self,
+        url: &'a str,
+        username: &'a str,
+        password: &'a [u8],
+        _cx: &'a AsyncApp,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + 'a>> {
+        self.credentials
+            .lock()
+            .insert(url.to_string(), (username.to_string(), password.to_vec()));
+        futures::future::ready(Ok(())).boxed_local()
+    }
+
+    fn delete_credentials<'a>(
+        &'a self,
+        url: &'a str,
+        _cx: &'a AsyncApp,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + 'a>> {
+        self.credentials.lock().remove(url);
+        futures::future::ready(Ok(())).boxed_local()
+    }
+}
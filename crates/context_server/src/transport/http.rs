@@ -3,17 +3,22 @@ use async_trait::async_trait;
 use collections::HashMap;
 use futures::{Stream, StreamExt};
 use gpui::BackgroundExecutor;
-use http_client::{AsyncBody, HttpClient, Request, Response, http::Method};
+use http_client::{AsyncBody, HttpClient, Request, Response, StatusCode, http::Method};
 use parking_lot::Mutex as SyncMutex;
 use smol::channel;
 use std::{pin::Pin, sync::Arc};
 
+use crate::oauth::{AUTHORIZATION_REQUIRED, OAuthSession};
 use crate::transport::Transport;
 
 // Constants from MCP spec
 const HEADER_SESSION_ID: &str = "Mcp-Session-Id";
 const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
 const JSON_MIME_TYPE: &str = "application/json";
+const HEADER_WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+
+// JSON-RPC error code of requests the server rejected as unauthorized
+const UNAUTHORIZED_ERROR: i32 = -32001;
 
 /// HTTP Transport with session management and SSE support
 pub struct HttpTransport {
@@ -27,6 +32,7 @@ pub struct HttpTransport {
     error_rx: channel::Receiver<String>,
     // Authentication headers to include in requests
     headers: HashMap<String, String>,
+    oauth: Option<Arc<OAuthSession>>,
 }
 
 impl HttpTransport {
@@ -49,14 +55,21 @@ impl HttpTransport {
             error_tx,
             error_rx,
             headers,
+            oauth: None,
         }
     }
 
-    /// Send a message and handle the response based on content type
-    async fn send_message(&self, message: String) -> Result<()> {
-        let is_notification =
-            !message.contains("\"id\":") || message.contains("notifications/initialized");
+    /// Authorizes requests with the access token of the session, refreshing it when rejected
+    pub fn with_oauth(mut self, session: Arc<OAuthSession>) -> Self {
+        self.oauth = Some(session);
+        self
+    }
 
+    fn build_request(
+        &self,
+        message: &str,
+        access_token: Option<&str>,
+    ) -> Result<Request<AsyncBody>> {
         let mut request_builder = Request::builder()
             .method(Method::POST)
             .uri(&self.endpoint)
@@ -70,13 +83,79 @@ impl HttpTransport {
             request_builder = request_builder.header(key.as_str(), value.as_str());
         }
 
+        if let Some(access_token) = access_token {
+            request_builder =
+                request_builder.header("Authorization", format!("Bearer {access_token}"));
+        }
+
         // Add session ID if we have one (except for initialize)
         if let Some(ref session_id) = *self.session_id.lock() {
             request_builder = request_builder.header(HEADER_SESSION_ID, session_id.as_str());
         }
 
-        let request = request_builder.body(AsyncBody::from(message.into_bytes()))?;
-        let mut response = self.http_client.send(request).await?;
+        Ok(request_builder.body(AsyncBody::from(message.as_bytes().to_vec()))?)
+    }
+
+    /// Fails a rejected request immediately, so that the client knows Zed needs to be authorized
+    async fn reject_unauthorized(&self, message: &str) -> Result<()> {
+        let id = serde_json::from_str::<serde_json::Value>(message)
+            .ok()
+            .and_then(|message| message.get("id").cloned());
+        if let Some(id) = id {
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": UNAUTHORIZED_ERROR,
+                    "message": AUTHORIZATION_REQUIRED,
+                },
+            });
+            self.response_tx
+                .send(response.to_string())
+                .await
+                .map_err(|_| anyhow!("Failed to send unauthorized response"))?;
+        }
+        Ok(())
+    }
+
+    /// Send a message and handle the response based on content type
+    async fn send_message(&self, message: String) -> Result<()> {
+        let is_notification =
+            !message.contains("\"id\":") || message.contains("notifications/initialized");
+
+        let mut retried = false;
+        let mut response = loop {
+            let access_token = self.oauth.as_ref().and_then(|oauth| oauth.access_token());
+            let request = self.build_request(&message, access_token.as_deref())?;
+            let response = self.http_client.send(request).await?;
+
+            if response.status() == StatusCode::UNAUTHORIZED
+                && let Some(oauth) = &self.oauth
+            {
+                let challenge = response
+                    .headers()
+                    .get(HEADER_WWW_AUTHENTICATE)
+                    .and_then(|v| v.to_str().ok());
+                if !retried
+                    && oauth
+                        .refresh_after_unauthorized(
+                            &self.http_client,
+                            challenge,
+                            access_token.as_deref(),
+                        )
+                        .await
+                {
+                    retried = true;
+                    continue;
+                }
+
+                log::info!("{} requires authorization", self.endpoint);
+                oauth.set_authorization_required();
+                return self.reject_unauthorized(&message).await;
+            }
+
+            break response;
+        };
 
         // Handle different response types based on status and content-type
         match response.status() {
@@ -233,6 +312,7 @@ impl Drop for HttpTransport {
         let endpoint = self.endpoint.clone();
         let session_id = self.session_id.lock().clone();
         let headers = self.headers.clone();
+        let access_token = self.oauth.as_ref().and_then(|oauth| oauth.access_token());
 
         if let Some(session_id) = session_id {
             self.executor
@@ -246,6 +326,10 @@ impl Drop for HttpTransport {
                     for (key, value) in headers {
                         request_builder = request_builder.header(key.as_str(), value.as_str());
                     }
+                    if let Some(access_token) = access_token {
+                        request_builder = request_builder
+                            .header("Authorization", format!("Bearer {access_token}"));
+                    }
 
                     let request = request_builder.body(AsyncBody::empty());
 
@@ -257,3 +341,111 @@ impl Drop for HttpTransport {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oauth::OAuthCredentials;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+
+    const ENDPOINT: &str = "https://mcp.example.com/mcp";
+
+    /// An MCP server only accepting `valid-token`, and an authorization server refreshing tokens
+    fn http_client() -> Arc<dyn HttpClient> {
+        FakeHttpClient::create(|request| async move {
+            if request.uri() == "https://auth.example.com/token" {
+                let json = serde_json::json!({ "access_token": "valid-token" });
+                return Ok(Response::builder()
+                    .status(200)
+                    .header("Content-Type", JSON_MIME_TYPE)
+                    .body(json.to_string().into())?);
+            }
+
+            let authorization = request
+                .headers()
+                .get("Authorization")
+                .and_then(|value| value.to_str().ok());
+            if authorization != Some("Bearer valid-token") {
+                return Ok(Response::builder()
+                    .status(401)
+                    .header(
+                        HEADER_WWW_AUTHENTICATE,
+                        r#"Bearer resource_metadata="https://mcp.example.com/.well-known/oauth-protected-resource""#,
+                    )
+                    .body("".into())?);
+            }
+            Ok(Response::builder()
+                .status(200)
+                .header("Content-Type", JSON_MIME_TYPE)
+                .body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#.into())?)
+        })
+    }
+
+    fn credentials(access_token: &str) -> OAuthCredentials {
+        OAuthCredentials {
+            client_id: "zed".into(),
+            client_secret: None,
+            token_endpoint: "https://auth.example.com/token".into(),
+            resource: ENDPOINT.into(),
+            access_token: access_token.into(),
+            refresh_token: Some("refresh-token".into()),
+            expires_at: None,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_refresh_expired_token(cx: &mut TestAppContext) {
+        let session = Arc::new(OAuthSession::new(
+            ENDPOINT.parse().unwrap(),
+            Some(credentials("expired-token")),
+        ));
+        let transport = HttpTransport::new(
+            http_client(),
+            ENDPOINT.to_string(),
+            HashMap::default(),
+            cx.executor(),
+        )
+        .with_oauth(session.clone());
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#.into())
+            .await
+            .unwrap();
+        let response = transport.receive().next().await.unwrap();
+        assert!(response.contains(r#""result""#));
+        assert_eq!(session.access_token().as_deref(), Some("valid-token"));
+        assert_eq!(
+            session.refreshed_credentials().try_recv().unwrap(),
+            credentials("valid-token")
+        );
+        assert!(!session.authorization_required());
+    }
+
+    #[gpui::test]
+    async fn test_authorization_required(cx: &mut TestAppContext) {
+        let session = Arc::new(OAuthSession::new(ENDPOINT.parse().unwrap(), None));
+        let transport = HttpTransport::new(
+            http_client(),
+            ENDPOINT.to_string(),
+            HashMap::default(),
+            cx.executor(),
+        )
+        .with_oauth(session.clone());
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":7,"method":"initialize"}"#.into())
+            .await
+            .unwrap();
+        let response: serde_json::Value =
+            serde_json::from_str(&transport.receive().next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["message"], AUTHORIZATION_REQUIRED);
+        assert!(session.authorization_required());
+        assert!(session.authorization_required_changes().try_recv().is_ok());
+        assert_eq!(
+            session.challenge().resource_metadata.as_deref(),
+            Some("https://mcp.example.com/.well-known/oauth-protected-resource")
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use editor::{Editor, EditorEvent};
 use feature_flags::{FeatureFlag, FeatureFlagAppExt as _};
 use gpui::{
-    AppContext, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, Task, actions,
+    AppContext, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, Subscription, Task,
+    actions,
 };
 use std::{
     collections::HashMap,
@@ -12,7 +13,12 @@ use crate::table_data_engine::TableDataEngine;
 use ui::{SharedString, TableColumnWidths, TableInteractionState, prelude::*};
 use workspace::{Item, SplitDirection, Workspace};
 
-use crate::{parser::EditorState, settings::CsvPreviewSettings, types::TableLikeContent};
+use crate::{
+    parser::{Delimiter, EditorState},
+    renderer::{CellEditor, ColumnFilterInput},
+    settings::CsvPreviewSettings,
+    types::{AnyColumn, TableLikeContent},
+};
 
 mod parser;
 mod renderer;
@@ -42,6 +48,13 @@ pub struct CsvPreviewView {
     pub(crate) list_state: gpui::ListState,
     /// Time when the last parsing operation ended, used for smart debouncing
     pub(crate) last_parse_end_time: Option<std::time::Instant>,
+    /// Query input of the find bar
+    pub(crate) find_editor: Entity<Editor>,
+    _find_editor_subscription: Subscription,
+    /// Inputs of column filters, present for columns with an opened filter
+    pub(crate) filter_inputs: HashMap<AnyColumn, ColumnFilterInput>,
+    /// Cell which is being edited in place
+    pub(crate) cell_editor: Option<CellEditor>,
 }
 
 pub fn init(cx: &mut App) {
@@ -61,7 +74,7 @@ impl CsvPreviewView {
                         .and_then(|item| item.act_as::<Editor>(cx))
                         .filter(|editor| Self::is_csv_file(editor, cx))
                     {
-                        let csv_preview = Self::new(&editor, cx);
+                        let csv_preview = Self::new(&editor, window, cx);
                         workspace.active_pane().update(cx, |pane, cx| {
                             let existing = pane
                                 .items_of_type::<CsvPreviewView>()
@@ -82,7 +95,7 @@ impl CsvPreviewView {
                             .and_then(|item| item.act_as::<Editor>(cx))
                             .filter(|editor| Self::is_csv_file(editor, cx))
                         {
-                            let csv_preview = Self::new(&editor, cx);
+                            let csv_preview = Self::new(&editor, window, cx);
                             let pane = workspace
                                 .find_pane_in_direction(SplitDirection::Right, cx)
                                 .unwrap_or_else(|| {
@@ -119,7 +132,11 @@ impl CsvPreviewView {
         });
     }
 
-    fn new(editor: &Entity<Editor>, cx: &mut Context<Workspace>) -> Entity<Self> {
+    fn new(
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
         let contents = TableLikeContent::default();
         let table_interaction_state = cx.new(|cx| {
             TableInteractionState::new(cx)
@@ -141,6 +158,23 @@ impl CsvPreviewView {
                 },
             );
 
+            let find_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Find in table…", window, cx);
+                editor
+            });
+            let find_editor_subscription = cx.subscribe(
+                &find_editor,
+                |this: &mut CsvPreviewView, find_editor, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        let query = find_editor.read(cx).text(cx);
+                        this.engine.set_search_query(&query);
+                        this.scroll_to_active_match();
+                        cx.notify();
+                    }
+                },
+            );
+
             let mut view = CsvPreviewView {
                 focus_handle: cx.focus_handle(),
                 active_editor_state: EditorState {
@@ -155,6 +189,10 @@ impl CsvPreviewView {
                 settings: CsvPreviewSettings::default(),
                 last_parse_end_time: None,
                 engine: TableDataEngine::default(),
+                find_editor,
+                _find_editor_subscription: find_editor_subscription,
+                filter_inputs: HashMap::default(),
+                cell_editor: None,
             };
 
             view.parse_csv_from_active_editor(false, cx);
@@ -192,6 +230,7 @@ impl CsvPreviewView {
         Self::is_csv_file(&editor, cx).then_some(editor)
     }
 
+    /// Whether the editor holds a delimiter-separated file (`.csv`, `.tsv` or `.psv`)
     fn is_csv_file(editor: &Entity<Editor>, cx: &App) -> bool {
         editor
             .read(cx)
@@ -203,7 +242,11 @@ impl CsvPreviewView {
                     .read(cx)
                     .file()
                     .and_then(|file| file.path().extension())
-                    .map(|ext| ext.eq_ignore_ascii_case("csv"))
+                    .map(|ext| {
+                        Delimiter::EXTENSIONS
+                            .iter()
+                            .any(|supported| ext.eq_ignore_ascii_case(supported))
+                    })
             })
             .unwrap_or(false)
     }
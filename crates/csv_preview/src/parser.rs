@@ -3,12 +3,15 @@ use crate::{
     types::TableLikeContent,
     types::{LineNumber, TableCell},
 };
+pub(crate) use delimiter::{Delimiter, sniff_delimiter};
 use editor::Editor;
 use gpui::{AppContext, Context, Entity, Subscription, Task};
 use std::time::{Duration, Instant};
 use text::BufferSnapshot;
 use ui::{SharedString, table_row::TableRow};
 
+mod delimiter;
+
 pub(crate) const REPARSE_DEBOUNCE: Duration = Duration::from_millis(200);
 
 pub(crate) struct EditorState {
@@ -54,14 +57,20 @@ impl CsvPreviewView {
                 }
             }
 
-            let buffer_snapshot = view.update(cx, |_, cx| {
-                editor
-                    .read(cx)
-                    .buffer()
-                    .read(cx)
-                    .as_singleton()
-                    .map(|b| b.read(cx).text_snapshot())
-            })?;
+            let (buffer_snapshot, delimiter_override, default_delimiter) =
+                view.update(cx, |view, cx| {
+                    let buffer = editor.read(cx).buffer().read(cx).as_singleton();
+                    let default_delimiter = buffer
+                        .as_ref()
+                        .and_then(|b| b.read(cx).file()?.path().extension())
+                        .and_then(Delimiter::from_extension)
+                        .unwrap_or_default();
+                    (
+                        buffer.map(|b| b.read(cx).text_snapshot()),
+                        view.settings.delimiter_override,
+                        default_delimiter,
+                    )
+                })?;
 
             let Some(buffer_snapshot) = buffer_snapshot else {
                 return Ok(());
@@ -69,7 +78,9 @@ impl CsvPreviewView {
 
             let instant = Instant::now();
             let parsed_csv = cx
-                .background_spawn(async move { from_buffer(&buffer_snapshot) })
+                .background_spawn(async move {
+                    from_buffer(&buffer_snapshot, delimiter_override, default_delimiter)
+                })
                 .await;
             let parse_duration = instant.elapsed();
             let parse_end_time: Instant = Instant::now();
@@ -94,16 +105,32 @@ impl CsvPreviewView {
     }
 }
 
-pub fn from_buffer(buffer_snapshot: &BufferSnapshot) -> TableLikeContent {
+/// Parse the buffer into table-like content.
+///
+/// Uses `delimiter_override` when set, otherwise sniffs the delimiter from the text,
+/// falling back to `default_delimiter` (usually implied by the file extension) when nothing is detected.
+pub fn from_buffer(
+    buffer_snapshot: &BufferSnapshot,
+    delimiter_override: Option<Delimiter>,
+    default_delimiter: Delimiter,
+) -> TableLikeContent {
     let text = buffer_snapshot.text();
 
     if text.trim().is_empty() {
         return TableLikeContent::default();
     }
 
-    let (parsed_cells_with_positions, line_numbers) = parse_csv_with_positions(&text);
+    let delimiter = delimiter_override
+        .or_else(|| sniff_delimiter(&text))
+        .unwrap_or(default_delimiter);
+
+    let (parsed_cells_with_positions, line_numbers) =
+        parse_csv_with_positions(&text, delimiter.as_char());
     if parsed_cells_with_positions.is_empty() {
-        return TableLikeContent::default();
+        return TableLikeContent {
+            delimiter,
+            ..Default::default()
+        };
     }
     let raw_headers = parsed_cells_with_positions[0].clone();
 
@@ -128,12 +155,15 @@ pub fn from_buffer(buffer_snapshot: &BufferSnapshot) -> TableLikeContent {
         rows,
         line_numbers: row_line_numbers,
         number_of_cols: max_number_of_cols,
+        delimiter,
     }
 }
 
-/// Parse CSV and track byte positions for each cell
+/// Parse CSV and track byte positions for each cell.
+/// `delimiter` separates fields, e.g. `,` for CSV or `\t` for TSV.
 fn parse_csv_with_positions(
     text: &str,
+    delimiter: char,
 ) -> (
     Vec<Vec<(SharedString, std::ops::Range<usize>)>>,
     Vec<LineNumber>,
@@ -173,7 +203,7 @@ fn parse_csv_with_positions(
                     }
                 }
             }
-            ',' if !in_quotes => {
+            ch if ch == delimiter && !in_quotes => {
                 // Field separator
                 let field_end_offset = current_offset;
                 if current_field.is_empty() && !in_quotes {
@@ -424,7 +454,7 @@ Jane,"Simple name""#;
     #[test]
     fn test_csv_parsing_quote_offset_handling() {
         let csv_data = r#"first,"se,cond",third"#;
-        let (parsed_cells, _) = parse_csv_with_positions(csv_data);
+        let (parsed_cells, _) = parse_csv_with_positions(csv_data, ',');
 
         assert_eq!(parsed_cells.len(), 1); // One row
         assert_eq!(parsed_cells[0].len(), 3); // Three cells
@@ -450,7 +480,7 @@ Jane,"Simple name""#;
         let csv_data = r#"id,"name with spaces","description, with commas",status
 1,"John Doe","A person with ""quotes"" and, commas",active
 2,"Jane Smith","Simple description",inactive"#;
-        let (parsed_cells, _) = parse_csv_with_positions(csv_data);
+        let (parsed_cells, _) = parse_csv_with_positions(csv_data, ',');
 
         assert_eq!(parsed_cells.len(), 3); // header + 2 rows
 
@@ -498,6 +528,55 @@ Jane,"Simple name""#;
         assert_eq!(first_row[3].0.as_ref(), "active");
         assert_eq!(first_row[3].1, 108..114);
     }
+
+    #[test]
+    fn test_tsv_parsing_with_positions() {
+        let tsv_data = "name\t\"a\tb\"\tc,d\nx\ty\tz";
+        let (parsed_cells, _) = parse_csv_with_positions(tsv_data, '\t');
+
+        assert_eq!(parsed_cells.len(), 2);
+        let header_row = &parsed_cells[0];
+        assert_eq!(header_row.len(), 3);
+
+        // name: 0..4
+        assert_eq!(header_row[0].0.as_ref(), "name");
+        assert_eq!(header_row[0].1, 0..4);
+
+        // "a\tb": 5..10 (includes quotes, tab inside quotes is preserved)
+        assert_eq!(header_row[1].0.as_ref(), "a\tb");
+        assert_eq!(header_row[1].1, 5..10);
+
+        // c,d: 11..14 (comma is not a separator in TSV)
+        assert_eq!(header_row[2].0.as_ref(), "c,d");
+        assert_eq!(header_row[2].1, 11..14);
+
+        assert_eq!(parsed_cells[1][2].0.as_ref(), "z");
+        assert_eq!(parsed_cells[1][2].1, 19..20);
+    }
+
+    #[test]
+    fn test_delimiter_detection_and_override() {
+        let csv_data = "name;price\nApple;1,50\nPear;2,25";
+        let parsed = TableLikeContent::from_str(csv_data.to_string());
+
+        assert_eq!(parsed.delimiter, Delimiter::Semicolon);
+        assert_eq!(parsed.headers.cols(), 2);
+        assert_eq!(parsed.rows[0][1].display_value().unwrap().as_ref(), "1,50");
+
+        let buffer = text::Buffer::new(
+            text::ReplicaId::LOCAL,
+            text::BufferId::new(1).unwrap(),
+            csv_data.to_string(),
+        );
+        let parsed = from_buffer(buffer.snapshot(), Some(Delimiter::Comma), Delimiter::Comma);
+
+        assert_eq!(parsed.delimiter, Delimiter::Comma);
+        assert_eq!(parsed.headers.cols(), 2);
+        assert_eq!(
+            parsed.rows[0][0].display_value().unwrap().as_ref(),
+            "Apple;1"
+        );
+    }
 }
 
 impl TableLikeContent {
@@ -508,6 +587,6 @@ impl TableLikeContent {
         let buffer_id = BufferId::new(1).unwrap();
         let buffer = Buffer::new(ReplicaId::LOCAL, buffer_id, text);
         let snapshot = buffer.snapshot();
-        from_buffer(snapshot)
+        from_buffer(snapshot, None, Delimiter::Comma)
     }
 }
@@ -0,0 +1,204 @@
+//! Field delimiter definitions and detection for delimiter-separated files (CSV, TSV, PSV).
+
+use std::collections::HashMap;
+
+/// Maximum amount of bytes inspected when sniffing the delimiter
+const SNIFF_SAMPLE_BYTES: usize = 64 * 1024;
+/// Maximum amount of records inspected when sniffing the delimiter
+const SNIFF_SAMPLE_RECORDS: usize = 50;
+
+/// Field separator of a delimiter-separated file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl Delimiter {
+    /// All supported delimiters, in order of preference when detection is ambiguous
+    pub const ALL: [Delimiter; 4] = [
+        Delimiter::Comma,
+        Delimiter::Tab,
+        Delimiter::Semicolon,
+        Delimiter::Pipe,
+    ];
+
+    pub fn as_char(self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+            Delimiter::Semicolon => ';',
+            Delimiter::Pipe => '|',
+        }
+    }
+
+    /// Human readable name, used in the toolbar
+    pub fn label(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "Comma",
+            Delimiter::Tab => "Tab",
+            Delimiter::Semicolon => "Semicolon",
+            Delimiter::Pipe => "Pipe",
+        }
+    }
+
+    /// File extensions recognized as delimiter-separated files
+    pub const EXTENSIONS: [&'static str; 3] = ["csv", "tsv", "psv"];
+
+    /// Conventional delimiter implied by the file extension.
+    /// Returns `None` for unsupported extensions.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        if extension.eq_ignore_ascii_case("csv") {
+            Some(Delimiter::Comma)
+        } else if extension.eq_ignore_ascii_case("tsv") {
+            Some(Delimiter::Tab)
+        } else if extension.eq_ignore_ascii_case("psv") {
+            Some(Delimiter::Pipe)
+        } else {
+            None
+        }
+    }
+}
+
+/// Guess the delimiter of the given text.
+///
+/// Counts unquoted occurrences of every candidate per record over a sample of the text
+/// and picks the candidate which splits the most records into the same (non-zero) number of fields.
+/// Ties are resolved by the more frequent delimiter, then by the order of [`Delimiter::ALL`].
+///
+/// Returns `None` when none of the candidates occur in the sample.
+pub fn sniff_delimiter(text: &str) -> Option<Delimiter> {
+    let sample = sample_prefix(text);
+
+    Delimiter::ALL
+        .iter()
+        .enumerate()
+        .filter_map(|(priority, &delimiter)| {
+            let counts = count_per_record(sample, delimiter.as_char());
+            let (mode, consistent_records) = mode_of(&counts)?;
+            Some((delimiter, consistent_records, mode, priority))
+        })
+        .max_by(|a, b| {
+            a.1.cmp(&b.1)
+                .then(a.2.cmp(&b.2))
+                // Lower priority index wins
+                .then(b.3.cmp(&a.3))
+        })
+        .map(|(delimiter, ..)| delimiter)
+}
+
+/// Cut the text to at most [`SNIFF_SAMPLE_BYTES`], respecting char boundaries
+fn sample_prefix(text: &str) -> &str {
+    if text.len() <= SNIFF_SAMPLE_BYTES {
+        return text;
+    }
+    let mut end = SNIFF_SAMPLE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Number of unquoted `delimiter` occurrences in each non-empty record of the sample
+fn count_per_record(sample: &str, delimiter: char) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut in_quotes = false;
+    let mut current_count = 0;
+    let mut record_is_empty = true;
+
+    for ch in sample.chars() {
+        match ch {
+            '"' => {
+                // Escaped quotes (`""`) toggle twice, which keeps the state intact
+                in_quotes = !in_quotes;
+                record_is_empty = false;
+            }
+            '\n' | '\r' if !in_quotes => {
+                if !record_is_empty {
+                    counts.push(current_count);
+                    if counts.len() >= SNIFF_SAMPLE_RECORDS {
+                        return counts;
+                    }
+                }
+                current_count = 0;
+                record_is_empty = true;
+            }
+            ch if ch == delimiter && !in_quotes => {
+                current_count += 1;
+                record_is_empty = false;
+            }
+            ch => {
+                if !ch.is_whitespace() {
+                    record_is_empty = false;
+                }
+            }
+        }
+    }
+    if !record_is_empty {
+        counts.push(current_count);
+    }
+
+    counts
+}
+
+/// Returns the most common non-zero count and how many records have it
+fn mode_of(counts: &[usize]) -> Option<(usize, usize)> {
+    let mut occurrences = HashMap::<usize, usize>::new();
+    for &count in counts.iter().filter(|&&count| count > 0) {
+        *occurrences.entry(count).or_default() += 1;
+    }
+    occurrences
+        .into_iter()
+        .max_by(|(count_a, records_a), (count_b, records_b)| {
+            records_a.cmp(records_b).then(count_a.cmp(count_b))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_common_delimiters() {
+        assert_eq!(
+            sniff_delimiter("a,b,c\n1,2,3\n4,5,6"),
+            Some(Delimiter::Comma)
+        );
+        assert_eq!(
+            sniff_delimiter("a\tb\tc\n1\t2\t3\n4\t5\t6"),
+            Some(Delimiter::Tab)
+        );
+        assert_eq!(
+            sniff_delimiter("a;b;c\n1;2;3\n4;5;6"),
+            Some(Delimiter::Semicolon)
+        );
+        assert_eq!(
+            sniff_delimiter("a|b|c\n1|2|3\n4|5|6"),
+            Some(Delimiter::Pipe)
+        );
+        assert_eq!(sniff_delimiter("single column\nvalue"), None);
+    }
+
+    #[test]
+    fn test_sniff_semicolon_with_decimal_commas() {
+        let text = "name;price;amount\nApple;1,50;3\nPear;2,25;10\nPlum;0,99;1,5";
+        assert_eq!(sniff_delimiter(text), Some(Delimiter::Semicolon));
+    }
+
+    #[test]
+    fn test_sniff_ignores_quoted_delimiters() {
+        let text = "id\tdescription\n1\t\"one, two, three\"\n2\t\"four, five\"";
+        assert_eq!(sniff_delimiter(text), Some(Delimiter::Tab));
+    }
+
+    #[test]
+    fn test_delimiter_from_extension() {
+        assert_eq!(Delimiter::from_extension("csv"), Some(Delimiter::Comma));
+        assert_eq!(Delimiter::from_extension("TSV"), Some(Delimiter::Tab));
+        assert_eq!(Delimiter::from_extension("psv"), Some(Delimiter::Pipe));
+        assert_eq!(Delimiter::from_extension("txt"), None);
+    }
+}
@@ -1,5 +1,10 @@
+mod filter_bar;
 mod preview_view;
 mod render_table;
 mod row_identifiers;
 mod table_cell;
 mod table_header;
+mod toolbar;
+
+pub(crate) use filter_bar::ColumnFilterInput;
+pub(crate) use table_cell::CellEditor;
@@ -0,0 +1,171 @@
+//! Per-column filter inputs rendered between the toolbar and the table
+
+use editor::{Editor, EditorEvent};
+use gpui::{Entity, Subscription};
+use ui::{Tooltip, prelude::*};
+
+use crate::{
+    CsvPreviewView, table_data_engine::filtering_by_column::ColumnFilter, types::AnyColumn,
+};
+
+/// Input of a single column filter
+pub(crate) struct ColumnFilterInput {
+    pub editor: Entity<Editor>,
+    /// Parsing error of the filter expression. Invalid filters are not applied
+    pub error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl CsvPreviewView {
+    /// Open the filter input of the column, or close it (removing the filter) if it's already open
+    pub(crate) fn toggle_column_filter(
+        &mut self,
+        col: AnyColumn,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.filter_inputs.contains_key(&col) {
+            self.remove_column_filter(col, cx);
+            return;
+        }
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Contains, /regex/ or >, <, =, != number", window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(
+            &editor,
+            move |this: &mut CsvPreviewView, _editor, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_column_filter(col, cx);
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window, cx);
+
+        self.filter_inputs.insert(
+            col,
+            ColumnFilterInput {
+                editor,
+                error: None,
+                _subscription: subscription,
+            },
+        );
+        cx.notify();
+    }
+
+    /// Re-parse the filter expression of the column and re-apply all filters
+    fn update_column_filter(&mut self, col: AnyColumn, cx: &mut Context<Self>) {
+        let Some(input) = self.filter_inputs.get_mut(&col) else {
+            return;
+        };
+        let expression = input.editor.read(cx).text(cx);
+        match ColumnFilter::parse(&expression) {
+            Ok(Some(filter)) => {
+                input.error = None;
+                self.engine.applied_filters.insert(col, filter);
+            }
+            Ok(None) => {
+                input.error = None;
+                self.engine.applied_filters.remove(&col);
+            }
+            Err(error) => {
+                input.error = Some(error.to_string().into());
+                self.engine.applied_filters.remove(&col);
+            }
+        }
+        self.apply_filter_sort();
+        cx.notify();
+    }
+
+    pub(crate) fn remove_column_filter(&mut self, col: AnyColumn, cx: &mut Context<Self>) {
+        self.filter_inputs.remove(&col);
+        if self.engine.applied_filters.remove(&col).is_some() {
+            self.apply_filter_sort();
+        }
+        cx.notify();
+    }
+
+    pub(crate) fn render_filter_bar(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if self.filter_inputs.is_empty() {
+            return None;
+        }
+
+        let mut columns = self.filter_inputs.keys().copied().collect::<Vec<_>>();
+        columns.sort();
+
+        let visible_rows = self.engine.d2d_mapping().visible_row_count();
+        let total_rows = self.engine.contents.rows.len();
+
+        Some(
+            h_flex()
+                .w_full()
+                .pb_2()
+                .gap_2()
+                .flex_wrap()
+                .children(columns.into_iter().filter_map(|col| {
+                    let input = self.filter_inputs.get(&col)?;
+                    Some(self.render_column_filter_input(col, input, cx))
+                }))
+                .child(
+                    Label::new(format!("{visible_rows} of {total_rows} rows"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_column_filter_input(
+        &self,
+        col: AnyColumn,
+        input: &ColumnFilterInput,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let header_text: SharedString = self
+            .engine
+            .contents
+            .headers
+            .get(col)
+            .and_then(|h| h.display_value().cloned())
+            .unwrap_or_else(|| format!("Col {}", *col + 1).into());
+        let colors = cx.theme().colors();
+
+        h_flex()
+            .id(ElementId::NamedInteger(
+                "csv-column-filter".into(),
+                *col as u64,
+            ))
+            .gap_1()
+            .px_2()
+            .py_0p5()
+            .w_64()
+            .rounded_md()
+            .border_1()
+            .border_color(if input.error.is_some() {
+                cx.theme().status().error_border
+            } else {
+                colors.border
+            })
+            .when_some(input.error.clone(), |this, error| {
+                this.tooltip(Tooltip::text(error))
+            })
+            .child(
+                Label::new(header_text)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1().child(input.editor.clone()))
+            .child(
+                IconButton::new(
+                    ElementId::NamedInteger("csv-remove-column-filter".into(), *col as u64),
+                    IconName::Close,
+                )
+                .icon_size(IconSize::XSmall)
+                .tooltip(Tooltip::text("Remove Filter"))
+                .on_click(cx.listener(move |this, _, _, cx| this.remove_column_filter(col, cx))),
+            )
+            .into_any_element()
+    }
+}
@@ -9,6 +9,7 @@ use ui::{DefiniteLength, div, prelude::*};
 
 use crate::{
     CsvPreviewView,
+    renderer::table_cell::CellHighlight,
     settings::RowRenderMechanism,
     types::{AnyColumn, DisplayCellId, DisplayRow},
 };
@@ -36,7 +37,7 @@ impl CsvPreviewView {
         resize_behaviors[0] = TableResizeBehavior::None;
 
         self.create_table_inner(
-            self.engine.contents.rows.len(),
+            self.engine.d2d_mapping().visible_row_count(),
             widths,
             resize_behaviors,
             current_widths,
@@ -155,15 +156,35 @@ impl CsvPreviewView {
 
             let display_cell_id = DisplayCellId::new(display_row, col);
 
-            let cell = div().size_full().whitespace_nowrap().text_ellipsis().child(
-                CsvPreviewView::create_selectable_cell(
-                    display_cell_id,
-                    cell_content,
-                    this.settings.vertical_alignment,
-                    this.settings.font_type,
-                    cx,
-                ),
-            );
+            let cell = match &this.cell_editor {
+                Some(cell_editor) if cell_editor.cell == display_cell_id => {
+                    CsvPreviewView::render_cell_editor(cell_editor, cx)
+                }
+                _ => {
+                    let search = this.engine.search();
+                    let highlight = if search.active_match() == Some(display_cell_id) {
+                        CellHighlight::ActiveMatch
+                    } else if search.is_match(display_cell_id) {
+                        CellHighlight::Match
+                    } else {
+                        CellHighlight::None
+                    };
+
+                    div()
+                        .size_full()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .child(CsvPreviewView::create_selectable_cell(
+                            display_cell_id,
+                            cell_content,
+                            this.settings.vertical_alignment,
+                            this.settings.font_type,
+                            highlight,
+                            cx,
+                        ))
+                        .into_any_element()
+                }
+            };
 
             elements.push(
                 div()
@@ -1,6 +1,7 @@
 //! Table Cell Rendering
 
-use gpui::{AnyElement, ElementId};
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use gpui::{AnyElement, ElementId, Entity, Subscription};
 use ui::{SharedString, Tooltip, div, prelude::*};
 
 use crate::{
@@ -9,6 +10,21 @@ use crate::{
     types::DisplayCellId,
 };
 
+/// Highlight of a cell matching the find query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellHighlight {
+    None,
+    Match,
+    ActiveMatch,
+}
+
+/// In-place editor of a single cell
+pub(crate) struct CellEditor {
+    pub cell: DisplayCellId,
+    pub editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
 impl CsvPreviewView {
     /// Create selectable table cell with mouse event handlers.
     pub fn create_selectable_cell(
@@ -16,6 +32,7 @@ impl CsvPreviewView {
         cell_content: SharedString,
         vertical_alignment: VerticalAlignment,
         font_type: FontType,
+        highlight: CellHighlight,
         cx: &Context<CsvPreviewView>,
     ) -> AnyElement {
         create_table_cell(
@@ -23,11 +40,124 @@ impl CsvPreviewView {
             cell_content,
             vertical_alignment,
             font_type,
+            highlight,
             cx,
         )
-        // Mouse events handlers will be here
+        .on_click(
+            cx.listener(move |this, event: &gpui::ClickEvent, window, cx| {
+                if event.click_count() == 2 {
+                    this.start_cell_edit(display_cell_id, window, cx);
+                }
+            }),
+        )
         .into_any_element()
     }
+
+    /// Render the in-place editor in place of the cell
+    pub(crate) fn render_cell_editor(
+        cell_editor: &CellEditor,
+        cx: &Context<CsvPreviewView>,
+    ) -> AnyElement {
+        div()
+            .key_context("CsvCellEditor")
+            .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                this.commit_cell_edit(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &menu::Cancel, window, cx| {
+                this.cancel_cell_edit(cx);
+                this.focus_handle.focus(window, cx);
+            }))
+            .size_full()
+            .px_1()
+            .border_1()
+            .border_color(cx.theme().colors().border_focused)
+            .bg(cx.theme().colors().editor_background)
+            .child(cell_editor.editor.clone())
+            .into_any_element()
+    }
+
+    /// Start editing the cell in place. Virtual cells (padding of malformed rows) can't be edited.
+    pub(crate) fn start_cell_edit(
+        &mut self,
+        cell: DisplayCellId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(value) = self.engine.cell_value(cell).cloned() else {
+            return;
+        };
+
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(1, 8, window, cx);
+            editor.set_text(value.to_string(), window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(
+            &editor,
+            |this: &mut CsvPreviewView, _editor, event: &EditorEvent, cx| {
+                // Like in spreadsheets, moving focus away keeps the edit
+                if let EditorEvent::Blurred = event {
+                    this.finish_cell_edit(cx);
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window, cx);
+
+        self.cell_editor = Some(CellEditor {
+            cell,
+            editor,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    /// Write the edited value back into the source buffer and focus the table again
+    pub(crate) fn commit_cell_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.finish_cell_edit(cx);
+        self.focus_handle.focus(window, cx);
+    }
+
+    /// Write the edited value back into the source buffer, quoting it when needed
+    fn finish_cell_edit(&mut self, cx: &mut Context<Self>) {
+        let Some(cell_editor) = self.cell_editor.take() else {
+            return;
+        };
+        cx.notify();
+
+        let new_value = cell_editor.editor.read(cx).text(cx);
+        if self
+            .engine
+            .cell_value(cell_editor.cell)
+            .is_some_and(|value| value.as_ref() == new_value)
+        {
+            return;
+        }
+        let Some(edit) = self.engine.cell_edit(cell_editor.cell, &new_value) else {
+            return;
+        };
+        let Some(buffer) = self
+            .editor_state()
+            .editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+        else {
+            return;
+        };
+
+        // Parsing is triggered by the editor's edit event
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(edit.range, edit.new_text)], None, cx);
+        });
+    }
+
+    pub(crate) fn cancel_cell_edit(&mut self, cx: &mut Context<Self>) {
+        if self.cell_editor.take().is_some() {
+            cx.notify();
+        }
+    }
 }
 
 /// Create styled table cell div element.
@@ -36,8 +166,10 @@ fn create_table_cell(
     cell_content: SharedString,
     vertical_alignment: VerticalAlignment,
     font_type: FontType,
+    highlight: CellHighlight,
     cx: &Context<'_, CsvPreviewView>,
 ) -> gpui::Stateful<Div> {
+    let colors = cx.theme().colors();
     div()
         .id(ElementId::NamedInteger(
             format!(
@@ -51,10 +183,14 @@ fn create_table_cell(
         .flex()
         .h_full()
         .px_1()
-        .bg(cx.theme().colors().editor_background)
+        .bg(match highlight {
+            CellHighlight::None => colors.editor_background,
+            CellHighlight::Match => colors.search_match_background,
+            CellHighlight::ActiveMatch => colors.search_active_match_background,
+        })
         .border_b_1()
         .border_r_1()
-        .border_color(cx.theme().colors().border_variant)
+        .border_color(colors.border_variant)
         .map(|div| match vertical_alignment {
             VerticalAlignment::Top => div.items_start(),
             VerticalAlignment::Center => div.items_center(),
@@ -26,10 +26,42 @@ impl CsvPreviewView {
                 FontType::Monospace => div.font_buffer(cx),
             })
             .child(div().child(header_text))
-            .child(h_flex().gap_1().child(self.create_sort_button(cx, col_idx)))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(self.create_filter_button(cx, col_idx))
+                    .child(self.create_sort_button(cx, col_idx)),
+            )
             .into_any_element()
     }
 
+    fn create_filter_button(
+        &self,
+        cx: &mut Context<'_, CsvPreviewView>,
+        col_idx: AnyColumn,
+    ) -> IconButton {
+        let is_filtered = self.engine.applied_filters.contains_key(&col_idx);
+        IconButton::new(
+            ElementId::NamedInteger("filter-button".into(), col_idx.get() as u64),
+            IconName::Filter,
+        )
+        .icon_size(IconSize::XSmall)
+        .style(if is_filtered {
+            ButtonStyle::Filled
+        } else {
+            ButtonStyle::Subtle
+        })
+        .toggle_state(self.filter_inputs.contains_key(&col_idx))
+        .tooltip(Tooltip::text(if is_filtered {
+            "Filtered. Click to remove the filter"
+        } else {
+            "Filter this column"
+        }))
+        .on_click(cx.listener(move |this, _event, window, cx| {
+            this.toggle_column_filter(col_idx, window, cx);
+        }))
+    }
+
     fn create_sort_button(
         &self,
         cx: &mut Context<'_, CsvPreviewView>,
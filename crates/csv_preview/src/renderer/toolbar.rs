@@ -0,0 +1,159 @@
+//! Toolbar rendered above the table with the find bar and per-file view options
+
+use ui::{ContextMenu, DropdownMenu, DropdownStyle, IconPosition, Tooltip, prelude::*};
+
+use crate::{CsvPreviewView, parser::Delimiter};
+
+impl CsvPreviewView {
+    pub(crate) fn render_toolbar(&self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        h_flex()
+            .w_full()
+            .pb_2()
+            .gap_2()
+            .child(self.render_find_bar(cx))
+            .child(div().flex_1())
+            .child(
+                Label::new("Delimiter")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.render_delimiter_dropdown(window, cx))
+            .into_any_element()
+    }
+
+    fn render_delimiter_dropdown(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> DropdownMenu {
+        let current_override = self.settings.delimiter_override;
+        let label: SharedString = match current_override {
+            Some(delimiter) => delimiter.label().into(),
+            None => format!("Auto ({})", self.engine.contents.delimiter.label()).into(),
+        };
+
+        let view = cx.entity().downgrade();
+        let menu = ContextMenu::build(window, cx, move |mut menu, _, _| {
+            menu = menu.toggleable_entry(
+                "Auto",
+                current_override.is_none(),
+                IconPosition::Start,
+                None,
+                {
+                    let view = view.clone();
+                    move |_, cx| {
+                        view.update(cx, |this, cx| this.set_delimiter_override(None, cx))
+                            .ok();
+                    }
+                },
+            );
+            menu = menu.separator();
+            for delimiter in Delimiter::ALL {
+                let view = view.clone();
+                menu = menu.toggleable_entry(
+                    delimiter.label(),
+                    current_override == Some(delimiter),
+                    IconPosition::Start,
+                    None,
+                    move |_, cx| {
+                        view.update(cx, |this, cx| {
+                            this.set_delimiter_override(Some(delimiter), cx)
+                        })
+                        .ok();
+                    },
+                );
+            }
+            menu
+        });
+
+        DropdownMenu::new("csv-delimiter-dropdown", label, menu)
+            .style(DropdownStyle::Outlined)
+            .trigger_size(ButtonSize::Compact)
+            .trigger_tooltip(Tooltip::text(
+                "Field delimiter. Auto detects it from the file contents",
+            ))
+    }
+
+    fn render_find_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let search = self.engine.search();
+        let match_count = search.matches().len();
+        let has_query = !self.find_editor.read(cx).text(cx).is_empty();
+        let match_label: SharedString = match search.active_match_index() {
+            Some(ix) => format!("{}/{match_count}", ix + 1).into(),
+            None if has_query => "No matches".into(),
+            None => SharedString::default(),
+        };
+
+        h_flex()
+            .key_context("CsvFindBar")
+            .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                this.select_next_match(window.modifiers().shift, cx)
+            }))
+            .on_action(cx.listener(|this, _: &menu::Cancel, window, cx| {
+                this.focus_handle.focus(window, cx);
+            }))
+            .gap_1()
+            .child(
+                h_flex()
+                    .w_64()
+                    .px_2()
+                    .py_0p5()
+                    .gap_1()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.find_editor.clone()),
+            )
+            .child(
+                IconButton::new("csv-find-previous", IconName::ChevronLeft)
+                    .icon_size(IconSize::Small)
+                    .disabled(match_count == 0)
+                    .tooltip(Tooltip::text("Previous Match"))
+                    .on_click(cx.listener(|this, _, _, cx| this.select_next_match(true, cx))),
+            )
+            .child(
+                IconButton::new("csv-find-next", IconName::ChevronRight)
+                    .icon_size(IconSize::Small)
+                    .disabled(match_count == 0)
+                    .tooltip(Tooltip::text("Next Match"))
+                    .on_click(cx.listener(|this, _, _, cx| this.select_next_match(false, cx))),
+            )
+            .child(
+                Label::new(match_label)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    /// Move to the next (or previous) cell matching the find query and scroll it into view
+    pub(crate) fn select_next_match(&mut self, backwards: bool, cx: &mut Context<Self>) {
+        self.engine.search_mut().select_next(backwards);
+        self.scroll_to_active_match();
+        cx.notify();
+    }
+
+    pub(crate) fn scroll_to_active_match(&self) {
+        if let Some(cell) = self.engine.search().active_match() {
+            self.list_state.scroll_to_reveal_item(*cell.row);
+        }
+    }
+
+    /// Set the delimiter for this file and re-parse it. `None` restores automatic detection.
+    pub(crate) fn set_delimiter_override(
+        &mut self,
+        delimiter: Option<Delimiter>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.settings.delimiter_override == delimiter {
+            return;
+        }
+        self.settings.delimiter_override = delimiter;
+        self.parse_csv_from_active_editor(false, cx);
+        cx.notify();
+    }
+}
@@ -1,3 +1,5 @@
+use crate::parser::Delimiter;
+
 #[derive(Default, Clone, Copy)]
 pub enum RowRenderMechanism {
     /// Default behaviour
@@ -43,4 +45,6 @@ pub(crate) struct CsvPreviewSettings {
     pub(crate) numbering_type: RowIdentifiers,
     pub(crate) show_debug_info: bool,
     pub(crate) multiline_cells_enabled: bool,
+    /// Delimiter chosen in the toolbar. `None` means it is detected from the file contents
+    pub(crate) delimiter_override: Option<Delimiter>,
 }
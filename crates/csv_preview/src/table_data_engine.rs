@@ -5,21 +5,34 @@
 //!
 //! It's designed to contain core logic of operations without relying on `CsvPreviewView`, context or window handles.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use ui::table_row::TableRow;
 
 use crate::{
-    table_data_engine::sorting_by_column::{AppliedSorting, sort_data_rows},
-    types::{DataRow, DisplayRow, TableCell, TableLikeContent},
+    table_data_engine::{
+        filtering_by_column::{ColumnFilter, filter_data_rows},
+        find_in_table::TableSearch,
+        sorting_by_column::{AppliedSorting, sort_data_rows},
+    },
+    types::{AnyColumn, DataRow, DisplayRow, TableCell, TableLikeContent},
 };
 
+pub mod cell_editing;
+pub mod filtering_by_column;
+pub mod find_in_table;
 pub mod sorting_by_column;
 
 #[derive(Default)]
 pub(crate) struct TableDataEngine {
     pub applied_sorting: Option<AppliedSorting>,
+    /// Filters of columns, all of them have to match for a row to be displayed
+    pub applied_filters: HashMap<AnyColumn, ColumnFilter>,
     d2d_mapping: DisplayToDataMapping,
+    search: TableSearch,
     pub contents: TableLikeContent,
 }
 
@@ -28,27 +41,50 @@ impl TableDataEngine {
         &self.d2d_mapping
     }
 
+    pub(crate) fn search(&self) -> &TableSearch {
+        &self.search
+    }
+
+    pub(crate) fn search_mut(&mut self) -> &mut TableSearch {
+        &mut self.search
+    }
+
+    pub(crate) fn set_search_query(&mut self, query: &str) {
+        self.search.set_query(query);
+        self.search
+            .update_matches(&self.contents, &self.d2d_mapping);
+    }
+
     pub(crate) fn apply_sort(&mut self) {
         self.d2d_mapping
             .apply_sorting(self.applied_sorting, &self.contents.rows);
         self.d2d_mapping.merge_mappings();
+        self.search
+            .update_matches(&self.contents, &self.d2d_mapping);
     }
 
     /// Applies sorting and filtering to the data and produces display to data mapping
     pub(crate) fn calculate_d2d_mapping(&mut self) {
         self.d2d_mapping
             .apply_sorting(self.applied_sorting, &self.contents.rows);
+        self.d2d_mapping
+            .apply_filtering(&self.applied_filters, &self.contents.rows);
         self.d2d_mapping.merge_mappings();
+        self.search
+            .update_matches(&self.contents, &self.d2d_mapping);
     }
 }
 
 /// Relation of Display (rendered) rows to Data (src) rows with applied transformations
 /// Transformations applied:
 /// - sorting by column
+/// - filtering by column
 #[derive(Debug, Default)]
 pub struct DisplayToDataMapping {
     /// All rows sorted, regardless of applied filtering. Applied every time sorting changes
     pub sorted_rows: Vec<DataRow>,
+    /// Rows hidden by filters, regardless of sorting. Applied every time filters change
+    pub filtered_out_rows: HashSet<DataRow>,
     /// Filtered and sorted rows. Computed cheaply from `sorted_mapping` and `filtered_out_rows`
     pub mapping: Arc<HashMap<DisplayRow, DataRow>>,
 }
@@ -77,11 +113,21 @@ impl DisplayToDataMapping {
         self.sorted_rows = sorted_rows;
     }
 
+    /// Computes filtering
+    fn apply_filtering(
+        &mut self,
+        filters: &HashMap<AnyColumn, ColumnFilter>,
+        rows: &[TableRow<TableCell>],
+    ) {
+        self.filtered_out_rows = filter_data_rows(rows, filters);
+    }
+
     /// Take pre-computed sorting and filtering results, and apply them to the mapping
     fn merge_mappings(&mut self) {
         self.mapping = Arc::new(
             self.sorted_rows
                 .iter()
+                .filter(|data| !self.filtered_out_rows.contains(*data))
                 .enumerate()
                 .map(|(display, data)| (DisplayRow(display), *data))
                 .collect(),
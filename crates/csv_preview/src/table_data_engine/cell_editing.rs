@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use text::Anchor;
+
+use crate::{
+    parser::Delimiter,
+    table_data_engine::TableDataEngine,
+    types::{DisplayCellId, TableCell},
+};
+
+/// Edit of the source buffer which replaces content of a single cell
+#[derive(Debug, Clone)]
+pub struct CellEdit {
+    /// Range of the whole cell in the source buffer, including surrounding quotes
+    pub range: Range<Anchor>,
+    /// Encoded cell value, quoted when needed
+    pub new_text: String,
+}
+
+impl TableDataEngine {
+    /// Value of the displayed cell, if the cell exists in the source buffer
+    pub(crate) fn cell_value(&self, cell: DisplayCellId) -> Option<&ui::SharedString> {
+        let data_row = self.d2d_mapping().get_data_row(cell.row)?;
+        self.contents
+            .get_row(data_row)?
+            .get(cell.col)?
+            .display_value()
+    }
+
+    /// Produce buffer edit which sets the displayed cell to `new_value`.
+    ///
+    /// Returns `None` for virtual cells (padding of malformed rows), which have no position in the buffer.
+    pub(crate) fn cell_edit(&self, cell: DisplayCellId, new_value: &str) -> Option<CellEdit> {
+        let data_row = self.d2d_mapping().get_data_row(cell.row)?;
+        match self.contents.get_row(data_row)?.get(cell.col)? {
+            TableCell::Real { position, .. } => Some(CellEdit {
+                range: position.start..position.end,
+                new_text: quote_field(new_value, self.contents.delimiter),
+            }),
+            TableCell::Virtual => None,
+        }
+    }
+}
+
+/// Encode a value as a field of a delimiter-separated file.
+///
+/// Values containing the delimiter, quotes, line breaks or leading/trailing whitespace
+/// are wrapped in quotes, with inner quotes escaped by doubling them.
+pub fn quote_field(value: &str, delimiter: Delimiter) -> String {
+    let needs_quotes = value
+        .chars()
+        .any(|ch| ch == delimiter.as_char() || matches!(ch, '"' | '\n' | '\r'))
+        || value.trim() != value;
+
+    if needs_quotes {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableLikeContent;
+
+    #[test]
+    fn test_quote_field() {
+        assert_eq!(quote_field("plain", Delimiter::Comma), "plain");
+        assert_eq!(quote_field("a,b", Delimiter::Comma), "\"a,b\"");
+        assert_eq!(quote_field("a,b", Delimiter::Tab), "a,b");
+        assert_eq!(quote_field("a\tb", Delimiter::Tab), "\"a\tb\"");
+        assert_eq!(
+            quote_field("say \"hi\"", Delimiter::Comma),
+            "\"say \"\"hi\"\"\""
+        );
+        assert_eq!(quote_field("two\nlines", Delimiter::Pipe), "\"two\nlines\"");
+        assert_eq!(quote_field(" padded", Delimiter::Comma), "\" padded\"");
+        assert_eq!(quote_field("", Delimiter::Comma), "");
+    }
+
+    #[test]
+    fn test_cell_edit_round_trip() {
+        let text = "Name,Note\nJohn,\"hello, world\"\nJane,plain";
+        let mut buffer = text::Buffer::new(
+            text::ReplicaId::LOCAL,
+            text::BufferId::new(1).unwrap(),
+            text.to_string(),
+        );
+        let mut engine = TableDataEngine {
+            contents: crate::parser::from_buffer(buffer.snapshot(), None, Delimiter::Comma),
+            ..Default::default()
+        };
+        engine.calculate_d2d_mapping();
+
+        assert_eq!(
+            engine
+                .cell_value(DisplayCellId::new(0, 1))
+                .unwrap()
+                .as_ref(),
+            "hello, world"
+        );
+
+        let edit = engine
+            .cell_edit(DisplayCellId::new(0, 1), "say \"bye\"")
+            .unwrap();
+        buffer.edit([(edit.range, edit.new_text)]);
+        let edit = engine.cell_edit(DisplayCellId::new(1, 1), "a,b").unwrap();
+        buffer.edit([(edit.range, edit.new_text)]);
+
+        assert_eq!(
+            buffer.text(),
+            "Name,Note\nJohn,\"say \"\"bye\"\"\"\nJane,\"a,b\""
+        );
+
+        let reparsed = TableLikeContent::from_str(buffer.text());
+        assert_eq!(
+            reparsed.rows[0][1].display_value().unwrap().as_ref(),
+            "say \"bye\""
+        );
+        assert_eq!(reparsed.rows[1][1].display_value().unwrap().as_ref(), "a,b");
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use ui::table_row::TableRow;
+
+use crate::types::{AnyColumn, DataRow, TableCell};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NumericComparison {
+    Eq,
+    NotEq,
+    Less,
+    LessOrEq,
+    Greater,
+    GreaterOrEq,
+}
+
+impl NumericComparison {
+    fn matches(self, value: f64, operand: f64) -> bool {
+        match self {
+            NumericComparison::Eq => value == operand,
+            NumericComparison::NotEq => value != operand,
+            NumericComparison::Less => value < operand,
+            NumericComparison::LessOrEq => value <= operand,
+            NumericComparison::Greater => value > operand,
+            NumericComparison::GreaterOrEq => value >= operand,
+        }
+    }
+}
+
+/// Condition a cell value has to satisfy for its row to stay visible
+#[derive(Debug, Clone)]
+pub enum ColumnFilter {
+    /// Case-insensitive substring match. Stored lowercased
+    Contains(String),
+    Regex(Regex),
+    Numeric {
+        comparison: NumericComparison,
+        operand: f64,
+    },
+}
+
+impl ColumnFilter {
+    /// Parse filter expression typed by the user into the column filter input.
+    ///
+    /// Supported syntax:
+    /// - `/pattern/` - regular expression
+    /// - `>10`, `>=10`, `<10`, `<=10`, `=10`, `!=10` - numeric comparisons
+    /// - anything else - case-insensitive "contains" match
+    ///
+    /// Returns `Ok(None)` for an empty expression (no filtering).
+    pub fn parse(expression: &str) -> anyhow::Result<Option<Self>> {
+        let expression = expression.trim();
+        if expression.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(pattern) = expression
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            return Ok(Some(ColumnFilter::Regex(Regex::new(pattern)?)));
+        }
+
+        // Two-character operators go first so `>=` isn't parsed as `>` followed by `=10`
+        const OPERATORS: [(&str, NumericComparison); 6] = [
+            (">=", NumericComparison::GreaterOrEq),
+            ("<=", NumericComparison::LessOrEq),
+            ("!=", NumericComparison::NotEq),
+            (">", NumericComparison::Greater),
+            ("<", NumericComparison::Less),
+            ("=", NumericComparison::Eq),
+        ];
+        for (operator, comparison) in OPERATORS {
+            if let Some(operand) = expression.strip_prefix(operator) {
+                let operand = operand.trim();
+                let operand = operand
+                    .parse::<f64>()
+                    .map_err(|_| anyhow::anyhow!("Expected a number after `{operator}`"))?;
+                return Ok(Some(ColumnFilter::Numeric {
+                    comparison,
+                    operand,
+                }));
+            }
+        }
+
+        Ok(Some(ColumnFilter::Contains(expression.to_lowercase())))
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            ColumnFilter::Contains(needle) => value.to_lowercase().contains(needle.as_str()),
+            ColumnFilter::Regex(regex) => regex.is_match(value),
+            ColumnFilter::Numeric {
+                comparison,
+                operand,
+            } => value
+                .trim()
+                .parse::<f64>()
+                .is_ok_and(|value| comparison.matches(value, *operand)),
+        }
+    }
+}
+
+/// Returns rows which don't satisfy every applied filter
+pub fn filter_data_rows(
+    content_rows: &[TableRow<TableCell>],
+    filters: &HashMap<AnyColumn, ColumnFilter>,
+) -> HashSet<DataRow> {
+    if filters.is_empty() {
+        return HashSet::default();
+    }
+
+    content_rows
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            !filters.iter().all(|(col, filter)| {
+                // Virtual cells are treated as empty values
+                let value = row
+                    .get(*col)
+                    .and_then(|cell| cell.display_value())
+                    .map(|value| value.as_str())
+                    .unwrap_or("");
+                filter.matches(value)
+            })
+        })
+        .map(|(data_row, _)| DataRow(data_row))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TableLikeContent;
+
+    #[test]
+    fn test_parse_filter_expressions() {
+        assert!(ColumnFilter::parse("  ").unwrap().is_none());
+        assert!(matches!(
+            ColumnFilter::parse("Foo").unwrap(),
+            Some(ColumnFilter::Contains(needle)) if needle == "foo"
+        ));
+        assert!(matches!(
+            ColumnFilter::parse("/^a.c$/").unwrap(),
+            Some(ColumnFilter::Regex(_))
+        ));
+        assert!(matches!(
+            ColumnFilter::parse(">= 10").unwrap(),
+            Some(ColumnFilter::Numeric {
+                comparison: NumericComparison::GreaterOrEq,
+                operand,
+            }) if operand == 10.
+        ));
+        assert!(matches!(
+            ColumnFilter::parse("!=2.5").unwrap(),
+            Some(ColumnFilter::Numeric {
+                comparison: NumericComparison::NotEq,
+                operand,
+            }) if operand == 2.5
+        ));
+        assert!(ColumnFilter::parse(">abc").is_err());
+        assert!(ColumnFilter::parse("/(/").is_err());
+    }
+
+    #[test]
+    fn test_filter_matching() {
+        let contains = ColumnFilter::parse("york").unwrap().unwrap();
+        assert!(contains.matches("New York"));
+        assert!(!contains.matches("Boston"));
+
+        let regex = ColumnFilter::parse("/^J/").unwrap().unwrap();
+        assert!(regex.matches("John"));
+        assert!(!regex.matches("Ann J"));
+
+        let numeric = ColumnFilter::parse("<30").unwrap().unwrap();
+        assert!(numeric.matches("25"));
+        assert!(numeric.matches(" 29.9 "));
+        assert!(!numeric.matches("30"));
+        assert!(!numeric.matches("not a number"));
+    }
+
+    #[test]
+    fn test_filter_data_rows_combines_filters() {
+        let content = TableLikeContent::from_str(
+            "Name,Age,City\nJohn,30,New York\nJane,25,Los Angeles\nJoe,41,York".to_string(),
+        );
+
+        let mut filters = HashMap::default();
+        filters.insert(AnyColumn(2), ColumnFilter::parse("york").unwrap().unwrap());
+        let filtered_out = filter_data_rows(&content.rows, &filters);
+        assert_eq!(filtered_out, HashSet::from_iter([DataRow(1)]));
+
+        filters.insert(AnyColumn(1), ColumnFilter::parse(">35").unwrap().unwrap());
+        let filtered_out = filter_data_rows(&content.rows, &filters);
+        assert_eq!(filtered_out, HashSet::from_iter([DataRow(0), DataRow(1)]));
+    }
+}
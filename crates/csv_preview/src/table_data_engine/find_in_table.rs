@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use crate::{
+    table_data_engine::DisplayToDataMapping,
+    types::{AnyColumn, DisplayCellId, DisplayRow, TableLikeContent},
+};
+
+/// State of the find bar: query and the cells matching it
+#[derive(Debug, Default)]
+pub struct TableSearch {
+    /// Lowercased query. Empty query matches nothing
+    query: String,
+    /// Matching cells, ordered by display row and then by column
+    matches: Vec<DisplayCellId>,
+    /// Same cells as `matches`, for fast lookup during rendering
+    match_set: HashSet<DisplayCellId>,
+    /// Index into `matches` of the currently selected match
+    active_match: Option<usize>,
+}
+
+impl TableSearch {
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_lowercase();
+        self.active_match = None;
+    }
+
+    pub fn matches(&self) -> &[DisplayCellId] {
+        &self.matches
+    }
+
+    pub fn is_match(&self, cell: DisplayCellId) -> bool {
+        self.match_set.contains(&cell)
+    }
+
+    pub fn active_match(&self) -> Option<DisplayCellId> {
+        self.matches.get(self.active_match?).copied()
+    }
+
+    pub fn active_match_index(&self) -> Option<usize> {
+        self.active_match
+    }
+
+    /// Recompute matches against currently visible rows.
+    /// Needs to be called whenever the query, contents or display mapping change.
+    pub fn update_matches(&mut self, contents: &TableLikeContent, mapping: &DisplayToDataMapping) {
+        let previous_active = self.active_match();
+        self.matches.clear();
+        self.match_set.clear();
+
+        if !self.query.is_empty() {
+            for display_row in (0..mapping.visible_row_count()).map(DisplayRow) {
+                let Some(row) = mapping
+                    .get_data_row(display_row)
+                    .and_then(|data_row| contents.get_row(data_row))
+                else {
+                    continue;
+                };
+                for (col, cell) in row.as_slice().iter().enumerate() {
+                    let is_match = cell
+                        .display_value()
+                        .is_some_and(|value| value.to_lowercase().contains(&self.query));
+                    if is_match {
+                        self.matches
+                            .push(DisplayCellId::new(display_row, AnyColumn(col)));
+                    }
+                }
+            }
+        }
+
+        self.match_set.extend(self.matches.iter().copied());
+        self.active_match = previous_active
+            .and_then(|previous| self.matches.iter().position(|cell| *cell == previous))
+            .or_else(|| (!self.matches.is_empty()).then_some(0));
+    }
+
+    /// Move the active match forward (or backward), wrapping around. Returns the new active match
+    pub fn select_next(&mut self, backwards: bool) -> Option<DisplayCellId> {
+        if self.matches.is_empty() {
+            self.active_match = None;
+            return None;
+        }
+        let len = self.matches.len();
+        let next = match self.active_match {
+            None => 0,
+            Some(ix) if backwards => (ix + len - 1) % len,
+            Some(ix) => (ix + 1) % len,
+        };
+        self.active_match = Some(next);
+        self.active_match()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_data_engine::TableDataEngine;
+
+    #[test]
+    fn test_find_matches_in_visible_rows() {
+        let mut engine = TableDataEngine {
+            contents: TableLikeContent::from_str(
+                "Name,City\nJohn,New York\nJane,Boston\nJoe,york".to_string(),
+            ),
+            ..Default::default()
+        };
+        engine.calculate_d2d_mapping();
+        engine.set_search_query("YORK");
+
+        assert_eq!(
+            engine.search().matches(),
+            &[DisplayCellId::new(0, 1), DisplayCellId::new(2, 1)]
+        );
+        assert_eq!(
+            engine.search().active_match(),
+            Some(DisplayCellId::new(0, 1))
+        );
+
+        assert_eq!(
+            engine.search_mut().select_next(false),
+            Some(DisplayCellId::new(2, 1))
+        );
+        assert_eq!(
+            engine.search_mut().select_next(false),
+            Some(DisplayCellId::new(0, 1))
+        );
+        assert_eq!(
+            engine.search_mut().select_next(true),
+            Some(DisplayCellId::new(2, 1))
+        );
+
+        engine.set_search_query("");
+        assert!(engine.search().matches().is_empty());
+        assert_eq!(engine.search().active_match(), None);
+    }
+}
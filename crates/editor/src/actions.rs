@@ -335,6 +335,67 @@ pub struct GoToPreviousDiagnostic {
     pub severity: GoToDiagnosticSeverityFilter,
 }
 
+/// Toggles a bookmark on each cursor's line, or puts a named bookmark on the newest cursor's line.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct ToggleBookmark {
+    /// The bookmark's name. A named bookmark is moved to the cursor's line when it's elsewhere in
+    /// the project.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Goes to the bookmark with the given name.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct GoToBookmark {
+    pub name: String,
+}
+
+/// Plays back a keyboard macro.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct PlayMacro {
+    /// The name of a macro saved in `macros.json`. The last recorded macro is played when this
+    /// is omitted.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// How many times to play the macro in a row.
+    ///
+    /// Default: 1
+    #[serde(default)]
+    pub times: Option<usize>,
+    /// Where the macro is played.
+    #[serde(default)]
+    pub target: MacroTarget,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroTarget {
+    /// Play the macro once, with all of the cursors.
+    #[default]
+    Cursors,
+    /// Play the macro separately for each selection, starting from that selection alone.
+    EachSelection,
+    /// Play the macro separately for each line touched by a selection, starting from the
+    /// beginning of that line.
+    EachLine,
+}
+
+/// Saves the last recorded keyboard macro to `macros.json`, so it can be bound in the keymap.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct SaveMacro {
+    /// The macro's name. You're asked for one when this is omitted.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 /// Adds a cursor above the current selection.
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -412,6 +473,8 @@ actions!(
         CancelFlycheck,
         /// Cancels pending language server work.
         CancelLanguageServerWork,
+        /// Removes the bookmarks in the current file.
+        ClearBookmarks,
         /// Clears flycheck results.
         ClearFlycheck,
         /// Confirms the rename operation.
@@ -462,6 +525,9 @@ actions!(
         CopyFileLocation,
         /// Copies the highlighted text as JSON.
         CopyHighlightJson,
+        /// Copies selected text to the clipboard along with HTML and RTF renderings of it
+        /// in the current theme's syntax highlighting colors.
+        CopyWithFormatting,
         /// Copies the current file name to the clipboard.
         CopyFileName,
         /// Copies the file name without extension to the clipboard.
@@ -493,6 +559,9 @@ actions!(
         CollapseAllDiffHunks,
         /// Expands macros recursively at cursor position.
         ExpandMacroRecursively,
+        /// Saves the editor's contents, in the current theme's syntax highlighting colors,
+        /// to an HTML file.
+        ExportToHtml,
         /// Finds the next match in the search.
         FindNextMatch,
         /// Finds the previous match in the search.
@@ -562,10 +631,14 @@ actions!(
         GoToImplementation,
         /// Goes to implementation in a split pane.
         GoToImplementationSplit,
+        /// Goes to the next bookmark in the project.
+        GoToNextBookmark,
         /// Goes to the next change in the file.
         GoToNextChange,
         /// Goes to the parent module of the current file.
         GoToParentModule,
+        /// Goes to the previous bookmark in the project.
+        GoToPreviousBookmark,
         /// Goes to the previous change in the file.
         GoToPreviousChange,
         /// Goes to the next reference to the symbol under the cursor.
@@ -837,6 +910,8 @@ actions!(
         ToggleLineNumbers,
         /// Toggles the minimap display.
         ToggleMinimap,
+        /// Starts recording a keyboard macro, or stops the recording in progress.
+        ToggleMacroRecording,
         /// Swaps the start and end of the current selection.
         SwapSelectionEnds,
         /// Sets a mark at the current position.
@@ -0,0 +1,497 @@
+use std::path::Path;
+
+use collections::HashMap;
+use gpui::{App, Context, Entity, SharedString, Window};
+use language::Buffer;
+use multi_buffer::{Anchor, ToPoint as _};
+use project::bookmark_store::{BookmarkEntry, BookmarkStore};
+use text::{Bias, Point};
+use ui::{IconButton, Tooltip, prelude::*};
+use workspace::Workspace;
+
+use crate::{
+    ClearBookmarks, DisplayPoint, Editor, GoToBookmark, GoToNextBookmark, GoToPreviousBookmark,
+    SelectionEffects, ToggleBookmark,
+    display_map::{DisplayRow, ToDisplayPoint as _},
+    scroll::Autoscroll,
+};
+
+impl Editor {
+    pub(crate) fn bookmark_store(&self, cx: &App) -> Option<Entity<BookmarkStore>> {
+        if !self.mode.is_full() {
+            return None;
+        }
+        Some(self.project.as_ref()?.read(cx).bookmark_store())
+    }
+
+    /// The start of each line with a cursor on it, once per line.
+    fn bookmark_positions_at_cursors(&self, cx: &App) -> Vec<(Entity<Buffer>, text::Anchor)> {
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut positions = Vec::new();
+        let mut seen_rows = Vec::new();
+        for selection in self.selections.disjoint_anchors_arc().iter() {
+            let row = selection.head().to_point(&snapshot).row;
+            if seen_rows.contains(&row) {
+                continue;
+            }
+            seen_rows.push(row);
+            let Some((buffer, point, _)) =
+                multi_buffer.point_to_buffer_point(Point::new(row, 0), cx)
+            else {
+                continue;
+            };
+            let position = buffer.read(cx).anchor_after(point);
+            positions.push((buffer, position));
+        }
+        positions
+    }
+
+    pub fn toggle_bookmark(
+        &mut self,
+        action: &ToggleBookmark,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return;
+        };
+        if let Some(name) = action.name.as_ref().filter(|name| !name.is_empty()) {
+            let newest = self.selections.newest_anchor().head();
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let row = newest.to_point(&snapshot).row;
+            let Some((buffer, point, _)) = self
+                .buffer
+                .read(cx)
+                .point_to_buffer_point(Point::new(row, 0), cx)
+            else {
+                return;
+            };
+            let position = buffer.read(cx).anchor_after(point);
+            let name = SharedString::from(name.clone());
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.set_named_bookmark(buffer, position, name, cx);
+            });
+        } else {
+            let positions = self.bookmark_positions_at_cursors(cx);
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                for (buffer, position) in positions {
+                    bookmark_store.toggle_bookmark(buffer, position, cx);
+                }
+            });
+        }
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, _: &ClearBookmarks, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let buffers = self.buffer.read(cx).all_buffers();
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            for buffer in &buffers {
+                bookmark_store.clear_bookmarks_in_buffer(buffer, cx);
+            }
+        });
+        cx.notify();
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_adjacent_bookmark(BookmarkDirection::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_adjacent_bookmark(BookmarkDirection::Previous, window, cx);
+    }
+
+    pub fn go_to_bookmark(
+        &mut self,
+        action: &GoToBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let Some(entry) = bookmark_store.read(cx).bookmark_named(&action.name, cx) else {
+            return;
+        };
+        self.go_to_bookmark_entry(&entry, window, cx);
+    }
+
+    fn go_to_adjacent_bookmark(
+        &mut self,
+        direction: BookmarkDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let entries = bookmark_store.read(cx).all_bookmarks(cx);
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest_anchor().head().to_point(&snapshot);
+        let cursor = self
+            .buffer
+            .read(cx)
+            .point_to_buffer_point(head, cx)
+            .and_then(|(buffer, point, _)| {
+                Some((BookmarkStore::abs_path_from_buffer(&buffer, cx)?, point.row))
+            });
+
+        let locations = entries
+            .iter()
+            .map(|entry| (entry.abs_path.as_ref(), entry.row))
+            .collect::<Vec<_>>();
+        let Some(ix) = adjacent_bookmark(
+            &locations,
+            cursor.as_ref().map(|(path, row)| (path.as_ref(), *row)),
+            direction,
+        ) else {
+            return;
+        };
+        self.go_to_bookmark_entry(&entries[ix], window, cx);
+    }
+
+    /// Moves the cursor to the bookmark, opening its file when this editor doesn't show it.
+    fn go_to_bookmark_entry(
+        &mut self,
+        entry: &BookmarkEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let point = Point::new(entry.row, 0);
+        if let Some(anchor) = self
+            .buffer
+            .read(cx)
+            .buffer_point_to_anchor(&entry.buffer, point, cx)
+        {
+            self.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()).nav_history(true),
+                window,
+                cx,
+                |selections| selections.select_anchor_ranges([anchor..anchor]),
+            );
+            return;
+        }
+
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let entry = entry.clone();
+        window.defer(cx, move |window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                open_bookmark(workspace, &entry, window, cx);
+            });
+        });
+    }
+
+    /// The bookmarks on the display rows in the range, with the anchors they're at.
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: std::ops::Range<DisplayRow>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> HashMap<DisplayRow, (Anchor, Option<SharedString>)> {
+        let mut bookmark_rows = HashMap::default();
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return bookmark_rows;
+        };
+
+        let snapshot = self.snapshot(window, cx);
+        let multi_buffer_snapshot = snapshot.buffer_snapshot();
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+
+        for (buffer_snapshot, range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(range.start..=range.end)
+        {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let bookmarks = bookmark_store.read(cx).bookmarks(
+                &buffer,
+                Some(
+                    buffer_snapshot.anchor_before(range.start)
+                        ..buffer_snapshot.anchor_after(range.end),
+                ),
+                buffer_snapshot,
+                cx,
+            );
+            for bookmark in bookmarks {
+                let anchor = Anchor::in_buffer(excerpt_id, bookmark.position);
+                let display_row = anchor
+                    .to_point(multi_buffer_snapshot)
+                    .to_display_point(&snapshot)
+                    .row();
+                bookmark_rows.insert(display_row, (anchor, bookmark.name.clone()));
+            }
+        }
+
+        bookmark_rows
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        position: Anchor,
+        name: Option<SharedString>,
+        row: DisplayRow,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let focus_handle = self.focus_handle.clone();
+        let title = name
+            .map(|name| SharedString::from(format!("Bookmark: {name}")))
+            .unwrap_or_else(|| SharedString::from("Bookmark"));
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _, window, cx| {
+                window.focus(&editor.focus_handle(cx), cx);
+                editor.remove_bookmark_at(position, cx);
+            }))
+            .tooltip(move |_window, cx| {
+                Tooltip::with_meta_in(
+                    title.clone(),
+                    Some(&ToggleBookmark::default()),
+                    "Click to remove",
+                    &focus_handle,
+                    cx,
+                )
+            })
+    }
+
+    fn remove_bookmark_at(&mut self, position: Anchor, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store(cx) else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer_for_anchor(position, cx) else {
+            return;
+        };
+        let Some(abs_path) = BookmarkStore::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.remove_bookmark(&abs_path, position.text_anchor, cx);
+        });
+        cx.notify();
+    }
+}
+
+/// Opens the bookmark's file in the workspace's active pane, with the cursor on the bookmark.
+pub fn open_bookmark(
+    workspace: &mut Workspace,
+    bookmark: &BookmarkEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let pane = workspace.active_pane().clone();
+    let editor = workspace.open_project_item::<Editor>(
+        pane,
+        bookmark.buffer.clone(),
+        true,
+        true,
+        false,
+        false,
+        window,
+        cx,
+    );
+    let row = bookmark.bookmark.row(&bookmark.buffer.read(cx).snapshot());
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()).nav_history(true),
+            window,
+            cx,
+            |selections| selections.select_ranges([Point::new(row, 0)..Point::new(row, 0)]),
+        );
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BookmarkDirection {
+    Next,
+    Previous,
+}
+
+/// Returns the index of the bookmark after or before the cursor, wrapping around at the ends.
+/// The locations must be sorted by path and then by row.
+fn adjacent_bookmark(
+    locations: &[(&Path, u32)],
+    cursor: Option<(&Path, u32)>,
+    direction: BookmarkDirection,
+) -> Option<usize> {
+    if locations.is_empty() {
+        return None;
+    }
+    let Some(cursor) = cursor else {
+        return Some(match direction {
+            BookmarkDirection::Next => 0,
+            BookmarkDirection::Previous => locations.len() - 1,
+        });
+    };
+    match direction {
+        BookmarkDirection::Next => Some(
+            locations
+                .iter()
+                .position(|location| *location > cursor)
+                .unwrap_or(0),
+        ),
+        BookmarkDirection::Previous => Some(
+            locations
+                .iter()
+                .rposition(|location| *location < cursor)
+                .unwrap_or(locations.len() - 1),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    #[test]
+    fn test_adjacent_bookmark() {
+        let a = Path::new("/root/a.rs");
+        let b = Path::new("/root/b.rs");
+        let locations = [(a, 2), (a, 7), (b, 0)];
+
+        let next = |cursor| adjacent_bookmark(&locations, cursor, BookmarkDirection::Next);
+        let previous = |cursor| adjacent_bookmark(&locations, cursor, BookmarkDirection::Previous);
+
+        assert_eq!(next(Some((a, 0))), Some(0));
+        assert_eq!(next(Some((a, 2))), Some(1));
+        assert_eq!(next(Some((a, 9))), Some(2));
+        assert_eq!(next(Some((b, 0))), Some(0));
+        assert_eq!(next(None), Some(0));
+
+        assert_eq!(previous(Some((a, 7))), Some(0));
+        assert_eq!(previous(Some((b, 3))), Some(2));
+        assert_eq!(previous(Some((a, 1))), Some(2));
+        assert_eq!(previous(None), Some(2));
+
+        assert_eq!(adjacent_bookmark(&[], None, BookmarkDirection::Next), None);
+    }
+
+    #[gpui::test]
+    async fn test_toggle_and_go_to_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state(indoc! {"
+            oneˇ
+            two
+            three
+            four
+        "});
+
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_bookmark(&ToggleBookmark::default(), window, cx);
+        });
+        cx.set_selections_state(indoc! {"
+            one
+            two
+            thˇree
+            four
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_bookmark(&ToggleBookmark::default(), window, cx);
+        });
+        cx.set_selections_state(indoc! {"
+            one
+            tˇwo
+            three
+            four
+        "});
+
+        cx.update_editor(|editor, window, cx| {
+            editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            one
+            two
+            ˇthree
+            four
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            ˇone
+            two
+            three
+            four
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.go_to_previous_bookmark(&GoToPreviousBookmark, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            one
+            two
+            ˇthree
+            four
+        "});
+
+        // Bookmarks move with their lines.
+        cx.set_selections_state(indoc! {"
+            ˇone
+            two
+            three
+            four
+        "});
+        cx.simulate_input("zero\n");
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_bookmark(
+                &ToggleBookmark {
+                    name: Some("start".into()),
+                },
+                window,
+                cx,
+            );
+            editor.move_to_end(&crate::MoveToEnd, window, cx);
+            editor.go_to_bookmark(
+                &GoToBookmark {
+                    name: "start".into(),
+                },
+                window,
+                cx,
+            );
+        });
+        cx.assert_editor_state(indoc! {"
+            zero
+            ˇone
+            two
+            three
+            four
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.go_to_next_bookmark(&GoToNextBookmark, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            zero
+            one
+            two
+            ˇthree
+            four
+        "});
+
+        cx.update_editor(|editor, window, cx| {
+            editor.clear_bookmarks(&ClearBookmarks, window, cx);
+            let bookmark_store = editor.bookmark_store(cx).unwrap();
+            assert!(bookmark_store.read(cx).all_bookmarks(cx).is_empty());
+        });
+    }
+}
@@ -0,0 +1,278 @@
+//! A history of the text copied and cut in editors, kept with the selections it was copied from,
+//! so that earlier entries can be pasted into multiple cursors just as they were copied.
+//!
+//! Once loaded, the history is saved to [`paths::clipboard_history_file`] after every change,
+//! newest entry first.
+
+use std::{collections::VecDeque, sync::Arc, time::SystemTime};
+
+use anyhow::{Context as _, Result};
+use fs::Fs;
+use gpui::{App, AppContext as _, ClipboardItem, Context, Global, Task, UpdateGlobal, Window};
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use util::ResultExt as _;
+
+use crate::{ClipboardSelection, Editor, EditorSettings, HideMouseCursorOrigin};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClipboardHistoryEntry {
+    pub text: String,
+    /// The selections the text was copied from, one per cursor.
+    #[serde(default)]
+    pub selections: Option<Vec<ClipboardSelection>>,
+    pub copied_at: SystemTime,
+}
+
+impl ClipboardHistoryEntry {
+    pub fn to_clipboard_item(&self) -> ClipboardItem {
+        match &self.selections {
+            Some(selections) => {
+                ClipboardItem::new_string_with_json_metadata(self.text.clone(), selections)
+            }
+            None => ClipboardItem::new_string(self.text.clone()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardHistoryEntry>,
+    /// Set once the saved history has been loaded, after which it's saved on every change.
+    fs: Option<Arc<dyn Fs>>,
+    save_task: Option<Task<()>>,
+}
+
+impl Global for ClipboardHistory {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(ClipboardHistory::default());
+}
+
+impl ClipboardHistory {
+    /// Loads the saved history, after which changes to it are saved.
+    pub fn load(fs: Arc<dyn Fs>, cx: &mut App) {
+        cx.spawn(async move |cx| {
+            let saved = load_entries(fs.as_ref())
+                .await
+                .log_err()
+                .unwrap_or_default();
+            cx.update(|cx| {
+                let max_entries = EditorSettings::get_global(cx).clipboard_history.max_entries;
+                Self::update_global(cx, |history, cx| {
+                    // Entries recorded while loading are newer than the saved ones.
+                    history.entries.extend(saved);
+                    history.entries.truncate(max_entries);
+                    history.fs = Some(fs);
+                    history.save(cx);
+                });
+            })
+        })
+        .detach();
+    }
+
+    /// The entries of the history, newest first.
+    pub fn entries(cx: &App) -> impl Iterator<Item = &ClipboardHistoryEntry> {
+        cx.try_global::<Self>()
+            .into_iter()
+            .flat_map(|history| history.entries.iter())
+    }
+
+    pub fn entry(ix: usize, cx: &App) -> Option<&ClipboardHistoryEntry> {
+        cx.try_global::<Self>()?.entries.get(ix)
+    }
+
+    pub(crate) fn record(text: &str, selections: Vec<ClipboardSelection>, cx: &mut App) {
+        let settings = EditorSettings::get_global(cx).clipboard_history;
+        if !settings.enabled
+            || text.is_empty()
+            || text.len() > settings.max_entry_size_kb * 1024
+            || !cx.has_global::<Self>()
+        {
+            return;
+        }
+        Self::update_global(cx, |history, cx| {
+            history.entries.retain(|entry| entry.text != text);
+            history.entries.push_front(ClipboardHistoryEntry {
+                text: text.to_string(),
+                selections: Some(selections),
+                copied_at: SystemTime::now(),
+            });
+            history.entries.truncate(settings.max_entries);
+            history.save(cx);
+        });
+    }
+
+    /// Moves the entry at `ix` to the front of the history, as though it had just been copied.
+    pub fn promote(ix: usize, cx: &mut App) {
+        Self::update_global(cx, |history, cx| {
+            if let Some(entry) = history.entries.remove(ix) {
+                history.entries.push_front(entry);
+                history.save(cx);
+            }
+        });
+    }
+
+    pub fn remove(ix: usize, cx: &mut App) {
+        Self::update_global(cx, |history, cx| {
+            if history.entries.remove(ix).is_some() {
+                history.save(cx);
+            }
+        });
+    }
+
+    pub fn clear(cx: &mut App) {
+        Self::update_global(cx, |history, cx| {
+            history.entries.clear();
+            history.save(cx);
+        });
+    }
+
+    fn save(&mut self, cx: &App) {
+        let Some(fs) = self.fs.clone() else {
+            return;
+        };
+        let entries = self.entries.clone();
+        // Replacing the previous task cancels it, as this one writes a newer history.
+        self.save_task = Some(cx.background_spawn(async move {
+            save_entries(fs.as_ref(), entries).await.log_err();
+        }));
+    }
+}
+
+async fn load_entries(fs: &dyn Fs) -> Result<Vec<ClipboardHistoryEntry>> {
+    let path = paths::clipboard_history_file();
+    if !fs.is_file(path).await {
+        return Ok(Vec::new());
+    }
+    let text = fs.load(path).await?;
+    serde_json::from_str(&text).with_context(|| format!("failed to parse {path:?}"))
+}
+
+async fn save_entries(fs: &dyn Fs, entries: VecDeque<ClipboardHistoryEntry>) -> Result<()> {
+    fs.create_dir(paths::data_dir()).await?;
+    fs.atomic_write(
+        paths::clipboard_history_file().clone(),
+        serde_json::to_string(&entries)?,
+    )
+    .await
+}
+
+impl Editor {
+    /// Records copied or cut text in the clipboard history. Only full editors that aren't masked
+    /// record anything, so that e.g. API keys copied from settings fields never reach the disk.
+    pub(crate) fn record_in_clipboard_history(
+        &self,
+        text: &str,
+        selections: Vec<ClipboardSelection>,
+        cx: &mut App,
+    ) {
+        if !self.mode.is_full() || self.display_map.read(cx).masked {
+            return;
+        }
+        ClipboardHistory::record(text, selections, cx);
+    }
+
+    /// Pastes the entry of the clipboard history at `ix` into the selections, and puts it back
+    /// on the clipboard.
+    pub fn paste_from_clipboard_history(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = ClipboardHistory::entry(ix, cx).cloned() else {
+            return;
+        };
+        ClipboardHistory::promote(ix, cx);
+        cx.write_to_clipboard(entry.to_clipboard_item());
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        self.do_paste(&entry.text, entry.selections, true, window, cx);
+    }
+
+    /// Opens a diff between the entry of the clipboard history at `ix` and the selected text.
+    pub fn diff_clipboard_history_entry_with_selection(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(entry) = ClipboardHistory::entry(ix, cx) {
+            let text = entry.text.clone();
+            self.diff_text_with_selection(text, window, cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Copy, Cut, editor_tests::init_test, test::editor_test_context::EditorTestContext};
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+
+    fn history_texts(cx: &mut EditorTestContext) -> Vec<String> {
+        cx.update(|_, cx| {
+            ClipboardHistory::entries(cx)
+                .map(|entry| entry.text.clone())
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_clipboard_history(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("«oneˇ» two «threeˇ»");
+        cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+        cx.set_state("one «twoˇ» three");
+        cx.update_editor(|editor, window, cx| editor.cut(&Cut, window, cx));
+        cx.assert_editor_state("one ˇ three");
+        assert_eq!(history_texts(&mut cx), ["two", "one\nthree"]);
+
+        // Pasting an earlier entry pastes it into each of the cursors it was
+        // copied from, and moves it to the front.
+        cx.set_state("ˇa\nˇb");
+        cx.update_editor(|editor, window, cx| editor.paste_from_clipboard_history(1, window, cx));
+        cx.assert_editor_state("oneˇa\nthreeˇb");
+        assert_eq!(history_texts(&mut cx), ["one\nthree", "two"]);
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("one\nthree".to_string())
+        );
+
+        // Copying text that's already in the history moves it to the front.
+        cx.set_state("«twoˇ»");
+        cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+        assert_eq!(history_texts(&mut cx), ["two", "one\nthree"]);
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    let history = settings.editor.clipboard_history.get_or_insert_default();
+                    history.max_entries = Some(2);
+                    history.max_entry_size_kb = Some(1);
+                });
+            });
+        });
+        cx.set_state("«fourˇ»");
+        cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+        assert_eq!(history_texts(&mut cx), ["four", "two"]);
+
+        cx.set_state(&format!("«{}ˇ»", "x".repeat(2048)));
+        cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+        assert_eq!(history_texts(&mut cx), ["four", "two"]);
+
+        // Text copied from masked editors is never recorded.
+        cx.update_editor(|editor, _, cx| editor.set_masked(true, cx));
+        cx.set_state("«secretˇ»");
+        cx.update_editor(|editor, window, cx| editor.copy(&Copy, window, cx));
+        assert_eq!(history_texts(&mut cx), ["four", "two"]);
+
+        cx.update(|_, cx| ClipboardHistory::remove(0, cx));
+        assert_eq!(history_texts(&mut cx), ["two"]);
+        cx.update(|_, cx| ClipboardHistory::clear(cx));
+        assert!(history_texts(&mut cx).is_empty());
+    }
+}
@@ -13,8 +13,10 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 pub mod blink_manager;
+mod bookmarks;
 mod bracket_colorization;
 mod clangd_ext;
+mod clipboard_history;
 pub mod code_context_menus;
 pub mod display_map;
 mod document_colors;
@@ -22,6 +24,7 @@ mod document_symbols;
 mod editor_settings;
 mod element;
 mod folding_ranges;
+mod formatted_text;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -32,6 +35,7 @@ pub mod items;
 mod jsx_tag_auto_close;
 mod linked_editing_ranges;
 mod lsp_ext;
+mod macros;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -40,6 +44,7 @@ mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
 pub mod semantic_tokens;
+mod spell_checking;
 mod split;
 pub mod split_editor_view;
 
@@ -54,6 +59,8 @@ mod signature_help;
 pub mod test;
 
 pub(crate) use actions::*;
+pub use bookmarks::open_bookmark;
+pub use clipboard_history::{ClipboardHistory, ClipboardHistoryEntry};
 pub use display_map::{
     ChunkRenderer, ChunkRendererContext, DisplayPoint, FoldPlaceholder, HighlightKey,
     SemanticTokenHighlight,
@@ -75,6 +82,7 @@ pub use items::MAX_TAB_TITLE_LEN;
 pub use linked_editing_ranges::LinkedEdits;
 pub use lsp::CompletionContext;
 pub use lsp_ext::lsp_tasks;
+pub use macros::MacroRecordingIndicator;
 pub use multi_buffer::{
     Anchor, AnchorRangeExt, BufferOffset, ExcerptId, ExcerptRange, MBTextSummary, MultiBuffer,
     MultiBufferOffset, MultiBufferOffsetUtf16, MultiBufferSnapshot, PathKey, RowInfo, ToOffset,
@@ -106,6 +114,7 @@ use edit_prediction_types::{
 };
 use editor_settings::{GoToDefinitionFallback, Minimap as MinimapSettings};
 use element::{AcceptEditPredictionBinding, LineWithInvisibles, PositionMap, layout_line};
+use formatted_text::{FormattedText, FormattedTextStyle};
 use futures::{
     FutureExt,
     future::{self, Shared, join},
@@ -114,8 +123,8 @@ use fuzzy::{StringMatch, StringMatchCandidate};
 use git::blame::{GitBlame, GlobalBlameRenderer};
 use gpui::{
     Action, Animation, AnimationExt, AnyElement, App, AppContext, AsyncWindowContext,
-    AvailableSpace, Background, Bounds, ClickEvent, ClipboardEntry, ClipboardItem, Context,
-    DispatchPhase, Edges, Entity, EntityId, EntityInputHandler, EventEmitter, FocusHandle,
+    AvailableSpace, Background, Bounds, ClickEvent, ClipboardEntry, ClipboardItem, ClipboardString,
+    Context, DispatchPhase, Edges, Entity, EntityId, EntityInputHandler, EventEmitter, FocusHandle,
     FocusOutEvent, Focusable, FontId, FontStyle, FontWeight, Global, HighlightStyle, Hsla,
     KeyContext, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, PaintQuad, ParentElement,
     Pixels, PressureStage, Render, ScrollHandle, SharedString, SharedUri, Size, Stateful, Styled,
@@ -156,9 +165,9 @@ use parking_lot::Mutex;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, InlayId,
-    InvalidationStrategy, Location, LocationLink, LspAction, PrepareRenameResponse, Project,
-    ProjectItem, ProjectPath, ProjectTransaction,
+    CompletionResponse, CompletionSource, DirectoryLister, DisableAiSettings, DocumentHighlight,
+    InlayHint, InlayId, InvalidationStrategy, Location, LocationLink, LspAction,
+    PrepareRenameResponse, Project, ProjectItem, ProjectPath, ProjectTransaction,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -185,6 +194,8 @@ use settings::{
 };
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
+use spell_check::GlobalSpellChecker;
+use spell_checking::SpellCheckCodeActionProvider;
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -339,6 +350,8 @@ pub fn init(cx: &mut App) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    macros::init(cx);
+    clipboard_history::init(cx);
 
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
@@ -1328,6 +1341,7 @@ pub struct Editor {
     sticky_headers_task: Task<()>,
     sticky_headers: Option<Vec<OutlineItem<Anchor>>>,
     pub(crate) colorize_brackets_task: Task<()>,
+    spell_check_task: Task<()>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -2335,7 +2349,8 @@ impl Editor {
                 )
                 .shared(),
             );
-            code_action_providers.push(Rc::new(project) as Rc<_>);
+            code_action_providers.push(Rc::new(project.clone()) as Rc<_>);
+            code_action_providers.push(Rc::new(SpellCheckCodeActionProvider { project }) as Rc<_>);
         }
 
         let mut editor = Self {
@@ -2510,6 +2525,9 @@ impl Editor {
                         cx.observe(&blink_manager, |_, _, cx| cx.notify()),
                         cx.observe_global_in::<SettingsStore>(window, Self::settings_changed),
                         cx.observe_global_in::<GlobalTheme>(window, Self::theme_changed),
+                        cx.observe_global::<GlobalSpellChecker>(|editor, cx| {
+                            editor.refresh_spell_check(false, cx)
+                        }),
                         observe_buffer_font_size_adjustment(cx, |_, cx| cx.notify()),
                         cx.observe_window_activation(window, |editor, window, cx| {
                             let active = window.is_window_active();
@@ -2578,6 +2596,7 @@ impl Editor {
             sticky_headers_task: Task::ready(()),
             sticky_headers: None,
             colorize_brackets_task: Task::ready(()),
+            spell_check_task: Task::ready(()),
         };
 
         if is_minimap {
@@ -2594,6 +2613,13 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = editor.bookmark_store(cx) {
+            editor
+                ._subscriptions
+                .push(cx.observe(&bookmarks, |_, _, cx| {
+                    cx.notify();
+                }));
+        }
         editor._subscriptions.extend(project_subscriptions);
 
         editor._subscriptions.push(cx.subscribe_in(
@@ -2655,6 +2681,14 @@ impl Editor {
                             .push_to_change_list(pop_state, new_positions);
                     }
                 }
+                EditorEvent::InputHandled {
+                    utf16_range_to_replace,
+                    text,
+                } => {
+                    if editor.mode.is_full() {
+                        macros::observe_insertion(text, utf16_range_to_replace.clone(), cx);
+                    }
+                }
                 _ => (),
             },
         ));
@@ -13527,6 +13561,15 @@ impl Editor {
     pub fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
         let item = self.cut_common(true, window, cx);
+        if let Some(ClipboardEntry::String(clipboard_string)) = item.entries().first() {
+            self.record_in_clipboard_history(
+                clipboard_string.text(),
+                clipboard_string
+                    .metadata_json::<Vec<ClipboardSelection>>()
+                    .unwrap_or_default(),
+                cx,
+            );
+        }
         cx.write_to_clipboard(item);
     }
 
@@ -13566,17 +13609,28 @@ impl Editor {
     }
 
     pub fn copy_and_trim(&mut self, _: &CopyAndTrim, _: &mut Window, cx: &mut Context<Self>) {
-        self.do_copy(true, cx);
+        self.do_copy(true, false, cx);
     }
 
     pub fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        self.do_copy(false, cx);
+        self.do_copy(false, false, cx);
     }
 
-    fn do_copy(&self, strip_leading_indents: bool, cx: &mut Context<Self>) {
+    pub fn copy_with_formatting(
+        &mut self,
+        _: &CopyWithFormatting,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.do_copy(false, true, cx);
+    }
+
+    fn do_copy(&self, strip_leading_indents: bool, with_formatting: bool, cx: &mut Context<Self>) {
         let selections = self.selections.all::<Point>(&self.display_snapshot(cx));
         let buffer = self.buffer.read(cx).read(cx);
+        let syntax = cx.theme().syntax().clone();
         let mut text = String::new();
+        let mut formatted_text = with_formatting.then(FormattedText::default);
         let mut clipboard_selections = Vec::with_capacity(selections.len());
 
         let max_point = buffer.max_point();
@@ -13643,6 +13697,9 @@ impl Editor {
                     is_first = false;
                 } else if is_multiline_trim || !prev_selection_was_entire_line {
                     text.push('\n');
+                    if let Some(formatted_text) = formatted_text.as_mut() {
+                        formatted_text.push_str("\n");
+                    }
                     if is_multiline_trim {
                         selection_len += 1;
                     }
@@ -13651,8 +13708,14 @@ impl Editor {
                     text.push_str(chunk);
                     selection_len += chunk.len();
                 }
+                if let Some(formatted_text) = formatted_text.as_mut() {
+                    formatted_text.push_range(&buffer, trimmed_range.clone(), &syntax);
+                }
                 if add_trailing_newline {
                     text.push('\n');
+                    if let Some(formatted_text) = formatted_text.as_mut() {
+                        formatted_text.push_str("\n");
+                    }
                     selection_len += 1;
                 }
             }
@@ -13667,10 +13730,82 @@ impl Editor {
             ));
         }
 
-        cx.write_to_clipboard(ClipboardItem::new_string_with_json_metadata(
-            text,
-            clipboard_selections,
-        ));
+        drop(buffer);
+        self.record_in_clipboard_history(&text, clipboard_selections.clone(), cx);
+        let mut clipboard_string =
+            ClipboardString::new(text).with_json_metadata(clipboard_selections);
+        if let Some(formatted_text) = formatted_text {
+            let style = self.formatted_text_style(cx);
+            clipboard_string = clipboard_string
+                .with_html(formatted_text.to_html(&style))
+                .with_rtf(formatted_text.to_rtf(&style));
+        }
+        cx.write_to_clipboard(ClipboardItem {
+            entries: vec![ClipboardEntry::String(clipboard_string)],
+        });
+    }
+
+    fn formatted_text_style(&self, cx: &App) -> FormattedTextStyle {
+        let settings = ThemeSettings::get_global(cx);
+        let colors = cx.theme().colors();
+        FormattedTextStyle {
+            font_family: settings.buffer_font.family.clone(),
+            font_size: settings.buffer_font_size(cx).into(),
+            foreground: colors.editor_foreground,
+            background: colors.editor_background,
+        }
+    }
+
+    pub fn export_to_html(
+        &mut self,
+        _: &ExportToHtml,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        if !project.read(cx).is_local() {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_error(
+                    &anyhow!("Exporting to HTML is only supported in local projects"),
+                    cx,
+                );
+            });
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut formatted_text = FormattedText::default();
+        formatted_text.push_range(
+            &snapshot,
+            MultiBufferOffset(0)..snapshot.len(),
+            cx.theme().syntax(),
+        );
+        let title = self.buffer.read(cx).title(cx).to_string();
+        let html = formatted_text.to_html_document(&title, &self.formatted_text_style(cx));
+        let suggested_name = self
+            .target_file(cx)
+            .map(|file| format!("{}.html", file.file_name(cx)))
+            .unwrap_or_else(|| "untitled.html".to_string());
+
+        let fs = project.read(cx).fs().clone();
+        let new_path = workspace.update(cx, |workspace, cx| {
+            let lister = DirectoryLister::Local(project.clone(), fs.clone());
+            workspace.prompt_for_new_path(lister, Some(suggested_name), window, cx)
+        });
+        let task = cx.spawn(async move |_, _| {
+            let Some(path) = new_path.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            fs.atomic_write(path.clone(), html)
+                .await
+                .with_context(|| format!("writing {}", path.display()))
+        });
+        self.detach_and_notify_err(task, window, cx);
     }
 
     pub fn do_paste(
@@ -13886,6 +14021,15 @@ impl Editor {
             return;
         };
 
+        self.diff_text_with_selection(clipboard_text, window, cx);
+    }
+
+    fn diff_text_with_selection(
+        &mut self,
+        clipboard_text: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         window.dispatch_action(
             Box::new(DiffClipboardWithSelectionData {
                 clipboard_text,
@@ -23732,6 +23876,7 @@ impl Editor {
                 self.refresh_matching_bracket_highlights(&snapshot, cx);
                 self.refresh_outline_symbols_at_cursor(cx);
                 self.refresh_sticky_headers(&snapshot, cx);
+                self.refresh_spell_check(true, cx);
                 if *is_local && self.has_active_edit_prediction() {
                     self.update_visible_edit_prediction(window, cx);
                 }
@@ -23791,6 +23936,7 @@ impl Editor {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
                 self.refresh_runnables(None, window, cx);
                 self.colorize_brackets(false, cx);
+                self.refresh_spell_check(false, cx);
                 self.refresh_selected_text_highlights(&self.display_snapshot(cx), true, window, cx);
                 cx.emit(EditorEvent::ExcerptsAdded {
                     buffer: buffer.clone(),
@@ -23849,6 +23995,7 @@ impl Editor {
                     }
                 }
                 self.colorize_brackets(false, cx);
+                self.refresh_spell_check(false, cx);
                 self.update_lsp_data(None, window, cx);
                 self.refresh_runnables(None, window, cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
@@ -23857,6 +24004,7 @@ impl Editor {
                 self.refresh_runnables(Some(*buffer_id), window, cx);
                 self.refresh_selected_text_highlights(&self.display_snapshot(cx), true, window, cx);
                 self.colorize_brackets(true, cx);
+                self.refresh_spell_check(true, cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
@@ -24066,6 +24214,7 @@ impl Editor {
             }
 
             if language_settings_changed {
+                self.refresh_spell_check(false, cx);
                 self.clear_disabled_lsp_folding_ranges(window, cx);
                 self.refresh_document_symbols(None, cx);
             }
@@ -24114,6 +24263,7 @@ impl Editor {
             self.accent_data = new_accents;
             self.colorize_brackets(true, cx);
         }
+        self.refresh_spell_check(false, cx);
 
         self.invalidate_semantic_tokens(None);
         self.refresh_semantic_tokens(None, None, cx);
@@ -25375,6 +25525,7 @@ impl Editor {
     fn update_data_on_scroll(&mut self, window: &mut Window, cx: &mut Context<'_, Self>) {
         self.register_visible_buffers(cx);
         self.colorize_brackets(false, cx);
+        self.refresh_spell_check(true, cx);
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
         if !self.buffer().read(cx).is_singleton() {
             self.update_lsp_data(None, window, cx);
@@ -55,6 +55,7 @@ pub struct EditorSettings {
     pub diagnostics_max_severity: Option<DiagnosticSeverity>,
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
+    pub clipboard_history: ClipboardHistory,
     pub lsp_document_colors: DocumentColorsRenderMode,
     pub minimum_contrast_for_highlights: f32,
     pub completion_menu_scrollbar: ShowScrollbar,
@@ -129,6 +130,7 @@ pub struct Gutter {
     pub line_numbers: bool,
     pub runnables: bool,
     pub breakpoints: bool,
+    pub bookmarks: bool,
     pub folds: bool,
 }
 
@@ -160,6 +162,23 @@ pub struct DragAndDropSelection {
     pub delay: DelayMs,
 }
 
+/// The history of text copied and cut in editors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClipboardHistory {
+    /// Whether to record copied and cut text.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The maximum number of entries kept.
+    ///
+    /// Default: 50
+    pub max_entries: usize,
+    /// Copied text larger than this many kilobytes is not recorded.
+    ///
+    /// Default: 256
+    pub max_entry_size_kb: usize,
+}
+
 /// Default options for buffer and project search items.
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct SearchSettings {
@@ -199,6 +218,7 @@ impl Settings for EditorSettings {
         let toolbar = editor.toolbar.unwrap();
         let search = editor.search.unwrap();
         let drag_and_drop_selection = editor.drag_and_drop_selection.unwrap();
+        let clipboard_history = editor.clipboard_history.unwrap();
         let sticky_scroll = editor.sticky_scroll.unwrap();
         Self {
             cursor_blink: editor.cursor_blink.unwrap(),
@@ -249,6 +269,7 @@ impl Settings for EditorSettings {
                 line_numbers: gutter.line_numbers.unwrap(),
                 runnables: gutter.runnables.unwrap(),
                 breakpoints: gutter.breakpoints.unwrap(),
+                bookmarks: gutter.bookmarks.unwrap(),
                 folds: gutter.folds.unwrap(),
             },
             scroll_beyond_last_line: editor.scroll_beyond_last_line.unwrap(),
@@ -292,6 +313,11 @@ impl Settings for EditorSettings {
                 enabled: drag_and_drop_selection.enabled.unwrap(),
                 delay: drag_and_drop_selection.delay.unwrap(),
             },
+            clipboard_history: ClipboardHistory {
+                enabled: clipboard_history.enabled.unwrap(),
+                max_entries: clipboard_history.max_entries.unwrap(),
+                max_entry_size_kb: clipboard_history.max_entry_size_kb.unwrap(),
+            },
             lsp_document_colors: editor.lsp_document_colors.unwrap(),
             minimum_contrast_for_highlights: editor.minimum_contrast_for_highlights.unwrap().0,
             completion_menu_scrollbar: editor.completion_menu_scrollbar.map(Into::into).unwrap(),
@@ -8288,6 +8288,39 @@ async fn test_copy_trim_line_mode(cx: &mut TestAppContext) {
     assert!(clipboard_selections[0].is_entire_line);
 }
 
+#[gpui::test]
+async fn test_copy_with_formatting(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("«<one>ˇ» two «threeˇ»");
+    cx.update_editor(|e, window, cx| e.copy_with_formatting(&CopyWithFormatting, window, cx));
+
+    let Some(gpui::ClipboardEntry::String(string)) = cx
+        .read_from_clipboard()
+        .and_then(|item| item.entries().first().cloned())
+    else {
+        panic!("expected a string on the clipboard");
+    };
+    assert_eq!(string.text(), "<one>\nthree");
+    let html = string.html.expect("expected an HTML rendering");
+    assert!(html.starts_with("<pre "), "{html}");
+    assert!(html.ends_with(">&lt;one&gt;\nthree</pre>"), "{html}");
+    let rtf = string.rtf.expect("expected an RTF rendering");
+    assert!(rtf.starts_with("{\\rtf1"), "{rtf}");
+    assert!(rtf.contains(" <one>\\line\nthree}"), "{rtf}");
+
+    cx.update_editor(|e, window, cx| e.copy(&Copy, window, cx));
+    let Some(gpui::ClipboardEntry::String(string)) = cx
+        .read_from_clipboard()
+        .and_then(|item| item.entries().first().cloned())
+    else {
+        panic!("expected a string on the clipboard");
+    };
+    assert_eq!(string.html, None);
+    assert_eq!(string.rtf, None);
+}
+
 #[gpui::test]
 async fn test_clipboard_line_numbers_from_multibuffer(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
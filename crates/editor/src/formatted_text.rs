@@ -0,0 +1,329 @@
+//! Renders buffer text with the current theme's syntax highlighting as HTML or RTF, so that
+//! code keeps its colors when copied into documents, slides and chat clients, or exported to a
+//! standalone HTML file.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+
+use gpui::{FontStyle, FontWeight, HighlightStyle, Hsla, Rgba, SharedString};
+use multi_buffer::{MultiBufferSnapshot, ToOffset};
+use theme::SyntaxTheme;
+
+/// The colors and font of the editor that formatted text is rendered with.
+#[derive(Clone, Debug)]
+pub(crate) struct FormattedTextStyle {
+    pub font_family: SharedString,
+    /// The font size, in pixels.
+    pub font_size: f32,
+    pub foreground: Hsla,
+    pub background: Hsla,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct RunStyle {
+    color: Option<Hsla>,
+    bold: bool,
+    italic: bool,
+}
+
+impl From<HighlightStyle> for RunStyle {
+    fn from(style: HighlightStyle) -> Self {
+        Self {
+            color: style.color,
+            bold: style
+                .font_weight
+                .is_some_and(|weight| weight >= FontWeight::SEMIBOLD),
+            italic: matches!(
+                style.font_style,
+                Some(FontStyle::Italic | FontStyle::Oblique)
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Run {
+    text: String,
+    style: RunStyle,
+}
+
+/// Text split into runs of the same syntax highlighting.
+#[derive(Debug, Default)]
+pub(crate) struct FormattedText {
+    runs: Vec<Run>,
+}
+
+impl FormattedText {
+    /// Appends the text of `range`, highlighted with the given syntax theme.
+    pub fn push_range<T: ToOffset>(
+        &mut self,
+        snapshot: &MultiBufferSnapshot,
+        range: Range<T>,
+        syntax: &SyntaxTheme,
+    ) {
+        for chunk in snapshot.chunks(range, true) {
+            let style = chunk
+                .syntax_highlight_id
+                .and_then(|id| id.style(syntax))
+                .map(RunStyle::from)
+                .unwrap_or_default();
+            self.push(chunk.text, style);
+        }
+    }
+
+    /// Appends text without any highlighting.
+    pub fn push_str(&mut self, text: &str) {
+        self.push(text, RunStyle::default());
+    }
+
+    fn push(&mut self, text: &str, style: RunStyle) {
+        if text.is_empty() {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(run) if run.style == style => run.text.push_str(text),
+            _ => self.runs.push(Run {
+                text: text.to_string(),
+                style,
+            }),
+        }
+    }
+
+    /// Renders the text as an HTML fragment: a `<pre>` element with one `<span>` per
+    /// highlighted run.
+    pub fn to_html(&self, style: &FormattedTextStyle) -> String {
+        let mut html = format!(
+            "<pre style=\"background-color:{};color:{};font-family:{},monospace;font-size:{}px;padding:8px;\">",
+            css_color(style.background),
+            css_color(style.foreground),
+            css_font_family(&style.font_family),
+            style.font_size,
+        );
+        for run in &self.runs {
+            let mut declarations = Vec::new();
+            if let Some(color) = run.style.color {
+                declarations.push(format!(
+                    "color:{}",
+                    css_color(style.background.blend(color))
+                ));
+            }
+            if run.style.bold {
+                declarations.push("font-weight:bold".to_string());
+            }
+            if run.style.italic {
+                declarations.push("font-style:italic".to_string());
+            }
+            if declarations.is_empty() {
+                push_escaped_html(&mut html, &run.text);
+            } else {
+                write!(html, "<span style=\"{}\">", declarations.join(";")).ok();
+                push_escaped_html(&mut html, &run.text);
+                html.push_str("</span>");
+            }
+        }
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Renders the text as a standalone HTML document with the given title.
+    pub fn to_html_document(&self, title: &str, style: &FormattedTextStyle) -> String {
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>");
+        push_escaped_html(&mut html, title);
+        html.push_str("</title>\n</head>\n");
+        write!(
+            html,
+            "<body style=\"margin:0;background-color:{};\">\n",
+            css_color(style.background)
+        )
+        .ok();
+        html.push_str(&self.to_html(style));
+        html.push_str("\n</body>\n</html>\n");
+        html
+    }
+
+    /// Renders the text as an RTF document.
+    pub fn to_rtf(&self, style: &FormattedTextStyle) -> String {
+        let mut colors = vec![rgb(style.foreground), rgb(style.background)];
+        let run_colors = self
+            .runs
+            .iter()
+            .map(|run| {
+                let color = rgb(run
+                    .style
+                    .color
+                    .map_or(style.foreground, |color| style.background.blend(color)));
+                // Indices in the color table start at 1, as the first entry is the default color.
+                match colors.iter().position(|existing| *existing == color) {
+                    Some(ix) => ix + 1,
+                    None => {
+                        colors.push(color);
+                        colors.len()
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut rtf = String::from("{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern ");
+        push_escaped_rtf(&mut rtf, &style.font_family);
+        rtf.push_str(";}}{\\colortbl;");
+        for (r, g, b) in &colors {
+            write!(rtf, "\\red{r}\\green{g}\\blue{b};").ok();
+        }
+        // RTF font sizes are in half points, and there are 0.75 points in a pixel.
+        let font_size = (style.font_size * 1.5).round() as u32;
+        write!(
+            rtf,
+            "}}\n\\pard\\f0\\fs{font_size}\\cf1\\cb2\\chshdng0\\chcbpat2 "
+        )
+        .ok();
+        for (run, color) in self.runs.iter().zip(run_colors) {
+            write!(rtf, "{{\\cf{color}").ok();
+            if run.style.bold {
+                rtf.push_str("\\b");
+            }
+            if run.style.italic {
+                rtf.push_str("\\i");
+            }
+            rtf.push(' ');
+            push_escaped_rtf(&mut rtf, &run.text);
+            rtf.push('}');
+        }
+        rtf.push_str("}\n");
+        rtf
+    }
+}
+
+fn rgb(color: Hsla) -> (u8, u8, u8) {
+    let color = Rgba::from(color);
+    (
+        (color.r * 255.).round() as u8,
+        (color.g * 255.).round() as u8,
+        (color.b * 255.).round() as u8,
+    )
+}
+
+fn css_color(color: Hsla) -> String {
+    let (r, g, b) = rgb(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn css_font_family(family: &str) -> String {
+    let family = family.replace(['"', '\'', ';', '<', '>', '&'], "");
+    format!("'{family}'")
+}
+
+fn push_escaped_html(html: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(ch),
+        }
+    }
+}
+
+fn push_escaped_rtf(rtf: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '\\' | '{' | '}' => {
+                rtf.push('\\');
+                rtf.push(ch);
+            }
+            '\n' => rtf.push_str("\\line\n"),
+            '\t' => rtf.push_str("\\tab "),
+            '\r' => {}
+            _ if ch.is_ascii() => rtf.push(ch),
+            // Other characters are written as signed 16 bit UTF-16 code units, each
+            // followed by a `?` for readers that don't support unicode.
+            _ => {
+                let mut units = [0; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    write!(rtf, "\\u{}?", *unit as i16).ok();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{hsla, rgb as gpui_rgb};
+
+    fn style() -> FormattedTextStyle {
+        FormattedTextStyle {
+            font_family: "Zed Mono".into(),
+            font_size: 16.,
+            foreground: gpui_rgb(0xffffff).into(),
+            background: gpui_rgb(0x000000).into(),
+        }
+    }
+
+    fn text() -> FormattedText {
+        let keyword = RunStyle {
+            color: Some(gpui_rgb(0xff0000).into()),
+            bold: true,
+            italic: false,
+        };
+        let mut text = FormattedText::default();
+        text.push("fn", keyword);
+        text.push_str(" a<T>() {}");
+        text.push_str("\n");
+        text.push("fn", keyword);
+        text.push_str(" é() {}");
+        text
+    }
+
+    #[test]
+    fn test_runs_are_merged() {
+        let text = text();
+        assert_eq!(
+            text.runs
+                .iter()
+                .map(|run| run.text.as_str())
+                .collect::<Vec<_>>(),
+            ["fn", " a<T>() {}\n", "fn", " é() {}"]
+        );
+    }
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(
+            text().to_html(&style()),
+            "<pre style=\"background-color:#000000;color:#ffffff;font-family:'Zed Mono',monospace;font-size:16px;padding:8px;\">\
+            <span style=\"color:#ff0000;font-weight:bold\">fn</span> a&lt;T&gt;() {}\n\
+            <span style=\"color:#ff0000;font-weight:bold\">fn</span> é() {}</pre>"
+        );
+    }
+
+    #[test]
+    fn test_to_rtf() {
+        assert_eq!(
+            text().to_rtf(&style()),
+            "{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0\\fmodern Zed Mono;}}\
+            {\\colortbl;\\red255\\green255\\blue255;\\red0\\green0\\blue0;\\red255\\green0\\blue0;}\n\
+            \\pard\\f0\\fs24\\cf1\\cb2\\chshdng0\\chcbpat2 \
+            {\\cf3\\b fn}{\\cf1  a<T>() \\{\\}\\line\n}\
+            {\\cf3\\b fn}{\\cf1  \\u233?() \\{\\}}}\n"
+        );
+    }
+
+    #[test]
+    fn test_translucent_colors_are_blended_with_the_background() {
+        let mut text = FormattedText::default();
+        text.push(
+            "x",
+            RunStyle {
+                color: Some(hsla(0., 0., 1., 0.5)),
+                ..RunStyle::default()
+            },
+        );
+        assert!(
+            text.to_html(&style())
+                .contains("<span style=\"color:#808080\">x</span>")
+        );
+    }
+}
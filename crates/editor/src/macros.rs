@@ -0,0 +1,696 @@
+//! Keyboard macros: the actions dispatched to editors and the text typed into them are recorded
+//! while a recording is running, and can be played back afterwards. Actions are recorded however
+//! they're dispatched, so those run from the command palette, menus or buttons are included.
+//!
+//! Named macros are saved to [`paths::macros_file`], one JSON array of steps per name. A step is
+//! an action name, an `[action name, arguments]` pair, or a `{ "text": ... }` insertion, so
+//! they read like the actions in a keymap file.
+
+use std::{ops::Range, sync::Arc, time::Instant};
+
+use anyhow::{Context as _, Result};
+use fs::Fs;
+use gpui::{
+    Action, ActionEvent, App, AppContext as _, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Global, SharedString, WeakEntity, Window,
+};
+use multi_buffer::{Anchor, ToPoint as _};
+use serde_json::{Map, Value, json};
+use text::Point;
+use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{ItemHandle, ModalView, StatusItemView};
+
+use crate::{Editor, MacroTarget, PlayMacro, SaveMacro, SelectionEffects, ToggleMacroRecording};
+
+/// Playback stops after this many steps, so that a macro that keeps re-triggering itself
+/// through the keymap can't hang the editor.
+const MAX_PLAYED_STEPS: usize = 100_000;
+
+#[derive(Debug)]
+enum MacroStep {
+    Action {
+        action: Box<dyn Action>,
+        /// The action's arguments as written in the keymap, used when saving the macro.
+        input: Option<SharedString>,
+    },
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl Clone for MacroStep {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action { action, input } => Self::Action {
+                action: action.boxed_clone(),
+                input: input.clone(),
+            },
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Self::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct MacroRecorder {
+    recording: Option<Vec<MacroStep>>,
+    last_macro: Option<Arc<[MacroStep]>>,
+    playing: bool,
+}
+
+impl Global for MacroRecorder {}
+
+impl MacroRecorder {
+    fn record(&mut self, step: MacroStep) {
+        if self.playing {
+            return;
+        }
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(step);
+        }
+    }
+}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(MacroRecorder::default());
+    cx.observe_actions(observe_action).detach();
+}
+
+fn observe_action(event: &ActionEvent, window: &mut Window, cx: &mut App) {
+    let action = event.action;
+    if cx
+        .try_global::<MacroRecorder>()
+        .is_none_or(|recorder| recorder.recording.is_none())
+        || is_ignored_action(action)
+    {
+        return;
+    }
+    let in_full_editor = event
+        .context_stack
+        .iter()
+        .rev()
+        .find(|context| context.contains("Editor"))
+        .is_some_and(|context| context.get("mode").is_some_and(|mode| mode == "full"));
+    if !in_full_editor {
+        return;
+    }
+    let input = window
+        .highest_precedence_binding_for_action(action)
+        .and_then(|binding| binding.action_input());
+    cx.global_mut::<MacroRecorder>().record(MacroStep::Action {
+        action: action.boxed_clone(),
+        input,
+    });
+}
+
+/// Actions that control the recording itself, or that open the command palette, whose chosen
+/// action is recorded instead.
+fn is_ignored_action(action: &dyn Action) -> bool {
+    action.as_any().is::<ToggleMacroRecording>()
+        || action.as_any().is::<PlayMacro>()
+        || action.as_any().is::<SaveMacro>()
+        || action.as_any().is::<zed_actions::command_palette::Toggle>()
+}
+
+pub(crate) fn observe_insertion(
+    text: &Arc<str>,
+    utf16_range_to_replace: Option<Range<isize>>,
+    cx: &mut App,
+) {
+    if cx
+        .try_global::<MacroRecorder>()
+        .is_none_or(|recorder| recorder.recording.is_none())
+    {
+        return;
+    }
+    cx.global_mut::<MacroRecorder>()
+        .record(MacroStep::Insertion {
+            text: text.clone(),
+            utf16_range_to_replace,
+        });
+}
+
+impl Editor {
+    pub fn toggle_macro_recording(
+        &mut self,
+        _: &ToggleMacroRecording,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let recorder = cx.global_mut::<MacroRecorder>();
+        if let Some(steps) = recorder.recording.take() {
+            if !steps.is_empty() {
+                recorder.last_macro = Some(steps.into());
+            }
+        } else {
+            recorder.recording = Some(Vec::new());
+        }
+        cx.notify();
+    }
+
+    pub fn play_macro(&mut self, action: &PlayMacro, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.mode.is_full() || cx.global::<MacroRecorder>().playing {
+            return;
+        }
+        let times = action.times.unwrap_or(1);
+        let target = action.target;
+
+        let Some(name) = action.name.clone() else {
+            let Some(steps) = cx.global::<MacroRecorder>().last_macro.clone() else {
+                return;
+            };
+            let editor = cx.entity();
+            window.defer(cx, move |window, cx| {
+                play_steps(editor, &steps, times, target, window, cx);
+            });
+            return;
+        };
+
+        let Some(fs) = self.project().map(|project| project.read(cx).fs().clone()) else {
+            return;
+        };
+        let task = cx.spawn_in(window, async move |editor, cx| {
+            let macros = load_macros(fs.as_ref()).await?;
+            let steps = macros
+                .get(&name)
+                .with_context(|| format!("no macro named {name:?} in {:?}", paths::macros_file()))?
+                .clone();
+            editor.update_in(cx, |_, window, cx| {
+                let steps = steps_from_json(&steps, cx)
+                    .with_context(|| format!("failed to load the macro {name:?}"))?;
+                let editor = cx.entity();
+                window.defer(cx, move |window, cx| {
+                    play_steps(editor, &steps, times, target, window, cx);
+                });
+                anyhow::Ok(())
+            })?
+        });
+        self.detach_and_notify_err(task, window, cx);
+    }
+
+    pub fn save_macro(&mut self, action: &SaveMacro, window: &mut Window, cx: &mut Context<Self>) {
+        if cx.global::<MacroRecorder>().last_macro.is_none() {
+            return;
+        }
+        if let Some(name) = action.name.clone().filter(|name| !name.trim().is_empty()) {
+            self.save_last_macro_as(name, window, cx);
+            return;
+        }
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let editor = cx.weak_entity();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                SaveMacroModal::new(editor, window, cx)
+            });
+        });
+    }
+
+    fn save_last_macro_as(&mut self, name: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(steps) = cx.global::<MacroRecorder>().last_macro.clone() else {
+            return;
+        };
+        let Some(fs) = self.project().map(|project| project.read(cx).fs().clone()) else {
+            return;
+        };
+        let steps = steps_to_json(&steps);
+        let task = cx.background_spawn(async move {
+            let mut macros = load_macros(fs.as_ref()).await?;
+            macros.insert(name, steps);
+            fs.create_dir(paths::config_dir()).await?;
+            fs.atomic_write(
+                paths::macros_file().clone(),
+                serde_json::to_string_pretty(&macros)?,
+            )
+            .await
+        });
+        self.detach_and_notify_err(task, window, cx);
+    }
+
+    /// The ranges each run of a macro starts from, or `None` when it runs once with all cursors.
+    fn macro_start_ranges(&self, target: MacroTarget, cx: &App) -> Option<Vec<Range<Anchor>>> {
+        match target {
+            MacroTarget::Cursors => None,
+            MacroTarget::EachSelection => Some(self.selections.disjoint_anchor_ranges().collect()),
+            MacroTarget::EachLine => {
+                let snapshot = self.buffer.read(cx).snapshot(cx);
+                let mut rows = Vec::new();
+                for range in self.selections.disjoint_anchor_ranges() {
+                    let start = range.start.to_point(&snapshot);
+                    let end = range.end.to_point(&snapshot);
+                    let end_row = if end.row > start.row && end.column == 0 {
+                        end.row - 1
+                    } else {
+                        end.row
+                    };
+                    rows.extend(start.row..=end_row);
+                }
+                rows.sort_unstable();
+                rows.dedup();
+                Some(
+                    rows.into_iter()
+                        .map(|row| {
+                            let anchor = snapshot.anchor_before(Point::new(row, 0));
+                            anchor..anchor
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+}
+
+/// Plays the steps into the editor as a single undoable transaction.
+fn play_steps(
+    editor: Entity<Editor>,
+    steps: &[MacroStep],
+    times: usize,
+    target: MacroTarget,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let focus_handle = editor.read(cx).focus_handle.clone();
+    cx.global_mut::<MacroRecorder>().playing = true;
+
+    let start_ranges = editor.update(cx, |editor, cx| {
+        editor.start_transaction_at(Instant::now(), window, cx);
+        editor.macro_start_ranges(target, cx)
+    });
+
+    let mut played_steps = 0;
+    match start_ranges {
+        None => {
+            for _ in 0..times {
+                play_once(&editor, &focus_handle, steps, &mut played_steps, window, cx);
+            }
+        }
+        Some(start_ranges) => {
+            let mut final_selections = Vec::new();
+            for range in start_ranges {
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                        s.select_anchor_ranges([range])
+                    });
+                });
+                for _ in 0..times {
+                    play_once(&editor, &focus_handle, steps, &mut played_steps, window, cx);
+                }
+                final_selections.extend(editor.read(cx).selections.disjoint_anchor_ranges());
+            }
+            if !final_selections.is_empty() {
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                        s.select_anchor_ranges(final_selections)
+                    });
+                });
+            }
+        }
+    }
+
+    editor.update(cx, |editor, cx| {
+        editor.end_transaction_at(Instant::now(), cx);
+        editor.finalize_last_transaction(cx);
+    });
+    cx.global_mut::<MacroRecorder>().playing = false;
+}
+
+fn play_once(
+    editor: &Entity<Editor>,
+    focus_handle: &FocusHandle,
+    steps: &[MacroStep],
+    played_steps: &mut usize,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    for step in steps {
+        *played_steps += 1;
+        if *played_steps > MAX_PLAYED_STEPS {
+            log::error!("Aborting macro playback after {MAX_PLAYED_STEPS} steps");
+            return;
+        }
+        match step {
+            MacroStep::Action { action, .. } => {
+                focus_handle.dispatch_action(action.as_ref(), window, cx);
+            }
+            MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => editor.update(cx, |editor, cx| {
+                editor.replay_insert_event(text, utf16_range_to_replace.clone(), window, cx);
+            }),
+        }
+    }
+}
+
+async fn load_macros(fs: &dyn Fs) -> Result<Map<String, Value>> {
+    let path = paths::macros_file();
+    if !fs.is_file(path).await {
+        return Ok(Map::new());
+    }
+    let text = fs.load(path).await?;
+    if text.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    settings::parse_json_with_comments(&text).with_context(|| format!("failed to parse {path:?}"))
+}
+
+fn steps_to_json(steps: &[MacroStep]) -> Value {
+    Value::Array(
+        steps
+            .iter()
+            .map(|step| match step {
+                MacroStep::Action { action, input } => {
+                    let arguments = input
+                        .as_ref()
+                        .and_then(|input| serde_json::from_str::<Value>(input).log_err());
+                    match arguments {
+                        Some(arguments) => json!([action.name(), arguments]),
+                        None => json!(action.name()),
+                    }
+                }
+                MacroStep::Insertion {
+                    text,
+                    utf16_range_to_replace: None,
+                } => json!({ "text": text.as_ref() }),
+                MacroStep::Insertion {
+                    text,
+                    utf16_range_to_replace: Some(range),
+                } => json!({ "text": text.as_ref(), "replace": [range.start, range.end] }),
+            })
+            .collect(),
+    )
+}
+
+fn steps_from_json(value: &Value, cx: &App) -> Result<Arc<[MacroStep]>> {
+    let steps = value
+        .as_array()
+        .context("a macro must be an array of steps")?;
+    steps
+        .iter()
+        .map(|step| match step {
+            Value::String(name) => Ok(MacroStep::Action {
+                action: cx.build_action(name, None)?,
+                input: None,
+            }),
+            Value::Array(pair) if pair.len() == 2 && pair[0].is_string() => {
+                let name = pair[0].as_str().unwrap_or_default();
+                Ok(MacroStep::Action {
+                    action: cx.build_action(name, Some(pair[1].clone()))?,
+                    input: Some(pair[1].to_string().into()),
+                })
+            }
+            Value::Object(object) => {
+                let text = object
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .context("an insertion step needs a \"text\" string")?;
+                let utf16_range_to_replace = match object.get("replace") {
+                    Some(range) => {
+                        let (start, end): (isize, isize) = serde_json::from_value(range.clone())
+                            .context("\"replace\" must be a [start, end] pair")?;
+                        Some(start..end)
+                    }
+                    None => None,
+                };
+                Ok(MacroStep::Insertion {
+                    text: text.into(),
+                    utf16_range_to_replace,
+                })
+            }
+            _ => anyhow::bail!("invalid macro step: {step}"),
+        })
+        .collect()
+}
+
+struct SaveMacroModal {
+    editor: WeakEntity<Editor>,
+    name_editor: Entity<Editor>,
+}
+
+impl SaveMacroModal {
+    fn new(editor: WeakEntity<Editor>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut name_editor = Editor::single_line(window, cx);
+            name_editor.set_placeholder_text("Macro name", window, cx);
+            name_editor
+        });
+        Self {
+            editor,
+            name_editor,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        self.editor
+            .update(cx, |editor, cx| editor.save_last_macro_as(name, window, cx))
+            .log_err();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for SaveMacroModal {}
+
+impl ModalView for SaveMacroModal {}
+
+impl Focusable for SaveMacroModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for SaveMacroModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SaveMacroModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_3(cx)
+            .w_96()
+            .overflow_hidden()
+            .child(
+                div()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().p_2().child(
+                    Label::new(format!(
+                        "Save the last recorded macro to {}",
+                        paths::macros_file().display()
+                    ))
+                    .color(Color::Muted)
+                    .size(LabelSize::Small),
+                ),
+            )
+    }
+}
+
+/// Shows in the status bar while a macro is being recorded.
+pub struct MacroRecordingIndicator {
+    _observe_recorder: gpui::Subscription,
+}
+
+impl MacroRecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            _observe_recorder: cx.observe_global::<MacroRecorder>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for MacroRecordingIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let recording = cx
+            .try_global::<MacroRecorder>()
+            .is_some_and(|recorder| recorder.recording.is_some() && !recorder.playing);
+        div().when(recording, |el| {
+            el.child(
+                Button::new("macro-recording", "Recording Macro")
+                    .label_size(LabelSize::Small)
+                    .start_icon(
+                        Icon::new(IconName::Circle)
+                            .size(IconSize::XSmall)
+                            .color(Color::Error),
+                    )
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(ToggleMacroRecording.boxed_clone(), cx)
+                    })
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Stop Recording", &ToggleMacroRecording, cx)
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for MacroRecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        MoveToEndOfLine, editor_tests::init_test, test::editor_test_context::EditorTestContext,
+    };
+    use gpui::{KeyBinding, TestAppContext};
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_record_and_play_macro(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.update(|_, cx| {
+            cx.bind_keys([KeyBinding::new(
+                "ctrl-e",
+                MoveToEndOfLine {
+                    stop_at_soft_wraps: true,
+                },
+                Some("Editor"),
+            )])
+        });
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three
+        "});
+
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_macro_recording(&ToggleMacroRecording, window, cx);
+        });
+        cx.simulate_keystrokes("ctrl-e");
+        cx.simulate_input(";");
+        cx.update_editor(|editor, window, cx| {
+            editor.toggle_macro_recording(&ToggleMacroRecording, window, cx);
+        });
+        cx.assert_editor_state(indoc! {"
+            one;ˇ
+            two
+            three
+        "});
+
+        // Play the macro from the start of each of the other lines.
+        cx.set_selections_state(indoc! {"
+            one;
+            t«wo
+            thˇ»ree
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.play_macro(
+                &PlayMacro {
+                    target: MacroTarget::EachLine,
+                    ..Default::default()
+                },
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            one;
+            two;ˇ
+            three;ˇ
+        "});
+
+        // The whole playback is undone at once.
+        cx.update_editor(|editor, window, cx| editor.undo(&crate::Undo, window, cx));
+        cx.assert_editor_state(indoc! {"
+            one;
+            t«wo
+            thˇ»ree
+        "});
+
+        cx.set_selections_state(indoc! {"
+            oneˇ;
+            two
+            three
+        "});
+        cx.update_editor(|editor, window, cx| {
+            editor.play_macro(
+                &PlayMacro {
+                    times: Some(2),
+                    ..Default::default()
+                },
+                window,
+                cx,
+            );
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            one;;;ˇ
+            two
+            three
+        "});
+    }
+
+    #[gpui::test]
+    async fn test_record_actions_dispatched_without_keystrokes(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state("ˇone");
+
+        cx.dispatch_action(ToggleMacroRecording);
+        assert!(cx.read_global::<MacroRecorder, _>(|recorder, _| recorder.recording.is_some()));
+        // As done by the command palette, menus and buttons.
+        cx.dispatch_action(MoveToEndOfLine {
+            stop_at_soft_wraps: true,
+        });
+        cx.simulate_input(";");
+        cx.dispatch_action(ToggleMacroRecording);
+        cx.assert_editor_state("one;ˇ");
+
+        let steps = cx.read_global::<MacroRecorder, _>(|recorder, _| {
+            steps_to_json(recorder.last_macro.as_deref().unwrap())
+        });
+        assert_eq!(
+            steps,
+            json!([
+                "editor::MoveToEndOfLine",
+                { "text": ";" },
+            ])
+        );
+    }
+
+    #[gpui::test]
+    fn test_macro_steps_round_trip(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            let saved = json!([
+                "editor::MoveToEndOfLine",
+                ["editor::SelectNext", { "replace_newest": true }],
+                { "text": "x" },
+                { "text": "é", "replace": [-1, 0] },
+            ]);
+            let steps = steps_from_json(&saved, cx).unwrap();
+            assert_eq!(steps.len(), 4);
+            assert_eq!(steps_to_json(&steps), saved);
+
+            assert!(steps_from_json(&json!(["editor::NoSuchAction"]), cx).is_err());
+            assert!(steps_from_json(&json!([{ "replace": [0, 1] }]), cx).is_err());
+        });
+    }
+}
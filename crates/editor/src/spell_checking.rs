@@ -0,0 +1,282 @@
+//! Underlines misspelled words in comments, strings and prose, and suggests
+//! corrections for them as code actions.
+
+use std::{ops::Range, path::PathBuf, sync::Arc, time::Duration};
+
+use crate::{CodeActionProvider, Editor, HighlightKey};
+use anyhow::{Context as _, Result, anyhow};
+use gpui::{
+    App, AppContext as _, Context, Entity, HighlightStyle, Task, UnderlineStyle, Window, px,
+};
+use itertools::Itertools as _;
+use language::{Buffer, OffsetRangeExt as _, language_settings::language_settings};
+use multi_buffer::ExcerptId;
+use project::{CodeAction, LspAction, Project, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use spell_check::{SpellChecker, WordList};
+use ui::ActiveTheme as _;
+
+const SPELL_CHECK_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl Editor {
+    /// Underlines the misspelled words in the visible excerpts of buffers
+    /// whose language has `spell_check` enabled.
+    pub(crate) fn refresh_spell_check(&mut self, debounce: bool, cx: &mut Context<Self>) {
+        if !self.mode.is_full() {
+            return;
+        }
+
+        let visible_excerpts = self
+            .visible_excerpts(false, cx)
+            .into_iter()
+            .filter(|(_, (buffer, _, _))| is_spell_check_enabled(buffer.read(cx), cx))
+            .collect::<Vec<_>>();
+        if visible_excerpts.is_empty() {
+            self.spell_check_task = Task::ready(());
+            self.clear_highlights(HighlightKey::SpellCheck, cx);
+            return;
+        }
+        // The highlights are refreshed once the dictionaries are loaded.
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return;
+        };
+
+        let project = self.project.as_ref().map(|project| project.read(cx));
+        let fs = project.map(|project| project.fs().clone());
+        let excerpts = visible_excerpts
+            .into_iter()
+            .map(|(excerpt_id, (buffer, _, range))| {
+                let word_list_path = project
+                    .and_then(|project| project_word_list_path(project, buffer.read(cx), cx));
+                (
+                    excerpt_id,
+                    buffer.read(cx).snapshot(),
+                    range,
+                    word_list_path,
+                )
+            })
+            .collect::<Vec<_>>();
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let style = HighlightStyle {
+            underline: Some(UnderlineStyle {
+                color: Some(cx.theme().status().info),
+                thickness: px(1.),
+                wavy: true,
+            }),
+            ..HighlightStyle::default()
+        };
+
+        self.spell_check_task = cx.spawn(async move |editor, cx| {
+            if debounce {
+                cx.background_executor().timer(SPELL_CHECK_DEBOUNCE).await;
+            }
+
+            let misspellings = cx
+                .background_spawn(async move {
+                    let mut misspellings = Vec::new();
+                    for (excerpt_id, buffer_snapshot, range, word_list_path) in excerpts {
+                        let project_words = match (&fs, word_list_path) {
+                            (Some(fs), Some(path)) => {
+                                spell_checker.word_list(fs.as_ref(), &path).await
+                            }
+                            _ => Arc::default(),
+                        };
+                        let ranges =
+                            spell_checker.misspellings(&buffer_snapshot, range, &project_words);
+                        let text_anchors = ranges.iter().flat_map(|range| {
+                            [
+                                buffer_snapshot.anchor_before(range.start),
+                                buffer_snapshot.anchor_after(range.end),
+                            ]
+                        });
+                        let Some(anchors) =
+                            multi_buffer_snapshot.anchors_in_excerpt(excerpt_id, text_anchors)
+                        else {
+                            continue;
+                        };
+                        misspellings.extend(
+                            anchors
+                                .tuples()
+                                .filter_map(|(start, end)| Some(start?..end?)),
+                        );
+                    }
+                    misspellings.sort_by(|a, b| a.start.cmp(&b.start, &multi_buffer_snapshot));
+                    misspellings
+                })
+                .await;
+
+            editor
+                .update(cx, |editor, cx| {
+                    editor.highlight_text(HighlightKey::SpellCheck, misspellings, style, cx);
+                })
+                .ok();
+        });
+    }
+}
+
+fn is_spell_check_enabled(buffer: &Buffer, cx: &App) -> bool {
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .spell_check
+}
+
+/// Returns the path of the word list of the worktree containing `buffer`,
+/// which can only be read and written in local projects.
+fn project_word_list_path(project: &Project, buffer: &Buffer, cx: &App) -> Option<PathBuf> {
+    if !project.is_local() {
+        return None;
+    }
+    let file = buffer.file()?;
+    let worktree = project.worktree_for_id(file.worktree_id(cx), cx)?;
+    Some(
+        worktree
+            .read(cx)
+            .abs_path()
+            .join(paths::local_dictionary_file_relative_path().as_std_path()),
+    )
+}
+
+/// The data of the code actions offered for misspelled words.
+#[derive(Serialize, Deserialize)]
+enum SpellCheckAction {
+    Replace(String),
+    AddToProjectDictionary(String),
+}
+
+/// Suggests corrections for the misspelled words at the cursor, and adding
+/// them to the project's word list.
+pub(crate) struct SpellCheckCodeActionProvider {
+    pub(crate) project: Entity<Project>,
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        if !is_spell_check_enabled(buffer.read(cx), cx) {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let project = self.project.read(cx);
+        let fs = project.fs().clone();
+        let word_list_path = project_word_list_path(project, buffer.read(cx), cx);
+        let snapshot = buffer.read(cx).snapshot();
+        let range = range.to_offset(&snapshot);
+        cx.background_spawn(async move {
+            let project_words = match &word_list_path {
+                Some(path) => spell_checker.word_list(fs.as_ref(), path).await,
+                None => Arc::new(WordList::default()),
+            };
+            let mut actions = Vec::new();
+            for misspelling in spell_checker.misspellings(&snapshot, range.clone(), &project_words)
+            {
+                if misspelling.end < range.start || misspelling.start > range.end {
+                    continue;
+                }
+                let word = snapshot
+                    .text_for_range(misspelling.clone())
+                    .collect::<String>();
+                let word_range = snapshot.anchor_before(misspelling.start)
+                    ..snapshot.anchor_after(misspelling.end);
+                for suggestion in spell_checker.suggestions(&word) {
+                    actions.push(code_action(
+                        word_range.clone(),
+                        format!("Change to \"{suggestion}\""),
+                        SpellCheckAction::Replace(suggestion),
+                    ));
+                }
+                if word_list_path.is_some() {
+                    actions.push(code_action(
+                        word_range,
+                        format!("Add \"{word}\" to project dictionary"),
+                        SpellCheckAction::AddToProjectDictionary(word),
+                    ));
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        push_to_history: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = match &action.lsp_action {
+            LspAction::Action(lsp_action) => lsp_action
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value(data).ok()),
+            _ => None,
+        };
+        let Some(spell_check_action) = spell_check_action else {
+            return Task::ready(Err(anyhow!("not a spell check code action")));
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace(replacement) => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    buffer.end_transaction(cx)?;
+                    let transaction = buffer.finalize_last_transaction()?.clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction.id);
+                    }
+                    Some(transaction)
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckAction::AddToProjectDictionary(word) => {
+                let project = self.project.read(cx);
+                let fs = project.fs().clone();
+                let Some(path) = project_word_list_path(project, buffer.read(cx), cx) else {
+                    return Task::ready(Err(anyhow!("the project has no word list")));
+                };
+                cx.spawn(async move |cx| {
+                    spell_check::add_to_word_list(fs.as_ref(), &path, &word)
+                        .await
+                        .with_context(|| format!("adding {word:?} to {path:?}"))?;
+                    cx.update(SpellChecker::word_lists_changed);
+                    Ok(ProjectTransaction::default())
+                })
+            }
+        }
+    }
+}
+
+fn code_action(range: Range<text::Anchor>, title: String, action: SpellCheckAction) -> CodeAction {
+    CodeAction {
+        server_id: language::LanguageServerId(0),
+        range,
+        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+            title,
+            data: serde_json::to_value(action).ok(),
+            ..lsp::CodeAction::default()
+        })),
+        resolved: true,
+    }
+}
@@ -0,0 +1,386 @@
+use crate::FileHistorySettings;
+use anyhow::{Context as _, Result};
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, BackgroundExecutor, Global, Task};
+use indoc::indoc;
+use parking_lot::Mutex;
+use sha2::{Digest as _, Sha256};
+use sqlez::connection::Connection;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zed_env_vars::ZED_STATELESS;
+
+/// What caused a snapshot of a file to be taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotSource {
+    /// The file was opened in an editor.
+    Opened,
+    /// The buffer was saved.
+    Saved,
+    /// The file was about to be overwritten by the first save of a buffer
+    /// whose file hadn't been snapshotted yet.
+    BeforeSave,
+    /// The file was changed by another program.
+    ChangedOnDisk,
+}
+
+impl SnapshotSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Opened => "opened",
+            Self::Saved => "saved",
+            Self::BeforeSave => "before_save",
+            Self::ChangedOnDisk => "changed_on_disk",
+        }
+    }
+
+    fn from_str(source: &str) -> Option<Self> {
+        match source {
+            "opened" => Some(Self::Opened),
+            "saved" => Some(Self::Saved),
+            "before_save" => Some(Self::BeforeSave),
+            "changed_on_disk" => Some(Self::ChangedOnDisk),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Opened => "Opened",
+            Self::Saved => "Saved",
+            Self::BeforeSave => "Before Save",
+            Self::ChangedOnDisk => "Changed on Disk",
+        }
+    }
+}
+
+/// A version of a file recorded in the history, without its contents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub id: i64,
+    pub timestamp: SystemTime,
+    pub source: SnapshotSource,
+    pub len: usize,
+}
+
+/// Stores the snapshots of every file, keyed by its absolute path. Contents
+/// are stored once per digest, so that snapshots of unchanged contents, such
+/// as those of files which are saved and later reverted, share storage.
+pub struct FileHistoryDatabase {
+    executor: BackgroundExecutor,
+    connection: Arc<Mutex<Connection>>,
+}
+
+struct GlobalFileHistoryDatabase(
+    Shared<Task<Result<Arc<FileHistoryDatabase>, Arc<anyhow::Error>>>>,
+);
+
+impl Global for GlobalFileHistoryDatabase {}
+
+impl FileHistoryDatabase {
+    pub fn connect(
+        cx: &mut App,
+    ) -> Shared<Task<Result<Arc<FileHistoryDatabase>, Arc<anyhow::Error>>>> {
+        if cx.has_global::<GlobalFileHistoryDatabase>() {
+            return cx.global::<GlobalFileHistoryDatabase>().0.clone();
+        }
+        let executor = cx.background_executor().clone();
+        let task = executor
+            .spawn({
+                let executor = executor.clone();
+                async move {
+                    match FileHistoryDatabase::new(executor) {
+                        Ok(db) => Ok(Arc::new(db)),
+                        Err(err) => Err(Arc::new(err)),
+                    }
+                }
+            })
+            .shared();
+
+        cx.set_global(GlobalFileHistoryDatabase(task.clone()));
+        task
+    }
+
+    pub fn new(executor: BackgroundExecutor) -> Result<Self> {
+        let connection = if *ZED_STATELESS {
+            Connection::open_memory(Some("FILE_HISTORY_FALLBACK_DB"))
+        } else if cfg!(any(feature = "test-support", test)) {
+            let thread = std::thread::current();
+            let test_name = thread.name();
+            Connection::open_memory(Some(&format!(
+                "FILE_HISTORY_FALLBACK_{}",
+                test_name.unwrap_or_default()
+            )))
+        } else {
+            let history_dir = paths::data_dir().join("file_history");
+            std::fs::create_dir_all(&history_dir)?;
+            let sqlite_path = history_dir.join("file_history.db");
+            Connection::open_file(&sqlite_path.to_string_lossy())
+        };
+
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS contents (
+                digest BLOB PRIMARY KEY,
+                text TEXT NOT NULL,
+                len INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path BLOB NOT NULL,
+                timestamp INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                digest BLOB NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS snapshots_path ON snapshots(path);
+            CREATE INDEX IF NOT EXISTS snapshots_digest ON snapshots(digest);
+        "})?()?;
+
+        Ok(Self {
+            executor,
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Records the contents of a file, unless they're the same as those of
+    /// its latest snapshot, and then discards the snapshots exceeding the
+    /// limits of the settings. Returns whether a snapshot was recorded.
+    pub fn record_snapshot(
+        &self,
+        path: PathBuf,
+        text: String,
+        source: SnapshotSource,
+        timestamp: SystemTime,
+        settings: FileHistorySettings,
+    ) -> Task<Result<bool>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let digest: [u8; 32] = Sha256::digest(text.as_bytes()).into();
+            let timestamp = unix_seconds(timestamp);
+            let connection = connection.lock();
+            connection.with_savepoint("record_snapshot", || {
+                let latest_digest = connection.select_row_bound::<&Path, [u8; 32]>(indoc! {"
+                    SELECT digest FROM snapshots WHERE path = ?1 ORDER BY id DESC LIMIT 1
+                "})?(path.as_path())?;
+                if latest_digest == Some(digest) {
+                    return Ok(false);
+                }
+
+                connection.exec_bound::<(&[u8; 32], &str, usize)>(indoc! {"
+                    INSERT OR IGNORE INTO contents (digest, text, len) VALUES (?1, ?2, ?3)
+                "})?((&digest, text.as_str(), text.len()))?;
+                connection.exec_bound::<(&Path, i64, &str, &[u8; 32])>(indoc! {"
+                    INSERT INTO snapshots (path, timestamp, source, digest)
+                    VALUES (?1, ?2, ?3, ?4)
+                "})?((path.as_path(), timestamp, source.as_str(), &digest))?;
+                let snapshot_id = connection.select_row::<i64>("SELECT last_insert_rowid()")?()?
+                    .context("failed to insert snapshot")?;
+
+                Self::prune_sync(&connection, &path, snapshot_id, timestamp, &settings)?;
+                Ok(true)
+            })
+        })
+    }
+
+    /// Returns the snapshots of a file, from newest to oldest.
+    pub fn snapshots(&self, path: PathBuf) -> Task<Result<Vec<Snapshot>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select =
+                connection.select_bound::<&Path, (i64, i64, String, usize)>(indoc! {"
+                    SELECT snapshots.id, snapshots.timestamp, snapshots.source, contents.len
+                    FROM snapshots
+                    JOIN contents ON contents.digest = snapshots.digest
+                    WHERE snapshots.path = ?1
+                    ORDER BY snapshots.id DESC
+                "})?;
+            Ok(select(path.as_path())?
+                .into_iter()
+                .filter_map(|(id, timestamp, source, len)| {
+                    Some(Snapshot {
+                        id,
+                        timestamp: UNIX_EPOCH + Duration::from_secs(timestamp.max(0) as u64),
+                        source: SnapshotSource::from_str(&source)?,
+                        len,
+                    })
+                })
+                .collect())
+        })
+    }
+
+    /// Returns the contents of a snapshot, if it hasn't been discarded.
+    pub fn snapshot_text(&self, snapshot_id: i64) -> Task<Result<Option<String>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.select_row_bound::<i64, String>(indoc! {"
+                SELECT contents.text FROM snapshots
+                JOIN contents ON contents.digest = snapshots.digest
+                WHERE snapshots.id = ?1
+            "})?(snapshot_id)
+        })
+    }
+
+    fn prune_sync(
+        connection: &Connection,
+        path: &Path,
+        snapshot_id: i64,
+        now: i64,
+        settings: &FileHistorySettings,
+    ) -> Result<()> {
+        connection.exec_bound::<(&Path, usize)>(indoc! {"
+            DELETE FROM snapshots WHERE path = ?1 AND id NOT IN (
+                SELECT id FROM snapshots WHERE path = ?1 ORDER BY id DESC LIMIT ?2
+            )
+        "})?((path, settings.max_snapshots_per_file.max(1)))?;
+
+        let max_age = settings.max_age_days.saturating_mul(24 * 60 * 60);
+        let cutoff = now.saturating_sub(i64::try_from(max_age).unwrap_or(i64::MAX));
+        connection.exec_bound::<(i64, i64)>(indoc! {"
+            DELETE FROM snapshots WHERE timestamp < ?1 AND id != ?2
+        "})?((cutoff, snapshot_id))?;
+        Self::delete_unreferenced_contents_sync(connection)?;
+
+        let max_total_size = settings.max_total_size_mb.saturating_mul(1024 * 1024);
+        let total_size =
+            connection.select_row::<u64>("SELECT COALESCE(SUM(len), 0) FROM contents")?()?
+                .unwrap_or_default();
+        if total_size <= max_total_size {
+            return Ok(());
+        }
+
+        // Discard the oldest snapshots until the contents fit, always keeping
+        // the snapshot that was just recorded. Contents are freed once their
+        // newest snapshot is discarded, so walking the newest snapshot of each
+        // digest from newest to oldest gives the size kept by discarding every
+        // older snapshot.
+        connection.exec_bound::<(i64, u64)>(indoc! {"
+            DELETE FROM snapshots WHERE id != ?1 AND id <= (
+                SELECT MAX(newest_id) FROM (
+                    SELECT newest_id, SUM(len) OVER (ORDER BY newest_id DESC) AS kept_size
+                    FROM (
+                        SELECT MAX(snapshots.id) AS newest_id, contents.len AS len
+                        FROM snapshots
+                        JOIN contents ON contents.digest = snapshots.digest
+                        GROUP BY snapshots.digest
+                    )
+                )
+                WHERE kept_size > ?2
+            )
+        "})?((snapshot_id, max_total_size))?;
+        Self::delete_unreferenced_contents_sync(connection)
+    }
+
+    fn delete_unreferenced_contents_sync(connection: &Connection) -> Result<()> {
+        connection.exec(indoc! {"
+            DELETE FROM contents WHERE digest NOT IN (SELECT digest FROM snapshots)
+        "})?()
+    }
+}
+
+fn unix_seconds(timestamp: SystemTime) -> i64 {
+    timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    fn settings() -> FileHistorySettings {
+        FileHistorySettings {
+            enabled: true,
+            max_snapshots_per_file: 50,
+            max_age_days: 30,
+            max_total_size_mb: 256,
+            max_file_size_kb: 1024,
+        }
+    }
+
+    async fn record(
+        db: &FileHistoryDatabase,
+        path: &str,
+        text: &str,
+        timestamp: SystemTime,
+        settings: &FileHistorySettings,
+    ) -> bool {
+        db.record_snapshot(
+            PathBuf::from(path),
+            text.to_string(),
+            SnapshotSource::Saved,
+            timestamp,
+            settings.clone(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn texts(db: &FileHistoryDatabase, path: &str) -> Vec<String> {
+        let mut texts = Vec::new();
+        for snapshot in db.snapshots(PathBuf::from(path)).await.unwrap() {
+            texts.push(db.snapshot_text(snapshot.id).await.unwrap().unwrap());
+        }
+        texts
+    }
+
+    #[gpui::test]
+    async fn test_deduplicated_snapshots(cx: &mut TestAppContext) {
+        let db = FileHistoryDatabase::new(cx.executor()).unwrap();
+        let settings = settings();
+        let now = SystemTime::now();
+
+        assert!(record(&db, "/a.txt", "one", now, &settings).await);
+        assert!(!record(&db, "/a.txt", "one", now, &settings).await);
+        assert!(record(&db, "/a.txt", "two", now, &settings).await);
+        assert!(record(&db, "/a.txt", "one", now, &settings).await);
+        assert!(record(&db, "/b.txt", "one", now, &settings).await);
+
+        assert_eq!(texts(&db, "/a.txt").await, ["one", "two", "one"]);
+        assert_eq!(texts(&db, "/b.txt").await, ["one"]);
+        let content_count = db
+            .connection
+            .lock()
+            .select_row::<usize>("SELECT COUNT(*) FROM contents")
+            .unwrap()()
+        .unwrap();
+        assert_eq!(content_count, Some(2));
+    }
+
+    #[gpui::test]
+    async fn test_pruned_snapshots(cx: &mut TestAppContext) {
+        let db = FileHistoryDatabase::new(cx.executor()).unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let settings = FileHistorySettings {
+            max_snapshots_per_file: 3,
+            max_age_days: 7,
+            ..settings()
+        };
+        record(&db, "/a.txt", "old", now - day * 10, &settings).await;
+        record(&db, "/b.txt", "old", now - day * 10, &settings).await;
+        assert_eq!(texts(&db, "/b.txt").await, ["old"]);
+        for text in ["one", "two", "three", "four"] {
+            record(&db, "/a.txt", text, now, &settings).await;
+        }
+        assert_eq!(texts(&db, "/a.txt").await, ["four", "three", "two"]);
+        assert_eq!(texts(&db, "/b.txt").await, Vec::<String>::new());
+
+        let large_text = "x".repeat(700 * 1024);
+        let settings = FileHistorySettings {
+            max_total_size_mb: 1,
+            ..settings
+        };
+        record(&db, "/c.txt", &large_text, now, &settings).await;
+        assert_eq!(texts(&db, "/a.txt").await, ["four", "three", "two"]);
+        record(&db, "/d.txt", &format!("{large_text}!"), now, &settings).await;
+        assert_eq!(texts(&db, "/a.txt").await, Vec::<String>::new());
+        assert_eq!(texts(&db, "/c.txt").await, Vec::<String>::new());
+        assert_eq!(texts(&db, "/d.txt").await.len(), 1);
+    }
+}
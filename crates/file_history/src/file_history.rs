@@ -0,0 +1,366 @@
+//! A local history of the files edited in Zed.
+//!
+//! The contents of local files are snapshotted when they're opened in an
+//! editor, saved, or changed on disk by another program, and kept in a
+//! bounded SQLite store in the data directory, independently of version
+//! control. The timeline of a file lists its snapshots, which can be compared
+//! with the buffer or restored into it.
+
+mod db;
+mod file_history_settings;
+mod timeline;
+
+use anyhow::anyhow;
+use collections::HashSet;
+use editor::Editor;
+use fs::Fs;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{App, AppContext as _, Entity, EntityId, Global, Task, actions};
+use language::{Buffer, BufferEvent, TextBufferSnapshot};
+use project::{Project, buffer_store::BufferStoreEvent};
+use settings::Settings as _;
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+use util::ResultExt as _;
+use workspace::{ItemHandle, Workspace};
+
+pub use db::{FileHistoryDatabase, Snapshot, SnapshotSource};
+pub use file_history_settings::FileHistorySettings;
+pub use timeline::FileHistoryTimeline;
+
+actions!(
+    file_history,
+    [
+        /// Shows the snapshots of the active file, to compare them with the
+        /// buffer or restore them.
+        ShowTimeline
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    let (snapshots_tx, snapshots_rx) = mpsc::unbounded();
+    let record_snapshots =
+        cx.spawn(async move |cx| FileHistory::record_snapshots(snapshots_rx, cx).await);
+    cx.set_global(FileHistory {
+        projects: HashSet::default(),
+        snapshotted_buffers: HashSet::default(),
+        snapshots_tx,
+        _record_snapshots: record_snapshots,
+    });
+
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        FileHistory::register_project(workspace.project().clone(), cx);
+        workspace.register_action(timeline::show_timeline);
+        cx.subscribe_self(|_, event: &workspace::Event, cx| {
+            if let workspace::Event::ItemAdded { item } = event {
+                FileHistory::snapshot_opened_item(item.as_ref(), cx);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// The contents of a file to be recorded in the history.
+enum PendingContents {
+    Buffer(TextBufferSnapshot),
+    Disk(Arc<dyn Fs>),
+}
+
+struct PendingSnapshot {
+    path: PathBuf,
+    contents: PendingContents,
+    source: SnapshotSource,
+    timestamp: SystemTime,
+    settings: FileHistorySettings,
+}
+
+/// Records snapshots of the buffers of the local projects open in workspaces.
+struct FileHistory {
+    projects: HashSet<EntityId>,
+    /// The buffers whose file has been snapshotted since they were opened.
+    snapshotted_buffers: HashSet<EntityId>,
+    snapshots_tx: mpsc::UnboundedSender<PendingSnapshot>,
+    _record_snapshots: Task<()>,
+}
+
+impl Global for FileHistory {}
+
+impl FileHistory {
+    fn register_project(project: Entity<Project>, cx: &mut App) {
+        let project_id = project.entity_id();
+        if !project.read(cx).is_local() || !cx.global_mut::<Self>().projects.insert(project_id) {
+            return;
+        }
+        cx.observe_release(&project, move |_, cx| {
+            cx.global_mut::<Self>().projects.remove(&project_id);
+        })
+        .detach();
+
+        let fs = project.read(cx).fs().clone();
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let buffers = buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        for buffer in buffers {
+            Self::watch_buffer(buffer, fs.clone(), cx);
+        }
+        cx.subscribe(&buffer_store, move |_, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                Self::watch_buffer(buffer.clone(), fs.clone(), cx);
+            }
+        })
+        .detach();
+    }
+
+    /// Snapshots the file of an editor opened in a workspace. Buffers opened
+    /// in bulk, e.g. by project search or agent tools, aren't snapshotted
+    /// until they change, so they can't crowd out the rest of the history.
+    fn snapshot_opened_item(item: &dyn ItemHandle, cx: &mut App) {
+        let Some(editor) = item.act_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        Self::snapshot_buffer(&buffer, SnapshotSource::Opened, cx);
+    }
+
+    fn watch_buffer(buffer: Entity<Buffer>, fs: Arc<dyn Fs>, cx: &mut App) {
+        let buffer_id = buffer.entity_id();
+        cx.observe_release(&buffer, move |_, cx| {
+            cx.global_mut::<Self>()
+                .snapshotted_buffers
+                .remove(&buffer_id);
+        })
+        .detach();
+
+        // What's on disk, to be recorded before the first save of a buffer
+        // whose file wasn't snapshotted, e.g. when it's saved by an agent or a
+        // tool without having been opened in an editor.
+        let mut saved_contents =
+            (!buffer.read(cx).is_dirty()).then(|| buffer.read(cx).text_snapshot());
+        cx.subscribe(&buffer, move |buffer, event, cx| match event {
+            BufferEvent::Saved => {
+                if let Some(saved_contents) = saved_contents.take()
+                    && !cx.global::<Self>().snapshotted_buffers.contains(&buffer_id)
+                {
+                    Self::enqueue(
+                        &buffer,
+                        PendingContents::Buffer(saved_contents),
+                        SnapshotSource::BeforeSave,
+                        cx,
+                    );
+                }
+                Self::snapshot_buffer(&buffer, SnapshotSource::Saved, cx)
+            }
+            BufferEvent::Reloaded => {
+                saved_contents = None;
+                Self::snapshot_buffer(&buffer, SnapshotSource::ChangedOnDisk, cx)
+            }
+            // The buffer has unsaved edits, so it wasn't reloaded. Record what
+            // was written to disk, as saving the buffer will overwrite it.
+            BufferEvent::FileHandleChanged if buffer.read(cx).has_conflict() => {
+                Self::snapshot_file_on_disk(&buffer, fs.clone(), cx)
+            }
+            _ => {}
+        })
+        .detach();
+    }
+
+    fn snapshot_buffer(buffer: &Entity<Buffer>, source: SnapshotSource, cx: &mut App) {
+        let snapshot = buffer.read(cx).text_snapshot();
+        Self::enqueue(buffer, PendingContents::Buffer(snapshot), source, cx);
+    }
+
+    fn snapshot_file_on_disk(buffer: &Entity<Buffer>, fs: Arc<dyn Fs>, cx: &mut App) {
+        Self::enqueue(
+            buffer,
+            PendingContents::Disk(fs),
+            SnapshotSource::ChangedOnDisk,
+            cx,
+        );
+    }
+
+    fn enqueue(
+        buffer: &Entity<Buffer>,
+        contents: PendingContents,
+        source: SnapshotSource,
+        cx: &mut App,
+    ) {
+        let settings = FileHistorySettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        let buffer_id = buffer.entity_id();
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+            return;
+        };
+        if buffer.len() as u64 > settings.max_file_size_kb.saturating_mul(1024) {
+            return;
+        }
+        let snapshot = PendingSnapshot {
+            path: file.abs_path(cx),
+            contents,
+            source,
+            timestamp: SystemTime::now(),
+            settings: settings.clone(),
+        };
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        let this = cx.global_mut::<Self>();
+        this.snapshotted_buffers.insert(buffer_id);
+        this.snapshots_tx.unbounded_send(snapshot).ok();
+    }
+
+    /// Records the pending snapshots one at a time, so that they're stored in
+    /// the order in which they were taken.
+    async fn record_snapshots(
+        mut snapshots_rx: mpsc::UnboundedReceiver<PendingSnapshot>,
+        cx: &mut gpui::AsyncApp,
+    ) {
+        let Some(db) = cx
+            .update(FileHistoryDatabase::connect)
+            .await
+            .map_err(|error| anyhow!("{error:#}"))
+            .log_err()
+        else {
+            return;
+        };
+        while let Some(snapshot) = snapshots_rx.next().await {
+            let text = match snapshot.contents {
+                PendingContents::Buffer(buffer_snapshot) => {
+                    cx.background_spawn(async move { buffer_snapshot.text() })
+                        .await
+                }
+                PendingContents::Disk(fs) => match fs.load(&snapshot.path).await.log_err() {
+                    Some(text) => text,
+                    None => continue,
+                },
+            };
+            db.record_snapshot(
+                snapshot.path,
+                text,
+                snapshot.source,
+                snapshot.timestamp,
+                snapshot.settings,
+            )
+            .await
+            .log_err();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    async fn snapshot_texts(path: &str, cx: &mut TestAppContext) -> Vec<(SnapshotSource, String)> {
+        let db = cx.update(FileHistoryDatabase::connect).await.unwrap();
+        let mut texts = Vec::new();
+        for snapshot in db.snapshots(PathBuf::from(path)).await.unwrap() {
+            let text = db.snapshot_text(snapshot.id).await.unwrap().unwrap();
+            texts.push((snapshot.source, text));
+        }
+        texts
+    }
+
+    #[gpui::test]
+    async fn test_snapshots_on_save_and_reload(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(init);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.txt": "one\n" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.update(|cx| FileHistory::register_project(project.clone(), cx));
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        // Buffers opened outside of editors aren't snapshotted until they change.
+        assert!(snapshot_texts(path!("/project/a.txt"), cx).await.is_empty());
+
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..3, "two")], None, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        fs.save(
+            path!("/project/a.txt").as_ref(),
+            &"three\n".into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "three\n");
+
+        assert_eq!(
+            snapshot_texts(path!("/project/a.txt"), cx).await,
+            [
+                (SnapshotSource::ChangedOnDisk, "three\n".to_string()),
+                (SnapshotSource::Saved, "two\n".to_string()),
+                (SnapshotSource::BeforeSave, "one\n".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_snapshot_before_first_save_of_unopened_buffer(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(init);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.txt": "one\n" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        cx.update(|cx| FileHistory::register_project(project.clone(), cx));
+
+        // As done by agent tools, which edit and save buffers that aren't
+        // shown in editors.
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        for text in ["two", "three"] {
+            buffer.update(cx, |buffer, cx| {
+                let len = buffer.len();
+                buffer.edit([(0..len, format!("{text}\n"))], None, cx)
+            });
+            project
+                .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+                .await
+                .unwrap();
+            cx.run_until_parked();
+        }
+
+        // The contents that were on disk are only recorded before the first save.
+        assert_eq!(
+            snapshot_texts(path!("/project/a.txt"), cx).await,
+            [
+                (SnapshotSource::Saved, "three\n".to_string()),
+                (SnapshotSource::Saved, "two\n".to_string()),
+                (SnapshotSource::BeforeSave, "one\n".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for the local history of files.
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct FileHistorySettings {
+    /// Whether to snapshot local files when they are opened, saved or changed
+    /// on disk.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The maximum number of snapshots kept for each file.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: usize,
+    /// Snapshots older than this many days are discarded.
+    ///
+    /// Default: 30
+    pub max_age_days: u64,
+    /// The maximum size of the history of all files, in megabytes.
+    ///
+    /// Default: 256
+    pub max_total_size_mb: u64,
+    /// Files larger than this many kilobytes are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: u64,
+}
+
+impl Settings for FileHistorySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let content = content.file_history.clone().unwrap();
+        Self {
+            enabled: content.enabled.unwrap(),
+            max_snapshots_per_file: content.max_snapshots_per_file.unwrap(),
+            max_age_days: content.max_age_days.unwrap(),
+            max_total_size_mb: content.max_total_size_mb.unwrap(),
+            max_file_size_kb: content.max_file_size_kb.unwrap(),
+        }
+    }
+}
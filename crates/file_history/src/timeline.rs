@@ -0,0 +1,447 @@
+use crate::{FileHistoryDatabase, ShowTimeline, Snapshot};
+use anyhow::{Context as _, anyhow};
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use git_ui::file_diff_view::FileDiffView;
+use gpui::{
+    Action as _, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    ParentElement, Render, Styled, Task, WeakEntity, Window,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::{path::PathBuf, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace, notifications::DetachAndPromptErr as _};
+
+pub(crate) fn show_timeline(
+    workspace: &mut Workspace,
+    _: &ShowTimeline,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(buffer) = workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+    else {
+        return;
+    };
+    let Some(path) = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.as_local())
+        .map(|file| file.abs_path(cx))
+    else {
+        return;
+    };
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, move |window, cx| {
+        FileHistoryTimeline::new(buffer, path, workspace_handle, window, cx)
+    });
+}
+
+/// Lists the snapshots of a file, newest first. Confirming a snapshot opens a
+/// diff of it against the file's buffer, and the secondary confirm restores
+/// it into the buffer.
+pub struct FileHistoryTimeline {
+    picker: Entity<Picker<TimelineDelegate>>,
+    _load_snapshots: Task<()>,
+}
+
+impl FileHistoryTimeline {
+    fn new(
+        buffer: Entity<Buffer>,
+        path: PathBuf,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TimelineDelegate::new(buffer, workspace, cx.entity().downgrade());
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        let db = FileHistoryDatabase::connect(cx);
+        let load_snapshots = cx.spawn_in(window, {
+            let picker = picker.downgrade();
+            async move |_, cx| {
+                let snapshots = async {
+                    let db = db.await.map_err(|error| anyhow!("{error:#}"))?;
+                    let snapshots = db.snapshots(path).await?;
+                    anyhow::Ok((db, snapshots))
+                };
+                let Some((db, snapshots)) = snapshots.await.log_err() else {
+                    return;
+                };
+                picker
+                    .update_in(cx, |picker, window, cx| {
+                        picker.delegate.set_snapshots(db, snapshots);
+                        picker.refresh(window, cx);
+                    })
+                    .log_err();
+            }
+        });
+
+        Self {
+            picker,
+            _load_snapshots: load_snapshots,
+        }
+    }
+}
+
+impl ModalView for FileHistoryTimeline {}
+
+impl EventEmitter<DismissEvent> for FileHistoryTimeline {}
+
+impl Focusable for FileHistoryTimeline {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for FileHistoryTimeline {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("FileHistoryTimeline")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+struct SnapshotCandidate {
+    snapshot: Snapshot,
+    label: String,
+    detail: String,
+}
+
+pub struct TimelineDelegate {
+    buffer: Entity<Buffer>,
+    workspace: WeakEntity<Workspace>,
+    timeline: WeakEntity<FileHistoryTimeline>,
+    db: Option<Arc<FileHistoryDatabase>>,
+    candidates: Vec<SnapshotCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl TimelineDelegate {
+    fn new(
+        buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        timeline: WeakEntity<FileHistoryTimeline>,
+    ) -> Self {
+        Self {
+            buffer,
+            workspace,
+            timeline,
+            db: None,
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn set_snapshots(&mut self, db: Arc<FileHistoryDatabase>, snapshots: Vec<Snapshot>) {
+        let now = OffsetDateTime::now_utc();
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        self.db = Some(db);
+        self.candidates = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let timestamp = OffsetDateTime::from(snapshot.timestamp);
+                let label = time_format::format_localized_timestamp(
+                    timestamp,
+                    now,
+                    local_offset,
+                    time_format::TimestampFormat::Relative,
+                );
+                let absolute_timestamp = time_format::format_localized_timestamp(
+                    timestamp,
+                    now,
+                    local_offset,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                );
+                SnapshotCandidate {
+                    detail: format!("{} · {absolute_timestamp}", snapshot.source.label()),
+                    label,
+                    snapshot,
+                }
+            })
+            .collect();
+    }
+
+    fn selected_candidate(&self) -> Option<&SnapshotCandidate> {
+        let mat = self.matches.get(self.selected_index)?;
+        self.candidates.get(mat.candidate_id)
+    }
+
+    fn open_diff(
+        &self,
+        candidate: &SnapshotCandidate,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<anyhow::Result<()>> {
+        let Some(db) = self.db.clone() else {
+            return Task::ready(Ok(()));
+        };
+        let snapshot_id = candidate.snapshot.id;
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        let file_name = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string())
+            .unwrap_or_default();
+        let title = format!("{file_name} ({})", candidate.label);
+        cx.spawn_in(window, async move |_, cx| {
+            let text = db
+                .snapshot_text(snapshot_id)
+                .await?
+                .context("the snapshot was discarded")?;
+            let open_diff = cx.update(|window, cx| {
+                let language = buffer.read(cx).language().cloned();
+                let old_buffer = cx.new(|cx| {
+                    let mut old_buffer = Buffer::local(text, cx);
+                    old_buffer.set_language(language, cx);
+                    old_buffer
+                });
+                FileDiffView::open_buffers(
+                    old_buffer,
+                    Some(title.into()),
+                    buffer,
+                    workspace,
+                    window,
+                    cx,
+                )
+            })?;
+            open_diff.await?;
+            Ok(())
+        })
+    }
+
+    /// Replaces the contents of the buffer with those of the snapshot, as a
+    /// single transaction that can be undone.
+    fn restore(
+        &self,
+        candidate: &SnapshotCandidate,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<anyhow::Result<()>> {
+        let Some(db) = self.db.clone() else {
+            return Task::ready(Ok(()));
+        };
+        let snapshot_id = candidate.snapshot.id;
+        let buffer = self.buffer.clone();
+        cx.spawn(async move |_, cx| {
+            let text = db
+                .snapshot_text(snapshot_id)
+                .await?
+                .context("the snapshot was discarded")?;
+            let diff = buffer.update(cx, |buffer, cx| buffer.diff(text, cx)).await;
+            buffer.update(cx, |buffer, cx| {
+                buffer.finalize_last_transaction();
+                buffer.apply_diff(diff, cx);
+                buffer.finalize_last_transaction();
+            });
+            Ok(())
+        })
+    }
+}
+
+impl PickerDelegate for TimelineDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        "Search snapshots...".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No snapshots of this file".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(candidate) = self.selected_candidate() {
+            if secondary {
+                self.restore(candidate, cx).detach_and_prompt_err(
+                    "Failed to restore snapshot",
+                    window,
+                    cx,
+                    |_, _, _| None,
+                );
+            } else {
+                self.open_diff(candidate, window, cx).detach_and_prompt_err(
+                    "Failed to open snapshot",
+                    window,
+                    cx,
+                    |_, _, _| None,
+                );
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.timeline
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, candidate)| {
+                StringMatchCandidate::new(id, &format!("{} {}", candidate.label, candidate.detail))
+            })
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the timeline in chronological order.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let candidate = self.candidates.get(mat.candidate_id)?;
+
+        // The match string is the label followed by a space and the detail.
+        let label_len = candidate.label.len();
+        let label_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|&position| position < label_len)
+            .collect::<Vec<_>>();
+        let detail_positions = mat
+            .positions
+            .iter()
+            .filter_map(|&position| position.checked_sub(label_len + 1))
+            .collect::<Vec<_>>();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .gap_x_2()
+                        .child(HighlightedLabel::new(
+                            candidate.label.clone(),
+                            label_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(candidate.detail.clone(), detail_positions)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                )
+                .end_hover_slot(
+                    IconButton::new(("restore-snapshot", ix), IconName::Undo)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Restore Snapshot"))
+                        .on_click(cx.listener(move |picker, _, window, cx| {
+                            cx.stop_propagation();
+                            picker.delegate.selected_index = ix;
+                            picker.delegate.confirm(true, window, cx);
+                        })),
+                ),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<gpui::AnyElement> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .p_1p5()
+                .w_full()
+                .justify_end()
+                .gap_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("compare-snapshot", "Compare with Buffer")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("restore-snapshot", "Restore")
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+}
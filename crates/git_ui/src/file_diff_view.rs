@@ -5,8 +5,8 @@ use buffer_diff::BufferDiff;
 use editor::{Editor, EditorEvent, MultiBuffer};
 use futures::{FutureExt, select_biased};
 use gpui::{
-    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
-    Focusable, Font, IntoElement, Render, Task, WeakEntity, Window,
+    AnyElement, App, AppContext as _, AsyncApp, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, Font, IntoElement, Render, Task, WeakEntity, Window,
 };
 use language::{Buffer, HighlightedText, LanguageRegistry};
 use project::Project;
@@ -29,6 +29,7 @@ pub struct FileDiffView {
     editor: Entity<Editor>,
     old_buffer: Entity<Buffer>,
     new_buffer: Entity<Buffer>,
+    old_title: Option<SharedString>,
     buffer_changes_tx: watch::Sender<()>,
     _recalculate_diff_task: Task<Result<()>>,
 }
@@ -52,29 +53,61 @@ impl FileDiffView {
             let new_buffer = project
                 .update(cx, |project, cx| project.open_local_buffer(&new_path, cx))
                 .await?;
-            let languages = project.update(cx, |project, _| project.languages().clone());
-
-            let buffer_diff = build_buffer_diff(&old_buffer, &new_buffer, languages, cx).await?;
-
-            workspace.update_in(cx, |workspace, window, cx| {
-                let diff_view = cx.new(|cx| {
-                    FileDiffView::new(
-                        old_buffer,
-                        new_buffer,
-                        buffer_diff,
-                        project.clone(),
-                        window,
-                        cx,
-                    )
-                });
+            Self::add_to_workspace(old_buffer, None, new_buffer, project, workspace, cx).await
+        })
+    }
 
-                let pane = workspace.active_pane();
-                pane.update(cx, |pane, cx| {
-                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
-                });
+    /// Opens a diff of two buffers that are already loaded, such as a buffer
+    /// holding an earlier version of a file and the file's current buffer.
+    ///
+    /// `old_title` names the old side of the diff in the tab, in place of the
+    /// old buffer's file name.
+    pub fn open_buffers(
+        old_buffer: Entity<Buffer>,
+        old_title: Option<SharedString>,
+        new_buffer: Entity<Buffer>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            Self::add_to_workspace(old_buffer, old_title, new_buffer, project, workspace, cx).await
+        })
+    }
 
+    async fn add_to_workspace(
+        old_buffer: Entity<Buffer>,
+        old_title: Option<SharedString>,
+        new_buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let languages = project.update(cx, |project, _| project.languages().clone());
+
+        let buffer_diff = build_buffer_diff(&old_buffer, &new_buffer, languages, cx).await?;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let diff_view = cx.new(|cx| {
+                let mut diff_view = FileDiffView::new(
+                    old_buffer,
+                    new_buffer,
+                    buffer_diff,
+                    project.clone(),
+                    window,
+                    cx,
+                );
+                diff_view.old_title = old_title;
                 diff_view
-            })
+            });
+
+            let pane = workspace.active_pane();
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+            });
+
+            diff_view
         })
     }
 
@@ -123,6 +156,7 @@ impl FileDiffView {
             buffer_changes_tx,
             old_buffer,
             new_buffer,
+            old_title: None,
             _recalculate_diff_task: cx.spawn(async move |this, cx| {
                 while buffer_changes_rx.recv().await.is_ok() {
                     loop {
@@ -239,7 +273,11 @@ impl Item for FileDiffView {
                 })
                 .unwrap_or_else(|| "untitled".into())
         };
-        let old_filename = title_text(&self.old_buffer);
+        let old_filename = self
+            .old_title
+            .as_ref()
+            .map(|title| title.to_string())
+            .unwrap_or_else(|| title_text(&self.old_buffer));
         let new_filename = title_text(&self.new_buffer);
 
         format!("{old_filename} ↔ {new_filename}").into()
@@ -253,7 +291,11 @@ impl Item for FileDiffView {
                 .map(|file| file.full_path(cx).compact().to_string_lossy().into_owned())
                 .unwrap_or_else(|| "untitled".into())
         };
-        let old_path = path(&self.old_buffer);
+        let old_path = self
+            .old_title
+            .as_ref()
+            .map(|title| title.to_string())
+            .unwrap_or_else(|| path(&self.old_buffer));
         let new_path = path(&self.new_buffer);
 
         Some(format!("{old_path} ↔ {new_path}").into())
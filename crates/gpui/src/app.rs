@@ -239,6 +239,8 @@ type Handler = Box<dyn FnMut(&mut App) -> bool + 'static>;
 type Listener = Box<dyn FnMut(&dyn Any, &mut App) -> bool + 'static>;
 pub(crate) type KeystrokeObserver =
     Box<dyn FnMut(&KeystrokeEvent, &mut Window, &mut App) -> bool + 'static>;
+pub(crate) type ActionObserver =
+    Box<dyn FnMut(&ActionEvent, &mut Window, &mut App) -> bool + 'static>;
 type QuitHandler = Box<dyn FnOnce(&mut App) -> LocalBoxFuture<'static, ()> + 'static>;
 type WindowClosedHandler = Box<dyn FnMut(&mut App)>;
 type ReleaseListener = Box<dyn FnOnce(&mut dyn Any, &mut App) + 'static>;
@@ -611,6 +613,10 @@ pub struct App {
     pub(crate) event_listeners: SubscriberSet<EntityId, (TypeId, Listener)>,
     pub(crate) keystroke_observers: SubscriberSet<(), KeystrokeObserver>,
     pub(crate) keystroke_interceptors: SubscriberSet<(), KeystrokeObserver>,
+    pub(crate) action_observers: SubscriberSet<(), ActionObserver>,
+    /// How many actions are being dispatched in windows, counting those dispatched by the
+    /// handlers of other actions.
+    pub(crate) action_dispatch_depth: usize,
     pub(crate) keyboard_layout_observers: SubscriberSet<(), Handler>,
     pub(crate) thermal_state_observers: SubscriberSet<(), Handler>,
     pub(crate) release_listeners: SubscriberSet<EntityId, ReleaseListener>,
@@ -698,6 +704,8 @@ impl App {
                 release_listeners: SubscriberSet::new(),
                 keystroke_observers: SubscriberSet::new(),
                 keystroke_interceptors: SubscriberSet::new(),
+                action_observers: SubscriberSet::new(),
+                action_dispatch_depth: 0,
                 keyboard_layout_observers: SubscriberSet::new(),
                 thermal_state_observers: SubscriberSet::new(),
                 global_observers: SubscriberSet::new(),
@@ -1866,6 +1874,25 @@ impl App {
         )
     }
 
+    /// Register a callback to be invoked when an action is dispatched in any window, whether it
+    /// was triggered by a keystroke, a menu, the command palette or a mouse click. It fires before
+    /// the action's handlers run, and isn't invoked for actions dispatched by the handlers of
+    /// another action.
+    pub fn observe_actions(
+        &mut self,
+        mut f: impl FnMut(&ActionEvent, &mut Window, &mut App) + 'static,
+    ) -> Subscription {
+        let (subscription, activate) = self.action_observers.insert(
+            (),
+            Box::new(move |event, window, cx| {
+                f(event, window, cx);
+                true
+            }),
+        );
+        activate();
+        subscription
+    }
+
     /// Register key bindings.
     pub fn bind_keys(&mut self, bindings: impl IntoIterator<Item = KeyBinding>) {
         self.keymap.borrow_mut().add_bindings(bindings);
@@ -2546,6 +2573,16 @@ pub struct KeystrokeEvent {
     pub context_stack: Vec<KeyContext>,
 }
 
+/// An action dispatched in a window
+#[derive(Debug)]
+pub struct ActionEvent<'a> {
+    /// The action that was dispatched
+    pub action: &'a dyn Action,
+
+    /// The context stack of the element the action was dispatched to
+    pub context_stack: Vec<KeyContext>,
+}
+
 struct NullHttpClient;
 
 impl HttpClient for NullHttpClient {
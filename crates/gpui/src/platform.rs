@@ -1671,8 +1671,8 @@ impl ClipboardItem {
     pub fn new_string_with_metadata(text: String, metadata: String) -> Self {
         Self {
             entries: vec![ClipboardEntry::String(ClipboardString {
-                text,
                 metadata: Some(metadata),
+                ..ClipboardString::new(text)
             })],
         }
     }
@@ -1699,7 +1699,7 @@ impl ClipboardItem {
         let mut answer = String::new();
 
         for entry in self.entries.iter() {
-            if let ClipboardEntry::String(ClipboardString { text, metadata: _ }) = entry {
+            if let ClipboardEntry::String(ClipboardString { text, .. }) = entry {
                 answer.push_str(text);
             }
         }
@@ -1967,6 +1967,12 @@ pub struct ClipboardString {
     pub text: String,
     /// Optional metadata associated with this clipboard string.
     pub metadata: Option<String>,
+    /// An optional HTML rendering of the text, offered to other applications
+    /// alongside the plain text where the platform clipboard supports it.
+    pub html: Option<String>,
+    /// An optional RTF rendering of the text, offered to other applications
+    /// alongside the plain text where the platform clipboard supports it.
+    pub rtf: Option<String>,
 }
 
 impl ClipboardString {
@@ -1975,9 +1981,23 @@ impl ClipboardString {
         Self {
             text,
             metadata: None,
+            html: None,
+            rtf: None,
         }
     }
 
+    /// Return a new clipboard string that also carries the given HTML rendering of its text.
+    pub fn with_html(mut self, html: String) -> Self {
+        self.html = Some(html);
+        self
+    }
+
+    /// Return a new clipboard string that also carries the given RTF rendering of its text.
+    pub fn with_rtf(mut self, rtf: String) -> Self {
+        self.rtf = Some(rtf);
+        self
+    }
+
     /// Return a new clipboard item with the metadata replaced by the given metadata,
     /// after serializing it as JSON.
     pub fn with_json_metadata<T: Serialize>(mut self, metadata: T) -> Self {
@@ -2016,9 +2036,6 @@ impl ClipboardString {
 
 impl From<String> for ClipboardString {
     fn from(value: String) -> Self {
-        Self {
-            text: value,
-            metadata: None,
-        }
+        Self::new(value)
     }
 }
@@ -1,9 +1,9 @@
 #[cfg(any(feature = "inspector", debug_assertions))]
 use crate::Inspector;
 use crate::{
-    Action, AnyDrag, AnyElement, AnyImageCache, AnyTooltip, AnyView, App, AppContext, Arena, Asset,
-    AsyncWindowContext, AvailableSpace, Background, BorderStyle, Bounds, BoxShadow, Capslock,
-    Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
+    Action, ActionEvent, AnyDrag, AnyElement, AnyImageCache, AnyTooltip, AnyView, App, AppContext,
+    Arena, Asset, AsyncWindowContext, AvailableSpace, Background, BorderStyle, Bounds, BoxShadow,
+    Capslock, Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
     DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
     FileDropEvent, FontId, Global, GlobalElementId, GlyphId, GpuSpecs, Hsla, InputHandler, IsZero,
     KeyBinding, KeyContext, KeyDownEvent, KeyEvent, Keystroke, KeystrokeEvent, LayoutId,
@@ -4508,6 +4508,40 @@ impl Window {
         node_id: DispatchNodeId,
         action: &dyn Action,
         cx: &mut App,
+    ) {
+        if cx.action_dispatch_depth == 0 {
+            self.dispatch_action_observers(node_id, action, cx);
+        }
+        cx.action_dispatch_depth += 1;
+        self.dispatch_action_on_path(node_id, action, cx);
+        cx.action_dispatch_depth -= 1;
+    }
+
+    fn dispatch_action_observers(
+        &mut self,
+        node_id: DispatchNodeId,
+        action: &dyn Action,
+        cx: &mut App,
+    ) {
+        let dispatch_tree = &self.rendered_frame.dispatch_tree;
+        let event = ActionEvent {
+            action,
+            context_stack: dispatch_tree
+                .dispatch_path(node_id)
+                .iter()
+                .filter_map(|&node_id| dispatch_tree.node(node_id).context.clone())
+                .collect(),
+        };
+        cx.action_observers
+            .clone()
+            .retain(&(), move |callback| (callback)(&event, self, cx));
+    }
+
+    fn dispatch_action_on_path(
+        &mut self,
+        node_id: DispatchNodeId,
+        action: &dyn Action,
+        cx: &mut App,
     ) {
         let dispatch_path = self.rendered_frame.dispatch_tree.dispatch_path(node_id);
 
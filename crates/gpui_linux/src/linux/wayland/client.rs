@@ -82,7 +82,9 @@ use crate::linux::{
     keystroke_from_xkb, keystroke_underlying_dead_key, modifiers_from_xkb, open_uri_internal,
     read_fd, reveal_path_internal,
     wayland::{
-        clipboard::{Clipboard, DataOffer, FILE_LIST_MIME_TYPE, TEXT_MIME_TYPES},
+        clipboard::{
+            Clipboard, DataOffer, FILE_LIST_MIME_TYPE, TEXT_MIME_TYPES, rich_text_mime_types,
+        },
         cursor::Cursor,
         serial::{SerialKind, SerialTracker},
         to_shape,
@@ -866,12 +868,15 @@ impl LinuxClient for WaylandClient {
             return;
         };
         if state.mouse_focused_window.is_some() || state.keyboard_focused_window.is_some() {
-            state.clipboard.set_primary(item);
             let serial = state.serial_tracker.get(SerialKind::KeyPress);
             let data_source = primary_selection_manager.create_source(&state.globals.qh, ());
-            for mime_type in TEXT_MIME_TYPES {
+            for mime_type in TEXT_MIME_TYPES
+                .into_iter()
+                .chain(rich_text_mime_types(&item))
+            {
                 data_source.offer(mime_type.to_string());
             }
+            state.clipboard.set_primary(item);
             data_source.offer(state.clipboard.self_mime());
             primary_selection.set_selection(Some(&data_source), serial);
         }
@@ -886,12 +891,15 @@ impl LinuxClient for WaylandClient {
             return;
         };
         if state.mouse_focused_window.is_some() || state.keyboard_focused_window.is_some() {
-            state.clipboard.set(item);
             let serial = state.serial_tracker.get(SerialKind::KeyPress);
             let data_source = data_device_manager.create_data_source(&state.globals.qh, ());
-            for mime_type in TEXT_MIME_TYPES {
+            for mime_type in TEXT_MIME_TYPES
+                .into_iter()
+                .chain(rich_text_mime_types(&item))
+            {
                 data_source.offer(mime_type.to_string());
             }
+            state.clipboard.set(item);
             data_source.offer(state.clipboard.self_mime());
             data_device.set_selection(Some(&data_source), serial);
         }
@@ -17,6 +17,8 @@ use gpui::{ClipboardEntry, ClipboardItem, Image, ImageFormat, hash};
 pub(crate) const TEXT_MIME_TYPES: [&str; 3] =
     ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
 pub(crate) const FILE_LIST_MIME_TYPE: &str = "text/uri-list";
+pub(crate) const HTML_MIME_TYPE: &str = "text/html";
+pub(crate) const RTF_MIME_TYPE: &str = "text/rtf";
 
 /// Text mime types that we'll accept from other programs.
 pub(crate) const ALLOWED_TEXT_MIME_TYPES: [&str; 2] = ["text/plain;charset=utf-8", "UTF8_STRING"];
@@ -177,19 +179,23 @@ impl Clipboard {
         self.self_mime.clone()
     }
 
-    pub fn send(&self, _mime_type: String, fd: OwnedFd) {
-        if let Some(text) = self.contents.as_ref().and_then(|contents| contents.text()) {
-            self.send_internal(fd, text.as_bytes().to_owned());
+    pub fn send(&self, mime_type: String, fd: OwnedFd) {
+        if let Some(bytes) = self
+            .contents
+            .as_ref()
+            .and_then(|contents| bytes_for_mime_type(contents, &mime_type))
+        {
+            self.send_internal(fd, bytes);
         }
     }
 
-    pub fn send_primary(&self, _mime_type: String, fd: OwnedFd) {
-        if let Some(text) = self
+    pub fn send_primary(&self, mime_type: String, fd: OwnedFd) {
+        if let Some(bytes) = self
             .primary_contents
             .as_ref()
-            .and_then(|contents| contents.text())
+            .and_then(|contents| bytes_for_mime_type(contents, &mime_type))
         {
-            self.send_internal(fd, text.as_bytes().to_owned());
+            self.send_internal(fd, bytes);
         }
     }
 
@@ -258,3 +264,34 @@ impl Clipboard {
             .unwrap();
     }
 }
+
+/// The mime types that the rich text renderings of the item can be offered
+/// under, in addition to the text ones.
+pub(crate) fn rich_text_mime_types(item: &ClipboardItem) -> impl Iterator<Item = &'static str> {
+    let string = match item.entries() {
+        [ClipboardEntry::String(string)] => Some(string),
+        _ => None,
+    };
+    [
+        string
+            .and_then(|string| string.html.as_ref())
+            .map(|_| HTML_MIME_TYPE),
+        string
+            .and_then(|string| string.rtf.as_ref())
+            .map(|_| RTF_MIME_TYPE),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+fn bytes_for_mime_type(item: &ClipboardItem, mime_type: &str) -> Option<Vec<u8>> {
+    let rich_text = match (item.entries(), mime_type) {
+        ([ClipboardEntry::String(string)], HTML_MIME_TYPE) => string.html.as_ref(),
+        ([ClipboardEntry::String(string)], RTF_MIME_TYPE) => string.rtf.as_ref(),
+        _ => None,
+    };
+    match rich_text {
+        Some(rich_text) => Some(rich_text.as_bytes().to_owned()),
+        None => item.text().map(String::into_bytes),
+    }
+}
@@ -1637,10 +1637,16 @@ impl LinuxClient for X11Client {
 
     fn write_to_clipboard(&self, item: gpui::ClipboardItem) {
         let mut state = self.0.borrow_mut();
+        let (html, rtf) = match item.entries() {
+            [gpui::ClipboardEntry::String(string)] => (string.html.clone(), string.rtf.clone()),
+            _ => (None, None),
+        };
         state
             .clipboard
-            .set_text(
+            .set_rich_text(
                 std::borrow::Cow::Owned(item.text().unwrap_or_default()),
+                html,
+                rtf,
                 clipboard::ClipboardKind::Clipboard,
                 clipboard::WaitConfig::None,
             )
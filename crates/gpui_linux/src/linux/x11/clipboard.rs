@@ -76,7 +76,8 @@ x11rb::atom_manager! {
         TEXT,
         TEXT_MIME_UNKNOWN: b"text/plain",
 
-        // HTML: b"text/html",
+        HTML: b"text/html",
+        RTF: b"text/rtf",
         // URI_LIST: b"text/uri-list",
 
         PNG__MIME: ImageFormat::mime_type(ImageFormat::Png ).as_bytes(),
@@ -989,6 +990,30 @@ impl Clipboard {
         self.inner.write(data, selection, wait)
     }
 
+    /// Like `set_text`, but also offers HTML and RTF renderings of the text.
+    pub(crate) fn set_rich_text(
+        &self,
+        message: Cow<'_, str>,
+        html: Option<String>,
+        rtf: Option<String>,
+        selection: ClipboardKind,
+        wait: WaitConfig,
+    ) -> Result<()> {
+        let mut data = vec![ClipboardData {
+            bytes: message.into_owned().into_bytes(),
+            format: self.inner.atoms.UTF8_STRING,
+        }];
+        for (rich_text, format) in [(html, self.inner.atoms.HTML), (rtf, self.inner.atoms.RTF)] {
+            if let Some(rich_text) = rich_text {
+                data.push(ClipboardData {
+                    bytes: rich_text.into_bytes(),
+                    format,
+                });
+            }
+        }
+        self.inner.write(data, selection, wait)
+    }
+
     #[allow(unused)]
     pub(crate) fn set_image(
         &self,
@@ -16,6 +16,8 @@ pub struct Pasteboard {
     inner: id,
     text_hash_type: id,
     metadata_type: id,
+    html_type: id,
+    rtf_type: id,
 }
 
 impl Pasteboard {
@@ -37,6 +39,9 @@ impl Pasteboard {
             inner,
             text_hash_type: unsafe { ns_string("zed-text-hash") },
             metadata_type: unsafe { ns_string("zed-metadata") },
+            // The values of `NSPasteboardTypeHTML` and `NSPasteboardTypeRTF`.
+            html_type: unsafe { ns_string("public.html") },
+            rtf_type: unsafe { ns_string("public.rtf") },
         }
     }
 
@@ -112,7 +117,10 @@ impl Pasteboard {
                 });
 
             ClipboardItem {
-                entries: vec![ClipboardEntry::String(ClipboardString { text, metadata })],
+                entries: vec![ClipboardEntry::String(ClipboardString {
+                    metadata,
+                    ..ClipboardString::new(text)
+                })],
             }
         }
     }
@@ -153,10 +161,7 @@ impl Pasteboard {
                     //
                     // Note how `any_images` is always `false`. We should fix that, but that's orthogonal to the refactor.
 
-                    let mut combined = ClipboardString {
-                        text: String::new(),
-                        metadata: None,
-                    };
+                    let mut combined = ClipboardString::new(String::new());
 
                     for entry in item.entries {
                         match entry {
@@ -205,6 +210,20 @@ impl Pasteboard {
                 self.inner
                     .setData_forType(metadata_bytes, self.metadata_type);
             }
+
+            for (rich_text, kind) in [
+                (string.html.as_ref(), self.html_type),
+                (string.rtf.as_ref(), self.rtf_type),
+            ] {
+                if let Some(rich_text) = rich_text {
+                    let bytes = NSData::dataWithBytes_length_(
+                        nil,
+                        rich_text.as_ptr() as *const c_void,
+                        rich_text.len() as u64,
+                    );
+                    self.inner.setData_forType(bytes, kind);
+                }
+            }
         }
     }
 
@@ -339,4 +358,30 @@ mod tests {
             Some(ClipboardItem::new_string(text_from_other_app.to_string()))
         );
     }
+
+    #[test]
+    fn test_rich_text() {
+        let pasteboard = Pasteboard::unique();
+        let string = ClipboardString::new("fn main() {}".to_string())
+            .with_html("<pre>fn main() {}</pre>".to_string())
+            .with_rtf("{\\rtf1 fn main() \\{\\}}".to_string());
+        pasteboard.write(ClipboardItem {
+            entries: vec![ClipboardEntry::String(string)],
+        });
+
+        unsafe {
+            assert_eq!(
+                pasteboard.data_for_type(pasteboard.html_type),
+                Some("<pre>fn main() {}</pre>".as_bytes())
+            );
+            assert_eq!(
+                pasteboard.data_for_type(pasteboard.rtf_type),
+                Some("{\\rtf1 fn main() \\{\\}}".as_bytes())
+            );
+        }
+        assert_eq!(
+            pasteboard.read(),
+            Some(ClipboardItem::new_string("fn main() {}".to_string()))
+        );
+    }
 }
@@ -38,6 +38,10 @@ static CLIPBOARD_PNG_FORMAT: LazyLock<u32> =
     LazyLock::new(|| register_clipboard_format(windows::core::w!("PNG")));
 static CLIPBOARD_JPG_FORMAT: LazyLock<u32> =
     LazyLock::new(|| register_clipboard_format(windows::core::w!("JFIF")));
+static CLIPBOARD_HTML_FORMAT: LazyLock<u32> =
+    LazyLock::new(|| register_clipboard_format(windows::core::w!("HTML Format")));
+static CLIPBOARD_RTF_FORMAT: LazyLock<u32> =
+    LazyLock::new(|| register_clipboard_format(windows::core::w!("Rich Text Format")));
 
 // Helper maps and sets
 static FORMATS_MAP: LazyLock<FxHashMap<u32, ClipboardFormatType>> = LazyLock::new(|| {
@@ -182,9 +186,41 @@ fn write_string_to_clipboard(item: &ClipboardString) -> Result<()> {
         let metadata_wide = metadata.encode_utf16().chain(Some(0)).collect_vec();
         set_data_to_clipboard(&metadata_wide, *CLIPBOARD_METADATA_FORMAT)?;
     }
+    if let Some(html) = item.html.as_ref() {
+        let bytes = encode_cf_html(html).into_bytes();
+        set_data_to_clipboard(&[bytes.as_slice(), &[0]].concat(), *CLIPBOARD_HTML_FORMAT)?;
+    }
+    if let Some(rtf) = item.rtf.as_ref() {
+        set_data_to_clipboard(&[rtf.as_bytes(), &[0]].concat(), *CLIPBOARD_RTF_FORMAT)?;
+    }
     Ok(())
 }
 
+// https://learn.microsoft.com/en-us/windows/win32/dataxchg/html-clipboard-format
+fn encode_cf_html(html: &str) -> String {
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    // The offsets are zero-padded to a fixed width, so the header's length
+    // doesn't depend on them.
+    let header = |start_html: usize,
+                  end_html: usize,
+                  start_fragment: usize,
+                  end_fragment: usize| {
+        format!(
+            "Version:0.9\r\nStartHTML:{start_html:010}\r\nEndHTML:{end_html:010}\r\nStartFragment:{start_fragment:010}\r\nEndFragment:{end_fragment:010}\r\n"
+        )
+    };
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + html.len();
+    let end_html = end_fragment + SUFFIX.len();
+    format!(
+        "{}{PREFIX}{html}{SUFFIX}",
+        header(start_html, end_html, start_fragment, end_fragment)
+    )
+}
+
 fn set_data_to_clipboard<T>(data: &[T], format: u32) -> Result<()> {
     unsafe {
         let global = GlobalAlloc(GMEM_MOVEABLE, std::mem::size_of_val(data))?;
@@ -307,8 +343,8 @@ fn read_string_from_clipboard() -> Option<ClipboardEntry> {
     };
     if hash == ClipboardString::text_hash(&text) {
         Some(ClipboardEntry::String(ClipboardString {
-            text,
             metadata: Some(metadata),
+            ..ClipboardString::new(text)
         }))
     } else {
         Some(ClipboardEntry::String(ClipboardString::new(text)))
@@ -0,0 +1,208 @@
+//! Search for byte patterns, streaming through the file instead of loading it whole.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, Read as _, Seek as _, SeekFrom},
+    path::Path,
+};
+
+use anyhow::{Result, bail};
+
+use crate::paged_bytes::apply_edits;
+
+/// Size of a chunk of the file searched at once
+const SEARCH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Sequence of bytes to look for, where `None` matches any byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytePattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl BytePattern {
+    /// Parse a pattern typed by the user.
+    ///
+    /// A quoted pattern (`"ELF"`) matches its UTF-8 bytes. Otherwise the pattern is hex bytes,
+    /// either separated by whitespace (`7f 45 4c 46`) or not (`7f454c46`), with `??` matching any byte.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if let Some(quoted) = text
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'))
+        {
+            if quoted.is_empty() {
+                bail!("Empty pattern");
+            }
+            return Ok(Self {
+                bytes: quoted.bytes().map(Some).collect(),
+            });
+        }
+
+        let digits = text
+            .strip_prefix("0x")
+            .unwrap_or(text)
+            .chars()
+            .filter(|ch| !ch.is_whitespace() && *ch != ',')
+            .collect::<Vec<_>>();
+        if digits.is_empty() {
+            bail!("Empty pattern");
+        }
+        if digits.len() % 2 != 0 {
+            bail!("Hex pattern must have two digits per byte");
+        }
+
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| match pair {
+                ['?', '?'] => Ok(None),
+                [high, low] => match (high.to_digit(16), low.to_digit(16)) {
+                    (Some(high), Some(low)) => Ok(Some((high * 16 + low) as u8)),
+                    _ => bail!("Invalid hex byte \"{high}{low}\""),
+                },
+                _ => unreachable!("chunks of two"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if bytes.iter().all(Option::is_none) {
+            bail!("Pattern must contain at least one concrete byte");
+        }
+        Ok(Self { bytes })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn matches_at(&self, haystack: &[u8]) -> bool {
+        haystack.len() >= self.bytes.len()
+            && self
+                .bytes
+                .iter()
+                .zip(haystack)
+                .all(|(expected, actual)| expected.is_none_or(|expected| expected == *actual))
+    }
+
+    /// Offset of the first match within `haystack`
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        if haystack.len() < self.bytes.len() {
+            return None;
+        }
+        (0..=haystack.len() - self.bytes.len()).find(|&ix| self.matches_at(&haystack[ix..]))
+    }
+}
+
+/// Find the next match starting at `start`, wrapping around to the beginning of the file.
+/// Unsaved `edits` are searched as if they were written to disk.
+pub fn find_next(
+    path: &Path,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &BytePattern,
+    start: u64,
+) -> io::Result<Option<u64>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = start.min(len);
+    if let Some(found) = find_in_range(&mut file, edits, pattern, start, len)? {
+        return Ok(Some(found));
+    }
+    let wrapped_end = (start + pattern.len() as u64).saturating_sub(1).min(len);
+    find_in_range(&mut file, edits, pattern, 0, wrapped_end)
+}
+
+/// Find the first match which starts and ends within `start..end`
+fn find_in_range(
+    file: &mut File,
+    edits: &BTreeMap<u64, u8>,
+    pattern: &BytePattern,
+    start: u64,
+    end: u64,
+) -> io::Result<Option<u64>> {
+    let overlap = pattern.len().saturating_sub(1);
+    let mut chunk_start = start;
+    let mut chunk = Vec::with_capacity(SEARCH_CHUNK_SIZE + overlap);
+    while chunk_start + (pattern.len() as u64) <= end {
+        let chunk_len = (SEARCH_CHUNK_SIZE + overlap).min((end - chunk_start) as usize);
+        chunk.clear();
+        file.seek(SeekFrom::Start(chunk_start))?;
+        (&mut *file)
+            .take(chunk_len as u64)
+            .read_to_end(&mut chunk)?;
+        if chunk.len() < pattern.len() {
+            break;
+        }
+        apply_edits(edits, chunk_start, &mut chunk);
+        if let Some(ix) = pattern.find(&chunk) {
+            return Ok(Some(chunk_start + ix as u64));
+        }
+        // Consecutive chunks overlap so that matches spanning their boundary are found
+        chunk_start += (chunk.len() - overlap) as u64;
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(
+            BytePattern::parse("7f 45 4C 46").unwrap().bytes,
+            vec![Some(0x7f), Some(0x45), Some(0x4c), Some(0x46)]
+        );
+        assert_eq!(
+            BytePattern::parse("0xdead??ef").unwrap().bytes,
+            vec![Some(0xde), Some(0xad), None, Some(0xef)]
+        );
+        assert_eq!(
+            BytePattern::parse("\"PK\"").unwrap().bytes,
+            vec![Some(b'P'), Some(b'K')]
+        );
+        assert!(BytePattern::parse("").is_err());
+        assert!(BytePattern::parse("abc").is_err());
+        assert!(BytePattern::parse("zz").is_err());
+        assert!(BytePattern::parse("?? ??").is_err());
+    }
+
+    #[test]
+    fn test_find_with_wildcards() {
+        let pattern = BytePattern::parse("01 ?? 03").unwrap();
+        assert_eq!(pattern.find(&[0, 1, 2, 4, 1, 9, 3]), Some(4));
+        assert_eq!(pattern.find(&[1, 2]), None);
+    }
+
+    #[test]
+    fn test_find_next_across_chunks_and_wrapping() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump.bin");
+        let mut content = vec![0u8; SEARCH_CHUNK_SIZE * 2];
+        // Spans the boundary between the first two chunks
+        let boundary_match = SEARCH_CHUNK_SIZE - 2;
+        content[boundary_match..boundary_match + 4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        content[10..14].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        std::fs::write(&path, &content).unwrap();
+
+        let pattern = BytePattern::parse("deadbeef").unwrap();
+        let no_edits = BTreeMap::new();
+        assert_eq!(find_next(&path, &no_edits, &pattern, 0).unwrap(), Some(10));
+        assert_eq!(
+            find_next(&path, &no_edits, &pattern, 11).unwrap(),
+            Some(boundary_match as u64)
+        );
+        assert_eq!(
+            find_next(&path, &no_edits, &pattern, boundary_match as u64 + 1).unwrap(),
+            Some(10)
+        );
+
+        // Unsaved edits are searched too
+        let edits = BTreeMap::from([(100, 0xde), (101, 0xad), (102, 0xbe), (103, 0xef)]);
+        assert_eq!(find_next(&path, &edits, &pattern, 11).unwrap(), Some(100));
+
+        let missing = BytePattern::parse("\"missing\"").unwrap();
+        assert_eq!(find_next(&path, &no_edits, &missing, 0).unwrap(), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Decoding of the bytes under the cursor as common numeric types.
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn label(self) -> &'static str {
+        match self {
+            Endianness::Little => "Little Endian",
+            Endianness::Big => "Big Endian",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+}
+
+/// Number of bytes needed to decode every type shown by the inspector
+pub const INSPECTED_BYTES: usize = 8;
+
+/// A single decoded value shown by the data inspector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedValue {
+    pub label: &'static str,
+    pub value: String,
+}
+
+macro_rules! decode {
+    ($ty:ty, $bytes:expr, $endianness:expr) => {{
+        const SIZE: usize = std::mem::size_of::<$ty>();
+        $bytes.get(..SIZE).map(|bytes| {
+            let bytes: [u8; SIZE] = bytes.try_into().expect("slice has the right length");
+            match $endianness {
+                Endianness::Little => <$ty>::from_le_bytes(bytes),
+                Endianness::Big => <$ty>::from_be_bytes(bytes),
+            }
+        })
+    }};
+}
+
+/// Decode `bytes` (starting at the cursor) as integers, floats and text.
+/// Types which need more bytes than available are skipped.
+pub fn inspect(bytes: &[u8], endianness: Endianness) -> Vec<InspectedValue> {
+    let mut values = Vec::new();
+    let mut push = |label, value: Option<String>| {
+        if let Some(value) = value {
+            values.push(InspectedValue { label, value });
+        }
+    };
+
+    push("Binary", bytes.first().map(|byte| format!("{byte:08b}")));
+    push("u8", decode!(u8, bytes, endianness).map(|v| v.to_string()));
+    push("i8", decode!(i8, bytes, endianness).map(|v| v.to_string()));
+    push(
+        "u16",
+        decode!(u16, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "i16",
+        decode!(i16, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "u32",
+        decode!(u32, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "i32",
+        decode!(i32, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "u64",
+        decode!(u64, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "i64",
+        decode!(i64, bytes, endianness).map(|v| v.to_string()),
+    );
+    push(
+        "f32",
+        decode!(f32, bytes, endianness).map(|v| format!("{v:e}")),
+    );
+    push(
+        "f64",
+        decode!(f64, bytes, endianness).map(|v| format!("{v:e}")),
+    );
+    push("UTF-8", decode_utf8_char(bytes));
+    values
+}
+
+/// The character encoded at the start of `bytes`, if they start with valid UTF-8
+fn decode_utf8_char(bytes: &[u8]) -> Option<String> {
+    let first = *bytes.first()?;
+    let len = match first {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return None,
+    };
+    let ch = std::str::from_utf8(bytes.get(..len)?)
+        .ok()?
+        .chars()
+        .next()?;
+    Some(if ch.is_control() {
+        format!("U+{:04X}", ch as u32)
+    } else {
+        format!("{ch} (U+{:04X})", ch as u32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(values: &[InspectedValue], label: &str) -> Option<String> {
+        values
+            .iter()
+            .find(|value| value.label == label)
+            .map(|value| value.value.clone())
+    }
+
+    #[test]
+    fn test_inspect_endianness() {
+        let bytes = [0x01, 0x02, 0x03, 0x04];
+
+        let little = inspect(&bytes, Endianness::Little);
+        assert_eq!(value(&little, "Binary").as_deref(), Some("00000001"));
+        assert_eq!(value(&little, "u16").as_deref(), Some("513"));
+        assert_eq!(value(&little, "u32").as_deref(), Some("67305985"));
+        // Not enough bytes for 64 bit types
+        assert_eq!(value(&little, "u64"), None);
+        assert_eq!(value(&little, "f64"), None);
+
+        let big = inspect(&bytes, Endianness::Big);
+        assert_eq!(value(&big, "u16").as_deref(), Some("258"));
+        assert_eq!(value(&big, "u32").as_deref(), Some("16909060"));
+    }
+
+    #[test]
+    fn test_inspect_signed_and_float() {
+        let values = inspect(&1.5f32.to_be_bytes(), Endianness::Big);
+        assert_eq!(value(&values, "f32").as_deref(), Some("1.5e0"));
+
+        let values = inspect(&[0xff, 0xff], Endianness::Little);
+        assert_eq!(value(&values, "i8").as_deref(), Some("-1"));
+        assert_eq!(value(&values, "i16").as_deref(), Some("-1"));
+        assert_eq!(value(&values, "u16").as_deref(), Some("65535"));
+    }
+
+    #[test]
+    fn test_inspect_utf8() {
+        let values = inspect("é!".as_bytes(), Endianness::Little);
+        assert_eq!(value(&values, "UTF-8").as_deref(), Some("é (U+00E9)"));
+
+        let values = inspect(&[0x0a], Endianness::Little);
+        assert_eq!(value(&values, "UTF-8").as_deref(), Some("U+000A"));
+
+        let values = inspect(&[0xc3], Endianness::Little);
+        assert_eq!(value(&values, "UTF-8"), None);
+    }
+}
@@ -0,0 +1,1078 @@
+mod byte_search;
+mod data_inspector;
+mod paged_bytes;
+
+use std::{
+    collections::HashSet,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use editor::{Editor, actions::SelectAll};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent,
+    MouseButton, MouseDownEvent, ScrollStrategy, Task, UniformListScrollHandle, Window, actions,
+    uniform_list,
+};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings as _;
+use ui::{Tooltip, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ItemEvent, ProjectItem, SaveOptions},
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+pub use crate::{
+    byte_search::BytePattern,
+    data_inspector::{Endianness, InspectedValue, inspect},
+    paged_bytes::PagedBytes,
+};
+use crate::{
+    byte_search::find_next,
+    data_inspector::INSPECTED_BYTES,
+    paged_bytes::{read_page, write_edits},
+};
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor one byte to the left.
+        MoveLeft,
+        /// Moves the cursor one byte to the right.
+        MoveRight,
+        /// Moves the cursor one row up.
+        MoveUp,
+        /// Moves the cursor one row down.
+        MoveDown,
+        /// Extends the selection one byte to the left.
+        SelectLeft,
+        /// Extends the selection one byte to the right.
+        SelectRight,
+        /// Extends the selection one row up.
+        SelectUp,
+        /// Extends the selection one row down.
+        SelectDown,
+        /// Moves the cursor one page up.
+        MovePageUp,
+        /// Moves the cursor one page down.
+        MovePageDown,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches typing between the hex and the ASCII column.
+        ToggleColumn,
+        /// Opens the input for jumping to an offset.
+        GoToOffset,
+        /// Opens the input for searching a byte pattern.
+        FindBytes,
+        /// Selects the next match of the last searched byte pattern.
+        FindNext,
+        /// Switches the data inspector between little and big endian.
+        ToggleEndianness,
+    ]
+);
+
+/// Extensions of files opened in the hex editor instead of a text editor.
+/// Other files can be opened with [`OpenInHexEditor`]. Extensions that are
+/// also commonly used for text files, like `.obj` or `.dat`, are left out.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "bin", "img", "rom", "fw", "elf", "exe", "dll", "so", "dylib", "o", "wasm", "class", "pyc",
+    "dex", "pcap",
+];
+
+const BYTES_PER_ROW: u64 = 16;
+const ROWS_PER_PAGE: u64 = 32;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_hex_editor);
+    })
+    .detach();
+}
+
+/// Open the active file in the hex editor, replacing the "could not open file" view of binary files
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let invalid_item = active_item.downcast::<InvalidItemView>();
+    let project_path = match &invalid_item {
+        Some(invalid_item) => project.find_project_path(&invalid_item.read(cx).abs_path, cx),
+        None => active_item.project_path(cx),
+    };
+    let Some(project_path) = project_path else {
+        return;
+    };
+    let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+        return;
+    };
+    let entry_id = project
+        .entry_for_path(&project_path, cx)
+        .map(|entry| entry.id);
+
+    let open_file = HexFile::open(abs_path, project_path, entry_id, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let file = open_file.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let editor = cx.new(|cx| HexEditor::new(file, window, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            if let Some(invalid_item) = invalid_item {
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.remove_item(invalid_item.entity_id(), false, false, window, cx)
+                });
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn is_binary_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            BINARY_EXTENSIONS
+                .iter()
+                .any(|binary| extension.eq_ignore_ascii_case(binary))
+        })
+}
+
+pub enum HexFileEvent {
+    DirtyChanged,
+}
+
+/// A file opened as raw bytes. Content is read from disk on demand, page by page.
+pub struct HexFile {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    bytes: PagedBytes,
+    /// Pages being read from disk, or which failed to be read
+    requested_pages: HashSet<u64>,
+}
+
+impl EventEmitter<HexFileEvent> for HexFile {}
+
+impl HexFile {
+    fn open(
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        entry_id: Option<ProjectEntryId>,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let len = cx
+                .background_spawn({
+                    let abs_path = abs_path.clone();
+                    async move { std::fs::metadata(&abs_path).map(|metadata| metadata.len()) }
+                })
+                .await
+                .with_context(|| format!("reading metadata of {abs_path:?}"))?;
+            Ok(cx.new(|_| HexFile {
+                abs_path,
+                project_path,
+                entry_id,
+                bytes: PagedBytes::new(len),
+                requested_pages: HashSet::default(),
+            }))
+        })
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn bytes(&self) -> &PagedBytes {
+        &self.bytes
+    }
+
+    /// Make sure the pages covering `range` are read from disk
+    pub fn load_range(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        for page in self.bytes.missing_pages(range) {
+            if !self.requested_pages.insert(page) {
+                continue;
+            }
+            let abs_path = self.abs_path.clone();
+            cx.spawn(async move |this, cx| {
+                let content = cx
+                    .background_spawn(async move { read_page(&abs_path, page) })
+                    .await;
+                this.update(cx, |this, cx| {
+                    match content {
+                        Ok(content) => {
+                            this.requested_pages.remove(&page);
+                            this.bytes.insert_page(page, content.into());
+                        }
+                        // Keep the page requested, so that it's not re-read on every frame
+                        Err(error) => log::error!("failed to read {:?}: {error}", this.abs_path),
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    pub fn set_byte(&mut self, offset: u64, value: u8, cx: &mut Context<Self>) {
+        let was_dirty = self.bytes.is_dirty();
+        self.bytes.set_byte(offset, value);
+        if was_dirty != self.bytes.is_dirty() {
+            cx.emit(HexFileEvent::DirtyChanged);
+        }
+        cx.notify();
+    }
+
+    /// Write the edited bytes back into the file, in place
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let saved = self.bytes.edits().clone();
+        if saved.is_empty() {
+            return Task::ready(Ok(()));
+        }
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn({
+                let saved = saved.clone();
+                async move { write_edits(&abs_path, &saved) }
+            })
+            .await?;
+            this.update(cx, |this, cx| {
+                this.bytes.commit_edits(&saved);
+                cx.emit(HexFileEvent::DirtyChanged);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl project::ProjectItem for HexFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        if !is_binary_path(path.path.as_std_path()) {
+            return None;
+        }
+        let project = project.read(cx);
+        // Pages are read straight from disk, which is only possible for local projects
+        if !project.is_local() {
+            return None;
+        }
+        let abs_path = project.absolute_path(path, cx)?;
+        let entry_id = project.entry_for_path(path, cx).map(|entry| entry.id);
+        Some(HexFile::open(abs_path, path.clone(), entry_id, cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.bytes.is_dirty()
+    }
+}
+
+/// Column of the row which receives typed input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputKind {
+    GoToOffset,
+    Search,
+}
+
+struct InputBar {
+    kind: InputKind,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+pub enum HexEditorEvent {
+    TitleChanged,
+}
+
+/// Offset/hex/ASCII view of a file with overwrite editing
+pub struct HexEditor {
+    file: Entity<HexFile>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: u64,
+    /// Other end of the selection, if any bytes besides the cursor are selected
+    selection_anchor: Option<u64>,
+    /// Whether the next typed hex digit replaces the low half of the byte under the cursor
+    low_nibble: bool,
+    column: Column,
+    endianness: Endianness,
+    input_bar: Option<InputBar>,
+    last_search: Option<(String, BytePattern)>,
+    last_match: Option<u64>,
+    status: Option<SharedString>,
+    search_task: Task<()>,
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl HexEditor {
+    pub fn new(file: Entity<HexFile>, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        cx.observe(&file, |_, _, cx| cx.notify()).detach();
+        cx.subscribe(&file, |_, _, event, cx| match event {
+            HexFileEvent::DirtyChanged => cx.emit(HexEditorEvent::TitleChanged),
+        })
+        .detach();
+
+        Self {
+            file,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_anchor: None,
+            low_nibble: false,
+            column: Column::Hex,
+            endianness: Endianness::default(),
+            input_bar: None,
+            last_search: None,
+            last_match: None,
+            status: None,
+            search_task: Task::ready(()),
+        }
+    }
+
+    fn len(&self, cx: &App) -> u64 {
+        self.file.read(cx).bytes().len()
+    }
+
+    fn selection(&self) -> Range<u64> {
+        let anchor = self.selection_anchor.unwrap_or(self.cursor);
+        anchor.min(self.cursor)..anchor.max(self.cursor) + 1
+    }
+
+    fn set_cursor(&mut self, offset: u64, select: bool, cx: &mut Context<Self>) {
+        let len = self.len(cx);
+        if len == 0 {
+            return;
+        }
+        let offset = offset.min(len - 1);
+        if select {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = offset;
+        self.low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item((offset / BYTES_PER_ROW) as usize, ScrollStrategy::Nearest);
+        cx.notify();
+    }
+
+    fn move_by(&mut self, delta: i64, select: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        // Moving up from the first row or down from the last one stays in the current column
+        let offset = if delta < 0 && self.cursor < delta.unsigned_abs() {
+            self.cursor % BYTES_PER_ROW
+        } else {
+            offset
+        };
+        self.set_cursor(offset, select, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, true, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-((BYTES_PER_ROW * ROWS_PER_PAGE) as i64), false, cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by((BYTES_PER_ROW * ROWS_PER_PAGE) as i64, false, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(u64::MAX, false, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn toggle_endianness(&mut self, _: &ToggleEndianness, _: &mut Window, cx: &mut Context<Self>) {
+        self.endianness = self.endianness.toggle();
+        cx.notify();
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: u64,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.set_cursor(offset, event.modifiers.shift, cx);
+        self.focus_handle.focus(window, cx);
+    }
+
+    /// Overwrite bytes with typed hex digits or ASCII characters
+    fn key_down(&mut self, event: &KeyDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.focus_handle.is_focused(window) {
+            return;
+        }
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.alt || keystroke.modifiers.platform {
+            return;
+        }
+        let Some(ch) = keystroke
+            .key_char
+            .as_ref()
+            .and_then(|key_char| key_char.chars().next())
+        else {
+            return;
+        };
+        let Some(current) = self.file.read(cx).bytes().byte(self.cursor) else {
+            return;
+        };
+
+        let (value, advance) = match self.column {
+            Column::Hex => {
+                let Some(digit) = ch.to_digit(16) else {
+                    return;
+                };
+                let digit = digit as u8;
+                if self.low_nibble {
+                    ((current & 0xf0) | digit, true)
+                } else {
+                    ((digit << 4) | (current & 0x0f), false)
+                }
+            }
+            Column::Ascii if ch.is_ascii() && !ch.is_ascii_control() => (ch as u8, true),
+            Column::Ascii => return,
+        };
+
+        let offset = self.cursor;
+        self.file
+            .update(cx, |file, cx| file.set_byte(offset, value, cx));
+        if advance {
+            self.set_cursor(offset + 1, false, cx);
+        } else {
+            self.selection_anchor = None;
+            self.low_nibble = true;
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_input_bar(InputKind::GoToOffset, window, cx);
+    }
+
+    fn find_bytes(&mut self, _: &FindBytes, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_input_bar(InputKind::Search, window, cx);
+    }
+
+    fn show_input_bar(&mut self, kind: InputKind, window: &mut Window, cx: &mut Context<Self>) {
+        let (placeholder, text) = match kind {
+            InputKind::GoToOffset => ("Offset: 0x1f0, 496, +16 or -0x10", String::new()),
+            InputKind::Search => (
+                "Bytes: 7f 45 4c 46, 7f??4c46 or \"text\"",
+                self.last_search
+                    .as_ref()
+                    .map(|(query, _)| query.clone())
+                    .unwrap_or_default(),
+            ),
+        };
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(placeholder, window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window, cx);
+        self.input_bar = Some(InputBar {
+            kind,
+            editor,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    fn confirm_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(input_bar) = &mut self.input_bar else {
+            return;
+        };
+        let text = input_bar.editor.read(cx).text(cx);
+        match input_bar.kind {
+            InputKind::GoToOffset => {
+                match parse_offset(&text, self.cursor, self.file.read(cx).bytes().len()) {
+                    Ok(offset) => {
+                        self.input_bar = None;
+                        self.focus_handle.focus(window, cx);
+                        self.set_cursor(offset, false, cx);
+                    }
+                    Err(error) => input_bar.error = Some(error.to_string().into()),
+                }
+            }
+            InputKind::Search => match BytePattern::parse(&text) {
+                Ok(pattern) => {
+                    self.input_bar = None;
+                    self.focus_handle.focus(window, cx);
+                    self.last_search = Some((text, pattern));
+                    self.last_match = None;
+                    self.search_next(cx);
+                }
+                Err(error) => input_bar.error = Some(error.to_string().into()),
+            },
+        }
+        cx.notify();
+    }
+
+    fn dismiss_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.input_bar = None;
+        self.focus_handle.focus(window, cx);
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.last_search.is_none() {
+            self.show_input_bar(InputKind::Search, window, cx);
+            return;
+        }
+        self.search_next(cx);
+    }
+
+    /// Search the file for the last pattern, starting after the previous match
+    fn search_next(&mut self, cx: &mut Context<Self>) {
+        let Some((_, pattern)) = self.last_search.clone() else {
+            return;
+        };
+        let selection_start = self.selection().start;
+        let start = if self.last_match == Some(selection_start) {
+            selection_start + 1
+        } else {
+            self.cursor
+        };
+        let file = self.file.read(cx);
+        let abs_path = file.abs_path().to_path_buf();
+        let edits = file.bytes().edits().clone();
+
+        self.status = Some("Searching…".into());
+        cx.notify();
+        self.search_task = cx.spawn(async move |this, cx| {
+            let found = cx
+                .background_spawn({
+                    let pattern = pattern.clone();
+                    async move { find_next(&abs_path, &edits, &pattern, start) }
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                match found {
+                    Ok(Some(offset)) => {
+                        this.status = None;
+                        this.last_match = Some(offset);
+                        this.set_cursor(offset, false, cx);
+                        this.set_cursor(offset + pattern.len() as u64 - 1, true, cx);
+                    }
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn offset_width(&self, cx: &App) -> usize {
+        let len = self.len(cx);
+        let digits = (u64::BITS - len.leading_zeros()).div_ceil(4) as usize;
+        digits.max(8)
+    }
+
+    fn render_rows(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let byte_range = range.start as u64 * BYTES_PER_ROW..range.end as u64 * BYTES_PER_ROW;
+        self.file
+            .update(cx, |file, cx| file.load_range(byte_range, cx));
+
+        let offset_width = self.offset_width(cx);
+        let selection = self.selection();
+        let colors = cx.theme().colors();
+        let selected_background = colors.element_selected;
+        let cursor_border = colors.border_focused;
+        let bytes = self.file.read(cx).bytes();
+        let len = bytes.len();
+
+        range
+            .map(|row| {
+                let row_start = row as u64 * BYTES_PER_ROW;
+                let row_end = (row_start + BYTES_PER_ROW).min(len);
+                let mut hex_cells = Vec::with_capacity(BYTES_PER_ROW as usize);
+                let mut ascii_cells = Vec::with_capacity(BYTES_PER_ROW as usize);
+
+                for offset in row_start..row_start + BYTES_PER_ROW {
+                    if offset >= row_end {
+                        hex_cells.push(div().w(rems(1.5)).into_any_element());
+                        continue;
+                    }
+                    let byte = bytes.byte(offset);
+                    let color = if bytes.is_edited(offset) {
+                        Color::Modified
+                    } else if byte == Some(0) {
+                        Color::Muted
+                    } else {
+                        Color::Default
+                    };
+                    let is_selected = selection.contains(&offset);
+                    let is_cursor = offset == self.cursor;
+
+                    let hex_text = byte.map_or("··".to_string(), |byte| format!("{byte:02x}"));
+                    let ascii_text = byte.map_or('·', |byte| {
+                        if byte.is_ascii_graphic() || byte == b' ' {
+                            byte as char
+                        } else {
+                            '.'
+                        }
+                    });
+
+                    let cell = |id: &'static str, column: Column| {
+                        div()
+                            .id((id, offset))
+                            .border_1()
+                            .border_color(gpui::transparent_black())
+                            .when(is_selected, |cell| cell.bg(selected_background))
+                            .when(is_cursor && self.column == column, |cell| {
+                                cell.border_color(cursor_border)
+                            })
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, event, window, cx| {
+                                    this.click_byte(offset, column, event, window, cx)
+                                }),
+                            )
+                    };
+                    hex_cells.push(
+                        cell("hex-byte", Column::Hex)
+                            .w(rems(1.5))
+                            .when(offset % BYTES_PER_ROW == 8, |cell| cell.ml_2())
+                            .child(Label::new(hex_text).buffer_font(cx).color(color))
+                            .into_any_element(),
+                    );
+                    ascii_cells.push(
+                        cell("ascii-byte", Column::Ascii)
+                            .child(
+                                Label::new(ascii_text.to_string())
+                                    .buffer_font(cx)
+                                    .color(color),
+                            )
+                            .into_any_element(),
+                    );
+                }
+
+                h_flex()
+                    .id(("hex-row", row))
+                    .gap_4()
+                    .child(
+                        Label::new(format!("{row_start:0offset_width$x}"))
+                            .buffer_font(cx)
+                            .color(Color::Muted),
+                    )
+                    .child(h_flex().gap_0p5().children(hex_cells))
+                    .child(h_flex().children(ascii_cells))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_inspector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let start = self.selection().start;
+        let bytes = self.file.read(cx).bytes();
+        // Decode as many bytes as loaded, up to the widest type
+        let inspected = (start..start + INSPECTED_BYTES as u64)
+            .map_while(|offset| bytes.byte(offset))
+            .collect::<Vec<_>>();
+        let values = inspect(&inspected, self.endianness);
+
+        v_flex()
+            .w_64()
+            .h_full()
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Data Inspector").size(LabelSize::Small))
+                    .child(
+                        Button::new("hex-endianness", self.endianness.label())
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::for_action_title(
+                                "Toggle Endianness",
+                                &ToggleEndianness,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_endianness(&ToggleEndianness, window, cx)
+                            })),
+                    ),
+            )
+            .children(values.into_iter().map(|InspectedValue { label, value }| {
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                    .child(Label::new(value).size(LabelSize::Small).buffer_font(cx))
+            }))
+    }
+
+    fn render_input_bar(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let input_bar = self.input_bar.as_ref()?;
+        let label = match input_bar.kind {
+            InputKind::GoToOffset => "Go to Offset",
+            InputKind::Search => "Find Bytes",
+        };
+        Some(
+            h_flex()
+                .key_context("HexEditorInput")
+                .on_action(
+                    cx.listener(|this, _: &menu::Confirm, window, cx| {
+                        this.confirm_input(window, cx)
+                    }),
+                )
+                .on_action(
+                    cx.listener(|this, _: &menu::Cancel, window, cx| {
+                        this.dismiss_input(window, cx)
+                    }),
+                )
+                .p_1()
+                .gap_2()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(Label::new(label).size(LabelSize::Small))
+                .child(
+                    div()
+                        .flex_1()
+                        .px_1()
+                        .border_1()
+                        .rounded_sm()
+                        .border_color(if input_bar.error.is_some() {
+                            cx.theme().status().error_border
+                        } else {
+                            cx.theme().colors().border_variant
+                        })
+                        .child(input_bar.editor.clone()),
+                )
+                .when_some(input_bar.error.clone(), |this, error| {
+                    this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+                })
+                .into_any_element(),
+        )
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let len = self.len(cx);
+        let selection = self.selection();
+        let mut position = if len == 0 {
+            "Empty file".to_string()
+        } else {
+            format!("Offset 0x{:x} ({})", self.cursor, self.cursor)
+        };
+        if selection.end - selection.start > 1 {
+            position.push_str(&format!(
+                " · {} bytes selected",
+                selection.end - selection.start
+            ));
+        }
+        let mode = match self.column {
+            Column::Hex => "HEX",
+            Column::Ascii => "ASCII",
+        };
+
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(position).size(LabelSize::Small))
+            .child(
+                Label::new(format!("{len} bytes"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(mode).size(LabelSize::Small).color(Color::Muted))
+            .when_some(self.status.clone(), |this, status| {
+                this.child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+}
+
+/// Parse a typed offset: decimal (`496`), hex (`0x1f0` or `1f0h`), or relative to the cursor (`+16`, `-0x10`)
+fn parse_offset(text: &str, cursor: u64, len: u64) -> Result<u64> {
+    let text = text.trim();
+    let (relative, text) = match text.as_bytes().first() {
+        Some(b'+') => (Some(true), &text[1..]),
+        Some(b'-') => (Some(false), &text[1..]),
+        _ => (None, text),
+    };
+    let text = text.trim();
+    let value = if let Some(hex) = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .or_else(|| text.strip_suffix('h'))
+    {
+        u64::from_str_radix(hex, 16)
+    } else {
+        text.parse::<u64>()
+    }
+    .map_err(|_| anyhow!("Invalid offset \"{text}\""))?;
+
+    let offset = match relative {
+        Some(true) => cursor.checked_add(value),
+        Some(false) => cursor.checked_sub(value),
+        None => Some(value),
+    }
+    .filter(|offset| *offset < len)
+    .ok_or_else(|| anyhow!("Offset is outside of the file (0..0x{len:x})"))?;
+    Ok(offset)
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.len(cx).div_ceil(BYTES_PER_ROW) as usize;
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::toggle_endianness))
+            .on_action(cx.listener(Self::go_to_offset))
+            .on_action(cx.listener(Self::find_bytes))
+            .on_action(cx.listener(Self::find_next))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .children(self.render_input_bar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        uniform_list("hex-rows", row_count, cx.processor(Self::render_rows))
+                            .track_scroll(&self.scroll_handle)
+                            .flex_1()
+                            .h_full()
+                            .px_2(),
+                    )
+                    .child(self.render_inspector(cx)),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::TitleChanged => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Binary File".into())
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.file.read(cx).bytes().is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.file.update(cx, |file, cx| file.save(cx))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexFile;
+
+    fn for_project_item(
+        _: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("496", 0, 1000).unwrap(), 496);
+        assert_eq!(parse_offset(" 0x1F0 ", 0, 1000).unwrap(), 0x1f0);
+        assert_eq!(parse_offset("1f0h", 0, 1000).unwrap(), 0x1f0);
+        assert_eq!(parse_offset("+16", 100, 1000).unwrap(), 116);
+        assert_eq!(parse_offset("- 0x10", 100, 1000).unwrap(), 84);
+
+        assert!(parse_offset("1000", 0, 1000).is_err());
+        assert!(parse_offset("-200", 100, 1000).is_err());
+        assert!(parse_offset("0xzz", 0, 1000).is_err());
+        assert!(parse_offset("", 0, 1000).is_err());
+    }
+
+    #[test]
+    fn test_is_binary_path() {
+        assert!(is_binary_path(Path::new("build/firmware.BIN")));
+        assert!(is_binary_path(Path::new("capture.pcap")));
+        assert!(!is_binary_path(Path::new("src/main.rs")));
+        assert!(!is_binary_path(Path::new("Makefile")));
+    }
+}
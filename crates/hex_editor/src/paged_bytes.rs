@@ -0,0 +1,254 @@
+//! Byte content of a file, read from disk page by page, with overwrite edits kept in memory.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::File,
+    io::{self, Read as _, Seek as _, SeekFrom, Write as _},
+    ops::Range,
+    path::Path,
+    sync::Arc,
+};
+
+/// Size of a chunk of the file read from disk at once
+pub const PAGE_SIZE: u64 = 64 * 1024;
+/// Number of pages kept in memory, which bounds memory use for large files
+const MAX_CACHED_PAGES: usize = 256;
+
+/// Cache of file pages plus the bytes overwritten by the user.
+///
+/// Edits never change the file length, so offsets stay stable and edits can be written back in place.
+#[derive(Debug, Default)]
+pub struct PagedBytes {
+    len: u64,
+    pages: HashMap<u64, Arc<[u8]>>,
+    /// Loaded pages, least recently inserted first
+    page_order: VecDeque<u64>,
+    edits: BTreeMap<u64, u8>,
+}
+
+impl PagedBytes {
+    pub fn new(len: u64) -> Self {
+        Self {
+            len,
+            ..Default::default()
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn page_index(offset: u64) -> u64 {
+        offset / PAGE_SIZE
+    }
+
+    /// Byte at `offset` including edits, or `None` when its page is not loaded yet
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if offset >= self.len {
+            return None;
+        }
+        if let Some(edited) = self.edits.get(&offset) {
+            return Some(*edited);
+        }
+        self.original_byte(offset)
+    }
+
+    fn original_byte(&self, offset: u64) -> Option<u8> {
+        let page = self.pages.get(&Self::page_index(offset))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    /// Bytes of `range` including edits, if all of them are loaded
+    pub fn bytes(&self, range: Range<u64>) -> Option<Vec<u8>> {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        range.map(|offset| self.byte(offset)).collect()
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    pub fn edits(&self) -> &BTreeMap<u64, u8> {
+        &self.edits
+    }
+
+    /// Indices of pages intersecting `range` which still need to be read from disk
+    pub fn missing_pages(&self, range: Range<u64>) -> Vec<u64> {
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return Vec::new();
+        }
+        (Self::page_index(range.start)..=Self::page_index(end - 1))
+            .filter(|page| !self.pages.contains_key(page))
+            .collect()
+    }
+
+    pub fn insert_page(&mut self, index: u64, content: Arc<[u8]>) {
+        if self.pages.insert(index, content).is_none() {
+            self.page_order.push_back(index);
+        }
+        while self.page_order.len() > MAX_CACHED_PAGES {
+            if let Some(evicted) = self.page_order.pop_front() {
+                self.pages.remove(&evicted);
+            }
+        }
+    }
+
+    /// Overwrite the byte at `offset`. Writing back the original value drops the edit.
+    pub fn set_byte(&mut self, offset: u64, value: u8) {
+        if offset >= self.len {
+            return;
+        }
+        if self.original_byte(offset) == Some(value) {
+            self.edits.remove(&offset);
+        } else {
+            self.edits.insert(offset, value);
+        }
+    }
+
+    /// Mark edits as written to disk: bake them into the cached pages and forget them.
+    /// Edits made after `saved` was taken are kept.
+    pub fn commit_edits(&mut self, saved: &BTreeMap<u64, u8>) {
+        for (&offset, &value) in saved {
+            let page_index = Self::page_index(offset);
+            if let Some(page) = self.pages.get_mut(&page_index) {
+                let mut content = page.to_vec();
+                if let Some(byte) = content.get_mut((offset % PAGE_SIZE) as usize) {
+                    *byte = value;
+                }
+                *page = content.into();
+            }
+            if self.edits.get(&offset) == Some(&value) {
+                self.edits.remove(&offset);
+            }
+        }
+    }
+}
+
+/// Read a single page of the file from disk
+pub fn read_page(path: &Path, index: u64) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(index * PAGE_SIZE))?;
+    let mut content = Vec::with_capacity(PAGE_SIZE as usize);
+    file.take(PAGE_SIZE).read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Overlay edits onto a chunk of the file which starts at `offset`
+pub fn apply_edits(edits: &BTreeMap<u64, u8>, offset: u64, chunk: &mut [u8]) {
+    let end = offset + chunk.len() as u64;
+    for (&edit_offset, &value) in edits.range(offset..end) {
+        chunk[(edit_offset - offset) as usize] = value;
+    }
+}
+
+/// Write edits into the file in place, one contiguous run of bytes at a time
+pub fn write_edits(path: &Path, edits: &BTreeMap<u64, u8>) -> io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let mut edits = edits.iter().peekable();
+    while let Some((&start, &value)) = edits.next() {
+        let mut run = vec![value];
+        while let Some((&offset, &value)) = edits.peek() {
+            if offset != start + run.len() as u64 {
+                break;
+            }
+            run.push(*value);
+            edits.next();
+        }
+        file.seek(SeekFrom::Start(start))?;
+        file.write_all(&run)?;
+    }
+    file.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded(content: &[u8]) -> PagedBytes {
+        let mut bytes = PagedBytes::new(content.len() as u64);
+        for (index, page) in content.chunks(PAGE_SIZE as usize).enumerate() {
+            bytes.insert_page(index as u64, page.into());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_edits_overlay_pages() {
+        let mut bytes = loaded(&[0x00, 0x11, 0x22, 0x33]);
+        assert_eq!(bytes.byte(1), Some(0x11));
+        assert_eq!(bytes.byte(4), None);
+
+        bytes.set_byte(1, 0xff);
+        assert!(bytes.is_edited(1));
+        assert_eq!(bytes.bytes(0..4), Some(vec![0x00, 0xff, 0x22, 0x33]));
+
+        // Restoring the original value is not an edit
+        bytes.set_byte(1, 0x11);
+        assert!(!bytes.is_dirty());
+
+        // Edits past the end of the file are ignored, as the length never changes
+        bytes.set_byte(4, 0xff);
+        assert!(!bytes.is_dirty());
+    }
+
+    #[test]
+    fn test_missing_pages_and_eviction() {
+        let mut bytes = PagedBytes::new(PAGE_SIZE * 3 + 10);
+        assert_eq!(
+            bytes.missing_pages(PAGE_SIZE - 1..PAGE_SIZE + 1),
+            vec![0, 1]
+        );
+        assert_eq!(bytes.missing_pages(0..u64::MAX), vec![0, 1, 2, 3]);
+        assert!(bytes.missing_pages(PAGE_SIZE * 4..PAGE_SIZE * 5).is_empty());
+
+        bytes.insert_page(1, vec![0; PAGE_SIZE as usize].into());
+        assert_eq!(bytes.missing_pages(0..PAGE_SIZE * 2), vec![0]);
+
+        let mut bytes = PagedBytes::new(PAGE_SIZE * (MAX_CACHED_PAGES as u64 + 1));
+        for index in 0..=MAX_CACHED_PAGES as u64 {
+            bytes.insert_page(index, vec![1; 4].into());
+        }
+        assert_eq!(bytes.missing_pages(0..PAGE_SIZE * 2), vec![0]);
+    }
+
+    #[test]
+    fn test_write_edits_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("firmware.bin");
+        std::fs::write(&path, (0..=255u8).collect::<Vec<_>>()).unwrap();
+
+        let mut bytes = PagedBytes::new(256);
+        bytes.insert_page(0, read_page(&path, 0).unwrap().into());
+        bytes.set_byte(0x10, 0xaa);
+        bytes.set_byte(0x11, 0xbb);
+        bytes.set_byte(0xf0, 0xcc);
+
+        let saved = bytes.edits().clone();
+        write_edits(&path, &saved).unwrap();
+        bytes.commit_edits(&saved);
+        assert!(!bytes.is_dirty());
+        assert_eq!(bytes.byte(0x11), Some(0xbb));
+
+        let written = std::fs::read(&path).unwrap();
+        assert_eq!(written.len(), 256);
+        assert_eq!(&written[0x0f..0x13], &[0x0f, 0xaa, 0xbb, 0x12]);
+        assert_eq!(written[0xf0], 0xcc);
+
+        let mut chunk = written[0x0e..0x12].to_vec();
+        apply_edits(
+            &BTreeMap::from([(0x0f, 0x01), (0x20, 0x02)]),
+            0x0e,
+            &mut chunk,
+        );
+        assert_eq!(chunk, vec![0x0e, 0x01, 0xaa, 0xbb]);
+    }
+}
@@ -902,6 +902,12 @@ pub enum LanguageModelCostInfo {
     TokenCost {
         input_token_cost_per_1m: f64,
         output_token_cost_per_1m: f64,
+        /// Cost per 1M input tokens read from the prompt cache, when it
+        /// differs from the input token cost
+        cache_read_token_cost_per_1m: Option<f64>,
+        /// Cost per 1M input tokens written to the prompt cache, when it
+        /// differs from the input token cost
+        cache_creation_token_cost_per_1m: Option<f64>,
     },
     /// Cost per request
     RequestCost { cost_per_request: f64 },
@@ -917,6 +923,7 @@ impl LanguageModelCostInfo {
             LanguageModelCostInfo::TokenCost {
                 input_token_cost_per_1m,
                 output_token_cost_per_1m,
+                ..
             } => {
                 let input_cost = Self::cost_value_to_string(input_token_cost_per_1m);
                 let output_cost = Self::cost_value_to_string(output_token_cost_per_1m);
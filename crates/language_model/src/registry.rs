@@ -413,6 +413,18 @@ impl LanguageModelRegistry {
         self.default_model.clone()
     }
 
+    /// The fast model of the default model's provider, falling back to the default model
+    pub fn default_fast_model(&self) -> Option<ConfiguredModel> {
+        #[cfg(debug_assertions)]
+        if std::env::var("ZED_SIMULATE_NO_LLM_PROVIDER").is_ok() {
+            return None;
+        }
+
+        self.default_fast_model
+            .clone()
+            .or_else(|| self.default_model.clone())
+    }
+
     pub fn inline_assistant_model(&self) -> Option<ConfiguredModel> {
         #[cfg(debug_assertions)]
         if std::env::var("ZED_SIMULATE_NO_LLM_PROVIDER").is_ok() {
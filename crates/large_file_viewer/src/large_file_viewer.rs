@@ -0,0 +1,876 @@
+mod large_file_viewer_settings;
+mod line_index;
+mod line_search;
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use editor::{Editor, actions::SelectAll};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    MouseButton, ScrollStrategy, Task, UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings as _;
+use ui::{Tooltip, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ProjectItem},
+};
+
+pub use crate::{
+    large_file_viewer_settings::LargeFileViewerSettings,
+    line_index::{LINES_PER_CHUNK, LineIndex},
+    line_search::{LineMatch, LineQuery},
+};
+
+actions!(
+    large_file_viewer,
+    [
+        /// Moves the active line up.
+        MoveUp,
+        /// Moves the active line down.
+        MoveDown,
+        /// Moves the active line one page up.
+        MovePageUp,
+        /// Moves the active line one page down.
+        MovePageDown,
+        /// Moves to the first line of the file.
+        MoveToBeginning,
+        /// Moves to the last line of the file.
+        MoveToEnd,
+        /// Copies the active line to the clipboard.
+        CopyLine,
+        /// Opens the input for jumping to a line.
+        GoToLine,
+        /// Opens the search input.
+        Find,
+        /// Selects the next match of the last search.
+        FindNext,
+        /// Re-reads the file from disk, picking up lines appended since it was opened.
+        Reload,
+        /// Opens the file in a regular editor, loading all of it into memory.
+        OpenInEditor,
+    ]
+);
+
+/// Number of chunks of lines kept in memory
+const MAX_CACHED_CHUNKS: usize = 64;
+const LINES_PER_PAGE: u64 = 40;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileView>(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_editor);
+    })
+    .detach();
+}
+
+/// Replace the large file view with a regular editor, for when the file fits in memory after all
+fn open_in_editor(
+    workspace: &mut Workspace,
+    _: &OpenInEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(view) = workspace.active_item_as::<LargeFileView>(cx) else {
+        return;
+    };
+    let project_path = view.read(cx).file.read(cx).project_path.clone();
+    let pane = workspace.active_pane().clone();
+    let open_buffer = workspace
+        .project()
+        .update(cx, |project, cx| project.open_buffer(project_path, cx));
+    cx.spawn_in(window, async move |workspace, cx| {
+        let buffer = open_buffer.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.open_project_item::<Editor>(
+                pane.clone(),
+                buffer,
+                true,
+                true,
+                false,
+                false,
+                window,
+                cx,
+            );
+            pane.update(cx, |pane, cx| {
+                pane.remove_item(view.entity_id(), false, false, window, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// A text file too large to be loaded into a buffer. Lines are read from disk on demand.
+pub struct LargeFile {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    index: Option<Arc<LineIndex>>,
+    index_error: Option<SharedString>,
+    chunks: HashMap<u64, Arc<[SharedString]>>,
+    /// Loaded chunks, least recently inserted first
+    chunk_order: VecDeque<u64>,
+    /// Chunks being read from disk, or which failed to be read
+    requested_chunks: HashSet<u64>,
+    index_task: Task<()>,
+}
+
+impl LargeFile {
+    fn new(
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        entry_id: Option<ProjectEntryId>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            abs_path,
+            project_path,
+            entry_id,
+            index: None,
+            index_error: None,
+            chunks: HashMap::default(),
+            chunk_order: VecDeque::new(),
+            requested_chunks: HashSet::default(),
+            index_task: Task::ready(()),
+        };
+        this.reload(cx);
+        this
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    /// Line index of the file, once it has been built
+    pub fn index(&self) -> Option<&Arc<LineIndex>> {
+        self.index.as_ref()
+    }
+
+    /// Index the file again and drop all loaded lines
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        let abs_path = self.abs_path.clone();
+        self.index_task = cx.spawn(async move |this, cx| {
+            let index = cx
+                .background_spawn(async move {
+                    let file = std::fs::File::open(&abs_path)?;
+                    LineIndex::build(file)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.chunks.clear();
+                this.chunk_order.clear();
+                this.requested_chunks.clear();
+                match index {
+                    Ok(index) => {
+                        this.index = Some(Arc::new(index));
+                        this.index_error = None;
+                    }
+                    Err(error) => {
+                        this.index_error = Some(format!("Failed to read file: {error}").into())
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    pub fn line(&self, line: u64) -> Option<&SharedString> {
+        let chunk = self.chunks.get(&LineIndex::chunk_for_line(line))?;
+        chunk.get((line % LINES_PER_CHUNK) as usize)
+    }
+
+    /// Make sure the chunks covering `lines` are read from disk
+    pub fn load_lines(&mut self, lines: Range<u64>, cx: &mut Context<Self>) {
+        let Some(index) = self.index.clone() else {
+            return;
+        };
+        if lines.is_empty() {
+            return;
+        }
+        let chunks =
+            LineIndex::chunk_for_line(lines.start)..=LineIndex::chunk_for_line(lines.end - 1);
+        for chunk in chunks {
+            if self.chunks.contains_key(&chunk) || !self.requested_chunks.insert(chunk) {
+                continue;
+            }
+            let abs_path = self.abs_path.clone();
+            let index = index.clone();
+            cx.spawn(async move |this, cx| {
+                let lines = cx
+                    .background_spawn({
+                        let index = index.clone();
+                        async move { index.read_chunk(&abs_path, chunk) }
+                    })
+                    .await;
+                this.update(cx, |this, cx| {
+                    // The file was reloaded while the chunk was being read
+                    if !this
+                        .index
+                        .as_ref()
+                        .is_some_and(|current| Arc::ptr_eq(current, &index))
+                    {
+                        return;
+                    }
+                    match lines {
+                        Ok(lines) => {
+                            this.requested_chunks.remove(&chunk);
+                            this.insert_chunk(chunk, lines.into_iter().map(Into::into).collect());
+                        }
+                        // Keep the chunk requested, so that it's not re-read on every frame
+                        Err(error) => log::error!("failed to read {:?}: {error}", this.abs_path),
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    fn insert_chunk(&mut self, chunk: u64, lines: Arc<[SharedString]>) {
+        if self.chunks.insert(chunk, lines).is_none() {
+            self.chunk_order.push_back(chunk);
+        }
+        while self.chunk_order.len() > MAX_CACHED_CHUNKS {
+            if let Some(evicted) = self.chunk_order.pop_front() {
+                self.chunks.remove(&evicted);
+            }
+        }
+    }
+}
+
+impl project::ProjectItem for LargeFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let threshold = LargeFileViewerSettings::get_global(cx).threshold_bytes()?;
+        let project = project.read(cx);
+        // Lines are read straight from disk, which is only possible for local projects
+        if !project.is_local() {
+            return None;
+        }
+        let entry = project.entry_for_path(path, cx)?;
+        if !entry.is_file() || entry.is_fifo || entry.size < threshold {
+            return None;
+        }
+        let entry_id = Some(entry.id);
+        let abs_path = project.absolute_path(path, cx)?;
+        let project_path = path.clone();
+        Some(cx.spawn(async move |cx| {
+            Ok(cx.new(|cx| LargeFile::new(abs_path, project_path, entry_id, cx)))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputKind {
+    GoToLine,
+    Search,
+}
+
+struct InputBar {
+    kind: InputKind,
+    editor: Entity<Editor>,
+    error: Option<SharedString>,
+}
+
+/// Read-only view of a large file, without syntax highlighting, language servers, git diffs or inlay hints
+pub struct LargeFileView {
+    file: Entity<LargeFile>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    active_line: u64,
+    active_match: Option<LineMatch>,
+    input_bar: Option<InputBar>,
+    last_query: String,
+    case_sensitive: bool,
+    regex: bool,
+    status: Option<SharedString>,
+    search_task: Task<()>,
+}
+
+impl LargeFileView {
+    pub fn new(file: Entity<LargeFile>, _window: &mut Window, cx: &mut Context<Self>) -> Self {
+        cx.observe(&file, |_, _, cx| cx.notify()).detach();
+        Self {
+            file,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            active_line: 0,
+            active_match: None,
+            input_bar: None,
+            last_query: String::new(),
+            case_sensitive: false,
+            regex: false,
+            status: None,
+            search_task: Task::ready(()),
+        }
+    }
+
+    fn line_count(&self, cx: &App) -> u64 {
+        self.file
+            .read(cx)
+            .index()
+            .map_or(0, |index| index.line_count())
+    }
+
+    fn set_active_line(&mut self, line: u64, cx: &mut Context<Self>) {
+        let line_count = self.line_count(cx);
+        if line_count == 0 {
+            return;
+        }
+        self.active_line = line.min(line_count - 1);
+        self.scroll_handle
+            .scroll_to_item(self.active_line as usize, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn move_by(&mut self, delta: i64, cx: &mut Context<Self>) {
+        self.active_match = None;
+        self.set_active_line(self.active_line.saturating_add_signed(delta), cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(LINES_PER_PAGE as i64), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(LINES_PER_PAGE as i64, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_match = None;
+        self.set_active_line(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_match = None;
+        self.set_active_line(u64::MAX, cx);
+    }
+
+    fn copy_line(&mut self, _: &CopyLine, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(line) = self.file.read(cx).line(self.active_line) {
+            cx.write_to_clipboard(ClipboardItem::new_string(line.to_string()));
+        }
+    }
+
+    fn reload(&mut self, _: &Reload, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_match = None;
+        self.file.update(cx, |file, cx| file.reload(cx));
+    }
+
+    fn go_to_line(&mut self, _: &GoToLine, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_input_bar(InputKind::GoToLine, window, cx);
+    }
+
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_input_bar(InputKind::Search, window, cx);
+    }
+
+    fn show_input_bar(&mut self, kind: InputKind, window: &mut Window, cx: &mut Context<Self>) {
+        let (placeholder, text) = match kind {
+            InputKind::GoToLine => (
+                format!("Line number (1–{})", self.line_count(cx)),
+                String::new(),
+            ),
+            InputKind::Search => ("Search…".to_string(), self.last_query.clone()),
+        };
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(&placeholder, window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        editor.focus_handle(cx).focus(window, cx);
+        self.input_bar = Some(InputBar {
+            kind,
+            editor,
+            error: None,
+        });
+        cx.notify();
+    }
+
+    fn confirm_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(input_bar) = &self.input_bar else {
+            return;
+        };
+        let kind = input_bar.kind;
+        let text = input_bar.editor.read(cx).text(cx);
+        let result = match kind {
+            InputKind::GoToLine => parse_line_number(&text, self.line_count(cx)).map(|line| {
+                self.active_match = None;
+                self.set_active_line(line, cx);
+            }),
+            InputKind::Search => {
+                LineQuery::new(&text, self.case_sensitive, self.regex).map(|query| {
+                    self.last_query = text;
+                    self.active_match = None;
+                    self.search_next(query, cx);
+                })
+            }
+        };
+        match result {
+            Ok(()) => self.dismiss_input(window, cx),
+            Err(error) => {
+                if let Some(input_bar) = &mut self.input_bar {
+                    input_bar.error = Some(error.to_string().into());
+                }
+                cx.notify();
+            }
+        }
+    }
+
+    fn dismiss_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.input_bar = None;
+        self.focus_handle.focus(window, cx);
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        match LineQuery::new(&self.last_query, self.case_sensitive, self.regex) {
+            Ok(query) => self.search_next(query, cx),
+            Err(_) => self.show_input_bar(InputKind::Search, window, cx),
+        }
+    }
+
+    /// Search forward from the active match, or from the start of the active line
+    fn search_next(&mut self, query: LineQuery, cx: &mut Context<Self>) {
+        let file = self.file.read(cx);
+        let Some(index) = file.index().cloned() else {
+            return;
+        };
+        let abs_path = file.abs_path().to_path_buf();
+        let (line, column) = match &self.active_match {
+            Some(active_match) => (active_match.line, active_match.range.end),
+            None => (self.active_line, 0),
+        };
+
+        self.status = Some("Searching…".into());
+        cx.notify();
+        self.search_task = cx.spawn(async move |this, cx| {
+            let found = cx
+                .background_spawn(async move {
+                    line_search::find_next(&abs_path, &index, &query, line, column)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                match found {
+                    Ok(Some(found)) => {
+                        this.status = None;
+                        this.set_active_line(found.line, cx);
+                        this.active_match = Some(found);
+                    }
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn toggle_case_sensitive(&mut self, cx: &mut Context<Self>) {
+        self.case_sensitive = !self.case_sensitive;
+        cx.notify();
+    }
+
+    fn toggle_regex(&mut self, cx: &mut Context<Self>) {
+        self.regex = !self.regex;
+        cx.notify();
+    }
+
+    fn render_rows(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let lines = range.start as u64..range.end as u64;
+        self.file
+            .update(cx, |file, cx| file.load_lines(lines.clone(), cx));
+
+        let gutter_width = self.line_count(cx).to_string().len();
+        let colors = cx.theme().colors();
+        let active_line_background = colors.editor_active_line_background;
+        let match_background = colors.search_active_match_background;
+        let file = self.file.read(cx);
+
+        lines
+            .map(|line| {
+                let text = file.line(line).cloned();
+                let is_loaded = text.is_some();
+                let text = text.unwrap_or_default();
+                let highlighted = self
+                    .active_match
+                    .as_ref()
+                    .filter(|active_match| active_match.line == line)
+                    .and_then(|active_match| split_at_match(&text, active_match.range.clone()))
+                    .map(|(before, matched, after)| {
+                        (before.to_string(), matched.to_string(), after.to_string())
+                    });
+
+                h_flex()
+                    .id(("large-file-line", line))
+                    .w_full()
+                    .gap_4()
+                    .when(line == self.active_line, |row| {
+                        row.bg(active_line_background)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _, window, cx| {
+                            this.active_match = None;
+                            this.active_line = line;
+                            this.focus_handle.focus(window, cx);
+                            cx.notify();
+                        }),
+                    )
+                    .child(
+                        Label::new(format!("{:>gutter_width$}", line + 1))
+                            .buffer_font(cx)
+                            .color(if line == self.active_line {
+                                Color::Default
+                            } else {
+                                Color::Muted
+                            }),
+                    )
+                    .child(
+                        h_flex()
+                            .font_buffer(cx)
+                            .whitespace_nowrap()
+                            .when(!is_loaded, |this| this.text_color(Color::Muted.color(cx)))
+                            .map(|this| match highlighted {
+                                Some((before, matched, after)) => this
+                                    .child(before)
+                                    .child(div().bg(match_background).child(matched))
+                                    .child(after),
+                                None => this.child(text),
+                            }),
+                    )
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_input_bar(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let input_bar = self.input_bar.as_ref()?;
+        let label = match input_bar.kind {
+            InputKind::GoToLine => "Go to Line",
+            InputKind::Search => "Find",
+        };
+        Some(
+            h_flex()
+                .key_context("LargeFileInput")
+                .on_action(
+                    cx.listener(|this, _: &menu::Confirm, window, cx| {
+                        this.confirm_input(window, cx)
+                    }),
+                )
+                .on_action(
+                    cx.listener(|this, _: &menu::Cancel, window, cx| {
+                        this.dismiss_input(window, cx)
+                    }),
+                )
+                .p_1()
+                .gap_2()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(Label::new(label).size(LabelSize::Small))
+                .child(
+                    div()
+                        .flex_1()
+                        .px_1()
+                        .border_1()
+                        .rounded_sm()
+                        .border_color(if input_bar.error.is_some() {
+                            cx.theme().status().error_border
+                        } else {
+                            cx.theme().colors().border_variant
+                        })
+                        .child(input_bar.editor.clone()),
+                )
+                .when(input_bar.kind == InputKind::Search, |this| {
+                    this.child(
+                        IconButton::new("large-file-case-sensitive", IconName::CaseSensitive)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.case_sensitive)
+                            .tooltip(Tooltip::text("Match Case"))
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_case_sensitive(cx))),
+                    )
+                    .child(
+                        IconButton::new("large-file-regex", IconName::Regex)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.regex)
+                            .tooltip(Tooltip::text("Use Regular Expression"))
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_regex(cx))),
+                    )
+                })
+                .when_some(input_bar.error.clone(), |this, error| {
+                    this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+                })
+                .into_any_element(),
+        )
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file.read(cx);
+        let position = match (file.index(), &file.index_error) {
+            (_, Some(error)) => error.clone(),
+            (Some(index), None) => format!(
+                "Line {} of {} · {:.1} MB",
+                self.active_line + 1,
+                index.line_count(),
+                index.len() as f64 / (1024. * 1024.)
+            )
+            .into(),
+            (None, None) => "Indexing lines…".into(),
+        };
+
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_4()
+            .justify_between()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(Label::new(position).size(LabelSize::Small))
+                    .when_some(self.status.clone(), |this, status| {
+                        this.child(
+                            Label::new(status)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Label::new("Large file: syntax highlighting and language features are off")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new("large-file-reload", "Reload")
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::for_action_title("Reload from Disk", &Reload))
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.reload(&Reload, window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("large-file-open-in-editor", "Open in Editor")
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::text("Load the whole file into a regular editor"))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(OpenInEditor.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+    }
+}
+
+/// Parse a 1-based line number typed by the user into a 0-based line
+fn parse_line_number(text: &str, line_count: u64) -> Result<u64> {
+    let text = text.trim();
+    let text = text.split(':').next().unwrap_or(text);
+    let line = text
+        .parse::<u64>()
+        .map_err(|_| anyhow!("Invalid line number \"{text}\""))?;
+    if line == 0 || line > line_count {
+        return Err(anyhow!("Line number must be between 1 and {line_count}"));
+    }
+    Ok(line - 1)
+}
+
+/// Split a line around a match's byte range. The range is clamped to the line and widened to
+/// character boundaries, since matches may end past truncated lines or inside a character.
+fn split_at_match(text: &str, range: Range<usize>) -> Option<(&str, &str, &str)> {
+    let start = text.floor_char_boundary(range.start.min(text.len()));
+    let end = text.ceil_char_boundary(range.end.min(text.len()));
+    if start >= end {
+        return None;
+    }
+    Some((&text[..start], &text[start..end], &text[end..]))
+}
+
+impl Render for LargeFileView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let line_count = self.line_count(cx) as usize;
+
+        v_flex()
+            .key_context("LargeFileView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::copy_line))
+            .on_action(cx.listener(Self::reload))
+            .on_action(cx.listener(Self::go_to_line))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::find_next))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .children(self.render_input_bar(cx))
+            .child(
+                uniform_list(
+                    "large-file-lines",
+                    line_count,
+                    cx.processor(Self::render_rows),
+                )
+                .track_scroll(&self.scroll_handle)
+                .flex_1()
+                .px_2(),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Focusable for LargeFileView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for LargeFileView {}
+
+impl Item for LargeFileView {
+    type Event = ();
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Large File".into())
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for LargeFileView {
+    type Item = LargeFile;
+
+    fn for_project_item(
+        _: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_number() {
+        assert_eq!(parse_line_number("1", 10).unwrap(), 0);
+        assert_eq!(parse_line_number(" 10 ", 10).unwrap(), 9);
+        assert_eq!(parse_line_number("7:12", 10).unwrap(), 6);
+        assert!(parse_line_number("0", 10).is_err());
+        assert!(parse_line_number("11", 10).is_err());
+        assert!(parse_line_number("ten", 10).is_err());
+    }
+
+    #[test]
+    fn test_split_at_match() {
+        assert_eq!(
+            split_at_match("let café = 1;", 4..9),
+            Some(("let ", "café", " = 1;"))
+        );
+        // Ranges inside a character are widened to the whole character
+        assert_eq!(
+            split_at_match("naïve ünïcode", 3..4),
+            Some(("na", "ï", "ve ünïcode"))
+        );
+        // Matches past the end of a truncated line are clamped to it
+        assert_eq!(split_at_match("日本語", 3..20), Some(("日", "本語", "")));
+        assert_eq!(split_at_match("日本語", 9..12), None);
+    }
+}
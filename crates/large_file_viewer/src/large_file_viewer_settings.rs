@@ -0,0 +1,31 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for opening files too large for the editor.
+#[derive(Clone, Debug, Default, RegisterSetting)]
+pub struct LargeFileViewerSettings {
+    /// Files of at least this many megabytes are opened in the large file view.
+    /// 0 disables the large file view.
+    ///
+    /// Default: 64
+    pub threshold_mb: u64,
+}
+
+impl LargeFileViewerSettings {
+    /// Size in bytes from which files are opened in the large file view, if enabled
+    pub fn threshold_bytes(&self) -> Option<u64> {
+        (self.threshold_mb > 0).then(|| self.threshold_mb.saturating_mul(1024 * 1024))
+    }
+}
+
+impl Settings for LargeFileViewerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            threshold_mb: content
+                .large_file_viewer
+                .clone()
+                .unwrap()
+                .threshold_mb
+                .unwrap(),
+        }
+    }
+}
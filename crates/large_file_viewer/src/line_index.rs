@@ -0,0 +1,230 @@
+//! Sparse index of line starts, so that any line of a large file can be read without reading the whole file.
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek as _, SeekFrom},
+    ops::{ControlFlow, Range},
+    path::Path,
+};
+
+/// Number of lines between two consecutive entries of the index, and the number of lines read at once
+pub const LINES_PER_CHUNK: u64 = 256;
+/// Longest prefix of a line which is kept in memory. The rest of the line is neither shown nor searched.
+pub const MAX_LINE_LEN: usize = 16 * 1024;
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Byte offsets of every [`LINES_PER_CHUNK`]-th line of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    chunk_starts: Vec<u64>,
+    line_count: u64,
+    len: u64,
+}
+
+impl LineIndex {
+    /// Index the file by streaming it once, without keeping its content in memory
+    pub fn build(mut reader: impl Read) -> io::Result<Self> {
+        let mut buffer = vec![0; READ_BUFFER_SIZE];
+        let mut chunk_starts = vec![0];
+        let mut newlines = 0u64;
+        let mut len = 0u64;
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            for (ix, _) in buffer[..read]
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+            {
+                newlines += 1;
+                if newlines % LINES_PER_CHUNK == 0 {
+                    chunk_starts.push(len + ix as u64 + 1);
+                }
+            }
+            len += read as u64;
+        }
+        Ok(Self {
+            chunk_starts,
+            // Like in the editor, a trailing newline starts one more (empty) line
+            line_count: newlines + 1,
+            len,
+        })
+    }
+
+    pub fn line_count(&self) -> u64 {
+        self.line_count
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn chunk_for_line(line: u64) -> u64 {
+        line / LINES_PER_CHUNK
+    }
+
+    /// Byte range of the lines of the chunk, including the newline ending its last line
+    fn chunk_byte_range(&self, chunk: u64) -> Option<Range<u64>> {
+        let start = *self.chunk_starts.get(chunk as usize)?;
+        let end = self
+            .chunk_starts
+            .get(chunk as usize + 1)
+            .copied()
+            .unwrap_or(self.len);
+        Some(start..end)
+    }
+
+    fn lines_in_chunk(&self, chunk: u64) -> u64 {
+        self.line_count
+            .saturating_sub(chunk * LINES_PER_CHUNK)
+            .min(LINES_PER_CHUNK)
+    }
+
+    /// Read the lines of a chunk from disk
+    pub fn read_chunk(&self, path: &Path, chunk: u64) -> io::Result<Vec<String>> {
+        let Some(range) = self.chunk_byte_range(chunk) else {
+            return Ok(Vec::new());
+        };
+        let expected_lines = self.lines_in_chunk(chunk) as usize;
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(range.start))?;
+        let reader = BufReader::new(file.take(range.end - range.start));
+
+        let mut lines = Vec::with_capacity(expected_lines);
+        for_each_line(reader, |line| {
+            lines.push(line);
+            if lines.len() == expected_lines {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })?;
+        Ok(lines)
+    }
+
+    /// Reader positioned at the start of the chunk containing `line`, and the number of that chunk's first line
+    pub fn reader_at_line(&self, path: &Path, line: u64) -> io::Result<(impl BufRead, u64)> {
+        let chunk = Self::chunk_for_line(line.min(self.line_count.saturating_sub(1)));
+        let start = self
+            .chunk_byte_range(chunk)
+            .map_or(self.len, |range| range.start);
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(start))?;
+        Ok((
+            BufReader::with_capacity(READ_BUFFER_SIZE, file.take(self.len - start)),
+            chunk * LINES_PER_CHUNK,
+        ))
+    }
+}
+
+/// Call `f` with every line of the reader, without line endings.
+/// Lines are decoded lossily and truncated to [`MAX_LINE_LEN`] bytes.
+pub fn for_each_line(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(String) -> ControlFlow<()>,
+) -> io::Result<()> {
+    let mut line = Vec::new();
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        let newline = buffer.iter().position(|byte| *byte == b'\n');
+        let content = &buffer[..newline.unwrap_or(buffer.len())];
+        let room = MAX_LINE_LEN.saturating_sub(line.len());
+        line.extend_from_slice(&content[..content.len().min(room)]);
+        let consumed = newline.map_or(buffer.len(), |newline| newline + 1);
+        reader.consume(consumed);
+
+        if newline.is_some() && f(decode_line(&mut line)).is_break() {
+            return Ok(());
+        }
+    }
+    // The last line has no newline, and is empty when the file ends with one
+    let _ = f(decode_line(&mut line));
+    Ok(())
+}
+
+fn decode_line(line: &mut Vec<u8>) -> String {
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    let decoded = String::from_utf8_lossy(line).into_owned();
+    line.clear();
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_lines(count: u64) -> String {
+        (0..count)
+            .map(|ix| format!("line {ix}\n"))
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_build_index() {
+        let index = LineIndex::build("".as_bytes()).unwrap();
+        assert_eq!(index.line_count(), 1);
+        assert!(index.is_empty());
+
+        let index = LineIndex::build("a\r\nb".as_bytes()).unwrap();
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.chunk_starts, vec![0]);
+
+        let text = numbered_lines(LINES_PER_CHUNK * 2 + 3);
+        let index = LineIndex::build(text.as_bytes()).unwrap();
+        assert_eq!(index.line_count(), LINES_PER_CHUNK * 2 + 4);
+        assert_eq!(index.len(), text.len() as u64);
+        assert_eq!(index.chunk_starts.len(), 3);
+        let second_chunk_start = index.chunk_starts[1] as usize;
+        assert!(text[second_chunk_start..].starts_with(&format!("line {LINES_PER_CHUNK}\n")));
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("server.log");
+        let text = numbered_lines(LINES_PER_CHUNK + 2);
+        std::fs::write(&path, &text).unwrap();
+        let index = LineIndex::build(text.as_bytes()).unwrap();
+
+        let first = index.read_chunk(&path, 0).unwrap();
+        assert_eq!(first.len(), LINES_PER_CHUNK as usize);
+        assert_eq!(first[0], "line 0");
+        assert_eq!(first[LINES_PER_CHUNK as usize - 1], "line 255");
+
+        // The last chunk ends with the empty line after the trailing newline
+        let last = index.read_chunk(&path, 1).unwrap();
+        assert_eq!(last, vec!["line 256", "line 257", ""]);
+        assert!(index.read_chunk(&path, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_long_lines_and_line_endings() {
+        let long_line = "x".repeat(MAX_LINE_LEN * 2);
+        let mut text = format!("crlf\r\n{long_line}\ninvalid ").into_bytes();
+        text.push(0xff);
+        let mut lines = Vec::new();
+        for_each_line(text.as_slice(), |line| {
+            lines.push(line);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "crlf");
+        assert_eq!(lines[1].len(), MAX_LINE_LEN);
+        assert_eq!(lines[2], "invalid \u{fffd}");
+    }
+}
@@ -0,0 +1,176 @@
+//! Search streaming through the file line by line, starting from an indexed position.
+
+use std::{
+    io,
+    ops::{ControlFlow, Range},
+    path::Path,
+};
+
+use anyhow::{Result, bail};
+use regex::{Regex, RegexBuilder};
+
+use crate::line_index::{LineIndex, for_each_line};
+
+/// Text or regex query, matched against each line separately
+#[derive(Debug, Clone)]
+pub struct LineQuery {
+    regex: Regex,
+}
+
+impl LineQuery {
+    pub fn new(query: &str, case_sensitive: bool, is_regex: bool) -> Result<Self> {
+        if query.is_empty() {
+            bail!("Empty query");
+        }
+        let pattern = if is_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()?;
+        if regex.is_match("") {
+            bail!("Query matches empty text");
+        }
+        Ok(Self { regex })
+    }
+
+    /// First match in `line` starting at or after byte `from`
+    pub fn find_from(&self, line: &str, from: usize) -> Option<Range<usize>> {
+        if from > line.len() || !line.is_char_boundary(from) {
+            return None;
+        }
+        self.regex.find_at(line, from).map(|found| found.range())
+    }
+}
+
+/// Position of a match: line number and byte range within the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    pub line: u64,
+    pub range: Range<usize>,
+}
+
+/// Find the first match at or after byte `column` of `line`, wrapping around to the start of the file
+pub fn find_next(
+    path: &Path,
+    index: &LineIndex,
+    query: &LineQuery,
+    line: u64,
+    column: usize,
+) -> io::Result<Option<LineMatch>> {
+    let (reader, first_line) = index.reader_at_line(path, line)?;
+    let mut found = None;
+    let mut line_number = first_line;
+    for_each_line(reader, |text| {
+        let from = match line_number.cmp(&line) {
+            std::cmp::Ordering::Less => None,
+            std::cmp::Ordering::Equal => Some(column),
+            std::cmp::Ordering::Greater => Some(0),
+        };
+        if let Some(range) = from.and_then(|from| query.find_from(&text, from)) {
+            found = Some(LineMatch {
+                line: line_number,
+                range,
+            });
+            return ControlFlow::Break(());
+        }
+        line_number += 1;
+        ControlFlow::Continue(())
+    })?;
+    if found.is_some() {
+        return Ok(found);
+    }
+
+    let (reader, _) = index.reader_at_line(path, 0)?;
+    let mut line_number = 0;
+    for_each_line(reader, |text| {
+        if line_number > line {
+            return ControlFlow::Break(());
+        }
+        if let Some(range) = query
+            .find_from(&text, 0)
+            .filter(|range| line_number < line || range.start < column)
+        {
+            found = Some(LineMatch {
+                line: line_number,
+                range,
+            });
+            return ControlFlow::Break(());
+        }
+        line_number += 1;
+        ControlFlow::Continue(())
+    })?;
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let query = LineQuery::new("error", false, false).unwrap();
+        assert_eq!(query.find_from("ok ERROR error", 0), Some(3..8));
+        assert_eq!(query.find_from("ok ERROR error", 4), Some(9..14));
+        assert_eq!(query.find_from("ok", 10), None);
+
+        let query = LineQuery::new("a.c", true, false).unwrap();
+        assert_eq!(query.find_from("abc a.c", 0), Some(4..7));
+
+        let query = LineQuery::new(r"id=\d+", true, true).unwrap();
+        assert_eq!(query.find_from("user id=42", 0), Some(5..10));
+
+        assert!(LineQuery::new("", false, false).is_err());
+        assert!(LineQuery::new("x*", false, true).is_err());
+        assert!(LineQuery::new("(", false, true).is_err());
+    }
+
+    #[test]
+    fn test_find_next_wraps_around() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let mut text = String::new();
+        for ix in 0..1000 {
+            if ix == 10 || ix == 700 {
+                text.push_str(&format!("{ix} ERROR failed, ERROR again\n"));
+            } else {
+                text.push_str(&format!("{ix} INFO ok\n"));
+            }
+        }
+        std::fs::write(&path, &text).unwrap();
+        let index = LineIndex::build(text.as_bytes()).unwrap();
+        let query = LineQuery::new("error", false, false).unwrap();
+
+        let found = find_next(&path, &index, &query, 0, 0).unwrap().unwrap();
+        assert_eq!(
+            found,
+            LineMatch {
+                line: 10,
+                range: 3..8
+            }
+        );
+        let found = find_next(&path, &index, &query, 10, 8).unwrap().unwrap();
+        assert_eq!(
+            found,
+            LineMatch {
+                line: 10,
+                range: 17..22
+            }
+        );
+        let found = find_next(&path, &index, &query, 10, 22).unwrap().unwrap();
+        assert_eq!(found.line, 700);
+        let found = find_next(&path, &index, &query, 700, 22).unwrap().unwrap();
+        assert_eq!(
+            found,
+            LineMatch {
+                line: 10,
+                range: 3..8
+            }
+        );
+
+        let missing = LineQuery::new("panic", false, false).unwrap();
+        assert_eq!(find_next(&path, &index, &missing, 500, 0).unwrap(), None);
+    }
+}
@@ -441,6 +441,68 @@ pub async fn stream_chat_completion(
     }
 }
 
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+/// Computes an embedding for each of the given texts, in order.
+pub async fn embed(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    texts: &[&str],
+) -> Result<Vec<Vec<f32>>> {
+    let uri = format!("{api_url}/embeddings");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+
+    if let Some(api_key) = api_key {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    let request = request_builder.body(AsyncBody::from(serde_json::to_string(
+        &EmbeddingsRequest {
+            model,
+            input: texts.to_vec(),
+        },
+    )?))?;
+
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    anyhow::ensure!(
+        response.status().is_success(),
+        "Failed to connect to LM Studio API: {} {}",
+        response.status(),
+        body,
+    );
+    let mut response: EmbeddingsResponse =
+        serde_json::from_str(&body).context("Unable to parse LM Studio embeddings response")?;
+    response.data.sort_by_key(|data| data.index);
+    Ok(response
+        .data
+        .into_iter()
+        .map(|data| data.embedding)
+        .collect())
+}
+
 pub async fn get_models(
     client: &dyn HttpClient,
     api_url: &str,
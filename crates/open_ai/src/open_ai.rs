@@ -648,9 +648,18 @@ pub enum OpenAiEmbeddingModel {
     TextEmbedding3Large,
 }
 
+impl OpenAiEmbeddingModel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::TextEmbedding3Small => "text-embedding-3-small",
+            Self::TextEmbedding3Large => "text-embedding-3-large",
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct OpenAiEmbeddingRequest<'a> {
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     input: Vec<&'a str>,
 }
 
@@ -668,7 +677,7 @@ pub fn embed<'a>(
     client: &dyn HttpClient,
     api_url: &str,
     api_key: &str,
-    model: OpenAiEmbeddingModel,
+    model: &'a str,
     texts: impl IntoIterator<Item = &'a str>,
 ) -> impl 'static + Future<Output = Result<OpenAiEmbeddingResponse>> {
     let uri = format!("{api_url}/embeddings");
@@ -0,0 +1,357 @@
+use anyhow::anyhow;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    Action, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    ParentElement, Render, Styled, Task, WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{
+    ModalView, NamedPaneLayout, PaneLayoutSource, Workspace, notifications::NotifyTaskExt,
+};
+
+actions!(
+    pane_layouts,
+    [
+        /// Opens a picker to switch to a saved or configured pane layout, or
+        /// to save the current one.
+        Toggle,
+        /// Removes the selected saved layout in the pane layouts picker.
+        RemoveSelected
+    ]
+);
+
+/// Switches to the pane layout with the given name.
+#[derive(Clone, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = pane_layouts)]
+#[serde(deny_unknown_fields)]
+pub struct Apply {
+    pub name: String,
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _window: Option<&mut Window>, _: &mut Context<Workspace>) {
+    workspace.register_action(toggle);
+    workspace.register_action(apply);
+}
+
+fn toggle(workspace: &mut Workspace, _: &Toggle, window: &mut Window, cx: &mut Context<Workspace>) {
+    let layouts = workspace.pane_layouts(cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let layouts = layouts.await;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let workspace_handle = cx.entity().downgrade();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                PaneLayoutPicker::new(layouts, workspace_handle, window, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn apply(
+    workspace: &mut Workspace,
+    action: &Apply,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let layouts = workspace.pane_layouts(cx);
+    let name = action.name.clone();
+    let workspace_handle = workspace.weak_handle();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let layouts = layouts.await;
+        let Some(layout) = layouts.into_iter().find(|layout| layout.name == name) else {
+            return Err(anyhow!("There is no pane layout named {name:?}"));
+        };
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.apply_pane_layout(&layout, window, cx)
+            })?
+            .await
+    })
+    .detach_and_notify_err(workspace_handle, window, cx);
+}
+
+pub struct PaneLayoutPicker {
+    picker: Entity<Picker<PaneLayoutPickerDelegate>>,
+}
+
+impl PaneLayoutPicker {
+    fn new(
+        layouts: Vec<NamedPaneLayout>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = PaneLayoutPickerDelegate {
+            layouts,
+            workspace,
+            pane_layout_picker: cx.entity().downgrade(),
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+
+    fn remove_selected(&mut self, _: &RemoveSelected, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let ix = picker.delegate.selected_index;
+            picker.delegate.remove_layout_at(ix, window, cx);
+        });
+    }
+}
+
+impl ModalView for PaneLayoutPicker {}
+
+impl EventEmitter<DismissEvent> for PaneLayoutPicker {}
+
+impl Focusable for PaneLayoutPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for PaneLayoutPicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("PaneLayoutPicker")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::remove_selected))
+            .child(self.picker.clone())
+    }
+}
+
+pub struct PaneLayoutPickerDelegate {
+    layouts: Vec<NamedPaneLayout>,
+    workspace: WeakEntity<Workspace>,
+    pane_layout_picker: WeakEntity<PaneLayoutPicker>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+}
+
+impl PaneLayoutPickerDelegate {
+    /// The name to save the current layout under, if the query names one.
+    fn new_layout_name(&self) -> Option<&str> {
+        let name = self.query.trim();
+        (!name.is_empty()).then_some(name)
+    }
+
+    fn remove_layout_at(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(mat) = self.matches.get(ix) else {
+            return;
+        };
+        let layout = &self.layouts[mat.candidate_id];
+        if layout.source != PaneLayoutSource::Saved {
+            return;
+        }
+        let name = layout.name.clone();
+        let Some(task) = self
+            .workspace
+            .update(cx, |workspace, cx| workspace.delete_pane_layout(name, cx))
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            task.await?;
+            let layouts = picker
+                .read_with(cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .workspace
+                        .read_with(cx, |workspace, cx| workspace.pane_layouts(cx))
+                })??
+                .await;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.layouts = layouts;
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for PaneLayoutPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        "Switch to a layout, or type a name to save the current one...".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No layouts".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + usize::from(self.new_layout_name().is_some())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let workspace = self.workspace.clone();
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let layout = self.layouts[mat.candidate_id].clone();
+            if let Some(task) = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.apply_pane_layout(&layout, window, cx)
+                })
+                .log_err()
+            {
+                task.detach_and_notify_err(workspace, window, cx);
+            }
+        } else if let Some(name) = self.new_layout_name() {
+            let name = name.to_string();
+            if let Some(task) = workspace
+                .update(cx, |workspace, cx| {
+                    workspace.save_pane_layout(name, window, cx)
+                })
+                .log_err()
+            {
+                task.detach_and_notify_err(workspace, window, cx);
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.pane_layout_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.query = query.clone();
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .layouts
+            .iter()
+            .enumerate()
+            .map(|(id, layout)| StringMatchCandidate::new(id, &layout.name))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.match_count().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let Some(mat) = self.matches.get(ix) else {
+            let name = self.new_layout_name()?;
+            let label = if self
+                .layouts
+                .iter()
+                .any(|layout| layout.name == name && layout.source == PaneLayoutSource::Saved)
+            {
+                format!("Replace Layout “{name}” with the Current Layout")
+            } else {
+                format!("Save Current Layout as “{name}”")
+            };
+            return Some(
+                ListItem::new(ix)
+                    .inset(true)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .start_slot(Icon::new(IconName::Plus).color(Color::Muted))
+                    .child(Label::new(label)),
+            );
+        };
+        let layout = self.layouts.get(mat.candidate_id)?;
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Split).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .gap_x_2()
+                        .child(HighlightedLabel::new(
+                            layout.name.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .when(layout.source == PaneLayoutSource::Settings, |this| {
+                            this.child(
+                                Label::new("settings")
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            )
+                        }),
+                )
+                .when(layout.source == PaneLayoutSource::Saved, |this| {
+                    this.end_hover_slot(
+                        IconButton::new(("remove-pane-layout", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Remove Layout"))
+                            .on_click(cx.listener(move |picker, _, window, cx| {
+                                cx.stop_propagation();
+                                picker.delegate.remove_layout_at(ix, window, cx);
+                            })),
+                    )
+                }),
+        )
+    }
+}
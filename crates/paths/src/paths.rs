@@ -272,6 +272,18 @@ pub fn debug_scenarios_file() -> &'static PathBuf {
     DEBUG_SCENARIOS_FILE.get_or_init(|| config_dir().join("debug.json"))
 }
 
+/// Returns the path to the `macros.json` file, where named keyboard macros are saved.
+pub fn macros_file() -> &'static PathBuf {
+    static MACROS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    MACROS_FILE.get_or_init(|| config_dir().join("macros.json"))
+}
+
+/// Returns the path to the `clipboard_history.json` file, where the text copied in editors is kept.
+pub fn clipboard_history_file() -> &'static PathBuf {
+    static CLIPBOARD_HISTORY_FILE: OnceLock<PathBuf> = OnceLock::new();
+    CLIPBOARD_HISTORY_FILE.get_or_init(|| data_dir().join("clipboard_history.json"))
+}
+
 /// Returns the path to the extensions directory.
 ///
 /// This is where installed extensions are stored.
@@ -310,6 +322,12 @@ pub fn snippets_dir() -> &'static PathBuf {
     SNIPPETS_DIR.get_or_init(|| config_dir().join("snippets"))
 }
 
+/// Returns the path to the directory of user-installed spell checking dictionaries.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
 // Returns old path to contexts directory.
 // Fallback
 fn text_threads_dir_fallback() -> &'static PathBuf {
@@ -461,6 +479,14 @@ pub fn local_tasks_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `dictionary.txt` file within a project, which
+/// lists the words accepted by the spell checker.
+pub fn local_dictionary_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::unix(".zed/dictionary.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
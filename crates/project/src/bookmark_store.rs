@@ -0,0 +1,364 @@
+//! Bookmarks are lines in the project's files that the user wants to come back to.
+//!
+//! A bookmark is kept as an anchor in its file's buffer, so it stays on its line as the file is
+//! edited. Bookmarks can have a name, which is unique within the project, and are saved with the
+//! workspace by their row.
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot};
+use std::{ops::Range, path::Path, sync::Arc};
+use text::Point;
+
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub name: Option<SharedString>,
+}
+
+/// A bookmark along with the file it's in.
+#[derive(Clone, Debug)]
+pub struct BookmarkEntry {
+    pub abs_path: Arc<Path>,
+    pub buffer: Entity<Buffer>,
+    pub bookmark: Bookmark,
+    /// The row the bookmark is on when the entry was created.
+    pub row: u32,
+}
+
+/// A bookmark as it's saved in the workspace database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub row: u32,
+    pub name: Option<Arc<str>>,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |this, buffer, event, cx| {
+            if let BufferEvent::FileHandleChanged = event {
+                this.on_file_handle_changed(buffer, cx);
+            }
+        });
+        Self {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+}
+
+impl Bookmark {
+    pub fn row(&self, snapshot: &BufferSnapshot) -> u32 {
+        self.position.summary::<Point>(snapshot).row
+    }
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated(Arc<Path>),
+    BookmarksCleared(Vec<Arc<Path>>),
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    worktree_store: Entity<WorktreeStore>,
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            buffer_store,
+            worktree_store,
+            bookmarks: BTreeMap::new(),
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.read(cx).absolutize(&file.path))
+            .map(Arc::<Path>::from)
+    }
+
+    fn on_file_handle_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, in_file)| in_file.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        if buffer
+            .read(cx)
+            .file()
+            .is_none_or(|file| file.disk_state().is_deleted())
+        {
+            self.bookmarks.remove(&old_path);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.notify();
+            return;
+        }
+
+        if let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx)
+            && new_path != old_path
+            && !self.bookmarks.contains_key(&new_path)
+            && let Some(in_file) = self.bookmarks.remove(&old_path)
+        {
+            self.bookmarks.insert(new_path.clone(), in_file);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(new_path));
+            cx.notify();
+        }
+    }
+
+    /// Removes the bookmarks on the position's row, or adds an anonymous bookmark there when it
+    /// has none.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+
+        let len_before = in_file.bookmarks.len();
+        in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.row(&snapshot) != row);
+        if in_file.bookmarks.len() == len_before {
+            in_file.bookmarks.push(Bookmark {
+                position,
+                name: None,
+            });
+        }
+
+        self.bookmarks_changed(abs_path, cx);
+    }
+
+    /// Puts the named bookmark at the position, moving it there when it's elsewhere in the
+    /// project. Other bookmarks on the position's row are replaced.
+    pub fn set_named_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        name: SharedString,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+
+        let mut changed_paths = Vec::new();
+        for (path, in_file) in &mut self.bookmarks {
+            let len_before = in_file.bookmarks.len();
+            in_file
+                .bookmarks
+                .retain(|bookmark| bookmark.name.as_ref() != Some(&name));
+            if in_file.bookmarks.len() != len_before && *path != abs_path {
+                changed_paths.push(path.clone());
+            }
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+        in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.row(&snapshot) != row);
+        in_file.bookmarks.push(Bookmark {
+            position,
+            name: Some(name),
+        });
+
+        for path in changed_paths {
+            self.bookmarks_changed(path, cx);
+        }
+        self.bookmarks_changed(abs_path, cx);
+    }
+
+    pub fn remove_bookmark(
+        &mut self,
+        abs_path: &Arc<Path>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(in_file) = self.bookmarks.get_mut(abs_path) else {
+            return;
+        };
+        in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.position != position);
+        self.bookmarks_changed(abs_path.clone(), cx);
+    }
+
+    pub fn clear_bookmarks_in_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx) else {
+            return;
+        };
+        if self.bookmarks.remove(&abs_path).is_some() {
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![abs_path]));
+            cx.notify();
+        }
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        let paths = self.bookmarks.keys().cloned().collect();
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksCleared(paths));
+        cx.notify();
+    }
+
+    fn bookmarks_changed(&mut self, abs_path: Arc<Path>, cx: &mut Context<Self>) {
+        if self
+            .bookmarks
+            .get(&abs_path)
+            .is_some_and(|in_file| in_file.bookmarks.is_empty())
+        {
+            self.bookmarks.remove(&abs_path);
+        }
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    /// The bookmarks in the buffer, optionally limited to a range of it, in no particular order.
+    pub fn bookmarks<'a>(
+        &'a self,
+        buffer: &Entity<Buffer>,
+        range: Option<Range<text::Anchor>>,
+        buffer_snapshot: &'a BufferSnapshot,
+        cx: &App,
+    ) -> impl Iterator<Item = &'a Bookmark> + 'a {
+        Self::abs_path_from_buffer(buffer, cx)
+            .and_then(|abs_path| self.bookmarks.get(&abs_path))
+            .into_iter()
+            .flat_map(|in_file| in_file.bookmarks.iter())
+            .filter(move |bookmark| {
+                buffer_snapshot.can_resolve(&bookmark.position)
+                    && range.as_ref().is_none_or(|range| {
+                        bookmark.position.cmp(&range.start, buffer_snapshot).is_ge()
+                            && bookmark.position.cmp(&range.end, buffer_snapshot).is_le()
+                    })
+            })
+    }
+
+    /// All of the project's bookmarks, ordered by path and then by row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        let mut entries = Vec::new();
+        for (abs_path, in_file) in &self.bookmarks {
+            let snapshot = in_file.buffer.read(cx).snapshot();
+            let start = entries.len();
+            entries.extend(in_file.bookmarks.iter().map(|bookmark| BookmarkEntry {
+                abs_path: abs_path.clone(),
+                buffer: in_file.buffer.clone(),
+                bookmark: bookmark.clone(),
+                row: bookmark.row(&snapshot),
+            }));
+            entries[start..].sort_by_key(|entry| entry.row);
+        }
+        entries
+    }
+
+    pub fn bookmark_named(&self, name: &str, cx: &App) -> Option<BookmarkEntry> {
+        self.all_bookmarks(cx)
+            .into_iter()
+            .find(|entry| entry.bookmark.name.as_deref() == Some(name))
+    }
+
+    pub fn all_serialized_bookmarks(
+        &self,
+        cx: &App,
+    ) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(abs_path, in_file)| {
+                let snapshot = in_file.buffer.read(cx).snapshot();
+                let bookmarks = in_file
+                    .bookmarks
+                    .iter()
+                    .map(|bookmark| SerializedBookmark {
+                        row: bookmark.row(&snapshot),
+                        name: bookmark.name.as_ref().map(|name| Arc::from(name.as_ref())),
+                    })
+                    .collect();
+                (abs_path.clone(), bookmarks)
+            })
+            .collect()
+    }
+
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.downgrade();
+        let buffer_store = self.buffer_store.downgrade();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (abs_path, serialized) in bookmarks {
+                if serialized.is_empty() {
+                    continue;
+                }
+                let (worktree, relative_path) = worktree_store
+                    .update(cx, |this, cx| {
+                        this.find_or_create_worktree(&abs_path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |this, cx| {
+                        let path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: relative_path,
+                        };
+                        this.open_buffer(path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::debug!("skipping bookmarks in {abs_path:?}, which couldn't be opened");
+                    continue;
+                };
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+                let mut in_file = this.update(cx, |_, cx| BookmarksInFile::new(buffer, cx))?;
+                for bookmark in serialized {
+                    if bookmark.row > snapshot.max_point().row {
+                        continue;
+                    }
+                    in_file.bookmarks.push(Bookmark {
+                        position: snapshot.anchor_after(Point::new(bookmark.row, 0)),
+                        name: bookmark
+                            .name
+                            .map(|name| SharedString::from(name.to_string())),
+                    });
+                }
+                new_bookmarks.insert(abs_path, in_file);
+            }
+            this.update(cx, |this, cx| {
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+}
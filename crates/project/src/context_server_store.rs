@@ -7,7 +7,13 @@ use std::time::Duration;
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+#[cfg(feature = "test-support")]
+use context_server::test::FakeCredentialsProvider;
+use context_server::{
+    ContextServer, ContextServerCommand, ContextServerId, ElicitationHandler, SamplingHandler,
+    oauth::{self, OAuthSession},
+};
+use credentials_provider::CredentialsProvider;
 use futures::{FutureExt as _, future::Either, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use itertools::Itertools;
@@ -27,6 +33,9 @@ use crate::{
 /// Prevents extremely large timeout values from tying up resources indefinitely.
 const MAX_TIMEOUT_SECS: u64 = 600; // 10 minutes
 
+/// How long to wait for the user to authorize Zed in their browser
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
 pub fn init(cx: &mut App) {
     extension::init(cx);
 }
@@ -45,6 +54,8 @@ pub enum ContextServerStatus {
     Running,
     Stopped,
     Error(Arc<str>),
+    /// The server rejected Zed until the user authorizes it with OAuth
+    AuthRequired,
 }
 
 impl ContextServerStatus {
@@ -54,6 +65,7 @@ impl ContextServerStatus {
             ContextServerState::Running { .. } => ContextServerStatus::Running,
             ContextServerState::Stopped { .. } => ContextServerStatus::Stopped,
             ContextServerState::Error { error, .. } => ContextServerStatus::Error(error.clone()),
+            ContextServerState::AuthRequired { .. } => ContextServerStatus::AuthRequired,
         }
     }
 }
@@ -77,6 +89,10 @@ enum ContextServerState {
         configuration: Arc<ContextServerConfiguration>,
         error: Arc<str>,
     },
+    AuthRequired {
+        server: Arc<ContextServer>,
+        configuration: Arc<ContextServerConfiguration>,
+    },
 }
 
 impl ContextServerState {
@@ -86,6 +102,7 @@ impl ContextServerState {
             ContextServerState::Running { server, .. } => server.clone(),
             ContextServerState::Stopped { server, .. } => server.clone(),
             ContextServerState::Error { server, .. } => server.clone(),
+            ContextServerState::AuthRequired { server, .. } => server.clone(),
         }
     }
 
@@ -95,6 +112,7 @@ impl ContextServerState {
             ContextServerState::Running { configuration, .. } => configuration.clone(),
             ContextServerState::Stopped { configuration, .. } => configuration.clone(),
             ContextServerState::Error { configuration, .. } => configuration.clone(),
+            ContextServerState::AuthRequired { configuration, .. } => configuration.clone(),
         }
     }
 }
@@ -221,11 +239,21 @@ pub struct ContextServerStore {
     registry: Entity<ContextServerDescriptorRegistry>,
     update_servers_task: Option<Task<Result<()>>>,
     context_server_factory: Option<ContextServerFactory>,
+    credentials_provider: Arc<dyn CredentialsProvider>,
+    sampling_handler: Option<SamplingHandler>,
+    elicitation_handler: Option<ElicitationHandler>,
+    oauth_sessions: HashMap<ContextServerId, OAuthSessionState>,
     needs_server_update: bool,
     ai_disabled: bool,
     _subscriptions: Vec<Subscription>,
 }
 
+struct OAuthSessionState {
+    session: Arc<OAuthSession>,
+    _persist_refreshed_credentials: Task<()>,
+    _watch_authorization_required: Task<()>,
+}
+
 pub struct ServerStatusChangedEvent {
     pub server_id: ContextServerId,
     pub status: ContextServerStatus,
@@ -243,6 +271,7 @@ impl ContextServerStore {
         Self::new_internal(
             !headless,
             None,
+            <dyn CredentialsProvider>::global(cx),
             ContextServerDescriptorRegistry::default_global(cx),
             worktree_store,
             weak_project,
@@ -264,6 +293,7 @@ impl ContextServerStore {
         Self::new_internal(
             true,
             None,
+            <dyn CredentialsProvider>::global(cx),
             ContextServerDescriptorRegistry::default_global(cx),
             worktree_store,
             weak_project,
@@ -311,6 +341,7 @@ impl ContextServerStore {
         Self::new_internal(
             false,
             None,
+            Arc::new(FakeCredentialsProvider::default()),
             registry,
             worktree_store,
             weak_project,
@@ -333,6 +364,7 @@ impl ContextServerStore {
         Self::new_internal(
             true,
             context_server_factory,
+            Arc::new(FakeCredentialsProvider::default()),
             registry,
             worktree_store,
             weak_project,
@@ -349,6 +381,11 @@ impl ContextServerStore {
         self.context_server_factory = Some(factory);
     }
 
+    #[cfg(feature = "test-support")]
+    pub fn set_credentials_provider(&mut self, provider: Arc<dyn CredentialsProvider>) {
+        self.credentials_provider = provider;
+    }
+
     #[cfg(feature = "test-support")]
     pub fn registry(&self) -> &Entity<ContextServerDescriptorRegistry> {
         &self.registry
@@ -371,6 +408,7 @@ impl ContextServerStore {
     fn new_internal(
         maintain_server_loop: bool,
         context_server_factory: Option<ContextServerFactory>,
+        credentials_provider: Arc<dyn CredentialsProvider>,
         registry: Entity<ContextServerDescriptorRegistry>,
         worktree_store: Entity<WorktreeStore>,
         weak_project: Option<WeakEntity<Project>>,
@@ -429,6 +467,10 @@ impl ContextServerStore {
             server_ids: Default::default(),
             update_servers_task: None,
             context_server_factory,
+            credentials_provider,
+            sampling_handler: None,
+            elicitation_handler: None,
+            oauth_sessions: HashMap::default(),
         };
         if maintain_server_loop && !DisableAiSettings::get_global(cx).disable_ai {
             this.available_context_servers_changed(cx);
@@ -436,6 +478,22 @@ impl ContextServerStore {
         this
     }
 
+    /// Sets the handler answering sampling requests of the servers started from now on
+    pub fn set_sampling_handler(&mut self, handler: Option<SamplingHandler>) {
+        for state in self.servers.values() {
+            state.server().set_sampling_handler(handler.clone());
+        }
+        self.sampling_handler = handler;
+    }
+
+    /// Sets the handler answering elicitation requests of the servers started from now on
+    pub fn set_elicitation_handler(&mut self, handler: Option<ElicitationHandler>) {
+        for state in self.servers.values() {
+            state.server().set_elicitation_handler(handler.clone());
+        }
+        self.elicitation_handler = handler;
+    }
+
     pub fn get_server(&self, id: &ContextServerId) -> Option<Arc<ContextServer>> {
         self.servers.get(id).map(|state| state.server())
     }
@@ -452,6 +510,13 @@ impl ContextServerStore {
         self.servers.get(id).map(ContextServerStatus::from_state)
     }
 
+    /// Whether Zed holds OAuth credentials for the server
+    pub fn has_oauth_credentials(&self, id: &ContextServerId) -> bool {
+        self.oauth_sessions
+            .get(id)
+            .is_some_and(|oauth| oauth.session.access_token().is_some())
+    }
+
     pub fn configuration_for_server(
         &self,
         id: &ContextServerId,
@@ -616,21 +681,29 @@ impl ContextServerStore {
                         })
                         .log_err()
                     }
-                    Err(err) => {
-                        log::error!("{} context server failed to start: {}", id, err);
-                        this.update(cx, |this, cx| {
-                            this.update_server_state(
-                                id.clone(),
+                    Err(err) => this
+                        .update(cx, |this, cx| {
+                            let state = if this
+                                .oauth_sessions
+                                .get(&id)
+                                .is_some_and(|oauth| oauth.session.authorization_required())
+                            {
+                                log::info!("{} context server requires authorization", id);
+                                ContextServerState::AuthRequired {
+                                    configuration,
+                                    server,
+                                }
+                            } else {
+                                log::error!("{} context server failed to start: {}", id, err);
                                 ContextServerState::Error {
                                     configuration,
                                     server,
                                     error: err.to_string().into(),
-                                },
-                                cx,
-                            )
+                                }
+                            };
+                            this.update_server_state(id.clone(), state, cx)
                         })
-                        .log_err()
-                    }
+                        .log_err(),
                 };
             }
         });
@@ -742,6 +815,18 @@ impl ContextServerStore {
             configuration
         };
 
+        let oauth_session = match configuration.as_ref() {
+            // Servers configured with their own authorization header don't use OAuth
+            ContextServerConfiguration::Http { url, headers, .. }
+                if !headers
+                    .keys()
+                    .any(|header| header.eq_ignore_ascii_case("authorization")) =>
+            {
+                Some(Self::oauth_session(this.clone(), &id, url, cx).await?)
+            }
+            _ => None,
+        };
+
         let server: Arc<ContextServer> = this.update(cx, |this, cx| {
             let global_timeout =
                 Self::resolve_project_settings(&this.worktree_store, cx).context_server_timeout;
@@ -764,6 +849,7 @@ impl ContextServerStore {
                     Some(Duration::from_secs(
                         timeout.unwrap_or(global_timeout).min(MAX_TIMEOUT_SECS),
                     )),
+                    oauth_session,
                 )?)),
                 _ => {
                     let mut command = configuration
@@ -787,10 +873,189 @@ impl ContextServerStore {
                 }
             }
         })??;
+        this.update(cx, |this, _| {
+            server.set_sampling_handler(this.sampling_handler.clone());
+            server.set_elicitation_handler(this.elicitation_handler.clone());
+        })?;
 
         Ok((server, configuration))
     }
 
+    /// Returns the OAuth session of an HTTP server, loading its stored credentials the first time.
+    async fn oauth_session(
+        this: WeakEntity<Self>,
+        id: &ContextServerId,
+        endpoint: &url::Url,
+        cx: &mut AsyncApp,
+    ) -> Result<Arc<OAuthSession>> {
+        let existing_session = this.read_with(cx, |this, _| {
+            this.oauth_sessions
+                .get(id)
+                .map(|oauth| oauth.session.clone())
+                .filter(|session| session.endpoint() == endpoint)
+        })?;
+        if let Some(session) = existing_session {
+            return Ok(session);
+        }
+
+        let credentials_provider =
+            this.read_with(cx, |this, _| this.credentials_provider.clone())?;
+        let credentials = oauth::read_credentials(credentials_provider.as_ref(), endpoint, cx)
+            .await
+            .log_err()
+            .flatten();
+        let session = Arc::new(OAuthSession::new(endpoint.clone(), credentials));
+        this.update(cx, |this, cx| {
+            let refreshed_credentials = session.refreshed_credentials();
+            let endpoint = endpoint.clone();
+            let persist_task = cx.spawn(async move |_, cx| {
+                while let Ok(credentials) = refreshed_credentials.recv().await {
+                    oauth::write_credentials(
+                        credentials_provider.as_ref(),
+                        &endpoint,
+                        &credentials,
+                        cx,
+                    )
+                    .await
+                    .log_err();
+                }
+            });
+
+            // Tokens can expire while the server is running, in which case the user needs to
+            // authorize Zed again before the server accepts requests
+            let authorization_required = session.authorization_required_changes();
+            let server_id = id.clone();
+            let watch_task = cx.spawn(async move |this, cx| {
+                while let Ok(()) = authorization_required.recv().await {
+                    this.update(cx, |this, cx| {
+                        this.require_authorization_of_running_server(&server_id, cx)
+                    })
+                    .ok();
+                }
+            });
+            this.oauth_sessions.insert(
+                id.clone(),
+                OAuthSessionState {
+                    session: session.clone(),
+                    _persist_refreshed_credentials: persist_task,
+                    _watch_authorization_required: watch_task,
+                },
+            );
+        })?;
+        Ok(session)
+    }
+
+    /// Moves a running server whose requests are rejected to [`ContextServerState::AuthRequired`].
+    /// Servers that are still starting end up there once their initialization fails.
+    fn require_authorization_of_running_server(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ContextServerState::Running {
+            server,
+            configuration,
+        }) = self.servers.get(id)
+        else {
+            return;
+        };
+        log::info!("{} context server requires authorization again", id);
+        let state = ContextServerState::AuthRequired {
+            server: server.clone(),
+            configuration: configuration.clone(),
+        };
+        self.update_server_state(id.clone(), state, cx);
+    }
+
+    /// Authorizes Zed with the OAuth authorization server of a context server in the browser,
+    /// then restarts the server with the obtained credentials.
+    pub fn authenticate_server(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(session) = self
+            .oauth_sessions
+            .get(id)
+            .map(|oauth| oauth.session.clone())
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "context server `{id}` does not support OAuth"
+            )));
+        };
+        let Some(state) = self.servers.get(id) else {
+            return Task::ready(Err(anyhow::anyhow!("context server `{id}` not found")));
+        };
+        let server = state.server();
+        let configuration = state.configuration();
+        let http_client = cx.http_client();
+        let credentials_provider = self.credentials_provider.clone();
+        cx.spawn(async move |this, cx| {
+            let authorization = oauth::prepare_authorization(
+                http_client.as_ref(),
+                session.endpoint(),
+                &session.challenge(),
+            )
+            .await?;
+            cx.update(|cx| cx.open_url(authorization.url.as_str()));
+
+            let timeout = cx.background_executor().timer(AUTHORIZATION_TIMEOUT);
+            let credentials = match futures::future::select(
+                authorization.complete(http_client.as_ref()).boxed_local(),
+                timeout,
+            )
+            .await
+            {
+                Either::Left((credentials, _)) => credentials?,
+                Either::Right(_) => anyhow::bail!("timed out waiting for authorization"),
+            };
+            oauth::write_credentials(
+                credentials_provider.as_ref(),
+                session.endpoint(),
+                &credentials,
+                cx,
+            )
+            .await
+            .log_err();
+            session.set_credentials(Some(credentials));
+
+            this.update(cx, |this, cx| this.run_server(server, configuration, cx))
+        })
+    }
+
+    /// Forgets the OAuth credentials of a context server, which then needs to be authorized again.
+    pub fn sign_out_server(
+        &mut self,
+        id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(session) = self
+            .oauth_sessions
+            .get(id)
+            .map(|oauth| oauth.session.clone())
+        else {
+            return Task::ready(Ok(()));
+        };
+        session.set_credentials(None);
+        // Restart a running server, so that it rejects the forgotten credentials
+        let server = match self.servers.get(id) {
+            Some(ContextServerState::Running {
+                server,
+                configuration,
+            }) => Some((server.clone(), configuration.clone())),
+            _ => None,
+        };
+        let credentials_provider = self.credentials_provider.clone();
+        cx.spawn(async move |this, cx| {
+            oauth::delete_credentials(credentials_provider.as_ref(), session.endpoint(), cx)
+                .await?;
+            if let Some((server, configuration)) = server {
+                this.update(cx, |this, cx| this.run_server(server, configuration, cx))?;
+            }
+            Ok(())
+        })
+    }
+
     async fn handle_get_context_server_command(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetContextServerCommand>,
@@ -365,6 +365,7 @@ impl Search {
                             grab_buffer_snapshot_rx,
                             find_all_matches_tx,
                             sorted_matches_tx,
+                            query.is_structural(),
                             cx.clone(),
                         )
                         .boxed_local(),
@@ -533,10 +534,14 @@ impl Search {
             oneshot::Sender<(Entity<Buffer>, Vec<Range<language::Anchor>>)>,
         )>,
         results: Sender<oneshot::Receiver<(Entity<Buffer>, Vec<Range<language::Anchor>>)>>,
+        wait_for_syntax: bool,
         mut cx: AsyncApp,
     ) {
         _ = maybe!(async move {
             while let Ok(buffer) = rx.recv().await {
+                if wait_for_syntax {
+                    Self::wait_for_syntax(&buffer, &mut cx).await;
+                }
                 let snapshot = buffer.read_with(&mut cx, |this, _| this.snapshot());
                 let (tx, rx) = oneshot::channel();
                 find_all_matches_tx.send((buffer, snapshot, tx)).await?;
@@ -548,6 +553,32 @@ impl Search {
         .await;
     }
 
+    /// Structural queries are matched against syntax trees, but a buffer that
+    /// was just opened for the search may not have its language or a parsed
+    /// tree yet.
+    async fn wait_for_syntax(buffer: &Entity<Buffer>, cx: &mut AsyncApp) {
+        let load_language = buffer.read_with(cx, |buffer, cx| {
+            if buffer.language().is_some() {
+                return None;
+            }
+            let language_registry = buffer.language_registry()?;
+            let language =
+                language_registry.language_for_file(buffer.file()?, Some(buffer.as_rope()), cx)?;
+            Some(language_registry.load_language(&language))
+        });
+        if let Some(load_language) = load_language
+            && let Ok(Ok(language)) = load_language.await
+        {
+            buffer.update(cx, |buffer, cx| {
+                if buffer.language().is_none() {
+                    buffer.set_language_async(Some(language), cx);
+                }
+            });
+        }
+        let parsing_idle = buffer.read_with(cx, |buffer, _| buffer.parsing_idle());
+        parsing_idle.await;
+    }
+
     async fn ensure_matched_ranges_are_reported_in_order(
         rx: Receiver<oneshot::Receiver<(Entity<Buffer>, Vec<Range<language::Anchor>>)>>,
         tx: Sender<SearchResult>,
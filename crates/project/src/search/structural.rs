@@ -0,0 +1,491 @@
+//! Structural search, in which patterns are written as code and matched against
+//! the syntax trees of buffers rather than their text.
+//!
+//! A pattern may contain metavariables: `$NAME` matches any single syntax node,
+//! and `$$$NAME` matches any sequence of sibling nodes, including an empty one.
+//! A metavariable that appears more than once must match the same text each
+//! time, except for `$_` and `$$$`, which match without capturing anything.
+//! Replacements substitute the text captured by the metavariables they mention.
+
+use aho_corasick::AhoCorasick;
+use anyhow::{Result, anyhow};
+use collections::HashMap;
+use language::{BufferSnapshot, Grammar, GrammarId, Language, Node, Tree};
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+use text::Rope;
+
+/// The prefix of the identifiers that stand in for metavariables when a
+/// pattern is parsed, chosen to be a valid identifier in most languages.
+const PLACEHOLDER_PREFIX: &str = "zed_metavar_";
+
+struct Metavariable {
+    /// `None` for metavariables that don't capture anything.
+    name: Option<String>,
+    multiple: bool,
+}
+
+/// A structural search pattern, compiled lazily for each language it's
+/// matched against.
+pub struct StructuralPattern {
+    /// The pattern with each metavariable replaced by a placeholder identifier.
+    code: String,
+    metavariables: Vec<Metavariable>,
+    placeholders: HashMap<String, usize>,
+    /// The longest identifier in the pattern outside of its metavariables,
+    /// which any file with a match must contain.
+    keyword: Option<AhoCorasick>,
+    compiled: Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>,
+}
+
+/// A pattern parsed with a particular language's grammar.
+struct CompiledPattern {
+    tree: Tree,
+    /// The child indices leading from the root of the tree to the node that
+    /// is matched against buffers, skipping the nodes that only wrap it.
+    root_path: Vec<usize>,
+}
+
+/// A token of a pattern or replacement's text.
+enum Token<'a> {
+    Text(&'a str),
+    Metavariable { name: &'a str, multiple: bool },
+}
+
+impl StructuralPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let mut code = String::new();
+        let mut metavariables = Vec::new();
+        let mut placeholders = HashMap::default();
+        let mut named_placeholders = HashMap::<(&str, bool), String>::default();
+        for token in tokenize(pattern) {
+            match token {
+                Token::Text(text) => code.push_str(text),
+                Token::Metavariable { name, multiple } => {
+                    let captures = !name.is_empty() && name != "_";
+                    if captures && let Some(placeholder) = named_placeholders.get(&(name, multiple))
+                    {
+                        code.push_str(placeholder);
+                        continue;
+                    }
+                    let placeholder = format!("{PLACEHOLDER_PREFIX}{}", metavariables.len());
+                    placeholders.insert(placeholder.clone(), metavariables.len());
+                    metavariables.push(Metavariable {
+                        name: captures.then(|| name.to_string()),
+                        multiple,
+                    });
+                    if captures {
+                        named_placeholders.insert((name, multiple), placeholder.clone());
+                    }
+                    code.push_str(&placeholder);
+                }
+            }
+        }
+
+        if placeholders.contains_key(trim_pattern(&code)) {
+            return Err(anyhow!(
+                "A structural pattern must contain code besides a metavariable"
+            ));
+        }
+
+        let keyword = pattern_text(pattern)
+            .flat_map(|text| text.split(|c: char| !c.is_alphanumeric() && c != '_'))
+            .max_by_key(|word| word.len())
+            .filter(|word| !word.is_empty())
+            .map(|word| AhoCorasick::new([word]))
+            .transpose()?;
+
+        Ok(Self {
+            code,
+            metavariables,
+            placeholders,
+            keyword,
+            compiled: Mutex::default(),
+        })
+    }
+
+    /// Returns whether a file's text might contain a match for this pattern.
+    pub(crate) fn may_match(&self, text: &str) -> bool {
+        self.keyword
+            .as_ref()
+            .is_none_or(|keyword| keyword.is_match(text))
+    }
+
+    /// Finds the non-overlapping matches for this pattern in the syntax trees
+    /// of the given range of a buffer.
+    pub fn find_matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers_for_range(range.clone(), true) {
+            let Some(compiled) = self.compiled_for(layer.language) else {
+                continue;
+            };
+            let root = compiled.root();
+            let mut cursor = layer.node().walk();
+            'traversal: loop {
+                let node = cursor.node();
+                let node_range = node.byte_range();
+                let mut descend = node_range.start < range.end && node_range.end > range.start;
+                if descend
+                    && node_range.start >= range.start
+                    && node_range.end <= range.end
+                    && node.kind_id() == root.kind_id()
+                    && self.match_root(&compiled, node, buffer.as_rope()).is_some()
+                {
+                    matches.push(node_range);
+                    descend = false;
+                }
+                if descend && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'traversal;
+                    }
+                }
+            }
+        }
+
+        // Matches in different layers may overlap, such as in injected code.
+        matches.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+        let mut last_end = 0;
+        matches.retain(|range| {
+            let keep = range.start >= last_end;
+            if keep {
+                last_end = range.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    /// Returns the text that replaces the match with the given range in the
+    /// buffer, substituting the metavariables in the replacement with the text
+    /// they captured.
+    pub fn replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Option<String> {
+        let rope = buffer.as_rope();
+        let captures = buffer
+            .syntax_layers_for_range(range.clone(), true)
+            .find_map(|layer| {
+                let compiled = self.compiled_for(layer.language)?;
+                let mut node = layer
+                    .node()
+                    .descendant_for_byte_range(range.start, range.end)?;
+                // Several nested nodes may span the same range, such as an
+                // expression statement and its expression.
+                loop {
+                    if node.byte_range() != range {
+                        return None;
+                    }
+                    if let Some(captures) = self.match_root(&compiled, node, rope) {
+                        return Some(captures);
+                    }
+                    node = node.parent()?;
+                }
+            })?;
+
+        let mut text = String::new();
+        for token in tokenize(replacement) {
+            match token {
+                Token::Text(token_text) => text.push_str(token_text),
+                Token::Metavariable { name, multiple } => {
+                    let captured = captures.iter().find_map(|(ix, range)| {
+                        let metavariable = &self.metavariables[*ix];
+                        (metavariable.name.as_deref() == Some(name)
+                            && metavariable.multiple == multiple)
+                            .then(|| range.clone())
+                    });
+                    match captured {
+                        Some(range) => text.extend(rope.chunks_in_range(range)),
+                        None => {
+                            text.push_str(if multiple { "$$$" } else { "$" });
+                            text.push_str(name);
+                        }
+                    }
+                }
+            }
+        }
+        Some(text)
+    }
+
+    fn compiled_for(&self, language: &Language) -> Option<Arc<CompiledPattern>> {
+        let grammar = language.grammar()?;
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| self.compile(grammar).map(Arc::new))
+            .clone()
+    }
+
+    fn compile(&self, grammar: &Grammar) -> Option<CompiledPattern> {
+        let tree = language::with_parser(|parser| {
+            parser.set_language(&grammar.ts_language).ok()?;
+            parser.parse(&self.code, None)
+        })?;
+
+        let mut root_path = Vec::new();
+        let mut node = tree.root_node();
+        loop {
+            let mut named_children = (0..node.child_count())
+                .filter_map(|ix| Some((ix, node.child(ix)?)))
+                .filter(|(_, child)| child.is_named() && !child.is_extra());
+            let Some((ix, child)) = named_children.next() else {
+                break;
+            };
+            if named_children.next().is_some()
+                || trim_pattern(&self.code[child.byte_range()])
+                    != trim_pattern(&self.code[node.byte_range()])
+            {
+                break;
+            }
+            root_path.push(ix);
+            node = child;
+        }
+
+        // A pattern that the language can't parse, such as one written in
+        // another language, can't match anything.
+        if contains_error(node) || self.metavariable(node).is_some() {
+            return None;
+        }
+        Some(CompiledPattern { tree, root_path })
+    }
+
+    fn metavariable(&self, node: Node) -> Option<&Metavariable> {
+        let text = self.code.get(node.byte_range())?;
+        if !text.starts_with(PLACEHOLDER_PREFIX) {
+            return None;
+        }
+        let ix = *self.placeholders.get(text)?;
+        Some(&self.metavariables[ix])
+    }
+
+    /// Matches the pattern against a node, returning the ranges captured by
+    /// each metavariable if it matches.
+    fn match_root(
+        &self,
+        compiled: &CompiledPattern,
+        node: Node,
+        rope: &Rope,
+    ) -> Option<Vec<(usize, Range<usize>)>> {
+        let mut matcher = Matcher {
+            pattern: self,
+            rope,
+            captures: Vec::new(),
+        };
+        matcher
+            .match_node(compiled.root(), node)
+            .then_some(matcher.captures)
+    }
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralPattern")
+            .field("code", &self.code)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CompiledPattern {
+    fn root(&self) -> Node<'_> {
+        let mut node = self.tree.root_node();
+        for &ix in &self.root_path {
+            node = node.child(ix).expect("pattern tree changed");
+        }
+        node
+    }
+}
+
+struct Matcher<'a> {
+    pattern: &'a StructuralPattern,
+    rope: &'a Rope,
+    /// The metavariables' indices and the ranges they captured.
+    captures: Vec<(usize, Range<usize>)>,
+}
+
+impl Matcher<'_> {
+    fn match_node(&mut self, pattern: Node, target: Node) -> bool {
+        if let Some(metavariable) = self.pattern.metavariable(pattern)
+            && !metavariable.multiple
+        {
+            return target.is_named() && self.capture(pattern, target.byte_range());
+        }
+        if pattern.kind_id() != target.kind_id() {
+            return false;
+        }
+        if pattern.child_count() == 0 {
+            return target.child_count() == 0
+                && rope_eq(
+                    self.rope,
+                    target.byte_range(),
+                    &self.pattern.code[pattern.byte_range()],
+                );
+        }
+
+        let pattern_children = significant_children(pattern, true);
+        let target_children = significant_children(target, false);
+        self.match_sequence(&pattern_children, &target_children, target.start_byte())
+    }
+
+    /// Matches a sequence of pattern nodes against a sequence of sibling
+    /// nodes, backtracking over the number of nodes each `$$$` metavariable
+    /// matches. `offset` is where an empty sequence of siblings would start.
+    fn match_sequence(&mut self, pattern: &[Node], target: &[Node], offset: usize) -> bool {
+        let Some((first, rest)) = pattern.split_first() else {
+            return target.is_empty();
+        };
+        let captures_len = self.captures.len();
+
+        if self
+            .pattern
+            .metavariable(*first)
+            .is_some_and(|metavariable| metavariable.multiple)
+        {
+            for count in 0..=target.len() {
+                let range = match target[..count] {
+                    [] => offset..offset,
+                    [first_target, .., last_target] => {
+                        first_target.start_byte()..last_target.end_byte()
+                    }
+                    [only_target] => only_target.byte_range(),
+                };
+                let end = range.end;
+                if self.capture(*first, range) && self.match_sequence(rest, &target[count..], end) {
+                    return true;
+                }
+                self.captures.truncate(captures_len);
+            }
+            return false;
+        }
+
+        let Some((first_target, rest_target)) = target.split_first() else {
+            return false;
+        };
+        if self.match_node(*first, *first_target)
+            && self.match_sequence(rest, rest_target, first_target.end_byte())
+        {
+            return true;
+        }
+        self.captures.truncate(captures_len);
+        false
+    }
+
+    /// Records the range captured by a metavariable, returning false if the
+    /// metavariable already captured different text.
+    fn capture(&mut self, pattern: Node, range: Range<usize>) -> bool {
+        let Some(ix) = self
+            .pattern
+            .code
+            .get(pattern.byte_range())
+            .and_then(|text| self.pattern.placeholders.get(text).copied())
+        else {
+            return false;
+        };
+        if self.pattern.metavariables[ix].name.is_none() {
+            return true;
+        }
+        if let Some((_, captured)) = self.captures.iter().find(|(other, _)| *other == ix) {
+            let captured = self
+                .rope
+                .chunks_in_range(captured.clone())
+                .collect::<String>();
+            return rope_eq(self.rope, range, &captured);
+        }
+        self.captures.push((ix, range));
+        true
+    }
+}
+
+/// Splits a pattern or replacement into text and metavariables. Metavariable
+/// names are uppercase, so that `$` can still appear in code, such as in PHP
+/// variables or shell parameters.
+fn tokenize(text: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut search_from = 0;
+        loop {
+            let Some(dollar) = rest[search_from..].find('$').map(|ix| ix + search_from) else {
+                let token = Token::Text(rest);
+                rest = "";
+                return Some(token);
+            };
+            let multiple = rest[dollar..].starts_with("$$$");
+            let name_start = dollar + if multiple { 3 } else { 1 };
+            let name_len = rest[name_start..]
+                .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+                .unwrap_or(rest.len() - name_start);
+            let name = &rest[name_start..name_start + name_len];
+            let followed_by_word = rest[name_start + name_len..]
+                .starts_with(|c: char| c.is_alphanumeric() || c == '_');
+            let is_metavariable = !followed_by_word
+                && if multiple {
+                    name.is_empty() || name.starts_with(|c: char| !c.is_ascii_digit())
+                } else {
+                    name.starts_with(|c: char| !c.is_ascii_digit())
+                };
+            if !is_metavariable {
+                search_from = dollar + 1;
+                continue;
+            }
+            if dollar > 0 {
+                let token = Token::Text(&rest[..dollar]);
+                rest = &rest[dollar..];
+                return Some(token);
+            }
+            rest = &rest[name_start + name_len..];
+            return Some(Token::Metavariable { name, multiple });
+        }
+    })
+}
+
+fn pattern_text(pattern: &str) -> impl Iterator<Item = &str> {
+    tokenize(pattern).filter_map(|token| match token {
+        Token::Text(text) => Some(text),
+        Token::Metavariable { .. } => None,
+    })
+}
+
+/// Trims the whitespace and trailing semicolons that a pattern's root node
+/// may not include.
+fn trim_pattern(text: &str) -> &str {
+    text.trim().trim_end_matches(';').trim_end()
+}
+
+/// Returns the children of a node that matching compares, leaving out
+/// comments and, in patterns, the nodes the parser inserted to recover from
+/// missing syntax.
+fn significant_children(node: Node, is_pattern: bool) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !(is_pattern && child.is_missing()))
+        .collect()
+}
+
+fn contains_error(node: Node) -> bool {
+    if node.is_error() {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(contains_error)
+}
+
+fn rope_eq(rope: &Rope, range: Range<usize>, text: &str) -> bool {
+    if range.len() != text.len() {
+        return false;
+    }
+    let mut offset = 0;
+    for chunk in rope.chunks_in_range(range) {
+        if text.get(offset..offset + chunk.len()) != Some(chunk) {
+            return false;
+        }
+        offset += chunk.len();
+    }
+    true
+}
@@ -0,0 +1,141 @@
+use fs::FakeFs;
+use gpui::{SharedString, TestAppContext};
+use project::Project;
+use project::bookmark_store::*;
+use serde_json::json;
+use settings::SettingsStore;
+use std::{path::Path, sync::Arc};
+use text::Point;
+
+pub fn init_test(cx: &mut TestAppContext) {
+    zlog::init_test();
+
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+    });
+}
+
+fn bookmark_rows(
+    project: &gpui::Entity<Project>,
+    cx: &TestAppContext,
+) -> Vec<(String, u32, Option<String>)> {
+    project.read_with(cx, |project, cx| {
+        project
+            .bookmark_store()
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.abs_path.to_string_lossy().into_owned(),
+                    entry.row,
+                    entry.bookmark.name.map(|name| name.to_string()),
+                )
+            })
+            .collect()
+    })
+}
+
+#[gpui::test]
+async fn test_bookmarks_follow_edits(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "a.rs": "one\ntwo\nthree\nfour\n",
+            "b.rs": "five\nsix\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/root".as_ref()], cx).await;
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/root/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/root/b.rs", cx)
+        })
+        .await
+        .unwrap();
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+
+    bookmark_store.update(cx, |store, cx| {
+        let position = buffer_a.read(cx).anchor_after(Point::new(2, 0));
+        store.toggle_bookmark(buffer_a.clone(), position, cx);
+        let position = buffer_b.read(cx).anchor_after(Point::new(1, 0));
+        store.set_named_bookmark(buffer_b.clone(), position, SharedString::from("six"), cx);
+        let position = buffer_a.read(cx).anchor_after(Point::new(0, 0));
+        store.toggle_bookmark(buffer_a.clone(), position, cx);
+    });
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [
+            ("/root/a.rs".to_string(), 0, None),
+            ("/root/a.rs".to_string(), 2, None),
+            ("/root/b.rs".to_string(), 1, Some("six".to_string())),
+        ]
+    );
+
+    // Bookmarks stay on their lines as lines are added above them.
+    buffer_a.update(cx, |buffer, cx| {
+        buffer.edit(
+            [(Point::new(1, 0)..Point::new(1, 0), "inserted\n")],
+            None,
+            cx,
+        )
+    });
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [
+            ("/root/a.rs".to_string(), 0, None),
+            ("/root/a.rs".to_string(), 3, None),
+            ("/root/b.rs".to_string(), 1, Some("six".to_string())),
+        ]
+    );
+
+    // Naming a bookmark moves it, and toggling a line with a bookmark removes it.
+    bookmark_store.update(cx, |store, cx| {
+        let position = buffer_a.read(cx).anchor_after(Point::new(3, 2));
+        store.set_named_bookmark(buffer_a.clone(), position, SharedString::from("six"), cx);
+        let position = buffer_a.read(cx).anchor_after(Point::new(0, 1));
+        store.toggle_bookmark(buffer_a.clone(), position, cx);
+    });
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [("/root/a.rs".to_string(), 3, Some("six".to_string()))]
+    );
+    assert_eq!(
+        bookmark_store.read_with(cx, |store, cx| store
+            .bookmark_named("six", cx)
+            .map(|entry| entry.row)),
+        Some(3)
+    );
+
+    let serialized = bookmark_store.read_with(cx, |store, cx| store.all_serialized_bookmarks(cx));
+    assert_eq!(
+        serialized.get(Path::new("/root/a.rs")),
+        Some(&vec![SerializedBookmark {
+            row: 3,
+            name: Some(Arc::from("six")),
+        }])
+    );
+
+    bookmark_store.update(cx, |store, cx| store.clear_bookmarks(cx));
+    assert_eq!(bookmark_rows(&project, cx), []);
+
+    bookmark_store
+        .update(cx, |store, cx| {
+            store.with_serialized_bookmarks(serialized, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [("/root/a.rs".to_string(), 3, Some("six".to_string()))]
+    );
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use context_server::test::create_fake_transport;
+use context_server::oauth::{self, OAuthCredentials};
+use context_server::test::{FakeCredentialsProvider, create_fake_transport};
 use context_server::{ContextServer, ContextServerId};
 use gpui::{AppContext, AsyncApp, Entity, Subscription, Task, TestAppContext, UpdateGlobal as _};
 use http_client::{FakeHttpClient, Response};
@@ -15,6 +16,7 @@ use serde_json::json;
 use settings::settings_content::SaturatingBool;
 use settings::{ContextServerCommand, Settings, SettingsStore};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{cell::RefCell, path::PathBuf, rc::Rc};
 use util::path;
 
@@ -1076,3 +1078,112 @@ impl ContextServerDescriptor for FakeContextServerDescriptor {
         Task::ready(Ok(None))
     }
 }
+
+#[gpui::test]
+async fn test_context_server_requires_authorization_after_token_revoked(cx: &mut TestAppContext) {
+    let server_id = ContextServerId("oauth-server".into());
+    let endpoint = url::Url::parse("https://mcp.example.com/mcp").unwrap();
+
+    // Accepts the stored token until it gets revoked
+    let revoked = Arc::new(AtomicBool::new(false));
+    let client = FakeHttpClient::create({
+        let revoked = revoked.clone();
+        move |request| {
+            let authorized = !revoked.load(Ordering::SeqCst)
+                && request
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|value| value.to_str().ok())
+                    == Some("Bearer valid-token");
+            async move {
+                if !authorized {
+                    return Ok(Response::builder()
+                        .status(401)
+                        .body(http_client::AsyncBody::empty())
+                        .unwrap());
+                }
+                Ok(Response::builder()
+                    .status(200)
+                    .header("Content-Type", "application/json")
+                    .body(http_client::AsyncBody::from(
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": 0,
+                            "result": {
+                                "protocolVersion": "2024-11-05",
+                                "capabilities": {},
+                                "serverInfo": { "name": "test-server", "version": "1.0.0" }
+                            }
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap())
+            }
+        }
+    });
+    cx.update(|cx| cx.set_http_client(client));
+
+    let (_fs, project) = setup_context_server_test(cx, json!({"code.rs": ""}), vec![]).await;
+
+    let credentials_provider = Arc::new(FakeCredentialsProvider::default());
+    oauth::write_credentials(
+        credentials_provider.as_ref(),
+        &endpoint,
+        &OAuthCredentials {
+            client_id: "zed".into(),
+            client_secret: None,
+            token_endpoint: "https://auth.example.com/token".into(),
+            resource: endpoint.to_string(),
+            access_token: "valid-token".into(),
+            refresh_token: None,
+            expires_at: None,
+        },
+        &cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+    let store = cx.new(|cx| {
+        let mut store = ContextServerStore::test(
+            registry.clone(),
+            project.read(cx).worktree_store(),
+            Some(project.downgrade()),
+            cx,
+        );
+        store.set_credentials_provider(credentials_provider.clone());
+        store
+    });
+
+    let (server, _) = ContextServerStore::create_context_server(
+        store.downgrade(),
+        server_id.clone(),
+        Arc::new(ContextServerConfiguration::Http {
+            url: endpoint.clone(),
+            headers: Default::default(),
+            timeout: None,
+        }),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    store.update(cx, |store, cx| store.test_start_server(server.clone(), cx));
+    cx.run_until_parked();
+    assert_eq!(
+        store.read_with(cx, |store, _| store.status_for_server(&server_id)),
+        Some(ContextServerStatus::Running)
+    );
+
+    revoked.store(true, Ordering::SeqCst);
+    let response = server
+        .client()
+        .unwrap()
+        .request::<context_server::types::requests::Ping>(())
+        .await;
+    assert!(response.is_err());
+    cx.run_until_parked();
+    assert_eq!(
+        store.read_with(cx, |store, _| store.status_for_server(&server_id)),
+        Some(ContextServerStatus::AuthRequired)
+    );
+}
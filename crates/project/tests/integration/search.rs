@@ -154,3 +154,111 @@ async fn test_multiline_regex(cx: &mut gpui::TestAppContext) {
     let results = search_query.search(&snapshot, None).await;
     assert_eq!(results, vec![0..6, 12..18]);
 }
+
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    use language::{Buffer, rust_lang};
+
+    let text = r#"fn main() {
+    let a = foo().unwrap();
+    let b = bar(1, 2).unwrap();
+    let c = a.unwrap_or(0);
+    // foo.unwrap()
+    let d = "foo.unwrap()";
+    if a == a && a == b {}
+}
+"#;
+    let snapshot = cx
+        .update(|app| Buffer::build_snapshot(Rope::from(text), Some(rust_lang()), None, app))
+        .await;
+    let range_of = |needle: &str| {
+        let start = text.find(needle).unwrap();
+        start..start + needle.len()
+    };
+
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("$X?".to_string());
+    let results = query.search(&snapshot, None).await;
+    assert_eq!(
+        results,
+        vec![range_of("foo().unwrap()"), range_of("bar(1, 2).unwrap()")],
+        "Matches should be syntax nodes, leaving out comments, strings and other methods"
+    );
+    assert_eq!(
+        query
+            .structural_replacement_for(&snapshot, results[1].clone())
+            .as_deref(),
+        Some("bar(1, 2)?")
+    );
+
+    let query = SearchQuery::structural(
+        "bar($$$ARGS)",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("baz($$$ARGS, 3)".to_string());
+    let results = query.search(&snapshot, None).await;
+    assert_eq!(results, vec![range_of("bar(1, 2)")]);
+    assert_eq!(
+        query
+            .structural_replacement_for(&snapshot, results[0].clone())
+            .as_deref(),
+        Some("baz(1, 2, 3)")
+    );
+
+    let query = SearchQuery::structural(
+        "$A == $A",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        query.search(&snapshot, None).await,
+        vec![range_of("a == a")],
+        "A repeated metavariable should only match the same text"
+    );
+
+    let subrange = range_of("let b = bar(1, 2).unwrap();");
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        query.search(&snapshot, Some(subrange)).await,
+        vec![8..26],
+        "Matches in a subrange should be relative to its start"
+    );
+
+    assert!(
+        SearchQuery::structural(
+            "$X",
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None
+        )
+        .is_err(),
+        "A pattern that's only a metavariable should be rejected"
+    );
+}
@@ -0,0 +1,326 @@
+use collections::{HashMap, HashSet};
+use project::{GitEntry, ProjectEntryId};
+use util::rel_path::RelPath;
+
+use crate::NEW_ENTRY_ID;
+
+/// Rules for nesting related files, such as lock files and build outputs,
+/// under a parent file in the same directory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileNestingRules {
+    rules: Vec<NestingRule>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct NestingRule {
+    parent_prefix: String,
+    /// The part of the parent pattern after its `*`, if it has one.
+    parent_suffix: Option<String>,
+    children: Vec<String>,
+}
+
+impl NestingRule {
+    /// Returns the part of `name` matched by the `*` of the parent pattern,
+    /// if `name` matches it.
+    fn capture<'a>(&self, name: &'a str) -> Option<&'a str> {
+        match &self.parent_suffix {
+            None => (name == self.parent_prefix).then_some(""),
+            Some(suffix) => {
+                let matches = name.len() >= self.parent_prefix.len() + suffix.len()
+                    && name.starts_with(&self.parent_prefix)
+                    && name.ends_with(suffix.as_str());
+                matches.then(|| &name[self.parent_prefix.len()..name.len() - suffix.len()])
+            }
+        }
+    }
+}
+
+impl FileNestingRules {
+    /// Compiles the rules from pairs of a parent pattern and a
+    /// comma-separated list of child patterns. Parent patterns with more
+    /// than one `*` and empty lists of children are ignored.
+    pub fn new<'a>(patterns: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let rules = patterns
+            .into_iter()
+            .filter_map(|(parent, children)| {
+                let (parent_prefix, parent_suffix) = match parent.split_once('*') {
+                    Some((_, suffix)) if suffix.contains('*') => return None,
+                    Some((prefix, suffix)) => (prefix, Some(suffix.to_string())),
+                    None => (parent, None),
+                };
+                let children = children
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|child| !child.is_empty())
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                (!children.is_empty()).then(|| NestingRule {
+                    parent_prefix: parent_prefix.to_string(),
+                    parent_suffix,
+                    children,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Given the names of sibling files, returns the index of the file each
+    /// nested file appears under. Files nested under a file that is nested
+    /// itself appear under the outermost one.
+    fn nesting_parents(&self, names: &[&str]) -> HashMap<usize, usize> {
+        let name_ixs = names
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| (*name, ix))
+            .collect::<HashMap<_, _>>();
+        let mut parents = HashMap::default();
+        for (parent_ix, &parent_name) in names.iter().enumerate() {
+            let (basename, extname) = parent_name.rsplit_once('.').unwrap_or((parent_name, ""));
+            for rule in &self.rules {
+                let Some(capture) = rule.capture(parent_name) else {
+                    continue;
+                };
+                for child in &rule.children {
+                    let child = child
+                        .replace("$(capture)", capture)
+                        .replace("$(basename)", basename)
+                        .replace("$(extname)", extname);
+                    if child.contains('*') {
+                        for (child_ix, name) in names.iter().enumerate() {
+                            if child_ix != parent_ix && wildcard_match(&child, name) {
+                                parents.entry(child_ix).or_insert(parent_ix);
+                            }
+                        }
+                    } else if let Some(&child_ix) = name_ixs.get(child.as_str())
+                        && child_ix != parent_ix
+                    {
+                        parents.entry(child_ix).or_insert(parent_ix);
+                    }
+                }
+            }
+        }
+
+        // Files nesting each other, as with `*.js` → `$(capture).ts` and
+        // `*.ts` → `$(capture).js`, would never be shown, so break such
+        // cycles.
+        let mut children = parents.keys().copied().collect::<Vec<_>>();
+        children.sort_unstable();
+        for child in children {
+            let mut visited = HashSet::default();
+            let mut current = child;
+            while let Some(&parent) = parents.get(&current) {
+                if !visited.insert(current) {
+                    parents.remove(&current);
+                    break;
+                }
+                current = parent;
+            }
+        }
+
+        parents
+            .keys()
+            .map(|&child| {
+                let mut outermost = parents[&child];
+                while let Some(&parent) = parents.get(&outermost) {
+                    outermost = parent;
+                }
+                (child, outermost)
+            })
+            .collect()
+    }
+}
+
+/// Moves the nested files among the sorted `entries` of a worktree after
+/// their parent file, leaving out those of collapsed parents, and records
+/// which files are nested under which.
+pub(crate) fn nest_entries(
+    entries: &mut Vec<GitEntry>,
+    rules: &FileNestingRules,
+    expanded_entry_ids: &[ProjectEntryId],
+    nested_entries: &mut HashMap<ProjectEntryId, Vec<ProjectEntryId>>,
+    nested_entry_parents: &mut HashMap<ProjectEntryId, ProjectEntryId>,
+) {
+    let mut siblings = HashMap::<&RelPath, Vec<usize>>::default();
+    for (ix, entry) in entries.iter().enumerate() {
+        if entry.is_file()
+            && entry.id != NEW_ENTRY_ID
+            && let Some(parent_path) = entry.path.parent()
+        {
+            siblings.entry(parent_path).or_default().push(ix);
+        }
+    }
+
+    let mut parent_ixs = HashMap::default();
+    let mut child_ixs = HashMap::<usize, Vec<usize>>::default();
+    for ixs in siblings.values() {
+        let names = ixs
+            .iter()
+            .map(|&ix| entries[ix].path.file_name().unwrap_or_default())
+            .collect::<Vec<_>>();
+        for (child, parent) in rules.nesting_parents(&names) {
+            parent_ixs.insert(ixs[child], ixs[parent]);
+            child_ixs.entry(ixs[parent]).or_default().push(ixs[child]);
+        }
+    }
+    if parent_ixs.is_empty() {
+        return;
+    }
+
+    let mut old_entries = std::mem::take(entries)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    for ix in 0..old_entries.len() {
+        if parent_ixs.contains_key(&ix) {
+            continue;
+        }
+        let Some(entry) = old_entries[ix].take() else {
+            continue;
+        };
+        let entry_id = entry.id;
+        entries.push(entry);
+        let Some(children) = child_ixs.get_mut(&ix) else {
+            continue;
+        };
+        children.sort_unstable();
+        let is_expanded = expanded_entry_ids.binary_search(&entry_id).is_ok();
+        for &child_ix in children.iter() {
+            if let Some(child) = old_entries[child_ix].take() {
+                nested_entries.entry(entry_id).or_default().push(child.id);
+                nested_entry_parents.insert(child.id, entry_id);
+                if is_expanded {
+                    entries.push(child);
+                }
+            }
+        }
+    }
+}
+
+/// Matches `name` against `pattern`, in which each `*` matches any sequence
+/// of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let Some((first, rest)) = parts.split_first() else {
+        return false;
+    };
+    let Some((last, middle)) = rest.split_last() else {
+        return pattern == name;
+    };
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut remaining = &name[first.len()..name.len() - last.len()];
+    for part in middle {
+        let Some(ix) = remaining.find(part) else {
+            return false;
+        };
+        remaining = &remaining[ix + part.len()..];
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nesting(
+        patterns: &[(&str, &str)],
+        names: &[&'static str],
+    ) -> Vec<(&'static str, &'static str)> {
+        let rules = FileNestingRules::new(patterns.iter().copied());
+        let mut nesting = rules
+            .nesting_parents(names)
+            .into_iter()
+            .map(|(child, parent)| (names[child], names[parent]))
+            .collect::<Vec<_>>();
+        nesting.sort_unstable();
+        nesting
+    }
+
+    #[test]
+    fn test_nesting_parents() {
+        assert_eq!(
+            nesting(
+                &[
+                    ("Cargo.toml", "Cargo.lock"),
+                    ("*.ts", "$(capture).js, $(capture).d.ts"),
+                ],
+                &[
+                    "Cargo.lock",
+                    "Cargo.toml",
+                    "main.d.ts",
+                    "main.js",
+                    "main.ts",
+                    "other.js"
+                ],
+            ),
+            [
+                ("Cargo.lock", "Cargo.toml"),
+                ("main.d.ts", "main.ts"),
+                ("main.js", "main.ts"),
+            ]
+        );
+        assert_eq!(
+            nesting(
+                &[
+                    ("Dockerfile", "*.dockerfile, compose.*"),
+                    ("*.txt", "$(basename).$(extname).bak"),
+                ],
+                &[
+                    "Dockerfile",
+                    "a.dockerfile",
+                    "compose.yaml",
+                    "notes.txt",
+                    "notes.txt.bak"
+                ],
+            ),
+            [
+                ("a.dockerfile", "Dockerfile"),
+                ("compose.yaml", "Dockerfile"),
+                ("notes.txt.bak", "notes.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_chains_and_cycles() {
+        // `a.js.map` is nested under `a.js`, which is nested under `a.ts`.
+        assert_eq!(
+            nesting(
+                &[("*.ts", "$(capture).js"), ("*.js", "$(capture).js.map")],
+                &["a.js", "a.js.map", "a.ts"],
+            ),
+            [("a.js", "a.ts"), ("a.js.map", "a.ts")]
+        );
+        assert_eq!(
+            nesting(
+                &[("*.ts", "$(capture).js"), ("*.js", "$(capture).ts")],
+                &["a.js", "a.ts"],
+            )
+            .len(),
+            1
+        );
+        assert_eq!(nesting(&[("*", "*")], &["a", "b"]).len(), 1);
+    }
+
+    #[test]
+    fn test_ignored_patterns() {
+        let rules = FileNestingRules::new([("*.*.ts", "$(capture).js"), ("Cargo.toml", "")]);
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("requirements*.txt", "requirements.txt"));
+        assert!(wildcard_match("requirements*.txt", "requirements-dev.txt"));
+        assert!(wildcard_match("*.a*b", "x.ab"));
+        assert!(!wildcard_match("*.a*b", "x.ba"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+        assert!(wildcard_match("exact", "exact"));
+        assert!(!wildcard_match("exact", "exactly"));
+    }
+}
@@ -1,3 +1,4 @@
+mod file_nesting;
 pub mod project_panel_settings;
 mod utils;
 
@@ -37,7 +38,7 @@ use project::{
     git_store::{GitStoreEvent, RepositoryEvent, git_traversal::ChildEntriesGitIter},
     project_settings::GoToDiagnosticSeverityFilter,
 };
-use project_panel_settings::ProjectPanelSettings;
+use project_panel_settings::{FileNestingSettings, ProjectPanelSettings};
 use rayon::slice::ParallelSliceMut;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -59,7 +60,7 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
-    Color, ContextMenu, ContextMenuEntry, DecoratedIcon, Divider, Icon, IconDecoration,
+    Color, ContextMenu, ContextMenuEntry, DecoratedIcon, Disclosure, Divider, Icon, IconDecoration,
     IconDecorationKind, IndentGuideColors, IndentGuideLayout, KeyBinding, Label, LabelSize,
     ListItem, ListItemSpacing, ScrollAxes, ScrollableHandle, Scrollbars, StickyCandidate, Tooltip,
     WithScrollbar, prelude::*, v_flex,
@@ -101,7 +102,14 @@ struct State {
     edit_state: Option<EditState>,
     temporarily_unfolded_pending_state: Option<TemporaryUnfoldedPendingState>,
     unfolded_dir_ids: HashSet<ProjectEntryId>,
+    /// The expanded directories of each worktree, along with the expanded
+    /// files that have nested files.
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
+    /// Maps from the files that have files nested under them, when file
+    /// nesting is enabled, to the nested files.
+    nested_entries: HashMap<ProjectEntryId, Vec<ProjectEntryId>>,
+    /// Maps from nested files to the file they're nested under.
+    nested_entry_parents: HashMap<ProjectEntryId, ProjectEntryId>,
 }
 
 impl State {
@@ -122,6 +130,8 @@ impl State {
             temporarily_unfolded_pending_state: None,
             unfolded_dir_ids: old.unfolded_dir_ids.clone(),
             expanded_dir_ids: old.expanded_dir_ids.clone(),
+            nested_entries: Default::default(),
+            nested_entry_parents: Default::default(),
         }
     }
 }
@@ -280,6 +290,7 @@ struct EntryDetails {
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Arc<Path>>,
+    has_nested_entries: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -830,6 +841,7 @@ impl ProjectPanel {
             .detach();
 
             let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
+            let mut file_nesting_settings = FileNestingSettings::get_global(cx).clone();
             cx.observe_global_in::<SettingsStore>(window, move |this, window, cx| {
                 let new_settings = *ProjectPanelSettings::get_global(cx);
                 if project_panel_settings != new_settings {
@@ -852,6 +864,11 @@ impl ProjectPanel {
                     this.update_diagnostics(cx);
                     cx.notify();
                 }
+                let new_file_nesting_settings = FileNestingSettings::get_global(cx);
+                if &file_nesting_settings != new_file_nesting_settings {
+                    file_nesting_settings = new_file_nesting_settings.clone();
+                    this.update_visible_entries(None, false, false, window, cx);
+                }
             })
             .detach();
 
@@ -1387,6 +1404,16 @@ impl ProjectPanel {
                         cx.notify();
                     }
                 }
+            } else if self.state.nested_entries.contains_key(&entry.id) {
+                let worktree_id = worktree.id();
+                let entry_id = entry.id;
+                if let Some(expanded_dir_ids) = self.state.expanded_dir_ids.get_mut(&worktree_id)
+                    && let Err(ix) = expanded_dir_ids.binary_search(&entry_id)
+                {
+                    expanded_dir_ids.insert(ix, entry_id);
+                    self.update_visible_entries(None, false, false, window, cx);
+                    cx.notify();
+                }
             }
         }
     }
@@ -1426,7 +1453,12 @@ impl ProjectPanel {
                 return;
             };
 
-        let mut entry = &entry;
+        let mut entry = self
+            .state
+            .nested_entry_parents
+            .get(&entry.id)
+            .and_then(|parent_id| worktree.entry_for_id(*parent_id))
+            .unwrap_or(&entry);
         loop {
             let entry_id = entry.id;
             match expanded_dir_ids.binary_search(&entry_id) {
@@ -1565,6 +1597,29 @@ impl ProjectPanel {
         }
     }
 
+    /// Shows or hides the files nested under a file.
+    fn toggle_nested_entries(
+        &mut self,
+        entry_id: ProjectEntryId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(worktree_id) = self.project.read(cx).worktree_id_for_entry(entry_id, cx)
+            && let Some(expanded_dir_ids) = self.state.expanded_dir_ids.get_mut(&worktree_id)
+        {
+            match expanded_dir_ids.binary_search(&entry_id) {
+                Ok(ix) => {
+                    expanded_dir_ids.remove(ix);
+                }
+                Err(ix) => {
+                    expanded_dir_ids.insert(ix, entry_id);
+                }
+            }
+            self.update_visible_entries(Some((worktree_id, entry_id)), false, false, window, cx);
+            cx.notify();
+        }
+    }
+
     fn toggle_expand_all(
         &mut self,
         entry_id: ProjectEntryId,
@@ -3876,6 +3931,9 @@ impl ProjectPanel {
             .collect();
         let hide_root = settings.hide_root && visible_worktrees.len() == 1;
         let hide_hidden = settings.hide_hidden;
+        let file_nesting_settings = FileNestingSettings::get_global(cx);
+        let file_nesting_rules = Some(file_nesting_settings.rules.clone())
+            .filter(|rules| file_nesting_settings.enabled && !rules.is_empty());
 
         let visible_entries_task = cx.spawn_in(window, async move |this, cx| {
             let new_state = cx
@@ -4092,6 +4150,18 @@ impl ProjectPanel {
                             &mut visible_worktree_entries,
                             sort_mode,
                         );
+                        if let Some(rules) = &file_nesting_rules {
+                            file_nesting::nest_entries(
+                                &mut visible_worktree_entries,
+                                rules,
+                                new_state
+                                    .expanded_dir_ids
+                                    .get(&worktree_id)
+                                    .map_or(&[], Vec::as_slice),
+                                &mut new_state.nested_entries,
+                                &mut new_state.nested_entry_parents,
+                            );
+                        }
                         new_state.visible_entries.push(VisibleEntriesForWorktree {
                             worktree_id,
                             entries: visible_worktree_entries,
@@ -4171,6 +4241,7 @@ impl ProjectPanel {
         entry_id: ProjectEntryId,
         cx: &mut Context<Self>,
     ) {
+        let nesting_parent_id = self.state.nested_entry_parents.get(&entry_id).copied();
         self.project.update(cx, |project, cx| {
             if let Some((worktree, expanded_dir_ids)) = project
                 .worktree_for_id(worktree_id, cx)
@@ -4179,9 +4250,18 @@ impl ProjectPanel {
                 project.expand_entry(worktree_id, entry_id, cx);
                 let worktree = worktree.read(cx);
 
+                if let Some(parent_id) = nesting_parent_id
+                    && let Err(ix) = expanded_dir_ids.binary_search(&parent_id)
+                {
+                    expanded_dir_ids.insert(ix, parent_id);
+                }
                 if let Some(mut entry) = worktree.entry_for_id(entry_id) {
                     loop {
-                        if let Err(ix) = expanded_dir_ids.binary_search(&entry.id) {
+                        // Files are only expanded to show the files nested
+                        // under them, which revealing them shouldn't do.
+                        if entry.is_dir()
+                            && let Err(ix) = expanded_dir_ids.binary_search(&entry.id)
+                        {
                             expanded_dir_ids.insert(ix, entry.id);
                         }
 
@@ -4990,6 +5070,21 @@ impl ProjectPanel {
         (depth, difference)
     }
 
+    /// Returns the depth at which `entry` is shown, which is one level below
+    /// the file it's nested under, if any.
+    fn visible_depth(
+        &self,
+        entry: &Entry,
+        visible_worktree_entries: &HashSet<Arc<RelPath>>,
+    ) -> usize {
+        let (depth, _) = Self::calculate_depth_and_difference(entry, visible_worktree_entries);
+        if self.state.nested_entry_parents.contains_key(&entry.id) {
+            depth + 1
+        } else {
+            depth
+        }
+    }
+
     fn highlight_entry_for_external_drag(
         &self,
         target_entry: &Entry,
@@ -5137,6 +5232,8 @@ impl ProjectPanel {
         let path_for_dragged_selection = path.clone();
 
         let depth = details.depth;
+        let is_expanded = details.is_expanded;
+        let has_nested_entries = details.has_nested_entries;
         let worktree_id = details.worktree_id;
         let dragged_selection = DraggedSelection {
             active_selection: SelectedEntry {
@@ -5697,6 +5794,20 @@ impl ProjectPanel {
                                         .into_any_element(),
                                 ),
                             })
+                            .when(has_nested_entries, |this| {
+                                this.child(
+                                    Disclosure::new(
+                                        ("nested-entries", entry_id.to_proto()),
+                                        is_expanded,
+                                    )
+                                    .on_click(cx.listener(
+                                        move |this, _, window, cx| {
+                                            cx.stop_propagation();
+                                            this.toggle_nested_entries(entry_id, window, cx);
+                                        },
+                                    )),
+                                )
+                            })
                     })
                     .on_secondary_mouse_down(cx.listener(
                         move |this, event: &MouseDownEvent, window, cx| {
@@ -5991,8 +6102,11 @@ impl ProjectPanel {
         };
 
         let path_style = self.project.read(cx).path_style(cx);
-        let (depth, difference) =
+        let (mut depth, difference) =
             ProjectPanel::calculate_depth_and_difference(entry, entries_paths);
+        if self.state.nested_entry_parents.contains_key(&entry.id) {
+            depth += 1;
+        }
 
         let filename = if difference > 1 {
             entry
@@ -6055,6 +6169,7 @@ impl ProjectPanel {
             is_private: entry.is_private,
             worktree_id,
             canonical_path: entry.canonical_path.clone(),
+            has_nested_entries: self.state.nested_entries.contains_key(&entry.id),
         }
     }
 
@@ -6538,11 +6653,7 @@ impl Render for ProjectPanel {
                                                 window,
                                                 cx,
                                                 &mut |entry, _, entries, _, _| {
-                                                    let (depth, _) =
-                                                        Self::calculate_depth_and_difference(
-                                                            entry, entries,
-                                                        );
-                                                    items.push(depth);
+                                                    items.push(this.visible_depth(entry, entries));
                                                 },
                                             );
                                             items
@@ -6651,10 +6762,7 @@ impl Render for ProjectPanel {
                                             window,
                                             cx,
                                             &mut |entry, index, entries, _, _| {
-                                                let (depth, _) =
-                                                    Self::calculate_depth_and_difference(
-                                                        entry, entries,
-                                                    );
+                                                let depth = this.visible_depth(entry, entries);
                                                 let candidate =
                                                     StickyProjectPanelCandidate { index, depth };
                                                 items.push(candidate);
@@ -1,3 +1,4 @@
+use crate::file_nesting::FileNestingRules;
 use editor::EditorSettings;
 use gpui::Pixels;
 use schemars::JsonSchema;
@@ -6,6 +7,7 @@ use settings::{
     DockSide, ProjectPanelEntrySpacing, ProjectPanelSortMode, RegisterSetting, Settings,
     ShowDiagnostics, ShowIndentGuides,
 };
+use std::sync::Arc;
 use ui::{
     px,
     scrollbars::{ScrollbarVisibility, ShowScrollbar},
@@ -38,6 +40,13 @@ pub struct ProjectPanelSettings {
     pub diagnostic_badges: bool,
 }
 
+/// Kept apart from [`ProjectPanelSettings`], which is `Copy`.
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct FileNestingSettings {
+    pub enabled: bool,
+    pub rules: Arc<FileNestingRules>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct IndentGuidesSettings {
     pub show: ShowIndentGuides,
@@ -140,3 +149,25 @@ impl Settings for ProjectPanelSettings {
         }
     }
 }
+
+impl Settings for FileNestingSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let file_nesting = content
+            .project_panel
+            .as_ref()
+            .unwrap()
+            .file_nesting
+            .as_ref()
+            .unwrap();
+        Self {
+            enabled: file_nesting.enabled.unwrap(),
+            rules: Arc::new(FileNestingRules::new(
+                file_nesting
+                    .patterns
+                    .iter()
+                    .flatten()
+                    .map(|(parent, children)| (parent.as_str(), children.as_str())),
+            )),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::file_nesting::FileNestingRules;
 use collections::HashSet;
 use editor::MultiBufferOffset;
 use gpui::{Empty, Entity, TestAppContext, VisualTestContext};
@@ -8858,6 +8859,147 @@ async fn test_hide_hidden_entries(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "Cargo.lock": "",
+            "Cargo.toml": "",
+            "src": {
+                "index.js": "",
+                "index.ts": "",
+                "other.js": "",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+    let window = cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+    let workspace = window
+        .read_with(cx, |mw, _| mw.workspace().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(window.into(), cx);
+
+    cx.update(|_, cx| {
+        FileNestingSettings::override_global(
+            FileNestingSettings {
+                enabled: true,
+                rules: Arc::new(FileNestingRules::new([
+                    ("Cargo.toml", "Cargo.lock"),
+                    ("*.ts", "$(capture).js"),
+                ])),
+            },
+            cx,
+        );
+    });
+
+    let panel = workspace.update_in(cx, ProjectPanel::new);
+    cx.run_until_parked();
+
+    toggle_expand_dir(&panel, "root/src", cx);
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src  <== selected",
+            "        > index.ts",
+            "          other.js",
+            "    > Cargo.toml",
+        ]
+    );
+
+    select_path(&panel, "root/src/index.ts", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.expand_selected_entry(&Default::default(), window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "        v index.ts  <== selected",
+            "              index.js",
+            "          other.js",
+            "    > Cargo.toml",
+        ]
+    );
+
+    select_path(&panel, "root/src/index.js", cx);
+    panel.update_in(cx, |panel, window, cx| {
+        panel.collapse_selected_entry(&Default::default(), window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "        > index.ts  <== selected",
+            "          other.js",
+            "    > Cargo.toml",
+        ]
+    );
+
+    // Revealing a nested file expands the file it's nested under.
+    let entry_id = find_project_entry(&panel, "root/Cargo.lock", cx).unwrap();
+    panel.update_in(cx, |panel, window, cx| {
+        let worktree_id = panel
+            .project
+            .read(cx)
+            .worktrees(cx)
+            .next()
+            .unwrap()
+            .read(cx)
+            .id();
+        panel.expand_entry(worktree_id, entry_id, cx);
+        panel.update_visible_entries(None, false, false, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "        > index.ts  <== selected",
+            "          other.js",
+            "    v Cargo.toml",
+            "          Cargo.lock",
+        ]
+    );
+
+    cx.update(|_, cx| {
+        FileNestingSettings::override_global(
+            FileNestingSettings {
+                enabled: false,
+                ..FileNestingSettings::get_global(cx).clone()
+            },
+            cx,
+        );
+    });
+    panel.update_in(cx, |panel, window, cx| {
+        panel.update_visible_entries(None, false, false, window, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        visible_entries_as_strings(&panel, 0..10, cx),
+        &[
+            "v root",
+            "    v src",
+            "          index.js",
+            "          index.ts  <== selected",
+            "          other.js",
+            "      Cargo.lock",
+            "      Cargo.toml",
+        ]
+    );
+}
+
 fn select_path(panel: &Entity<ProjectPanel>, path: &str, cx: &mut VisualTestContext) {
     let path = rel_path(path);
     panel.update_in(cx, |panel, window, cx| {
@@ -9034,7 +9176,7 @@ fn visible_entries_as_strings(
             }
 
             let indent = "    ".repeat(details.depth);
-            let icon = if details.kind.is_dir() {
+            let icon = if details.kind.is_dir() || details.has_nested_entries {
                 if details.is_expanded { "v " } else { "> " }
             } else {
                 "  "
@@ -1,7 +1,8 @@
 use crate::{
     BufferSearchBar, FocusSearch, HighlightKey, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleSemantic,
+    ToggleStructural, ToggleWholeWord,
     buffer_search::Deploy,
     search_bar::{
         ActionButtonState, alignment_element, input_base_styles, render_action_button,
@@ -25,13 +26,14 @@ use gpui::{
     SharedString, Styled, Subscription, Task, UpdateGlobal, WeakEntity, Window, actions, div,
 };
 use itertools::Itertools;
-use language::{Buffer, Language};
+use language::{Bias, Buffer, Language};
 use menu::Confirm;
 use project::{
     Project, ProjectPath, SearchResults,
     search::{SearchInputKind, SearchQuery},
     search_history::SearchHistoryCursor,
 };
+use semantic_index::{ProjectIndex, SemanticDb};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -68,6 +70,9 @@ actions!(
     ]
 );
 
+/// The number of chunks of code shown by a semantic search.
+const SEMANTIC_SEARCH_LIMIT: usize = 50;
+
 fn split_glob_patterns(text: &str) -> Vec<&str> {
     let mut patterns = Vec::new();
     let mut pattern_start = 0;
@@ -129,6 +134,18 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleSemantic, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::SEMANTIC, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -333,23 +350,27 @@ impl ProjectSearch {
         }
     }
 
+    fn add_to_history(&mut self, query: &SearchQuery, project: &mut Project) {
+        project
+            .search_history_mut(SearchInputKind::Query)
+            .add(&mut self.search_history_cursor, query.as_str().to_string());
+        let included = query.as_inner().files_to_include().sources().join(",");
+        if !included.is_empty() {
+            project
+                .search_history_mut(SearchInputKind::Include)
+                .add(&mut self.search_included_history_cursor, included);
+        }
+        let excluded = query.as_inner().files_to_exclude().sources().join(",");
+        if !excluded.is_empty() {
+            project
+                .search_history_mut(SearchInputKind::Exclude)
+                .add(&mut self.search_excluded_history_cursor, excluded);
+        }
+    }
+
     fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) {
         let search = self.project.update(cx, |project, cx| {
-            project
-                .search_history_mut(SearchInputKind::Query)
-                .add(&mut self.search_history_cursor, query.as_str().to_string());
-            let included = query.as_inner().files_to_include().sources().join(",");
-            if !included.is_empty() {
-                project
-                    .search_history_mut(SearchInputKind::Include)
-                    .add(&mut self.search_included_history_cursor, included);
-            }
-            let excluded = query.as_inner().files_to_exclude().sources().join(",");
-            if !excluded.is_empty() {
-                project
-                    .search_history_mut(SearchInputKind::Exclude)
-                    .add(&mut self.search_excluded_history_cursor, excluded);
-            }
+            self.add_to_history(&query, project);
             project.search(query.clone(), cx)
         });
         self.last_search_query_text = Some(query.as_str().to_string());
@@ -438,6 +459,132 @@ impl ProjectSearch {
         }));
         cx.notify();
     }
+
+    /// Searches the project's semantic index for the code most related to the
+    /// query's text, showing the files in order of their best match.
+    fn semantic_search(
+        &mut self,
+        query: SearchQuery,
+        project_index: Entity<ProjectIndex>,
+        cx: &mut Context<Self>,
+    ) {
+        self.project
+            .update(cx, |project, _| self.add_to_history(&query, project));
+        let search =
+            project_index
+                .read(cx)
+                .search(query.as_str().to_string(), SEMANTIC_SEARCH_LIMIT, cx);
+        self.last_search_query_text = Some(query.as_str().to_string());
+        self.search_id += 1;
+        self.active_query = Some(query.clone());
+        self.match_ranges.clear();
+        self.pending_search = Some(cx.spawn(async move |project_search, cx| {
+            let results = search.await.log_err().unwrap_or_default();
+            let project = project_search
+                .update(cx, |project_search, cx| {
+                    project_search.match_ranges.clear();
+                    project_search
+                        .excerpts
+                        .update(cx, |excerpts, cx| excerpts.clear(cx));
+                    project_search.no_results = Some(true);
+                    project_search.limit_reached = false;
+                    project_search.project.clone()
+                })
+                .ok()?;
+
+            // Results are ordered by score, and several of them may be in the
+            // same file, so they're grouped by file in order of each file's
+            // best match.
+            let mut results_by_file: Vec<(ProjectPath, Vec<Range<usize>>)> = Vec::new();
+            for result in results {
+                let project_path = ProjectPath {
+                    worktree_id: result.worktree_id,
+                    path: result.path,
+                };
+                let included = project.read_with(cx, |project, cx| {
+                    if !query.filters_path() {
+                        return true;
+                    }
+                    let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+                    else {
+                        return false;
+                    };
+                    if query.match_full_paths() {
+                        let mut full_path = worktree.read(cx).root_name().to_owned();
+                        full_path.push(&project_path.path);
+                        query.match_path(&full_path)
+                    } else {
+                        query.match_path(&project_path.path)
+                    }
+                });
+                if !included {
+                    continue;
+                }
+                match results_by_file
+                    .iter_mut()
+                    .find(|(path, _)| *path == project_path)
+                {
+                    Some((_, ranges)) => ranges.push(result.range),
+                    None => results_by_file.push((project_path, vec![result.range])),
+                }
+            }
+
+            for (rank, (project_path, ranges)) in results_by_file.into_iter().enumerate() {
+                let open_buffer = project.update(cx, |project, cx| {
+                    project.open_buffer(project_path.clone(), cx)
+                });
+                let Some(buffer) = open_buffer.await.log_err() else {
+                    continue;
+                };
+                let new_ranges = project_search
+                    .update(cx, |project_search, cx| {
+                        let snapshot = buffer.read(cx).snapshot();
+                        // The file may have changed since it was indexed.
+                        let mut ranges = ranges
+                            .into_iter()
+                            .map(|range| {
+                                let start = snapshot
+                                    .clip_offset(range.start.min(snapshot.len()), Bias::Left);
+                                let end =
+                                    snapshot.clip_offset(range.end.min(snapshot.len()), Bias::Left);
+                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                            })
+                            .collect::<Vec<_>>();
+                        ranges.sort_by(|a, b| a.start.cmp(&b.start, &snapshot));
+                        project_search.excerpts.update(cx, |excerpts, cx| {
+                            excerpts.set_anchored_excerpts_for_path(
+                                PathKey::with_sort_prefix(rank as u64, project_path.path),
+                                buffer,
+                                ranges,
+                                multibuffer_context_lines(cx),
+                                cx,
+                            )
+                        })
+                    })
+                    .ok()?;
+                let new_ranges = new_ranges.await;
+                project_search
+                    .update(cx, |project_search, cx| {
+                        project_search.match_ranges.extend(new_ranges);
+                        cx.notify();
+                    })
+                    .ok()?;
+            }
+
+            project_search
+                .update(cx, |project_search, cx| {
+                    if !project_search.match_ranges.is_empty() {
+                        project_search.no_results = Some(false);
+                    }
+                    project_search.pending_search.take();
+                    cx.notify();
+                })
+                .ok()?;
+
+            None
+        }));
+        cx.notify();
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -716,6 +863,12 @@ impl ProjectSearchView {
     }
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
+        // Regex, structural and semantic searches are separate modes, so
+        // enabling one of them disables the others.
+        let modes = SearchOptions::REGEX | SearchOptions::STRUCTURAL | SearchOptions::SEMANTIC;
+        if modes.contains(option) && !self.search_options.contains(option) {
+            self.search_options.remove(modes);
+        }
         self.search_options.toggle(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
@@ -735,6 +888,11 @@ impl ProjectSearchView {
     }
 
     fn replace_next(&mut self, _: &ReplaceNext, window: &mut Window, cx: &mut Context<Self>) {
+        // Semantic matches are related code rather than occurrences of the
+        // query, so there's nothing to replace.
+        if self.search_options.contains(SearchOptions::SEMANTIC) {
+            return;
+        }
         if let Some(last_search_query_text) = &self.entity.read(cx).last_search_query_text
             && self.query_editor.read(cx).text(cx) != *last_search_query_text
         {
@@ -763,6 +921,9 @@ impl ProjectSearchView {
         }
     }
     fn replace_all(&mut self, _: &ReplaceAll, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search_options.contains(SearchOptions::SEMANTIC) {
+            return;
+        }
         if let Some(last_search_query_text) = &self.entity.read(cx).last_search_query_text
             && self.query_editor.read(cx).text(cx) != *last_search_query_text
         {
@@ -1228,8 +1389,28 @@ impl ProjectSearchView {
         } else {
             None
         };
-        if let Some(query) = self.build_search_query(cx, open_buffers) {
+        let Some(query) = self.build_search_query(cx, open_buffers) else {
+            return;
+        };
+        if !self.search_options.contains(SearchOptions::SEMANTIC) {
             self.entity.update(cx, |model, cx| model.search(query, cx));
+            return;
+        }
+
+        let project = self.entity.read(cx).project.clone();
+        match SemanticDb::project_index(&project, cx) {
+            Some(project_index) => {
+                self.entity.update(cx, |model, cx| {
+                    model.semantic_search(query, project_index, cx)
+                });
+            }
+            None => {
+                self.panels_with_errors.insert(
+                    InputPanel::Query,
+                    "The semantic index isn't available for this project".to_string(),
+                );
+                cx.notify();
+            }
         }
     }
 
@@ -1308,7 +1489,41 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        // Semantic searches use the query's text as is, and only use the query
+        // to filter the paths of the results.
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL)
+            && !self.search_options.contains(SearchOptions::SEMANTIC)
+        {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error.is_some() {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(e) => {
+                    let should_mark_error = self
+                        .panels_with_errors
+                        .insert(InputPanel::Query, e.to_string());
+                    if should_mark_error.is_none() {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX)
+            && !self.search_options.contains(SearchOptions::SEMANTIC)
+        {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1605,6 +1820,18 @@ impl ProjectSearchView {
                         window.dispatch_action(ToggleRegex.boxed_clone(), cx)
                     }),
             )
+            .child(
+                Button::new("structural", "Match syntax structure")
+                    .start_icon(Icon::new(IconName::Code).size(IconSize::Small))
+                    .key_binding(KeyBinding::for_action_in(
+                        &ToggleStructural,
+                        &focus_handle,
+                        cx,
+                    ))
+                    .on_click(|_event, window, cx| {
+                        window.dispatch_action(ToggleStructural.boxed_clone(), cx)
+                    }),
+            )
             .child(
                 Button::new("match-case", "Match case")
                     .start_icon(Icon::new(IconName::CaseSensitive).size(IconSize::Small))
@@ -1654,7 +1881,8 @@ impl ProjectSearchView {
     }
 
     fn adjust_query_regex_language(&self, cx: &mut App) {
-        let enable = self.search_options.contains(SearchOptions::REGEX);
+        let enable = self.search_options.contains(SearchOptions::REGEX)
+            && !self.search_options.contains(SearchOptions::STRUCTURAL);
         let query_buffer = self
             .query_editor
             .read(cx)
@@ -2067,6 +2295,8 @@ impl Render for ProjectSearchBar {
             .unwrap_or_else(|| "0/0".to_string());
 
         let query_focus = search.query_editor.focus_handle(cx);
+        let semantic_index_enabled = SemanticDb::project_index(&project_search.project, cx)
+            .is_some_and(|index| index.read(cx).status() != semantic_index::Status::Disabled);
 
         let query_column = input_base_styles(InputPanel::Query)
             .on_action(cx.listener(|this, action, window, cx| this.confirm(action, window, cx)))
@@ -2094,7 +2324,19 @@ impl Render for ProjectSearchBar {
                         search.search_options,
                         SearchSource::Project(cx),
                         focus_handle.clone(),
-                    )),
+                    ))
+                    .child(SearchOption::Structural.as_button(
+                        search.search_options,
+                        SearchSource::Project(cx),
+                        focus_handle.clone(),
+                    ))
+                    .when(semantic_index_enabled, |this| {
+                        this.child(SearchOption::Semantic.as_button(
+                            search.search_options,
+                            SearchSource::Project(cx),
+                            focus_handle.clone(),
+                        ))
+                    }),
             );
 
         let matches_column = h_flex()
@@ -4596,6 +4838,62 @@ pub mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_search_modes_are_exclusive(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(path!("/dir"), json!({ "one.rs": "const ONE: usize = 1;" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let window =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window
+            .read_with(cx, |mw, _| mw.workspace().clone())
+            .unwrap();
+        let mut cx = VisualTestContext::from_window(window.into(), cx);
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            ProjectSearchView::new_search(workspace, &workspace::NewSearch, window, cx)
+        });
+        cx.run_until_parked();
+        let search_view = workspace
+            .read_with(&cx, |workspace, cx| {
+                workspace
+                    .active_pane()
+                    .read(cx)
+                    .active_item()
+                    .and_then(|item| item.downcast::<ProjectSearchView>())
+            })
+            .expect("should open a project search view");
+
+        search_view.update(&mut cx, |search_view, cx| {
+            search_view.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
+            search_view.toggle_search_option(SearchOptions::REGEX, cx);
+            assert_eq!(
+                search_view.search_options,
+                SearchOptions::CASE_SENSITIVE | SearchOptions::REGEX
+            );
+
+            search_view.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+            assert_eq!(
+                search_view.search_options,
+                SearchOptions::CASE_SENSITIVE | SearchOptions::STRUCTURAL,
+                "enabling structural search should disable regex search"
+            );
+
+            search_view.toggle_search_option(SearchOptions::SEMANTIC, cx);
+            assert_eq!(
+                search_view.search_options,
+                SearchOptions::CASE_SENSITIVE | SearchOptions::SEMANTIC,
+                "enabling semantic search should disable structural search"
+            );
+
+            search_view.toggle_search_option(SearchOptions::SEMANTIC, cx);
+            assert_eq!(search_view.search_options, SearchOptions::CASE_SENSITIVE);
+        });
+    }
+
     #[gpui::test]
     async fn test_search_dismisses_modal(cx: &mut TestAppContext) {
         init_test(cx);
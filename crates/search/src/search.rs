@@ -39,6 +39,10 @@ actions!(
         ToggleRegex,
         /// Toggles the replace interface.
         ToggleReplace,
+        /// Toggles searching the project by meaning, using the semantic index.
+        ToggleSemantic,
+        /// Toggles structural search, which matches patterns written as code against syntax trees.
+        ToggleStructural,
         /// Toggles searching within selection only.
         ToggleSelection,
         /// Selects the next search match.
@@ -62,15 +66,19 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0;
-        const WHOLE_WORD = 1 << SearchOption::WholeWord as u8;
-        const CASE_SENSITIVE = 1 << SearchOption::CaseSensitive as u8;
-        const INCLUDE_IGNORED = 1 << SearchOption::IncludeIgnored as u8;
-        const REGEX = 1 << SearchOption::Regex as u8;
-        const ONE_MATCH_PER_LINE = 1 << SearchOption::OneMatchPerLine as u8;
+        const WHOLE_WORD = 1 << SearchOption::WholeWord as u16;
+        const CASE_SENSITIVE = 1 << SearchOption::CaseSensitive as u16;
+        const INCLUDE_IGNORED = 1 << SearchOption::IncludeIgnored as u16;
+        const REGEX = 1 << SearchOption::Regex as u16;
+        const ONE_MATCH_PER_LINE = 1 << SearchOption::OneMatchPerLine as u16;
         /// If set, reverse direction when finding the active match
-        const BACKWARDS = 1 << SearchOption::Backwards as u8;
+        const BACKWARDS = 1 << SearchOption::Backwards as u16;
+        /// If set, the project is searched by meaning rather than by text
+        const SEMANTIC = 1 << SearchOption::Semantic as u16;
+        /// If set, the query is a pattern matched against the syntax trees of the project's files
+        const STRUCTURAL = 1 << SearchOption::Structural as u16;
     }
 }
 
@@ -83,6 +91,8 @@ pub enum SearchOption {
     Regex,
     OneMatchPerLine,
     Backwards,
+    Semantic,
+    Structural,
 }
 
 pub(crate) enum SearchSource<'a, 'b> {
@@ -92,7 +102,7 @@ pub(crate) enum SearchSource<'a, 'b> {
 
 impl SearchOption {
     pub fn as_options(&self) -> SearchOptions {
-        SearchOptions::from_bits(1 << *self as u8).unwrap()
+        SearchOptions::from_bits(1 << *self as u16).unwrap()
     }
 
     pub fn label(&self) -> &'static str {
@@ -103,6 +113,8 @@ impl SearchOption {
             SearchOption::Regex => "Use Regular Expressions",
             SearchOption::OneMatchPerLine => "One Match Per Line",
             SearchOption::Backwards => "Search Backwards",
+            SearchOption::Semantic => "Search by Meaning",
+            SearchOption::Structural => "Match Syntax Structure",
         }
     }
 
@@ -112,6 +124,8 @@ impl SearchOption {
             SearchOption::CaseSensitive => ui::IconName::CaseSensitive,
             SearchOption::IncludeIgnored => ui::IconName::Sliders,
             SearchOption::Regex => ui::IconName::Regex,
+            SearchOption::Semantic => ui::IconName::Sparkle,
+            SearchOption::Structural => ui::IconName::Code,
             _ => panic!("{self:?} is not a named SearchOption"),
         }
     }
@@ -122,6 +136,8 @@ impl SearchOption {
             SearchOption::CaseSensitive => &ToggleCaseSensitive,
             SearchOption::IncludeIgnored => &ToggleIncludeIgnored,
             SearchOption::Regex => &ToggleRegex,
+            SearchOption::Semantic => &ToggleSemantic,
+            SearchOption::Structural => &ToggleStructural,
             _ => panic!("{self:?} is not a toggle action"),
         }
     }
@@ -173,6 +189,7 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
         options
     }
 
@@ -0,0 +1,287 @@
+use language::{BufferSnapshot, OffsetRangeExt as _};
+use sha2::{Digest as _, Sha256};
+use std::ops::Range;
+
+/// Chunks longer than this are split along nested outline items, or failing
+/// that, between lines.
+const MAX_CHUNK_LEN: usize = 2048;
+/// Chunks shorter than this are merged into their neighbors.
+const MIN_CHUNK_LEN: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    pub range: Range<usize>,
+    pub digest: [u8; 32],
+}
+
+/// The extent of an item in a file's outline, such as a function or a type.
+#[derive(Clone, Debug)]
+pub struct OutlineRange {
+    pub range: Range<usize>,
+    pub depth: usize,
+}
+
+/// Splits a buffer into chunks along the boundaries of its outline items.
+pub fn chunk_snapshot(snapshot: &BufferSnapshot) -> Vec<Chunk> {
+    let outline = snapshot
+        .outline_items_containing(0..snapshot.len(), false, None)
+        .into_iter()
+        .map(|item| OutlineRange {
+            range: item.range.to_offset(snapshot),
+            depth: item.depth,
+        })
+        .collect::<Vec<_>>();
+    chunk_text(&snapshot.text(), &outline)
+}
+
+/// Splits text into chunks of whole lines, keeping outline items together
+/// whenever they fit into a single chunk.
+pub fn chunk_text(text: &str, outline: &[OutlineRange]) -> Vec<Chunk> {
+    let mut ranges = Vec::new();
+    split_range(text, outline, 0..text.len(), 0, &mut ranges);
+    merge_small_ranges(ranges)
+        .into_iter()
+        .filter(|range| !text[range.clone()].trim().is_empty())
+        .map(|range| Chunk {
+            digest: Sha256::digest(text[range.clone()].as_bytes()).into(),
+            range,
+        })
+        .collect()
+}
+
+fn split_range(
+    text: &str,
+    outline: &[OutlineRange],
+    range: Range<usize>,
+    depth: usize,
+    ranges: &mut Vec<Range<usize>>,
+) {
+    if range.len() <= MAX_CHUNK_LEN {
+        ranges.push(range);
+        return;
+    }
+
+    let mut items = outline
+        .iter()
+        .filter(|item| {
+            item.depth == depth && item.range.start >= range.start && item.range.end <= range.end
+        })
+        .peekable();
+    if items.peek().is_none() {
+        split_lines(text, range, ranges);
+        return;
+    }
+
+    let mut start = range.start;
+    for item in items {
+        let item_start = line_start(text, item.range.start);
+        // Items sharing a line with the previous one belong to its chunk.
+        if item_start < start {
+            continue;
+        }
+        let item_end = line_end(text, item.range.end).min(range.end);
+        if item_start > start {
+            split_range(text, &[], start..item_start, depth, ranges);
+        }
+        split_range(text, outline, item_start..item_end, depth + 1, ranges);
+        start = item_end;
+    }
+    if start < range.end {
+        split_range(text, &[], start..range.end, depth, ranges);
+    }
+}
+
+fn split_lines(text: &str, range: Range<usize>, ranges: &mut Vec<Range<usize>>) {
+    let mut chunk_start = range.start;
+    let mut offset = range.start;
+    while offset < range.end {
+        let end = line_end(text, offset).min(range.end);
+        if end - chunk_start > MAX_CHUNK_LEN && offset > chunk_start {
+            ranges.push(chunk_start..offset);
+            chunk_start = offset;
+        }
+        // A single line longer than a chunk, as in minified code, is split
+        // wherever it exceeds the limit.
+        while end - chunk_start > MAX_CHUNK_LEN {
+            let mut split = chunk_start + MAX_CHUNK_LEN;
+            while !text.is_char_boundary(split) {
+                split -= 1;
+            }
+            ranges.push(chunk_start..split);
+            chunk_start = split;
+        }
+        offset = end;
+    }
+    if chunk_start < range.end {
+        ranges.push(chunk_start..range.end);
+    }
+}
+
+/// Merges small ranges into the range that follows them, so that comments and
+/// attributes stay with the item they describe.
+fn merge_small_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    let mut pending: Option<Range<usize>> = None;
+    for range in ranges {
+        let range = match pending.take() {
+            Some(pending) if range.end - pending.start <= MAX_CHUNK_LEN => pending.start..range.end,
+            Some(pending) => {
+                merged.push(pending);
+                range
+            }
+            None => range,
+        };
+        if range.len() < MIN_CHUNK_LEN {
+            pending = Some(range);
+        } else {
+            merged.push(range);
+        }
+    }
+
+    if let Some(pending) = pending {
+        match merged.last_mut() {
+            Some(last) if pending.end - last.start <= MAX_CHUNK_LEN => last.end = pending.end,
+            _ => merged.push(pending),
+        }
+    }
+    merged
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |ix| ix + 1)
+}
+
+fn line_end(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .find('\n')
+        .map_or(text.len(), |ix| offset + ix + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write as _;
+
+    fn function(name: &str, body_lines: usize) -> String {
+        let mut text = format!("/// Documentation for {name}.\nfn {name}() {{\n");
+        for ix in 0..body_lines {
+            writeln!(text, "    let value_{ix} = compute_something_for({ix});").unwrap();
+        }
+        text.push_str("}\n");
+        text
+    }
+
+    fn outline_for(text: &str, names: &[&str], depth: usize) -> Vec<OutlineRange> {
+        names
+            .iter()
+            .map(|name| {
+                let start = text.find(&format!("fn {name}()")).unwrap();
+                let end = start + text[start..].find("\n}\n").unwrap() + 2;
+                OutlineRange {
+                    range: start..end,
+                    depth,
+                }
+            })
+            .collect()
+    }
+
+    fn assert_chunks_cover(text: &str, chunks: &[Chunk]) {
+        let mut offset = 0;
+        for chunk in chunks {
+            assert!(
+                text[offset..chunk.range.start].trim().is_empty(),
+                "text between chunks was dropped"
+            );
+            assert!(chunk.range.len() <= MAX_CHUNK_LEN);
+            offset = chunk.range.end;
+        }
+        assert!(text[offset..].trim().is_empty());
+    }
+
+    #[test]
+    fn test_small_file_is_one_chunk() {
+        let text = function("a", 3);
+        let chunks = chunk_text(&text, &outline_for(&text, &["a"], 0));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].range, 0..text.len());
+    }
+
+    #[test]
+    fn test_chunks_follow_outline() {
+        let names = ["first", "second", "third", "fourth"];
+        let text = names
+            .iter()
+            .map(|name| function(name, 20))
+            .collect::<String>();
+        let chunks = chunk_text(&text, &outline_for(&text, &names, 0));
+
+        assert_chunks_cover(&text, &chunks);
+        assert_eq!(chunks.len(), names.len());
+        for (chunk, name) in chunks.iter().zip(names) {
+            let chunk_text = &text[chunk.range.clone()];
+            assert!(
+                chunk_text.starts_with(&format!("/// Documentation for {name}.")),
+                "documentation should be kept with its function: {chunk_text:?}"
+            );
+            assert!(chunk_text.ends_with("}\n"));
+        }
+    }
+
+    #[test]
+    fn test_large_items_are_split_between_lines() {
+        let text = function("huge", 200);
+        let chunks = chunk_text(&text, &outline_for(&text, &["huge"], 0));
+
+        assert_chunks_cover(&text, &chunks);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(text[..chunk.range.end].ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn test_long_lines_are_split() {
+        let text = "x".repeat(MAX_CHUNK_LEN * 2 + 10);
+        let chunks = chunk_text(&text, &[]);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.range.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                0..MAX_CHUNK_LEN,
+                MAX_CHUNK_LEN..MAX_CHUNK_LEN * 2,
+                MAX_CHUNK_LEN * 2..text.len()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_digests_identify_contents() {
+        let names = ["first", "second", "third"];
+        let text = names
+            .iter()
+            .map(|name| function(name, 20))
+            .collect::<String>();
+        let edited = [
+            function("first", 20),
+            function("second", 21),
+            function("third", 20),
+        ]
+        .concat();
+
+        let digests = chunk_text(&text, &outline_for(&text, &names, 0))
+            .into_iter()
+            .map(|chunk| chunk.digest)
+            .collect::<Vec<_>>();
+        let edited_digests = chunk_text(&edited, &outline_for(&edited, &names, 0))
+            .into_iter()
+            .map(|chunk| chunk.digest)
+            .collect::<Vec<_>>();
+        assert_eq!(digests.len(), 3);
+        assert_eq!(edited_digests.len(), 3);
+        assert_eq!(digests[0], edited_digests[0]);
+        assert_ne!(digests[1], edited_digests[1]);
+        assert_eq!(digests[2], edited_digests[2]);
+    }
+}
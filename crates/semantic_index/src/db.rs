@@ -0,0 +1,283 @@
+use crate::{Embedding, chunking::Chunk, embedding::values_from_bytes};
+use anyhow::Result;
+use collections::HashMap;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, BackgroundExecutor, Global, Task};
+use indoc::indoc;
+use parking_lot::Mutex;
+use sqlez::{connection::Connection, statement::Statement};
+use std::{ops::Range, sync::Arc};
+use zed_env_vars::ZED_STATELESS;
+
+/// The modification time of an indexed file, as stored in the database.
+pub type StoredMtime = (i64, i64);
+
+/// A chunk of an indexed file, along with its embedding.
+pub struct EmbeddedChunk {
+    pub chunk: Chunk,
+    pub embedding: Embedding,
+}
+
+/// A chunk found by [`SemanticIndexDatabase::search`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkMatch {
+    pub worktree_path: String,
+    pub path: String,
+    pub range: Range<usize>,
+    pub score: f32,
+}
+
+/// Stores the embeddings of the chunks of every indexed file, keyed by the
+/// absolute path of its worktree and the model that computed them.
+pub struct SemanticIndexDatabase {
+    executor: BackgroundExecutor,
+    connection: Arc<Mutex<Connection>>,
+}
+
+struct GlobalSemanticIndexDatabase(
+    Shared<Task<Result<Arc<SemanticIndexDatabase>, Arc<anyhow::Error>>>>,
+);
+
+impl Global for GlobalSemanticIndexDatabase {}
+
+impl SemanticIndexDatabase {
+    pub fn connect(
+        cx: &mut App,
+    ) -> Shared<Task<Result<Arc<SemanticIndexDatabase>, Arc<anyhow::Error>>>> {
+        if cx.has_global::<GlobalSemanticIndexDatabase>() {
+            return cx.global::<GlobalSemanticIndexDatabase>().0.clone();
+        }
+        let executor = cx.background_executor().clone();
+        let task = executor
+            .spawn({
+                let executor = executor.clone();
+                async move {
+                    match SemanticIndexDatabase::new(executor) {
+                        Ok(db) => Ok(Arc::new(db)),
+                        Err(err) => Err(Arc::new(err)),
+                    }
+                }
+            })
+            .shared();
+
+        cx.set_global(GlobalSemanticIndexDatabase(task.clone()));
+        task
+    }
+
+    pub fn new(executor: BackgroundExecutor) -> Result<Self> {
+        let connection = if *ZED_STATELESS {
+            Connection::open_memory(Some("SEMANTIC_INDEX_FALLBACK_DB"))
+        } else if cfg!(any(feature = "test-support", test)) {
+            let thread = std::thread::current();
+            let test_name = thread.name();
+            Connection::open_memory(Some(&format!(
+                "SEMANTIC_INDEX_FALLBACK_{}",
+                test_name.unwrap_or_default()
+            )))
+        } else {
+            let index_dir = paths::data_dir().join("semantic_index");
+            std::fs::create_dir_all(&index_dir)?;
+            let sqlite_path = index_dir.join("semantic_index.db");
+            Connection::open_file(&sqlite_path.to_string_lossy())
+        };
+
+        connection.exec(indoc! {"
+            CREATE TABLE IF NOT EXISTS files (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                worktree_path TEXT NOT NULL,
+                path TEXT NOT NULL,
+                embedding_provider TEXT NOT NULL,
+                mtime_seconds INTEGER NOT NULL,
+                mtime_nanos INTEGER NOT NULL,
+                UNIQUE(worktree_path, path)
+            );
+            CREATE TABLE IF NOT EXISTS chunks (
+                file_id INTEGER NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                digest BLOB NOT NULL,
+                embedding BLOB NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS chunks_file_id ON chunks(file_id);
+        "})?()?;
+
+        Ok(Self {
+            executor,
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Returns the modification times of the files of a worktree that were
+    /// indexed with the given embedding provider.
+    pub fn file_mtimes(
+        &self,
+        worktree_path: String,
+        embedding_provider: String,
+    ) -> Task<Result<HashMap<String, StoredMtime>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select =
+                connection.select_bound::<(String, String), (String, i64, i64)>(indoc! {"
+                    SELECT path, mtime_seconds, mtime_nanos FROM files
+                    WHERE worktree_path = ?1 AND embedding_provider = ?2
+                "})?;
+            Ok(select((worktree_path, embedding_provider))?
+                .into_iter()
+                .map(|(path, seconds, nanos)| (path, (seconds, nanos)))
+                .collect())
+        })
+    }
+
+    /// Returns the embeddings of the chunks a file had when it was last
+    /// indexed, keyed by their digest, so that unchanged chunks aren't
+    /// embedded again.
+    pub fn embeddings_for_file(
+        &self,
+        worktree_path: String,
+        path: String,
+        embedding_provider: String,
+    ) -> Task<Result<HashMap<[u8; 32], Embedding>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut select = connection
+                .select_bound::<(String, String, String), ([u8; 32], Vec<u8>)>(indoc! {"
+                    SELECT chunks.digest, chunks.embedding FROM chunks
+                    JOIN files ON files.id = chunks.file_id
+                    WHERE files.worktree_path = ?1 AND files.path = ?2 AND files.embedding_provider = ?3
+                "})?;
+            select((worktree_path, path, embedding_provider))?
+                .into_iter()
+                .map(|(digest, embedding)| Ok((digest, Embedding::from_bytes(&embedding)?)))
+                .collect()
+        })
+    }
+
+    /// Replaces the chunks of a file.
+    pub fn save_file(
+        &self,
+        worktree_path: String,
+        path: String,
+        embedding_provider: String,
+        mtime: StoredMtime,
+        chunks: Vec<EmbeddedChunk>,
+    ) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.with_savepoint("save_file", || {
+                Self::delete_file_sync(&connection, &worktree_path, &path)?;
+                connection.exec_bound::<(&str, &str, &str, i64, i64)>(indoc! {"
+                    INSERT INTO files (worktree_path, path, embedding_provider, mtime_seconds, mtime_nanos)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                "})?((
+                    worktree_path.as_str(),
+                    path.as_str(),
+                    embedding_provider.as_str(),
+                    mtime.0,
+                    mtime.1,
+                ))?;
+                let file_id = connection.select_row::<i64>("SELECT last_insert_rowid()")?()?
+                    .ok_or_else(|| anyhow::anyhow!("failed to insert file"))?;
+
+                let mut insert = connection.exec_bound::<(i64, usize, usize, &[u8; 32], Vec<u8>)>(
+                    indoc! {"
+                        INSERT INTO chunks (file_id, start_offset, end_offset, digest, embedding)
+                        VALUES (?1, ?2, ?3, ?4, ?5)
+                    "},
+                )?;
+                for EmbeddedChunk { chunk, embedding } in chunks {
+                    insert((
+                        file_id,
+                        chunk.range.start,
+                        chunk.range.end,
+                        &chunk.digest,
+                        embedding.to_bytes(),
+                    ))?;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    pub fn delete_files(&self, worktree_path: String, paths: Vec<String>) -> Task<Result<()>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            connection.with_savepoint("delete_files", || {
+                for path in &paths {
+                    Self::delete_file_sync(&connection, &worktree_path, path)?;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn delete_file_sync(connection: &Connection, worktree_path: &str, path: &str) -> Result<()> {
+        connection.exec_bound::<(&str, &str)>(indoc! {"
+            DELETE FROM chunks WHERE file_id IN (
+                SELECT id FROM files WHERE worktree_path = ?1 AND path = ?2
+            )
+        "})?((worktree_path, path))?;
+        connection.exec_bound::<(&str, &str)>(indoc! {"
+            DELETE FROM files WHERE worktree_path = ?1 AND path = ?2
+        "})?((worktree_path, path))?;
+        Ok(())
+    }
+
+    /// Returns the chunks of the given worktrees most similar to the query,
+    /// from most to least similar.
+    pub fn search(
+        &self,
+        worktree_paths: Vec<String>,
+        embedding_provider: String,
+        query: Embedding,
+        limit: usize,
+    ) -> Task<Result<Vec<ChunkMatch>>> {
+        let connection = self.connection.clone();
+        self.executor.spawn(async move {
+            let connection = connection.lock();
+            let mut matches = Vec::new();
+            for worktree_path in worktree_paths {
+                let mut statement = Statement::prepare(
+                    &connection,
+                    indoc! {"
+                        SELECT files.path, chunks.start_offset, chunks.end_offset, chunks.embedding
+                        FROM chunks JOIN files ON files.id = chunks.file_id
+                        WHERE files.worktree_path = ?1 AND files.embedding_provider = ?2
+                    "},
+                )?;
+                statement.with_bindings(&(worktree_path.as_str(), embedding_provider.as_str()))?;
+                // Embeddings are scored as rows are read, rather than loading
+                // every embedding of the worktree into memory first.
+                statement.map(|row| {
+                    let embedding = values_from_bytes(row.column_blob(3)?)?.collect::<Vec<_>>();
+                    if embedding.len() != query.len() {
+                        return Ok(());
+                    }
+                    let score = query.similarity(&embedding);
+                    let start = row.column_int64(1)? as usize;
+                    let end = row.column_int64(2)? as usize;
+                    matches.push(ChunkMatch {
+                        worktree_path: worktree_path.clone(),
+                        path: row.column_text(0)?.to_string(),
+                        range: start..end,
+                        score,
+                    });
+                    if matches.len() >= limit * 2 {
+                        truncate_matches(&mut matches, limit);
+                    }
+                    Ok(())
+                })?;
+            }
+            truncate_matches(&mut matches, limit);
+            Ok(matches)
+        })
+    }
+}
+
+fn truncate_matches(matches: &mut Vec<ChunkMatch>, limit: usize) {
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+}
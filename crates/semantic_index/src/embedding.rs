@@ -0,0 +1,272 @@
+use anyhow::{Context as _, Result, anyhow};
+use credentials_provider::CredentialsProvider;
+use futures::{FutureExt as _, future::BoxFuture};
+use gpui::{App, Task};
+use http_client::HttpClient;
+use settings::EmbeddingProviderContent;
+use std::sync::Arc;
+
+use crate::SemanticIndexSettings;
+
+const OPEN_AI_API_KEY_ENV_VAR_NAME: &str = "OPENAI_API_KEY";
+
+/// A unit-length vector representing the meaning of some text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embedding(Vec<f32>);
+
+impl Embedding {
+    pub fn new(mut values: Vec<f32>) -> Self {
+        let norm = values.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0. {
+            for value in &mut values {
+                *value /= norm;
+            }
+        }
+        Self(values)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The cosine similarity of two embeddings, which is their dot product
+    /// since both are normalized.
+    pub fn similarity(&self, other: &[f32]) -> f32 {
+        self.0.iter().zip(other).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self(values_from_bytes(bytes)?.collect()))
+    }
+}
+
+/// Decodes the values of an embedding stored with [`Embedding::to_bytes`].
+pub(crate) fn values_from_bytes(bytes: &[u8]) -> Result<impl Iterator<Item = f32> + '_> {
+    anyhow::ensure!(
+        bytes.len() % 4 == 0,
+        "invalid embedding of {} bytes",
+        bytes.len()
+    );
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+}
+
+/// A service that computes embeddings.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifies both the provider and its model, so that embeddings computed
+    /// by different models are never compared with each other.
+    fn id(&self) -> &str;
+    /// The maximum number of texts to embed in a single request.
+    fn batch_size(&self) -> usize;
+    /// Computes an embedding for each of the given texts, in order.
+    fn embed(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>>;
+}
+
+/// An [`EmbeddingProvider`] calling the HTTP API of Ollama, LM Studio, or an
+/// OpenAI-compatible service.
+pub struct HttpEmbeddingProvider {
+    id: String,
+    kind: EmbeddingProviderContent,
+    client: Arc<dyn HttpClient>,
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl HttpEmbeddingProvider {
+    /// Creates the provider configured in the settings, reading the OpenAI
+    /// API key the same way the OpenAI language model provider does.
+    pub fn from_settings(
+        settings: &SemanticIndexSettings,
+        cx: &App,
+    ) -> Task<Result<Arc<dyn EmbeddingProvider>>> {
+        let kind = settings.provider;
+        let model = settings.model.clone();
+        let api_url = settings
+            .api_url
+            .clone()
+            .unwrap_or_else(|| match kind {
+                EmbeddingProviderContent::Ollama => ollama::OLLAMA_API_URL.to_string(),
+                EmbeddingProviderContent::LmStudio => lmstudio::LMSTUDIO_API_URL.to_string(),
+                EmbeddingProviderContent::OpenAi => open_ai::OPEN_AI_API_URL.to_string(),
+            })
+            .trim_end_matches('/')
+            .to_string();
+        let client = cx.http_client();
+        let credentials_provider = <dyn CredentialsProvider>::global(cx);
+
+        cx.spawn(async move |cx| {
+            let api_key = match kind {
+                EmbeddingProviderContent::OpenAi => {
+                    if let Some(api_key) = std::env::var(OPEN_AI_API_KEY_ENV_VAR_NAME)
+                        .ok()
+                        .filter(|api_key| !api_key.is_empty())
+                    {
+                        Some(api_key)
+                    } else {
+                        let (_, api_key) = credentials_provider
+                            .read_credentials(&api_url, cx)
+                            .await?
+                            .with_context(|| {
+                                format!(
+                                    "no API key for {api_url}. Set {OPEN_AI_API_KEY_ENV_VAR_NAME} or add one in the OpenAI provider settings"
+                                )
+                            })?;
+                        Some(String::from_utf8(api_key).context("API key is not utf8")?)
+                    }
+                }
+                EmbeddingProviderContent::Ollama | EmbeddingProviderContent::LmStudio => None,
+            };
+
+            Ok(Arc::new(Self {
+                id: format!("{}/{}", kind_id(kind), model),
+                kind,
+                client,
+                api_url,
+                api_key,
+                model,
+            }) as Arc<dyn EmbeddingProvider>)
+        })
+    }
+}
+
+fn kind_id(kind: EmbeddingProviderContent) -> &'static str {
+    match kind {
+        EmbeddingProviderContent::Ollama => "ollama",
+        EmbeddingProviderContent::LmStudio => "lm_studio",
+        EmbeddingProviderContent::OpenAi => "open_ai",
+    }
+}
+
+impl EmbeddingProvider for HttpEmbeddingProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn batch_size(&self) -> usize {
+        match self.kind {
+            EmbeddingProviderContent::Ollama | EmbeddingProviderContent::LmStudio => 32,
+            EmbeddingProviderContent::OpenAi => 256,
+        }
+    }
+
+    fn embed(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        let kind = self.kind;
+        let client = self.client.clone();
+        let api_url = self.api_url.clone();
+        let api_key = self.api_key.clone();
+        let model = self.model.clone();
+        async move {
+            let texts = texts.iter().map(String::as_str).collect::<Vec<_>>();
+            let embeddings = match kind {
+                EmbeddingProviderContent::Ollama => {
+                    ollama::embed(
+                        client.as_ref(),
+                        &api_url,
+                        api_key.as_deref(),
+                        &model,
+                        &texts,
+                    )
+                    .await?
+                }
+                EmbeddingProviderContent::LmStudio => {
+                    lmstudio::embed(
+                        client.as_ref(),
+                        &api_url,
+                        api_key.as_deref(),
+                        &model,
+                        &texts,
+                    )
+                    .await?
+                }
+                EmbeddingProviderContent::OpenAi => open_ai::embed(
+                    client.as_ref(),
+                    &api_url,
+                    api_key.as_deref().unwrap_or_default(),
+                    &model,
+                    texts.iter().copied(),
+                )
+                .await?
+                .data
+                .into_iter()
+                .map(|embedding| embedding.embedding)
+                .collect(),
+            };
+            if embeddings.len() != texts.len() {
+                return Err(anyhow!(
+                    "expected {} embeddings from {api_url}, got {}",
+                    texts.len(),
+                    embeddings.len()
+                ));
+            }
+            Ok(embeddings.into_iter().map(Embedding::new).collect())
+        }
+        .boxed()
+    }
+}
+
+/// Embeds texts as a bag of their words, so that texts sharing words are
+/// similar.
+#[cfg(any(test, feature = "test-support"))]
+pub struct FakeEmbeddingProvider;
+
+#[cfg(any(test, feature = "test-support"))]
+impl EmbeddingProvider for FakeEmbeddingProvider {
+    fn id(&self) -> &str {
+        "fake"
+    }
+
+    fn batch_size(&self) -> usize {
+        4
+    }
+
+    fn embed(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+        use std::hash::{Hash as _, Hasher as _};
+
+        let embeddings = texts
+            .iter()
+            .map(|text| {
+                let mut values = vec![0.; 64];
+                for word in text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                {
+                    let mut hasher = collections::FxHasher::default();
+                    word.to_lowercase().hash(&mut hasher);
+                    values[hasher.finish() as usize % 64] += 1.;
+                }
+                Embedding::new(values)
+            })
+            .collect();
+        futures::future::ready(Ok(embeddings)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_roundtrip() {
+        let embedding = Embedding::new(vec![3., 4.]);
+        assert_eq!(embedding, Embedding(vec![0.6, 0.8]));
+        assert_eq!(
+            Embedding::from_bytes(&embedding.to_bytes()).unwrap(),
+            embedding
+        );
+        assert!((embedding.similarity(&[0.6, 0.8]) - 1.).abs() < f32::EPSILON);
+        assert!(Embedding::from_bytes(&[0, 1, 2]).is_err());
+    }
+}
@@ -0,0 +1,791 @@
+use crate::{
+    Embedding, EmbeddingProvider,
+    chunking::{Chunk, chunk_snapshot},
+    db::{EmbeddedChunk, SemanticIndexDatabase, StoredMtime},
+};
+use anyhow::{Context as _, Result, anyhow};
+use collections::{BTreeSet, HashMap};
+use fs::Fs;
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
+};
+use language::{Buffer, LanguageRegistry, Rope};
+use project::{Project, WorktreeId};
+use std::{mem, ops::Range, sync::Arc, time::Duration};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::{Entry, Snapshot, UpdatedEntriesSet, Worktree};
+
+/// How long to wait for more changes to a worktree before indexing them.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Files larger than this aren't indexed.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A chunk of a project file matching a semantic search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub worktree_id: WorktreeId,
+    pub path: Arc<RelPath>,
+    /// The byte range of the chunk in the file when it was last indexed.
+    pub range: Range<usize>,
+    pub score: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// Semantic indexing is disabled in the settings, or couldn't be set up.
+    Disabled,
+    Idle,
+    Indexing {
+        remaining_files: usize,
+    },
+    /// Some files couldn't be indexed, e.g. because the embedding provider
+    /// couldn't be reached. They're indexed again on the next change to the
+    /// worktree, or on [`ProjectIndex::reindex`].
+    Failed,
+}
+
+/// Where a [`ProjectIndex`] stores and computes embeddings.
+#[derive(Clone)]
+pub struct IndexBackend {
+    pub db: Arc<SemanticIndexDatabase>,
+    pub embedding_provider: Arc<dyn EmbeddingProvider>,
+}
+
+/// Keeps the embeddings of the files in a project's local worktrees up to
+/// date, and searches them.
+pub struct ProjectIndex {
+    project: WeakEntity<Project>,
+    backend: Option<IndexBackend>,
+    worktree_indices: HashMap<WorktreeId, WorktreeIndex>,
+    status: Status,
+    _subscription: Subscription,
+}
+
+struct WorktreeIndex {
+    worktree_path: String,
+    remaining_files: usize,
+    failed: bool,
+    _subscription: Subscription,
+    _task: Task<()>,
+}
+
+impl EventEmitter<Status> for ProjectIndex {}
+
+impl ProjectIndex {
+    pub fn new(
+        project: Entity<Project>,
+        backend: Option<IndexBackend>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&project, |this, _, event, cx| match event {
+            project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                this.update_worktree_indices(cx);
+            }
+            _ => {}
+        });
+        let mut this = Self {
+            project: project.downgrade(),
+            backend,
+            worktree_indices: HashMap::default(),
+            status: Status::Disabled,
+            _subscription: subscription,
+        };
+        this.update_worktree_indices(cx);
+        this
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_backend(&mut self, backend: Option<IndexBackend>, cx: &mut Context<Self>) {
+        self.backend = backend;
+        self.reindex(cx);
+    }
+
+    /// Indexes all files again that changed since they were last indexed.
+    pub fn reindex(&mut self, cx: &mut Context<Self>) {
+        self.worktree_indices.clear();
+        self.update_worktree_indices(cx);
+    }
+
+    /// Returns the indexed chunks most similar to the query, from most to
+    /// least similar.
+    pub fn search(&self, query: String, limit: usize, cx: &App) -> Task<Result<Vec<SearchResult>>> {
+        let Some(backend) = self.backend.clone() else {
+            return Task::ready(Err(anyhow!(
+                "the semantic index is disabled. Set `semantic_index.enabled` in the settings to enable it"
+            )));
+        };
+        let worktrees = self
+            .worktree_indices
+            .iter()
+            .map(|(worktree_id, index)| (index.worktree_path.clone(), *worktree_id))
+            .collect::<HashMap<_, _>>();
+
+        cx.background_spawn(async move {
+            let query = backend
+                .embedding_provider
+                .embed(vec![query])
+                .await?
+                .pop()
+                .context("no embedding for the query")?;
+            let matches = backend
+                .db
+                .search(
+                    worktrees.keys().cloned().collect(),
+                    backend.embedding_provider.id().to_string(),
+                    query,
+                    limit,
+                )
+                .await?;
+            Ok(matches
+                .into_iter()
+                .filter_map(|chunk_match| {
+                    Some(SearchResult {
+                        worktree_id: *worktrees.get(&chunk_match.worktree_path)?,
+                        path: RelPath::unix(&chunk_match.path).ok()?.into_arc(),
+                        range: chunk_match.range,
+                        score: chunk_match.score,
+                    })
+                })
+                .collect())
+        })
+    }
+
+    fn update_worktree_indices(&mut self, cx: &mut Context<Self>) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let Some(backend) = self.backend.clone() else {
+            self.worktree_indices.clear();
+            self.update_status(cx);
+            return;
+        };
+
+        let worktrees = project
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .map(|worktree| (worktree.read(cx).id(), worktree))
+            .collect::<HashMap<_, _>>();
+        self.worktree_indices
+            .retain(|worktree_id, _| worktrees.contains_key(worktree_id));
+        for (worktree_id, worktree) in worktrees {
+            if !self.worktree_indices.contains_key(&worktree_id) {
+                let index = self.index_worktree(&project, worktree, backend.clone(), cx);
+                self.worktree_indices.insert(worktree_id, index);
+            }
+        }
+        self.update_status(cx);
+    }
+
+    fn index_worktree(
+        &self,
+        project: &Entity<Project>,
+        worktree: Entity<Worktree>,
+        backend: IndexBackend,
+        cx: &mut Context<Self>,
+    ) -> WorktreeIndex {
+        let worktree_path = worktree.read(cx).abs_path().to_string_lossy().into_owned();
+        let (updates_tx, updates_rx) = mpsc::unbounded();
+        let subscription = cx.subscribe(&worktree, move |_, _, event, _| {
+            if let worktree::Event::UpdatedEntries(changes) = event {
+                updates_tx.unbounded_send(changes.clone()).ok();
+            }
+        });
+        let indexer = Indexer {
+            worktree_id: worktree.read(cx).id(),
+            worktree_path: worktree_path.clone(),
+            fs: project.read(cx).fs().clone(),
+            language_registry: project.read(cx).languages().clone(),
+            backend,
+        };
+        let task =
+            cx.spawn(async move |this, cx| indexer.run(this, worktree, updates_rx, cx).await);
+
+        WorktreeIndex {
+            worktree_path,
+            remaining_files: 0,
+            failed: false,
+            _subscription: subscription,
+            _task: task,
+        }
+    }
+
+    fn update_status(&mut self, cx: &mut Context<Self>) {
+        let status = if self.backend.is_none() {
+            Status::Disabled
+        } else {
+            let remaining_files = self
+                .worktree_indices
+                .values()
+                .map(|index| index.remaining_files)
+                .sum();
+            if remaining_files > 0 {
+                Status::Indexing { remaining_files }
+            } else if self.worktree_indices.values().any(|index| index.failed) {
+                Status::Failed
+            } else {
+                Status::Idle
+            }
+        };
+        if status != self.status {
+            self.status = status;
+            cx.emit(status);
+            cx.notify();
+        }
+    }
+}
+
+/// Indexes the files of a worktree, first all of those changed since they
+/// were last indexed, and then those reported as changed by the worktree.
+struct Indexer {
+    worktree_id: WorktreeId,
+    worktree_path: String,
+    fs: Arc<dyn Fs>,
+    language_registry: Arc<LanguageRegistry>,
+    backend: IndexBackend,
+}
+
+struct PendingFile {
+    path: Arc<RelPath>,
+    mtime: StoredMtime,
+    chunks: Vec<PendingChunk>,
+}
+
+struct PendingChunk {
+    chunk: Chunk,
+    text: String,
+    embedding: Option<Embedding>,
+}
+
+impl Indexer {
+    async fn run(
+        self,
+        this: WeakEntity<ProjectIndex>,
+        worktree: Entity<Worktree>,
+        mut updates: mpsc::UnboundedReceiver<UpdatedEntriesSet>,
+        cx: &mut AsyncApp,
+    ) {
+        let scan_complete = worktree.read_with(cx, |worktree, _| {
+            worktree.as_local().map(|worktree| worktree.scan_complete())
+        });
+        if let Some(scan_complete) = scan_complete {
+            scan_complete.await;
+        }
+
+        let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot());
+        let mut failed = self.try_index(&this, &snapshot, None, cx).await;
+
+        while let Some(changes) = updates.next().await {
+            let mut paths = changes
+                .iter()
+                .map(|(path, _, _)| path.clone())
+                .collect::<BTreeSet<_>>();
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            while let Ok(Some(changes)) = updates.try_next() {
+                paths.extend(changes.iter().map(|(path, _, _)| path.clone()));
+            }
+
+            let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot());
+            // The files that failed to be indexed are still out of date, so
+            // look at all files again to retry them.
+            let paths = if failed { None } else { Some(paths) };
+            failed = self.try_index(&this, &snapshot, paths, cx).await;
+        }
+    }
+
+    /// Indexes the given paths like [`Self::index`], and reports whether it
+    /// failed, so that the status doesn't stay at the files that were left.
+    async fn try_index(
+        &self,
+        this: &WeakEntity<ProjectIndex>,
+        snapshot: &Snapshot,
+        paths: Option<BTreeSet<Arc<RelPath>>>,
+        cx: &mut AsyncApp,
+    ) -> bool {
+        let failed = self
+            .index(this, snapshot, paths, cx)
+            .await
+            .with_context(|| format!("indexing {}", self.worktree_path))
+            .log_err()
+            .is_none();
+        this.update(cx, |this, cx| {
+            if let Some(index) = this.worktree_indices.get_mut(&self.worktree_id) {
+                index.failed = failed;
+                if failed {
+                    index.remaining_files = 0;
+                }
+            }
+            this.update_status(cx);
+        })
+        .ok();
+        failed
+    }
+
+    /// Indexes the given paths of the worktree, or all of its files, if they
+    /// changed since they were last indexed.
+    async fn index(
+        &self,
+        this: &WeakEntity<ProjectIndex>,
+        snapshot: &Snapshot,
+        paths: Option<BTreeSet<Arc<RelPath>>>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let db = &self.backend.db;
+        let indexed = db
+            .file_mtimes(
+                self.worktree_path.clone(),
+                self.backend.embedding_provider.id().to_string(),
+            )
+            .await?;
+
+        let mut changed = Vec::new();
+        let mut deleted = Vec::new();
+        match paths {
+            None => {
+                for entry in snapshot.files(false, 0) {
+                    if let Some(mtime) = indexable_mtime(entry)
+                        && indexed.get(entry.path.as_unix_str()) != Some(&mtime)
+                    {
+                        changed.push((entry.path.clone(), mtime));
+                    }
+                }
+                deleted.extend(
+                    indexed
+                        .keys()
+                        .filter(|path| {
+                            RelPath::unix(path.as_str())
+                                .ok()
+                                .and_then(|path| snapshot.entry_for_path(path))
+                                .and_then(indexable_mtime)
+                                .is_none()
+                        })
+                        .cloned(),
+                );
+            }
+            Some(paths) => {
+                for path in paths {
+                    let key = path.as_unix_str();
+                    match snapshot.entry_for_path(&path).and_then(indexable_mtime) {
+                        Some(mtime) => {
+                            if indexed.get(key) != Some(&mtime) {
+                                changed.push((path, mtime));
+                            }
+                        }
+                        None => {
+                            if indexed.contains_key(key) {
+                                deleted.push(key.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if !deleted.is_empty() {
+            db.delete_files(self.worktree_path.clone(), deleted).await?;
+        }
+
+        let batch_size = self.backend.embedding_provider.batch_size();
+        let mut remaining_files = changed.len();
+        self.set_remaining_files(this, remaining_files, cx)?;
+        let mut pending_files = Vec::new();
+        let mut pending_texts = 0;
+        for (path, mtime) in changed {
+            let file = self.chunk_file(snapshot, path, mtime, cx).await?;
+            pending_texts += file
+                .chunks
+                .iter()
+                .filter(|chunk| chunk.embedding.is_none())
+                .count();
+            pending_files.push(file);
+
+            // Embed chunks of several files at once, so that small files
+            // don't each need a request.
+            if pending_texts >= batch_size {
+                remaining_files -= pending_files.len();
+                self.embed_and_save(mem::take(&mut pending_files)).await?;
+                pending_texts = 0;
+                self.set_remaining_files(this, remaining_files, cx)?;
+            }
+        }
+        self.embed_and_save(pending_files).await?;
+        self.set_remaining_files(this, 0, cx)
+    }
+
+    async fn chunk_file(
+        &self,
+        snapshot: &Snapshot,
+        path: Arc<RelPath>,
+        mtime: StoredMtime,
+        cx: &mut AsyncApp,
+    ) -> Result<PendingFile> {
+        // Files that aren't text are saved without chunks, so that they're only
+        // read again once they change.
+        let Some(text) = self.fs.load(&snapshot.absolutize(&path)).await.ok() else {
+            return Ok(PendingFile {
+                path,
+                mtime,
+                chunks: Vec::new(),
+            });
+        };
+
+        let language = self
+            .language_registry
+            .load_language_for_file_path(path.as_std_path())
+            .await
+            .ok();
+        let buffer_snapshot = cx.update(|cx| {
+            Buffer::build_snapshot(
+                Rope::from(text.as_str()),
+                language,
+                Some(self.language_registry.clone()),
+                cx,
+            )
+        });
+        let chunks = cx
+            .background_spawn(async move {
+                let snapshot = buffer_snapshot.await;
+                chunk_snapshot(&snapshot)
+                    .into_iter()
+                    .map(|chunk| {
+                        let text = snapshot
+                            .text_for_range(chunk.range.clone())
+                            .collect::<String>();
+                        (chunk, text)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+
+        let embeddings = self
+            .backend
+            .db
+            .embeddings_for_file(
+                self.worktree_path.clone(),
+                path.as_unix_str().to_string(),
+                self.backend.embedding_provider.id().to_string(),
+            )
+            .await?;
+        let chunks = chunks
+            .into_iter()
+            .map(|(chunk, text)| PendingChunk {
+                embedding: embeddings.get(&chunk.digest).cloned(),
+                // The path gives the model context that the chunk often lacks.
+                text: format!("{}\n{text}", path.as_unix_str()),
+                chunk,
+            })
+            .collect();
+        Ok(PendingFile {
+            path,
+            mtime,
+            chunks,
+        })
+    }
+
+    async fn embed_and_save(&self, files: Vec<PendingFile>) -> Result<()> {
+        let provider = &self.backend.embedding_provider;
+        let texts = files
+            .iter()
+            .flat_map(|file| &file.chunks)
+            .filter(|chunk| chunk.embedding.is_none())
+            .map(|chunk| chunk.text.clone())
+            .collect::<Vec<_>>();
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(provider.batch_size().max(1)) {
+            embeddings.extend(provider.embed(batch.to_vec()).await?);
+        }
+
+        let mut embeddings = embeddings.into_iter();
+        for file in files {
+            let chunks = file
+                .chunks
+                .into_iter()
+                .map(|chunk| {
+                    let embedding = match chunk.embedding {
+                        Some(embedding) => embedding,
+                        None => embeddings.next().context("missing embedding")?,
+                    };
+                    Ok(EmbeddedChunk {
+                        chunk: chunk.chunk,
+                        embedding,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.backend
+                .db
+                .save_file(
+                    self.worktree_path.clone(),
+                    file.path.as_unix_str().to_string(),
+                    provider.id().to_string(),
+                    file.mtime,
+                    chunks,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn set_remaining_files(
+        &self,
+        this: &WeakEntity<ProjectIndex>,
+        remaining_files: usize,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        this.update(cx, |this, cx| {
+            if let Some(index) = this.worktree_indices.get_mut(&self.worktree_id) {
+                index.remaining_files = remaining_files;
+            }
+            this.update_status(cx);
+        })
+    }
+}
+
+fn indexable_mtime(entry: &Entry) -> Option<StoredMtime> {
+    if !entry.is_file()
+        || entry.is_ignored
+        || entry.is_external
+        || entry.is_private
+        || entry.is_fifo
+        || entry.size > MAX_FILE_SIZE
+    {
+        return None;
+    }
+    let (seconds, nanos) = entry.mtime?.to_seconds_and_nanos_for_persistence()?;
+    Some((seconds as i64, nanos as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FakeEmbeddingProvider;
+    use fs::FakeFs;
+    use futures::{FutureExt as _, future::BoxFuture};
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    async fn project_index(
+        fs: Arc<FakeFs>,
+        cx: &mut TestAppContext,
+    ) -> (Entity<Project>, Entity<ProjectIndex>) {
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let db = Arc::new(SemanticIndexDatabase::new(cx.background_executor.clone()).unwrap());
+        let backend = IndexBackend {
+            db,
+            embedding_provider: Arc::new(FakeEmbeddingProvider),
+        };
+        let project_index = cx.new(|cx| ProjectIndex::new(project.clone(), Some(backend), cx));
+        cx.run_until_parked();
+        (project, project_index)
+    }
+
+    async fn search(
+        project_index: &Entity<ProjectIndex>,
+        query: &str,
+        limit: usize,
+        cx: &mut TestAppContext,
+    ) -> Vec<String> {
+        project_index
+            .read_with(cx, |index, cx| index.search(query.to_string(), limit, cx))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.path.as_unix_str().to_string())
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_search_and_incremental_updates(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "parser.txt": "tokenize the input into lexemes and build a syntax tree",
+                "network.txt": "open a socket connection and send the request bytes",
+            }),
+        )
+        .await;
+        let (_project, project_index) = project_index(fs.clone(), cx).await;
+
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Idle
+        );
+        assert_eq!(
+            search(&project_index, "build syntax tree", 1, cx).await,
+            ["parser.txt"]
+        );
+        assert_eq!(
+            search(&project_index, "socket connection", 1, cx).await,
+            ["network.txt"]
+        );
+
+        fs.insert_file(
+            path!("/project/storage.txt"),
+            b"write the records to disk and flush the database".to_vec(),
+        )
+        .await;
+        fs.remove_file(path!("/project/network.txt").as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+
+        assert_eq!(
+            search(&project_index, "flush the database", 1, cx).await,
+            ["storage.txt"]
+        );
+        assert!(
+            !search(&project_index, "socket connection", 10, cx)
+                .await
+                .contains(&"network.txt".to_string())
+        );
+    }
+
+    #[gpui::test]
+    async fn test_failed_files_are_retried(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "parser.txt": "tokenize the input into lexemes and build a syntax tree",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let db = Arc::new(SemanticIndexDatabase::new(cx.background_executor.clone()).unwrap());
+        let unreachable = Arc::new(AtomicBool::new(true));
+        let backend = IndexBackend {
+            db,
+            embedding_provider: Arc::new(FlakyEmbeddingProvider {
+                unreachable: unreachable.clone(),
+            }),
+        };
+        let project_index = cx.new(|cx| ProjectIndex::new(project.clone(), Some(backend), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Failed
+        );
+
+        // Changing another file also retries the files that failed before.
+        unreachable.store(false, Ordering::SeqCst);
+        fs.insert_file(
+            path!("/project/storage.txt"),
+            b"write the records to disk and flush the database".to_vec(),
+        )
+        .await;
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Idle
+        );
+        assert_eq!(
+            search(&project_index, "build syntax tree", 1, cx).await,
+            ["parser.txt"]
+        );
+        assert_eq!(
+            search(&project_index, "flush the database", 1, cx).await,
+            ["storage.txt"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_reindex_after_failure(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "network.txt": "open a socket connection and send the request bytes",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let db = Arc::new(SemanticIndexDatabase::new(cx.background_executor.clone()).unwrap());
+        let unreachable = Arc::new(AtomicBool::new(true));
+        let backend = IndexBackend {
+            db,
+            embedding_provider: Arc::new(FlakyEmbeddingProvider {
+                unreachable: unreachable.clone(),
+            }),
+        };
+        let project_index = cx.new(|cx| ProjectIndex::new(project.clone(), Some(backend), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Failed
+        );
+
+        unreachable.store(false, Ordering::SeqCst);
+        project_index.update(cx, |index, cx| index.reindex(cx));
+        cx.run_until_parked();
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Idle
+        );
+        assert_eq!(
+            search(&project_index, "socket connection", 1, cx).await,
+            ["network.txt"]
+        );
+    }
+
+    /// Fails to embed texts while it's unreachable.
+    struct FlakyEmbeddingProvider {
+        unreachable: Arc<AtomicBool>,
+    }
+
+    impl EmbeddingProvider for FlakyEmbeddingProvider {
+        fn id(&self) -> &str {
+            FakeEmbeddingProvider.id()
+        }
+
+        fn batch_size(&self) -> usize {
+            FakeEmbeddingProvider.batch_size()
+        }
+
+        fn embed(&self, texts: Vec<String>) -> BoxFuture<'static, Result<Vec<Embedding>>> {
+            if self.unreachable.load(Ordering::SeqCst) {
+                return futures::future::ready(Err(anyhow!("connection refused"))).boxed();
+            }
+            FakeEmbeddingProvider.embed(texts)
+        }
+    }
+
+    #[gpui::test]
+    async fn test_search_when_disabled(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "a.txt": "a" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let project_index = cx.new(|cx| ProjectIndex::new(project, None, cx));
+
+        assert_eq!(
+            project_index.read_with(cx, |index, _| index.status()),
+            Status::Disabled
+        );
+        assert!(
+            project_index
+                .read_with(cx, |index, cx| index.search("a".into(), 1, cx))
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! A local index of the meaning of the code in a project.
+//!
+//! Files in local worktrees are split into chunks along their outline, and an
+//! embedding of each chunk is computed by the configured provider and stored
+//! in SQLite. The index is updated as files change, and is searched by the
+//! agent's `semantic_search` tool and by the semantic mode of project search.
+
+mod chunking;
+mod db;
+mod embedding;
+mod project_index;
+mod semantic_index_settings;
+
+use anyhow::{Context as _, anyhow};
+use collections::HashMap;
+use gpui::{App, AppContext as _, Entity, EntityId, Global, Task};
+use project::Project;
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use db::SemanticIndexDatabase;
+#[cfg(any(test, feature = "test-support"))]
+pub use embedding::FakeEmbeddingProvider;
+pub use embedding::{Embedding, EmbeddingProvider, HttpEmbeddingProvider};
+pub use project_index::{IndexBackend, ProjectIndex, SearchResult, Status};
+pub use semantic_index_settings::SemanticIndexSettings;
+
+pub fn init(cx: &mut App) {
+    cx.set_global(SemanticDb::default());
+    SemanticDb::update_backend(cx);
+
+    let mut settings = SemanticIndexSettings::get_global(cx).clone();
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_settings = SemanticIndexSettings::get_global(cx);
+        if *new_settings != settings {
+            settings = new_settings.clone();
+            SemanticDb::update_backend(cx);
+        }
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        SemanticDb::register_project(workspace.project().clone(), cx);
+    })
+    .detach();
+}
+
+/// The semantic indices of the projects open in workspaces.
+#[derive(Default)]
+pub struct SemanticDb {
+    backend: Option<IndexBackend>,
+    project_indices: HashMap<EntityId, Entity<ProjectIndex>>,
+    update_backend_task: Option<Task<()>>,
+}
+
+impl Global for SemanticDb {}
+
+impl SemanticDb {
+    /// Returns the index of a project, if it's open in a workspace and local.
+    pub fn project_index(project: &Entity<Project>, cx: &App) -> Option<Entity<ProjectIndex>> {
+        cx.try_global::<Self>()?
+            .project_indices
+            .get(&project.entity_id())
+            .cloned()
+    }
+
+    fn register_project(project: Entity<Project>, cx: &mut App) {
+        let project_id = project.entity_id();
+        if !project.read(cx).is_local()
+            || cx
+                .global::<Self>()
+                .project_indices
+                .contains_key(&project_id)
+        {
+            return;
+        }
+
+        let backend = cx.global::<Self>().backend.clone();
+        let project_index = cx.new(|cx| ProjectIndex::new(project.clone(), backend, cx));
+        cx.observe_release(&project, move |_, cx| {
+            cx.global_mut::<Self>().project_indices.remove(&project_id);
+        })
+        .detach();
+        cx.global_mut::<Self>()
+            .project_indices
+            .insert(project_id, project_index);
+    }
+
+    fn update_backend(cx: &mut App) {
+        let settings = SemanticIndexSettings::get_global(cx).clone();
+        let backend = settings.enabled.then(|| {
+            let db = SemanticIndexDatabase::connect(cx);
+            let embedding_provider = HttpEmbeddingProvider::from_settings(&settings, cx);
+            async move {
+                let db = db.await.map_err(|error| anyhow!("{error:#}"))?;
+                let embedding_provider = embedding_provider.await?;
+                anyhow::Ok(IndexBackend {
+                    db,
+                    embedding_provider,
+                })
+            }
+        });
+
+        let task = cx.spawn(async move |cx| {
+            let backend = match backend {
+                Some(backend) => backend
+                    .await
+                    .context("setting up the semantic index")
+                    .log_err(),
+                None => None,
+            };
+            cx.update(|cx| {
+                let this = cx.global_mut::<Self>();
+                this.backend = backend.clone();
+                let project_indices = this.project_indices.values().cloned().collect::<Vec<_>>();
+                for project_index in project_indices {
+                    project_index.update(cx, |project_index, cx| {
+                        project_index.set_backend(backend.clone(), cx)
+                    });
+                }
+            });
+        });
+        cx.global_mut::<Self>().update_backend_task = Some(task);
+    }
+}
@@ -0,0 +1,32 @@
+use settings::{EmbeddingProviderContent, RegisterSetting, Settings};
+
+/// The settings for the local semantic index of the project.
+#[derive(Clone, Debug, Default, PartialEq, RegisterSetting)]
+pub struct SemanticIndexSettings {
+    /// Whether to embed the files of local worktrees.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The service that computes embeddings.
+    ///
+    /// Default: ollama
+    pub provider: EmbeddingProviderContent,
+    /// The embedding model to request from the provider.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: String,
+    /// The URL of the provider's API, if not the provider's default.
+    pub api_url: Option<String>,
+}
+
+impl Settings for SemanticIndexSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let content = content.semantic_index.clone().unwrap();
+        Self {
+            enabled: content.enabled.unwrap(),
+            provider: content.provider.unwrap(),
+            model: content.model.unwrap(),
+            api_url: content.api_url,
+        }
+    }
+}
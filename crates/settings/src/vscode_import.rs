@@ -184,6 +184,7 @@ impl VsCodeSettings {
             editor: self.editor_settings_content(),
             extension: ExtensionSettingsContent::default(),
             file_finder: None,
+            file_history: self.file_history_settings_content(),
             git: self.git_settings_content(),
             git_panel: self.git_panel_settings_content(),
             global_lsp_settings: skip_default(GlobalLspSettingsContent {
@@ -194,6 +195,7 @@ impl VsCodeSettings {
             image_viewer: None,
             journal: None,
             language_models: None,
+            large_file_viewer: None,
             line_indicator_format: None,
             log: None,
             message_editor: None,
@@ -206,8 +208,10 @@ impl VsCodeSettings {
             proxy: self.read_string("http.proxy"),
             remote: RemoteSettingsContent::default(),
             repl: None,
+            semantic_index: None,
             server_url: None,
             session: None,
+            spelling: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -215,6 +219,7 @@ impl VsCodeSettings {
             terminal: self.terminal_settings_content(),
             theme: Box::new(self.theme_settings_content()),
             title_bar: None,
+            todo_panel: None,
             vim: None,
             vim_mode: None,
             workspace: self.workspace_settings_content(),
@@ -253,6 +258,7 @@ impl VsCodeSettings {
                 "all" => Some(CurrentLineHighlight::All),
                 _ => None,
             }),
+            clipboard_history: None,
             diagnostics_max_severity: None,
             double_click_in_multibuffer: None,
             drag_and_drop_selection: None,
@@ -484,6 +490,15 @@ impl VsCodeSettings {
         })
     }
 
+    fn file_history_settings_content(&self) -> Option<FileHistorySettingsContent> {
+        skip_default(FileHistorySettingsContent {
+            enabled: self.read_bool("workbench.localHistory.enabled"),
+            max_snapshots_per_file: self.read_usize("workbench.localHistory.maxFileEntries"),
+            max_file_size_kb: self.read_u64("workbench.localHistory.maxFileSize"),
+            ..Default::default()
+        })
+    }
+
     fn git_panel_settings_content(&self) -> Option<GitPanelSettingsContent> {
         skip_default(GitPanelSettingsContent {
             button: self.read_bool("git.enabled"),
@@ -510,6 +525,7 @@ impl VsCodeSettings {
             slash_commands: None,
             git_hosting_providers: None,
             disable_ai: None,
+            layouts: None,
         }
     }
 
@@ -589,6 +605,7 @@ impl VsCodeSettings {
                 "off" => Some(SoftWrap::None),
                 _ => None,
             }),
+            spell_check: None,
             tab_size: self
                 .read_u32("editor.tabSize")
                 .and_then(|n| NonZeroU32::new(n)),
@@ -807,6 +824,7 @@ impl VsCodeSettings {
             sticky_scroll: None,
             auto_open: None,
             diagnostic_badges: None,
+            file_nesting: self.file_nesting_settings_content(),
         };
 
         if let (Some(false), Some(false)) = (
@@ -820,6 +838,24 @@ impl VsCodeSettings {
         skip_default(project_panel_settings)
     }
 
+    fn file_nesting_settings_content(&self) -> Option<ProjectPanelFileNestingSettingsContent> {
+        let patterns = self
+            .read_value("explorer.fileNesting.patterns")
+            .and_then(|v| v.as_object())
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|(parent, children)| {
+                        Some((parent.clone(), children.as_str()?.to_string()))
+                    })
+                    .collect()
+            });
+        skip_default(ProjectPanelFileNestingSettingsContent {
+            enabled: self.read_bool("explorer.fileNesting.enabled"),
+            patterns,
+        })
+    }
+
     fn telemetry_settings_content(&self) -> Option<TelemetrySettingsContent> {
         self.read_enum("telemetry.telemetryLevel", |level| {
             let (metrics, diagnostics) = match level {
@@ -139,6 +139,19 @@ pub struct AgentSettingsContent {
     /// `always_confirm`) match against the tool's text input (command, path,
     /// URL, etc.).
     pub tool_permissions: Option<ToolPermissionsContent>,
+    /// Confines the commands run by the agent's terminal tool in a sandbox.
+    ///
+    /// Only supported for local projects on Linux, where it requires
+    /// bubblewrap (`bwrap`) to be installed.
+    pub terminal_sandbox: Option<TerminalSandboxSettingsContent>,
+    /// Shell commands to run at points of the agent's turns, such as after it
+    /// edits files. Their output is sent to the model.
+    pub hooks: Option<AgentHooksContent>,
+    /// Limits on the tokens and cost the agent can use, per thread and per
+    /// day. Limits per profile are set in the profiles.
+    pub budgets: Option<AgentBudgetsContent>,
+    /// The providers the agent's `web_search` tool can search with.
+    pub web_search: Option<WebSearchSettingsContent>,
 }
 
 impl AgentSettingsContent {
@@ -229,6 +242,12 @@ pub struct AgentProfileContent {
     pub context_servers: IndexMap<Arc<str>, ContextServerPresetContent>,
     /// The default language model selected when using this profile.
     pub default_model: Option<LanguageModelSelection>,
+    /// Limits on the tokens and cost the agent can use per day in threads
+    /// using this profile.
+    pub budget: Option<AgentBudgetContent>,
+    /// The provider the `web_search` tool searches with in threads using this
+    /// profile. Uses `agent.web_search.provider` when unset.
+    pub web_search_provider: Option<WebSearchProviderContent>,
 }
 
 #[with_fallible_options]
@@ -500,6 +519,215 @@ pub struct ToolPermissionsContent {
     pub tools: HashMap<Arc<str>, ToolRulesContent>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Hooks run before a tool is called. The tool isn't called if a hook
+    /// exits with a non-zero status, and a hook can replace the tool's input
+    /// by printing `{"tool_input": ...}`.
+    ///
+    /// Default: []
+    pub before_tool_call: Option<Vec<AgentHookContent>>,
+    /// Hooks run after a tool is called.
+    ///
+    /// Default: []
+    pub after_tool_call: Option<Vec<AgentHookContent>>,
+    /// Hooks run after a tool call in which the agent edited files, such as
+    /// formatters or linters.
+    ///
+    /// Default: []
+    pub after_edit: Option<Vec<AgentHookContent>>,
+    /// Hooks run when the agent finishes its turn. When a hook exits with a
+    /// non-zero status, its output is sent to the model and the turn goes on.
+    ///
+    /// Default: []
+    pub turn_finished: Option<Vec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The shell command to run in the project's first worktree. It receives
+    /// the event as JSON on its standard input.
+    #[serde(default)]
+    pub command: String,
+    /// The label of a task from the project's or the global `tasks.json` to
+    /// run instead of `command`. Like commands, it receives the event as JSON
+    /// on its standard input.
+    pub task: Option<String>,
+    /// The names of the tools whose calls run the hook. Calls to any tool run
+    /// it when unset. Ignored by `turn_finished` hooks.
+    pub tools: Option<Vec<String>>,
+    /// How long the hook can run before it's stopped and considered failed.
+    ///
+    /// Default: 60
+    pub timeout_seconds: Option<u64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentBudgetsContent {
+    /// The fraction of a budget after which the agent warns that it's close
+    /// to being reached.
+    ///
+    /// Default: 0.8
+    pub warning_threshold: Option<f64>,
+    /// Limits on the tokens and cost of each thread.
+    pub thread: Option<AgentBudgetContent>,
+    /// Limits on the tokens and cost of all threads on each day.
+    pub daily: Option<AgentBudgetContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentBudgetContent {
+    /// The maximum number of tokens, including cached ones.
+    pub max_tokens: Option<u64>,
+    /// The maximum cost, in US dollars. Only requests to models whose token
+    /// prices are known count toward it.
+    pub max_cost: Option<f64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct WebSearchSettingsContent {
+    /// The provider to search with. When unset, Zed's provider is used with
+    /// the models provided by Zed, and web search isn't available with other
+    /// models.
+    ///
+    /// Default: null
+    pub provider: Option<WebSearchProviderContent>,
+    /// A SearXNG instance with its JSON output format enabled.
+    pub searxng: Option<SearxngSettingsContent>,
+    /// The Brave Search API. Its API key is read from the
+    /// `BRAVE_SEARCH_API_KEY` environment variable.
+    pub brave: Option<WebSearchApiSettingsContent>,
+    /// Kagi's search API. Its API key is read from the `KAGI_API_KEY`
+    /// environment variable.
+    pub kagi: Option<WebSearchApiSettingsContent>,
+    /// Any HTTP endpoint that returns search results as JSON.
+    pub custom: Option<CustomWebSearchSettingsContent>,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WebSearchProviderContent {
+    /// Zed's web search, only available with the models provided by Zed.
+    #[serde(rename = "zed.dev")]
+    ZedDev,
+    Searxng,
+    Brave,
+    Kagi,
+    Custom,
+}
+
+impl WebSearchProviderContent {
+    /// The ID the provider is registered with.
+    pub fn id(&self) -> &'static str {
+        match self {
+            WebSearchProviderContent::ZedDev => "zed.dev",
+            WebSearchProviderContent::Searxng => "searxng",
+            WebSearchProviderContent::Brave => "brave",
+            WebSearchProviderContent::Kagi => "kagi",
+            WebSearchProviderContent::Custom => "custom",
+        }
+    }
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SearxngSettingsContent {
+    /// The URL of the SearXNG instance, such as `http://localhost:8888`.
+    pub url: Option<String>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct WebSearchApiSettingsContent {
+    /// The URL of the search API. Uses the provider's URL when unset.
+    ///
+    /// Default: null
+    pub api_url: Option<String>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct CustomWebSearchSettingsContent {
+    /// The URL to search with. The query is sent in the `query_parameter`
+    /// parameter of the URL for `GET` requests, and in that field of a JSON
+    /// body for `POST` requests.
+    pub url: Option<String>,
+    /// The HTTP method of search requests.
+    ///
+    /// Default: "get"
+    pub method: Option<CustomWebSearchMethod>,
+    /// The name of the parameter or field the query is sent in.
+    ///
+    /// Default: "q"
+    pub query_parameter: Option<String>,
+    /// Headers to send with search requests. `${NAME}` in a value is replaced
+    /// with the value of the `NAME` environment variable, so that API keys
+    /// don't have to be stored in the settings.
+    ///
+    /// Default: {}
+    pub headers: Option<HashMap<String, String>>,
+    /// Where the results are in the response, as JSON pointers.
+    pub fields: Option<CustomWebSearchFieldsContent>,
+}
+
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomWebSearchMethod {
+    #[default]
+    Get,
+    Post,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct CustomWebSearchFieldsContent {
+    /// The JSON pointer of the array of results in the response.
+    ///
+    /// Default: "/results"
+    pub results: Option<String>,
+    /// The JSON pointer of a result's title, relative to the result.
+    ///
+    /// Default: "/title"
+    pub title: Option<String>,
+    /// The JSON pointer of a result's URL, relative to the result.
+    ///
+    /// Default: "/url"
+    pub url: Option<String>,
+    /// The JSON pointer of a result's snippet, relative to the result.
+    ///
+    /// Default: "/snippet"
+    pub text: Option<String>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct TerminalSandboxSettingsContent {
+    /// Whether to run the terminal tool's commands in a sandbox, which only
+    /// allows them to write to the project's worktrees and the temporary
+    /// directory. When the sandbox can't be set up, commands aren't run.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Whether sandboxed commands can access the network.
+    ///
+    /// Default: false
+    pub allow_network: Option<bool>,
+    /// Additional absolute paths that sandboxed commands can write to, such
+    /// as `~/.cargo`.
+    ///
+    /// Default: []
+    pub writable_paths: Option<Vec<String>>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct ToolRulesContent {
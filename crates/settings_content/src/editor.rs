@@ -199,6 +199,9 @@ pub struct EditorSettingsContent {
     /// Drag and drop related settings
     pub drag_and_drop_selection: Option<DragAndDropSelectionContent>,
 
+    /// Settings for the history of text copied and cut in editors.
+    pub clipboard_history: Option<ClipboardHistoryContent>,
+
     /// How to render LSP `textDocument/documentColor` colors in the editor.
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
@@ -427,6 +430,10 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub breakpoints: Option<bool>,
+    /// Whether to show bookmarks in the gutter.
+    ///
+    /// Default: true
+    pub bookmarks: Option<bool>,
     /// Whether to show fold buttons in the gutter.
     ///
     /// Default: true
@@ -860,6 +867,29 @@ pub struct DragAndDropSelectionContent {
     pub delay: Option<DelayMs>,
 }
 
+/// Settings for the history of text copied and cut in editors.
+#[with_fallible_options]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct ClipboardHistoryContent {
+    /// Whether to record text copied and cut in editors, so that it can be
+    /// pasted again with `clipboard_history: toggle` (`ctrl-k ctrl-v`, or
+    /// `cmd-k cmd-v` on macOS).
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+
+    /// The maximum number of entries kept in the history. The oldest entries
+    /// are discarded first.
+    ///
+    /// Default: 50
+    pub max_entries: Option<usize>,
+
+    /// Copied text larger than this many kilobytes is not recorded.
+    ///
+    /// Default: 256
+    pub max_entry_size_kb: Option<usize>,
+}
+
 /// When to show the minimap in the editor.
 ///
 /// Default: never
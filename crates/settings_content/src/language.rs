@@ -640,6 +640,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: false
     pub colorize_brackets: Option<bool>,
+    /// Whether to check the spelling of comments, strings and prose with the
+    /// dictionaries from the `spelling` settings.
+    ///
+    /// Default: false
+    pub spell_check: Option<bool>,
 }
 
 /// Controls how whitespace should be displayedin the editor.
@@ -4,7 +4,7 @@ use std::{
 };
 
 use anyhow::Context;
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, IndexMap};
 use gpui::Rgba;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -13,8 +13,9 @@ use settings_macros::{MergeFrom, with_fallible_options};
 use util::serde::default_true;
 
 use crate::{
-    AllLanguageSettingsContent, DelayMs, ExtendingVec, ParseStatus, ProjectTerminalSettingsContent,
-    RootUserSettings, SaturatingBool, SlashCommandSettings, fallible_options,
+    AllLanguageSettingsContent, DelayMs, ExtendingVec, PaneLayoutContent, ParseStatus,
+    ProjectTerminalSettingsContent, RootUserSettings, SaturatingBool, SlashCommandSettings,
+    fallible_options,
 };
 
 #[with_fallible_options]
@@ -88,6 +89,12 @@ pub struct ProjectSettingsContent {
     ///
     /// Default: false
     pub disable_ai: Option<SaturatingBool>,
+
+    /// Named arrangements of panes, open files and docks that can be
+    /// switched to from the pane layout picker.
+    ///
+    /// Default: {}
+    pub layouts: Option<IndexMap<String, PaneLayoutContent>>,
 }
 
 #[with_fallible_options]
@@ -133,6 +133,9 @@ pub struct SettingsContent {
     /// Configuration for Diagnostics-related features.
     pub diagnostics: Option<DiagnosticsSettingsContent>,
 
+    /// The settings for the local history of files.
+    pub file_history: Option<FileHistorySettingsContent>,
+
     /// Configuration for Git-related features
     pub git: Option<GitSettings>,
 
@@ -142,6 +145,15 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    /// The settings for opening files too large for the editor.
+    pub large_file_viewer: Option<LargeFileViewerSettingsContent>,
+
+    /// The settings for the local semantic index of the project.
+    pub semantic_index: Option<SemanticIndexSettingsContent>,
+
+    /// The dictionaries and word list used for spell checking.
+    pub spelling: Option<SpellingSettingsContent>,
+
     pub repl: Option<ReplSettingsContent>,
 
     /// Whether or not to enable Helix mode.
@@ -189,6 +201,9 @@ pub struct SettingsContent {
 
     pub title_bar: Option<TitleBarSettingsContent>,
 
+    /// Configuration for the TODO panel.
+    pub todo_panel: Option<TodoPanelSettingsContent>,
+
     /// Whether or not to enable Vim mode.
     ///
     /// Default: false
@@ -966,6 +981,41 @@ pub struct OutlinePanelSettingsContent {
     pub expand_outlines_with_depth: Option<usize>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct TodoPanelSettingsContent {
+    /// Whether to show the TODO panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the TODO panel.
+    ///
+    /// Default: left
+    pub dock: Option<DockSide>,
+    /// Default width of the TODO panel in pixels.
+    ///
+    /// Default: 300
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub default_width: Option<f32>,
+    /// The tags to list when they appear in comments, such as `TODO` or
+    /// `FIXME`. Setting this replaces the default tags.
+    ///
+    /// Default: TODO, FIXME, HACK and NOTE
+    pub tags: Option<Vec<TodoTagContent>>,
+}
+
+/// A tag listed in the TODO panel.
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct TodoTagContent {
+    /// The tag, matched case-sensitively as a whole word.
+    #[serde(default)]
+    pub name: String,
+    /// The color of the tag, as a hex string such as "#e06c75". When unset,
+    /// the theme's accent color is used.
+    pub color: Option<String>,
+}
+
 #[derive(
     Clone,
     Copy,
@@ -1054,6 +1104,114 @@ pub enum ImageFileSizeUnit {
     Decimal,
 }
 
+/// The settings for opening files too large for the editor.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LargeFileViewerSettingsContent {
+    /// Files of at least this many megabytes are opened in a read-only view
+    /// which reads lines from disk on demand, instead of in the editor.
+    /// Set to 0 to always open files in the editor.
+    ///
+    /// Default: 64
+    pub threshold_mb: Option<u64>,
+}
+
+/// The settings for the local history of files.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct FileHistorySettingsContent {
+    /// Whether to keep a snapshot of local files when they are opened, saved
+    /// or changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The maximum number of snapshots kept for each file. Older snapshots
+    /// are discarded first.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: Option<usize>,
+    /// Snapshots older than this many days are discarded.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// The maximum size of the history of all files, in megabytes. The
+    /// oldest snapshots are discarded when it is exceeded.
+    ///
+    /// Default: 256
+    pub max_total_size_mb: Option<u64>,
+    /// Files larger than this many kilobytes are not snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<u64>,
+}
+
+/// The dictionaries and word list used for spell checking, in languages
+/// where `spell_check` is enabled.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SpellingSettingsContent {
+    /// The Hunspell dictionaries to check words against, by name, such as
+    /// "en_US" or "de_DE". Each is read from `<name>.aff` and `<name>.dic`
+    /// files in the `dictionaries` directory of Zed's config directory, or in
+    /// the system's Hunspell directories.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Words to accept in every project, in addition to those in the
+    /// dictionaries and in a project's `.zed/dictionary.txt`.
+    ///
+    /// Default: []
+    pub words: Option<Vec<String>>,
+}
+
+/// The settings for the local semantic index of the project.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SemanticIndexSettingsContent {
+    /// Whether to embed the files of local worktrees so that they can be
+    /// searched by meaning from the agent and from project search.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The service that computes embeddings.
+    ///
+    /// Default: "ollama"
+    pub provider: Option<EmbeddingProviderContent>,
+    /// The embedding model to request from the provider.
+    ///
+    /// Default: "nomic-embed-text"
+    pub model: Option<String>,
+    /// The URL of the provider's API. Uses the provider's default URL when unset.
+    ///
+    /// Default: null
+    pub api_url: Option<String>,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    Default,
+    PartialEq,
+    Eq,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderContent {
+    /// A local Ollama server.
+    #[default]
+    Ollama,
+    /// A local LM Studio server.
+    LmStudio,
+    /// OpenAI, or any service with an OpenAI-compatible embeddings endpoint.
+    OpenAi,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
 pub struct RemoteSettingsContent {
@@ -1,6 +1,6 @@
 use std::num::NonZeroUsize;
 
-use collections::HashMap;
+use collections::{HashMap, IndexMap};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings_macros::{MergeFrom, with_fallible_options};
@@ -743,6 +743,29 @@ pub struct ProjectPanelSettingsContent {
     ///
     /// Default: true
     pub diagnostic_badges: Option<bool>,
+    /// Settings for nesting related files under a parent file.
+    pub file_nesting: Option<ProjectPanelFileNestingSettingsContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq, Default)]
+pub struct ProjectPanelFileNestingSettingsContent {
+    /// Whether to nest related files under a parent file in the project panel.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Maps the name pattern of a parent file to a comma-separated list of
+    /// patterns for the files nested under it, in the same directory.
+    ///
+    /// A parent pattern may contain a single `*`, whose match can be used in
+    /// the child patterns as `$(capture)`. Child patterns may also use
+    /// `$(basename)` and `$(extname)` for the parent file's name without its
+    /// extension and for its extension, as well as `*` wildcards. An empty
+    /// list disables a pattern from the defaults.
+    ///
+    /// For example, `"*.ts": "$(capture).js, $(capture).d.ts"` nests the
+    /// compiled output of a TypeScript file under it.
+    pub patterns: Option<IndexMap<String, String>>,
 }
 
 #[derive(
@@ -920,3 +943,74 @@ impl DocumentSymbols {
         self == &Self::On
     }
 }
+
+/// An arrangement of panes, their open items and the docks that can be
+/// switched to with `pane_layouts: toggle`.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct PaneLayoutContent {
+    /// The splits and panes of the center of the workspace.
+    ///
+    /// Default: null
+    pub center: Option<PaneLayoutMemberContent>,
+    /// Which docks are open and which of their panels are active. Docks
+    /// left out are kept as they are.
+    ///
+    /// Default: null
+    pub docks: Option<PaneLayoutDocksContent>,
+}
+
+/// Either a split containing other splits and panes, or a single pane.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum PaneLayoutMemberContent {
+    Split {
+        /// Whether the members are laid out side by side (`horizontal`) or
+        /// stacked on top of each other (`vertical`).
+        axis: PaneLayoutAxis,
+        members: Vec<PaneLayoutMemberContent>,
+        /// The relative sizes of the members, one per member.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flexes: Option<Vec<f32>>,
+    },
+    Pane {
+        /// The paths of the files open in the pane, relative to the project
+        /// root or absolute.
+        #[serde(default)]
+        items: Vec<String>,
+        /// The index of the item shown in the pane.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        active_item: Option<usize>,
+        /// Whether this pane is focused after switching to the layout.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        active: bool,
+    },
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PaneLayoutAxis {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct PaneLayoutDocksContent {
+    pub left: Option<PaneLayoutDockContent>,
+    pub right: Option<PaneLayoutDockContent>,
+    pub bottom: Option<PaneLayoutDockContent>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct PaneLayoutDockContent {
+    /// Whether the dock is open.
+    pub visible: Option<bool>,
+    /// The panel to show in the dock, such as "Project Panel", "GitPanel" or
+    /// "TerminalPanel".
+    pub panel: Option<String>,
+}
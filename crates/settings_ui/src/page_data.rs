@@ -1811,7 +1811,7 @@ fn editor_page() -> SettingsPage {
         ]
     }
 
-    fn gutter_section() -> [SettingsPageItem; 8] {
+    fn gutter_section() -> [SettingsPageItem; 9] {
         [
             SettingsPageItem::SectionHeader("Gutter"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -1896,6 +1896,29 @@ fn editor_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Show Bookmarks",
+                description: "Show bookmarks in the gutter.",
+                field: Box::new(SettingField {
+                    json_path: Some("gutter.bookmarks"),
+                    pick: |settings_content| {
+                        settings_content
+                            .editor
+                            .gutter
+                            .as_ref()
+                            .and_then(|gutter| gutter.bookmarks.as_ref())
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .editor
+                            .gutter
+                            .get_or_insert_default()
+                            .bookmarks = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Show Folds",
                 description: "Show code folding controls in the gutter.",
@@ -4819,6 +4842,67 @@ fn panels_page() -> SettingsPage {
         ]
     }
 
+    fn file_nesting_section() -> [SettingsPageItem; 3] {
+        [
+            SettingsPageItem::SectionHeader("File Nesting"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Enabled",
+                description: "Whether to nest related files under a parent file in the project panel.",
+                field: Box::new(SettingField {
+                    json_path: Some("project_panel.file_nesting.enabled"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project_panel
+                            .as_ref()?
+                            .file_nesting
+                            .as_ref()?
+                            .enabled
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .project_panel
+                            .get_or_insert_default()
+                            .file_nesting
+                            .get_or_insert_default()
+                            .enabled = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Patterns",
+                description: "Patterns of parent files and of the files nested under them.",
+                field: Box::new(
+                    SettingField {
+                        json_path: Some("project_panel.file_nesting.patterns"),
+                        pick: |settings_content| {
+                            settings_content
+                                .project_panel
+                                .as_ref()?
+                                .file_nesting
+                                .as_ref()?
+                                .patterns
+                                .as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .project_panel
+                                .get_or_insert_default()
+                                .file_nesting
+                                .get_or_insert_default()
+                                .patterns = value;
+                        },
+                    }
+                    .unimplemented(),
+                ),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn terminal_panel_section() -> [SettingsPageItem; 2] {
         [
             SettingsPageItem::SectionHeader("Terminal Panel"),
@@ -5325,6 +5409,56 @@ fn panels_page() -> SettingsPage {
         ]
     }
 
+    fn todo_panel_section() -> [SettingsPageItem; 4] {
+        [
+            SettingsPageItem::SectionHeader("TODO Panel"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "TODO Panel Button",
+                description: "Show the TODO panel button in the status bar.",
+                field: Box::new(SettingField {
+                    json_path: Some("todo_panel.button"),
+                    pick: |settings_content| settings_content.todo_panel.as_ref()?.button.as_ref(),
+                    write: |settings_content, value| {
+                        settings_content.todo_panel.get_or_insert_default().button = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "TODO Panel Dock",
+                description: "Where to dock the TODO panel.",
+                field: Box::new(SettingField {
+                    json_path: Some("todo_panel.dock"),
+                    pick: |settings_content| settings_content.todo_panel.as_ref()?.dock.as_ref(),
+                    write: |settings_content, value| {
+                        settings_content.todo_panel.get_or_insert_default().dock = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "TODO Panel Default Width",
+                description: "Default width of the TODO panel in pixels.",
+                field: Box::new(SettingField {
+                    json_path: Some("todo_panel.default_width"),
+                    pick: |settings_content| {
+                        settings_content.todo_panel.as_ref()?.default_width.as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .todo_panel
+                            .get_or_insert_default()
+                            .default_width = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn collaboration_panel_section() -> [SettingsPageItem; 4] {
         [
             SettingsPageItem::SectionHeader("Collaboration Panel"),
@@ -5463,11 +5597,13 @@ fn panels_page() -> SettingsPage {
         items: concat_sections![
             project_panel_section(),
             auto_open_files_section(),
+            file_nesting_section(),
             terminal_panel_section(),
             outline_panel_section(),
             git_panel_section(),
             debugger_panel_section(),
             notification_panel_section(),
+            todo_panel_section(),
             collaboration_panel_section(),
             agent_panel_section(),
         ],
@@ -7239,6 +7375,64 @@ fn ai_page() -> SettingsPage {
         ]
     }
 
+    fn terminal_sandbox_section() -> [SettingsPageItem; 3] {
+        [
+            SettingsPageItem::SectionHeader("Terminal Sandbox"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Sandbox Terminal Commands",
+                description: "Only allow the agent's terminal commands to write to the project and the temporary directory. Requires Linux and bubblewrap.",
+                field: Box::new(SettingField {
+                    json_path: Some("agent.terminal_sandbox.enabled"),
+                    pick: |settings_content| {
+                        settings_content
+                            .agent
+                            .as_ref()?
+                            .terminal_sandbox
+                            .as_ref()?
+                            .enabled
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .agent
+                            .get_or_insert_default()
+                            .terminal_sandbox
+                            .get_or_insert_default()
+                            .enabled = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Allow Network Access",
+                description: "Whether sandboxed terminal commands can access the network.",
+                field: Box::new(SettingField {
+                    json_path: Some("agent.terminal_sandbox.allow_network"),
+                    pick: |settings_content| {
+                        settings_content
+                            .agent
+                            .as_ref()?
+                            .terminal_sandbox
+                            .as_ref()?
+                            .allow_network
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .agent
+                            .get_or_insert_default()
+                            .terminal_sandbox
+                            .get_or_insert_default()
+                            .allow_network = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn context_servers_section() -> [SettingsPageItem; 2] {
         [
             SettingsPageItem::SectionHeader("Context Servers"),
@@ -7322,6 +7516,7 @@ fn ai_page() -> SettingsPage {
         items: concat_sections![
             general_section(),
             agent_configuration_section(),
+            terminal_sandbox_section(),
             context_servers_section(),
             edit_prediction_language_settings_section(),
             edit_prediction_display_sub_section()
@@ -8415,7 +8610,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
         ]
     }
 
-    fn miscellaneous_section() -> [SettingsPageItem; 6] {
+    fn miscellaneous_section() -> [SettingsPageItem; 7] {
         [
             SettingsPageItem::SectionHeader("Miscellaneous"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -8514,6 +8709,25 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER | PROJECT,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Spell Check",
+                description: "Whether to check the spelling of comments, strings and prose.",
+                field: Box::new(SettingField {
+                    json_path: Some("languages.$(language).spell_check"),
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| {
+                            language.spell_check.as_ref()
+                        })
+                    },
+                    write: |settings_content, value| {
+                        language_settings_field_mut(settings_content, value, |language, value| {
+                            language.spell_check = value;
+                        })
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
         ]
     }
 
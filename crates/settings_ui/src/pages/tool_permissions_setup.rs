@@ -1397,16 +1397,23 @@ mod tests {
         const EXCLUDED_TOOLS: &[&str] = &[
             // Read-only / low-risk tools that don't call decide_permission_from_settings
             "diagnostics",
+            "find_definition",
             "find_path",
+            "find_references",
             "grep",
+            "hover",
             "list_directory",
             "now",
             "open",
             "read_file",
+            "semantic_search",
+            "symbol_search",
             "thinking",
-            // streaming_edit_file uses "edit_file" for permission lookups,
-            // so its rules are configured under the edit_file entry.
+            // streaming_edit_file and rename_symbol use "edit_file" for
+            // permission lookups, so their rules are configured under the
+            // edit_file entry.
             "streaming_edit_file",
+            "rename_symbol",
             // Subagent permission checks happen at the level of individual
             // tool calls within the subagent, not at the spawning level.
             "spawn_agent",
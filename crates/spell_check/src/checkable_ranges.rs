@@ -0,0 +1,251 @@
+use language::{BufferSnapshot, Node, SyntaxLayer, ToOffset as _};
+use std::ops::Range;
+
+/// Languages whose text is prose, checked in full except for the nodes in
+/// [`PROSE_EXCLUDED_KINDS`].
+const PROSE_LANGUAGES: &[&str] = &["Markdown", "Markdown-Inline", "Git Commit"];
+
+/// Nodes of prose languages that hold code, links or metadata rather than
+/// prose.
+const PROSE_EXCLUDED_KINDS: &[&str] = &[
+    "code_span",
+    "comment",
+    "fenced_code_block",
+    "html_block",
+    "html_tag",
+    "indented_code_block",
+    "link_destination",
+    "minus_metadata",
+    "plus_metadata",
+    "uri_autolink",
+];
+
+/// Parts of strings and comments that hold code, such as `{name}` in
+/// `f"Hello {name}"`.
+const CODE_EXCLUDED_KIND_SUBSTRINGS: &[&str] = &["escape", "interpolation", "substitution"];
+
+/// Returns the sorted, disjoint ranges of the buffer within `range` whose
+/// text should be spell checked: the comments and strings of code, and the
+/// prose of Markdown and plain text documents.
+pub fn checkable_ranges(snapshot: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut prose_ranges = Vec::new();
+    let mut prose_excluded_ranges = Vec::new();
+    let mut code_ranges = Vec::new();
+    let mut has_layers = false;
+    for layer in snapshot.syntax_layers_for_range(range.clone(), true) {
+        has_layers = true;
+        let root = layer.node();
+        if PROSE_LANGUAGES.contains(&layer.language.name().as_ref()) {
+            prose_ranges.extend(
+                layer_ranges(&layer, snapshot)
+                    .into_iter()
+                    .filter_map(|layer_range| intersect(&layer_range, &range)),
+            );
+            prose_excluded_ranges.extend(
+                collect_nodes(root, &range, |node| {
+                    PROSE_EXCLUDED_KINDS.contains(&node.kind())
+                })
+                .into_iter()
+                .map(|node| node.byte_range()),
+            );
+        } else {
+            let string_and_comment_nodes = collect_nodes(root, &range, |node| {
+                let kind = node.kind();
+                kind.contains("comment") || kind.contains("string")
+            });
+            for node in string_and_comment_nodes {
+                code_ranges.extend(
+                    without_code(node)
+                        .into_iter()
+                        .filter_map(|node_range| intersect(&node_range, &range)),
+                );
+            }
+        }
+    }
+
+    // Documents without a grammar, such as plain text, are all prose.
+    if !has_layers
+        && snapshot
+            .language()
+            .is_none_or(|language| language.grammar().is_none())
+    {
+        prose_ranges.push(range);
+    }
+
+    let mut ranges = subtract(merge(prose_ranges), &merge(prose_excluded_ranges));
+    ranges.extend(code_ranges);
+    merge(ranges)
+}
+
+/// Returns the ranges of the buffer covered by `layer`, which for injected
+/// layers may be several disjoint parts of the host document.
+fn layer_ranges(layer: &SyntaxLayer, snapshot: &BufferSnapshot) -> Vec<Range<usize>> {
+    match layer.included_sub_ranges {
+        Some(sub_ranges) => sub_ranges
+            .iter()
+            .map(|sub_range| sub_range.start.to_offset(snapshot)..sub_range.end.to_offset(snapshot))
+            .collect(),
+        None => vec![layer.node().byte_range()],
+    }
+}
+
+/// Returns the outermost nodes intersecting `range` for which `predicate`
+/// returns true.
+fn collect_nodes<'a>(
+    root: Node<'a>,
+    range: &Range<usize>,
+    predicate: impl Fn(&Node) -> bool,
+) -> Vec<Node<'a>> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let node_range = node.byte_range();
+        if node_range.end <= range.start || node_range.start >= range.end {
+            continue;
+        }
+        if predicate(&node) {
+            nodes.push(node);
+        } else {
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+    }
+    nodes
+}
+
+/// Returns the range of a string or comment node, without the children that
+/// hold code.
+fn without_code(node: Node) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = node.start_byte();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let kind = child.kind();
+        if CODE_EXCLUDED_KIND_SUBSTRINGS
+            .iter()
+            .any(|substring| kind.contains(substring))
+        {
+            ranges.push(start..child.start_byte());
+            start = child.end_byte();
+        }
+    }
+    ranges.push(start..node.end_byte());
+    ranges.retain(|range| !range.is_empty());
+    ranges
+}
+
+fn intersect(a: &Range<usize>, b: &Range<usize>) -> Option<Range<usize>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (!range.is_empty()).then_some(range)
+}
+
+/// Sorts `ranges` and merges the overlapping and adjacent ones.
+fn merge(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Removes the sorted, disjoint `excluded` ranges from the sorted, disjoint
+/// `ranges`.
+fn subtract(ranges: Vec<Range<usize>>, excluded: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut result = Vec::with_capacity(ranges.len());
+    let mut excluded = excluded.iter().peekable();
+    for mut range in ranges {
+        while let Some(next) = excluded.peek() {
+            if next.end <= range.start {
+                excluded.next();
+                continue;
+            }
+            if next.start >= range.end {
+                break;
+            }
+            if next.start > range.start {
+                result.push(range.start..next.start);
+            }
+            if next.end >= range.end {
+                range.start = range.end;
+                break;
+            }
+            range.start = next.end;
+            excluded.next();
+        }
+        if !range.is_empty() {
+            result.push(range);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::words::words;
+    use gpui::{AppContext as _, Entity, TestAppContext};
+    use indoc::indoc;
+    use language::{Buffer, markdown_lang, rust_lang};
+
+    fn checked_words(buffer: &Entity<Buffer>, cx: &mut TestAppContext) -> Vec<String> {
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        checkable_ranges(&snapshot, 0..snapshot.len())
+            .into_iter()
+            .flat_map(|range| {
+                let text = snapshot.text_for_range(range).collect::<String>();
+                words(&text)
+                    .into_iter()
+                    .map(|word| text[word].to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_code(cx: &mut TestAppContext) {
+        let text = indoc! {r#"
+            // A commment
+            fn greet(name: &str) -> String {
+                let misspeled = 1;
+                format!("Hello {name}\nthere")
+            }
+        "#};
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            checked_words(&buffer, cx),
+            ["commment", "Hello", "name", "there"]
+        );
+    }
+
+    #[gpui::test]
+    fn test_markdown(cx: &mut TestAppContext) {
+        let text = indoc! {"
+            # Title
+
+            Some prose.
+
+            ```rust
+            let code = 1;
+            ```
+
+            More prose.
+        "};
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(markdown_lang(), cx));
+        cx.run_until_parked();
+        assert_eq!(
+            checked_words(&buffer, cx),
+            ["Title", "Some", "prose", "More", "prose"]
+        );
+    }
+
+    #[gpui::test]
+    fn test_plain_text(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("Some plain text", cx));
+        assert_eq!(checked_words(&buffer, cx), ["Some", "plain", "text"]);
+    }
+}
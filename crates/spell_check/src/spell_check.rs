@@ -0,0 +1,402 @@
+//! Spell checking of comments, strings and prose.
+//!
+//! Words are checked against Hunspell dictionaries, read from Zed's config
+//! directory or from the system's Hunspell directories, so that checking
+//! works offline. The syntax tree of a buffer restricts checking to the
+//! comments and strings of code, and to the prose of Markdown and plain
+//! text documents. Words can also be accepted through the `spelling`
+//! settings and a project's `.zed/dictionary.txt`.
+
+mod checkable_ranges;
+mod spelling_settings;
+mod words;
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashMap, HashSet};
+use fs::{Fs, MTime};
+use gpui::{App, AppContext as _, BorrowAppContext as _, Global, Task};
+use language::{BufferSnapshot, Point, ToOffset as _};
+use parking_lot::Mutex;
+use settings::{Settings as _, SettingsStore};
+use spellbook::Dictionary;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt as _;
+
+pub use checkable_ranges::checkable_ranges;
+pub use spelling_settings::SpellingSettings;
+pub use words::words;
+
+/// The maximum number of corrections suggested for a misspelled word.
+const MAX_SUGGESTIONS: usize = 5;
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    cx.set_global(GlobalSpellChecker {
+        fs,
+        state: SpellCheckerState::Unloaded,
+    });
+
+    let mut settings = SpellingSettings::get_global(cx).clone();
+    cx.observe_global::<SettingsStore>(move |cx| {
+        let new_settings = SpellingSettings::get_global(cx);
+        if *new_settings != settings {
+            settings = new_settings.clone();
+            cx.global_mut::<GlobalSpellChecker>().state = SpellCheckerState::Unloaded;
+        }
+    })
+    .detach();
+}
+
+/// The spell checker for the current `spelling` settings. Observe this global
+/// to be notified when the dictionaries are loaded or the settings change.
+pub struct GlobalSpellChecker {
+    fs: Arc<dyn Fs>,
+    state: SpellCheckerState,
+}
+
+impl Global for GlobalSpellChecker {}
+
+enum SpellCheckerState {
+    Unloaded,
+    Loading(Task<()>),
+    Loaded(Arc<SpellChecker>),
+}
+
+/// Checks words against a set of dictionaries and word lists.
+pub struct SpellChecker {
+    dictionaries: Vec<Dictionary>,
+    words: WordList,
+    word_lists: Mutex<HashMap<PathBuf, (Option<MTime>, Arc<WordList>)>>,
+}
+
+impl SpellChecker {
+    /// Returns the spell checker for the current settings, or `None` while
+    /// its dictionaries are being loaded.
+    pub fn global(cx: &mut App) -> Option<Arc<Self>> {
+        let global = cx.try_global::<GlobalSpellChecker>()?;
+        match &global.state {
+            SpellCheckerState::Loaded(spell_checker) => return Some(spell_checker.clone()),
+            SpellCheckerState::Loading(_) => return None,
+            SpellCheckerState::Unloaded => {}
+        }
+
+        let fs = global.fs.clone();
+        let settings = SpellingSettings::get_global(cx).clone();
+        let spell_checker = cx.background_spawn(async move { Self::load(fs, settings).await });
+        let load = cx.spawn(async move |cx| {
+            let spell_checker = Arc::new(spell_checker.await);
+            cx.update_global(|global: &mut GlobalSpellChecker, _| {
+                global.state = SpellCheckerState::Loaded(spell_checker);
+            });
+        });
+        cx.global_mut::<GlobalSpellChecker>().state = SpellCheckerState::Loading(load);
+        None
+    }
+
+    /// Notifies the observers of [`GlobalSpellChecker`] that a word list file
+    /// was changed, so that they check words again.
+    pub fn word_lists_changed(cx: &mut App) {
+        if cx.has_global::<GlobalSpellChecker>() {
+            cx.update_global::<GlobalSpellChecker, _>(|_, _| {});
+        }
+    }
+
+    fn new(dictionaries: Vec<Dictionary>, words: Vec<String>) -> Self {
+        Self {
+            dictionaries,
+            words: WordList::new(words),
+            word_lists: Mutex::default(),
+        }
+    }
+
+    async fn load(fs: Arc<dyn Fs>, settings: SpellingSettings) -> Self {
+        let mut dictionaries = Vec::new();
+        for name in &settings.dictionaries {
+            match load_dictionary(fs.as_ref(), name).await {
+                Ok(dictionary) => dictionaries.push(dictionary),
+                Err(error) => log::warn!("failed to load dictionary {name:?}: {error:#}"),
+            }
+        }
+        Self::new(dictionaries, settings.words)
+    }
+
+    /// Returns whether `word` is spelled correctly. Every word is accepted
+    /// when no dictionaries could be loaded.
+    pub fn check(&self, word: &str, project_words: &WordList) -> bool {
+        if self.dictionaries.is_empty() {
+            return true;
+        }
+        let word = word.replace('’', "'");
+        let is_known = |word: &str| {
+            self.words.contains(word)
+                || project_words.contains(word)
+                || self
+                    .dictionaries
+                    .iter()
+                    .any(|dictionary| dictionary.check(word))
+        };
+        is_known(&word) || word.strip_suffix("'s").is_some_and(is_known)
+    }
+
+    /// Returns the ranges of the misspelled words within `range` of the
+    /// buffer, checking only its comments, strings and prose.
+    pub fn misspellings(
+        &self,
+        snapshot: &BufferSnapshot,
+        range: Range<usize>,
+        project_words: &WordList,
+    ) -> Vec<Range<usize>> {
+        if self.dictionaries.is_empty() {
+            return Vec::new();
+        }
+
+        // Check whole lines, so that words cut at the edges of the range
+        // aren't reported.
+        let start = Point::new(snapshot.offset_to_point(range.start).row, 0);
+        let end_row = snapshot.offset_to_point(range.end).row;
+        let end = Point::new(end_row, snapshot.line_len(end_row));
+        let range = start.to_offset(snapshot)..end.to_offset(snapshot);
+
+        let mut checked_words = HashMap::<String, bool>::default();
+        let mut misspellings = Vec::new();
+        for checkable_range in checkable_ranges(snapshot, range) {
+            let text = snapshot
+                .text_for_range(checkable_range.clone())
+                .collect::<String>();
+            for word_range in words(&text) {
+                let word = &text[word_range.clone()];
+                let is_correct = match checked_words.get(word) {
+                    Some(is_correct) => *is_correct,
+                    None => {
+                        let is_correct = self.check(word, project_words);
+                        checked_words.insert(word.to_string(), is_correct);
+                        is_correct
+                    }
+                };
+                if !is_correct {
+                    misspellings.push(
+                        checkable_range.start + word_range.start
+                            ..checkable_range.start + word_range.end,
+                    );
+                }
+            }
+        }
+        misspellings
+    }
+
+    /// Returns the corrections suggested by the dictionaries for `word`, the
+    /// most likely first.
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        let mut dictionary_suggestions = Vec::new();
+        for dictionary in &self.dictionaries {
+            dictionary.suggest(word, &mut dictionary_suggestions);
+            for suggestion in dictionary_suggestions.drain(..) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Returns the words listed in the file at `path`, such as a project's
+    /// `.zed/dictionary.txt`. Lists are read again when their file changes.
+    pub async fn word_list(&self, fs: &dyn Fs, path: &Path) -> Arc<WordList> {
+        let mtime = fs
+            .metadata(path)
+            .await
+            .ok()
+            .flatten()
+            .map(|metadata| metadata.mtime);
+        if let Some((cached_mtime, word_list)) = self.word_lists.lock().get(path)
+            && *cached_mtime == mtime
+        {
+            return word_list.clone();
+        }
+
+        let word_list = match mtime {
+            Some(_) => fs
+                .load(path)
+                .await
+                .log_err()
+                .map(|text| WordList::parse(&text))
+                .unwrap_or_default(),
+            None => WordList::default(),
+        };
+        let word_list = Arc::new(word_list);
+        self.word_lists
+            .lock()
+            .insert(path.to_path_buf(), (mtime, word_list.clone()));
+        word_list
+    }
+}
+
+/// A list of words accepted in addition to the dictionaries' words.
+#[derive(Debug, Default)]
+pub struct WordList {
+    words: HashSet<String>,
+}
+
+impl WordList {
+    pub fn new(words: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            words: words.into_iter().collect(),
+        }
+    }
+
+    /// Parses a word list file, which has a word per line. Empty lines and
+    /// lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Self {
+        Self::new(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        )
+    }
+
+    /// Returns whether `word` is in the list, either as listed or in
+    /// lowercase, so that listing "zed" also accepts "Zed".
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word) || self.words.contains(&word.to_lowercase())
+    }
+}
+
+/// Appends `word` to the word list file at `path`, creating it if needed.
+pub async fn add_to_word_list(fs: &dyn Fs, path: &Path, word: &str) -> Result<()> {
+    let mut text = if fs.is_file(path).await {
+        fs.load(path).await?
+    } else {
+        String::new()
+    };
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(word);
+    text.push('\n');
+    if let Some(parent) = path.parent() {
+        fs.create_dir(parent).await?;
+    }
+    fs.atomic_write(path.to_path_buf(), text).await
+}
+
+/// Loads the dictionary with the given name from the first directory that
+/// has both its `.aff` and `.dic` files.
+async fn load_dictionary(fs: &dyn Fs, name: &str) -> Result<Dictionary> {
+    let dirs = dictionary_dirs();
+    for dir in &dirs {
+        let aff_path = dir.join(format!("{name}.aff"));
+        let dic_path = dir.join(format!("{name}.dic"));
+        if !fs.is_file(&aff_path).await || !fs.is_file(&dic_path).await {
+            continue;
+        }
+        let aff = fs
+            .load_bytes(&aff_path)
+            .await
+            .with_context(|| format!("reading {aff_path:?}"))?;
+        let dic = fs
+            .load_bytes(&dic_path)
+            .await
+            .with_context(|| format!("reading {dic_path:?}"))?;
+        return Dictionary::new(
+            &String::from_utf8_lossy(&aff),
+            &String::from_utf8_lossy(&dic),
+        )
+        .map_err(|error| anyhow!("parsing {dic_path:?}: {error}"));
+    }
+    Err(anyhow!("not found in {dirs:?}"))
+}
+
+/// The directories searched for dictionaries, in order of precedence.
+fn dictionary_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![paths::dictionaries_dir().clone()];
+    if let Some(dicpath) = std::env::var_os("DICPATH") {
+        dirs.extend(std::env::split_paths(&dicpath));
+    }
+    if cfg!(target_os = "macos") {
+        dirs.push(util::paths::home_dir().join("Library/Spelling"));
+        dirs.push(PathBuf::from("/Library/Spelling"));
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        dirs.extend(
+            [
+                "/usr/share/hunspell",
+                "/usr/share/myspell",
+                "/usr/share/myspell/dicts",
+                "/usr/local/share/hunspell",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use language::Buffer;
+    use serde_json::json;
+    use util::path;
+
+    const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'\n";
+    const DIC: &str = "4\nhello\nthere\nworld\nZed\n";
+
+    fn spell_checker() -> SpellChecker {
+        SpellChecker::new(
+            vec![Dictionary::new(AFF, DIC).unwrap()],
+            vec!["gpui".to_string()],
+        )
+    }
+
+    #[gpui::test]
+    fn test_misspellings(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("Hello wrold, Zed's gpui and cratez there", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let spell_checker = spell_checker();
+        let project_words = WordList::parse("# Project words\ncratez\n");
+
+        let misspelled_words = |project_words: &WordList| {
+            spell_checker
+                .misspellings(&snapshot, 8..10, project_words)
+                .into_iter()
+                .map(|range| snapshot.text_for_range(range).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            misspelled_words(&WordList::default()),
+            ["wrold", "and", "cratez"]
+        );
+        assert_eq!(misspelled_words(&project_words), ["wrold", "and"]);
+        assert_eq!(spell_checker.suggestions("wrold"), ["world"]);
+    }
+
+    #[gpui::test]
+    async fn test_word_lists(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({})).await;
+        let path = Path::new(path!("/project/.zed/dictionary.txt"));
+        let spell_checker = spell_checker();
+
+        assert!(
+            !spell_checker
+                .word_list(fs.as_ref(), path)
+                .await
+                .contains("cratez")
+        );
+        add_to_word_list(fs.as_ref(), path, "cratez").await.unwrap();
+        add_to_word_list(fs.as_ref(), path, "zeditor")
+            .await
+            .unwrap();
+        assert_eq!(fs.load(path).await.unwrap(), "cratez\nzeditor\n");
+
+        let word_list = spell_checker.word_list(fs.as_ref(), path).await;
+        assert!(word_list.contains("cratez"));
+        assert!(word_list.contains("Zeditor"));
+    }
+}
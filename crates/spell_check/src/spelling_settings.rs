@@ -0,0 +1,24 @@
+use settings::{RegisterSetting, Settings};
+
+/// The dictionaries and word list used for spell checking.
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct SpellingSettings {
+    /// The names of the Hunspell dictionaries to check words against.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Vec<String>,
+    /// Words accepted in every project.
+    ///
+    /// Default: []
+    pub words: Vec<String>,
+}
+
+impl Settings for SpellingSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let content = content.spelling.clone().unwrap();
+        Self {
+            dictionaries: content.dictionaries.unwrap(),
+            words: content.words.unwrap(),
+        }
+    }
+}
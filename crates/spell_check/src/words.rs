@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+/// Words shorter than this are never reported, as they're mostly
+/// abbreviations or parts of identifiers.
+const MIN_WORD_LEN: usize = 3;
+
+/// Returns the byte ranges of the words to spell check in `text`.
+///
+/// Identifiers are split into their words, so that `parseHttpRequest` and
+/// `parse_http_request` are both checked as "parse", "http" and "request".
+/// Tokens that look like URLs, e-mail addresses, paths or qualified names
+/// are skipped entirely, as are code spans between backticks, words
+/// containing digits and words written in capitals, which tend to be
+/// acronyms.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let text = mask_code_spans(text);
+    let mut words = Vec::new();
+    for (token_start, token) in tokens(&text) {
+        if !should_check_token(token) {
+            continue;
+        }
+        for run in alphanumeric_runs(token) {
+            let run_text = &token[run.clone()];
+            if run_text.chars().any(|c| c.is_numeric()) {
+                continue;
+            }
+            for word in split_identifier(run_text) {
+                let word_text = &run_text[word.clone()];
+                if should_check_word(word_text) {
+                    let start = token_start + run.start + word.start;
+                    words.push(start..start + word_text.len());
+                }
+            }
+        }
+    }
+    words
+}
+
+/// Replaces the code spans in `text` with spaces, preserving offsets.
+fn mask_code_spans(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut in_code_span = false;
+    for c in text.chars() {
+        if c == '`' {
+            in_code_span = !in_code_span;
+        }
+        if in_code_span || c == '`' {
+            masked.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        } else {
+            masked.push(c);
+        }
+    }
+    masked
+}
+
+/// Splits `text` on whitespace, returning each token with its offset.
+fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |token| (token.as_ptr() as usize - text.as_ptr() as usize, token))
+}
+
+fn should_check_token(token: &str) -> bool {
+    if token.contains("://") || token.contains('@') || token.contains('/') || token.contains('\\') {
+        return false;
+    }
+    // `std::fs`, `foo.bar()` or `example.com`, but not the end of a sentence.
+    let chars = token.chars().collect::<Vec<_>>();
+    !chars.windows(3).any(|window| {
+        window[0].is_alphanumeric()
+            && (window[1] == '.' || window[1] == ':')
+            && (window[2].is_alphanumeric() || window[2] == ':')
+    })
+}
+
+/// Returns the runs of alphanumeric characters in `token`, including
+/// apostrophes between letters, as in "don't".
+fn alphanumeric_runs(token: &str) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut chars = token.char_indices().peekable();
+    let mut run_start = None;
+    let mut previous = None;
+    while let Some((ix, c)) = chars.next() {
+        let is_apostrophe = (c == '\'' || c == '’')
+            && previous.is_some_and(char::is_alphabetic)
+            && chars.peek().is_some_and(|(_, next)| next.is_alphabetic());
+        if c.is_alphanumeric() || is_apostrophe {
+            run_start.get_or_insert(ix);
+        } else if let Some(start) = run_start.take() {
+            runs.push(start..ix);
+        }
+        previous = Some(c);
+    }
+    if let Some(start) = run_start {
+        runs.push(start..token.len());
+    }
+    runs
+}
+
+/// Splits a camelCase or PascalCase identifier into its words. An acronym
+/// followed by a word, as in `HTMLElement`, is split before the word's
+/// capital letter.
+fn split_identifier(identifier: &str) -> Vec<Range<usize>> {
+    let chars = identifier.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word_start = 0;
+    for (ix, &(offset, c)) in chars.iter().enumerate().skip(1) {
+        let previous = chars[ix - 1].1;
+        let next = chars.get(ix + 1).map(|(_, c)| *c);
+        let is_boundary = c.is_uppercase()
+            && (previous.is_lowercase()
+                || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+        if is_boundary {
+            words.push(word_start..offset);
+            word_start = offset;
+        }
+    }
+    words.push(word_start..identifier.len());
+    words
+}
+
+fn should_check_word(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() >= MIN_WORD_LEN
+        && !word.chars().all(|c| !c.is_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_prose() {
+        assert_eq!(
+            word_texts("The quick brown fox doesn't jump, does it?"),
+            ["The", "quick", "brown", "fox", "doesn't", "jump", "does"]
+        );
+        assert_eq!(word_texts("Über naïve café"), ["Über", "naïve", "café"]);
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            word_texts("parseHttpRequest parse_http_request"),
+            ["parse", "Http", "Request", "parse", "http", "request"]
+        );
+        assert_eq!(
+            word_texts("HTMLElement XMLHttpRequest"),
+            ["Element", "Http", "Request"]
+        );
+    }
+
+    #[test]
+    fn test_skipped_tokens() {
+        assert_eq!(
+            word_texts(
+                "See https://zed.dev, mail hi@zed.dev, open src/main.rs, \
+                 call std::fs::read or foo.bar() on utf8 and TODO, or `run_tets`."
+            ),
+            ["See", "mail", "open", "call", "and"]
+        );
+    }
+}
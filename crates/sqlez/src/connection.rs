@@ -57,6 +57,21 @@ impl Connection {
         Self::open(uri, true).unwrap_or_else(|_| Self::open_memory(Some(uri)))
     }
 
+    /// Opens an existing database file without write access. Statements that write are
+    /// rejected when prepared, and the file is never created if it doesn't exist.
+    pub fn open_read_only(path: &str) -> Result<Self> {
+        let connection =
+            Self::open_with_flags(path, true, SQLITE_OPEN_READONLY | SQLITE_OPEN_NOMUTEX)?;
+        *connection.write.borrow_mut() = false;
+        Ok(connection)
+    }
+
+    /// Opens an existing database file for reading and writing. Unlike [`Self::open_file`],
+    /// fails instead of creating the file or falling back to an in-memory database.
+    pub fn open_existing(path: &str) -> Result<Self> {
+        Self::open_with_flags(path, true, SQLITE_OPEN_READWRITE | SQLITE_OPEN_NOMUTEX)
+    }
+
     pub fn open_memory(uri: Option<&str>) -> Self {
         if let Some(uri) = uri {
             let in_memory_path = format!("file:{}?mode=memory&cache=shared", uri);
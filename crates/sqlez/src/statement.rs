@@ -252,6 +252,18 @@ impl<'a> Statement<'a> {
         Ok(T::column(self, 0)?.0)
     }
 
+    /// Number of columns returned by the current statement
+    pub fn column_count(&self) -> i32 {
+        unsafe { sqlite3_column_count(self.current_statement()) }
+    }
+
+    pub fn column_name(&self, index: i32) -> Result<&str> {
+        let index = index as c_int;
+        let pointer = unsafe { sqlite3_column_name(self.current_statement(), index) };
+        anyhow::ensure!(!pointer.is_null(), "Failed to read name of column {index}");
+        Ok(unsafe { CStr::from_ptr(pointer) }.to_str()?)
+    }
+
     pub fn column_type(&mut self, index: i32) -> Result<SqlType> {
         let result = unsafe { sqlite3_column_type(self.current_statement(), index) };
         self.connection.last_error()?;
@@ -270,7 +282,7 @@ impl<'a> Statement<'a> {
         Ok(self)
     }
 
-    fn step(&mut self) -> Result<StepResult> {
+    pub fn step(&mut self) -> Result<StepResult> {
         match unsafe { sqlite3_step(self.current_statement()) } {
             SQLITE_ROW => Ok(StepResult::Row),
             SQLITE_DONE => {
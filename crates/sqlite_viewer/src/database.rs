@@ -0,0 +1,416 @@
+//! Read access to a user's SQLite database file: schema introspection, paging and ad-hoc queries.
+
+use std::{fmt, path::Path};
+
+use anyhow::{Context as _, Result};
+use sqlez::{
+    connection::Connection,
+    statement::{SqlType, Statement, StepResult},
+};
+
+/// Number of rows loaded at once when browsing a table
+pub const PAGE_SIZE: usize = 200;
+/// Maximum number of rows kept from an ad-hoc query
+pub const MAX_QUERY_ROWS: usize = 10_000;
+/// Number of bytes of a blob shown in a cell
+const BLOB_PREVIEW_BYTES: usize = 16;
+
+/// First bytes of every SQLite 3 database file
+pub const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaObjectKind {
+    Table,
+    View,
+    Index,
+    Trigger,
+}
+
+impl SchemaObjectKind {
+    fn from_sqlite_type(kind: &str) -> Option<Self> {
+        match kind {
+            "table" => Some(Self::Table),
+            "view" => Some(Self::View),
+            "index" => Some(Self::Index),
+            "trigger" => Some(Self::Trigger),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Table => "Tables",
+            Self::View => "Views",
+            Self::Index => "Indexes",
+            Self::Trigger => "Triggers",
+        }
+    }
+
+    /// Whether rows of the object can be browsed
+    pub fn has_rows(self) -> bool {
+        matches!(self, Self::Table | Self::View)
+    }
+}
+
+/// Entry of the `sqlite_schema` table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaObject {
+    pub kind: SchemaObjectKind,
+    pub name: String,
+    /// Table the object belongs to. Same as `name` for tables and views
+    pub table_name: String,
+    /// `CREATE` statement of the object. Missing for automatically created indexes
+    pub sql: Option<String>,
+}
+
+/// Column of a table or view, as reported by `PRAGMA table_info`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub declared_type: String,
+    pub not_null: bool,
+    pub default_value: Option<String>,
+    pub primary_key: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl fmt::Display for SqlValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqlValue::Null => write!(f, "NULL"),
+            SqlValue::Integer(value) => write!(f, "{value}"),
+            SqlValue::Float(value) => write!(f, "{value}"),
+            SqlValue::Text(value) => write!(f, "{value}"),
+            SqlValue::Blob(bytes) => {
+                write!(f, "x'")?;
+                for byte in bytes.iter().take(BLOB_PREVIEW_BYTES) {
+                    write!(f, "{byte:02x}")?;
+                }
+                if bytes.len() > BLOB_PREVIEW_BYTES {
+                    write!(f, "…' ({} bytes)", bytes.len())
+                } else {
+                    write!(f, "'")
+                }
+            }
+        }
+    }
+}
+
+/// Rows returned by a query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<SqlValue>>,
+    /// Whether more rows were available than were read
+    pub truncated: bool,
+}
+
+pub struct SqliteDatabase {
+    connection: Connection,
+}
+
+impl SqliteDatabase {
+    /// Open an existing database file. Read-only databases reject statements that write.
+    pub fn open(path: &Path, writable: bool) -> Result<Self> {
+        let path = path
+            .to_str()
+            .with_context(|| format!("Non UTF-8 database path {path:?}"))?;
+        let connection = if writable {
+            Connection::open_existing(path)
+        } else {
+            Connection::open_read_only(path)
+        }
+        .with_context(|| format!("Opening SQLite database {path}"))?;
+        Ok(Self::from_connection(connection))
+    }
+
+    pub fn from_connection(connection: Connection) -> Self {
+        Self { connection }
+    }
+
+    /// All user-defined tables, views, indexes and triggers, ordered by kind and name
+    pub fn schema(&self) -> Result<Vec<SchemaObject>> {
+        let mut statement = Statement::prepare(
+            &self.connection,
+            "SELECT type, name, tbl_name, sql FROM sqlite_schema \
+             WHERE name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let mut objects = statement
+            .map(|row| {
+                let kind = row.column_text(0)?.to_string();
+                let name = row.column_text(1)?.to_string();
+                let table_name = row.column_text(2)?.to_string();
+                let sql = match row.column_type(3)? {
+                    SqlType::Null => None,
+                    _ => Some(row.column_text(3)?.to_string()),
+                };
+                Ok((kind, name, table_name, sql))
+            })?
+            .into_iter()
+            .filter_map(|(kind, name, table_name, sql)| {
+                Some(SchemaObject {
+                    kind: SchemaObjectKind::from_sqlite_type(&kind)?,
+                    name,
+                    table_name,
+                    sql,
+                })
+            })
+            .collect::<Vec<_>>();
+        objects.sort_by_key(|object| object.kind);
+        Ok(objects)
+    }
+
+    pub fn columns(&self, table: &str) -> Result<Vec<ColumnInfo>> {
+        let mut statement = Statement::prepare(
+            &self.connection,
+            format!("PRAGMA table_info({})", quote_identifier(table)),
+        )?;
+        statement.map(|row| {
+            Ok(ColumnInfo {
+                name: row.column_text(1)?.to_string(),
+                declared_type: row.column_text(2)?.to_string(),
+                not_null: row.column_int(3)? != 0,
+                default_value: match row.column_type(4)? {
+                    SqlType::Null => None,
+                    _ => Some(row.column_text(4)?.to_string()),
+                },
+                primary_key: row.column_int(5)? != 0,
+            })
+        })
+    }
+
+    pub fn row_count(&self, table: &str) -> Result<usize> {
+        let mut statement = Statement::prepare(
+            &self.connection,
+            format!("SELECT COUNT(*) FROM {}", quote_identifier(table)),
+        )?;
+        let count = statement.single(|row| row.column_int64(0))?;
+        Ok(count.max(0) as usize)
+    }
+
+    /// Rows of a table or view, starting at `offset`
+    pub fn page(&self, table: &str, offset: usize, limit: usize) -> Result<QueryResult> {
+        let statement = Statement::prepare(
+            &self.connection,
+            format!(
+                "SELECT * FROM {} LIMIT {limit} OFFSET {offset}",
+                quote_identifier(table)
+            ),
+        )?;
+        read_rows(statement, limit)
+    }
+
+    /// Run arbitrary SQL, keeping at most `max_rows` rows of the result
+    pub fn execute(&self, sql: &str, max_rows: usize) -> Result<QueryResult> {
+        let mut statement = Statement::prepare(&self.connection, sql)?;
+        // SQLite prepares comments without a statement as null statements
+        statement
+            .raw_statements
+            .retain(|raw_statement| !raw_statement.is_null());
+        read_rows(statement, max_rows)
+    }
+}
+
+fn read_rows(mut statement: Statement<'_>, max_rows: usize) -> Result<QueryResult> {
+    anyhow::ensure!(
+        !statement.raw_statements.is_empty(),
+        "The query has no statement to run"
+    );
+    let mut result = QueryResult::default();
+    loop {
+        if statement.step()? == StepResult::Done {
+            break;
+        }
+        if result.rows.len() == max_rows {
+            result.truncated = true;
+            break;
+        }
+        if result.columns.is_empty() {
+            result.columns = column_names(&statement)?;
+        }
+        let row = (0..statement.column_count())
+            .map(|index| read_value(&mut statement, index))
+            .collect::<Result<Vec<_>>>()?;
+        result.rows.push(row);
+    }
+    if result.columns.is_empty() {
+        result.columns = column_names(&statement)?;
+    }
+    statement.reset();
+    Ok(result)
+}
+
+fn column_names(statement: &Statement) -> Result<Vec<String>> {
+    (0..statement.column_count())
+        .map(|index| Ok(statement.column_name(index)?.to_string()))
+        .collect()
+}
+
+fn read_value(statement: &mut Statement, index: i32) -> Result<SqlValue> {
+    Ok(match statement.column_type(index)? {
+        SqlType::Null => SqlValue::Null,
+        SqlType::Integer => SqlValue::Integer(statement.column_int64(index)?),
+        SqlType::Float => SqlValue::Float(statement.column_double(index)?),
+        SqlType::Text => match statement.column_text(index) {
+            Ok(text) => SqlValue::Text(text.to_string()),
+            // Text which is not valid UTF-8
+            Err(_) => {
+                SqlValue::Text(String::from_utf8_lossy(statement.column_blob(index)?).into_owned())
+            }
+        },
+        SqlType::Blob => SqlValue::Blob(statement.column_blob(index)?.to_vec()),
+    })
+}
+
+/// Quote a table or column name to be used in SQL
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn test_database(name: &str) -> SqliteDatabase {
+        let connection = Connection::open_memory(Some(name));
+        connection
+            .exec(indoc! {r#"
+                CREATE TABLE "my ""items""" (
+                    id INTEGER PRIMARY KEY,
+                    name TEXT NOT NULL DEFAULT 'unnamed',
+                    price REAL,
+                    data BLOB
+                );
+                CREATE INDEX items_by_name ON "my ""items"""(name);
+                CREATE VIEW expensive AS SELECT name FROM "my ""items""" WHERE price > 10;
+                INSERT INTO "my ""items""" (name, price, data) VALUES
+                    ('apple', 1.5, NULL),
+                    ('laptop', 999.0, x'00ff'),
+                    ('book', 12.0, NULL);
+            "#})
+            .unwrap()()
+        .unwrap();
+        SqliteDatabase::from_connection(connection)
+    }
+
+    #[test]
+    fn test_schema_and_columns() {
+        let database = test_database("test_schema_and_columns");
+
+        let schema = database.schema().unwrap();
+        let summary = schema
+            .iter()
+            .map(|object| {
+                (
+                    object.kind,
+                    object.name.as_str(),
+                    object.table_name.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (SchemaObjectKind::Table, "my \"items\"", "my \"items\""),
+                (SchemaObjectKind::View, "expensive", "expensive"),
+                (SchemaObjectKind::Index, "items_by_name", "my \"items\""),
+            ]
+        );
+        assert!(schema[1].sql.as_ref().unwrap().starts_with("CREATE VIEW"));
+
+        let columns = database.columns("my \"items\"").unwrap();
+        assert_eq!(
+            columns
+                .iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>(),
+            ["id", "name", "price", "data"]
+        );
+        assert!(columns[0].primary_key);
+        assert!(columns[1].not_null);
+        assert_eq!(columns[1].default_value.as_deref(), Some("'unnamed'"));
+        assert_eq!(columns[2].declared_type, "REAL");
+    }
+
+    #[test]
+    fn test_paging_rows() {
+        let database = test_database("test_paging_rows");
+
+        assert_eq!(database.row_count("my \"items\"").unwrap(), 3);
+
+        let page = database.page("my \"items\"", 1, 1).unwrap();
+        assert_eq!(page.columns, ["id", "name", "price", "data"]);
+        assert_eq!(
+            page.rows,
+            [vec![
+                SqlValue::Integer(2),
+                SqlValue::Text("laptop".into()),
+                SqlValue::Float(999.0),
+                SqlValue::Blob(vec![0x00, 0xff]),
+            ]]
+        );
+        assert_eq!(page.rows[0][3].to_string(), "x'00ff'");
+
+        let view_page = database.page("expensive", 0, PAGE_SIZE).unwrap();
+        assert_eq!(view_page.columns, ["name"]);
+        assert_eq!(view_page.rows.len(), 2);
+    }
+
+    #[test]
+    fn test_ad_hoc_queries() {
+        let database = test_database("test_ad_hoc_queries");
+
+        let result = database
+            .execute(
+                "SELECT name, price * 2 AS doubled FROM \"my \"\"items\"\"\" ORDER BY price",
+                MAX_QUERY_ROWS,
+            )
+            .unwrap();
+        assert_eq!(result.columns, ["name", "doubled"]);
+        assert_eq!(
+            result.rows[0],
+            [SqlValue::Text("apple".into()), SqlValue::Float(3.0)]
+        );
+        assert!(!result.truncated);
+
+        let truncated = database
+            .execute("SELECT * FROM \"my \"\"items\"\"\"", 2)
+            .unwrap();
+        assert_eq!(truncated.rows.len(), 2);
+        assert!(truncated.truncated);
+
+        let empty = database
+            .execute("SELECT id FROM \"my \"\"items\"\"\" WHERE id > 100", 10)
+            .unwrap();
+        assert_eq!(empty.columns, ["id"]);
+        assert!(empty.rows.is_empty());
+
+        assert!(database.execute("SELECT * FROM missing", 10).is_err());
+    }
+
+    #[test]
+    fn test_queries_without_statements() {
+        let database = test_database("test_queries_without_statements");
+
+        for sql in [";", "-- comment", "  ;  ", "/* comment */ ;"] {
+            assert_eq!(
+                database.execute(sql, 10).unwrap_err().to_string(),
+                "The query has no statement to run",
+                "query {sql:?}"
+            );
+        }
+
+        let result = database.execute("SELECT 1 AS one; -- comment", 10).unwrap();
+        assert_eq!(result.columns, ["one"]);
+        assert_eq!(result.rows, [vec![SqlValue::Integer(1)]]);
+    }
+}
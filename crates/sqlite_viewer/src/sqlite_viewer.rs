@@ -0,0 +1,857 @@
+mod database;
+
+use std::{
+    io::Read as _,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use editor::Editor;
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Task, Window, actions,
+};
+use parking_lot::Mutex;
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings as _;
+use ui::{
+    Checkbox, DefiniteLength, ListHeader, ListItem, ListItemSpacing, Table, TableInteractionState,
+    ToggleState, Tooltip, prelude::*,
+};
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane,
+    invalid_item_view::InvalidItemView,
+    item::{Item, ItemBufferKind, ProjectItem},
+};
+
+pub use crate::database::*;
+
+actions!(
+    sqlite_viewer,
+    [
+        /// Runs the SQL from the query editor against the open database.
+        RunQuery
+    ]
+);
+
+/// Extensions of files opened in the database browser instead of a text editor
+const SQLITE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3", "db3"];
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<SqliteViewer>(cx);
+}
+
+/// A SQLite database file opened from the project
+pub struct SqliteDatabaseItem {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    database: Arc<Mutex<SqliteDatabase>>,
+    writable: bool,
+}
+
+impl SqliteDatabaseItem {
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn database(&self) -> Arc<Mutex<SqliteDatabase>> {
+        self.database.clone()
+    }
+
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Reopen the database, allowing or forbidding statements which modify it
+    pub fn set_writable(
+        &mut self,
+        writable: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        if self.writable == writable {
+            return Task::ready(Ok(()));
+        }
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let database = cx
+                .background_spawn(async move { SqliteDatabase::open(&abs_path, writable) })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.database = Arc::new(Mutex::new(database));
+                this.writable = writable;
+                cx.notify();
+            })
+        })
+    }
+}
+
+fn is_sqlite_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            SQLITE_EXTENSIONS
+                .iter()
+                .any(|supported| extension.eq_ignore_ascii_case(supported))
+        })
+}
+
+/// Checks the file header, so that files which merely share the extension open as usual
+fn has_sqlite_header(abs_path: &Path) -> bool {
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    std::fs::File::open(abs_path)
+        .and_then(|file| {
+            file.take(SQLITE_HEADER.len() as u64)
+                .read_to_end(&mut header)
+        })
+        .is_ok_and(|_| header == SQLITE_HEADER)
+}
+
+impl project::ProjectItem for SqliteDatabaseItem {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
+        if !is_sqlite_path(path.path.as_std_path()) {
+            return None;
+        }
+        let project = project.read(cx);
+        // The database is read straight from disk, so remote projects use the regular editor
+        if !project.is_local() {
+            return None;
+        }
+        let abs_path = project.absolute_path(path, cx)?;
+        if !has_sqlite_header(&abs_path) {
+            return None;
+        }
+        let entry_id = project.entry_for_path(path, cx).map(|entry| entry.id);
+        let project_path = path.clone();
+
+        Some(cx.spawn(async move |cx| {
+            let database = cx
+                .background_spawn({
+                    let abs_path = abs_path.clone();
+                    async move { SqliteDatabase::open(&abs_path, false) }
+                })
+                .await
+                .with_context(|| format!("opening database {abs_path:?}"))?;
+
+            Ok(cx.new(|_| SqliteDatabaseItem {
+                abs_path,
+                project_path,
+                entry_id,
+                database: Arc::new(Mutex::new(database)),
+                writable: false,
+            }))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// What the rows grid currently shows
+enum GridSource {
+    /// A page of rows of a table or view
+    Object {
+        name: String,
+        offset: usize,
+        total_rows: usize,
+    },
+    /// Result of an ad-hoc query from the query editor
+    Query,
+}
+
+struct Grid {
+    source: GridSource,
+    result: Arc<QueryResult>,
+}
+
+/// Browser of a SQLite database: schema objects, their rows, and ad-hoc queries
+pub struct SqliteViewer {
+    database_item: Entity<SqliteDatabaseItem>,
+    focus_handle: FocusHandle,
+    schema: Vec<SchemaObject>,
+    selected_object: Option<usize>,
+    columns: Vec<ColumnInfo>,
+    grid: Option<Grid>,
+    error: Option<SharedString>,
+    query_editor: Entity<Editor>,
+    table_interaction_state: Entity<TableInteractionState>,
+    load_task: Task<()>,
+}
+
+impl SqliteViewer {
+    pub fn new(
+        database_item: Entity<SqliteDatabaseItem>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::multi_line(window, cx);
+            editor.set_placeholder_text("SELECT * FROM …", window, cx);
+            editor
+        });
+
+        let sql_language = project.read(cx).languages().language_for_name("SQL");
+        let buffer = query_editor.read(cx).buffer().read(cx).as_singleton();
+        cx.spawn(async move |_, cx| {
+            if let (Ok(language), Some(buffer)) = (sql_language.await, buffer) {
+                buffer.update(cx, |buffer, cx| buffer.set_language(Some(language), cx));
+            }
+        })
+        .detach();
+
+        cx.observe(&database_item, |_, _, cx| cx.notify()).detach();
+
+        let mut this = Self {
+            database_item,
+            focus_handle: cx.focus_handle(),
+            schema: Vec::new(),
+            selected_object: None,
+            columns: Vec::new(),
+            grid: None,
+            error: None,
+            query_editor,
+            table_interaction_state: cx.new(|cx| TableInteractionState::new(cx)),
+            load_task: Task::ready(()),
+        };
+        this.reload_schema(false, cx);
+        this
+    }
+
+    fn database(&self, cx: &App) -> Arc<Mutex<SqliteDatabase>> {
+        self.database_item.read(cx).database()
+    }
+
+    /// Reload the schema objects. Unless `keep_view` is set, the previously selected object is
+    /// selected again, replacing the rows shown in the grid.
+    fn reload_schema(&mut self, keep_view: bool, cx: &mut Context<Self>) {
+        let database = self.database(cx);
+        self.load_task = cx.spawn(async move |this, cx| {
+            let schema = cx
+                .background_spawn(async move { database.lock().schema() })
+                .await;
+            this.update(cx, |this, cx| match schema {
+                Ok(schema) => {
+                    let selected_name = this
+                        .selected_object
+                        .and_then(|ix| this.schema.get(ix))
+                        .map(|object| object.name.clone());
+                    this.schema = schema;
+                    let selected_object = selected_name
+                        .and_then(|name| this.schema.iter().position(|object| object.name == name));
+                    if keep_view {
+                        this.selected_object = selected_object;
+                        cx.notify();
+                        return;
+                    }
+                    let selected_object = selected_object.or_else(|| {
+                        this.schema
+                            .iter()
+                            .position(|object| object.kind == SchemaObjectKind::Table)
+                    });
+                    this.selected_object = None;
+                    if let Some(ix) = selected_object {
+                        this.select_object(ix, cx);
+                    }
+                    cx.notify();
+                }
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        });
+    }
+
+    fn select_object(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if self.selected_object == Some(ix) {
+            return;
+        }
+        let Some(object) = self.schema.get(ix) else {
+            return;
+        };
+        self.selected_object = Some(ix);
+        self.error = None;
+        self.grid = None;
+        self.columns.clear();
+
+        let table_name = object.table_name.clone();
+        let database = self.database(cx);
+        if object.kind.has_rows() {
+            self.load_page(object.name.clone(), 0, cx);
+        } else {
+            // Indexes and triggers show the columns of the table they belong to
+            self.load_task = cx.spawn(async move |this, cx| {
+                let columns = cx
+                    .background_spawn(async move { database.lock().columns(&table_name) })
+                    .await;
+                this.update(cx, |this, cx| match columns {
+                    Ok(columns) => {
+                        this.columns = columns;
+                        cx.notify();
+                    }
+                    Err(error) => this.set_error(error, cx),
+                })
+                .ok();
+            });
+        }
+        cx.notify();
+    }
+
+    fn load_page(&mut self, name: String, offset: usize, cx: &mut Context<Self>) {
+        let database = self.database(cx);
+        self.load_task = cx.spawn(async move |this, cx| {
+            let loaded = cx
+                .background_spawn({
+                    let name = name.clone();
+                    async move {
+                        let database = database.lock();
+                        anyhow::Ok((
+                            database.columns(&name)?,
+                            database.row_count(&name)?,
+                            database.page(&name, offset, PAGE_SIZE)?,
+                        ))
+                    }
+                })
+                .await;
+            this.update(cx, |this, cx| match loaded {
+                Ok((columns, total_rows, result)) => {
+                    this.error = None;
+                    this.columns = columns;
+                    this.grid = Some(Grid {
+                        source: GridSource::Object {
+                            name,
+                            offset,
+                            total_rows,
+                        },
+                        result: Arc::new(result),
+                    });
+                    cx.notify();
+                }
+                Err(error) => this.set_error(error, cx),
+            })
+            .ok();
+        });
+    }
+
+    fn change_page(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let Some(Grid {
+            source:
+                GridSource::Object {
+                    name,
+                    offset,
+                    total_rows,
+                },
+            ..
+        }) = &self.grid
+        else {
+            return;
+        };
+        let new_offset = if forward {
+            offset + PAGE_SIZE
+        } else {
+            offset.saturating_sub(PAGE_SIZE)
+        };
+        if new_offset >= *total_rows && forward {
+            return;
+        }
+        self.load_page(name.clone(), new_offset, cx);
+    }
+
+    fn run_query(&mut self, _: &RunQuery, _: &mut Window, cx: &mut Context<Self>) {
+        let sql = self.query_editor.read(cx).text(cx);
+        if sql.trim().is_empty() {
+            return;
+        }
+        let database = self.database(cx);
+        let writable = self.database_item.read(cx).is_writable();
+        self.load_task = cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_spawn(async move { database.lock().execute(&sql, MAX_QUERY_ROWS) })
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(result) => {
+                        this.error = None;
+                        this.grid = Some(Grid {
+                            source: GridSource::Query,
+                            result: Arc::new(result),
+                        });
+                    }
+                    Err(error) => this.set_error(error, cx),
+                }
+                // The query might have changed the schema. Only the list of objects is
+                // refreshed, so that the query's result or error stays visible.
+                if writable {
+                    this.reload_schema(true, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn toggle_writable(&mut self, cx: &mut Context<Self>) {
+        let writable = !self.database_item.read(cx).is_writable();
+        let task = self
+            .database_item
+            .update(cx, |item, cx| item.set_writable(writable, cx));
+        cx.spawn(async move |this, cx| {
+            if let Err(error) = task.await {
+                this.update(cx, |this, cx| this.set_error(error, cx)).ok();
+            }
+        })
+        .detach();
+    }
+
+    fn set_error(&mut self, error: anyhow::Error, cx: &mut Context<Self>) {
+        self.error = Some(format!("{error:#}").into());
+        cx.notify();
+    }
+
+    fn render_sidebar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut items = Vec::new();
+        let mut current_kind = None;
+        for (ix, object) in self.schema.iter().enumerate() {
+            if current_kind != Some(object.kind) {
+                current_kind = Some(object.kind);
+                items.push(ListHeader::new(object.kind.label()).into_any_element());
+            }
+            items.push(
+                ListItem::new(("schema-object", ix))
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(self.selected_object == Some(ix))
+                    .child(Label::new(object.name.clone()).single_line())
+                    .on_click(cx.listener(move |this, _, _, cx| this.select_object(ix, cx)))
+                    .into_any_element(),
+            );
+        }
+
+        v_flex()
+            .id("sqlite-schema")
+            .w_56()
+            .h_full()
+            .p_1()
+            .overflow_y_scroll()
+            .border_r_1()
+            .border_color(cx.theme().colors().border)
+            .when(self.schema.is_empty(), |this| {
+                this.child(
+                    Label::new("No tables")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                )
+            })
+            .children(items)
+    }
+
+    fn render_object_details(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let object = self.schema.get(self.selected_object?)?;
+        Some(
+            v_flex()
+                .gap_1()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Headline::new(object.name.clone()).size(HeadlineSize::Small))
+                        .when(object.name != object.table_name, |this| {
+                            this.child(
+                                Label::new(format!("on {}", object.table_name)).color(Color::Muted),
+                            )
+                        }),
+                )
+                .when_some(object.sql.clone(), |this, sql| {
+                    this.child(
+                        div()
+                            .p_1()
+                            .rounded_sm()
+                            .bg(cx.theme().colors().editor_background)
+                            .font_buffer(cx)
+                            .text_ui_sm(cx)
+                            .child(sql),
+                    )
+                })
+                .child(
+                    h_flex()
+                        .flex_wrap()
+                        .gap_2()
+                        .children(self.columns.iter().map(|column| {
+                            let mut description = column.name.clone();
+                            if !column.declared_type.is_empty() {
+                                description.push(' ');
+                                description.push_str(&column.declared_type);
+                            }
+                            if column.primary_key {
+                                description.push_str(" PK");
+                            }
+                            if column.not_null {
+                                description.push_str(" NOT NULL");
+                            }
+                            if let Some(default_value) = &column.default_value {
+                                description.push_str(&format!(" = {default_value}"));
+                            }
+                            Label::new(description)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                        })),
+                )
+                .into_any_element(),
+        )
+    }
+
+    fn render_grid(&self, cx: &mut Context<Self>) -> AnyElement {
+        let Some(grid) = &self.grid else {
+            return div().into_any_element();
+        };
+        let result = grid.result.clone();
+        let cols = result.columns.len();
+        if cols == 0 {
+            return Label::new("Statement executed, no rows returned")
+                .color(Color::Muted)
+                .into_any_element();
+        }
+
+        let widths = vec![DefiniteLength::Fraction(1. / cols as f32); cols];
+        let headers = result
+            .columns
+            .iter()
+            .map(|column| Label::new(column.clone()).into_any_element())
+            .collect::<Vec<_>>();
+
+        Table::new(cols)
+            .interactable(&self.table_interaction_state)
+            .striped()
+            .column_widths(widths)
+            .header(headers)
+            .uniform_list("sqlite-rows", result.rows.len(), {
+                let result = result.clone();
+                move |range, _, _| {
+                    range
+                        .filter_map(|ix| result.rows.get(ix))
+                        .map(|row| {
+                            row.iter()
+                                .map(|value| match value {
+                                    SqlValue::Null => {
+                                        Label::new("NULL").color(Color::Muted).into_any_element()
+                                    }
+                                    value => Label::new(value.to_string())
+                                        .single_line()
+                                        .into_any_element(),
+                                })
+                                .collect()
+                        })
+                        .collect()
+                }
+            })
+            .empty_table_callback(|_, _| {
+                Label::new("No rows").color(Color::Muted).into_any_element()
+            })
+            .into_any_element()
+    }
+
+    fn render_grid_footer(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let grid = self.grid.as_ref()?;
+        let (status, paging) = match &grid.source {
+            GridSource::Object {
+                offset, total_rows, ..
+            } => {
+                let status = if *total_rows == 0 {
+                    "No rows".to_string()
+                } else {
+                    format!(
+                        "Rows {}–{} of {total_rows}",
+                        offset + 1,
+                        offset + grid.result.rows.len()
+                    )
+                };
+                (
+                    status,
+                    Some((*offset > 0, offset + PAGE_SIZE < *total_rows)),
+                )
+            }
+            GridSource::Query => {
+                let rows = grid.result.rows.len();
+                let status = if grid.result.truncated {
+                    format!("Showing first {rows} rows")
+                } else {
+                    format!("{rows} rows")
+                };
+                (status, None)
+            }
+        };
+
+        Some(
+            h_flex()
+                .gap_1()
+                .child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .when_some(paging, |this, (has_previous, has_next)| {
+                    this.child(
+                        IconButton::new("sqlite-previous-page", IconName::ChevronLeft)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_previous)
+                            .tooltip(Tooltip::text("Previous Page"))
+                            .on_click(cx.listener(|this, _, _, cx| this.change_page(false, cx))),
+                    )
+                    .child(
+                        IconButton::new("sqlite-next-page", IconName::ChevronRight)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_next)
+                            .tooltip(Tooltip::text("Next Page"))
+                            .on_click(cx.listener(|this, _, _, cx| this.change_page(true, cx))),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+
+    fn render_query_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let writable = self.database_item.read(cx).is_writable();
+        v_flex()
+            .gap_1()
+            .pt_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .h_24()
+                    .p_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.query_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Checkbox::new("sqlite-allow-writes", ToggleState::from(writable))
+                            .label("Allow Writes")
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_writable(cx))),
+                    )
+                    .child(
+                        Button::new("sqlite-run-query", "Run")
+                            .style(ButtonStyle::Filled)
+                            .key_binding(ui::KeyBinding::for_action_in(
+                                &RunQuery,
+                                &self.query_editor.focus_handle(cx),
+                                cx,
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(RunQuery.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+    }
+}
+
+impl Render for SqliteViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .key_context("SqliteViewer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::run_query))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_sidebar(cx))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .h_full()
+                    .p_2()
+                    .gap_2()
+                    .children(self.render_object_details(cx))
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Label::new(error).color(Color::Error))
+                    })
+                    .child(div().flex_1().min_h_0().child(self.render_grid(cx)))
+                    .children(self.render_grid_footer(cx))
+                    .child(self.render_query_panel(cx)),
+            )
+    }
+}
+
+impl Focusable for SqliteViewer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for SqliteViewer {}
+
+impl Item for SqliteViewer {
+    type Event = ();
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.database_item.entity_id(), self.database_item.read(cx))
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.database_item
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_else(|| "Database".into())
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.database_item.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.database_item.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for SqliteViewer {
+    type Item = SqliteDatabaseItem;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, WorktreeId};
+    use sqlez::connection::Connection;
+    use util::rel_path::rel_path;
+
+    #[test]
+    fn test_has_sqlite_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+
+        assert!(has_sqlite_header(&write(
+            "real.db",
+            &[SQLITE_HEADER, &[0; 84]].concat()
+        )));
+        assert!(!has_sqlite_header(&write("notes.db", b"not a database")));
+        assert!(!has_sqlite_header(&write("empty.db", b"")));
+        assert!(!has_sqlite_header(&dir.path().join("missing.db")));
+    }
+
+    #[gpui::test]
+    async fn test_write_query_result_stays_visible(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            workspace::AppState::test(cx);
+            editor::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+
+        let connection = Connection::open_memory(Some("test_write_query_result_stays_visible"));
+        connection
+            .exec("CREATE TABLE items (name TEXT); INSERT INTO items VALUES ('apple');")
+            .unwrap()()
+        .unwrap();
+        let database_item = cx.new(|_| SqliteDatabaseItem {
+            abs_path: PathBuf::from("/test.db"),
+            project_path: ProjectPath {
+                worktree_id: WorktreeId::from_usize(0),
+                path: rel_path("test.db").into(),
+            },
+            entry_id: None,
+            database: Arc::new(Mutex::new(SqliteDatabase::from_connection(connection))),
+            writable: true,
+        });
+        let window =
+            cx.add_window(|window, cx| SqliteViewer::new(database_item, project, window, cx));
+        cx.run_until_parked();
+
+        window
+            .update(cx, |viewer, window, cx| {
+                viewer.query_editor.update(cx, |editor, cx| {
+                    editor.set_text(
+                        "CREATE TABLE extra (id INTEGER); INSERT INTO extra VALUES (7) RETURNING id",
+                        window,
+                        cx,
+                    )
+                });
+                viewer.run_query(&RunQuery, window, cx);
+            })
+            .unwrap();
+        cx.run_until_parked();
+
+        window
+            .update(cx, |viewer, _, _| {
+                assert_eq!(
+                    viewer
+                        .schema
+                        .iter()
+                        .map(|object| object.name.as_str())
+                        .collect::<Vec<_>>(),
+                    ["extra", "items"]
+                );
+                assert_eq!(viewer.error, None);
+                let grid = viewer
+                    .grid
+                    .as_ref()
+                    .expect("the query result should be shown");
+                assert!(matches!(grid.source, GridSource::Query));
+                assert_eq!(grid.result.columns, ["id"]);
+                assert_eq!(grid.result.rows, [vec![SqlValue::Integer(7)]]);
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,767 @@
+use crate::todo_panel_settings::{TodoPanelSettings, TodoTag};
+use anyhow::Result;
+use collections::{BTreeMap, BTreeSet, HashMap};
+use futures::StreamExt as _;
+use gpui::{
+    AppContext as _, AsyncApp, Context, Entity, SharedString, Subscription, Task, WeakEntity,
+};
+use language::{Buffer, BufferEvent, BufferId, BufferSnapshot, LanguageRegistry, Point, Rope};
+use project::{
+    PathChange, Project, ProjectPath, SearchResults, WorktreeId,
+    buffer_store::BufferStoreEvent,
+    search::{SearchQuery, SearchResult},
+};
+use regex::Regex;
+use settings::{Settings as _, SettingsStore};
+use std::{ops::Range, sync::Arc, time::Duration};
+use util::{ResultExt as _, paths::PathMatcher};
+
+/// How long to wait for more changes before scanning the changed files.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A tag found in a comment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TodoItem {
+    pub tag: SharedString,
+    /// The rest of the comment's line after the tag.
+    pub text: SharedString,
+    /// The position of the tag in its file.
+    pub position: Point,
+}
+
+/// The tags in the comments of a project's files, found with a project
+/// search and then kept up to date as files change on disk and open buffers
+/// are edited.
+///
+/// The project is only searched once the tags are first needed, see
+/// [`TodoIndex::ensure_scanned`].
+pub struct TodoIndex {
+    project: Entity<Project>,
+    tags: Vec<TodoTag>,
+    regex: Option<Regex>,
+    files: BTreeMap<ProjectPath, Vec<TodoItem>>,
+    /// Whether the project was searched for tags, after which the index is
+    /// kept up to date.
+    scanned: bool,
+    scanning: bool,
+    limit_reached: bool,
+    changed_paths: BTreeSet<ProjectPath>,
+    changed_buffers: HashMap<BufferId, WeakEntity<Buffer>>,
+    open_buffers: HashMap<BufferId, OpenBuffer>,
+    scan_task: Task<()>,
+    rescan_task: Option<Task<()>>,
+    update_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct OpenBuffer {
+    path: Option<ProjectPath>,
+    /// Whether the buffer was edited since it was opened, in which case its
+    /// file is scanned again when it's closed, to drop unsaved changes.
+    edited: bool,
+    _subscription: Subscription,
+}
+
+impl TodoIndex {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let subscriptions = vec![
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeAdded(_) => this.schedule_rescan(cx),
+                project::Event::WorktreeRemoved(worktree_id) => {
+                    this.worktree_removed(*worktree_id, cx)
+                }
+                project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+                    // Files found in a worktree's initial scan are covered by
+                    // the project search, which waits for it.
+                    this.changed_paths.extend(
+                        changes
+                            .iter()
+                            .filter(|(_, _, change)| *change != PathChange::Loaded)
+                            .map(|(path, _, _)| ProjectPath {
+                                worktree_id: *worktree_id,
+                                path: path.clone(),
+                            }),
+                    );
+                    this.schedule_update(cx);
+                }
+                _ => {}
+            }),
+            cx.subscribe(&buffer_store, |this, _, event, cx| match event {
+                BufferStoreEvent::BufferAdded(buffer) => this.watch_buffer(buffer, cx),
+                BufferStoreEvent::BufferDropped(buffer_id) => this.buffer_dropped(*buffer_id, cx),
+                BufferStoreEvent::BufferChangedFilePath { buffer, old_file } => {
+                    if let Some(old_file) = old_file {
+                        let old_path = ProjectPath::from_file(old_file.as_ref(), cx);
+                        this.set_items(old_path, Vec::new(), cx);
+                    }
+                    this.watch_buffer(buffer, cx);
+                    this.changed_buffers
+                        .insert(buffer.read(cx).remote_id(), buffer.downgrade());
+                    this.schedule_update(cx);
+                }
+                _ => {}
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| {
+                let tags = &TodoPanelSettings::get_global(cx).tags;
+                if *tags != this.tags {
+                    this.tags = tags.clone();
+                    this.regex = tags_regex(&this.tags);
+                    this.schedule_rescan(cx);
+                }
+            }),
+        ];
+
+        let tags = TodoPanelSettings::get_global(cx).tags.clone();
+        let mut this = Self {
+            project,
+            regex: tags_regex(&tags),
+            tags,
+            files: BTreeMap::default(),
+            scanned: false,
+            scanning: false,
+            limit_reached: false,
+            changed_paths: BTreeSet::default(),
+            changed_buffers: HashMap::default(),
+            open_buffers: HashMap::default(),
+            scan_task: Task::ready(()),
+            rescan_task: None,
+            update_task: None,
+            _subscriptions: subscriptions,
+        };
+        for buffer in buffer_store.read(cx).buffers().collect::<Vec<_>>() {
+            this.watch_buffer(&buffer, cx);
+        }
+        this
+    }
+
+    /// Searches the project for the tags, unless it was already searched.
+    pub fn ensure_scanned(&mut self, cx: &mut Context<Self>) {
+        if !self.scanned {
+            self.rescan(cx);
+        }
+    }
+
+    /// The files with tags and the tags found in each, ordered by path.
+    pub fn files(&self) -> &BTreeMap<ProjectPath, Vec<TodoItem>> {
+        &self.files
+    }
+
+    pub fn tags(&self) -> &[TodoTag] {
+        &self.tags
+    }
+
+    /// Whether the project is still being searched for tags.
+    pub fn is_scanning(&self) -> bool {
+        self.scanning
+    }
+
+    /// Whether the project search stopped at its limit on the number of
+    /// results, leaving some files out.
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached
+    }
+
+    /// Discards the found tags and searches the project for them again.
+    pub fn rescan(&mut self, cx: &mut Context<Self>) {
+        self.scanned = true;
+        self.rescan_task = None;
+        self.files.clear();
+        self.changed_paths.clear();
+        self.changed_buffers.clear();
+        self.limit_reached = false;
+        let Some(regex) = self.regex.clone() else {
+            self.scanning = false;
+            self.scan_task = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        self.scanning = true;
+        let project = self.project.clone();
+        self.scan_task = cx.spawn(async move |this, cx| {
+            scan_project(&this, project, regex, cx).await.log_err();
+            this.update(cx, |this, cx| {
+                this.scanning = false;
+                cx.notify();
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    /// Searches the project again shortly, so that worktrees added together,
+    /// e.g. while a workspace is restored, are covered by a single search.
+    fn schedule_rescan(&mut self, cx: &mut Context<Self>) {
+        if !self.scanned || self.rescan_task.is_some() {
+            return;
+        }
+        self.rescan_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            this.update(cx, |this, cx| this.rescan(cx)).ok();
+        }));
+    }
+
+    fn worktree_removed(&mut self, worktree_id: WorktreeId, cx: &mut Context<Self>) {
+        self.files.retain(|path, _| path.worktree_id != worktree_id);
+        self.changed_paths
+            .retain(|path| path.worktree_id != worktree_id);
+        cx.notify();
+    }
+
+    fn watch_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let path = buffer
+            .read(cx)
+            .file()
+            .map(|file| ProjectPath::from_file(file.as_ref(), cx));
+        let edited = self
+            .open_buffers
+            .get(&buffer_id)
+            .is_some_and(|open_buffer| open_buffer.edited);
+        let subscription = cx.subscribe(buffer, move |this, buffer, event, cx| match event {
+            BufferEvent::Edited { .. } => {
+                if let Some(open_buffer) = this.open_buffers.get_mut(&buffer_id) {
+                    open_buffer.edited = true;
+                }
+            }
+            // Buffers opened by the project search are scanned by it, so only
+            // edited buffers are scanned again once they're parsed.
+            BufferEvent::Reparsed => {
+                if this
+                    .open_buffers
+                    .get(&buffer_id)
+                    .is_some_and(|open_buffer| open_buffer.edited)
+                {
+                    this.changed_buffers.insert(buffer_id, buffer.downgrade());
+                    this.schedule_update(cx);
+                }
+            }
+            BufferEvent::LanguageChanged(_) => {
+                this.changed_buffers.insert(buffer_id, buffer.downgrade());
+                this.schedule_update(cx);
+            }
+            _ => {}
+        });
+        self.open_buffers.insert(
+            buffer_id,
+            OpenBuffer {
+                path,
+                edited,
+                _subscription: subscription,
+            },
+        );
+    }
+
+    fn buffer_dropped(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        self.changed_buffers.remove(&buffer_id);
+        if let Some(open_buffer) = self.open_buffers.remove(&buffer_id)
+            && open_buffer.edited
+            && let Some(path) = open_buffer.path
+        {
+            self.changed_paths.insert(path);
+            self.schedule_update(cx);
+        }
+    }
+
+    fn set_items(&mut self, path: ProjectPath, items: Vec<TodoItem>, cx: &mut Context<Self>) {
+        let changed = if items.is_empty() {
+            self.files.remove(&path).is_some()
+        } else {
+            self.files.insert(path, items.clone()) != Some(items)
+        };
+        if changed {
+            cx.notify();
+        }
+    }
+
+    fn schedule_update(&mut self, cx: &mut Context<Self>) {
+        // Until the project is searched, changes are covered by that search.
+        if !self.scanned {
+            self.changed_paths.clear();
+            self.changed_buffers.clear();
+            return;
+        }
+        if self.update_task.is_some() {
+            return;
+        }
+        self.update_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            loop {
+                let Ok(Some((project, regex, paths, buffers))) = this.update(cx, |this, _| {
+                    if this.changed_paths.is_empty() && this.changed_buffers.is_empty() {
+                        this.update_task = None;
+                        return None;
+                    }
+                    let paths = std::mem::take(&mut this.changed_paths);
+                    let buffers = std::mem::take(&mut this.changed_buffers);
+                    Some((this.project.clone(), this.regex.clone(), paths, buffers))
+                }) else {
+                    break;
+                };
+                // Without tags, nothing is listed, so there's nothing to update.
+                let Some(regex) = regex else {
+                    continue;
+                };
+                let languages = project.read_with(cx, |project, _| project.languages().clone());
+
+                for buffer in buffers.into_values().filter_map(|buffer| buffer.upgrade()) {
+                    let Some(path) = buffer.read_with(cx, |buffer, cx| {
+                        buffer
+                            .file()
+                            .map(|file| ProjectPath::from_file(file.as_ref(), cx))
+                    }) else {
+                        continue;
+                    };
+                    let snapshot = parsed_snapshot(&buffer, &languages, cx).await;
+                    let items = find_todos_in_background(snapshot, regex.clone(), cx).await;
+                    if this
+                        .update(cx, |this, cx| this.set_items(path, items, cx))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+
+                for path in paths {
+                    let items = scan_path(&project, &path, &regex, &languages, cx)
+                        .await
+                        .log_err()
+                        .unwrap_or_default();
+                    if this
+                        .update(cx, |this, cx| this.set_items(path, items, cx))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        }));
+    }
+}
+
+/// Searches the project for the tags, once its worktrees are scanned, and
+/// adds those in comments to the index.
+async fn scan_project(
+    this: &WeakEntity<TodoIndex>,
+    project: Entity<Project>,
+    regex: Regex,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let scans_complete = project.read_with(cx, |project, cx| {
+        project
+            .worktrees(cx)
+            .filter_map(|worktree| {
+                worktree
+                    .read(cx)
+                    .as_local()
+                    .map(|local| local.scan_complete())
+            })
+            .collect::<Vec<_>>()
+    });
+    futures::future::join_all(scans_complete).await;
+
+    let query = SearchQuery::regex(
+        regex.as_str(),
+        false,
+        true,
+        false,
+        true,
+        PathMatcher::default(),
+        PathMatcher::default(),
+        false,
+        None,
+    )?;
+    let languages = project.read_with(cx, |project, _| project.languages().clone());
+    let SearchResults { rx, _task_handle } =
+        project.update(cx, |project, cx| project.search(query, cx));
+    futures::pin_mut!(rx);
+    while let Some(result) = rx.next().await {
+        match result {
+            SearchResult::Buffer { buffer, .. } => {
+                let Some(path) = buffer.read_with(cx, |buffer, cx| {
+                    buffer
+                        .file()
+                        .map(|file| ProjectPath::from_file(file.as_ref(), cx))
+                }) else {
+                    continue;
+                };
+                let snapshot = parsed_snapshot(&buffer, &languages, cx).await;
+                drop(buffer);
+                let items = find_todos_in_background(snapshot, regex.clone(), cx).await;
+                this.update(cx, |this, cx| this.set_items(path, items, cx))?;
+            }
+            SearchResult::LimitReached => {
+                this.update(cx, |this, cx| {
+                    this.limit_reached = true;
+                    cx.notify();
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds the tags in a file that changed on disk, reading it from disk in
+/// local worktrees and opening it in remote ones.
+async fn scan_path(
+    project: &Entity<Project>,
+    path: &ProjectPath,
+    regex: &Regex,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TodoItem>> {
+    enum Source {
+        Buffer(Entity<Buffer>),
+        Disk(std::path::PathBuf, Arc<dyn project::Fs>),
+        Remote,
+    }
+
+    let source = project.read_with(cx, |project, cx| {
+        if let Some(buffer) = project.get_open_buffer(path, cx) {
+            return Some(Source::Buffer(buffer));
+        }
+        let worktree = project.worktree_for_id(path.worktree_id, cx)?.read(cx);
+        let entry = worktree.entry_for_path(&path.path)?;
+        if !entry.is_file() || entry.is_ignored || entry.is_external {
+            return None;
+        }
+        Some(if worktree.is_local() {
+            Source::Disk(worktree.absolutize(&path.path), project.fs().clone())
+        } else {
+            Source::Remote
+        })
+    });
+
+    let snapshot = match source {
+        None => return Ok(Vec::new()),
+        Some(Source::Buffer(buffer)) => parsed_snapshot(&buffer, languages, cx).await,
+        Some(Source::Remote) => {
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(path.clone(), cx))
+                .await?;
+            parsed_snapshot(&buffer, languages, cx).await
+        }
+        Some(Source::Disk(abs_path, fs)) => {
+            // Files that aren't text can't be loaded, and have no comments.
+            let Ok(text) = fs.load(&abs_path).await else {
+                return Ok(Vec::new());
+            };
+            if !regex.is_match(&text) {
+                return Ok(Vec::new());
+            }
+            let language = languages
+                .load_language_for_file_path(path.path.as_std_path())
+                .await
+                .ok();
+            let snapshot = cx.update(|cx| {
+                Buffer::build_snapshot(
+                    Rope::from(text.as_str()),
+                    language,
+                    Some(languages.clone()),
+                    cx,
+                )
+            });
+            snapshot.await
+        }
+    };
+    Ok(find_todos_in_background(snapshot, regex.clone(), cx).await)
+}
+
+/// Waits for the buffer to be parsed and returns its snapshot. Buffers whose
+/// language hasn't been assigned yet are parsed with the language of their
+/// path, so that their comments can be found.
+async fn parsed_snapshot(
+    buffer: &Entity<Buffer>,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> BufferSnapshot {
+    let parsing_idle = buffer.read_with(cx, |buffer, _| buffer.parsing_idle());
+    parsing_idle.await;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    if snapshot.language().is_some() {
+        return snapshot;
+    }
+    let Some(path) = snapshot.file().map(|file| file.path().clone()) else {
+        return snapshot;
+    };
+    let Ok(language) = languages
+        .load_language_for_file_path(path.as_std_path())
+        .await
+    else {
+        return snapshot;
+    };
+    let snapshot = cx.update(|cx| {
+        Buffer::build_snapshot(
+            snapshot.as_rope().clone(),
+            Some(language),
+            Some(languages.clone()),
+            cx,
+        )
+    });
+    snapshot.await
+}
+
+async fn find_todos_in_background(
+    snapshot: BufferSnapshot,
+    regex: Regex,
+    cx: &mut AsyncApp,
+) -> Vec<TodoItem> {
+    cx.background_spawn(async move { find_todos(&snapshot, &regex) })
+        .await
+}
+
+/// Returns a regex matching any of the tags as whole words, or `None` if
+/// there are no tags.
+pub(crate) fn tags_regex(tags: &[TodoTag]) -> Option<Regex> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let alternatives = tags
+        .iter()
+        .map(|tag| {
+            let start = if tag.name.starts_with(is_word_char) {
+                r"\b"
+            } else {
+                ""
+            };
+            let end = if tag.name.ends_with(is_word_char) {
+                r"\b"
+            } else {
+                ""
+            };
+            format!("{start}{}{end}", regex::escape(&tag.name))
+        })
+        .collect::<Vec<_>>();
+    if alternatives.is_empty() {
+        return None;
+    }
+    Regex::new(&alternatives.join("|")).log_err()
+}
+
+/// Returns the tags matched by `regex` that are inside comments, according
+/// to the buffer's syntax layers.
+pub(crate) fn find_todos(snapshot: &BufferSnapshot, regex: &Regex) -> Vec<TodoItem> {
+    let text = snapshot.text();
+    regex
+        .find_iter(&text)
+        .filter_map(|tag| {
+            let comment = enclosing_comment(snapshot, tag.range())?;
+            let line_end = text[tag.end()..]
+                .find('\n')
+                .map_or(text.len(), |ix| tag.end() + ix);
+            let rest = &text[tag.end()..line_end.min(comment.end)];
+            let rest = rest
+                .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                .trim_end();
+            let rest = rest
+                .strip_suffix("*/")
+                .or_else(|| rest.strip_suffix("-->"))
+                .unwrap_or(rest)
+                .trim_end();
+            Some(TodoItem {
+                tag: tag.as_str().to_string().into(),
+                text: rest.to_string().into(),
+                position: snapshot.offset_to_point(tag.start()),
+            })
+        })
+        .collect()
+}
+
+/// Returns the range of the comment containing `range` in any of the syntax
+/// layers covering it.
+fn enclosing_comment(snapshot: &BufferSnapshot, range: Range<usize>) -> Option<Range<usize>> {
+    snapshot
+        .syntax_layers_for_range(range.clone(), true)
+        .find_map(|layer| {
+            let mut node = layer
+                .node()
+                .descendant_for_byte_range(range.start, range.end)?;
+            loop {
+                if node.kind().contains("comment") {
+                    return Some(node.byte_range());
+                }
+                node = node.parent()?;
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::{App, TestAppContext};
+    use indoc::indoc;
+    use language::rust_lang;
+    use serde_json::json;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+    }
+
+    /// Waits for buffers to be parsed and for the index's debounced update.
+    fn settle(cx: &mut TestAppContext) {
+        cx.run_until_parked();
+        cx.executor().advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+    }
+
+    fn default_regex() -> Regex {
+        tags_regex(&["TODO", "FIXME", "@hack"].map(|name| TodoTag {
+            name: name.into(),
+            color: None,
+        }))
+        .unwrap()
+    }
+
+    fn items(files: &BTreeMap<ProjectPath, Vec<TodoItem>>) -> Vec<(String, u32, String, String)> {
+        files
+            .iter()
+            .flat_map(|(path, items)| {
+                items.iter().map(|item| {
+                    (
+                        path.path.as_unix_str().to_string(),
+                        item.position.row,
+                        item.tag.to_string(),
+                        item.text.to_string(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn test_find_todos(cx: &mut App) {
+        let text = indoc! {r#"
+            // TODO: handle errors
+            fn main() {
+                let s = "TODO: not a comment";
+                /* FIXME(alice) leaks memory */
+                // TODOS and XTODO aren't tags, but @hack is
+                println!("{s}"); // TODO
+            }
+        "#};
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        let snapshot = buffer.read(cx).snapshot();
+        assert_eq!(
+            find_todos(&snapshot, &default_regex())
+                .into_iter()
+                .map(|item| (item.position, item.tag.to_string(), item.text.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    Point::new(0, 3),
+                    "TODO".to_string(),
+                    "handle errors".to_string()
+                ),
+                (
+                    Point::new(3, 7),
+                    "FIXME".to_string(),
+                    "(alice) leaks memory".to_string()
+                ),
+                (Point::new(4, 40), "@hack".to_string(), "is".to_string()),
+                (Point::new(5, 24), "TODO".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_index_updates(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "a.rs": "// TODO: first\nfn a() {}\n",
+                "b.rs": "fn b() { \"TODO\"; }\n",
+                "c.txt": "TODO: not code",
+            }),
+        )
+        .await;
+        let project = project::Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+        let index = cx.new(|cx| TodoIndex::new(project.clone(), cx));
+        cx.run_until_parked();
+        // The project isn't searched until the tags are needed.
+        index.read_with(cx, |index, _| {
+            assert!(!index.is_scanning());
+            assert!(index.files().is_empty());
+        });
+
+        index.update(cx, |index, cx| index.ensure_scanned(cx));
+        cx.run_until_parked();
+        assert_eq!(
+            index.read_with(cx, |index, _| items(index.files())),
+            [(
+                "a.rs".to_string(),
+                0,
+                "TODO".to_string(),
+                "first".to_string()
+            )]
+        );
+
+        // Edits to open buffers are reflected before they're saved.
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/b.rs"), cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "// FIXME: second\n")], None, cx)
+        });
+        settle(cx);
+        assert_eq!(
+            index.read_with(cx, |index, _| items(index.files())),
+            [
+                (
+                    "a.rs".to_string(),
+                    0,
+                    "TODO".to_string(),
+                    "first".to_string()
+                ),
+                (
+                    "b.rs".to_string(),
+                    0,
+                    "FIXME".to_string(),
+                    "second".to_string()
+                ),
+            ]
+        );
+
+        // Closing the buffer without saving it drops its changes.
+        drop(buffer);
+        settle(cx);
+        assert_eq!(
+            index.read_with(cx, |index, _| items(index.files())),
+            [(
+                "a.rs".to_string(),
+                0,
+                "TODO".to_string(),
+                "first".to_string()
+            )]
+        );
+
+        // Changes on disk are picked up from the worktree's events.
+        fs.insert_file(
+            path!("/project/d.rs"),
+            b"fn d() {}\n/* TODO: third */\n".to_vec(),
+        )
+        .await;
+        fs.remove_file(path!("/project/a.rs").as_ref(), Default::default())
+            .await
+            .unwrap();
+        settle(cx);
+        assert_eq!(
+            index.read_with(cx, |index, _| items(index.files())),
+            [(
+                "d.rs".to_string(),
+                1,
+                "TODO".to_string(),
+                "third".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,656 @@
+mod todo_index;
+mod todo_panel_settings;
+
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    Action, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, FontWeight, Hsla,
+    KeyContext, Render, ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity,
+    actions, uniform_list,
+};
+use menu::{
+    Confirm, SelectChild, SelectFirst, SelectLast, SelectNext, SelectParent, SelectPrevious,
+};
+use project::{Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use settings::{DockSide, Settings as _};
+use std::{ops::Range, sync::Arc};
+use ui::{ListItem, ListItemSpacing, Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+pub use todo_index::{TodoIndex, TodoItem};
+pub use todo_panel_settings::{TodoPanelSettings, TodoTag};
+
+const TODO_PANEL_KEY: &str = "TodoPanel";
+
+actions!(
+    todo_panel,
+    [
+        /// Toggles focus on the TODO panel.
+        ToggleFocus,
+        /// Searches the project for tags again.
+        Rescan,
+        /// Collapses all of the files in the TODO panel.
+        CollapseAllEntries,
+        /// Expands all of the files in the TODO panel.
+        ExpandAllEntries
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<TodoPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTodoPanel {
+    width: Option<Pixels>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Row {
+    File(ProjectPath),
+    Item(ProjectPath, usize),
+}
+
+/// Lists the tags found in the comments of the project's files, grouped by
+/// file.
+pub struct TodoPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    index: Entity<TodoIndex>,
+    rows: Vec<Row>,
+    collapsed: HashSet<ProjectPath>,
+    selected_row: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TodoPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = match workspace
+            .read_with(&cx, |workspace, _| Self::serialization_key(workspace))
+            .ok()
+            .flatten()
+        {
+            Some(serialization_key) => cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+                .await
+                .context("loading TODO panel")
+                .log_err()
+                .flatten()
+                .map(|panel| serde_json::from_str::<SerializedTodoPanel>(&panel))
+                .transpose()
+                .log_err()
+                .flatten(),
+            None => None,
+        };
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = Self::new(workspace, window, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace = workspace.weak_handle();
+        cx.new(|cx| {
+            let index = cx.new(|cx| TodoIndex::new(project.clone(), cx));
+            let mut old_dock_position = Self::dock_position(cx);
+            let subscriptions = vec![
+                cx.observe_in(&index, window, |this: &mut Self, _, _, cx| {
+                    this.update_rows(cx);
+                }),
+                cx.observe_global_in::<settings::SettingsStore>(
+                    window,
+                    move |_, _, cx: &mut Context<Self>| {
+                        let new_dock_position = Self::dock_position(cx);
+                        if new_dock_position != old_dock_position {
+                            old_dock_position = new_dock_position;
+                            cx.emit(PanelEvent::ZoomOut);
+                        }
+                        cx.notify();
+                    },
+                ),
+            ];
+            let mut this = Self {
+                workspace,
+                project,
+                fs,
+                index,
+                rows: Vec::new(),
+                collapsed: HashSet::default(),
+                selected_row: None,
+                scroll_handle: UniformListScrollHandle::new(),
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            };
+            this.update_rows(cx);
+            this
+        })
+    }
+
+    fn dock_position(cx: &App) -> DockPosition {
+        match TodoPanelSettings::get_global(cx).dock {
+            DockSide::Left => DockPosition::Left,
+            DockSide::Right => DockPosition::Right,
+        }
+    }
+
+    fn serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| i64::from(id).to_string())
+            .or(workspace.session_id())
+            .map(|id| format!("{}-{:?}", TODO_PANEL_KEY, id))
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self
+            .workspace
+            .read_with(cx, |workspace, _| Self::serialization_key(workspace))
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        serialization_key,
+                        serde_json::to_string(&SerializedTodoPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Rebuilds the rows from the index, keeping the selection on the same
+    /// row if it still exists.
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        let selected = self.selected_row.and_then(|ix| self.rows.get(ix)).cloned();
+        let index = self.index.read(cx);
+        self.collapsed
+            .retain(|path| index.files().contains_key(path));
+        self.rows.clear();
+        for (path, items) in index.files() {
+            self.rows.push(Row::File(path.clone()));
+            if !self.collapsed.contains(path) {
+                self.rows
+                    .extend((0..items.len()).map(|ix| Row::Item(path.clone(), ix)));
+            }
+        }
+        self.selected_row = selected.and_then(|selected| {
+            self.rows
+                .iter()
+                .position(|row| *row == selected)
+                .or_else(|| self.rows.len().checked_sub(1))
+        });
+        cx.notify();
+    }
+
+    fn item_count(&self, cx: &App) -> usize {
+        self.index.read(cx).files().values().map(Vec::len).sum()
+    }
+
+    fn select_row(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix < self.rows.len() {
+            self.selected_row = Some(ix);
+            self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_row.map_or(0, |ix| ix + 1);
+        self.select_row(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_row.and_then(|ix| ix.checked_sub(1)) {
+            self.select_row(ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_row(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.rows.len().checked_sub(1) {
+            self.select_row(ix, cx);
+        }
+    }
+
+    fn select_parent(&mut self, _: &SelectParent, _: &mut Window, cx: &mut Context<Self>) {
+        match self.selected_row.and_then(|ix| self.rows.get(ix)).cloned() {
+            Some(Row::File(path)) => self.set_collapsed(path, true, cx),
+            Some(Row::Item(path, _)) => {
+                if let Some(ix) = self
+                    .rows
+                    .iter()
+                    .position(|row| *row == Row::File(path.clone()))
+                {
+                    self.select_row(ix, cx);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn select_child(&mut self, _: &SelectChild, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(Row::File(path)) = self.selected_row.and_then(|ix| self.rows.get(ix)).cloned() {
+            self.set_collapsed(path, false, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_row {
+            self.activate_row(ix, window, cx);
+        }
+    }
+
+    fn rescan(&mut self, _: &Rescan, _: &mut Window, cx: &mut Context<Self>) {
+        self.index.update(cx, |index, cx| index.rescan(cx));
+    }
+
+    fn collapse_all(&mut self, _: &CollapseAllEntries, _: &mut Window, cx: &mut Context<Self>) {
+        self.collapsed = self.index.read(cx).files().keys().cloned().collect();
+        self.update_rows(cx);
+    }
+
+    fn expand_all(&mut self, _: &ExpandAllEntries, _: &mut Window, cx: &mut Context<Self>) {
+        self.collapsed.clear();
+        self.update_rows(cx);
+    }
+
+    fn set_collapsed(&mut self, path: ProjectPath, collapsed: bool, cx: &mut Context<Self>) {
+        if collapsed {
+            self.collapsed.insert(path);
+        } else {
+            self.collapsed.remove(&path);
+        }
+        self.update_rows(cx);
+    }
+
+    /// Toggles a file's row, or opens the file at an item's tag.
+    fn activate_row(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_row = Some(ix);
+        match self.rows.get(ix).cloned() {
+            Some(Row::File(path)) => {
+                let collapsed = !self.collapsed.contains(&path);
+                self.set_collapsed(path, collapsed, cx);
+            }
+            Some(Row::Item(path, item_ix)) => {
+                let Some(item) = self
+                    .index
+                    .read(cx)
+                    .files()
+                    .get(&path)
+                    .and_then(|items| items.get(item_ix))
+                else {
+                    return;
+                };
+                let position = item.position;
+                let Some(open_task) = self
+                    .workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.open_path(path, None, true, window, cx)
+                    })
+                    .log_err()
+                else {
+                    return;
+                };
+                cx.spawn_in(window, async move |_, cx| {
+                    let item = open_task.await?;
+                    if let Some(editor) = item.downcast::<Editor>() {
+                        editor.update_in(cx, |editor, window, cx| {
+                            editor.go_to_singleton_buffer_point(position, window, cx);
+                        })?;
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+                cx.notify();
+            }
+            None => {}
+        }
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("TodoPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_row(
+        &self,
+        ix: usize,
+        tag_colors: &HashMap<SharedString, Hsla>,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let index = self.index.read(cx);
+        let selected = self.selected_row == Some(ix);
+        let row = match self.rows.get(ix)? {
+            Row::File(path) => {
+                let count = index.files().get(path)?.len();
+                let file_name = path.path.file_name().unwrap_or_default().to_string();
+                let path_style = self.project.read(cx).path_style(cx);
+                let directory = path
+                    .path
+                    .parent()
+                    .map(|parent| parent.display(path_style).into_owned())
+                    .unwrap_or_default();
+                let collapsed = self.collapsed.contains(path);
+                ListItem::new(ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle_state(selected)
+                    .start_slot(
+                        Icon::new(if collapsed {
+                            IconName::ChevronRight
+                        } else {
+                            IconName::ChevronDown
+                        })
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .min_w_0()
+                            .child(Label::new(file_name).truncate())
+                            .child(
+                                Label::new(directory)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                    )
+                    .end_slot(
+                        Label::new(count.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            }
+            Row::Item(path, item_ix) => {
+                let item = index.files().get(path)?.get(*item_ix)?;
+                let color = tag_colors
+                    .get(&item.tag)
+                    .copied()
+                    .unwrap_or_else(|| cx.theme().colors().text_accent);
+                ListItem::new(ix)
+                    .spacing(ListItemSpacing::Sparse)
+                    .indent_level(1)
+                    .toggle_state(selected)
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .min_w_0()
+                            .child(
+                                Label::new(item.tag.clone())
+                                    .size(LabelSize::Small)
+                                    .weight(FontWeight::BOLD)
+                                    .color(Color::Custom(color)),
+                            )
+                            .child(Label::new(item.text.clone()).truncate()),
+                    )
+                    .end_slot(
+                        Label::new(format!("{}", item.position.row + 1))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            }
+        };
+        Some(
+            row.on_click(cx.listener(move |this, _, window, cx| {
+                this.activate_row(ix, window, cx);
+            }))
+            .into_any_element(),
+        )
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let index = self.index.read(cx);
+        let status = if index.is_scanning() {
+            "Searching…".to_string()
+        } else {
+            let count = self.item_count(cx);
+            let files = index.files().len();
+            format!(
+                "{count} {} in {files} {}",
+                if count == 1 { "tag" } else { "tags" },
+                if files == 1 { "file" } else { "files" },
+            )
+        };
+        let limit_reached = index.limit_reached();
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .min_w_0()
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                    .when(limit_reached, |this| {
+                        this.child(
+                            div()
+                                .id("limit-reached")
+                                .child(
+                                    Icon::new(IconName::Warning)
+                                        .size(IconSize::Small)
+                                        .color(Color::Warning),
+                                )
+                                .tooltip(Tooltip::text(
+                                    "The search stopped at its result limit, so some files aren't listed",
+                                )),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new("collapse-all", IconName::ListCollapse)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Collapse All"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.collapse_all(&CollapseAllEntries, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("rescan", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Search Again"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.rescan(&Rescan, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for TodoPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tag_colors = self
+            .index
+            .read(cx)
+            .tags()
+            .iter()
+            .filter_map(|tag| Some((tag.name.clone(), tag.color?)))
+            .collect::<HashMap<_, _>>();
+        let is_empty = self.rows.is_empty();
+        let is_scanning = self.index.read(cx).is_scanning();
+
+        v_flex()
+            .id("todo-panel")
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::select_parent))
+            .on_action(cx.listener(Self::select_child))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::rescan))
+            .on_action(cx.listener(Self::collapse_all))
+            .on_action(cx.listener(Self::expand_all))
+            .child(self.render_header(cx))
+            .map(|this| {
+                if is_empty {
+                    this.child(
+                        v_flex().p_4().items_center().child(
+                            Label::new(if is_scanning {
+                                "Searching the project's comments…"
+                            } else {
+                                "No tags in the project's comments"
+                            })
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .flex_1()
+                            .min_h_0()
+                            .child(
+                                uniform_list(
+                                    "todo-entries",
+                                    self.rows.len(),
+                                    cx.processor(move |this, range: Range<usize>, _, cx| {
+                                        range
+                                            .filter_map(|ix| this.render_row(ix, &tag_colors, cx))
+                                            .collect()
+                                    }),
+                                )
+                                .size_full()
+                                .track_scroll(&self.scroll_handle),
+                            )
+                            .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for TodoPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TodoPanel {}
+
+impl Panel for TodoPanel {
+    fn persistent_name() -> &'static str {
+        "TodoPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        TODO_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        Self::dock_position(cx)
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            let dock = match position {
+                DockPosition::Left | DockPosition::Bottom => DockSide::Left,
+                DockPosition::Right => DockSide::Right,
+            };
+            settings.todo_panel.get_or_insert_default().dock = Some(dock);
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TodoPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        // The project is searched for tags the first time the panel is shown,
+        // rather than whenever a workspace is opened.
+        if active {
+            self.index.update(cx, |index, cx| index.ensure_scanned(cx));
+        }
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        TodoPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("TODO Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
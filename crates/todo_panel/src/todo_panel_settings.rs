@@ -0,0 +1,44 @@
+use gpui::{Hsla, Pixels, Rgba, SharedString, px};
+use settings::{DockSide, RegisterSetting, Settings};
+
+#[derive(Debug, Clone, PartialEq, RegisterSetting)]
+pub struct TodoPanelSettings {
+    pub button: bool,
+    pub dock: DockSide,
+    pub default_width: Pixels,
+    pub tags: Vec<TodoTag>,
+}
+
+/// A tag listed in the TODO panel when it appears in a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoTag {
+    pub name: SharedString,
+    /// The color of the tag, or `None` to use the theme's accent color.
+    pub color: Option<Hsla>,
+}
+
+impl Settings for TodoPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.todo_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap(),
+            default_width: panel.default_width.map(px).unwrap(),
+            tags: panel
+                .tags
+                .as_ref()
+                .unwrap()
+                .iter()
+                .filter(|tag| !tag.name.is_empty())
+                .map(|tag| TodoTag {
+                    name: tag.name.clone().into(),
+                    color: tag
+                        .color
+                        .as_deref()
+                        .and_then(|color| Rgba::try_from(color).ok())
+                        .map(Hsla::from),
+                })
+                .collect(),
+        }
+    }
+}
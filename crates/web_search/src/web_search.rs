@@ -16,6 +16,12 @@ pub struct WebSearchProviderId(pub SharedString);
 pub trait WebSearchProvider {
     fn id(&self) -> WebSearchProviderId;
     fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>>;
+
+    /// Whether the provider can only be used with the language models
+    /// provided by Zed.
+    fn requires_zed_models(&self) -> bool {
+        false
+    }
 }
 
 struct GlobalWebSearchRegistry(Entity<WebSearchRegistry>);
@@ -26,6 +32,9 @@ impl Global for GlobalWebSearchRegistry {}
 pub struct WebSearchRegistry {
     providers: HashMap<WebSearchProviderId, Arc<dyn WebSearchProvider>>,
     active_provider: Option<Arc<dyn WebSearchProvider>>,
+    /// The providers configured in the settings, which are only used when
+    /// they're selected.
+    setting_providers: HashMap<WebSearchProviderId, Arc<dyn WebSearchProvider>>,
 }
 
 impl WebSearchRegistry {
@@ -33,12 +42,27 @@ impl WebSearchRegistry {
         cx.global::<GlobalWebSearchRegistry>().0.clone()
     }
 
+    /// Returns the global registry, if web search was initialized.
+    pub fn try_global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalWebSearchRegistry>()
+            .map(|registry| registry.0.clone())
+    }
+
     pub fn read_global(cx: &App) -> &Self {
         cx.global::<GlobalWebSearchRegistry>().0.read(cx)
     }
 
     pub fn providers(&self) -> impl Iterator<Item = &Arc<dyn WebSearchProvider>> {
-        self.providers.values()
+        self.providers
+            .values()
+            .chain(self.setting_providers.values())
+    }
+
+    pub fn provider(&self, id: &WebSearchProviderId) -> Option<Arc<dyn WebSearchProvider>> {
+        self.providers
+            .get(id)
+            .or_else(|| self.setting_providers.get(id))
+            .cloned()
     }
 
     pub fn active_provider(&self) -> Option<Arc<dyn WebSearchProvider>> {
@@ -63,6 +87,17 @@ impl WebSearchRegistry {
         }
     }
 
+    /// Replaces the providers configured in the settings.
+    pub fn set_setting_providers(
+        &mut self,
+        providers: impl IntoIterator<Item = Arc<dyn WebSearchProvider>>,
+    ) {
+        self.setting_providers = providers
+            .into_iter()
+            .map(|provider| (provider.id(), provider))
+            .collect();
+    }
+
     pub fn unregister_provider(&mut self, id: WebSearchProviderId) {
         self.providers.remove(&id);
         if self.active_provider.as_ref().map(|provider| provider.id()) == Some(id) {
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use cloud_llm_client::{WebSearchResponse, WebSearchResult};
+use gpui::{App, AppContext as _, Task};
+use http_client::{HttpClient, Method, Url};
+use serde::Deserialize;
+use web_search::{WebSearchProvider, WebSearchProviderId};
+
+use crate::{MAX_RESULTS, api_key_from_env, fetch_json, strip_html_tags};
+
+pub const BRAVE_WEB_SEARCH_PROVIDER_ID: &str = "brave";
+pub const BRAVE_SEARCH_API_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const BRAVE_SEARCH_API_KEY_ENV_VAR_NAME: &str = "BRAVE_SEARCH_API_KEY";
+
+pub struct BraveWebSearchProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+}
+
+impl BraveWebSearchProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, api_url: Option<String>) -> Self {
+        Self {
+            http_client,
+            api_url: api_url.unwrap_or_else(|| BRAVE_SEARCH_API_URL.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveWebResults>,
+}
+
+#[derive(Deserialize)]
+struct BraveWebResults {
+    results: Vec<BraveResult>,
+}
+
+#[derive(Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+}
+
+impl WebSearchProvider for BraveWebSearchProvider {
+    fn id(&self) -> WebSearchProviderId {
+        WebSearchProviderId(BRAVE_WEB_SEARCH_PROVIDER_ID.into())
+    }
+
+    fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>> {
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+        cx.background_spawn(async move {
+            let api_key = api_key_from_env(BRAVE_SEARCH_API_KEY_ENV_VAR_NAME)?;
+            let mut url = Url::parse(&api_url)
+                .with_context(|| format!("invalid Brave Search API URL {api_url:?}"))?;
+            url.query_pairs_mut()
+                .append_pair("q", &query)
+                .append_pair("count", &MAX_RESULTS.to_string());
+            let request = http_client::Request::builder()
+                .method(Method::GET)
+                .uri(url.as_str())
+                .header("Accept", "application/json")
+                .header("X-Subscription-Token", api_key)
+                .body(Default::default())?;
+            let response: BraveResponse =
+                serde_json::from_value(fetch_json(http_client.as_ref(), request).await?)
+                    .context("unexpected Brave Search response")?;
+            Ok(WebSearchResponse {
+                results: response
+                    .web
+                    .map(|web| web.results)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .take(MAX_RESULTS)
+                    .map(|result| WebSearchResult {
+                        // Brave highlights the query's terms with HTML tags.
+                        title: strip_html_tags(&result.title),
+                        url: result.url,
+                        text: strip_html_tags(&result.description),
+                    })
+                    .collect(),
+            })
+        })
+    }
+}
@@ -47,6 +47,10 @@ impl WebSearchProvider for CloudWebSearchProvider {
         WebSearchProviderId(ZED_WEB_SEARCH_PROVIDER_ID.into())
     }
 
+    fn requires_zed_models(&self) -> bool {
+        true
+    }
+
     fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>> {
         let state = self.state.read(cx);
         let client = state.client.clone();
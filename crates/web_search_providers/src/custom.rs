@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use cloud_llm_client::{WebSearchResponse, WebSearchResult};
+use gpui::{App, AppContext as _, Task};
+use http_client::{HttpClient, Method, Url};
+use settings::{CustomWebSearchMethod, CustomWebSearchSettingsContent};
+use web_search::{WebSearchProvider, WebSearchProviderId};
+
+use crate::{MAX_RESULTS, fetch_json};
+
+pub const CUSTOM_WEB_SEARCH_PROVIDER_ID: &str = "custom";
+
+/// Searches with any HTTP endpoint that returns results as JSON, finding the
+/// results in the response with the JSON pointers from the settings.
+pub struct CustomWebSearchProvider {
+    http_client: Arc<dyn HttpClient>,
+    url: String,
+    method: CustomWebSearchMethod,
+    query_parameter: String,
+    headers: Vec<(String, String)>,
+    fields: ResultFields,
+}
+
+#[derive(Clone, Debug)]
+struct ResultFields {
+    results: String,
+    title: String,
+    url: String,
+    text: String,
+}
+
+impl CustomWebSearchProvider {
+    /// Returns `None` when the settings don't have a URL to search with.
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        settings: CustomWebSearchSettingsContent,
+    ) -> Option<Self> {
+        let url = settings.url.filter(|url| !url.trim().is_empty())?;
+        let fields = settings.fields.unwrap_or_default();
+        Some(Self {
+            http_client,
+            url,
+            method: settings.method.unwrap_or_default(),
+            query_parameter: settings.query_parameter.unwrap_or_else(|| "q".to_string()),
+            headers: settings.headers.unwrap_or_default().into_iter().collect(),
+            fields: ResultFields {
+                results: fields.results.unwrap_or_else(|| "/results".to_string()),
+                title: fields.title.unwrap_or_else(|| "/title".to_string()),
+                url: fields.url.unwrap_or_else(|| "/url".to_string()),
+                text: fields.text.unwrap_or_else(|| "/snippet".to_string()),
+            },
+        })
+    }
+}
+
+impl WebSearchProvider for CustomWebSearchProvider {
+    fn id(&self) -> WebSearchProviderId {
+        WebSearchProviderId(CUSTOM_WEB_SEARCH_PROVIDER_ID.into())
+    }
+
+    fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>> {
+        let http_client = self.http_client.clone();
+        let url = self.url.clone();
+        let method = self.method;
+        let query_parameter = self.query_parameter.clone();
+        let headers = self.headers.clone();
+        let fields = self.fields.clone();
+        cx.background_spawn(async move {
+            let mut url =
+                Url::parse(&url).with_context(|| format!("invalid web search URL {url:?}"))?;
+            let mut request = http_client::Request::builder().header("Accept", "application/json");
+            for (name, value) in &headers {
+                let value = expand_env_vars(value, |name| std::env::var(name).ok())
+                    .with_context(|| format!("invalid value for web search header {name:?}"))?;
+                request = request.header(name.as_str(), value);
+            }
+            let request = match method {
+                CustomWebSearchMethod::Get => {
+                    url.query_pairs_mut().append_pair(&query_parameter, &query);
+                    request
+                        .method(Method::GET)
+                        .uri(url.as_str())
+                        .body(Default::default())?
+                }
+                CustomWebSearchMethod::Post => {
+                    let body = serde_json::Value::Object(
+                        [(query_parameter, serde_json::Value::String(query))]
+                            .into_iter()
+                            .collect(),
+                    );
+                    request
+                        .method(Method::POST)
+                        .uri(url.as_str())
+                        .header("Content-Type", "application/json")
+                        .body(serde_json::to_string(&body)?.into())?
+                }
+            };
+            let response = fetch_json(http_client.as_ref(), request).await?;
+            Ok(WebSearchResponse {
+                results: parse_results(&response, &fields)?,
+            })
+        })
+    }
+}
+
+fn parse_results(
+    response: &serde_json::Value,
+    fields: &ResultFields,
+) -> Result<Vec<WebSearchResult>> {
+    let results = response
+        .pointer(&fields.results)
+        .and_then(|results| results.as_array())
+        .ok_or_else(|| {
+            anyhow!(
+                "the web search response has no array of results at {:?}",
+                fields.results
+            )
+        })?;
+    let field = |result: &serde_json::Value, pointer: &str| match result.pointer(pointer) {
+        Some(serde_json::Value::String(text)) => Some(text.clone()),
+        Some(serde_json::Value::Null) | None => None,
+        Some(value) => Some(value.to_string()),
+    };
+    Ok(results
+        .iter()
+        .filter_map(|result| {
+            Some(WebSearchResult {
+                url: field(result, &fields.url)?,
+                title: field(result, &fields.title).unwrap_or_default(),
+                text: field(result, &fields.text).unwrap_or_default(),
+            })
+        })
+        .take(MAX_RESULTS)
+        .collect())
+}
+
+/// Replaces each `${NAME}` in the value with the value of the `NAME`
+/// environment variable.
+fn expand_env_vars(value: &str, get_var: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .context("unterminated `${`")?;
+        let name = &rest[start + 2..end];
+        let var =
+            get_var(name).with_context(|| format!("the environment variable {name} isn't set"))?;
+        expanded.push_str(&var);
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::AsyncReadExt as _;
+    use gpui::TestAppContext;
+    use http_client::{AsyncBody, FakeHttpClient, Response};
+    use serde_json::json;
+
+    fn fields() -> ResultFields {
+        ResultFields {
+            results: "/data/items".to_string(),
+            title: "/name".to_string(),
+            url: "/link".to_string(),
+            text: "/summary/text".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let response = json!({
+            "data": {
+                "items": [
+                    {"name": "Zed", "link": "https://zed.dev", "summary": {"text": "A code editor"}},
+                    {"name": "No link"},
+                    {"link": "https://example.com", "summary": {"text": 42}},
+                ]
+            }
+        });
+        let results = parse_results(&response, &fields()).unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (
+                    result.title.as_str(),
+                    result.url.as_str(),
+                    result.text.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("Zed", "https://zed.dev", "A code editor"),
+                ("", "https://example.com", "42"),
+            ]
+        );
+
+        let error = parse_results(&json!({"results": []}), &fields()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the web search response has no array of results at \"/data/items\""
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        let get_var = |name: &str| (name == "SEARCH_KEY").then(|| "secret".to_string());
+        assert_eq!(
+            expand_env_vars("Bearer ${SEARCH_KEY}", get_var).unwrap(),
+            "Bearer secret"
+        );
+        assert_eq!(expand_env_vars("plain", get_var).unwrap(), "plain");
+        assert!(expand_env_vars("${MISSING}", get_var).is_err());
+        assert!(expand_env_vars("${SEARCH_KEY", get_var).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_custom_provider_search(cx: &mut TestAppContext) {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.method(), Method::POST);
+            assert_eq!(request.uri().to_string(), "http://search.internal/api");
+            assert_eq!(request.headers()["X-Team"], "tools");
+            let mut body = String::new();
+            request.into_body().read_to_string(&mut body).await?;
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&body)?,
+                json!({"query": "zed editor"})
+            );
+            Ok(Response::builder().status(200).body(AsyncBody::from(
+                json!({
+                    "data": {
+                        "items": [
+                            {"name": "Zed", "link": "https://zed.dev", "summary": {"text": "A code editor"}}
+                        ]
+                    }
+                })
+                .to_string(),
+            ))?)
+        });
+        let provider = CustomWebSearchProvider::new(
+            http_client,
+            CustomWebSearchSettingsContent {
+                url: Some("http://search.internal/api".to_string()),
+                method: Some(CustomWebSearchMethod::Post),
+                query_parameter: Some("query".to_string()),
+                headers: Some(
+                    [("X-Team".to_string(), "tools".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+                fields: Some(settings::CustomWebSearchFieldsContent {
+                    results: Some("/data/items".to_string()),
+                    title: Some("/name".to_string()),
+                    url: Some("/link".to_string()),
+                    text: Some("/summary/text".to_string()),
+                }),
+            },
+        )
+        .unwrap();
+
+        let response = cx
+            .update(|cx| provider.search("zed editor".to_string(), cx))
+            .await
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].url, "https://zed.dev");
+        assert_eq!(response.results[0].text, "A code editor");
+    }
+}
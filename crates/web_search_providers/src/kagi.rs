@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use cloud_llm_client::{WebSearchResponse, WebSearchResult};
+use gpui::{App, AppContext as _, Task};
+use http_client::{HttpClient, Method, Url};
+use serde::Deserialize;
+use web_search::{WebSearchProvider, WebSearchProviderId};
+
+use crate::{MAX_RESULTS, api_key_from_env, fetch_json};
+
+pub const KAGI_WEB_SEARCH_PROVIDER_ID: &str = "kagi";
+pub const KAGI_SEARCH_API_URL: &str = "https://kagi.com/api/v0/search";
+const KAGI_API_KEY_ENV_VAR_NAME: &str = "KAGI_API_KEY";
+
+/// The type of the search results in Kagi's responses, as opposed to related
+/// searches.
+const KAGI_SEARCH_RESULT_TYPE: u32 = 0;
+
+pub struct KagiWebSearchProvider {
+    http_client: Arc<dyn HttpClient>,
+    api_url: String,
+}
+
+impl KagiWebSearchProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, api_url: Option<String>) -> Self {
+        Self {
+            http_client,
+            api_url: api_url.unwrap_or_else(|| KAGI_SEARCH_API_URL.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct KagiResponse {
+    data: Vec<KagiResult>,
+}
+
+#[derive(Deserialize)]
+struct KagiResult {
+    t: u32,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    snippet: Option<String>,
+}
+
+impl WebSearchProvider for KagiWebSearchProvider {
+    fn id(&self) -> WebSearchProviderId {
+        WebSearchProviderId(KAGI_WEB_SEARCH_PROVIDER_ID.into())
+    }
+
+    fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>> {
+        let http_client = self.http_client.clone();
+        let api_url = self.api_url.clone();
+        cx.background_spawn(async move {
+            let api_key = api_key_from_env(KAGI_API_KEY_ENV_VAR_NAME)?;
+            let mut url = Url::parse(&api_url)
+                .with_context(|| format!("invalid Kagi API URL {api_url:?}"))?;
+            url.query_pairs_mut()
+                .append_pair("q", &query)
+                .append_pair("limit", &MAX_RESULTS.to_string());
+            let request = http_client::Request::builder()
+                .method(Method::GET)
+                .uri(url.as_str())
+                .header("Accept", "application/json")
+                .header("Authorization", format!("Bot {api_key}"))
+                .body(Default::default())?;
+            let response: KagiResponse =
+                serde_json::from_value(fetch_json(http_client.as_ref(), request).await?)
+                    .context("unexpected Kagi response")?;
+            Ok(WebSearchResponse {
+                results: response
+                    .data
+                    .into_iter()
+                    .filter(|result| result.t == KAGI_SEARCH_RESULT_TYPE)
+                    .take(MAX_RESULTS)
+                    .map(|result| WebSearchResult {
+                        title: result.title,
+                        url: result.url,
+                        text: result.snippet.unwrap_or_default(),
+                    })
+                    .collect(),
+            })
+        })
+    }
+}
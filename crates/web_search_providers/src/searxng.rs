@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use cloud_llm_client::{WebSearchResponse, WebSearchResult};
+use gpui::{App, AppContext as _, Task};
+use http_client::{HttpClient, Method, Url};
+use serde::Deserialize;
+use web_search::{WebSearchProvider, WebSearchProviderId};
+
+use crate::{MAX_RESULTS, fetch_json};
+
+pub const SEARXNG_WEB_SEARCH_PROVIDER_ID: &str = "searxng";
+
+/// Searches with a SearXNG instance. The instance must have the JSON output
+/// format enabled in its `search.formats` setting.
+pub struct SearxngWebSearchProvider {
+    http_client: Arc<dyn HttpClient>,
+    url: String,
+}
+
+impl SearxngWebSearchProvider {
+    pub fn new(http_client: Arc<dyn HttpClient>, url: String) -> Self {
+        Self { http_client, url }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearxngResponse {
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Deserialize)]
+struct SearxngResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    content: String,
+}
+
+impl WebSearchProvider for SearxngWebSearchProvider {
+    fn id(&self) -> WebSearchProviderId {
+        WebSearchProviderId(SEARXNG_WEB_SEARCH_PROVIDER_ID.into())
+    }
+
+    fn search(&self, query: String, cx: &mut App) -> Task<Result<WebSearchResponse>> {
+        let http_client = self.http_client.clone();
+        let url = self.url.clone();
+        cx.background_spawn(async move {
+            // Instances can be served from a subpath, which `join` would
+            // replace without a trailing slash.
+            let mut url = Url::parse(&format!("{}/", url.trim_end_matches('/')))
+                .with_context(|| format!("invalid SearXNG URL {url:?}"))?
+                .join("search")?;
+            url.query_pairs_mut()
+                .append_pair("q", &query)
+                .append_pair("format", "json");
+            let request = http_client::Request::builder()
+                .method(Method::GET)
+                .uri(url.as_str())
+                .header("Accept", "application/json")
+                .body(Default::default())?;
+            let response: SearxngResponse =
+                serde_json::from_value(fetch_json(http_client.as_ref(), request).await?)
+                    .context("unexpected SearXNG response")?;
+            Ok(WebSearchResponse {
+                results: response
+                    .results
+                    .into_iter()
+                    .take(MAX_RESULTS)
+                    .map(|result| WebSearchResult {
+                        title: result.title,
+                        url: result.url,
+                        text: result.content,
+                    })
+                    .collect(),
+            })
+        })
+    }
+}
@@ -1,16 +1,115 @@
+mod brave;
 mod cloud;
+mod custom;
+mod kagi;
+mod searxng;
+mod web_search_settings;
 
+use anyhow::{Context as _, Result};
 use client::{Client, UserStore};
+use futures::AsyncReadExt as _;
 use gpui::{App, Context, Entity};
+use http_client::{AsyncBody, HttpClient, Request};
 use language_model::LanguageModelRegistry;
+use settings::{Settings as _, SettingsStore};
 use std::sync::Arc;
-use web_search::{WebSearchProviderId, WebSearchRegistry};
+use web_search::{WebSearchProvider, WebSearchProviderId, WebSearchRegistry};
+
+pub use brave::BraveWebSearchProvider;
+pub use custom::CustomWebSearchProvider;
+pub use kagi::KagiWebSearchProvider;
+pub use searxng::SearxngWebSearchProvider;
+pub use web_search_settings::WebSearchProviderSettings;
+
+/// Search results beyond this many are dropped, to keep the `web_search`
+/// tool's output short.
+const MAX_RESULTS: usize = 10;
 
 pub fn init(client: Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     let registry = WebSearchRegistry::global(cx);
     registry.update(cx, |registry, cx| {
         register_web_search_providers(registry, client, user_store, cx);
     });
+
+    update_setting_providers(cx);
+    cx.observe_global::<SettingsStore>(update_setting_providers)
+        .detach();
+}
+
+fn update_setting_providers(cx: &mut App) {
+    let settings = WebSearchProviderSettings::get_global(cx).clone();
+    let http_client = cx.http_client();
+    let mut providers: Vec<Arc<dyn WebSearchProvider>> = vec![
+        Arc::new(BraveWebSearchProvider::new(
+            http_client.clone(),
+            settings.brave.api_url,
+        )),
+        Arc::new(KagiWebSearchProvider::new(
+            http_client.clone(),
+            settings.kagi.api_url,
+        )),
+    ];
+    if let Some(url) = settings
+        .searxng
+        .and_then(|searxng| searxng.url)
+        .filter(|url| !url.trim().is_empty())
+    {
+        providers.push(Arc::new(SearxngWebSearchProvider::new(
+            http_client.clone(),
+            url,
+        )));
+    }
+    if let Some(provider) = settings
+        .custom
+        .and_then(|custom| CustomWebSearchProvider::new(http_client, custom))
+    {
+        providers.push(Arc::new(provider));
+    }
+
+    WebSearchRegistry::global(cx).update(cx, |registry, _cx| {
+        registry.set_setting_providers(providers);
+    });
+}
+
+async fn fetch_json(
+    http_client: &dyn HttpClient,
+    request: Request<AsyncBody>,
+) -> Result<serde_json::Value> {
+    let mut response = http_client
+        .send(request)
+        .await
+        .context("failed to send web search request")?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "error performing web search.\nStatus: {:?}\nBody: {body}",
+        response.status(),
+    );
+    serde_json::from_str(&body).context("the web search response isn't valid JSON")
+}
+
+fn api_key_from_env(env_var_name: &str) -> Result<String> {
+    std::env::var(env_var_name)
+        .ok()
+        .filter(|api_key| !api_key.is_empty())
+        .with_context(|| format!("no API key for web search. Set {env_var_name}"))
+}
+
+/// Removes the tags from text with HTML markup, such as `<strong>` around
+/// the terms matching the query.
+fn strip_html_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for char in text.chars() {
+        match char {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(char),
+            _ => {}
+        }
+    }
+    stripped
 }
 
 fn register_web_search_providers(
@@ -66,3 +165,69 @@ fn register_zed_web_search_provider(
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_client::{FakeHttpClient, Response};
+    use serde_json::json;
+
+    #[test]
+    fn test_strip_html_tags() {
+        assert_eq!(
+            strip_html_tags("The <strong>Zed</strong> editor"),
+            "The Zed editor"
+        );
+        assert_eq!(strip_html_tags("1 < 2"), "1 ");
+    }
+
+    #[gpui::test]
+    async fn test_searxng_search(cx: &mut gpui::TestAppContext) {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(
+                request.uri().to_string(),
+                "http://localhost:8888/searx/search?q=rust+async&format=json"
+            );
+            Ok(Response::builder().status(200).body(AsyncBody::from(
+                json!({
+                    "query": "rust async",
+                    "results": [
+                        {"title": "Async Rust", "url": "https://rust-lang.github.io/async-book/", "content": "The async book"},
+                        {"title": "Tokio", "url": "https://tokio.rs"}
+                    ]
+                })
+                .to_string(),
+            ))?)
+        });
+        let provider =
+            SearxngWebSearchProvider::new(http_client, "http://localhost:8888/searx".to_string());
+        let response = cx
+            .update(|cx| provider.search("rust async".to_string(), cx))
+            .await
+            .unwrap();
+        assert_eq!(
+            response
+                .results
+                .iter()
+                .map(|result| (result.title.as_str(), result.text.as_str()))
+                .collect::<Vec<_>>(),
+            [("Async Rust", "The async book"), ("Tokio", "")]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_search_error(cx: &mut gpui::TestAppContext) {
+        let http_client = FakeHttpClient::create(|_| async move {
+            Ok(Response::builder()
+                .status(403)
+                .body(AsyncBody::from("JSON format is disabled".to_string()))?)
+        });
+        let provider =
+            SearxngWebSearchProvider::new(http_client, "http://localhost:8888".to_string());
+        let error = cx
+            .update(|cx| provider.search("zed".to_string(), cx))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("JSON format is disabled"));
+    }
+}
@@ -0,0 +1,32 @@
+use settings::{
+    CustomWebSearchSettingsContent, RegisterSetting, SearxngSettingsContent, Settings,
+    WebSearchApiSettingsContent,
+};
+
+/// The configuration of the web search providers that are set up in the
+/// `agent.web_search` settings.
+#[derive(Clone, Debug, Default, PartialEq, RegisterSetting)]
+pub struct WebSearchProviderSettings {
+    pub searxng: Option<SearxngSettingsContent>,
+    pub brave: WebSearchApiSettingsContent,
+    pub kagi: WebSearchApiSettingsContent,
+    pub custom: Option<CustomWebSearchSettingsContent>,
+}
+
+impl Settings for WebSearchProviderSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let Some(content) = content
+            .agent
+            .as_ref()
+            .and_then(|agent| agent.web_search.clone())
+        else {
+            return Self::default();
+        };
+        Self {
+            searxng: content.searxng,
+            brave: content.brave.unwrap_or_default(),
+            kagi: content.kagi.unwrap_or_default(),
+            custom: content.custom,
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use std::{path::Path, sync::Arc};
 
-use gpui::{EventEmitter, FocusHandle, Focusable};
+use gpui::{Action, EventEmitter, FocusHandle, Focusable};
 use ui::{
     App, Button, ButtonCommon, ButtonStyle, Clickable, Context, FluentBuilder, InteractiveElement,
     KeyBinding, Label, LabelCommon, LabelSize, ParentElement, Render, SharedString, Styled as _,
     Window, h_flex, v_flex,
 };
-use zed_actions::workspace::OpenWithSystem;
+use zed_actions::{hex_editor::OpenInHexEditor, workspace::OpenWithSystem};
 
 use crate::Item;
 
@@ -18,6 +18,7 @@ pub struct InvalidItemView {
     /// An error message, happened when opening the item.
     pub error: SharedString,
     is_local: bool,
+    is_binary: bool,
     focus_handle: FocusHandle,
 }
 
@@ -31,6 +32,7 @@ impl InvalidItemView {
     ) -> Self {
         Self {
             is_local,
+            is_binary: is_binary_content_error(e),
             abs_path: Arc::from(abs_path),
             error: format!("{}", e.root_cause()).into(),
             focus_handle: cx.focus_handle(),
@@ -38,6 +40,19 @@ impl InvalidItemView {
     }
 }
 
+/// Whether the item failed to open because its contents aren't text, as opposed
+/// to e.g. the file missing or being unreadable.
+fn is_binary_content_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        if cause.is::<std::str::Utf8Error>() || cause.is::<std::string::FromUtf8Error>() {
+            return true;
+        }
+        // Errors from remote worktrees arrive as plain messages.
+        let message = cause.to_string().to_lowercase();
+        message.contains("binary files are not supported") || message.contains("invalid utf-8")
+    })
+}
+
 impl Item for InvalidItemView {
     type Event = ();
 
@@ -98,14 +113,32 @@ impl Render for InvalidItemView {
                         )
                         .when(self.is_local, |contents| {
                             contents.child(
-                                h_flex().justify_center().child(
-                                    Button::new("open-with-system", "Open in Default App")
-                                        .on_click(move |_, _, cx| {
-                                            cx.open_with_system(&abs_path);
-                                        })
-                                        .style(ButtonStyle::Outlined)
-                                        .key_binding(KeyBinding::for_action(&OpenWithSystem, cx)),
-                                ),
+                                h_flex()
+                                    .justify_center()
+                                    .gap_2()
+                                    .child(
+                                        Button::new("open-with-system", "Open in Default App")
+                                            .on_click(move |_, _, cx| {
+                                                cx.open_with_system(&abs_path);
+                                            })
+                                            .style(ButtonStyle::Outlined)
+                                            .key_binding(KeyBinding::for_action(
+                                                &OpenWithSystem,
+                                                cx,
+                                            )),
+                                    )
+                                    .when(self.is_binary, |buttons| {
+                                        buttons.child(
+                                            Button::new("open-in-hex-editor", "Open in Hex Editor")
+                                                .on_click(|_, window, cx| {
+                                                    window.dispatch_action(
+                                                        OpenInHexEditor.boxed_clone(),
+                                                        cx,
+                                                    );
+                                                })
+                                                .style(ButtonStyle::Outlined),
+                                        )
+                                    }),
                             )
                         }),
                 ),
@@ -0,0 +1,797 @@
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use collections::{HashMap, IndexMap};
+use gpui::{App, AppContext as _, Axis, Context, Entity, Focusable as _, Task, Window};
+use project::{ProjectPath, WorktreeId};
+use settings::{
+    PaneLayoutAxis, PaneLayoutContent, PaneLayoutDockContent, PaneLayoutDocksContent,
+    PaneLayoutMemberContent, RegisterSetting, Settings, SettingsLocation,
+};
+use util::{ResultExt as _, rel_path::RelPath};
+
+use crate::{
+    ItemHandle, Member, Pane, PaneAxis, PaneGroup, Workspace, WorkspaceItemBuilder,
+    persistence::{DB, model::DockData},
+};
+
+#[derive(Clone, Debug, PartialEq, RegisterSetting)]
+pub struct PaneLayoutSettings {
+    pub layouts: IndexMap<String, PaneLayoutContent>,
+}
+
+impl Settings for PaneLayoutSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            layouts: content.project.layouts.clone().unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaneLayoutSource {
+    /// Saved from the current workspace.
+    Saved,
+    /// Defined in the user's or the project's settings.
+    Settings,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedPaneLayout {
+    pub name: String,
+    pub source: PaneLayoutSource,
+    pub layout: PaneLayoutContent,
+    /// The worktree whose settings define the layout. Relative paths are
+    /// looked up in it first.
+    worktree_id: Option<WorktreeId>,
+}
+
+/// A [`PaneLayoutMemberContent`] whose paths have been resolved in the
+/// project.
+enum ResolvedPaneLayoutMember {
+    Split {
+        axis: Axis,
+        members: Vec<ResolvedPaneLayoutMember>,
+        flexes: Option<Vec<f32>>,
+    },
+    Pane {
+        paths: Vec<ProjectPath>,
+        active_item: Option<usize>,
+        active: bool,
+    },
+}
+
+impl ResolvedPaneLayoutMember {
+    fn collect_paths<'a>(&'a self, paths: &mut Vec<&'a ProjectPath>) {
+        match self {
+            Self::Split { members, .. } => {
+                for member in members {
+                    member.collect_paths(paths);
+                }
+            }
+            Self::Pane { paths: items, .. } => paths.extend(items),
+        }
+    }
+}
+
+impl Workspace {
+    /// Returns the layouts saved in this workspace, followed by the ones
+    /// defined in settings that aren't shadowed by a saved layout.
+    pub fn pane_layouts(&self, cx: &App) -> Task<Vec<NamedPaneLayout>> {
+        let settings_layouts = self.settings_pane_layouts(cx);
+        let database_id = self.database_id;
+        cx.background_spawn(async move {
+            let mut layouts = database_id
+                .and_then(|database_id| DB.pane_layouts(database_id).log_err())
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(name, layout)| {
+                    Some(NamedPaneLayout {
+                        name,
+                        source: PaneLayoutSource::Saved,
+                        layout: serde_json::from_str(&layout).log_err()?,
+                        worktree_id: None,
+                    })
+                })
+                .collect::<Vec<_>>();
+            for layout in settings_layouts {
+                if !layouts.iter().any(|saved| saved.name == layout.name) {
+                    layouts.push(layout);
+                }
+            }
+            layouts
+        })
+    }
+
+    fn settings_pane_layouts(&self, cx: &App) -> Vec<NamedPaneLayout> {
+        let mut layouts = Vec::<NamedPaneLayout>::new();
+        let worktree_ids = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        if worktree_ids.is_empty() {
+            for (name, layout) in &PaneLayoutSettings::get_global(cx).layouts {
+                layouts.push(NamedPaneLayout {
+                    name: name.clone(),
+                    source: PaneLayoutSource::Settings,
+                    layout: layout.clone(),
+                    worktree_id: None,
+                });
+            }
+        }
+        for worktree_id in worktree_ids {
+            let settings = PaneLayoutSettings::get(
+                Some(SettingsLocation {
+                    worktree_id,
+                    path: RelPath::empty(),
+                }),
+                cx,
+            );
+            for (name, layout) in &settings.layouts {
+                if !layouts.iter().any(|existing| &existing.name == name) {
+                    layouts.push(NamedPaneLayout {
+                        name: name.clone(),
+                        source: PaneLayoutSource::Settings,
+                        layout: layout.clone(),
+                        worktree_id: Some(worktree_id),
+                    });
+                }
+            }
+        }
+        layouts
+    }
+
+    /// Saves the current arrangement of panes, items and docks under the
+    /// given name, replacing any layout saved under it before.
+    pub fn save_pane_layout(
+        &mut self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id else {
+            return Task::ready(Err(anyhow!(
+                "Cannot save layouts in a workspace that isn't persisted"
+            )));
+        };
+        let layout = self.capture_pane_layout(window, cx);
+        cx.background_spawn(async move {
+            let layout = serde_json::to_string(&layout)?;
+            DB.save_pane_layout(database_id, name, layout).await
+        })
+    }
+
+    pub fn delete_pane_layout(&self, name: String, cx: &App) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id else {
+            return Task::ready(Ok(()));
+        };
+        cx.background_spawn(async move { DB.delete_pane_layout(database_id, name).await })
+    }
+
+    /// Describes the current arrangement of panes, items and docks. Items
+    /// without a path, such as terminals, are left out.
+    pub fn capture_pane_layout(&self, window: &Window, cx: &App) -> PaneLayoutContent {
+        let docks = self.capture_dock_state(window, cx);
+        let dock_content = |data: DockData| PaneLayoutDockContent {
+            visible: Some(data.visible),
+            panel: data.active_panel,
+        };
+        PaneLayoutContent {
+            center: Some(self.capture_pane_layout_member(&self.center.root, cx)),
+            docks: Some(PaneLayoutDocksContent {
+                left: Some(dock_content(docks.left)),
+                right: Some(dock_content(docks.right)),
+                bottom: Some(dock_content(docks.bottom)),
+            }),
+        }
+    }
+
+    fn capture_pane_layout_member(&self, member: &Member, cx: &App) -> PaneLayoutMemberContent {
+        match member {
+            Member::Axis(axis) => {
+                let flexes = axis.flexes.lock().clone();
+                PaneLayoutMemberContent::Split {
+                    axis: match axis.axis {
+                        Axis::Horizontal => PaneLayoutAxis::Horizontal,
+                        Axis::Vertical => PaneLayoutAxis::Vertical,
+                    },
+                    members: axis
+                        .members
+                        .iter()
+                        .map(|member| self.capture_pane_layout_member(member, cx))
+                        .collect(),
+                    flexes: flexes
+                        .iter()
+                        .any(|flex| (flex - 1.).abs() >= 0.001)
+                        .then_some(flexes),
+                }
+            }
+            Member::Pane(pane) => {
+                let active = *pane == self.active_pane;
+                let pane = pane.read(cx);
+                let mut items = Vec::new();
+                let mut active_item = None;
+                for (ix, item) in pane.items().enumerate() {
+                    let Some(path) = item
+                        .project_path(cx)
+                        .and_then(|project_path| self.pane_layout_path(&project_path, cx))
+                    else {
+                        continue;
+                    };
+                    if ix == pane.active_item_index() {
+                        active_item = Some(items.len());
+                    }
+                    items.push(path);
+                }
+                PaneLayoutMemberContent::Pane {
+                    items,
+                    active_item,
+                    active,
+                }
+            }
+        }
+    }
+
+    /// Paths in the first visible worktree are stored relative to it, so
+    /// that layouts keep working when the project is moved.
+    fn pane_layout_path(&self, project_path: &ProjectPath, cx: &App) -> Option<String> {
+        let project = self.project.read(cx);
+        let first_worktree_id = project
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).id());
+        if first_worktree_id == Some(project_path.worktree_id) {
+            return Some(
+                project_path
+                    .path
+                    .display(project.path_style(cx))
+                    .into_owned(),
+            );
+        }
+        project
+            .absolute_path(project_path, cx)
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    fn resolve_pane_layout_path(
+        &self,
+        path: &str,
+        worktree_id: Option<WorktreeId>,
+        cx: &App,
+    ) -> Option<ProjectPath> {
+        let project = self.project.read(cx);
+        let worktree = match worktree_id {
+            Some(worktree_id) => project.worktree_for_id(worktree_id, cx),
+            None => project.visible_worktrees(cx).next(),
+        };
+        if let Some(worktree) = worktree
+            && let Ok(relative_path) = RelPath::new(Path::new(path), project.path_style(cx))
+            && worktree.read(cx).entry_for_path(&relative_path).is_some()
+        {
+            return Some(ProjectPath {
+                worktree_id: worktree.read(cx).id(),
+                path: relative_path.into_arc(),
+            });
+        }
+        project.find_project_path(path, cx)
+    }
+
+    fn resolve_pane_layout_member(
+        &self,
+        member: &PaneLayoutMemberContent,
+        worktree_id: Option<WorktreeId>,
+        cx: &App,
+    ) -> ResolvedPaneLayoutMember {
+        match member {
+            PaneLayoutMemberContent::Split {
+                axis,
+                members,
+                flexes,
+            } if !members.is_empty() => ResolvedPaneLayoutMember::Split {
+                axis: match axis {
+                    PaneLayoutAxis::Horizontal => Axis::Horizontal,
+                    PaneLayoutAxis::Vertical => Axis::Vertical,
+                },
+                members: members
+                    .iter()
+                    .map(|member| self.resolve_pane_layout_member(member, worktree_id, cx))
+                    .collect(),
+                flexes: flexes.clone(),
+            },
+            PaneLayoutMemberContent::Split { .. } => ResolvedPaneLayoutMember::Pane {
+                paths: Vec::new(),
+                active_item: None,
+                active: false,
+            },
+            PaneLayoutMemberContent::Pane {
+                items,
+                active_item,
+                active,
+            } => {
+                let mut paths = Vec::new();
+                let mut resolved_active_item = None;
+                for (ix, item) in items.iter().enumerate() {
+                    let Some(path) = self.resolve_pane_layout_path(item, worktree_id, cx) else {
+                        log::warn!("Could not find {item:?} in the project for a pane layout");
+                        continue;
+                    };
+                    if *active_item == Some(ix) {
+                        resolved_active_item = Some(paths.len());
+                    }
+                    paths.push(path);
+                }
+                ResolvedPaneLayoutMember::Pane {
+                    paths,
+                    active_item: resolved_active_item,
+                    active: *active,
+                }
+            }
+        }
+    }
+
+    /// Replaces the panes in the center of the workspace with the ones of the
+    /// layout and applies its dock states. Items already open for a path in
+    /// the layout are moved rather than reopened, and items with unsaved
+    /// changes that aren't part of the layout are kept in its active pane.
+    pub fn apply_pane_layout(
+        &mut self,
+        layout: &NamedPaneLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let docks = layout.layout.docks.clone();
+        let Some(center) = &layout.layout.center else {
+            if let Some(docks) = docks {
+                self.apply_pane_layout_docks(&docks, window, cx);
+                self.serialize_workspace(window, cx);
+            }
+            return Task::ready(Ok(()));
+        };
+        let center = self.resolve_pane_layout_member(center, layout.worktree_id, cx);
+
+        let mut open_items = HashMap::<ProjectPath, usize>::default();
+        for pane in self.center.panes() {
+            for item in pane.read(cx).items() {
+                if let Some(project_path) = item.project_path(cx) {
+                    *open_items.entry(project_path).or_default() += 1;
+                }
+            }
+        }
+        let mut paths = Vec::new();
+        center.collect_paths(&mut paths);
+        let mut loads = Vec::new();
+        for path in paths {
+            match open_items.get_mut(path) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => loads.push((path.clone(), self.load_path(path.clone(), window, cx))),
+            }
+        }
+
+        cx.spawn_in(window, async move |this, cx| {
+            let mut builders = HashMap::<ProjectPath, Vec<WorkspaceItemBuilder>>::default();
+            for (path, load) in loads {
+                match load.await {
+                    Ok((_, builder)) => builders.entry(path).or_default().push(builder),
+                    Err(error) => {
+                        log::warn!("Could not open {path:?} for a pane layout: {error:#}")
+                    }
+                }
+            }
+            this.update_in(cx, |this, window, cx| {
+                this.replace_center_with_pane_layout(center, builders, window, cx);
+                if let Some(docks) = docks {
+                    this.apply_pane_layout_docks(&docks, window, cx);
+                }
+                this.serialize_workspace(window, cx);
+            })
+        })
+    }
+
+    fn replace_center_with_pane_layout(
+        &mut self,
+        center: ResolvedPaneLayoutMember,
+        mut builders: HashMap<ProjectPath, Vec<WorkspaceItemBuilder>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let old_root = self.center.root.clone();
+        let mut old_items = self
+            .center
+            .panes()
+            .into_iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .map(|item| (pane.clone(), item.boxed_clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut active_pane = None;
+        let root = self.build_pane_layout_member(
+            center,
+            &mut old_items,
+            &mut builders,
+            &mut active_pane,
+            window,
+            cx,
+        );
+        let center = PaneGroup::with_root(root);
+        let active_pane = active_pane.unwrap_or_else(|| center.first_pane());
+
+        for (old_pane, item) in old_items {
+            old_pane.update(cx, |old_pane, cx| {
+                old_pane.remove_item(item.item_id(), false, false, window, cx)
+            });
+            if item.is_dirty(cx) {
+                active_pane.update(cx, |active_pane, cx| {
+                    active_pane.add_item(item, false, false, None, window, cx)
+                });
+            }
+        }
+
+        self.remove_panes(old_root, window, cx);
+        self.center = center;
+        self.center.set_is_center(true);
+        self.center.mark_positions(cx);
+        self.set_active_pane(&active_pane, window, cx);
+        window.focus(&active_pane.focus_handle(cx), cx);
+        cx.notify();
+    }
+
+    fn build_pane_layout_member(
+        &mut self,
+        member: ResolvedPaneLayoutMember,
+        old_items: &mut Vec<(Entity<Pane>, Box<dyn ItemHandle>)>,
+        builders: &mut HashMap<ProjectPath, Vec<WorkspaceItemBuilder>>,
+        active_pane: &mut Option<Entity<Pane>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Member {
+        match member {
+            ResolvedPaneLayoutMember::Split {
+                axis,
+                members,
+                flexes,
+            } => {
+                let mut members = members
+                    .into_iter()
+                    .map(|member| {
+                        self.build_pane_layout_member(
+                            member,
+                            old_items,
+                            builders,
+                            active_pane,
+                            window,
+                            cx,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if members.len() == 1 {
+                    return members.remove(0);
+                }
+                Member::Axis(PaneAxis::load(axis, members, flexes))
+            }
+            ResolvedPaneLayoutMember::Pane {
+                paths,
+                active_item,
+                active,
+            } => {
+                let pane = self.add_pane(window, cx);
+                let mut active_item_index = None;
+                for (ix, path) in paths.into_iter().enumerate() {
+                    let index = pane.read(cx).items_len();
+                    if let Some(old_ix) = old_items
+                        .iter()
+                        .position(|(_, item)| item.project_path(cx).as_ref() == Some(&path))
+                    {
+                        let (old_pane, item) = old_items.remove(old_ix);
+                        old_pane.update(cx, |old_pane, cx| {
+                            old_pane.remove_item(item.item_id(), false, false, window, cx)
+                        });
+                        pane.update(cx, |pane, cx| {
+                            pane.add_item(item, false, false, Some(index), window, cx)
+                        });
+                    } else if let Some(builder) =
+                        builders.get_mut(&path).and_then(|builders| builders.pop())
+                    {
+                        pane.update(cx, |pane, cx| {
+                            let item = builder(pane, window, cx);
+                            pane.add_item(item, false, false, Some(index), window, cx)
+                        });
+                    } else {
+                        continue;
+                    }
+                    if active_item == Some(ix) {
+                        active_item_index = Some(index);
+                    }
+                }
+                if let Some(index) = active_item_index {
+                    pane.update(cx, |pane, cx| {
+                        pane.activate_item(index, false, false, window, cx)
+                    });
+                }
+                if active && active_pane.is_none() {
+                    *active_pane = Some(pane.clone());
+                }
+                Member::Pane(pane)
+            }
+        }
+    }
+
+    fn apply_pane_layout_docks(
+        &mut self,
+        docks: &PaneLayoutDocksContent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut structure = self.capture_dock_state(window, cx);
+        for (data, content) in [
+            (&mut structure.left, &docks.left),
+            (&mut structure.right, &docks.right),
+            (&mut structure.bottom, &docks.bottom),
+        ] {
+            let Some(content) = content else {
+                continue;
+            };
+            // Naming a panel without saying whether the dock is open means
+            // showing that panel.
+            data.visible = content
+                .visible
+                .unwrap_or(data.visible || content.panel.is_some());
+            if let Some(panel) = &content.panel {
+                data.active_panel = Some(panel.clone());
+            }
+        }
+        self.set_dock_structure(structure, window, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SplitDirection,
+        item::{Item, ItemBufferKind, ProjectItem},
+        register_project_item,
+        tests::init_test,
+    };
+    use fs::FakeFs;
+    use gpui::{
+        EntityId, EventEmitter, FocusHandle, Focusable, IntoElement, Render, SharedString,
+        TestAppContext, VisualTestContext,
+    };
+    use project::{Project, ProjectEntryId};
+    use serde_json::json;
+    use util::path;
+
+    struct TestFile {
+        project_path: ProjectPath,
+    }
+
+    impl project::ProjectItem for TestFile {
+        fn try_open(
+            _project: &Entity<Project>,
+            path: &ProjectPath,
+            cx: &mut App,
+        ) -> Option<Task<Result<Entity<Self>>>> {
+            let project_path = path.clone();
+            Some(cx.spawn(async move |cx| Ok(cx.new(|_| TestFile { project_path }))))
+        }
+
+        fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+            None
+        }
+
+        fn project_path(&self, _: &App) -> Option<ProjectPath> {
+            Some(self.project_path.clone())
+        }
+
+        fn is_dirty(&self) -> bool {
+            false
+        }
+    }
+
+    struct TestFileView {
+        file: Entity<TestFile>,
+        is_dirty: bool,
+        focus_handle: FocusHandle,
+    }
+
+    impl Item for TestFileView {
+        type Event = ();
+
+        fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+            "".into()
+        }
+
+        fn for_each_project_item(
+            &self,
+            cx: &App,
+            f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+        ) {
+            f(self.file.entity_id(), self.file.read(cx))
+        }
+
+        fn buffer_kind(&self, _cx: &App) -> ItemBufferKind {
+            ItemBufferKind::Singleton
+        }
+
+        fn is_dirty(&self, _cx: &App) -> bool {
+            self.is_dirty
+        }
+    }
+
+    impl EventEmitter<()> for TestFileView {}
+
+    impl Focusable for TestFileView {
+        fn focus_handle(&self, _cx: &App) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestFileView {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            gpui::Empty
+        }
+    }
+
+    impl ProjectItem for TestFileView {
+        type Item = TestFile;
+
+        fn for_project_item(
+            _project: Entity<Project>,
+            _pane: Option<&Pane>,
+            file: Entity<Self::Item>,
+            _: &mut Window,
+            cx: &mut Context<Self>,
+        ) -> Self {
+            Self {
+                file,
+                is_dirty: false,
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+
+    async fn open_file(
+        workspace: &Entity<Workspace>,
+        path: &str,
+        pane: Option<&Entity<Pane>>,
+        cx: &mut VisualTestContext,
+    ) -> Box<dyn ItemHandle> {
+        let project_path = workspace.read_with(cx, |workspace, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .find_project_path(path, cx)
+                .unwrap()
+        });
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path(
+                    project_path,
+                    pane.map(|pane| pane.downgrade()),
+                    true,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+    }
+
+    fn pane_items(pane: &Entity<Pane>, cx: &App) -> Vec<String> {
+        pane.read(cx)
+            .items()
+            .map(|item| {
+                item.project_path(cx)
+                    .unwrap()
+                    .path
+                    .as_unix_str()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn pane(items: &[&str], active_item: Option<usize>, active: bool) -> PaneLayoutMemberContent {
+        PaneLayoutMemberContent::Pane {
+            items: items.iter().map(ToString::to_string).collect(),
+            active_item,
+            active,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_capture_and_apply_pane_layout(cx: &mut TestAppContext) {
+        init_test(cx);
+        cx.update(register_project_item::<TestFileView>);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({ "a.txt": "", "b.txt": "", "c.txt": "", "d.txt": "" }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let first_pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+        let a = open_file(&workspace, "a.txt", Some(&first_pane), cx).await;
+        let d = open_file(&workspace, "d.txt", Some(&first_pane), cx).await;
+        d.to_any_view()
+            .downcast::<TestFileView>()
+            .unwrap()
+            .update(cx, |view, _| view.is_dirty = true);
+        let second_pane = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.split_pane(first_pane.clone(), SplitDirection::Right, window, cx)
+        });
+        open_file(&workspace, "b.txt", Some(&second_pane), cx).await;
+        cx.run_until_parked();
+
+        let layout = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.capture_pane_layout(window, cx)
+        });
+        assert_eq!(
+            layout.center,
+            Some(PaneLayoutMemberContent::Split {
+                axis: PaneLayoutAxis::Horizontal,
+                members: vec![
+                    pane(&["a.txt", "d.txt"], Some(1), false),
+                    pane(&["b.txt"], Some(0), true),
+                ],
+                flexes: None,
+            })
+        );
+
+        // Files already open are moved into their new pane, files that
+        // don't exist are skipped and unsaved files that aren't part of the
+        // layout are kept in its active pane.
+        let layout = NamedPaneLayout {
+            name: "review".to_string(),
+            source: PaneLayoutSource::Settings,
+            layout: PaneLayoutContent {
+                center: Some(PaneLayoutMemberContent::Split {
+                    axis: PaneLayoutAxis::Vertical,
+                    members: vec![
+                        pane(&["c.txt", "missing.txt", "a.txt"], Some(0), false),
+                        pane(&["b.txt"], None, true),
+                    ],
+                    flexes: Some(vec![1.5, 0.5]),
+                }),
+                docks: None,
+            },
+            worktree_id: None,
+        };
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.apply_pane_layout(&layout, window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        workspace.read_with(cx, |workspace, cx| {
+            let Member::Axis(axis) = &workspace.center.root else {
+                panic!("expected a split");
+            };
+            assert_eq!(axis.axis, Axis::Vertical);
+            assert_eq!(*axis.flexes.lock(), [1.5, 0.5]);
+
+            let panes = workspace.center.panes();
+            assert_eq!(panes.len(), 2);
+            assert_eq!(workspace.panes().len(), 2);
+            assert_eq!(pane_items(panes[0], cx), ["c.txt", "a.txt"]);
+            assert_eq!(panes[0].read(cx).active_item_index(), 0);
+            assert_eq!(
+                panes[0].read(cx).items().nth(1).unwrap().item_id(),
+                a.item_id()
+            );
+            assert_eq!(pane_items(panes[1], cx), ["b.txt", "d.txt"]);
+            assert_eq!(workspace.active_pane(), panes[1]);
+        });
+    }
+}
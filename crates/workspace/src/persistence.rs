@@ -21,6 +21,7 @@ use db::{
 };
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
 use project::{
+    bookmark_store::SerializedBookmark,
     debugger::breakpoint_store::{BreakpointState, SourceBreakpoint},
     trusted_worktrees::{DbTrustedPaths, RemoteHostLocation},
 };
@@ -971,6 +972,28 @@ impl Domain for WorkspaceDb {
         sql!(
             ALTER TABLE remote_connections ADD COLUMN use_podman BOOLEAN;
         ),
+        sql!(
+            CREATE TABLE bookmarks (
+                workspace_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                bookmark_row INTEGER NOT NULL,
+                name TEXT,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
+        sql!(
+            CREATE TABLE pane_layouts (
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                layout TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, name),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1106,6 +1129,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             window_id,
             user_toolchains: self.user_toolchains(workspace_id, remote_connection_id),
         })
@@ -1196,6 +1220,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             window_id,
             user_toolchains: self.user_toolchains(workspace_id, remote_connection_id),
         })
@@ -1247,6 +1272,35 @@ impl WorkspaceDb {
         }
     }
 
+    fn bookmarks(&self, workspace_id: WorkspaceId) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        let bookmarks: Result<Vec<(PathBuf, u32, Option<String>)>> = self
+            .select_bound(sql! {
+                SELECT path, bookmark_row, name
+                FROM bookmarks
+                WHERE workspace_id = ?
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match bookmarks {
+            Ok(bookmarks) => {
+                let mut map: BTreeMap<Arc<Path>, Vec<SerializedBookmark>> = Default::default();
+                for (path, row, name) in bookmarks {
+                    map.entry(path.into())
+                        .or_default()
+                        .push(SerializedBookmark {
+                            row,
+                            name: name.map(Arc::from),
+                        });
+                }
+                map
+            }
+            Err(error) => {
+                log::error!("Bookmarks query failed: {error}");
+                Default::default()
+            }
+        }
+    }
+
     fn user_toolchains(
         &self,
         workspace_id: WorkspaceId,
@@ -1375,6 +1429,27 @@ impl WorkspaceDb {
                     }
                 }
 
+                conn.exec_bound(
+                    sql!(
+                        DELETE FROM bookmarks WHERE workspace_id = ?1;
+                    )
+                )?(workspace.id).context("Clearing old bookmarks")?;
+
+                for (path, bookmarks) in workspace.bookmarks {
+                    for bookmark in bookmarks {
+                        conn.exec_bound(sql!(
+                            INSERT INTO bookmarks (workspace_id, path, bookmark_row, name)
+                            VALUES (?1, ?2, ?3, ?4);
+                        ))?((
+                            workspace.id,
+                            path.as_ref(),
+                            bookmark.row,
+                            bookmark.name.as_deref(),
+                        ))
+                        .log_err();
+                    }
+                }
+
                 conn.exec_bound(
                     sql!(
                         DELETE FROM user_toolchains WHERE workspace_id = ?1;
@@ -1784,6 +1859,29 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        pub fn pane_layouts(workspace_id: WorkspaceId) -> Result<Vec<(String, String)>> {
+            SELECT name, layout
+            FROM pane_layouts
+            WHERE workspace_id = ?
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub async fn save_pane_layout(workspace_id: WorkspaceId, name: String, layout: String) -> Result<()> {
+            INSERT OR REPLACE INTO pane_layouts (workspace_id, name, layout)
+            VALUES (?1, ?2, ?3)
+        }
+    }
+
+    query! {
+        pub async fn delete_pane_layout(workspace_id: WorkspaceId, name: String) -> Result<()> {
+            DELETE FROM pane_layouts
+            WHERE workspace_id = ?1 AND name = ?2
+        }
+    }
+
     async fn all_paths_exist_with_a_directory(
         paths: &[PathBuf],
         fs: &dyn Fs,
@@ -2468,6 +2566,88 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_bookmarks() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_bookmarks").await;
+        let id = db.next_id().await.unwrap();
+
+        let bookmarks = vec![
+            SerializedBookmark { row: 4, name: None },
+            SerializedBookmark {
+                row: 12,
+                name: Some(Arc::from("setup")),
+            },
+        ];
+        let mut workspace = SerializedWorkspace {
+            id,
+            paths: PathList::new(&["/tmp"]),
+            location: SerializedWorkspaceLocation::Local,
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            bookmarks: collections::BTreeMap::from_iter([(
+                Arc::from(Path::new("/tmp/test.rs")),
+                bookmarks.clone(),
+            )]),
+            session_id: None,
+            window_id: None,
+            user_toolchains: Default::default(),
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(
+            loaded.bookmarks.get(Path::new("/tmp/test.rs")),
+            Some(&bookmarks)
+        );
+
+        workspace.bookmarks.clear();
+        db.save_workspace(workspace).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert!(loaded.bookmarks.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_pane_layouts() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_pane_layouts").await;
+        let id = db.next_id().await.unwrap();
+
+        db.save_pane_layout(id, "review".to_string(), "{}".to_string())
+            .await
+            .unwrap();
+        db.save_pane_layout(id, "debugging".to_string(), "{}".to_string())
+            .await
+            .unwrap();
+        db.save_pane_layout(id, "review".to_string(), r#"{"docks":{}}"#.to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.pane_layouts(id).unwrap(),
+            [
+                ("debugging".to_string(), "{}".to_string()),
+                ("review".to_string(), r#"{"docks":{}}"#.to_string()),
+            ]
+        );
+
+        db.delete_pane_layout(id, "debugging".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.pane_layouts(id).unwrap(),
+            [("review".to_string(), r#"{"docks":{}}"#.to_string())]
+        );
+
+        let other_id = db.next_id().await.unwrap();
+        assert!(db.pane_layouts(other_id).unwrap().is_empty());
+    }
+
     #[gpui::test]
     async fn test_breakpoints() {
         zlog::init_test();
@@ -2576,6 +2756,7 @@ mod tests {
                 map
             },
             session_id: None,
+            bookmarks: Default::default(),
             window_id: None,
             user_toolchains: Default::default(),
         };
@@ -2697,6 +2878,7 @@ mod tests {
                 map
             },
             session_id: None,
+            bookmarks: Default::default(),
             window_id: None,
             user_toolchains: Default::default(),
         };
@@ -2730,6 +2912,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2828,6 +3011,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2843,6 +3027,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -2947,6 +3132,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2981,6 +3167,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2999,6 +3186,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: Some(2),
             user_toolchains: Default::default(),
@@ -3038,6 +3226,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -3079,6 +3268,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
             user_toolchains: Default::default(),
@@ -3094,6 +3284,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
             user_toolchains: Default::default(),
@@ -3109,6 +3300,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
             user_toolchains: Default::default(),
@@ -3124,6 +3316,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
             user_toolchains: Default::default(),
@@ -3150,6 +3343,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
             user_toolchains: Default::default(),
@@ -3162,6 +3356,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -3221,6 +3416,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -3264,6 +3460,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -3376,6 +3573,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
             user_toolchains: Default::default(),
         })
@@ -3735,6 +3933,7 @@ mod tests {
             display: None,
             docks: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -3813,6 +4012,7 @@ mod tests {
                 centered_layout: false,
                 session_id: Some("test-session".to_owned()),
                 breakpoints: Default::default(),
+                bookmarks: Default::default(),
                 window_id: Some(*window_id),
                 user_toolchains: Default::default(),
             })
@@ -4149,6 +4349,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("remove-test-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(99),
             user_toolchains: Default::default(),
         })
@@ -4231,6 +4432,7 @@ mod tests {
             centered_layout: false,
             session_id: Some(session_id.to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id_val),
             user_toolchains: Default::default(),
         })
@@ -4247,6 +4449,7 @@ mod tests {
             centered_layout: false,
             session_id: Some(session_id.to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id_val),
             user_toolchains: Default::default(),
         })
@@ -4325,6 +4528,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("pending-removal-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(88),
             user_toolchains: Default::default(),
         })
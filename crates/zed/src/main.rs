@@ -689,7 +689,10 @@ fn main() {
         load_embedded_fonts(cx);
 
         editor::init(cx);
+        large_file_viewer::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
+        sqlite_viewer::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -704,10 +707,15 @@ fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        todo_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
+        bookmarks::init(cx);
+        pane_layouts::init(cx);
+        clipboard_history::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
+        semantic_index::init(cx);
         cx.set_global(workspace::PaneSearchBarCallbacks {
             setup_search_bar: |languages, toolbar, window, cx| {
                 let search_bar = cx.new(|cx| search::BufferSearchBar::new(languages, window, cx));
@@ -732,6 +740,8 @@ fn main() {
         collab_ui::init(&app_state, cx);
         git_ui::init(cx);
         git_graph::init(cx);
+        file_history::init(cx);
+        spell_check::init(app_state.fs.clone(), cx);
         feedback::init(cx);
         markdown_preview::init(cx);
         csv_preview::init(cx);
@@ -2694,6 +2694,7 @@ fn run_multi_workspace_sidebar_visual_tests(
                             initial_project_snapshot: None,
                             cumulative_token_usage: Default::default(),
                             request_token_usage: Default::default(),
+                            budget: Default::default(),
                             model: None,
                             profile: None,
                             imported: false,
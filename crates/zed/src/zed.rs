@@ -77,6 +77,7 @@ use std::{
 };
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, GlobalTheme, SystemAppearance, ThemeRegistry, ThemeSettings};
+use todo_panel::TodoPanel;
 use ui::{PopoverMenuHandle, prelude::*};
 use util::markdown::MarkdownString;
 use util::rel_path::RelPath;
@@ -456,6 +457,7 @@ pub fn initialize_workspace(
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let macro_recording_indicator = cx.new(editor::MacroRecordingIndicator::new);
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
         let lsp_button_menu_handle = PopoverMenuHandle::default();
@@ -481,6 +483,7 @@ pub fn initialize_workspace(
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
+            status_bar.add_right_item(macro_recording_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
@@ -617,6 +620,7 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let todo_panel = TodoPanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
@@ -645,6 +649,7 @@ fn initialize_panels(
         futures::join!(
             add_panel_when_ready(project_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(outline_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(todo_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(terminal_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
@@ -4830,11 +4835,13 @@ mod tests {
                 "auto_update",
                 "branch_picker",
                 "bedrock",
+                "bookmarks",
                 "branches",
                 "buffer_search",
                 "channel_modal",
                 "cli",
                 "client",
+                "clipboard_history",
                 "collab",
                 "collab_panel",
                 "command_palette",
@@ -4851,12 +4858,14 @@ mod tests {
                 "encoding_selector",
                 "feedback",
                 "file_finder",
+                "file_history",
                 "git",
                 "git_graph",
                 "git_onboarding",
                 "git_panel",
                 "git_picker",
                 "go_to_line",
+                "hex_editor",
                 "highlights_tree_view",
                 "icon_theme_selector",
                 "image_viewer",
@@ -4865,6 +4874,7 @@ mod tests {
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "large_file_viewer",
                 "welcome",
                 "line_ending_selector",
                 "lsp_tool",
@@ -4878,6 +4888,7 @@ mod tests {
                 "outline",
                 "outline_panel",
                 "pane",
+                "pane_layouts",
                 "panel",
                 "picker",
                 "project_panel",
@@ -4892,6 +4903,7 @@ mod tests {
                 "settings_editor",
                 "settings_profile_selector",
                 "snippets",
+                "sqlite_viewer",
                 "stash_picker",
                 "svg",
                 "syntax_tree_view",
@@ -4902,6 +4914,7 @@ mod tests {
                 "theme",
                 "theme_selector",
                 "toast",
+                "todo_panel",
                 "toolchain",
                 "variable_list",
                 "vim",
@@ -5090,6 +5103,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            todo_panel::init(cx);
             terminal_view::init(cx);
             copilot_chat::init(
                 app_state.fs.clone(),